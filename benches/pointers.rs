@@ -0,0 +1,94 @@
+//! # What the Pointer Types Cost
+//! The lessons say which pointer is cheap and which isn’t; these benchmarks measure it. Each
+//! group compares ways of doing the same thing:
+//! - `box`: a value on the stack, and the same value in a `Box<T>` on the heap
+//! - `clone`: `Rc::clone` of a `Vec<String>`, and a deep clone of it
+//! - `borrow`: reading a value directly, and through `RefCell::borrow`
+//! - `shared_clone`: `Rc::clone` and `Arc::clone`, on a single thread
+//! - `counter`: incrementing a counter behind a `Mutex`, a `RwLock`, and as an `AtomicUsize`
+//!
+//! ```text
+//! cargo bench --features bench
+//! cargo bench --features bench -- clone
+//! ```
+//! `black_box` keeps the compiler from optimizing away the work whose cost is being measured.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::{
+    cell::RefCell,
+    hint::black_box,
+    rc::Rc,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, RwLock,
+    },
+};
+
+/// `Box::new` allocates, which is the whole difference: the value is the same either way.
+fn boxing(c: &mut Criterion) {
+    let mut group = c.benchmark_group("box");
+    group.bench_function("stack", |b| b.iter(|| black_box([0u64; 16])));
+    group.bench_function("box", |b| b.iter(|| Box::new(black_box([0u64; 16]))));
+    group.finish();
+}
+
+/// A deep clone allocates a new `Vec` and a new `String` for each word; `Rc::clone` increments
+/// the strong count.
+fn cloning(c: &mut Criterion) {
+    let words: Vec<String> = (1..=100).map(|i| format!("word {i}")).collect();
+    let shared = Rc::new(words.clone());
+    let mut group = c.benchmark_group("clone");
+    group.bench_function("deep", |b| b.iter(|| black_box(&words).clone()));
+    group.bench_function("rc", |b| b.iter(|| Rc::clone(black_box(&shared))));
+    group.finish();
+}
+
+/// `RefCell::borrow` checks and updates the borrow count before handing out the reference, and
+/// updates it again when the `Ref` is dropped.
+fn borrowing(c: &mut Criterion) {
+    let value = 42u64;
+    let cell = RefCell::new(42u64);
+    let mut group = c.benchmark_group("borrow");
+    group.bench_function("direct", |b| b.iter(|| *black_box(&value)));
+    group.bench_function("ref_cell", |b| b.iter(|| *black_box(&cell).borrow()));
+    group.finish();
+}
+
+/// `Arc` updates its counts with atomic instructions, so that it can be shared between threads;
+/// `Rc` can't be, and gets away with plain ones.
+fn shared_cloning(c: &mut Criterion) {
+    let rc = Rc::new(42u64);
+    let arc = Arc::new(42u64);
+    let mut group = c.benchmark_group("shared_clone");
+    group.bench_function("rc", |b| b.iter(|| Rc::clone(black_box(&rc))));
+    group.bench_function("arc", |b| b.iter(|| Arc::clone(black_box(&arc))));
+    group.finish();
+}
+
+/// Without contention, each of these is the cost of its synchronization alone.
+fn counting(c: &mut Criterion) {
+    let mutex = Mutex::new(0usize);
+    let rw_lock = RwLock::new(0usize);
+    let atomic = AtomicUsize::new(0);
+    let mut group = c.benchmark_group("counter");
+    group.bench_function("mutex", |b| {
+        b.iter(|| *black_box(&mutex).lock().unwrap() += 1)
+    });
+    group.bench_function("rw_lock", |b| {
+        b.iter(|| *black_box(&rw_lock).write().unwrap() += 1)
+    });
+    group.bench_function("atomic", |b| {
+        b.iter(|| black_box(&atomic).fetch_add(1, Ordering::Relaxed))
+    });
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    boxing,
+    cloning,
+    borrowing,
+    shared_cloning,
+    counting
+);
+criterion_main!(benches);
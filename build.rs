@@ -0,0 +1,169 @@
+//! # The Build Script
+//! Cargo compiles and runs `build.rs` before it compiles the package, for what the package
+//! needs built or generated first:
+//!
+//! - the chapters of the lessons, each submitted to `Registry::discover` with
+//!   `inventory::submit!`, which it writes to `$OUT_DIR/lessons.rs` from the manifest,
+//!   `src/intermediate_rust/lessons.toml`, after checking it;
+//! - with the `ffi` feature, the C its lessons call, which the `cc` crate compiles with the
+//!   platform’s C compiler into a static library, and tells Cargo to link.
+//!
+//! The `build-scripts` lesson is about how it does both.
+
+use std::{collections::HashSet, env, fmt::Write as _, fs, path::Path};
+use toml::{Table, Value};
+
+const MANIFEST: &str = "src/intermediate_rust/lessons.toml";
+const CHAPTERS: &str = "src/intermediate_rust";
+
+fn main() {
+    // without them, Cargo would run the script again whenever any file of the package changed
+    println!("cargo::rerun-if-changed={MANIFEST}");
+    println!("cargo::rerun-if-changed=src/intermediate_rust/ffi/helper.c");
+
+    let manifest = fs::read_to_string(MANIFEST).unwrap_or_else(|e| panic!("{MANIFEST}: {e}"));
+    let (code, lessons) = submit_chapters(&manifest).unwrap_or_else(|e| panic!("{MANIFEST}: {e}"));
+    let out_dir = env::var_os("OUT_DIR").expect("Cargo sets `OUT_DIR` for build scripts");
+    fs::write(Path::new(&out_dir).join("lessons.rs"), code).expect("writing `lessons.rs`");
+    // what the compiler sees as `env!("IR_MANIFEST_LESSONS")`
+    println!("cargo::rustc-env=IR_MANIFEST_LESSONS={lessons}");
+
+    #[cfg(feature = "ffi")]
+    cc::Build::new()
+        .file("src/intermediate_rust/ffi/helper.c")
+        .warnings(true)
+        .compile("intermediate_rust_ffi");
+}
+
+/// The code that submits the chapters with lessons in the manifest, and how many lessons they
+/// register, or what is wrong with the manifest.
+fn submit_chapters(manifest: &str) -> Result<(String, usize), String> {
+    let manifest: Table = manifest.parse().map_err(|e| format!("{e}"))?;
+    let mut code = String::from(
+        "// Generated by `build.rs` from `src/intermediate_rust/lessons.toml`: edit that instead.\n",
+    );
+    // what a lesson may have as a prerequisite: the lessons before it, and the topics of the
+    // chapters that submit themselves
+    let mut ids = HashSet::new();
+    let mut topics = Vec::new();
+    let mut lessons = 0;
+    for chapter in array(&manifest, "chapter")? {
+        let chapter = chapter
+            .as_table()
+            .ok_or("a `[[chapter]]` that isn't a table")?;
+        let topic = string(chapter, "topic")?;
+        let Some(chapter_lessons) = chapter.get("lesson") else {
+            topics.push(topic);
+            continue;
+        };
+        let chapter_lessons = chapter_lessons
+            .as_array()
+            .ok_or("`[[chapter.lesson]]` isn't an array")?;
+        write!(
+            code,
+            "\n#[cfg(feature = {:?})]\n\
+             ir_core::inventory::submit! {{\n\
+             \x20   lesson::Chapter {{\n\
+             \x20       topic: {topic:?},\n\
+             \x20       register: |registry| {{\n",
+            string(chapter, "feature")?,
+        )
+        .unwrap();
+        for lesson in chapter_lessons {
+            let lesson = lesson
+                .as_table()
+                .ok_or("a `[[chapter.lesson]]` that isn't a table")?;
+            let id = string(lesson, "id")?;
+            if id != topic && !id.starts_with(&format!("{topic}/")) {
+                return Err(format!("`{id}` isn't a lesson of `{topic}`"));
+            }
+            if !ids.insert(id) {
+                return Err(format!("`{id}` is there twice"));
+            }
+            let file = string(lesson, "file")?;
+            if !Path::new(CHAPTERS).join(file).is_file() {
+                return Err(format!("`{id}`'s file, `{CHAPTERS}/{file}`, isn't there"));
+            }
+            // a lesson of a type of its own, which says the rest itself, with `#[derive(Lesson)]`
+            if let Some(lesson_type) = lesson.get("type") {
+                let lesson_type = lesson_type.as_str().ok_or("`type` isn't a string")?;
+                writeln!(code, "            registry.register({lesson_type});").unwrap();
+                lessons += 1;
+                continue;
+            }
+            let prerequisites = strings(lesson, "prerequisites")?;
+            if let Some(missing) = prerequisites.iter().find(|prerequisite| {
+                !ids.contains(*prerequisite)
+                    && !topics.iter().any(|topic| prerequisite.starts_with(topic))
+            }) {
+                return Err(format!(
+                    "`{id}` builds on `{missing}`, which isn't before it"
+                ));
+            }
+            let difficulty = string(lesson, "difficulty")?;
+            if !["Beginner", "Intermediate", "Advanced"].contains(&difficulty) {
+                return Err(format!("`{id}` has no `Difficulty::{difficulty}`"));
+            }
+            writeln!(
+                code,
+                "\x20           registry.register(lesson::Demo {{\n\
+                 \x20               id: {id:?},\n\
+                 \x20               title: {title:?},\n\
+                 \x20               run: {run},\n\
+                 \x20               info: lesson::LessonInfo {{\n\
+                 \x20                   description: {description:?},\n\
+                 \x20                   difficulty: lesson::Difficulty::{difficulty},\n\
+                 \x20                   prerequisites: &{prerequisites:?},\n\
+                 \x20                   tags: &{tags:?},\n\
+                 \x20                   source: include_str!(concat!(\n\
+                 \x20                       env!(\"CARGO_MANIFEST_DIR\"),\n\
+                 \x20                       \"/{CHAPTERS}/{file}\"\n\
+                 \x20                   )),\n\
+                 \x20               }},\n\
+                 \x20           }});",
+                title = string(lesson, "title")?,
+                run = string(lesson, "run")?,
+                description = string(lesson, "description")?,
+                tags = strings(lesson, "tags")?,
+            )
+            .unwrap();
+            lessons += 1;
+        }
+        if let Some(default) = chapter.get("default") {
+            let default = default.as_str().ok_or("`default` isn't a string")?;
+            if !ids.contains(default) {
+                return Err(format!(
+                    "the default of `{topic}`, `{default}`, isn't one of its"
+                ));
+            }
+            writeln!(code, "            registry.set_default({default:?});").unwrap();
+        }
+        code.push_str("        },\n    }\n}\n");
+    }
+    Ok((code, lessons))
+}
+
+fn array<'t>(table: &'t Table, key: &str) -> Result<&'t Vec<Value>, String> {
+    table
+        .get(key)
+        .and_then(Value::as_array)
+        .ok_or_else(|| format!("no `[[{key}]]`"))
+}
+
+fn string<'t>(table: &'t Table, key: &str) -> Result<&'t str, String> {
+    table
+        .get(key)
+        .and_then(Value::as_str)
+        .ok_or_else(|| format!("no `{key}` string in {table}"))
+}
+
+fn strings<'t>(table: &'t Table, key: &str) -> Result<Vec<&'t str>, String> {
+    array(table, key)?
+        .iter()
+        .map(|value| {
+            value
+                .as_str()
+                .ok_or_else(|| format!("`{key}` has a {value}"))
+        })
+        .collect()
+}
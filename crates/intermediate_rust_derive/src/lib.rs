@@ -0,0 +1,260 @@
+//! # The Procedural Macros of the Lessons
+//! A procedural macro is a function from code to code, `fn(TokenStream) -> TokenStream`, in a
+//! crate of its own, compiled for the machine that compiles, which the compiler loads and calls
+//! while it compiles the crates that use it. `syn` parses the tokens it is given into a syntax
+//! tree, and `quote!` writes the tokens it returns, as `macro_rules!` would, with `#name` where
+//! `$name` would be.
+//!
+//! There are two here, which say what a lesson is in fewer words than a `Demo` does:
+//! ```
+//! use intermediate_rust_derive::{lesson, Lesson};
+//! use ir_core::lesson::Lesson as _;
+//!
+//! // a `Demo`, with its `LessonInfo`, and this file as its `source`
+//! let demo = lesson! {
+//!     id: "macros/demo",
+//!     title: "A lesson",
+//!     run: || Ok(()),
+//!     difficulty: Beginner,
+//!     tags: ["macros"],
+//! };
+//! assert_eq!(demo.id(), "macros/demo");
+//!
+//! // `impl Lesson for Derived`, whose `run` is the function `run` names
+//! #[derive(Lesson)]
+//! #[lesson(id = "macros/derived", title = "Another", run = derived)]
+//! #[lesson(prerequisites = ["macros/demo"], tags = ["macros"])]
+//! struct Derived;
+//! fn derived() -> std::io::Result<()> {
+//!     Ok(())
+//! }
+//! assert_eq!(Derived.info().prerequisites, ["macros/demo"]);
+//! ```
+//! Both check, while compiling, what a `Registry` would only find out when it runs: that the
+//! ids are a topic and a name, in kebab-case, and that the difficulty is one there is.
+//! ```compile_fail
+//! intermediate_rust_derive::lesson! {
+//!     id: "Macros::Demo", // a lesson's id is its topic and its name, in kebab-case
+//!     title: "A lesson",
+//!     run: || Ok(()),
+//! }
+//! ```
+//! What they expand to names `::ir_core`, which the crate using them has to depend on.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::quote;
+use syn::{
+    bracketed,
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+    punctuated::Punctuated,
+    DeriveInput, Error, Ident, LitStr, Result, Token,
+};
+
+/// Implements `ir_core::lesson::Lesson` for a type, from `#[lesson(...)]` attributes: `id`,
+/// `title` and `run`, the function that runs the lesson, and the `LessonInfo`, whose fields are
+/// named as they are there. The `source` is the file the type is in, unless it is given.
+#[proc_macro_derive(Lesson, attributes(lesson))]
+pub fn derive_lesson(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    derive(input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// An `ir_core::lesson::Demo`, from `key: value` pairs of the same keys as `#[lesson(...)]`’s.
+#[proc_macro]
+pub fn lesson(input: TokenStream) -> TokenStream {
+    let fields = parse_macro_input!(input as Fields);
+    demo(fields)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// What the macros are told about a lesson.
+#[derive(Default)]
+struct Fields {
+    id: Option<LitStr>,
+    title: Option<LitStr>,
+    run: Option<syn::Expr>,
+    description: Option<LitStr>,
+    difficulty: Option<Ident>,
+    prerequisites: Option<Vec<LitStr>>,
+    tags: Option<Vec<LitStr>>,
+    source: Option<LitStr>,
+}
+impl Fields {
+    /// Parses the value of `key` from `input`, which is just past the `=` or `:`.
+    fn parse_value(&mut self, key: &Ident, input: ParseStream) -> Result<()> {
+        match key.to_string().as_str() {
+            "id" => self.id = Some(id(input.parse()?)?),
+            "title" => self.title = Some(input.parse()?),
+            "run" => self.run = Some(input.parse()?),
+            "description" => self.description = Some(input.parse()?),
+            "difficulty" => {
+                let difficulty: Ident = input.parse()?;
+                if !["Beginner", "Intermediate", "Advanced"].contains(&&*difficulty.to_string()) {
+                    let e = "the difficulties are `Beginner`, `Intermediate` and `Advanced`";
+                    return Err(Error::new(difficulty.span(), e));
+                }
+                self.difficulty = Some(difficulty);
+            }
+            "prerequisites" => {
+                let ids = strings(input)?;
+                self.prerequisites = Some(ids.into_iter().map(id).collect::<Result<_>>()?);
+            }
+            "tags" => self.tags = Some(strings(input)?),
+            "source" => self.source = Some(input.parse()?),
+            _ => return Err(Error::new(key.span(), format!("a lesson has no `{key}`"))),
+        }
+        Ok(())
+    }
+
+    /// `id`, `title` and `run`, which every lesson has.
+    fn required(&self) -> Result<(&LitStr, &LitStr, &syn::Expr)> {
+        let missing = |key| Error::new(Span::call_site(), format!("a lesson needs a `{key}`"));
+        Ok((
+            self.id.as_ref().ok_or_else(|| missing("id"))?,
+            self.title.as_ref().ok_or_else(|| missing("title"))?,
+            self.run.as_ref().ok_or_else(|| missing("run"))?,
+        ))
+    }
+
+    /// The `LessonInfo`, with `LessonInfo::default()`’s for what wasn’t given.
+    fn info(&self) -> TokenStream2 {
+        let mut fields = Vec::new();
+        if let Some(description) = &self.description {
+            fields.push(quote!(description: #description));
+        }
+        if let Some(difficulty) = &self.difficulty {
+            fields.push(quote!(difficulty: ::ir_core::lesson::Difficulty::#difficulty));
+        }
+        if let Some(prerequisites) = &self.prerequisites {
+            fields.push(quote!(prerequisites: &[#(#prerequisites),*]));
+        }
+        if let Some(tags) = &self.tags {
+            fields.push(quote!(tags: &[#(#tags),*]));
+        }
+        // the file the macro is used in, unless it was told another, relative to that file,
+        // as `include_str!` takes it
+        let source = self.source.clone().or_else(|| {
+            let file = proc_macro::Span::call_site().local_file()?;
+            let file = file.canonicalize().ok()?;
+            Some(LitStr::new(file.to_str()?, Span::call_site()))
+        });
+        if let Some(source) = source {
+            fields.push(quote!(source: include_str!(#source)));
+        }
+        // `LessonInfo` has five fields, and clippy objects to `..default()` after all of them
+        let rest = (fields.len() < 5).then(|| quote!(..::ir_core::lesson::LessonInfo::default()));
+        quote! {
+            ::ir_core::lesson::LessonInfo {
+                #(#fields,)*
+                #rest
+            }
+        }
+    }
+}
+
+/// `key: value, ...`, as `lesson!` is given them.
+impl Parse for Fields {
+    fn parse(input: ParseStream) -> Result<Fields> {
+        let mut fields = Fields::default();
+        while !input.is_empty() {
+            let key: Ident = input.parse()?;
+            input.parse::<Token![:]>()?;
+            fields.parse_value(&key, input)?;
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        Ok(fields)
+    }
+}
+
+/// `["a", "b"]`.
+fn strings(input: ParseStream) -> Result<Vec<LitStr>> {
+    let content;
+    bracketed!(content in input);
+    let strings = Punctuated::<LitStr, Token![,]>::parse_terminated(&content)?;
+    Ok(strings.into_iter().collect())
+}
+
+/// `id`, if it is a lesson’s id: a topic, or a topic and a name, in kebab-case.
+fn id(id: LitStr) -> Result<LitStr> {
+    let value = id.value();
+    let kebab_case = |part: &str| {
+        !part.is_empty()
+            && part
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+    };
+    match value.split('/').count() <= 2 && value.split('/').all(kebab_case) {
+        true => Ok(id),
+        false => Err(Error::new(
+            id.span(),
+            format!(
+                "`{value}` isn't a lesson's id: its topic and its name, in kebab-case, \
+                 separated by a `/`"
+            ),
+        )),
+    }
+}
+
+fn demo(fields: Fields) -> Result<TokenStream2> {
+    let (id, title, run) = fields.required()?;
+    let info = fields.info();
+    Ok(quote! {
+        ::ir_core::lesson::Demo {
+            id: #id,
+            title: #title,
+            run: #run,
+            info: #info,
+        }
+    })
+}
+
+fn derive(input: DeriveInput) -> Result<TokenStream2> {
+    let mut fields = Fields::default();
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("lesson"))
+    {
+        attr.parse_nested_meta(|meta| {
+            let key = meta.path.require_ident()?.clone();
+            fields.parse_value(&key, meta.value()?)
+        })?;
+    }
+    let (id, title, run) = fields.required()?;
+    let info = fields.info();
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::ir_core::lesson::Lesson for #name #type_generics #where_clause {
+            fn id(&self) -> &str {
+                #id
+            }
+
+            fn title(&self) -> &str {
+                #title
+            }
+
+            /// Runs as a `Demo` of `run` would.
+            fn run(&self, ctx: &mut ::ir_core::lesson::LessonCtx) -> ::std::io::Result<()> {
+                let demo = ::ir_core::lesson::Demo {
+                    id: #id,
+                    title: #title,
+                    run: #run,
+                    info: ::ir_core::lesson::Lesson::info(self),
+                };
+                ::ir_core::lesson::Lesson::run(&demo, ctx)
+            }
+
+            fn info(&self) -> ::ir_core::lesson::LessonInfo {
+                #info
+            }
+        }
+    })
+}
@@ -0,0 +1,37 @@
+//! # Concurrency
+//! The lessons about running code on more than one thread at once. A closure passed to
+//! `thread::spawn` may outlive the function that spawns it, so it has to own what it uses: the
+//! `lambda` lesson’s `move` is what makes that possible, and these lessons build on it.
+
+// the lessons print with `ir_core`’s `println!`, and clean up with `defer!`
+#[macro_use]
+extern crate ir_core;
+
+use intermediate_rust_derive::lesson;
+use ir_core::lesson::{Chapter, Registry};
+
+// the lesson writes each part as `Ok({ ... })`, as the ones in `src/lib.rs` do
+#[allow(clippy::unit_arg)]
+pub mod thread_and_move;
+
+/// Registers the chapter’s lessons, after `lambda`, which they build on.
+pub fn register(registry: &mut Registry) {
+    registry.register(lesson! {
+        id: "thread-and-move",
+        title: "Moving values into threads",
+        run: thread_and_move::main_thread,
+        description: "`move` closures hand a spawned thread ownership of the values it uses",
+        difficulty: Intermediate,
+        prerequisites: ["lambda"],
+        tags: ["closures", "concurrency"],
+        source: "thread_and_move.rs",
+    });
+}
+
+// how `Registry::discover` finds the chapter, without being told about this crate
+ir_core::inventory::submit! {
+    Chapter {
+        topic: "thread-and-move",
+        register,
+    }
+}
@@ -1,8 +1,10 @@
+use ir_core::utils::scope_guard::{guard, Guard};
 use std::{
     io::{Error, ErrorKind, Result},
     thread,
 };
 
+#[allow(clippy::io_other_error)] // the lesson spells out the `ErrorKind`
 pub fn main_thread() -> Result<()> {
     Ok({
         let i = 42;
@@ -11,11 +13,18 @@ pub fn main_thread() -> Result<()> {
         closure may outlive the current function, but it borrows `i`, which is owned by the current function may outlive borrowed value `i`
         to force the closure to take ownership of `i` (and any other referenced variables), use the `move` keyword: `move`
         */
-        let thread_handle = thread::spawn(move || i);
+        // printed last, whether we return normally or early with the error below
+        defer! { println!("main_thread done") }
+
+        // if we return before joining, the guard still joins the thread instead of detaching it
+        let thread_handle = guard(thread::spawn(move || i), |handle| {
+            let _ = handle.join();
+        });
 
         println!("at main_thread: {i}");
 
-        match thread_handle.join() {
+        // joining by hand takes the handle back out of the guard, so it isn’t joined twice
+        match Guard::into_inner(thread_handle).join() {
             Ok(thread_result) => println!("at thread::join: {thread_result}"),
             Err(_) => {
                 return Err(Error::new(
@@ -28,3 +37,7 @@ pub fn main_thread() -> Result<()> {
         println!("after thread::join: {i}");
     })
 }
+// at main_thread: 42
+// at thread::join: 42
+// after thread::join: 42
+// main_thread done
@@ -0,0 +1,536 @@
+//! # Lessons and the Registry
+//! Every lesson implements `Lesson`, and every chapter adds its lessons to the `Registry` in its
+//! `register` function. Nothing else needs to know which lessons there are: the command line
+//! looks them up by name, and `--all` runs whatever has been registered, in order.
+//!
+//! Nor which chapters there are: a chapter submits itself, as a `Chapter`, with
+//! `inventory::submit!`, next to its lessons, and `Registry::discover` finds every chapter that
+//! is linked into the program.
+//!
+//! Or even which programs: a plugin, a library loaded while the program runs, registers lessons
+//! of its own, with an `extern "C" fn register(registry: &mut Registry)` that `plugin!` exports.
+//!
+//! A lesson’s id is its topic and its name, separated by a `/`: `smart-pointers/ref-cell`. A
+//! topic with a single lesson is just the topic: `lambda`.
+//!
+//! A lesson can also describe itself with a `LessonInfo`: how hard it is, which lessons it
+//! builds on, and what it is about. The prerequisites are ids, and have to be registered before
+//! the lessons that need them.
+
+use super::{
+    output::{self, Output},
+    random,
+};
+use rand::rngs::StdRng;
+use std::{
+    ffi::CStr,
+    fmt::{self, Display},
+    io::Result,
+};
+
+/// What a lesson is run with.
+#[derive(Debug)]
+pub struct LessonCtx {
+    /// Where the lesson prints to, and how much; see `output::redirect`.
+    pub output: Output,
+    /// Where the lesson’s random numbers come from; see `random::with_rng`.
+    pub rng: StdRng,
+}
+/// Prints to stdout, with random numbers seeded with `0`.
+impl Default for LessonCtx {
+    fn default() -> LessonCtx {
+        LessonCtx {
+            output: Output::default(),
+            rng: random::seeded(0),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Difficulty {
+    #[default]
+    Beginner,
+    Intermediate,
+    Advanced,
+}
+impl Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Difficulty::Beginner => "beginner",
+            Difficulty::Intermediate => "intermediate",
+            Difficulty::Advanced => "advanced",
+        })
+    }
+}
+
+/// What a lesson says about itself, besides its title.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct LessonInfo {
+    /// One line, longer than the title.
+    pub description: &'static str,
+    pub difficulty: Difficulty,
+    /// The ids of the lessons to complete first.
+    pub prerequisites: &'static [&'static str],
+    /// What the lesson is about, such as `smart-pointers` or `concurrency`; a tag can be, but
+    /// doesn’t have to be, a topic.
+    pub tags: &'static [&'static str],
+    /// The source file the lesson is in, with `include_str!`, for searching and showing.
+    pub source: &'static str,
+}
+
+pub trait Lesson {
+    fn id(&self) -> &str;
+    fn title(&self) -> &str;
+    fn run(&self, ctx: &mut LessonCtx) -> Result<()>;
+
+    fn info(&self) -> LessonInfo {
+        LessonInfo::default()
+    }
+
+    /// The part of the id before the `/`.
+    fn topic(&self) -> &str {
+        self.id().split('/').next().unwrap_or_default()
+    }
+}
+
+/// A lesson that is a single function, which is most of them.
+pub struct Demo {
+    pub id: &'static str,
+    pub title: &'static str,
+    pub run: fn() -> Result<()>,
+    pub info: LessonInfo,
+}
+impl Lesson for Demo {
+    fn id(&self) -> &str {
+        self.id
+    }
+
+    fn title(&self) -> &str {
+        self.title
+    }
+
+    fn run(&self, ctx: &mut LessonCtx) -> Result<()> {
+        random::with_rng(&mut ctx.rng, || output::redirect(&mut ctx.output, self.run))
+    }
+
+    fn info(&self) -> LessonInfo {
+        self.info
+    }
+}
+
+/// A chapter, as it submits itself to `Registry::discover`:
+/// ```text
+/// ir_core::inventory::submit! {
+///     Chapter { topic: "thread-and-move", register }
+/// }
+/// ```
+pub struct Chapter {
+    /// The topic its lessons’ ids start with, which `discover` orders the chapters by.
+    pub topic: &'static str,
+    /// Registers its lessons.
+    pub register: fn(&mut Registry),
+}
+inventory::collect!(Chapter);
+
+/// The version of `ir-core` a plugin was built with, which has to be the program’s: Rust has no
+/// stable ABI, so a `Registry`, or a `Lesson`’s vtable, is only laid out the same by the same
+/// version of the crate, built by the same compiler.
+pub const PLUGIN_VERSION: &CStr =
+    match CStr::from_bytes_with_nul(concat!(env!("CARGO_PKG_VERSION"), "\0").as_bytes()) {
+        Ok(version) => version,
+        Err(_) => panic!("the version has a `\\0` in it"),
+    };
+
+/// Exports `$register`, a `fn(&mut Registry)`, as a plugin’s `register`, and the version of
+/// `ir-core` it was built with as its `ir_core_version`, for a program to check before it calls
+/// `register`. A panic can’t unwind out of an `extern "C" fn`, so one in `$register`, such as
+/// `Registry::register`’s, aborts the program.
+/// ```text
+/// ir_core::plugin!(register);
+/// ```
+#[macro_export]
+macro_rules! plugin {
+    ($register:path) => {
+        // named otherwise in Rust, as `$register` is likely to be called `register` as well
+        #[export_name = "ir_core_version"]
+        pub extern "C" fn __ir_core_plugin_version() -> *const ::std::ffi::c_char {
+            $crate::lesson::PLUGIN_VERSION.as_ptr()
+        }
+
+        #[export_name = "register"]
+        pub extern "C" fn __ir_core_plugin_register(registry: &mut $crate::lesson::Registry) {
+            $register(registry)
+        }
+    };
+}
+
+#[derive(Default)]
+pub struct Registry {
+    lessons: Vec<Box<dyn Lesson>>,
+    /// The lesson a topic named on its own runs, for the topics with more than one lesson.
+    defaults: Vec<&'static str>,
+}
+impl Registry {
+    pub fn new() -> Registry {
+        Registry::default()
+    }
+
+    /// A registry of every chapter submitted to the program: the ones whose topics are in
+    /// `order`, in that order, which has to be the one they build on each other in, then the
+    /// others, by topic. `inventory` visits them in no particular order of its own.
+    ///
+    /// # Panics
+    /// As `register` does, if a lesson’s prerequisites are in a chapter that comes after it.
+    pub fn discover(order: &[&str]) -> Registry {
+        let mut chapters: Vec<&Chapter> = inventory::iter::<Chapter>.into_iter().collect();
+        chapters.sort_by_key(|chapter| {
+            let position = order.iter().position(|topic| *topic == chapter.topic);
+            (position.unwrap_or(order.len()), chapter.topic)
+        });
+        let mut registry = Registry::new();
+        for chapter in chapters {
+            (chapter.register)(&mut registry);
+        }
+        registry
+    }
+
+    /// # Panics
+    /// If a lesson with the same id was registered already, or one of its prerequisites wasn’t.
+    pub fn register(&mut self, lesson: impl Lesson + 'static) {
+        assert!(
+            self.get(lesson.id()).is_none(),
+            "lesson `{}` is registered twice",
+            lesson.id()
+        );
+        for prerequisite in lesson.info().prerequisites {
+            assert!(
+                self.get(prerequisite).is_some(),
+                "lesson `{}` needs `{prerequisite}`, which isn't registered before it",
+                lesson.id()
+            );
+        }
+        self.lessons.push(Box::new(lesson));
+    }
+
+    /// Makes the lesson `id` the one that runs when only its topic is named.
+    pub fn set_default(&mut self, id: &'static str) {
+        self.defaults.push(id);
+    }
+
+    /// Every lesson, in the order it was registered.
+    pub fn iter(&self) -> impl Iterator<Item = &dyn Lesson> {
+        self.lessons.iter().map(|lesson| &**lesson)
+    }
+
+    /// The lessons `predicate` is true for, in the order they were registered.
+    pub fn filter<'r>(
+        &'r self,
+        mut predicate: impl FnMut(&dyn Lesson) -> bool + 'r,
+    ) -> impl Iterator<Item = &'r dyn Lesson> {
+        self.iter().filter(move |&lesson| predicate(lesson))
+    }
+
+    /// The topics, each once, in the order their first lesson was registered.
+    pub fn topics(&self) -> Vec<&str> {
+        let mut topics: Vec<&str> = Vec::new();
+        for lesson in self.iter() {
+            if !topics.contains(&lesson.topic()) {
+                topics.push(lesson.topic());
+            }
+        }
+        topics
+    }
+
+    /// Each tag with the lessons that have it, in the order the tags and lessons were registered.
+    pub fn by_tag(&self) -> Vec<(&'static str, Vec<&dyn Lesson>)> {
+        let mut tags: Vec<(&'static str, Vec<&dyn Lesson>)> = Vec::new();
+        for lesson in self.iter() {
+            for &tag in lesson.info().tags {
+                match tags.iter_mut().find(|(name, _)| *name == tag) {
+                    Some((_, lessons)) => lessons.push(lesson),
+                    None => tags.push((tag, vec![lesson])),
+                }
+            }
+        }
+        tags
+    }
+
+    pub fn get(&self, id: &str) -> Option<&dyn Lesson> {
+        self.iter().find(|lesson| lesson.id() == id)
+    }
+
+    /// Looks a lesson up by its id, as it is typed on the command line. The topic and lesson can
+    /// be separated by a space as well as a `/`, and `snake_case` works as well as `kebab-case`:
+    /// `smart_pointers ref_cell`. A topic on its own is its default lesson, or its first.
+    pub fn find(&self, name: &str) -> Option<&dyn Lesson> {
+        let id = name
+            .trim()
+            .replace('_', "-")
+            .split([' ', '/'])
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join("/");
+        let default = self
+            .defaults
+            .iter()
+            .find(|default| default.split('/').next() == Some(id.as_str()));
+        match (self.get(&id), default) {
+            (Some(lesson), _) => Some(lesson),
+            (None, Some(default)) => self.get(default),
+            (None, None) => self.iter().find(|lesson| lesson.topic() == id),
+        }
+    }
+
+    /// `find`, or why nothing was found: a list of the topics, or of the lessons of the topic if
+    /// `name` starts with one.
+    pub fn lookup(&self, name: &str) -> std::result::Result<&dyn Lesson, String> {
+        self.find(name).ok_or_else(|| {
+            let name = name.trim();
+            let topic = name.split([' ', '/']).next().unwrap_or_default();
+            let topic = topic.replace('_', "-");
+            match self.topics().contains(&topic.as_str()) {
+                true => {
+                    let lessons: Vec<_> = self
+                        .filter(|lesson| lesson.topic() == topic)
+                        .map(|lesson| lesson.id())
+                        .collect();
+                    format!(
+                        "there is no lesson `{name}`, the lessons of `{topic}` are: {}",
+                        lessons.join(", ")
+                    )
+                }
+                false => format!(
+                    "there is no topic `{topic}`, the topics are: {}",
+                    self.topics().join(", ")
+                ),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Error;
+
+    fn registry() -> Registry {
+        let mut registry = Registry::new();
+        registry.register(Demo {
+            id: "lambda",
+            title: "Closures",
+            run: || Ok(()),
+            info: LessonInfo::default(),
+        });
+        registry.register(Demo {
+            id: "memory/niche",
+            title: "Niches",
+            run: || Ok(()),
+            info: LessonInfo {
+                tags: &["memory"],
+                ..LessonInfo::default()
+            },
+        });
+        registry.register(Demo {
+            id: "memory/replace",
+            title: "Replacing",
+            run: || Err(Error::other("replaced")),
+            info: LessonInfo {
+                prerequisites: &["memory/niche"],
+                tags: &["memory", "ownership"],
+                ..LessonInfo::default()
+            },
+        });
+        registry.register(Demo {
+            id: "smart-pointers/ref-cell",
+            title: "RefCell",
+            run: || Ok(()),
+            info: LessonInfo::default(),
+        });
+        registry.register(Demo {
+            id: "smart-pointers/rc",
+            title: "Rc",
+            run: || Ok(()),
+            info: LessonInfo {
+                tags: &["ownership"],
+                ..LessonInfo::default()
+            },
+        });
+        registry.set_default("memory/replace");
+        registry
+    }
+
+    fn found(name: &str) -> Option<String> {
+        registry().find(name).map(|lesson| lesson.id().to_string())
+    }
+
+    #[test]
+    fn lessons_are_found_by_id_however_they_are_typed() {
+        for name in [
+            "memory/niche",
+            "memory niche",
+            "  memory   niche ",
+            "memory/niche/",
+        ] {
+            assert_eq!(found(name).as_deref(), Some("memory/niche"), "{name:?}");
+        }
+        assert_eq!(
+            found("smart_pointers/ref_cell").as_deref(),
+            Some("smart-pointers/ref-cell")
+        );
+        assert_eq!(found("lambda").as_deref(), Some("lambda"));
+    }
+
+    #[test]
+    fn a_topic_alone_is_its_default_lesson_or_its_first() {
+        assert_eq!(found("memory").as_deref(), Some("memory/replace"));
+        assert_eq!(
+            found("smart-pointers").as_deref(),
+            Some("smart-pointers/ref-cell")
+        );
+    }
+
+    #[test]
+    fn unknown_names_are_not_found() {
+        assert_eq!(found("memory/layout"), None);
+        assert_eq!(found("lambda/niche"), None);
+        assert_eq!(found("strings"), None);
+        assert_eq!(found(""), None);
+    }
+
+    #[test]
+    fn lookup_says_what_there_is_instead() {
+        let registry = registry();
+        assert_eq!(
+            registry.lookup("memory layout").err().as_deref(),
+            Some("there is no lesson `memory layout`, the lessons of `memory` are: memory/niche, memory/replace")
+        );
+        assert_eq!(
+            registry.lookup("strings").err().as_deref(),
+            Some("there is no topic `strings`, the topics are: lambda, memory, smart-pointers")
+        );
+        assert_eq!(registry.lookup("lambda").map(|x| x.id()), Ok("lambda"));
+    }
+
+    #[test]
+    fn topics_are_listed_once_each_in_order() {
+        assert_eq!(registry().topics(), ["lambda", "memory", "smart-pointers"]);
+    }
+
+    #[test]
+    fn lessons_can_be_filtered_and_run() {
+        let registry = registry();
+        let memory: Vec<_> = registry
+            .filter(|lesson| lesson.topic() == "memory")
+            .map(|lesson| lesson.title())
+            .collect();
+        assert_eq!(memory, ["Niches", "Replacing"]);
+
+        let mut ctx = LessonCtx::default();
+        assert!(registry.get("memory/niche").unwrap().run(&mut ctx).is_ok());
+        assert!(registry
+            .get("memory/replace")
+            .unwrap()
+            .run(&mut ctx)
+            .is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "lesson `lambda` is registered twice")]
+    fn an_id_can_only_be_registered_once() {
+        let mut registry = registry();
+        registry.register(Demo {
+            id: "lambda",
+            title: "Closures, again",
+            run: || Ok(()),
+            info: LessonInfo::default(),
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "lesson `strings` needs `memory/slices`, which isn't registered")]
+    fn prerequisites_are_registered_first() {
+        registry().register(Demo {
+            id: "strings",
+            title: "Strings",
+            run: || Ok(()),
+            info: LessonInfo {
+                prerequisites: &["memory/slices"],
+                ..LessonInfo::default()
+            },
+        });
+    }
+
+    #[test]
+    fn lessons_are_grouped_by_tag() {
+        let registry = registry();
+        let tags: Vec<_> = registry
+            .by_tag()
+            .into_iter()
+            .map(|(tag, lessons)| (tag, lessons.iter().map(|l| l.id()).collect::<Vec<_>>()))
+            .collect();
+        assert_eq!(
+            tags,
+            [
+                ("memory", vec!["memory/niche", "memory/replace"]),
+                ("ownership", vec!["memory/replace", "smart-pointers/rc"]),
+            ]
+        );
+    }
+
+    // submitted in the wrong order, and with a topic `discover` isn't given
+    inventory::submit! {
+        Chapter {
+            topic: "strings",
+            register: |registry| {
+                registry.register(Demo {
+                    id: "strings",
+                    title: "Strings",
+                    run: || Ok(()),
+                    info: LessonInfo {
+                        prerequisites: &["memory/slices"],
+                        ..LessonInfo::default()
+                    },
+                })
+            },
+        }
+    }
+    inventory::submit! {
+        Chapter {
+            topic: "memory",
+            register: |registry| {
+                registry.register(Demo {
+                    id: "memory/slices",
+                    title: "Slices",
+                    run: || Ok(()),
+                    info: LessonInfo::default(),
+                })
+            },
+        }
+    }
+    inventory::submit! {
+        Chapter {
+            topic: "extra",
+            register: |registry| {
+                registry.register(Demo {
+                    id: "extra",
+                    title: "Extra",
+                    run: || Ok(()),
+                    info: LessonInfo::default(),
+                })
+            },
+        }
+    }
+
+    #[test]
+    fn submitted_chapters_are_discovered_in_order() {
+        let registry = Registry::discover(&["memory", "strings"]);
+        assert_eq!(registry.topics(), ["memory", "strings", "extra"]);
+    }
+
+    #[test]
+    fn difficulties_are_ordered() {
+        assert!(Difficulty::Beginner < Difficulty::Intermediate);
+        assert!(Difficulty::Intermediate < Difficulty::Advanced);
+        assert_eq!(format!("[{:<12}]", Difficulty::Advanced), "[advanced    ]");
+    }
+}
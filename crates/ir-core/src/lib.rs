@@ -0,0 +1,38 @@
+//! # What Every Chapter Builds On
+//! A chapter of lessons is a crate of its own, like `ir-smart-pointers`, and this is what all of
+//! them use: `lesson`, the `Lesson` trait and the `Registry` a chapter registers its lessons in,
+//! `output`, where what a lesson prints goes, `style`, `locale`, `random` and `metrics`, and the
+//! helpers in `utils`. The lessons print with the macros exported here, which take the place of
+//! the standard ones in a chapter that imports them:
+//! ```text
+//! #[macro_use]
+//! extern crate ir_core;
+//! ```
+//! `println!`, `print!`, `eprintln!` and `eprint!` write to the current lesson’s output,
+//! `header!` prints the header of a part of a lesson, `span!` traces it, and `defer!` runs code
+//! when the scope ends.
+
+// declared first: the macros in `output`, `style` and `utils` are only visible to the modules
+// declared after them, and `output`’s stand in for the standard printing macros in all of those
+#[macro_use]
+pub mod output;
+#[macro_use]
+pub mod style;
+#[macro_use]
+pub mod utils;
+
+// before the lessons, whose `header!`s it translates
+pub mod locale;
+
+pub mod lesson;
+pub mod metrics;
+pub mod random;
+
+// the chapters submit themselves to `Registry::discover` with `inventory::submit!`, see `lesson`
+#[doc(hidden)]
+pub use inventory;
+
+// `span!` expands to `tracing`’s macros in the chapters, which needn’t depend on it themselves
+#[cfg(feature = "tracing")]
+#[doc(hidden)]
+pub use tracing;
@@ -0,0 +1,163 @@
+//! # The Language of the Lessons
+//! What the lessons teach is text: their explanations, the headers of their parts, their titles
+//! and descriptions, and the messages they print. That text is written in English, in the source,
+//! and the English is also what looks a translation up: `tr("Reference Cycles Can Leak Memory")`
+//! is that header in the current language, or the English as it is when there is no
+//! translation of it. `header!` translates the headers it prints, the catalog the titles and
+//! descriptions, and `explain` a lesson’s explanation, as a whole.
+//!
+//! The language is English, or Indonesian with `--lang id`, or whichever of them `LANG` names:
+//! ```text
+//! intermediate_rust --lang id --explain smart-pointers rc
+//! LANG=id_ID.UTF-8 intermediate_rust list
+//! ```
+//! A translation is a line in a language’s table, in `locale/`; a text without one is shown in
+//! English. Every title, description and header of the lessons has one in Indonesian, which
+//! `intermediate_rust`’s tests check, while the messages the lessons print are translated a
+//! little at a time.
+
+use std::{
+    env,
+    fmt::{self, Display},
+    str::FromStr,
+    sync::atomic::{AtomicU8, Ordering},
+};
+
+mod id;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Lang {
+    #[default]
+    En,
+    Id,
+}
+impl Lang {
+    /// The language a locale like `id_ID.UTF-8` is in, if there are lessons in it.
+    pub fn from_locale(locale: &str) -> Option<Lang> {
+        let code = locale.split(['_', '-', '.', '@']).next()?;
+        code.parse().ok()
+    }
+
+    /// The language `LANG` names, or English.
+    pub fn from_env() -> Lang {
+        env::var("LANG")
+            .ok()
+            .and_then(|locale| Lang::from_locale(&locale))
+            .unwrap_or_default()
+    }
+
+    /// The translations into this language, by their English.
+    pub fn table(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Lang::En => &[],
+            Lang::Id => id::TEXTS,
+        }
+    }
+
+    /// The explanations of the lessons in this language, by the lesson’s id, in the Markdown
+    /// the lessons’ doc comments are in.
+    pub fn explanations(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Lang::En => &[],
+            Lang::Id => id::EXPLANATIONS,
+        }
+    }
+}
+impl FromStr for Lang {
+    type Err = String;
+
+    fn from_str(code: &str) -> Result<Lang, String> {
+        match code.to_ascii_lowercase().as_str() {
+            "en" => Ok(Lang::En),
+            // `in` is what Indonesian was before ISO 639 renamed it
+            "id" | "in" => Ok(Lang::Id),
+            _ => Err(format!(
+                "there are no lessons in `{code}`, only in `en` and `id`"
+            )),
+        }
+    }
+}
+impl Display for Lang {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Lang::En => "en",
+            Lang::Id => "id",
+        })
+    }
+}
+
+/// The language of the whole program, not only of the current thread, since the lessons print
+/// from the threads they spawn too.
+static CURRENT: AtomicU8 = AtomicU8::new(0);
+
+pub fn set(lang: Lang) {
+    CURRENT.store(lang as u8, Ordering::Relaxed);
+}
+
+pub fn current() -> Lang {
+    match CURRENT.load(Ordering::Relaxed) {
+        1 => Lang::Id,
+        _ => Lang::En,
+    }
+}
+
+/// `text` in `lang`, or `text` if it hasn’t been translated into it.
+pub fn translate(lang: Lang, text: &str) -> &str {
+    lang.table()
+        .iter()
+        .find(|(english, _)| *english == text)
+        .map_or(text, |(_, translation)| translation)
+}
+
+/// `text` in the current language.
+pub fn tr(text: &str) -> &str {
+    translate(current(), text)
+}
+
+/// The explanation of the lesson `id` in the current language, if it has been translated into
+/// it.
+pub fn explanation(id: &str) -> Option<&'static str> {
+    current()
+        .explanations()
+        .iter()
+        .find(|(lesson, _)| *lesson == id)
+        .map(|(_, explanation)| *explanation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_language_is_named_by_its_code_or_a_locale() {
+        assert_eq!("ID".parse(), Ok(Lang::Id));
+        assert_eq!(Lang::from_locale("id_ID.UTF-8"), Some(Lang::Id));
+        assert_eq!(Lang::from_locale("en-GB"), Some(Lang::En));
+        assert_eq!(Lang::from_locale("C.UTF-8"), None);
+        assert!("fr".parse::<Lang>().is_err());
+    }
+
+    #[test]
+    fn what_isnt_translated_is_shown_in_english() {
+        assert_eq!(
+            translate(Lang::Id, "Reference Cycles Can Leak Memory"),
+            "Siklus Referensi Bisa Membocorkan Memori"
+        );
+        assert_eq!(translate(Lang::Id, "not translated"), "not translated");
+        assert_eq!(
+            translate(Lang::En, "Reference Cycles Can Leak Memory"),
+            "Reference Cycles Can Leak Memory"
+        );
+    }
+
+    #[test]
+    fn the_tables_translate_each_text_once() {
+        let table = Lang::Id.table();
+        for (at, (english, _)) in table.iter().enumerate() {
+            assert!(
+                !table[..at].iter().any(|(earlier, _)| earlier == english),
+                "{english:?} is translated twice"
+            );
+        }
+    }
+}
@@ -0,0 +1,792 @@
+//! Bahasa Indonesia: the lessons’ text, by its English.
+
+pub const TEXTS: &[(&str, &str)] = &[
+    // titles
+    ("Closures and the `Fn` traits", "Closure dan trait `Fn`"),
+    (
+        "Moving values into threads",
+        "Memindahkan nilai ke dalam thread",
+    ),
+    ("`String` and `&str`", "`String` dan `&str`"),
+    (
+        "Logging with the `log` facade",
+        "Logging dengan fasad `log`",
+    ),
+    (
+        "Shared until the first write, then cloned by `Rc::make_mut`",
+        "Dibagi sampai penulisan pertama, lalu disalin oleh `Rc::make_mut`",
+    ),
+    (
+        "`HashMap<K, V>` and `BTreeMap<K, V>`, including the `Entry` API",
+        "`HashMap<K, V>` dan `BTreeMap<K, V>`, termasuk API `Entry`",
+    ),
+    (
+        "`&[T]` and `&str` as fat pointers: a pointer plus a length",
+        "`&[T]` dan `&str` sebagai fat pointer: sebuah pointer ditambah panjang",
+    ),
+    (
+        "Running code on cleanup with the `Drop` trait",
+        "Menjalankan kode saat pembersihan dengan trait `Drop`",
+    ),
+    (
+        "Treating smart pointers like regular references with `Deref`",
+        "Memperlakukan smart pointer seperti referensi biasa dengan `Deref`",
+    ),
+    (
+        "`Box<T>` for allocating values on the heap",
+        "`Box<T>` untuk mengalokasikan nilai di heap",
+    ),
+    (
+        "`Rc<T>`, a reference counting type that enables multiple ownership",
+        "`Rc<T>`, tipe penghitung referensi yang memungkinkan banyak pemilik",
+    ),
+    (
+        "`RefCell<T>` and the interior mutability pattern",
+        "`RefCell<T>` dan pola interior mutability",
+    ),
+    (
+        "Checking unsafe code for undefined behavior with Miri",
+        "Memeriksa kode unsafe dari undefined behavior dengan Miri",
+    ),
+    (
+        "Fields, temporaries, `mem::drop`, `ManuallyDrop` and `mem::forget`",
+        "Field, nilai sementara, `mem::drop`, `ManuallyDrop` dan `mem::forget`",
+    ),
+    (
+        "An immutable, versioned list and map sharing structure through `Rc<T>`",
+        "List dan map immutable berversi yang berbagi struktur lewat `Rc<T>`",
+    ),
+    (
+        "`VecDeque<T>` as a queue and a sliding window, and a hand-made ring buffer",
+        "`VecDeque<T>` sebagai antrean dan jendela geser, dan ring buffer buatan tangan",
+    ),
+    (
+        "`BinaryHeap<T>` as a max-heap, as a min-heap, and with a custom `Ord`",
+        "`BinaryHeap<T>` sebagai max-heap, sebagai min-heap, dan dengan `Ord` buatan sendiri",
+    ),
+    (
+        "`len`, `capacity` and reallocation inside a `Vec<T>`",
+        "`len`, `capacity` dan realokasi di dalam `Vec<T>`",
+    ),
+    (
+        "Dynamically sized types, `?Sized`, and a struct ending in a slice",
+        "Tipe berukuran dinamis, `?Sized`, dan struct yang diakhiri sebuah slice",
+    ),
+    (
+        "Enum layout, and why `Option<Box<T>>` is the size of a pointer",
+        "Tata letak enum, dan mengapa `Option<Box<T>>` berukuran sebesar pointer",
+    ),
+    (
+        "Size, alignment, padding, and `#[repr(C)]`",
+        "Ukuran, perataan, padding, dan `#[repr(C)]`",
+    ),
+    (
+        "`mem::replace`, `mem::take`, `Option::take` and `mem::swap`: moving out of a `&mut`",
+        "`mem::replace`, `mem::take`, `Option::take` dan `mem::swap`: memindahkan keluar dari \
+         `&mut`",
+    ),
+    (
+        "`const fn`, `const` and `static`: computing values while compiling",
+        "`const fn`, `const` dan `static`: menghitung nilai saat kompilasi",
+    ),
+    (
+        "The five unsafe superpowers, and the safe code around them",
+        "Lima kekuatan super unsafe, dan kode aman di sekitarnya",
+    ),
+    (
+        "`as`, `From`, pointer casts and `transmute`, and what each can get wrong",
+        "`as`, `From`, cast pointer dan `transmute`, dan kesalahan yang bisa dibuat masing-masing",
+    ),
+    (
+        "`NonNull<T>`, the raw pointer inside `Box<T>`, `Rc<T>`, and `Vec<T>`",
+        "`NonNull<T>`, raw pointer di dalam `Box<T>`, `Rc<T>`, dan `Vec<T>`",
+    ),
+    (
+        "Fuzzing unsafe code, and how it complements Miri",
+        "Fuzzing kode unsafe, dan bagaimana ia melengkapi Miri",
+    ),
+    (
+        "Calling C from Rust with `extern \"C\"`",
+        "Memanggil C dari Rust dengan `extern \"C\"`",
+    ),
+    (
+        "Being called from C with `#[no_mangle] pub extern \"C\" fn`",
+        "Dipanggil dari C dengan `#[no_mangle] pub extern \"C\" fn`",
+    ),
+    (
+        "Build scripts: `build.rs`, `OUT_DIR`, `include!` and `rerun-if-changed`",
+        "Build script: `build.rs`, `OUT_DIR`, `include!` dan `rerun-if-changed`",
+    ),
+    (
+        "Lessons that register themselves: `inventory` and `linkme`",
+        "Pelajaran yang mendaftarkan dirinya sendiri: `inventory` dan `linkme`",
+    ),
+    (
+        "Loading a library while the program runs, with `libloading`",
+        "Memuat library saat program berjalan, dengan `libloading`",
+    ),
+    (
+        "Declarative macros with `macro_rules!`",
+        "Macro deklaratif dengan `macro_rules!`",
+    ),
+    (
+        "Procedural macros: `#[derive(Lesson)]` and `lesson!`",
+        "Macro prosedural: `#[derive(Lesson)]` dan `lesson!`",
+    ),
+    (
+        "Patterns: guards, `@` bindings, slices, binding modes and `let else`",
+        "Pola: guard, binding `@`, slice, mode binding dan `let else`",
+    ),
+    (
+        "Serialization with serde: derives, field attributes, a `Serialize` of our own",
+        "Serialisasi dengan serde: derive, atribut field, `Serialize` buatan sendiri",
+    ),
+    (
+        "Files: `BufReader`, `BufWriter`, temporary files and `?`",
+        "File: `BufReader`, `BufWriter`, file sementara dan `?`",
+    ),
+    (
+        "TCP: a line-echo server, its clients, and `TcpMessenger`",
+        "TCP: server gema baris, kliennya, dan `TcpMessenger`",
+    ),
+    (
+        "Child processes: `Command`, pipes and exit statuses",
+        "Proses anak: `Command`, pipe dan status keluar",
+    ),
+    (
+        "Configuration: the environment, `FromStr`, and layered settings",
+        "Konfigurasi: environment, `FromStr`, dan pengaturan berlapis",
+    ),
+    (
+        "Testing: unit and integration tests, test doubles, fixtures and `should_panic`",
+        "Pengujian: unit test dan integration test, test double, fixture dan `should_panic`",
+    ),
+    ("`Box<T>`, from `alloc`", "`Box<T>`, dari `alloc`"),
+    (
+        "`Rc<T>` and `Weak<T>`, from `alloc`",
+        "`Rc<T>` dan `Weak<T>`, dari `alloc`",
+    ),
+    (
+        "`Cell<T>` and `RefCell<T>`, from `core`",
+        "`Cell<T>` dan `RefCell<T>`, dari `core`",
+    ),
+    ("Iterators, from `core`", "Iterator, dari `core`"),
+    (
+        "A `Box<T>` of our own, with `alloc::alloc`",
+        "`Box<T>` buatan kita sendiri, dengan `alloc::alloc`",
+    ),
+    // descriptions
+    (
+        "How a closure captures its environment decides which of `FnOnce`, `FnMut` and `Fn` it \
+         implements",
+        "Cara sebuah closure menangkap lingkungannya menentukan mana dari `FnOnce`, `FnMut` dan \
+         `Fn` yang diimplementasikannya",
+    ),
+    (
+        "`move` closures hand a spawned thread ownership of the values it uses",
+        "Closure `move` menyerahkan kepemilikan nilai yang dipakainya kepada thread yang dibuat",
+    ),
+    (
+        "`String`, `&str`, UTF-8, and why a string can’t be indexed",
+        "`String`, `&str`, UTF-8, dan mengapa string tidak bisa diindeks",
+    ),
+    (
+        "Why a `&[T]` or a `&str` is two words",
+        "Mengapa sebuah `&[T]` atau `&str` berukuran dua word",
+    ),
+    (
+        "Customizing what happens when a value goes out of scope",
+        "Mengatur apa yang terjadi ketika sebuah nilai keluar dari scope",
+    ),
+    (
+        "`Deref`, `DerefMut` and deref coercion, on a `MyBox<T>` of our own",
+        "`Deref`, `DerefMut` dan deref coercion, pada `MyBox<T>` buatan kita sendiri",
+    ),
+    (
+        "Recursive types like the cons list, which only have a known size behind a box",
+        "Tipe rekursif seperti cons list, yang ukurannya hanya diketahui di balik sebuah box",
+    ),
+    (
+        "Sharing a cons list between owners, and watching the reference count change",
+        "Berbagi sebuah cons list di antara para pemilik, dan mengamati perubahan jumlah referensi",
+    ),
+    (
+        "Mutating through a shared reference, a mock messenger, and reference cycles with \
+         `Weak<T>`",
+        "Mengubah lewat referensi bersama, messenger tiruan, dan siklus referensi dengan `Weak<T>`",
+    ),
+    (
+        "The order values are dropped in, and how to drop them earlier, or never",
+        "Urutan nilai di-drop, dan cara men-drop-nya lebih awal, atau tidak sama sekali",
+    ),
+    (
+        "A list and a map whose every version stays valid, sharing their unchanged parts",
+        "List dan map yang setiap versinya tetap valid, dengan berbagi bagian yang tidak berubah",
+    ),
+    (
+        "Sharing a value until someone writes to it, with `Rc::make_mut`",
+        "Berbagi sebuah nilai sampai ada yang menulis ke dalamnya, dengan `Rc::make_mut`",
+    ),
+    (
+        "Storing keys with associated values, hashed or in order",
+        "Menyimpan kunci beserta nilainya, di-hash atau berurutan",
+    ),
+    (
+        "A ring buffer that pushes and pops at both ends",
+        "Ring buffer yang bisa push dan pop di kedua ujungnya",
+    ),
+    (
+        "A priority queue that always knows its greatest element",
+        "Antrean prioritas yang selalu tahu elemen terbesarnya",
+    ),
+    (
+        "What a `Vec<T>` is on the stack and on the heap, and when it reallocates",
+        "Apa isi sebuah `Vec<T>` di stack dan di heap, dan kapan ia melakukan realokasi",
+    ),
+    (
+        "Types whose size is only known at runtime, and the pointers to them",
+        "Tipe yang ukurannya baru diketahui saat runtime, dan pointer ke tipe itu",
+    ),
+    (
+        "How an enum stores its variant, and when it needs no room for it at all",
+        "Cara enum menyimpan variannya, dan kapan ia sama sekali tidak butuh tempat untuk itu",
+    ),
+    (
+        "How big a type is, where its fields go, and how to choose that ourselves",
+        "Seberapa besar sebuah tipe, di mana field-nya diletakkan, dan cara kita memilihnya \
+         sendiri",
+    ),
+    (
+        "Taking a value out from behind a mutable reference, and leaving another in its place",
+        "Mengambil nilai dari balik referensi mutable, dan meninggalkan nilai lain di tempatnya",
+    ),
+    (
+        "What the compiler can compute before the program runs, and what it puts in the binary",
+        "Apa yang bisa dihitung compiler sebelum program berjalan, dan apa yang ditaruhnya di \
+         binary",
+    ),
+    (
+        "Log levels and targets, and letting them through with `RUST_LOG`",
+        "Level dan target log, dan meloloskannya dengan `RUST_LOG`",
+    ),
+    (
+        "Raw pointers, unsafe functions, mutable statics, unions and unsafe traits, each kept \
+         sound",
+        "Raw pointer, fungsi unsafe, static mutable, union dan trait unsafe, masing-masing dijaga \
+         tetap sound",
+    ),
+    (
+        "Converting between types, from checked conversions to `transmute`, and the safe \
+         alternatives",
+        "Konversi antar tipe, dari konversi yang diperiksa sampai `transmute`, dan alternatif \
+         yang aman",
+    ),
+    (
+        "The raw pointer the standard smart pointers are built on, and the promises it makes",
+        "Raw pointer yang menjadi dasar smart pointer standar, dan janji-janji yang dibuatnya",
+    ),
+    (
+        "What Miri checks, and the undefined behavior it would catch in a few snippets",
+        "Apa yang diperiksa Miri, dan undefined behavior yang akan ditangkapnya pada beberapa \
+         potongan kode",
+    ),
+    (
+        "A `Vec<T>`, an `Rc<T>` and a `RefCell<T>` of our own, checked against the standard \
+         library's by fuzz targets",
+        "`Vec<T>`, `Rc<T>` dan `RefCell<T>` buatan kita sendiri, yang dibandingkan dengan milik \
+         standard library oleh target fuzz",
+    ),
+    (
+        "Declaring C functions, calling them, and `CString` and `CStr` for text",
+        "Mendeklarasikan fungsi C, memanggilnya, dan `CString` serta `CStr` untuk teks",
+    ),
+    (
+        "`LimitTracker` behind a C ABI, used by a C program, which calls back",
+        "`LimitTracker` di balik ABI C, dipakai oleh program C, yang memanggil balik",
+    ),
+    (
+        "Running code before the build to generate some of it, as this registry is",
+        "Menjalankan kode sebelum build untuk menghasilkan sebagian kodenya, seperti registry ini",
+    ),
+    (
+        "Collecting values from all over a program, with code that runs before `main`, or with \
+         the linker",
+        "Mengumpulkan nilai dari seluruh program, dengan kode yang berjalan sebelum `main`, atau \
+         dengan linker",
+    ),
+    (
+        "Looking functions up by name in a library, and lessons of a plugin, which `--plugin` \
+         loads",
+        "Mencari fungsi berdasarkan nama di dalam library, dan pelajaran dari plugin, yang dimuat \
+         oleh `--plugin`",
+    ),
+    (
+        "Matchers, repetition and hygiene, building `cons!`, `defer!` and `hashmap!` step by step",
+        "Matcher, pengulangan dan hygiene, membangun `cons!`, `defer!` dan `hashmap!` langkah \
+         demi langkah",
+    ),
+    (
+        "Functions from code to code, which the compiler calls, with `syn` and `quote`",
+        "Fungsi dari kode ke kode, yang dipanggil oleh compiler, dengan `syn` dan `quote`",
+    ),
+    (
+        "Taking values apart with patterns, from match guards to the lessons’ own `List` and \
+         `TreeNode`",
+        "Membongkar nilai dengan pola, dari match guard sampai `List` dan `TreeNode` milik \
+         pelajaran ini",
+    ),
+    (
+        "Turning values into JSON and `bincode`, and back, including the `Box<T>` lesson’s `List`",
+        "Mengubah nilai menjadi JSON dan `bincode`, dan kembali lagi, termasuk `List` dari \
+         pelajaran `Box<T>`",
+    ),
+    (
+        "Reading and writing files a buffer at a time, and cleaning up after them with `Drop`",
+        "Membaca dan menulis file satu buffer demi satu buffer, dan membersihkannya dengan `Drop`",
+    ),
+    (
+        "A server with a thread for each connection, and clients that talk to it over `localhost`",
+        "Server dengan satu thread untuk setiap koneksi, dan klien yang berbicara dengannya lewat \
+         `localhost`",
+    ),
+    (
+        "Running other programs, capturing and streaming what they write, and piping one into \
+         another",
+        "Menjalankan program lain, menangkap dan mengalirkan apa yang ditulisnya, dan menyalurkan \
+         satu ke yang lain",
+    ),
+    (
+        "How the runner’s own settings come from defaults, a file, the environment and the \
+         command line",
+        "Bagaimana pengaturan runner ini berasal dari nilai bawaan, sebuah file, environment dan \
+         command line",
+    ),
+    (
+        "Testing code that sends messages and reads the clock, with a fake `Clock` and a \
+         recording `Messenger`",
+        "Menguji kode yang mengirim pesan dan membaca jam, dengan `Clock` palsu dan `Messenger` \
+         yang merekam",
+    ),
+    (
+        "`Box`, `Vec` and `format!` are in `alloc`, which is all they need",
+        "`Box`, `Vec` dan `format!` ada di `alloc`, yang hanya itu yang mereka butuhkan",
+    ),
+    (
+        "Counting the owners of a value takes a heap, and no operating system",
+        "Menghitung pemilik sebuah nilai butuh heap, tetapi tidak butuh sistem operasi",
+    ),
+    (
+        "Interior mutability needs no heap, only `Rc` around it does",
+        "Interior mutability tidak butuh heap, hanya `Rc` di sekelilingnya yang butuh",
+    ),
+    (
+        "`Iterator` and its adapters are in `core`; `collect` may need `alloc`",
+        "`Iterator` dan adapternya ada di `core`; `collect` mungkin butuh `alloc`",
+    ),
+    (
+        "Allocating, dropping and freeing a value by hand, without `std`",
+        "Mengalokasikan, men-drop dan membebaskan sebuah nilai dengan tangan, tanpa `std`",
+    ),
+    // the parts of the smart pointer lessons
+    (
+        "List Surgery: Moving Ownership In and Out of a Box",
+        "Bedah List: Memindahkan Kepemilikan Masuk dan Keluar dari Box",
+    ),
+    (
+        "Appending by Sharing the Tail",
+        "Menyambung dengan Berbagi Ekor",
+    ),
+    (
+        "Counting the Cost of a Clone",
+        "Menghitung Biaya Sebuah Clone",
+    ),
+    (
+        "Visualizing Changes to strong_count and weak_count",
+        "Memvisualisasikan Perubahan strong_count dan weak_count",
+    ),
+    (
+        "Reference Cycles Can Leak Memory",
+        "Siklus Referensi Bisa Membocorkan Memori",
+    ),
+    (
+        "Breaking the Cycle with a Weak Link",
+        "Memutus Siklus dengan Tautan Weak",
+    ),
+    (
+        "Creating a Tree Data Structure: a Node with Child Nodes",
+        "Membuat Struktur Data Pohon: Node dengan Node Anak",
+    ),
+    (
+        "Keeping Parent Links Consistent",
+        "Menjaga Tautan Induk Tetap Konsisten",
+    ),
+    ("Searching a Tree", "Mencari di dalam Pohon"),
+    ("Traversal Orders", "Urutan Penelusuran"),
+    ("Tree Metrics", "Metrik Pohon"),
+    ("Comparing Trees", "Membandingkan Pohon"),
+    (
+        "Round-Tripping a Tree Through JSON",
+        "Membawa Pohon Bolak-balik Lewat JSON",
+    ),
+    (
+        "Finding Where a Conflicting Borrow Came From",
+        "Mencari Asal Peminjaman yang Bertabrakan",
+    ),
+    // the parts of the other lessons
+    (
+        "Sharing the Tail of a Persistent List",
+        "Berbagi Ekor dari List Persisten",
+    ),
+    (
+        "Path Copying in a Persistent Map",
+        "Penyalinan Jalur pada Map Persisten",
+    ),
+    (
+        "Sharing Until the First Write",
+        "Berbagi Sampai Penulisan Pertama",
+    ),
+    (
+        "Inserting and Reading Values",
+        "Menyisipkan dan Membaca Nilai",
+    ),
+    (
+        "Updating Values with the Entry API",
+        "Memperbarui Nilai dengan API Entry",
+    ),
+    ("Ordered Iteration", "Iterasi Berurutan"),
+    ("Custom Key Types", "Tipe Kunci Buatan Sendiri"),
+    ("Borrowed Lookups", "Pencarian dengan Pinjaman"),
+    (
+        "Using VecDeque as a Queue",
+        "Memakai VecDeque sebagai Antrean",
+    ),
+    (
+        "Using VecDeque as a Sliding Window",
+        "Memakai VecDeque sebagai Jendela Geser",
+    ),
+    (
+        "A Fixed-Capacity Ring Buffer",
+        "Ring Buffer Berkapasitas Tetap",
+    ),
+    ("A Max-Heap", "Max-Heap"),
+    ("A Min-Heap with Reverse", "Min-Heap dengan Reverse"),
+    (
+        "A Custom Ord for Dijkstra’s Frontier",
+        "Ord Buatan Sendiri untuk Frontier Dijkstra",
+    ),
+    (
+        "Mutating Keys Inside the Heap",
+        "Mengubah Kunci di Dalam Heap",
+    ),
+    ("Watching a Vec Grow", "Mengamati Vec Bertumbuh"),
+    ("Reserving Capacity Up Front", "Memesan Kapasitas di Awal"),
+    ("Giving Capacity Back", "Mengembalikan Kapasitas"),
+    (
+        "Holding a Reference Across a Push",
+        "Memegang Referensi Melewati Sebuah Push",
+    ),
+    (
+        "Slice References Are Two Words",
+        "Referensi Slice Berukuran Dua Word",
+    ),
+    ("Slicing Syntax", "Sintaks Slicing"),
+    (
+        "Passing Slices Instead of Owners",
+        "Mengoper Slice Alih-alih Pemilik",
+    ),
+    ("Splitting a Slice Mutably", "Membelah Slice Secara Mutable"),
+    ("A Custom DST", "DST Buatan Sendiri"),
+    ("Generic Functions and ?Sized", "Fungsi Generik dan ?Sized"),
+    ("Boxes as DST Containers", "Box sebagai Wadah DST"),
+    ("The Null Pointer Niche", "Niche Pointer Null"),
+    ("Niches in Other Types", "Niche pada Tipe Lain"),
+    ("Nested Enums", "Enum Bersarang"),
+    ("Enums Without a Niche", "Enum Tanpa Niche"),
+    (
+        "Primitive Sizes and Alignments",
+        "Ukuran dan Perataan Tipe Primitif",
+    ),
+    (
+        "Padding and Field Reordering",
+        "Padding dan Penyusunan Ulang Field",
+    ),
+    (
+        "The Layout of This Crate’s Types",
+        "Tata Letak Tipe-tipe di Crate Ini",
+    ),
+    ("Indexing into Strings", "Mengindeks String"),
+    (
+        "Bytes, Scalar Values, and Grapheme Clusters",
+        "Byte, Nilai Skalar, dan Kluster Grafem",
+    ),
+    ("How a String Grows", "Bagaimana String Bertumbuh"),
+    (
+        "Concatenation with push_str, +, and format!",
+        "Penggabungan dengan push_str, +, dan format!",
+    ),
+    (
+        "Converting Through Deref<Target = str>",
+        "Konversi Lewat Deref<Target = str>",
+    ),
+    ("Levels", "Level"),
+    ("Targets", "Target"),
+    ("The Null Niche", "Niche Null"),
+    ("A Pointer Round Trip", "Pointer Pulang-Pergi"),
+    ("Covariance", "Kovarians"),
+    ("A Use After Free", "Use After Free"),
+    ("Aliasing", "Aliasing"),
+    ("Invalid Values", "Nilai yang Tidak Valid"),
+    ("A `Vec<T>` of Our Own", "`Vec<T>` Buatan Kita Sendiri"),
+    ("A Fuzz Target", "Target Fuzz"),
+    ("`const fn`", "`const fn`"),
+    ("`const` Items and `static`s", "Item `const` dan `static`"),
+    ("`const` Blocks", "Blok `const`"),
+    (
+        "A Lookup Table Built While Compiling",
+        "Tabel Pencarian yang Dibangun saat Kompilasi",
+    ),
+    (
+        "The Limits of Const Evaluation",
+        "Batas-batas Evaluasi Const",
+    ),
+    ("Dereferencing Raw Pointers", "Mendereferensi Raw Pointer"),
+    ("Calling Unsafe Functions", "Memanggil Fungsi Unsafe"),
+    ("Safe Abstractions", "Abstraksi yang Aman"),
+    (
+        "Mutable Statics, and What to Use Instead",
+        "Static Mutable, dan Apa yang Dipakai Sebagai Gantinya",
+    ),
+    ("Unions", "Union"),
+    (
+        "Implementing Unsafe Traits",
+        "Mengimplementasikan Trait Unsafe",
+    ),
+    ("`as` Casts", "Cast dengan `as`"),
+    ("`From` and `TryFrom`", "`From` dan `TryFrom`"),
+    ("Pointer Casts", "Cast Pointer"),
+    ("`transmute`", "`transmute`"),
+    ("Safe Alternatives", "Alternatif yang Aman"),
+    (
+        "An `Rc<T>` and a `RefCell<T>` of Our Own",
+        "`Rc<T>` dan `RefCell<T>` Buatan Kita Sendiri",
+    ),
+    ("Declaring C Functions", "Mendeklarasikan Fungsi C"),
+    ("Text Across the Boundary", "Teks Melintasi Batas"),
+    (
+        "A Helper Built by `build.rs`",
+        "Helper yang Dibangun oleh `build.rs`",
+    ),
+    ("A C Program Using Rust", "Program C yang Memakai Rust"),
+    ("`OUT_DIR`", "`OUT_DIR`"),
+    (
+        "Including Generated Code",
+        "Menyertakan Kode yang Dihasilkan",
+    ),
+    ("Talking to Cargo", "Berbicara dengan Cargo"),
+    ("Submitting to a Collection", "Mengirim ke Sebuah Koleksi"),
+    ("Life Before `main`", "Kehidupan Sebelum `main`"),
+    ("Distributed Slices", "Slice Terdistribusi"),
+    ("Opening a Library", "Membuka Sebuah Library"),
+    (
+        "Symbols Borrow Their Library",
+        "Simbol Meminjam Library-nya",
+    ),
+    ("Plugins in Rust", "Plugin dalam Rust"),
+    ("Matchers and Fragments", "Matcher dan Fragmen"),
+    ("Repetition", "Pengulangan"),
+    (
+        "Building `cons!`, Step by Step",
+        "Membangun `cons!`, Langkah demi Langkah",
+    ),
+    (
+        "Building `defer!`, Step by Step",
+        "Membangun `defer!`, Langkah demi Langkah",
+    ),
+    ("Hygiene", "Hygiene"),
+    ("A `hashmap!` Constructor", "Konstruktor `hashmap!`"),
+    ("Deriving a Trait", "Men-derive Sebuah Trait"),
+    ("A Function-like Macro", "Macro Mirip Fungsi"),
+    ("Match Guards", "Match Guard"),
+    ("`@` Bindings", "Binding `@`"),
+    (
+        "Destructuring the Lessons’ Types",
+        "Membongkar Tipe-tipe Pelajaran Ini",
+    ),
+    ("Slice Patterns", "Pola Slice"),
+    ("Binding Modes and `ref`", "Mode Binding dan `ref`"),
+    (
+        "`let else`, and `if let` Instead of Chains",
+        "`let else`, dan `if let` Alih-alih Rantai",
+    ),
+    (
+        "Deriving `Serialize` and `Deserialize`",
+        "Men-derive `Serialize` dan `Deserialize`",
+    ),
+    ("Field Attributes", "Atribut Field"),
+    (
+        "Serializing a Type of Another Crate",
+        "Menserialisasi Tipe dari Crate Lain",
+    ),
+    ("JSON and a Binary Format", "JSON dan Format Biner"),
+    (
+        "Temporary Files, Cleaned Up by `Drop`",
+        "File Sementara, Dibersihkan oleh `Drop`",
+    ),
+    ("Buffered Writing", "Penulisan dengan Buffer"),
+    (
+        "Reading All at Once, or Line by Line",
+        "Membaca Sekaligus, atau Baris demi Baris",
+    ),
+    ("Errors and the `?` Operator", "Galat dan Operator `?`"),
+    ("A Line-Echo Server", "Server Gema Baris"),
+    (
+        "Reading and Writing a Stream",
+        "Membaca dan Menulis Sebuah Stream",
+    ),
+    (
+        "A Thread for Each Connection",
+        "Satu Thread untuk Setiap Koneksi",
+    ),
+    (
+        "Sending a `LimitTracker`’s Messages over TCP",
+        "Mengirim Pesan `LimitTracker` lewat TCP",
+    ),
+    (
+        "Running a Command and Capturing Its Output",
+        "Menjalankan Perintah dan Menangkap Keluarannya",
+    ),
+    (
+        "Standard Error and the Exit Status",
+        "Standard Error dan Status Keluar",
+    ),
+    (
+        "Piping One Child into Another",
+        "Menyalurkan Satu Proses Anak ke Proses Lain",
+    ),
+    (
+        "Streaming a Child’s Output",
+        "Mengalirkan Keluaran Proses Anak",
+    ),
+    ("Reading the Environment", "Membaca Environment"),
+    ("Parsing with `FromStr`", "Parsing dengan `FromStr`"),
+    ("Layered Configuration", "Konfigurasi Berlapis"),
+    ("Test Doubles Behind a Trait", "Test Double di Balik Trait"),
+    (
+        "Matching the Shape of a Result",
+        "Mencocokkan Bentuk Sebuah Hasil",
+    ),
+    // the parts of the lessons without `std`
+    ("A Value on the Heap", "Sebuah Nilai di Heap"),
+    ("A Recursive Type", "Tipe Rekursif"),
+    ("Sharing a Value", "Berbagi Sebuah Nilai"),
+    ("A Weak Reference", "Referensi Weak"),
+    ("Interior Mutability", "Interior Mutability"),
+    (
+        "A Borrow Checked at Runtime",
+        "Peminjaman yang Diperiksa saat Runtime",
+    ),
+    ("Shared and Mutable", "Dibagi dan Mutable"),
+    ("An Iterator of Our Own", "Iterator Buatan Kita Sendiri"),
+    ("Collecting without `std`", "Mengumpulkan tanpa `std`"),
+    ("Allocating by Hand", "Mengalokasikan dengan Tangan"),
+    // what `LimitTracker` tells its messenger
+    (
+        "Error: You are over your quota!",
+        "Galat: Anda sudah melebihi kuota Anda!",
+    ),
+    (
+        "Urgent warning: You've used up over 90% of your quota!",
+        "Peringatan mendesak: Anda sudah memakai lebih dari 90% kuota Anda!",
+    ),
+    (
+        "Warning: You've used up over 75% of your quota!",
+        "Peringatan: Anda sudah memakai lebih dari 75% kuota Anda!",
+    ),
+];
+
+pub const EXPLANATIONS: &[(&str, &str)] = &[
+    (
+        "smart-pointers/box",
+        "\
+# Memakai `Box<T>` untuk Menunjuk Data di Heap
+Smart pointer yang paling sederhana adalah box, yang tipenya ditulis `Box<T>`. Box
+memungkinkan kita menyimpan data di heap alih-alih di stack. Yang tersisa di stack adalah
+pointer ke data di heap itu.
+
+Box tidak punya overhead kinerja, selain menyimpan datanya di heap alih-alih di stack. Tetapi
+box juga tidak punya banyak kemampuan tambahan. Box paling sering dipakai dalam situasi ini:
+1. Ketika kita punya tipe yang ukurannya tidak bisa diketahui saat kompilasi, dan kita ingin
+   memakai nilai bertipe itu dalam konteks yang membutuhkan ukuran pasti
+2. Ketika kita punya data dalam jumlah besar dan ingin memindahkan kepemilikannya, tetapi
+   memastikan datanya tidak disalin ketika kita melakukannya
+3. Ketika kita ingin memiliki sebuah nilai dan hanya peduli bahwa tipenya mengimplementasikan
+   trait tertentu, bukan tipe yang spesifik
+
+`cargo bench --features bench -- box` mengukur overhead yang ada: alokasinya.",
+    ),
+    (
+        "smart-pointers/rc",
+        "\
+# `Rc<T>`, Smart Pointer dengan Penghitung Referensi
+Pada sebagian besar kasus, kepemilikan itu jelas: kita tahu persis variabel mana yang memiliki
+sebuah nilai. Namun, ada kasus ketika satu nilai bisa punya banyak pemilik. Misalnya, pada
+struktur data graf, banyak sisi bisa menunjuk ke node yang sama, dan node itu secara konsep
+dimiliki oleh semua sisi yang menunjuk kepadanya. Sebuah node tidak boleh dibersihkan kecuali
+tidak ada sisi yang menunjuk kepadanya, sehingga ia tidak punya pemilik.
+
+Kepemilikan ganda harus diaktifkan secara eksplisit dengan tipe Rust `Rc<T>`, singkatan dari
+_reference counting_. Tipe `Rc<T>` mencatat jumlah referensi ke sebuah nilai untuk menentukan
+apakah nilai itu masih dipakai. Jika referensi ke sebuah nilai tinggal nol, nilai itu bisa
+dibersihkan tanpa ada referensi yang menjadi tidak valid.
+
+Bayangkan `Rc<T>` sebagai TV di ruang keluarga. Ketika orang pertama masuk untuk menonton, ia
+menyalakannya. Orang lain bisa masuk dan ikut menonton. Ketika orang terakhir keluar, ia
+mematikan TV karena tidak dipakai lagi. Kalau seseorang mematikan TV sementara yang lain masih
+menonton, penonton yang tersisa tentu akan protes!
+
+Perhatikan bahwa `Rc<T>` hanya untuk skenario satu thread. `Arc<T>`, yang bisa dibagi antar
+thread, memperbarui hitungannya dengan instruksi atomik, yang lebih mahal:
+`cargo bench --features bench -- shared_clone` membandingkan keduanya.",
+    ),
+    (
+        "smart-pointers/ref-cell",
+        "\
+# `RefCell<T>` dan Pola Interior Mutability
+_Interior mutability_ adalah pola desain di Rust yang memungkinkan kita mengubah data bahkan
+ketika ada referensi immutable ke data itu; biasanya, tindakan ini dilarang oleh aturan
+peminjaman. Untuk mengubah data, pola ini memakai kode `unsafe` di dalam sebuah struktur data
+untuk melenturkan aturan Rust yang biasa tentang perubahan dan peminjaman.
+
+Kita hanya bisa memakai tipe dengan pola interior mutability ketika kita bisa memastikan aturan
+peminjaman dipatuhi saat runtime, walaupun compiler tidak bisa menjaminnya. Kode `unsafe` yang
+terlibat kemudian dibungkus dalam API yang aman, dan tipe luarnya tetap immutable.
+
+Memeriksa aturan itu saat runtime sedikit memakan biaya pada setiap peminjaman, yang diukur oleh
+`cargo bench --features bench -- borrow`.",
+    ),
+    (
+        "smart-pointers/drop",
+        "\
+# Menjalankan Kode saat Pembersihan dengan Trait `Drop`
+`Drop` memungkinkan kita mengatur apa yang terjadi ketika sebuah nilai akan keluar dari scope.
+Implementasi trait `Drop` bisa diberikan pada tipe apa pun, dan kode itu bisa dipakai untuk
+melepaskan sumber daya seperti file atau koneksi jaringan.
+
+Trait `Drop` hampir selalu dipakai ketika mengimplementasikan smart pointer. Misalnya, ketika
+sebuah `Box<T>` di-drop, ia akan membebaskan ruang di heap yang ditunjuknya.
+
+Di Rust, kita bisa menentukan kode yang dijalankan setiap kali sebuah nilai keluar dari scope,
+dan compiler akan menyisipkan kode itu secara otomatis. Hasilnya, kita tidak perlu berhati-hati
+menaruh kode pembersihan di setiap tempat sebuah nilai selesai dipakai, dan tetap tidak
+membocorkan sumber daya!",
+    ),
+    (
+        "smart-pointers/deref",
+        "\
+# Memperlakukan Smart Pointer Seperti Referensi Biasa dengan Trait `Deref`
+Mengimplementasikan trait `Deref` memungkinkan kita mengatur perilaku operator dereferensi `*`
+(jangan tertukar dengan operator perkalian atau glob). Dengan mengimplementasikan `Deref`
+sedemikian rupa sehingga sebuah smart pointer bisa diperlakukan seperti referensi biasa, kita
+bisa menulis kode yang bekerja pada referensi, dan memakai kode itu pada smart pointer juga.",
+    ),
+];
@@ -0,0 +1,151 @@
+//! # Counting What a Lesson Does
+//! The lessons make claims about cost: that `Rc::clone` only increments a count, while a deep
+//! clone copies every value; that a value is dropped exactly once. `Metrics` counts those things
+//! while a lesson runs, so that the claims come with numbers:
+//! - `rc_clone` stands in for `Rc::clone` and counts it,
+//! - `deep_clone` stands in for `Clone::clone` and counts it, as does an instrumented `Clone`
+//!   impl with `count(Counter::DeepClone)`,
+//! - an instrumented `Drop` impl counts each drop with `count(Counter::Drop)`.
+//!
+//! The runner collects the metrics of each lesson and prints them after it, and a lesson can
+//! `collect` the metrics of one of its parts to compare it with another. With the `track-alloc`
+//! feature, `collect` also counts the bytes allocated. Outside of `collect`, nothing is counted.
+
+use super::utils::{measure, scope_guard::ScopeGuard};
+use std::{
+    cell::Cell,
+    fmt::{self, Display},
+    rc::Rc,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Counter {
+    RcClone,
+    DeepClone,
+    Drop,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Metrics {
+    pub rc_clones: usize,
+    pub deep_clones: usize,
+    pub drops: usize,
+    /// `None` without the `track-alloc` feature.
+    pub allocated: Option<usize>,
+}
+impl Metrics {
+    /// Whether there is anything to show.
+    pub fn is_empty(&self) -> bool {
+        self.rc_clones == 0 && self.deep_clones == 0 && self.drops == 0 && self.allocated.is_none()
+    }
+
+    fn add(&mut self, other: Metrics) {
+        self.rc_clones += other.rc_clones;
+        self.deep_clones += other.deep_clones;
+        self.drops += other.drops;
+    }
+}
+impl Display for Metrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} Rc clone(s), {} deep clone(s), {} drop(s)",
+            self.rc_clones, self.deep_clones, self.drops
+        )?;
+        match self.allocated {
+            Some(allocated) => write!(f, ", {} allocated", measure::bytes(allocated)),
+            None => Ok(()),
+        }
+    }
+}
+
+thread_local! {
+    static CURRENT: Cell<Option<Metrics>> = const { Cell::new(None) };
+}
+
+/// Adds one to `counter`, if the lesson is running inside `collect`.
+pub fn count(counter: Counter) {
+    if let Some(mut metrics) = CURRENT.get() {
+        match counter {
+            Counter::RcClone => metrics.rc_clones += 1,
+            Counter::DeepClone => metrics.deep_clones += 1,
+            Counter::Drop => metrics.drops += 1,
+        }
+        CURRENT.set(Some(metrics));
+    }
+}
+
+/// `Rc::clone`, counted.
+pub fn rc_clone<T: ?Sized>(rc: &Rc<T>) -> Rc<T> {
+    count(Counter::RcClone);
+    Rc::clone(rc)
+}
+
+/// `Clone::clone`, counted as a deep clone.
+pub fn deep_clone<T: Clone>(value: &T) -> T {
+    count(Counter::DeepClone);
+    value.clone()
+}
+
+/// Runs `f`, and returns what it returned and what it did. What `f` did also counts towards the
+/// `collect` this one is nested in, if any. Whatever was collecting before is collecting again
+/// afterwards, even if `f` panics.
+pub fn collect<R>(f: impl FnOnce() -> R) -> (R, Metrics) {
+    let previous = CURRENT.replace(Some(Metrics::default()));
+    let restore = ScopeGuard::new(|| CURRENT.set(previous));
+    let (result, measurement) = measure::measure(f);
+    let mut metrics = CURRENT.take().unwrap_or_default();
+    drop(restore);
+    if let Some(mut outer) = CURRENT.get() {
+        outer.add(metrics);
+        CURRENT.set(Some(outer));
+    }
+    metrics.allocated = measurement.allocs.map(|allocs| allocs.bytes);
+    (result, metrics)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_only_counts_inside_collect() {
+        count(Counter::Drop);
+        let a = Rc::new(vec![1, 2, 3]);
+        let ((), metrics) = collect(|| {
+            let _b = rc_clone(&a);
+            let _c = deep_clone(&*a);
+            count(Counter::Drop);
+        });
+        assert_eq!(
+            (metrics.rc_clones, metrics.deep_clones, metrics.drops),
+            (1, 1, 1)
+        );
+        assert_eq!(metrics.allocated.is_some(), cfg!(feature = "track-alloc"));
+    }
+
+    #[test]
+    fn a_nested_collect_counts_towards_the_outer_one() {
+        let a = Rc::new(5);
+        let (inner, outer) = collect(|| {
+            let _b = rc_clone(&a);
+            collect(|| rc_clone(&a)).1
+        });
+        assert_eq!(inner.rc_clones, 1);
+        assert_eq!(outer.rc_clones, 2);
+    }
+
+    #[test]
+    fn it_shows_the_counts() {
+        let metrics = Metrics {
+            rc_clones: 3,
+            drops: 1,
+            allocated: Some(2048),
+            ..Metrics::default()
+        };
+        assert_eq!(
+            metrics.to_string(),
+            "3 Rc clone(s), 0 deep clone(s), 1 drop(s), 2.0 KiB allocated"
+        );
+    }
+}
@@ -0,0 +1,495 @@
+//! # Where the Lessons Print To
+//! The lessons print with `println!` and friends, from their demo functions and from their
+//! `Drop` impls alike, and `Drop::drop` can’t be handed a writer. So instead of a writer being
+//! passed down to every function that prints, the `Output` a lesson is run with becomes the
+//! current thread’s output for as long as the lesson runs, and this module’s `println!`,
+//! `print!`, `eprintln!` and `eprint!`, which the lessons use in place of the standard ones,
+//! write to it.
+//!
+//! Outside of a lesson the macros print as the standard ones do, and so they do on a thread the
+//! lesson starts with `thread::spawn`. A thread started with this module’s `spawn` instead
+//! prints to the output of the thread that started it: what it prints is sent back to that
+//! thread, which writes it before the next thing it prints itself, and when it `join`s it.
+//!
+//! What is logged with `log` goes the same way, through `Logged`: the logger writes its lines
+//! there, and they end up with whatever the current lesson `eprint`s.
+//!
+//! The current output also says how much the lesson should show, its `verbosity`, and whether
+//! what it prints may be colored; see `style`.
+
+use super::utils::scope_guard::ScopeGuard;
+use std::{
+    cell::RefCell,
+    env, fmt,
+    io::{self, IsTerminal, Write},
+    mem,
+    rc::Rc,
+    str::FromStr,
+    sync::mpsc::{self, Receiver, Sender, TryRecvError},
+    thread,
+};
+
+/// How much a lesson shows: `--quiet` nothing, `-v` the extra detail some lessons have, and
+/// `-vv` each step on the way as well.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Verbosity {
+    Quiet,
+    #[default]
+    Normal,
+    Verbose,
+    Debug,
+}
+impl FromStr for Verbosity {
+    type Err = String;
+
+    /// The name of a level, as a configuration file or an environment variable has it.
+    fn from_str(name: &str) -> Result<Verbosity, String> {
+        match name.to_ascii_lowercase().as_str() {
+            "quiet" => Ok(Verbosity::Quiet),
+            "normal" => Ok(Verbosity::Normal),
+            "verbose" => Ok(Verbosity::Verbose),
+            "debug" => Ok(Verbosity::Debug),
+            _ => Err(format!(
+                "`{name}` isn't a verbosity: `quiet`, `normal`, `verbose` or `debug`"
+            )),
+        }
+    }
+}
+impl fmt::Display for Verbosity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            Verbosity::Quiet => "quiet",
+            Verbosity::Normal => "normal",
+            Verbosity::Verbose => "verbose",
+            Verbosity::Debug => "debug",
+        })
+    }
+}
+
+/// Where a lesson’s output goes: `out` for what it prints, `err` for what it `eprint`s.
+pub struct Output {
+    pub out: Box<dyn Write>,
+    pub err: Box<dyn Write>,
+    /// Whether `style` may color what is printed.
+    pub color: bool,
+    pub verbosity: Verbosity,
+}
+impl Output {
+    /// Prints as the standard macros do, which the test harness can capture. It is colored if
+    /// stdout is a terminal, unless `NO_COLOR` is set to anything at all, as no-color.org asks.
+    pub fn stdout() -> Output {
+        Output {
+            out: Box::new(Printed(false)),
+            err: Box::new(Printed(true)),
+            color: io::stdout().is_terminal()
+                && env::var_os("NO_COLOR").is_none_or(|value| value.is_empty()),
+            verbosity: Verbosity::Normal,
+        }
+    }
+
+    pub fn silent() -> Output {
+        Output {
+            out: Box::new(io::sink()),
+            err: Box::new(io::sink()),
+            color: false,
+            verbosity: Verbosity::Quiet,
+        }
+    }
+
+    /// Collects what is printed in `out` and `err`, without color, so it can be compared.
+    pub fn captured(out: &Buffer, err: &Buffer) -> Output {
+        Output {
+            out: Box::new(out.clone()),
+            err: Box::new(err.clone()),
+            color: false,
+            verbosity: Verbosity::Normal,
+        }
+    }
+
+    /// Hands what is printed to `callback`, with whether it was `eprint`ed, as it is printed and
+    /// without color: for where there is no stdout to print to, such as a browser.
+    pub fn callback(callback: impl FnMut(bool, &str) + 'static) -> Output {
+        let callback: Shared = Rc::new(RefCell::new(callback));
+        Output {
+            out: Box::new(Callback(false, Rc::clone(&callback))),
+            err: Box::new(Callback(true, callback)),
+            color: false,
+            verbosity: Verbosity::Normal,
+        }
+    }
+
+    fn writer(&mut self, err: bool) -> &mut Box<dyn Write> {
+        match err {
+            false => &mut self.out,
+            true => &mut self.err,
+        }
+    }
+}
+impl Default for Output {
+    fn default() -> Output {
+        Output::stdout()
+    }
+}
+impl fmt::Debug for Output {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Output { .. }")
+    }
+}
+
+/// Writes with `print!` or `eprint!`.
+struct Printed(bool);
+impl Write for Printed {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let text = String::from_utf8_lossy(buf);
+        match self.0 {
+            false => std::print!("{text}"),
+            true => std::eprint!("{text}"),
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Writes to a callback, which `out` and `err` share, so it is called in the order things were
+/// printed in.
+struct Callback(bool, Shared);
+type Shared = Rc<RefCell<dyn FnMut(bool, &str)>>;
+impl Write for Callback {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        (self.1.borrow_mut())(self.0, &String::from_utf8_lossy(buf));
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Writes with `eprint!`, for the logger, which needs a writer of its own that it can keep.
+pub struct Logged;
+impl Write for Logged {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        write(true, format_args!("{}", String::from_utf8_lossy(buf)));
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A shared, growable buffer: clones write to the same bytes, so one clone can be written to
+/// while another is kept to read what was written.
+#[derive(Debug, Clone, Default)]
+pub struct Buffer(Rc<RefCell<Vec<u8>>>);
+impl Buffer {
+    pub fn new() -> Buffer {
+        Buffer::default()
+    }
+
+    pub fn text(&self) -> String {
+        String::from_utf8_lossy(&self.0.borrow()).into_owned()
+    }
+
+    /// `text`, which is then cleared, so that the next one only has what is written after.
+    pub fn take(&self) -> String {
+        String::from_utf8_lossy(&self.0.take()).into_owned()
+    }
+}
+impl Write for Buffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Sends what a `spawn`ed thread prints back to the thread that spawned it, with whether it was
+/// `eprint`ed. Once that thread’s output is no longer current, there is nowhere to send it.
+struct Relay(bool, Sender<(bool, String)>);
+impl Write for Relay {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let _ = self
+            .1
+            .send((self.0, String::from_utf8_lossy(buf).into_owned()));
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<Output>> = const { RefCell::new(None) };
+    /// Where what the threads `spawn`ed with the current output print arrives.
+    static RELAYED: RefCell<Vec<Receiver<(bool, String)>>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Runs `f` with `output` as the current thread’s output, and then gives `output` back, even if
+/// `f` panics. Whatever was the current output before is current again afterwards. What the
+/// threads `f` `spawn`ed have printed by then is written to `output` before it is given back.
+pub fn redirect<R>(output: &mut Output, f: impl FnOnce() -> R) -> R {
+    // `output` is only borrowed, so it is swapped out for a placeholder while it is current
+    let previous = CURRENT.replace(Some(mem::replace(output, Output::silent())));
+    let relayed = RELAYED.take();
+    let _restore = ScopeGuard::new(|| {
+        let current = CURRENT.replace(previous);
+        if let Some(mut current) = current {
+            write_relayed(&mut current);
+            *output = current;
+        }
+        RELAYED.set(relayed);
+    });
+    f()
+}
+
+/// Writes to `output` what has arrived from the threads `spawn`ed with it, and forgets the
+/// threads that have finished and sent everything.
+fn write_relayed(output: &mut Output) {
+    RELAYED.with_borrow_mut(|relayed| {
+        relayed.retain(|receiver| loop {
+            match receiver.try_recv() {
+                Ok((err, text)) => {
+                    let _ = output.writer(err).write_all(text.as_bytes());
+                }
+                Err(TryRecvError::Empty) => return true,
+                Err(TryRecvError::Disconnected) => return false,
+            }
+        })
+    });
+}
+
+/// `thread::spawn`, but the new thread prints to the current output, and with its color and
+/// verbosity; see the top of this module. Without a current output, it prints as the standard
+/// macros do, as it would have anyway.
+pub fn spawn<T, F>(f: F) -> JoinHandle<T>
+where
+    F: FnOnce() -> T + Send + 'static,
+    T: Send + 'static,
+{
+    let current = CURRENT.with_borrow(|current| {
+        let current = current.as_ref()?;
+        Some((current.color, current.verbosity))
+    });
+    let Some((color, verbosity)) = current else {
+        return JoinHandle(thread::spawn(f));
+    };
+    let (sender, receiver) = mpsc::channel();
+    RELAYED.with_borrow_mut(|relayed| relayed.push(receiver));
+    JoinHandle(thread::spawn(move || {
+        let mut output = Output {
+            out: Box::new(Relay(false, sender.clone())),
+            err: Box::new(Relay(true, sender)),
+            color,
+            verbosity,
+        };
+        redirect(&mut output, f)
+    }))
+}
+
+/// A `spawn`ed thread, which is detached when dropped, as a `thread::JoinHandle` is.
+#[derive(Debug)]
+pub struct JoinHandle<T>(thread::JoinHandle<T>);
+impl<T> JoinHandle<T> {
+    /// Waits for the thread to finish, as `thread::JoinHandle::join` does, and then writes what
+    /// it printed, if it hasn’t been already.
+    pub fn join(self) -> thread::Result<T> {
+        let result = self.0.join();
+        CURRENT.with_borrow_mut(|current| {
+            if let Some(current) = current {
+                write_relayed(current);
+            }
+        });
+        result
+    }
+}
+
+/// Whether the current output may be colored; without one, it may not.
+pub fn color() -> bool {
+    CURRENT.with_borrow(|current| current.as_ref().is_some_and(|current| current.color))
+}
+
+/// The verbosity of the current output, or `Normal` without one.
+pub fn verbosity() -> Verbosity {
+    CURRENT.with_borrow(|current| {
+        current
+            .as_ref()
+            .map_or(Verbosity::Normal, |current| current.verbosity)
+    })
+}
+
+/// Writes to the current output, or prints if there isn’t one. Errors are ignored: the output
+/// of a lesson is there to be read, and a lesson shouldn’t fail because it couldn’t be.
+pub fn write(err: bool, args: fmt::Arguments<'_>) {
+    let written = CURRENT.with_borrow_mut(|current| {
+        let Some(current) = current else {
+            return false;
+        };
+        // what the threads it spawned printed came first
+        write_relayed(current);
+        let _ = current.writer(err).write_fmt(args);
+        true
+    });
+    if !written {
+        let _ = Printed(err).write_fmt(args);
+    }
+}
+
+#[macro_export]
+macro_rules! print {
+    ($($arg:tt)*) => {
+        $crate::output::write(false, format_args!($($arg)*))
+    };
+}
+#[macro_export]
+macro_rules! println {
+    () => {
+        $crate::print!("\n")
+    };
+    ($($arg:tt)*) => {
+        $crate::print!("{}\n", format_args!($($arg)*))
+    };
+}
+// the lessons log their warnings rather than `eprint` them, but one that `eprint`s mustn't get
+// the standard macros, which would write past the current output
+#[macro_export]
+macro_rules! eprint {
+    ($($arg:tt)*) => {
+        $crate::output::write(true, format_args!($($arg)*))
+    };
+}
+#[macro_export]
+macro_rules! eprintln {
+    () => {
+        $crate::eprint!("\n")
+    };
+    ($($arg:tt)*) => {
+        $crate::eprint!("{}\n", format_args!($($arg)*))
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::panic::{self, AssertUnwindSafe};
+
+    fn lesson() {
+        println!("to out");
+        eprintln!("to err: {}", 42);
+        print!("no newline");
+    }
+
+    #[test]
+    fn a_redirected_lesson_prints_to_its_output() {
+        let (out, err) = (Buffer::new(), Buffer::new());
+        let mut output = Output::captured(&out, &err);
+        redirect(&mut output, lesson);
+
+        assert_eq!(out.text(), "to out\nno newline");
+        assert_eq!(err.text(), "to err: 42\n");
+    }
+
+    #[test]
+    fn the_output_is_given_back_even_after_a_panic() {
+        let out = Buffer::new();
+        let mut output = Output::captured(&out, &Buffer::new());
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            redirect(&mut output, || {
+                println!("before");
+                panic!("in the lesson")
+            })
+        }));
+        assert!(result.is_err());
+
+        redirect(&mut output, || println!("after"));
+        assert_eq!(out.text(), "before\nafter\n");
+    }
+
+    #[test]
+    fn a_callback_is_handed_out_and_err_in_the_order_they_were_printed() {
+        let printed = Rc::new(RefCell::new(Vec::<(bool, String)>::new()));
+        let mut output = Output::callback({
+            let printed = Rc::clone(&printed);
+            // a line may be handed over in pieces
+            move |err, text| {
+                let mut printed = printed.borrow_mut();
+                match printed.last_mut() {
+                    Some((last, piece)) if *last == err => piece.push_str(text),
+                    _ => printed.push((err, text.to_string())),
+                }
+            }
+        });
+        redirect(&mut output, lesson);
+
+        assert_eq!(
+            *printed.borrow(),
+            [
+                (false, "to out\n".to_string()),
+                (true, "to err: 42\n".to_string()),
+                (false, "no newline".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn redirects_nest() {
+        let (outer, inner) = (Buffer::new(), Buffer::new());
+        let mut outer_output = Output::captured(&outer, &Buffer::new());
+        let mut inner_output = Output::captured(&inner, &Buffer::new());
+        redirect(&mut outer_output, || {
+            println!("outer");
+            redirect(&mut inner_output, || println!("inner"));
+            println!("outer again");
+        });
+
+        assert_eq!(outer.text(), "outer\nouter again\n");
+        assert_eq!(inner.text(), "inner\n");
+    }
+
+    #[test]
+    fn a_spawned_thread_prints_to_the_output_it_was_spawned_with() {
+        let (out, err) = (Buffer::new(), Buffer::new());
+        let mut output = Output::captured(&out, &err);
+        redirect(&mut output, || {
+            println!("before");
+            let thread = spawn(|| {
+                println!("on the thread");
+                // and on to the thread that spawned it, through this one
+                spawn(|| eprintln!("on its thread")).join().unwrap();
+                42
+            });
+            assert_eq!(thread.join().unwrap(), 42);
+            println!("after");
+
+            // printed after the last `join`, and written once the lesson is over
+            let detached = spawn(|| println!("detached"));
+            while !detached.0.is_finished() {
+                thread::yield_now();
+            }
+        });
+
+        assert_eq!(out.text(), "before\non the thread\nafter\ndetached\n");
+        assert_eq!(err.text(), "on its thread\n");
+    }
+
+    #[test]
+    fn a_verbosity_is_parsed_from_its_name() {
+        for verbosity in [
+            Verbosity::Quiet,
+            Verbosity::Normal,
+            Verbosity::Verbose,
+            Verbosity::Debug,
+        ] {
+            assert_eq!(verbosity.to_string().parse(), Ok(verbosity));
+        }
+        assert_eq!("VERBOSE".parse(), Ok(Verbosity::Verbose));
+        assert!("loud".parse::<Verbosity>().is_err());
+    }
+}
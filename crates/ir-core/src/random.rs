@@ -0,0 +1,75 @@
+//! # Reproducible Randomness
+//! A lesson that needs random numbers, to fill a map or to come up with inputs, takes them from
+//! `rng`, the current thread’s `StdRng`. The runner makes the one in the `LessonCtx` current
+//! while a lesson runs, seeded from `--seed N`, or from the OS without it, so that a run with
+//! the same seed and the same lessons draws the same numbers, and prints the same:
+//! ```text
+//! intermediate_rust --seed 42 unsafe-rust fuzzing
+//! ```
+//! `-v` prints the seed of a run, to repeat one that wasn’t given one. Outside of a lesson run,
+//! in a test for instance, `rng` is seeded with `0`.
+
+use super::utils::scope_guard::ScopeGuard;
+use rand::{rngs::StdRng, SeedableRng};
+use std::{cell::RefCell, mem};
+
+pub fn seeded(seed: u64) -> StdRng {
+    StdRng::seed_from_u64(seed)
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<StdRng>> = const { RefCell::new(None) };
+}
+
+/// Runs `f` with `rng` as the current thread’s `StdRng`, and then gives `rng` back, with what
+/// `f` drew from it, even if `f` panics. Whatever was current before is current again afterwards.
+pub fn with_rng<R>(rng: &mut StdRng, f: impl FnOnce() -> R) -> R {
+    // `rng` is only borrowed, so it is swapped out for a placeholder while it is current
+    let previous = CURRENT.replace(Some(mem::replace(rng, seeded(0))));
+    let _restore = ScopeGuard::new(|| {
+        if let Some(current) = CURRENT.replace(previous) {
+            *rng = current;
+        }
+    });
+    f()
+}
+
+/// Calls `f` with the current thread’s `StdRng`, or, without one, with one seeded with `0`.
+///
+/// # Panics
+/// If `f` calls `rng` itself.
+pub fn rng<R>(f: impl FnOnce(&mut StdRng) -> R) -> R {
+    CURRENT.with_borrow_mut(|current| f(current.get_or_insert_with(|| seeded(0))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::RngExt;
+
+    fn draw() -> Vec<u32> {
+        rng(|rng| (0..4).map(|_| rng.random_range(0..1000)).collect())
+    }
+
+    #[test]
+    fn the_same_seed_draws_the_same_numbers() {
+        let first = with_rng(&mut seeded(42), draw);
+        let again = with_rng(&mut seeded(42), draw);
+        let other = with_rng(&mut seeded(43), draw);
+
+        assert_eq!(first, again);
+        assert_ne!(first, other);
+    }
+
+    #[test]
+    fn the_rng_is_given_back_where_the_run_left_it() {
+        let mut rng = seeded(7);
+        let first = with_rng(&mut rng, draw);
+        let second = with_rng(&mut rng, draw);
+
+        let mut expected = seeded(7);
+        assert_eq!(first, with_rng(&mut expected, draw));
+        assert_eq!(second, with_rng(&mut expected, draw));
+        assert_ne!(first, second);
+    }
+}
@@ -0,0 +1,120 @@
+//! # Color
+//! A few kinds of line stand out from the rest of what a lesson prints: the header of each part
+//! of a lesson, the counts it is about, and the warnings of something that went wrong on purpose.
+//! The lessons print those with `header!` and `paint`, and the logger paints the level of the
+//! warnings and errors it logs. They are colored when the current output may be colored, and
+//! left as they are when it may not: when it isn’t a terminal, when `NO_COLOR` is set, and when
+//! it is captured by the tests. `Style::Code` is for the `code` in the lessons’ explanations, and
+//! `Style::Removed` and `Style::Added` for the lines of a diff.
+
+use super::output;
+use std::fmt::{self, Display};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Style {
+    Header,
+    Count,
+    Warning,
+    Code,
+    Removed,
+    Added,
+}
+impl Style {
+    /// The ANSI escape code that turns the style on.
+    fn code(self) -> &'static str {
+        match self {
+            Style::Header => "\x1b[1;36m", // bold cyan
+            Style::Count => "\x1b[33m",    // yellow
+            Style::Warning => "\x1b[31m",  // red
+            Style::Code => "\x1b[32m",     // green
+            Style::Removed => "\x1b[31m",  // red
+            Style::Added => "\x1b[32m",    // green
+        }
+    }
+
+    /// `value`, colored only if `color`; for printing to an output that isn’t the current one.
+    pub fn paint<T: Display>(self, value: T, color: bool) -> Painted<T> {
+        Painted {
+            style: self,
+            value,
+            color,
+        }
+    }
+}
+
+/// A value displayed in a `Style`. Whether to color it is decided when it is painted, not when it
+/// is displayed, since it is displayed while the current output is busy being written to.
+#[derive(Debug, Clone, Copy)]
+pub struct Painted<T> {
+    style: Style,
+    value: T,
+    color: bool,
+}
+impl<T: Display> Display for Painted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.color {
+            true => write!(f, "{}{}\x1b[0m", self.style.code(), self.value),
+            false => self.value.fmt(f),
+        }
+    }
+}
+
+/// `value`, colored if the current output may be.
+pub fn paint<T: Display>(style: Style, value: T) -> Painted<T> {
+    style.paint(value, output::color())
+}
+
+/// Prints the header of a part of a lesson.
+#[macro_export]
+macro_rules! header {
+    // a header without arguments is translated, see `locale.rs`
+    ($text:literal) => {
+        $crate::println!(
+            "{}",
+            $crate::style::paint(
+                $crate::style::Style::Header,
+                $crate::locale::tr($text)
+            )
+        )
+    };
+    ($($arg:tt)*) => {
+        $crate::println!(
+            "{}",
+            $crate::style::paint(
+                $crate::style::Style::Header,
+                format_args!($($arg)*)
+            )
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::{Buffer, Output};
+
+    #[test]
+    fn only_an_output_that_may_be_colored_is() {
+        let (out, err) = (Buffer::new(), Buffer::new());
+        let mut plain = Output::captured(&out, &err);
+        output::redirect(&mut plain, || {
+            header!("Part {}", 1);
+            eprintln!("{}", paint(Style::Warning, "oops"));
+        });
+        assert_eq!(out.text(), "Part 1\n");
+        assert_eq!(err.text(), "oops\n");
+
+        let out = Buffer::new();
+        let mut colored = Output {
+            color: true,
+            ..Output::captured(&out, &Buffer::new())
+        };
+        output::redirect(&mut colored, || header!("Part {}", 2));
+        assert_eq!(out.text(), "\x1b[1;36mPart 2\x1b[0m\n");
+    }
+
+    #[test]
+    fn padding_applies_to_the_value_without_color() {
+        assert_eq!(format!("{:>4}", Style::Count.paint(7, false)), "   7");
+    }
+}
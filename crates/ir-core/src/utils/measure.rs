@@ -0,0 +1,224 @@
+//! # Measuring a Run
+//! `measure` runs a closure and says how long it took, and, with the `track-alloc` feature, how
+//! much it allocated: how many allocations it made, and the most heap it held at once beyond what
+//! was already held when it started (its _peak_). The runner measures each lesson with it, and a
+//! lesson comparing two ways of doing the same thing can measure each of them, and print the
+//! `Measurement`s side by side.
+//!
+//! Counting allocations takes a `#[global_allocator]`: every allocation of the program goes
+//! through `Tracking`, which hands it to the `System` allocator and counts it on the way. The
+//! counts are kept per thread, so that what another thread allocates meanwhile, such as another
+//! test, isn’t counted; by the same token, neither is what the threads a closure spawns allocate.
+
+use std::{
+    fmt::{self, Display},
+    time::{Duration, Instant},
+};
+
+/// What a closure allocated, on the thread it ran on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Allocs {
+    pub count: usize,
+    /// The bytes of all the allocations together, however many were freed again.
+    pub bytes: usize,
+    /// The most bytes held at once, beyond what was held before.
+    pub peak: usize,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Measurement {
+    pub duration: Duration,
+    /// `None` without the `track-alloc` feature.
+    pub allocs: Option<Allocs>,
+}
+impl Display for Measurement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.1?}", self.duration)?;
+        match self.allocs {
+            Some(Allocs { count, peak, .. }) => {
+                write!(f, ", {count} allocation(s), peak {}", bytes(peak))
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+/// `n` bytes in B, KiB or MiB, whichever reads best.
+pub fn bytes(n: usize) -> String {
+    match n {
+        0..1024 => format!("{n} B"),
+        1024..1048576 => format!("{:.1} KiB", n as f64 / 1024.0),
+        _ => format!("{:.1} MiB", n as f64 / 1048576.0),
+    }
+}
+
+/// Runs `f`, and measures it.
+pub fn measure<R>(f: impl FnOnce() -> R) -> (R, Measurement) {
+    let start = tracking::start();
+    let clock = Instant::now();
+    let result = f();
+    let duration = clock.elapsed();
+    let allocs = tracking::stop(start);
+    (result, Measurement { duration, allocs })
+}
+
+#[cfg(feature = "track-alloc")]
+mod tracking {
+    use super::Allocs;
+    use std::{
+        alloc::{GlobalAlloc, Layout, System},
+        cell::Cell,
+    };
+
+    #[derive(Debug, Clone, Copy)]
+    struct Counts {
+        count: usize,
+        bytes: usize,
+        held: usize,
+        peak: usize,
+    }
+
+    thread_local! {
+        // `const`, and without a destructor, so that using it never allocates, which it mustn't
+        // from inside the allocator
+        static COUNTS: Cell<Counts> = const { Cell::new(Counts { count: 0, bytes: 0, held: 0, peak: 0 }) };
+    }
+
+    fn update(f: impl FnOnce(&mut Counts)) {
+        // once a thread is being torn down, its allocations go uncounted
+        let _ = COUNTS.try_with(|counts| {
+            let mut c = counts.get();
+            f(&mut c);
+            counts.set(c);
+        });
+    }
+
+    struct Tracking;
+
+    unsafe impl GlobalAlloc for Tracking {
+        unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+            let ptr = unsafe { System.alloc(layout) };
+            if !ptr.is_null() {
+                update(|c| {
+                    c.count += 1;
+                    c.bytes += layout.size();
+                    c.held += layout.size();
+                    c.peak = c.peak.max(c.held);
+                });
+            }
+            ptr
+        }
+
+        unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+            unsafe { System.dealloc(ptr, layout) };
+            // memory allocated on another thread may be freed on this one
+            update(|c| c.held = c.held.saturating_sub(layout.size()));
+        }
+    }
+
+    #[global_allocator]
+    static ALLOCATOR: Tracking = Tracking;
+
+    /// Where the counts were when a measurement started.
+    #[derive(Debug, Clone, Copy)]
+    pub struct Start {
+        count: usize,
+        bytes: usize,
+        held: usize,
+        peak: usize,
+    }
+
+    pub fn start() -> Start {
+        let mut start = None;
+        update(|c| {
+            start = Some(Start {
+                count: c.count,
+                bytes: c.bytes,
+                held: c.held,
+                peak: c.peak,
+            });
+            // the peak from here on is what this measurement is after
+            c.peak = c.held;
+        });
+        start.unwrap_or(Start {
+            count: 0,
+            bytes: 0,
+            held: 0,
+            peak: 0,
+        })
+    }
+
+    pub fn stop(start: Start) -> Option<Allocs> {
+        let mut allocs = None;
+        update(|c| {
+            allocs = Some(Allocs {
+                count: c.count - start.count,
+                bytes: c.bytes - start.bytes,
+                peak: c.peak.saturating_sub(start.held),
+            });
+            // an enclosing measurement still needs the peak it had seen
+            c.peak = c.peak.max(start.peak);
+        });
+        allocs
+    }
+}
+
+#[cfg(not(feature = "track-alloc"))]
+mod tracking {
+    use super::Allocs;
+
+    pub struct Start;
+
+    pub fn start() -> Start {
+        Start
+    }
+
+    pub fn stop(_: Start) -> Option<Allocs> {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_reads_best_in_the_closest_unit() {
+        assert_eq!(bytes(512), "512 B");
+        assert_eq!(bytes(1536), "1.5 KiB");
+        assert_eq!(bytes(3 * 1048576), "3.0 MiB");
+        let measurement = Measurement {
+            duration: Duration::from_micros(1500),
+            allocs: Some(Allocs {
+                count: 2,
+                bytes: 3072,
+                peak: 2048,
+            }),
+        };
+        assert_eq!(
+            measurement.to_string(),
+            "1.5ms, 2 allocation(s), peak 2.0 KiB"
+        );
+    }
+
+    #[test]
+    fn it_measures_what_the_closure_allocated() {
+        let (len, measurement) = measure(|| {
+            let kept = vec![0u8; 4096];
+            drop(vec![0u8; 1024]);
+            kept.len()
+        });
+        assert_eq!(len, 4096);
+        match cfg!(feature = "track-alloc") {
+            true => assert_eq!(
+                measurement.allocs,
+                Some(Allocs {
+                    count: 2,
+                    bytes: 5120,
+                    peak: 5120
+                })
+            ),
+            false => assert_eq!(measurement.allocs, None),
+        }
+    }
+}
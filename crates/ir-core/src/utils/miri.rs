@@ -0,0 +1,28 @@
+//! # Running under Miri
+//! Miri runs the program in _isolation_ from the host: there is no file system, no network, and
+//! no clock but its own, so that a run can be repeated exactly. A lesson with a part that needs
+//! the host skips that part under Miri, and says so; a test that needs the host is marked
+//! `#[cfg_attr(miri, ignore)]`. See `unsafe_rust/miri.rs` for what Miri checks.
+
+/// Whether the part of a lesson that does `what` has to be skipped, because it runs under Miri.
+pub fn skip_under_miri(what: &str) -> bool {
+    if cfg!(miri) {
+        println!("skipped under Miri, which runs in isolation from the host: {what}");
+    }
+    cfg!(miri)
+}
+
+/// The property tests’ configuration. Under Miri, which runs them many times slower, they try a
+/// few cases instead of 256, and don’t look for the files failures are saved in. It is for the
+/// chapters’ tests, which have `ir-core` with the `proptest` feature as a dev-dependency.
+#[cfg(feature = "proptest")]
+pub fn proptest_config() -> proptest::test_runner::Config {
+    match cfg!(miri) {
+        true => proptest::test_runner::Config {
+            cases: 4,
+            failure_persistence: None,
+            ..Default::default()
+        },
+        false => Default::default(),
+    }
+}
@@ -0,0 +1,12 @@
+//! Small helpers shared by the lessons. They aren’t lessons themselves, but each one is built
+//! from what a chapter teaches, and its doc comments say which.
+
+#[macro_use]
+pub mod scope_guard;
+#[macro_use]
+mod span;
+
+pub mod measure;
+pub mod miri;
+pub mod retry;
+pub mod temp_dir;
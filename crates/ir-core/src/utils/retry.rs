@@ -0,0 +1,66 @@
+//! # Trying Again, a Little Later Each Time
+//! Some failures are temporary: a socket that isn’t accepting connections yet, a file another
+//! process has locked for a moment. `retry` runs an operation until it succeeds or runs out of
+//! attempts, waiting between attempts, and doubling the wait each time (_exponential backoff_),
+//! so that a struggling resource isn’t hammered with retries.
+//!
+//! The operation is an `FnMut` rather than an `Fn` because an attempt may well change some state,
+//! such as a counter of its own, and rather than an `FnOnce` because it is called more than once.
+
+use std::{io::Result, thread, time::Duration};
+
+/// Calls `op` up to `attempts` times, sleeping `backoff`, then twice that, and so on between
+/// attempts. Returns the first success, or the last error. `attempts` is at least 1: `op` is
+/// always called once.
+pub fn retry<T>(
+    attempts: usize,
+    backoff: Duration,
+    mut op: impl FnMut() -> Result<T>,
+) -> Result<T> {
+    let mut delay = backoff;
+    for attempt in 1..attempts {
+        match op() {
+            Ok(value) => return Ok(value),
+            Err(x) => {
+                log::debug!("attempt {attempt} failed: {x}, retrying in {delay:?}");
+                thread::sleep(delay);
+                delay *= 2;
+            }
+        }
+    }
+    op()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Error, ErrorKind};
+
+    /// An operation that fails its first `failures` calls.
+    fn flaky(failures: usize) -> impl FnMut() -> Result<usize> {
+        let mut calls = 0;
+        move || {
+            calls += 1;
+            match calls > failures {
+                true => Ok(calls),
+                false => Err(Error::new(ErrorKind::Interrupted, format!("call {calls}"))),
+            }
+        }
+    }
+
+    #[test]
+    fn it_returns_the_first_success() {
+        assert_eq!(retry(5, Duration::ZERO, flaky(2)).unwrap(), 3);
+    }
+
+    #[test]
+    fn it_returns_the_last_error_once_out_of_attempts() {
+        let x = retry(3, Duration::ZERO, flaky(10)).unwrap_err();
+        assert_eq!(x.to_string(), "call 3");
+    }
+
+    #[test]
+    fn it_calls_at_least_once() {
+        assert_eq!(retry(0, Duration::ZERO, flaky(0)).unwrap(), 1);
+    }
+}
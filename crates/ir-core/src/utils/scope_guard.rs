@@ -0,0 +1,166 @@
+//! # Running Clean-up Code on Every Way Out of a Scope
+//! A function that has to undo something before it returns (release a resource, restore a
+//! setting, join a thread) has to do it on _every_ path out: the normal one, each early
+//! `return`, each `?`, and a panic. Writing the clean-up before each of them is easy to get wrong.
+//!
+//! The `Drop` chapter already gives us something that runs on every path out of a scope: the
+//! `drop` of a local. A `ScopeGuard` is a local whose `drop` calls a closure, so the clean-up
+//! is written once, right next to the code that made it necessary:
+//! ```text
+//! let _guard = ScopeGuard::new(|| println!("cleaned up"));
+//! defer! { println!("cleaned up") } // the same, without naming the guard
+//! ```
+//! `guard(value, cleanup)` is the cancellable variant: the guard owns `value` and hands it to
+//! `cleanup` when dropped, unless the value was taken back out with `Guard::into_inner`.
+
+use std::ops::{Deref, DerefMut};
+
+/// Calls `cleanup` when dropped, unless `dismiss`ed first.
+pub struct ScopeGuard<F: FnOnce()> {
+    // an `FnOnce` can only be called by value, and `drop` only gets `&mut self`, so the closure
+    // waits in an `Option` to be `take`n out
+    cleanup: Option<F>,
+}
+impl<F: FnOnce()> ScopeGuard<F> {
+    pub fn new(cleanup: F) -> ScopeGuard<F> {
+        ScopeGuard {
+            cleanup: Some(cleanup),
+        }
+    }
+
+    /// Drops the guard without calling `cleanup`.
+    #[allow(dead_code)]
+    pub fn dismiss(mut self) {
+        self.cleanup = None;
+    }
+}
+impl<F: FnOnce()> Drop for ScopeGuard<F> {
+    fn drop(&mut self) {
+        if let Some(cleanup) = self.cleanup.take() {
+            cleanup();
+        }
+    }
+}
+
+/// Runs the given statements when the enclosing scope ends, however it ends.
+///
+/// Each `defer!` is a separate local, so they run in the reverse order of their appearance.
+#[macro_export]
+macro_rules! defer {
+    ($($body:tt)*) => {
+        let _guard = $crate::utils::scope_guard::ScopeGuard::new(|| {
+            $($body)*
+        });
+    };
+}
+
+/// Owns a value and hands it to `cleanup` when dropped; see `guard`.
+pub struct Guard<T, F: FnOnce(T)> {
+    // both are only ever `None` once the guard is being consumed, by `drop` or `into_inner`
+    value: Option<T>,
+    cleanup: Option<F>,
+}
+
+/// Guards `value`: if the returned `Guard` is dropped, `cleanup(value)` runs. Taking the
+/// value back with `Guard::into_inner` cancels the clean-up. In between, the guard derefs to
+/// the value.
+pub fn guard<T, F: FnOnce(T)>(value: T, cleanup: F) -> Guard<T, F> {
+    Guard {
+        value: Some(value),
+        cleanup: Some(cleanup),
+    }
+}
+impl<T, F: FnOnce(T)> Guard<T, F> {
+    /// Takes the value back out without running the clean-up. An associated function rather
+    /// than a method, so that it can’t be confused with a method of `T` called through `Deref`.
+    pub fn into_inner(mut guard: Guard<T, F>) -> T {
+        guard.cleanup = None;
+        guard.value.take().unwrap()
+    }
+}
+impl<T, F: FnOnce(T)> Deref for Guard<T, F> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.value.as_ref().unwrap()
+    }
+}
+impl<T, F: FnOnce(T)> DerefMut for Guard<T, F> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.value.as_mut().unwrap()
+    }
+}
+impl<T, F: FnOnce(T)> Drop for Guard<T, F> {
+    fn drop(&mut self) {
+        if let (Some(value), Some(cleanup)) = (self.value.take(), self.cleanup.take()) {
+            cleanup(value);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        cell::{Cell, RefCell},
+        panic::{self, AssertUnwindSafe},
+    };
+
+    #[test]
+    fn it_runs_on_an_early_return() {
+        let cleaned_up = Cell::new(false);
+        let early = |stop: bool| -> Option<()> {
+            let _guard = ScopeGuard::new(|| cleaned_up.set(true));
+            if stop {
+                return None;
+            }
+            unreachable!()
+        };
+
+        assert_eq!(early(true), None);
+        assert!(cleaned_up.get());
+    }
+
+    #[test]
+    fn it_runs_while_unwinding_from_a_panic() {
+        let cleaned_up = Cell::new(false);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            defer! { cleaned_up.set(true) }
+            panic!("boom");
+        }));
+
+        assert!(result.is_err());
+        assert!(cleaned_up.get());
+    }
+
+    #[test]
+    fn deferred_statements_run_in_reverse_order() {
+        let order = RefCell::new(Vec::new());
+        {
+            defer! { order.borrow_mut().push(1) }
+            defer! { order.borrow_mut().push(2) }
+        }
+        assert_eq!(order.into_inner(), [2, 1]);
+    }
+
+    #[test]
+    fn a_dismissed_guard_does_nothing() {
+        let cleaned_up = Cell::new(false);
+        ScopeGuard::new(|| cleaned_up.set(true)).dismiss();
+        assert!(!cleaned_up.get());
+    }
+
+    #[test]
+    fn a_guarded_value_is_cleaned_up_unless_taken_back() {
+        let released = RefCell::new(Vec::new());
+        {
+            let mut kept = guard(vec![1], |v| released.borrow_mut().push(v));
+            kept.push(2);
+            assert_eq!(Guard::into_inner(kept), [1, 2]);
+
+            let mut dropped = guard(vec![3], |v| released.borrow_mut().push(v));
+            dropped.push(4);
+        }
+        assert_eq!(released.into_inner(), [vec![3, 4]]);
+    }
+}
@@ -0,0 +1,24 @@
+//! # Tracing the Parts of a Lesson
+//! A `tracing` span is a stretch of the program’s run with a name: it is entered, and exited
+//! again, and one entered while in another is nested in it. With the `tracing` feature, the
+//! runner enters a span for each lesson it runs, and each part of a lesson enters one of its own
+//! with `span!`, so the subscriber the binary sets up can show the parts nested in their lessons,
+//! each with how long it took. Without the feature, `span!` is nothing at all.
+//!
+//! Which of the two `span!`s there is depends on `ir-core`’s feature, not on the feature of the
+//! chapter that uses it, whose `cfg`s a macro would otherwise be checked against.
+
+/// Enters a span named `$name` until the enclosing scope ends.
+#[cfg(feature = "tracing")]
+#[macro_export]
+macro_rules! span {
+    ($name:literal) => {
+        let _span = $crate::tracing::info_span!($name).entered();
+    };
+}
+/// Nothing at all, without the `tracing` feature.
+#[cfg(not(feature = "tracing"))]
+#[macro_export]
+macro_rules! span {
+    ($name:literal) => {};
+}
@@ -0,0 +1,61 @@
+//! # A Temporary Directory, Removed by `Drop`
+//! A lesson that writes files writes them into a directory of its own, so that two runs at once,
+//! as the tests make, don’t write over or remove each other’s files; and it removes the directory
+//! with its `drop`, which runs however the lesson ends, as the io chapter’s files lesson teaches.
+//! The `tempfile` crate is the same idea, with more care for names that can’t be guessed, and
+//! permissions.
+
+use std::{
+    env, fs,
+    io::Result,
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// A directory of its own, in the system’s temporary directory, removed with all that is in it
+/// when the `TempDir` is dropped, however the scope ends.
+pub struct TempDir {
+    path: PathBuf,
+}
+impl TempDir {
+    /// A new directory, named after `name`, the process, and how many this process has made.
+    pub fn new(name: &str) -> Result<TempDir> {
+        // the lessons may run on several threads at once, in the tests, so each gets a number
+        static CREATED: AtomicUsize = AtomicUsize::new(0);
+        let n = CREATED.fetch_add(1, Ordering::Relaxed);
+        let path = env::temp_dir().join(format!("intermediate_rust-{}-{n}-{name}", process::id()));
+        fs::create_dir_all(&path)?;
+        Ok(TempDir { path })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+impl Drop for TempDir {
+    /// `drop` can’t return an error, so one is only logged: a temporary file left behind is
+    /// the operating system’s to clean up.
+    fn drop(&mut self) {
+        if let Err(x) = fs::remove_dir_all(&self.path) {
+            log::warn!("couldn't remove {}: {x}", self.path.display());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn each_temp_dir_is_a_directory_of_its_own() {
+        let (a, b) = (TempDir::new("same").unwrap(), TempDir::new("same").unwrap());
+        assert_ne!(a.path(), b.path());
+        fs::write(a.path().join("a.txt"), "a").unwrap();
+        let path = a.path().to_path_buf();
+        drop(a);
+        assert!(!path.exists());
+        assert!(b.path().exists());
+    }
+}
@@ -0,0 +1,76 @@
+//! # Clone-on-Write with `Cow<'a, B>`
+//! `Cow` is a smart pointer to a value that is either borrowed or owned: `Cow::Borrowed(&'a B)`
+//! or `Cow::Owned(<B as ToOwned>::Owned)`, such as a `&str` or a `String`. It derefs to `&B`
+//! either way, so it reads like a reference, and it clones what it borrowed only when it is
+//! written to: a function that changes its input only sometimes can return a `Cow` and allocate
+//! only then.
+
+use std::{borrow::Cow, io::Result};
+
+pub fn cow() -> Result<()> {
+    Ok({
+        borrowed_or_owned()?;
+        cloning_on_write()?;
+    })
+}
+
+/// Replaces the spaces in `input` with `_`, copying it only if there are any.
+fn underscored(input: &str) -> Cow<'_, str> {
+    match input.contains(' ') {
+        true => Cow::Owned(input.replace(' ', "_")),
+        false => Cow::Borrowed(input),
+    }
+}
+
+/// Which of the two `cow` is, which its `Debug` doesn’t show: only the value.
+#[allow(clippy::ptr_arg)] // it is the `Cow` being asked about, not what it derefs to
+fn kind<B: ToOwned + ?Sized>(cow: &Cow<'_, B>) -> &'static str {
+    match cow {
+        Cow::Borrowed(_) => "borrowed",
+        Cow::Owned(_) => "owned",
+    }
+}
+
+/// # Borrowed or Owned
+/// `underscored` returns what it was given, borrowed, when it has nothing to change, and a new
+/// `String` only when it has. Its callers can’t tell the difference, unless they ask.
+fn borrowed_or_owned() -> Result<()> {
+    Ok({
+        span!("borrowed_or_owned");
+        header!("Borrowed or Owned");
+
+        let hello = underscored("Hello");
+        println!("{hello:?} is {}", kind(&hello)); // "Hello" is borrowed
+        let hello_world = underscored("Hello, world!");
+        println!("{hello_world:?} is {}", kind(&hello_world)); // "Hello,_world!" is owned
+
+        // both deref to `&str`
+        println!("{} and {}", hello.len(), hello_world.len()); // 5 and 13
+    })
+}
+
+/// # Cloning on Write
+/// `to_mut` hands out a `&mut` to the owned value, cloning the borrowed one into it first, the
+/// first time only; `into_owned` takes the owned value out, cloning it if it was borrowed.
+fn cloning_on_write() -> Result<()> {
+    Ok({
+        span!("cloning_on_write");
+        header!("Cloning on Write");
+
+        let numbers = [1, -2, 3];
+        let mut absolute = Cow::Borrowed(&numbers[..]);
+        for i in 0..absolute.len() {
+            if absolute[i] < 0 {
+                absolute.to_mut()[i] = -absolute[i];
+            }
+        }
+        println!("{absolute:?} is {}", kind(&absolute)); // [1, 2, 3] is owned
+        println!("{numbers:?} is untouched"); // [1, -2, 3] is untouched
+
+        let positive = [1, 2, 3];
+        let untouched = Cow::Borrowed(&positive[..]);
+        println!("{untouched:?} is {}", kind(&untouched)); // [1, 2, 3] is borrowed
+        let owned: Vec<i32> = untouched.into_owned();
+        println!("{owned:?}"); // [1, 2, 3]
+    })
+}
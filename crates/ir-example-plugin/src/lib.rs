@@ -0,0 +1,65 @@
+//! # An Example Plugin
+//! A lesson that isn’t in `intermediate_rust`, in a library the program loads while it runs:
+//! ```text
+//! cargo build -p ir-example-plugin
+//! cargo run -- --plugin target/debug/libir_example_plugin.so extras cow
+//! ```
+//! A plugin is a `cdylib` that depends on `ir-core`, and exports its `register` with
+//! `ir_core::plugin!`; it needs nothing else from the program, so a lesson can be shipped
+//! without forking it. It does need the same `ir-core`, built by the same compiler, as Rust has
+//! no stable ABI to agree on otherwise, which `--plugin` checks as far as it can.
+//!
+//! A plugin has a copy of `ir-core` of its own, statics and all: its lessons print to the output
+//! they are run with, which the program passes them, but don’t see the program’s `--lang`.
+
+// the lessons print with `ir_core`’s `println!`
+#[macro_use]
+extern crate ir_core;
+
+use ir_core::lesson::{Demo, Difficulty, LessonInfo, Registry};
+
+// the lesson writes each part as `Ok({ ... })`, as the program’s do
+#[allow(clippy::unit_arg)]
+pub mod cow;
+
+/// Registers the plugin’s lessons, after every lesson of the program that loads it.
+pub fn register(registry: &mut Registry) {
+    registry.register(Demo {
+        id: "extras/cow",
+        title: "Clone-on-write with `Cow<'a, B>`",
+        run: cow::cow,
+        info: LessonInfo {
+            description: "Borrowing what can be borrowed, and owning only what has to change",
+            difficulty: Difficulty::Intermediate,
+            prerequisites: &[],
+            tags: &["smart-pointers", "plugins"],
+            source: include_str!("cow.rs"),
+        },
+    });
+}
+
+ir_core::plugin!(register);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ir_core::{
+        lesson::{LessonCtx, PLUGIN_VERSION},
+        output::{Buffer, Output},
+    };
+
+    #[test]
+    fn the_plugin_registers_its_lessons() {
+        let mut registry = Registry::new();
+        // as the program that loads it calls it, through what `plugin!` exports
+        __ir_core_plugin_register(&mut registry);
+        let (stdout, stderr) = (Buffer::new(), Buffer::new());
+        let mut ctx = LessonCtx {
+            output: Output::captured(&stdout, &stderr),
+            ..LessonCtx::default()
+        };
+        registry.get("extras/cow").unwrap().run(&mut ctx).unwrap();
+        assert!(stdout.text().contains("\"Hello,_world!\" is owned\n"));
+        assert_eq!(__ir_core_plugin_version(), PLUGIN_VERSION.as_ptr());
+    }
+}
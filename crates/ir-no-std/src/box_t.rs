@@ -0,0 +1,101 @@
+//! # `Box<T>` without `std`
+//! `Box<T>` is in `alloc`, not in `std`: all it needs is an allocator to put its value on the
+//! heap, and `#![no_std]` crates that have one use it exactly as any other crate does. The same
+//! goes for the recursive cons list, whose size is only known because of the `Box` in it. `Vec`,
+//! `String` and `format!` come from `alloc` too; `std` re-exports all of them, which is why a
+//! crate with `std` never has to say where they come from.
+
+use super::Output;
+use alloc::{boxed::Box, format, string::String, vec::Vec};
+use core::fmt::{self, Display};
+
+pub fn box_t(out: &mut dyn Output) -> fmt::Result {
+    Ok({
+        a_value_on_the_heap(out)?;
+        a_recursive_type(out)?;
+    })
+}
+
+/// # A Value on the Heap
+/// `use alloc::boxed::Box` is the only difference: there is no prelude to bring `Box` into
+/// scope, as `std`’s does. `writeln!(out, ...)` is `println!` with the output made explicit.
+fn a_value_on_the_heap(out: &mut dyn Output) -> fmt::Result {
+    Ok({
+        out.header("A Value on the Heap")?;
+
+        let b = Box::new(5);
+        writeln!(out, "b = {b}")?; // b = 5
+
+        let words: Vec<String> = ["heap", "allocated"]
+            .iter()
+            .map(|w| format!("{w}!"))
+            .collect();
+        writeln!(out, "{words:?}")?; // ["heap!", "allocated!"]
+    })
+}
+
+pub enum List<T> {
+    Cons(T, Box<List<T>>),
+    Nil,
+}
+impl<T> List<T> {
+    pub fn len(&self) -> usize {
+        match self {
+            List::Cons(_, next) => 1 + next.len(),
+            List::Nil => 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        matches!(self, List::Nil)
+    }
+}
+impl<T: Display> Display for List<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            List::Cons(value, next) => write!(f, "{value} -> {next}"),
+            List::Nil => write!(f, "Nil"),
+        }
+    }
+}
+impl<T> FromIterator<T> for List<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(values: I) -> List<T> {
+        let values: Vec<T> = values.into_iter().collect();
+        values
+            .into_iter()
+            .rev()
+            .fold(List::Nil, |list, value| List::Cons(value, Box::new(list)))
+    }
+}
+
+/// # A Recursive Type
+/// The list is the one of the `Box<T>` lesson, and `Display` and `FromIterator` are traits of
+/// `core`, so it works the same without `std`.
+fn a_recursive_type(out: &mut dyn Output) -> fmt::Result {
+    Ok({
+        out.header("A Recursive Type")?;
+
+        let list: List<i32> = (1..=3).collect();
+        writeln!(out, "{list}, len = {}", list.len())?; // 1 -> 2 -> 3 -> Nil, len = 3
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_prints_to_the_output_it_is_given() {
+        let mut out = String::new();
+        box_t(&mut out).unwrap();
+        assert!(out.contains("b = 5\n"), "{out}");
+        assert!(out.contains("1 -> 2 -> 3 -> Nil, len = 3\n"), "{out}");
+    }
+
+    #[test]
+    fn it_collects_in_order() {
+        let list: List<_> = "abc".chars().collect();
+        assert_eq!(list.to_string(), "a -> b -> c -> Nil");
+        assert!(List::<char>::Nil.is_empty());
+    }
+}
@@ -0,0 +1,92 @@
+//! # Iterators without `std`
+//! `Iterator` and all of its adapters are in `core`: `map`, `filter`, `zip` and `sum` only call
+//! `next`, and hold no more than the iterators they adapt, so they need no heap. Only `collect`
+//! may, depending on what it collects into: a `Vec` or a `BTreeMap` from `alloc`, but not a
+//! `HashMap`, which is in `std` because its hasher is seeded with the operating system’s
+//! randomness.
+
+use super::Output;
+use alloc::{collections::BTreeMap, vec::Vec};
+use core::fmt;
+
+pub fn iterators(out: &mut dyn Output) -> fmt::Result {
+    Ok({
+        an_iterator_of_our_own(out)?;
+        collecting_without_std(out)?;
+    })
+}
+
+/// Counts from 1 to 5.
+pub struct Counter {
+    count: u32,
+}
+impl Counter {
+    pub fn new() -> Counter {
+        Counter { count: 0 }
+    }
+}
+impl Default for Counter {
+    fn default() -> Counter {
+        Counter::new()
+    }
+}
+impl Iterator for Counter {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        if self.count < 5 {
+            self.count += 1;
+            Some(self.count)
+        } else {
+            None
+        }
+    }
+}
+
+/// # An Iterator of Our Own
+/// Implementing `next` is all it takes for every adapter of `Iterator` to work, none of which
+/// allocates.
+fn an_iterator_of_our_own(out: &mut dyn Output) -> fmt::Result {
+    Ok({
+        out.header("An Iterator of Our Own")?;
+
+        let sum: u32 = Counter::new()
+            .zip(Counter::new().skip(1))
+            .map(|(a, b)| a * b)
+            .filter(|x| x % 3 == 0)
+            .sum();
+        writeln!(out, "sum = {sum}")?; // sum = 18
+    })
+}
+
+/// # Collecting without `std`
+/// A `BTreeMap` keeps its keys in order, which is what it needs instead of a hasher.
+fn collecting_without_std(out: &mut dyn Output) -> fmt::Result {
+    Ok({
+        out.header("Collecting without `std`")?;
+
+        let squares: Vec<u32> = Counter::new().map(|x| x * x).collect();
+        writeln!(out, "squares = {squares:?}")?; // squares = [1, 4, 9, 16, 25]
+
+        let mut lengths = BTreeMap::new();
+        for word in "the quick brown fox jumps over the lazy dog".split(' ') {
+            *lengths.entry(word.len()).or_insert(0) += 1;
+        }
+        writeln!(out, "words by length = {lengths:?}")?;
+        // words by length = {3: 4, 4: 2, 5: 3}
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+
+    #[test]
+    fn the_counter_counts_to_five() {
+        assert_eq!(Counter::new().collect::<Vec<_>>(), [1, 2, 3, 4, 5]);
+        let mut out = String::new();
+        iterators(&mut out).unwrap();
+        assert!(out.contains("sum = 18\n"), "{out}");
+    }
+}
@@ -0,0 +1,52 @@
+//! # Lessons without `std`
+//! The standard library is three crates, one on top of the other. `core` is what every Rust
+//! program has, even one with no operating system underneath: `Option`, `Result`, `Iterator`,
+//! `Cell` and `RefCell`, `Deref` and `Drop`, and formatting with `fmt`. `alloc` adds what needs a
+//! heap, given an allocator: `Box`, `Rc`, `Vec`, `String`, `BTreeMap` and `format!`. `std` adds
+//! what needs an operating system: files, threads, `Mutex`, `HashMap`, whose hasher is seeded
+//! with the OS’s randomness, and stdout, which `println!` prints to.
+//!
+//! A crate that is `#![no_std]` has only `core` in scope, and `alloc` if it asks for it with
+//! `extern crate alloc`. The lessons here are such a crate: the `Box<T>`, `Rc<T>` and
+//! `RefCell<T>` lessons, iterators, and a `Box<T>` of our own, which all get by with `core` and
+//! `alloc`. What they can’t do is `println!`, as there is no stdout to print to; whoever runs a
+//! lesson hands it an `Output` instead, anything that implements `fmt::Write`, and the lesson
+//! prints with `writeln!(out, ...)`. The command line hands them one that prints where the other
+//! lessons do:
+//! ```text
+//! cargo run --features no-std -- no-std ref-cell
+//! ```
+//! On a microcontroller, the `Output` would be a serial port, and the allocator one over a static
+//! buffer of bytes.
+// The test harness needs `std`, so only the tests are built with it.
+#![cfg_attr(not(test), no_std)]
+
+extern crate alloc;
+
+use core::fmt::{self, Write};
+
+// the lessons write each part as `Ok({ ... })`, like the ones with `std`
+#[allow(clippy::unit_arg)]
+pub mod box_t;
+#[allow(clippy::unit_arg)]
+pub mod iterators;
+#[allow(clippy::unit_arg)]
+pub mod my_box;
+#[allow(clippy::unit_arg)]
+pub mod rc_t;
+#[allow(clippy::unit_arg)]
+pub mod ref_cell_t;
+
+/// Where a lesson prints to, since it has no stdout of its own. Anything that implements
+/// `fmt::Write` will do, such as a `String`; an `Output` that can style the headers of the parts
+/// of a lesson implements `header` as well.
+pub trait Output: Write {
+    /// Prints the header of a part of a lesson.
+    fn header(&mut self, text: &str) -> fmt::Result {
+        writeln!(self, "{text}")
+    }
+}
+impl Output for alloc::string::String {}
+
+/// What a lesson is: a function that prints to the `Output` it is given.
+pub type Run = fn(&mut dyn Output) -> fmt::Result;
@@ -0,0 +1,111 @@
+//! # A `Box<T>` of Our Own
+//! The `MyBox<T>` of the `Deref` lesson only wraps its value; this one puts it on the heap, as
+//! `Box<T>` does, with what `alloc` provides underneath `Box`: `alloc::alloc` asks the global
+//! allocator for memory with the `Layout` of a `T`, and `dealloc` gives it back. `Deref`,
+//! `DerefMut` and `Drop` are traits of `core`, so it is a smart pointer without `std` too.
+//!
+//! A `T` of size zero, like `()`, needs no memory at all, and the allocator mustn’t be asked for
+//! none: such a `MyBox` holds a dangling pointer, which is well aligned and never read through.
+
+use super::Output;
+use alloc::{
+    alloc::{alloc, dealloc, handle_alloc_error, Layout},
+    string::String,
+};
+use core::{
+    fmt,
+    ops::{Deref, DerefMut},
+    ptr::{self, NonNull},
+};
+
+pub fn my_box(out: &mut dyn Output) -> fmt::Result {
+    Ok({
+        allocating_by_hand(out)?;
+    })
+}
+
+pub struct MyBox<T> {
+    ptr: NonNull<T>,
+}
+impl<T> MyBox<T> {
+    pub fn new(value: T) -> MyBox<T> {
+        let layout = Layout::new::<T>();
+        let ptr = match layout.size() {
+            0 => NonNull::dangling(),
+            // SAFETY: the layout has a non-zero size
+            _ => match NonNull::new(unsafe { alloc(layout) }.cast::<T>()) {
+                Some(ptr) => ptr,
+                None => handle_alloc_error(layout),
+            },
+        };
+        // SAFETY: `ptr` is aligned for a `T`, and has room for one, or needs none
+        unsafe { ptr.as_ptr().write(value) };
+        MyBox { ptr }
+    }
+}
+impl<T> Deref for MyBox<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        // SAFETY: `ptr` holds a `T` from `new` until `drop`, and `&self` keeps it from changing
+        unsafe { self.ptr.as_ref() }
+    }
+}
+impl<T> DerefMut for MyBox<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        // SAFETY: as for `deref`, and `&mut self` makes this the only reference to it
+        unsafe { self.ptr.as_mut() }
+    }
+}
+/// The value is dropped first, in place, and then the memory it was in is freed, which is the
+/// order `Box<T>` does it in.
+impl<T> Drop for MyBox<T> {
+    fn drop(&mut self) {
+        let layout = Layout::new::<T>();
+        // SAFETY: the `T` is dropped once, here, and the memory was allocated with `layout`
+        unsafe {
+            ptr::drop_in_place(self.ptr.as_ptr());
+            if layout.size() != 0 {
+                dealloc(self.ptr.as_ptr().cast(), layout);
+            }
+        }
+    }
+}
+
+/// # Allocating by Hand
+/// Deref coercion turns a `&MyBox<String>` into a `&str`, as it does for a `&Box<String>`.
+fn allocating_by_hand(out: &mut dyn Output) -> fmt::Result {
+    Ok({
+        out.header("Allocating by Hand")?;
+
+        let mut name = MyBox::new(String::from("Rust"));
+        name.push_str("acean");
+        let hello = |name: &str| -> String { alloc::format!("Hello, {name}!") };
+        writeln!(out, "{}", hello(&name))?; // Hello, Rustacean!
+
+        let nothing = MyBox::new(());
+        writeln!(out, "a MyBox<()> holds {:?}", *nothing)?; // a MyBox<()> holds ()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::rc::Rc;
+
+    #[test]
+    fn it_drops_its_value_exactly_once() {
+        let value = Rc::new(());
+        let boxed = MyBox::new(Rc::clone(&value));
+        assert_eq!(Rc::strong_count(&boxed), 2);
+        drop(boxed);
+        assert_eq!(Rc::strong_count(&value), 1);
+    }
+
+    #[test]
+    fn it_coerces_to_what_it_holds() {
+        let mut out = String::new();
+        my_box(&mut out).unwrap();
+        assert!(out.contains("Hello, Rustacean!\n"), "{out}");
+    }
+}
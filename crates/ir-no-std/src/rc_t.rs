@@ -0,0 +1,74 @@
+//! # `Rc<T>` without `std`
+//! `Rc<T>` and `Weak<T>` are in `alloc::rc`: counting the owners of a value takes a heap to
+//! keep the value and its counts on, and nothing else. `Arc<T>`, in `alloc::sync`, needs no more
+//! than that either, only atomic instructions, which `core::sync::atomic` has; what is missing
+//! without `std` is the threads to share it between, which it takes an operating system to run.
+
+use super::Output;
+use alloc::rc::{Rc, Weak};
+use core::fmt;
+
+pub fn rc_t(out: &mut dyn Output) -> fmt::Result {
+    Ok({
+        sharing_a_value(out)?;
+        a_weak_reference(out)?;
+    })
+}
+
+/// # Sharing a Value
+/// Each `Rc::clone` is one more owner, and each owner that goes out of scope one fewer; the
+/// value is dropped with the last of them.
+fn sharing_a_value(out: &mut dyn Output) -> fmt::Result {
+    Ok({
+        out.header("Sharing a Value")?;
+
+        let a = Rc::new(5);
+        writeln!(out, "count after creating a = {}", Rc::strong_count(&a))?;
+        let b = Rc::clone(&a);
+        writeln!(out, "count after creating b = {}", Rc::strong_count(&a))?;
+        {
+            let _c = Rc::clone(&a);
+            writeln!(out, "count after creating c = {}", Rc::strong_count(&a))?;
+        }
+        writeln!(
+            out,
+            "count after c goes out of scope = {}",
+            Rc::strong_count(&b)
+        )?;
+        // count after creating a = 1
+        // count after creating b = 2
+        // count after creating c = 3
+        // count after c goes out of scope = 2
+    })
+}
+
+/// # A Weak Reference
+/// A `Weak<T>` doesn’t keep the value alive; `upgrade` is `Some` only while an `Rc<T>` does.
+fn a_weak_reference(out: &mut dyn Output) -> fmt::Result {
+    Ok({
+        out.header("A Weak Reference")?;
+
+        let strong = Rc::new("value");
+        let weak: Weak<_> = Rc::downgrade(&strong);
+        writeln!(out, "upgrade = {:?}", weak.upgrade())?; // upgrade = Some("value")
+        drop(strong);
+        writeln!(out, "upgrade = {:?}", weak.upgrade())?; // upgrade = None
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+
+    #[test]
+    fn the_count_goes_back_down() {
+        let mut out = String::new();
+        rc_t(&mut out).unwrap();
+        assert!(
+            out.contains("count after c goes out of scope = 2\n"),
+            "{out}"
+        );
+        assert!(out.ends_with("upgrade = None\n"), "{out}");
+    }
+}
@@ -0,0 +1,85 @@
+//! # `RefCell<T>` without `std`
+//! `Cell<T>` and `RefCell<T>` are in `core::cell`: checking the borrows at runtime takes a
+//! counter next to the value, and no heap at all. It is `Rc<RefCell<T>>`, a value with several
+//! owners that any of them can change, that needs `alloc`, for the `Rc`. `Mutex<T>` and
+//! `RwLock<T>`, the versions for threads, are only in `std`, since blocking a thread until the
+//! lock is free takes an operating system.
+
+use super::Output;
+use alloc::{rc::Rc, vec::Vec};
+use core::{
+    cell::{Cell, RefCell},
+    fmt,
+};
+
+pub fn ref_cell_t(out: &mut dyn Output) -> fmt::Result {
+    Ok({
+        interior_mutability(out)?;
+        a_borrow_checked_at_runtime(out)?;
+        shared_and_mutable(out)?;
+    })
+}
+
+/// # Interior Mutability
+/// A `Cell<T>` changes behind a shared reference by copying values in and out of it, so it never
+/// hands out a reference to what is inside.
+fn interior_mutability(out: &mut dyn Output) -> fmt::Result {
+    Ok({
+        out.header("Interior Mutability")?;
+
+        let counter = Cell::new(0);
+        let count = |counter: &Cell<i32>| counter.set(counter.get() + 1);
+        count(&counter);
+        count(&counter);
+        writeln!(out, "counter = {}", counter.get())?; // counter = 2
+    })
+}
+
+/// # A Borrow Checked at Runtime
+/// `try_borrow_mut` says that a borrow is still alive where `borrow_mut` would panic.
+fn a_borrow_checked_at_runtime(out: &mut dyn Output) -> fmt::Result {
+    Ok({
+        out.header("A Borrow Checked at Runtime")?;
+
+        let cell = RefCell::new(Vec::from([1, 2]));
+        {
+            let first = cell.borrow();
+            writeln!(out, "while borrowed: {:?}", cell.try_borrow_mut().is_ok())?;
+            writeln!(out, "first = {}", first[0])?;
+        }
+        cell.borrow_mut().push(3);
+        writeln!(out, "after the borrow: {:?}", cell.borrow())?;
+        // while borrowed: false
+        // first = 1
+        // after the borrow: [1, 2, 3]
+    })
+}
+
+/// # Shared and Mutable
+/// Each owner of an `Rc<RefCell<T>>` can change the value, and the others see the change.
+fn shared_and_mutable(out: &mut dyn Output) -> fmt::Result {
+    Ok({
+        out.header("Shared and Mutable")?;
+
+        let value = Rc::new(RefCell::new(5));
+        let a = Rc::clone(&value);
+        let b = Rc::clone(&value);
+        *a.borrow_mut() += 10;
+        *b.borrow_mut() *= 2;
+        writeln!(out, "value = {}", value.borrow())?; // value = 30
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::String;
+
+    #[test]
+    fn the_owners_see_each_others_changes() {
+        let mut out = String::new();
+        ref_cell_t(&mut out).unwrap();
+        assert!(out.contains("while borrowed: false\n"), "{out}");
+        assert!(out.ends_with("value = 30\n"), "{out}");
+    }
+}
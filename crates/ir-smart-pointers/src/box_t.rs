@@ -0,0 +1,398 @@
+//! # Using `Box<T>` to Point to Data on the Heap
+//! The most straightforward smart pointer is a box, whose type is
+//! written `Box<T>`. Boxes allow you to store data on the heap rather
+//! than the stack. What remains on the stack is the pointer to the
+//! heap data.
+//!
+//! Boxes don’t have performance overhead, other than storing their data
+//! on the heap instead of on the stack. But they don’t have many extra
+//! capabilities either. You’ll use them most often in these situations:
+//!
+//! 1. When you have a type whose size can’t be known at compile time
+//!    and you want to use a value of that type in a context that requires
+//!    an exact size
+//! 2. When you have a large amount of data and you want to transfer ownership
+//!    but ensure the data won’t be copied when you do so
+//! 3. When you want to own a value and you care only that it’s a type that
+//!    implements a particular trait rather than being of a specific type
+//!
+//! `cargo bench --features bench -- box` measures the overhead there is: the allocation.
+
+use std::{
+    fmt::{self, Display},
+    io::Result,
+    mem,
+};
+
+pub fn box_t() -> Result<()> {
+    Ok({
+        storing_an_i32_value_on_the_heap_using_a_box()?;
+        enabling_recursive_types_with_boxes()?;
+        list_surgery_moving_ownership_in_and_out_of_a_box()?;
+    })
+}
+
+fn storing_an_i32_value_on_the_heap_using_a_box() -> Result<()> {
+    Ok({
+        let b = Box::new(5);
+        println!("b = {}", b);
+    })
+}
+
+/// # Enabling Recursive Types with `Boxes`
+/// A value of recursive type can have another value of the same type as part of itself.
+/// Recursive types pose an issue because at compile time Rust needs to know how much
+/// space a type takes up. However, the nesting of values of recursive types could
+/// theoretically continue infinitely, so Rust can’t know how much space the value needs.
+/// Because boxes have a known size, we can enable recursive types by inserting a box in
+/// the recursive type definition.
+///
+/// As an example of a recursive type, let’s explore the _cons list_. This is a data
+/// type commonly found in functional programming languages. The cons list type we’ll
+/// define is straightforward except for the recursion; therefore, the concepts in the
+/// example we’ll work with will be useful any time you get into more complex situations
+/// involving recursive types.
+///
+/// # Without `Box<T>`
+/// recursive type `box_t::List` has infinite size
+/// ```compile_fail
+/// enum List<T> {
+///     Cons(T, List<T>),
+///     Nil,
+/// }
+/// ```
+/// insert some indirection (e.g., a `Box`, `Rc`, or `&`) to break the cycle: `Box<`, `>`
+///
+/// The error shows this type “has infinite size.” The reason is that we’ve defined `List`
+/// with a variant that is recursive: it holds another value of itself directly. As a result,
+/// Rust can’t figure out how much space it needs to store a `List` value.
+#[derive(Debug)]
+pub enum List<T> {
+    Cons(T, Box<List<T>>), // The `Cons` variant needs the size of a `T` plus the space to store the box’s pointer data.
+    Nil, //                   The `Nil` variant stores no values, so it needs less space than the `Cons` variant.
+}
+// We now know that any `List` value will take up the size of a `T` plus the size of a box’s pointer data.
+// By using a box, we’ve broken the infinite, recursive chain,
+// so the compiler can figure out the size it needs to store a `List` value.
+impl<T> List<T> {
+    pub fn for_each(&self, f: impl Fn(&T) -> ()) {
+        use List::*;
+
+        if let Cons(t, next) = self {
+            f(t);
+            Self::for_each(next, f);
+        }
+    }
+
+    /// Moves `value` in as the new head, and the old list in as its tail.
+    pub fn push_front(&mut self, value: T) {
+        let old = mem::replace(self, List::Nil);
+        *self = List::Cons(value, Box::new(old));
+    }
+
+    /// Moves the head value out and the tail up into its place.
+    pub fn pop_front(&mut self) -> Option<T> {
+        use List::*;
+
+        match mem::replace(self, Nil) {
+            Cons(value, next) => {
+                *self = *next;
+                Some(value)
+            }
+            Nil => None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        let mut len = 0;
+        let mut node = self;
+        while let List::Cons(_, next) = node {
+            len += 1;
+            node = next;
+        }
+        len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        matches!(self, List::Nil)
+    }
+
+    /// Walks to the `Nil` at the end and replaces it with `other`. Nothing is copied: `other`’s
+    /// nodes are moved in, and its first node, which lived wherever `other` was, is moved into
+    /// the place where the `Nil` was.
+    pub fn append(&mut self, other: List<T>) {
+        let mut node = self;
+        while let List::Cons(_, next) = node {
+            node = next;
+        }
+        *node = other;
+    }
+}
+/// Renders `1 -> 2 -> 3 -> Nil`. Walking the list with a loop instead of recursing keeps the
+/// stack flat however long the list is.
+impl<T: Display> Display for List<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut node = self;
+        while let List::Cons(value, next) = node {
+            write!(f, "{value} -> ")?;
+            node = next;
+        }
+        write!(f, "Nil")
+    }
+}
+/// Builds the list back to front, so the first element of the `Vec` ends up at the head.
+impl<T> From<Vec<T>> for List<T> {
+    fn from(values: Vec<T>) -> List<T> {
+        values
+            .into_iter()
+            .rev()
+            .fold(List::Nil, |list, value| List::Cons(value, Box::new(list)))
+    }
+}
+
+/// # A `cons!` Macro
+/// Declarative macros match their input against patterns, like `match` does with values, and
+/// may call themselves recursively. Each step peels off the first expression and conses it onto
+/// the expansion of the rest, until nothing is left and the list ends in `Nil`:
+/// ```text
+/// cons![1, 2, 3]
+/// Cons(1, Box::new(cons![2, 3]))
+/// Cons(1, Box::new(Cons(2, Box::new(cons![3]))))
+/// Cons(1, Box::new(Cons(2, Box::new(Cons(3, Box::new(cons![]))))))
+/// Cons(1, Box::new(Cons(2, Box::new(Cons(3, Box::new(Nil))))))
+/// ```
+/// The paths start with `$crate` so that the macro expands correctly wherever it is used,
+/// whether or not the caller has imported `List`.
+macro_rules! cons {
+    () => {
+        $crate::box_t::List::Nil
+    };
+    ($head:expr $(, $tail:expr)* $(,)?) => {
+        $crate::box_t::List::Cons(
+            $head,
+            Box::new(cons![$($tail),*]),
+        )
+    };
+}
+#[allow(clippy::identity_op)] // `0 + *i` makes the dereference visible
+fn enabling_recursive_types_with_boxes() -> Result<()> {
+    Ok({
+        println!("i32");
+        {
+            // `cons![1, 2, 3]` expands to `Cons(1, Box::new(Cons(2, Box::new(Cons(3, Box::new(Nil))))))`
+            let list = cons![1, 2, 3];
+            list.for_each(|i| println!("{}", 0 + *i));
+            println!("{list}");
+
+            println!("do this twice to make sure nothing was moved");
+            list.for_each(|i| println!("{}", 0 + *i));
+            println!("{list}");
+        }
+
+        println!("String");
+        {
+            let list = List::from(vec![
+                String::from("one"),
+                String::from("two"),
+                String::from("three"),
+            ]);
+            list.for_each(|s| println!("{s}"));
+            println!("{list}");
+
+            println!("do this twice to make sure nothing was moved");
+            list.for_each(|i| println!("{i}"));
+            println!("{list}");
+        }
+    })
+    // Boxes provide only the indirection and heap allocation; they don’t have any other special capabilities.
+    // They also don’t have the performance overhead that these special capabilities incur, so they can be useful
+    // in cases like the cons list where the indirection is the only feature we need.
+}
+// The `Box<T>` type is a smart pointer because it implements the `Deref` trait, which allows `Box<T>`
+// values to be treated like references. When a `Box<T>` value goes out of scope, the heap data that the
+// box is pointing to is cleaned up as well because of the `Drop` trait implementation.
+
+/// # List Surgery: Moving Ownership In and Out of a Box
+/// To put a new head in front of a list we have to move the old list into a new `Box`, but we
+/// only have `&mut self`, and Rust won’t let us move out of a borrow and leave the place empty:
+/// ```compile_fail
+/// # enum List<T> { Cons(T, Box<List<T>>), Nil }
+/// # impl<T> List<T> {
+/// pub fn push_front(&mut self, value: T) {
+///     *self = List::Cons(value, Box::new(*self));
+/// }
+/// # }
+/// ```
+/// cannot move out of `*self` which is behind a mutable reference
+///
+/// `std::mem::replace` swaps a placeholder (`Nil`, which costs nothing to create) into the place
+/// and hands us the old value by ownership. `pop_front` does the same in reverse: take the whole
+/// node, keep its value, and move the boxed tail out with `*next` to become the new list.
+fn list_surgery_moving_ownership_in_and_out_of_a_box() -> Result<()> {
+    Ok({
+        span!("list_surgery_moving_ownership_in_and_out_of_a_box");
+        header!("List Surgery: Moving Ownership In and Out of a Box");
+
+        let mut list = List::Nil;
+        println!("len = {}, is_empty = {}", list.len(), list.is_empty()); // len = 0, is_empty = true
+
+        for s in ["three", "two", "one"] {
+            list.push_front(String::from(s));
+        }
+        println!("len = {}, is_empty = {}", list.len(), list.is_empty()); // len = 3, is_empty = false
+        list.for_each(|s| println!("{s}"));
+
+        while let Some(s) = list.pop_front() {
+            println!("popped {s}, {} left", list.len());
+        }
+        println!("pop_front on Nil = {:?}", list.pop_front()); // pop_front on Nil = None
+
+        // with a single owner, concatenation can simply move the second list onto the end
+        let mut front = cons![1, 2];
+        front.append(cons![3, 4]);
+        println!("appended = {front}"); // appended = 1 -> 2 -> 3 -> 4 -> Nil
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_pushes_and_pops_in_lifo_order() {
+        let mut list = List::Nil;
+        list.push_front(1);
+        list.push_front(2);
+        list.push_front(3);
+
+        assert_eq!(list.pop_front(), Some(3));
+        assert_eq!(list.pop_front(), Some(2));
+        assert_eq!(list.pop_front(), Some(1));
+        assert_eq!(list.pop_front(), None);
+    }
+
+    #[test]
+    fn it_counts_its_nodes() {
+        let mut list = List::Nil;
+        assert_eq!(list.len(), 0);
+        assert!(list.is_empty());
+
+        list.push_front("a");
+        list.push_front("b");
+        assert_eq!(list.len(), 2);
+        assert!(!list.is_empty());
+
+        list.pop_front();
+        assert_eq!(list.len(), 1);
+    }
+
+    #[test]
+    fn it_moves_owned_values_out() {
+        let mut list = List::Nil;
+        list.push_front(String::from("owned"));
+
+        let s: String = list.pop_front().unwrap();
+        assert_eq!(s, "owned");
+        assert!(list.is_empty());
+    }
+
+    #[test]
+    fn it_builds_the_same_list_from_a_macro_or_a_vec() {
+        let from_macro: List<i32> = cons![1, 2, 3];
+        let from_vec = List::from(vec![1, 2, 3]);
+
+        assert_eq!(from_macro.to_string(), "1 -> 2 -> 3 -> Nil");
+        assert_eq!(from_vec.to_string(), "1 -> 2 -> 3 -> Nil");
+        assert!(List::<i32>::from(vec![]).is_empty());
+        assert!(matches!(cons![] as List<i32>, List::Nil));
+    }
+
+    #[test]
+    fn it_appends_by_moving_the_other_list_in() {
+        let mut list = cons![String::from("a"), String::from("b")];
+        list.append(cons![String::from("c")]);
+        assert_eq!(list.to_string(), "a -> b -> c -> Nil");
+
+        let mut empty = List::Nil;
+        empty.append(cons![1]);
+        assert_eq!(empty.to_string(), "1 -> Nil");
+
+        let mut list = cons![1];
+        list.append(List::Nil);
+        assert_eq!(list.len(), 1);
+    }
+
+    #[test]
+    fn it_displays_as_a_chain() {
+        let mut list = List::Nil;
+        assert_eq!(list.to_string(), "Nil");
+
+        for i in [3, 2, 1] {
+            list.push_front(i);
+        }
+        assert_eq!(list.to_string(), "1 -> 2 -> 3 -> Nil");
+    }
+}
+
+/// A `List` should behave like a `Vec` whose front is its head: whatever sequence of operations
+/// is applied to both, they should end up holding the same values in the same order.
+#[cfg(test)]
+mod list_properties {
+    use super::*;
+    use proptest::prelude::*;
+
+    #[derive(Debug, Clone)]
+    enum Op {
+        PushFront(i32),
+        PopFront,
+        Append(Vec<i32>),
+    }
+
+    fn op() -> impl Strategy<Value = Op> {
+        prop_oneof![
+            any::<i32>().prop_map(Op::PushFront),
+            Just(Op::PopFront),
+            prop::collection::vec(any::<i32>(), 0..4).prop_map(Op::Append),
+        ]
+    }
+
+    fn to_vec(list: &List<i32>) -> Vec<i32> {
+        let mut values = vec![];
+        let mut node = list;
+        while let List::Cons(value, next) = node {
+            values.push(*value);
+            node = next;
+        }
+        values
+    }
+
+    proptest! {
+        #![proptest_config(ir_core::utils::miri::proptest_config())]
+
+        #[test]
+        fn it_behaves_like_a_vec(initial in prop::collection::vec(any::<i32>(), 0..8),
+                                 ops in prop::collection::vec(op(), 0..32)) {
+            let mut list = List::from(initial.clone());
+            let mut model = initial;
+            for op in ops {
+                match op {
+                    Op::PushFront(value) => {
+                        list.push_front(value);
+                        model.insert(0, value);
+                    }
+                    Op::PopFront => {
+                        let expected = (!model.is_empty()).then(|| model.remove(0));
+                        prop_assert_eq!(list.pop_front(), expected);
+                    }
+                    Op::Append(values) => {
+                        model.extend(&values);
+                        list.append(List::from(values));
+                    }
+                }
+                prop_assert_eq!(list.len(), model.len());
+                prop_assert_eq!(list.is_empty(), model.is_empty());
+            }
+            prop_assert_eq!(to_vec(&list), model);
+        }
+    }
+}
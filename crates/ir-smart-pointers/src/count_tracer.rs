@@ -0,0 +1,166 @@
+//! # Following the Counts over Time
+//! The lessons about `Rc<T>` are about how its counts change: up by one with each `Rc::clone`,
+//! down by one as each owner goes out of scope. A `CountTracer` follows one value: each
+//! `snapshot` records its strong and weak counts at that point, with a label for what just
+//! happened, and at the end the whole timeline is shown at once, as a `table` or a `chart`:
+//! ```text
+//! `a`                strong  weak
+//! after creating `a`      1     0
+//! after creating `b`      2     0
+//! ```
+//! Taking a snapshot only reads the counts, so the tracer itself never changes them. With `-vv`,
+//! each snapshot is also printed as it is taken.
+
+use ir_core::{
+    output::{self, Verbosity},
+    style::{paint, Style},
+};
+use std::{
+    fmt::Write as _,
+    rc::{Rc, Weak},
+};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snapshot {
+    pub label: String,
+    pub strong: usize,
+    pub weak: usize,
+}
+
+/// The timeline of the counts of the value called `name`.
+#[derive(Debug, Clone)]
+pub struct CountTracer {
+    name: String,
+    snapshots: Vec<Snapshot>,
+}
+impl CountTracer {
+    pub fn new(name: &str) -> CountTracer {
+        CountTracer {
+            name: name.to_string(),
+            snapshots: vec![],
+        }
+    }
+
+    pub fn snapshot<T: ?Sized>(&mut self, label: &str, rc: &Rc<T>) {
+        self.push(label, Rc::strong_count(rc), Rc::weak_count(rc));
+    }
+
+    /// A snapshot through a `Weak`, which can still be asked for the counts once every `Rc` is
+    /// gone, and then says 0 and 0.
+    pub fn snapshot_weak<T>(&mut self, label: &str, weak: &Weak<T>) {
+        self.push(label, weak.strong_count(), weak.weak_count());
+    }
+
+    fn push(&mut self, label: &str, strong: usize, weak: usize) {
+        if output::verbosity() >= Verbosity::Debug {
+            println!("`{}` {label}: strong = {strong}, weak = {weak}", self.name);
+        }
+        self.snapshots.push(Snapshot {
+            label: label.to_string(),
+            strong,
+            weak,
+        });
+    }
+
+    #[allow(dead_code)] // for the tests, which check the timeline rather than its drawing
+    pub fn snapshots(&self) -> &[Snapshot] {
+        &self.snapshots
+    }
+
+    fn width(&self) -> usize {
+        self.snapshots
+            .iter()
+            .map(|snapshot| snapshot.label.chars().count())
+            .chain([self.name.chars().count() + 2])
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// One row per snapshot, with the counts in columns.
+    pub fn table(&self) -> String {
+        let width = self.width();
+        let mut table = format!("{:<width$}  strong  weak\n", format!("`{}`", self.name));
+        for Snapshot {
+            label,
+            strong,
+            weak,
+        } in &self.snapshots
+        {
+            let counts = paint(Style::Count, format!("{strong:>6}  {weak:>4}"));
+            let _ = writeln!(table, "{label:<width$}  {counts}");
+        }
+        table
+    }
+
+    /// One bar per snapshot, a `#` for each strong count and a `-` for each weak one.
+    pub fn chart(&self) -> String {
+        let width = self.width();
+        let bars = self
+            .snapshots
+            .iter()
+            .map(|snapshot| snapshot.strong + snapshot.weak)
+            .max()
+            .unwrap_or(0);
+        let mut chart = format!("`{}`, # strong, - weak\n", self.name);
+        for Snapshot {
+            label,
+            strong,
+            weak,
+        } in &self.snapshots
+        {
+            let bar = "#".repeat(*strong) + &"-".repeat(*weak);
+            let counts = paint(Style::Count, format!("{strong} {weak}"));
+            let _ = writeln!(chart, "{label:<width$}  {bar:<bars$}  {counts}");
+        }
+        chart
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tracer() -> CountTracer {
+        let mut tracer = CountTracer::new("a");
+        let a = Rc::new(5);
+        tracer.snapshot("created", &a);
+        let b = Rc::clone(&a);
+        let weak = Rc::downgrade(&a);
+        tracer.snapshot("cloned and downgraded", &a);
+        drop((a, b));
+        tracer.snapshot_weak("dropped", &weak);
+        tracer
+    }
+
+    #[test]
+    fn it_records_the_counts_without_changing_them() {
+        let counts: Vec<_> = tracer()
+            .snapshots()
+            .iter()
+            .map(|snapshot| (snapshot.strong, snapshot.weak))
+            .collect();
+        assert_eq!(counts, [(1, 0), (2, 1), (0, 0)]);
+    }
+
+    #[test]
+    fn it_shows_the_timeline_as_a_table() {
+        assert_eq!(
+            tracer().table(),
+            "`a`                    strong  weak\n\
+             created                     1     0\n\
+             cloned and downgraded       2     1\n\
+             dropped                     0     0\n"
+        );
+    }
+
+    #[test]
+    fn it_shows_the_timeline_as_a_chart() {
+        assert_eq!(
+            tracer().chart(),
+            "`a`, # strong, - weak\n\
+             created                #    1 0\n\
+             cloned and downgraded  ##-  2 1\n\
+             dropped                     0 0\n"
+        );
+    }
+}
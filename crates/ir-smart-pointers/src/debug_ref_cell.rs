@@ -0,0 +1,357 @@
+//! # A `RefCell<T>` That Remembers Where It Was Borrowed
+//! When `RefCell<T>` refuses a borrow, it panics with `already borrowed: BorrowMutError` and the
+//! location of the borrow that failed. The location we actually need is the other one: where
+//! the borrow that is _still alive_ was taken. In a program that passes `Rc<RefCell<T>>`s around,
+//! that can be anywhere.
+//!
+//! `DebugRefCell<T>` wraps a `RefCell<T>` and, in debug builds, records the caller of every
+//! `borrow` and `borrow_mut` until the returned guard is dropped. `#[track_caller]` is what makes
+//! this possible: inside a function marked with it, `Location::caller()` is the location of the
+//! call to that function rather than a line inside it. In release builds nothing is recorded,
+//! and the messages are as terse as `RefCell`’s.
+//!
+//! The cons `List` and `TreeNode` of the `RefCell<T>` lesson are built of `DebugRefCell`s too,
+//! made with `when_verbose`: walking a tree borrows at every step, so they record the borrows
+//! only when the lessons run with `--verbose` or `--debug`.
+
+use ir_core::output::{self, Verbosity};
+use std::{
+    cell::{Cell, Ref, RefCell, RefMut},
+    fmt::{self, Debug, Display},
+    ops::{Deref, DerefMut},
+    panic::Location,
+};
+
+pub struct DebugRefCell<T> {
+    value: RefCell<T>,
+    origins: Origins,
+}
+
+struct Origins {
+    /// Whether the borrows are recorded at all, which they never are in release builds.
+    recording: bool,
+    active: RefCell<Vec<Origin>>,
+    next_id: Cell<usize>,
+}
+#[derive(Clone, Copy)]
+struct Origin {
+    id: usize,
+    kind: BorrowKind,
+    location: &'static Location<'static>,
+}
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BorrowKind {
+    Shared,
+    Mutable,
+}
+impl Display for BorrowKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BorrowKind::Shared => write!(f, "borrow"),
+            BorrowKind::Mutable => write!(f, "borrow_mut"),
+        }
+    }
+}
+
+impl Origins {
+    fn new(recording: bool) -> Origins {
+        Origins {
+            recording: recording && cfg!(debug_assertions),
+            active: RefCell::default(),
+            next_id: Cell::default(),
+        }
+    }
+
+    fn record(&self, kind: BorrowKind, location: &'static Location<'static>) -> OriginGuard<'_> {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+        if self.recording {
+            self.active.borrow_mut().push(Origin { id, kind, location });
+        }
+        OriginGuard { origins: self, id }
+    }
+}
+/// Removes its `Origin` from the list when the borrow it belongs to ends.
+struct OriginGuard<'b> {
+    origins: &'b Origins,
+    id: usize,
+}
+impl Drop for OriginGuard<'_> {
+    fn drop(&mut self) {
+        self.origins
+            .active
+            .borrow_mut()
+            .retain(|origin| origin.id != self.id);
+    }
+}
+
+/// Why a borrow was refused, and, in debug builds, where the conflicting borrows were taken.
+#[derive(Debug)]
+pub struct BorrowConflict {
+    attempted: BorrowKind,
+    at: &'static Location<'static>,
+    outstanding: Vec<(BorrowKind, &'static Location<'static>)>,
+}
+impl Display for BorrowConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let reason = match self.attempted {
+            BorrowKind::Shared => "already mutably borrowed",
+            BorrowKind::Mutable => "already borrowed",
+        };
+        write!(
+            f,
+            "{reason}: {} at {} conflicts with",
+            self.attempted, self.at
+        )?;
+        if self.outstanding.is_empty() {
+            return write!(
+                f,
+                " a borrow recorded only in debug builds, and for the tree and the list, only \
+                 with `--verbose`"
+            );
+        }
+        for (kind, location) in &self.outstanding {
+            write!(f, "\n  outstanding {kind} at {location}")?;
+        }
+        Ok(())
+    }
+}
+
+pub struct DebugRef<'b, T: ?Sized> {
+    value: Ref<'b, T>,
+    _origin: OriginGuard<'b>,
+}
+pub struct DebugRefMut<'b, T> {
+    value: RefMut<'b, T>,
+    _origin: OriginGuard<'b>,
+}
+impl<'b, T: ?Sized> DebugRef<'b, T> {
+    /// Like `Ref::map`: a borrow of a part of the value, still remembered where it was taken.
+    pub fn map<U: ?Sized>(orig: DebugRef<'b, T>, f: impl FnOnce(&T) -> &U) -> DebugRef<'b, U> {
+        DebugRef {
+            value: Ref::map(orig.value, f),
+            _origin: orig._origin,
+        }
+    }
+}
+impl<T: ?Sized> Deref for DebugRef<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+impl<T> Deref for DebugRefMut<'_, T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
+impl<T> DerefMut for DebugRefMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.value
+    }
+}
+// formatted as what they borrow, as `Ref` and `RefMut` are
+impl<T: ?Sized + Display> Display for DebugRef<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+impl<T: ?Sized + Debug> Debug for DebugRef<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+impl<T: Display> Display for DebugRefMut<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+impl<T: Debug> Debug for DebugRefMut<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+impl<T> DebugRefCell<T> {
+    pub fn new(value: T) -> DebugRefCell<T> {
+        DebugRefCell {
+            value: RefCell::new(value),
+            origins: Origins::new(true),
+        }
+    }
+
+    /// Like `new`, but the borrows are only recorded if the lesson runs with `--verbose` or
+    /// `--debug`, so that a cell that is borrowed all the time costs what a `RefCell` does.
+    pub fn when_verbose(value: T) -> DebugRefCell<T> {
+        DebugRefCell {
+            value: RefCell::new(value),
+            origins: Origins::new(output::verbosity() >= Verbosity::Verbose),
+        }
+    }
+
+    /// Like `RefCell::borrow`, but the panic names every outstanding `borrow_mut`.
+    #[track_caller]
+    pub fn borrow(&self) -> DebugRef<'_, T> {
+        self.try_borrow()
+            .unwrap_or_else(|conflict| panic!("{conflict}"))
+    }
+
+    /// Like `RefCell::borrow_mut`, but the panic names every outstanding borrow.
+    #[track_caller]
+    pub fn borrow_mut(&self) -> DebugRefMut<'_, T> {
+        self.try_borrow_mut()
+            .unwrap_or_else(|conflict| panic!("{conflict}"))
+    }
+
+    #[track_caller]
+    pub fn try_borrow(&self) -> Result<DebugRef<'_, T>, BorrowConflict> {
+        let at = Location::caller();
+        match self.value.try_borrow() {
+            Ok(value) => Ok(DebugRef {
+                value,
+                _origin: self.origins.record(BorrowKind::Shared, at),
+            }),
+            Err(_) => Err(self.conflict(BorrowKind::Shared, at)),
+        }
+    }
+
+    #[track_caller]
+    pub fn try_borrow_mut(&self) -> Result<DebugRefMut<'_, T>, BorrowConflict> {
+        let at = Location::caller();
+        match self.value.try_borrow_mut() {
+            Ok(value) => Ok(DebugRefMut {
+                value,
+                _origin: self.origins.record(BorrowKind::Mutable, at),
+            }),
+            Err(_) => Err(self.conflict(BorrowKind::Mutable, at)),
+        }
+    }
+
+    fn conflict(&self, attempted: BorrowKind, at: &'static Location<'static>) -> BorrowConflict {
+        BorrowConflict {
+            attempted,
+            at,
+            outstanding: self
+                .origins
+                .active
+                .borrow()
+                .iter()
+                .map(|origin| (origin.kind, origin.location))
+                .collect(),
+        }
+    }
+}
+/// Shown as the `RefCell` it wraps, so that a `TreeNode` prints as the book’s does.
+impl<T: Debug> Debug for DebugRefCell<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.value.fmt(f)
+    }
+}
+
+/// Serialized as the `RefCell` it wraps, which fails while it is mutably borrowed.
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for DebugRefCell<T> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.value.serialize(serializer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(debug_assertions)] // `cargo bench` builds the tests in release, without the origins
+    use {
+        ir_core::output::Output,
+        std::panic::{self, AssertUnwindSafe},
+    };
+
+    #[test]
+    fn it_allows_what_ref_cell_allows() {
+        let cell = DebugRefCell::new(vec![1]);
+        {
+            let a = cell.borrow();
+            let b = cell.borrow();
+            assert_eq!(a.len() + b.len(), 2);
+        }
+        cell.borrow_mut().push(2);
+        assert_eq!(*cell.borrow(), vec![1, 2]);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn it_forgets_a_borrow_once_its_guard_is_dropped() {
+        let cell = DebugRefCell::new(0);
+        drop(cell.borrow_mut());
+        let _shared = cell.borrow();
+        assert_eq!(cell.origins.active.borrow().len(), 1);
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn the_conflict_names_the_outstanding_borrow() {
+        let cell = DebugRefCell::new(0);
+        let _first = cell.borrow_mut();
+        let first_line = line!() - 1;
+
+        let conflict = cell.try_borrow().err().unwrap().to_string();
+
+        assert!(conflict.starts_with("already mutably borrowed: borrow at"));
+        assert!(conflict.contains(&format!(
+            "outstanding borrow_mut at {}:{first_line}:",
+            file!()
+        )));
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn a_cell_made_when_verbose_records_only_with_verbose() {
+        let normal = DebugRefCell::when_verbose(0);
+        let _a = normal.borrow_mut();
+        let conflict = normal.try_borrow().err().unwrap().to_string();
+        assert!(!conflict.contains("outstanding"));
+
+        let mut verbose = Output {
+            verbosity: Verbosity::Verbose,
+            ..Output::silent()
+        };
+        let verbose = output::redirect(&mut verbose, || DebugRefCell::when_verbose(0));
+        let _b = verbose.borrow_mut();
+        let conflict = verbose.try_borrow().err().unwrap().to_string();
+        assert!(conflict.contains("outstanding borrow_mut at"));
+    }
+
+    #[test]
+    fn it_shows_as_the_ref_cell_it_wraps() {
+        let cell = DebugRefCell::new(vec![1, 2]);
+        assert_eq!(
+            format!("{cell:?}"),
+            format!("{:?}", RefCell::new(vec![1, 2]))
+        );
+        assert_eq!(
+            format!("{:?}", DebugRef::map(cell.borrow(), |v| &v[1..])),
+            "[2]"
+        );
+    }
+
+    #[cfg(debug_assertions)]
+    #[test]
+    fn the_panic_message_names_every_outstanding_borrow() {
+        let cell = DebugRefCell::new(0);
+        let _a = cell.borrow();
+        let _b = cell.borrow();
+        let b_line = line!() - 1;
+
+        let panic = panic::catch_unwind(AssertUnwindSafe(|| {
+            *cell.borrow_mut() += 1;
+        }))
+        .unwrap_err();
+        let message = panic.downcast_ref::<String>().unwrap();
+
+        assert!(message.starts_with("already borrowed: borrow_mut at"));
+        assert_eq!(message.matches("outstanding borrow at").count(), 2);
+        assert!(message.contains(&format!("{}:{b_line}:", file!())));
+    }
+}
@@ -0,0 +1,440 @@
+//! # A `Messenger` That Appends to a Log File
+//! `FileLogger` owns its path as a `PathBuf`, so it can be built from a `&str`, a `String`, a
+//! `&Path` or a `PathBuf`, and doesn’t borrow from whoever built it. Its options are set with a
+//! builder, since most of them have sensible defaults:
+//! ```text
+//! let logger = FileLogger::builder("app.log")
+//!     .timestamps(true)
+//!     .min_level(LogLevel::Warn)
+//!     .build();
+//! ```
+//! Each message is written on its own line, prefixed with its level and, optionally, the time
+//! it was sent: `[2024-01-01T00:00:00Z][WARN] Warning: You've used up over 75% of your quota!`
+//!
+//! With `.rotate(max_bytes, max_files)`, a log that is about to grow past `max_bytes` is moved
+//! aside before the next line is written: `app.log` becomes `app.log.1`, the old `app.log.1`
+//! becomes `app.log.2`, and so on up to `app.log.{max_files}`; the oldest file is deleted.
+//!
+//! The log is opened by the first message and kept open, in a `BufWriter`, for the ones after
+//! it, rather than opened again for each line. Each message is flushed as it is sent, so the
+//! log is up to date whenever it is read; with `.buffered(true)`, lines wait in the buffer until
+//! it is full, or the logger is dropped, which is fewer writes for a chatty program, and a log
+//! that lags behind it. `Messenger::send` takes `&self`, so the open file is in a `RefCell`.
+
+use super::ref_cell_t::Messenger;
+use std::{
+    cell::RefCell,
+    fmt::{self, Display},
+    fs::{self, File, OpenOptions},
+    io::{BufWriter, ErrorKind, Result, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// How serious a message is. Levels are ordered, so that `min_level` can filter out the ones
+/// below it. The sender says which level a message is, with `Messenger::send_at`; `send` sends
+/// it as `Info`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+impl Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LogLevel::Debug => write!(f, "DEBUG"),
+            LogLevel::Info => write!(f, "INFO"),
+            LogLevel::Warn => write!(f, "WARN"),
+            LogLevel::Error => write!(f, "ERROR"),
+        }
+    }
+}
+
+pub struct FileLogger {
+    path: PathBuf,
+    timestamps: bool,
+    min_level: LogLevel,
+    rotation: Option<Rotation>,
+    buffered: bool,
+    /// The log, once a message has opened it. `BufWriter` flushes what is left in it when it is
+    /// dropped, with the logger.
+    file: RefCell<Option<BufWriter<File>>>,
+}
+#[derive(Debug, Clone, Copy)]
+struct Rotation {
+    max_bytes: u64,
+    max_files: usize,
+}
+impl FileLogger {
+    /// A logger without timestamps that writes every message.
+    pub fn new(path: impl Into<PathBuf>) -> FileLogger {
+        FileLogger::builder(path).build()
+    }
+
+    pub fn builder(path: impl Into<PathBuf>) -> FileLoggerBuilder {
+        FileLoggerBuilder {
+            logger: FileLogger {
+                path: path.into(),
+                timestamps: false,
+                min_level: LogLevel::Debug,
+                rotation: None,
+                buffered: false,
+                file: RefCell::new(None),
+            },
+        }
+    }
+
+    fn line(&self, level: LogLevel, msg: &str) -> String {
+        match self.timestamps {
+            true => format!("[{}][{level}] {msg}", utc_timestamp(SystemTime::now())),
+            false => format!("[{level}] {msg}"),
+        }
+    }
+
+    /// Moves the log aside if writing `next_line` would take it past `max_bytes`. A log that is
+    /// still empty is never rotated, so a line longer than `max_bytes` gets a file to itself. The
+    /// buffer is flushed first, for the size on disk to be the whole log, and the file closed
+    /// before it is moved, to be opened again, new, by the next line.
+    fn rotate_before(&self, next_line: &str, file: &mut Option<BufWriter<File>>) -> Result<()> {
+        let Some(Rotation {
+            max_bytes,
+            max_files,
+        }) = self.rotation
+        else {
+            return Ok(());
+        };
+        if let Some(file) = file {
+            file.flush()?;
+        }
+        let size = match fs::metadata(&self.path) {
+            Ok(metadata) => metadata.len(),
+            Err(x) if x.kind() == ErrorKind::NotFound => return Ok(()),
+            Err(x) => return Err(x),
+        };
+        if size == 0 || size + next_line.len() as u64 <= max_bytes {
+            return Ok(());
+        }
+        *file = None;
+
+        if max_files == 0 {
+            return fs::remove_file(&self.path);
+        }
+        // oldest first, so that no file is renamed onto one that hasn’t been moved yet
+        remove_if_exists(&rotated(&self.path, max_files))?;
+        for n in (1..max_files).rev() {
+            let from = rotated(&self.path, n);
+            if from.exists() {
+                fs::rename(from, rotated(&self.path, n + 1))?;
+            }
+        }
+        fs::rename(&self.path, rotated(&self.path, 1))
+    }
+}
+
+/// `app.log` -> `app.log.{n}`, for any `n` but 0, which is the live log itself.
+fn rotated(path: &Path, n: usize) -> PathBuf {
+    if n == 0 {
+        return path.to_path_buf();
+    }
+    let mut name = path.as_os_str().to_owned();
+    name.push(format!(".{n}"));
+    PathBuf::from(name)
+}
+
+fn remove_if_exists(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Err(x) if x.kind() != ErrorKind::NotFound => Err(x),
+        _ => Ok(()),
+    }
+}
+impl Messenger for FileLogger {
+    fn send(&self, msg: &str) {
+        self.send_at(LogLevel::Info, msg);
+    }
+
+    fn try_send(&self, msg: &str) -> Result<()> {
+        self.try_send_at(LogLevel::Info, msg)
+    }
+
+    fn send_at(&self, level: LogLevel, msg: &str) {
+        if let Err(x) = self.try_send_at(level, msg) {
+            log::warn!("{x}, printing instead");
+            println!("{msg}");
+        }
+    }
+
+    fn try_send_at(&self, level: LogLevel, msg: &str) -> Result<()> {
+        if level < self.min_level {
+            return Ok(());
+        }
+        let line = format!("{}\n", self.line(level, msg));
+        let mut file = self.file.borrow_mut();
+        self.rotate_before(&line, &mut file)?;
+        let file = match &mut *file {
+            Some(file) => file,
+            None => {
+                let opened = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&self.path)?;
+                file.insert(BufWriter::new(opened))
+            }
+        };
+        file.write_all(line.as_bytes())?;
+        match self.buffered {
+            true => Ok(()),
+            false => file.flush(),
+        }
+    }
+}
+
+/// Sets `FileLogger`’s options one at a time; see `FileLogger::builder`.
+pub struct FileLoggerBuilder {
+    logger: FileLogger,
+}
+impl FileLoggerBuilder {
+    /// Prefix each line with the UTC time it was written. Off by default.
+    pub fn timestamps(mut self, timestamps: bool) -> FileLoggerBuilder {
+        self.logger.timestamps = timestamps;
+        self
+    }
+
+    /// Skip messages below `min_level`. `LogLevel::Debug`, which skips nothing, by default.
+    pub fn min_level(mut self, min_level: LogLevel) -> FileLoggerBuilder {
+        self.logger.min_level = min_level;
+        self
+    }
+
+    /// Rotate the log before it grows past `max_bytes`, keeping at most `max_files` old logs.
+    /// Off by default, which lets the log grow forever.
+    pub fn rotate(mut self, max_bytes: u64, max_files: usize) -> FileLoggerBuilder {
+        self.logger.rotation = Some(Rotation {
+            max_bytes,
+            max_files,
+        });
+        self
+    }
+
+    /// Keep lines in the buffer until it is full, rather than flushing each message. Off by
+    /// default, which keeps the log up to date with the messages sent.
+    pub fn buffered(mut self, buffered: bool) -> FileLoggerBuilder {
+        self.logger.buffered = buffered;
+        self
+    }
+
+    pub fn build(self) -> FileLogger {
+        self.logger
+    }
+}
+
+/// Formats `time` as an RFC 3339 UTC timestamp, to the second: `2024-01-01T00:00:00Z`.
+///
+/// The standard library only tells us how many seconds have passed since the Unix epoch, so the
+/// calendar date is worked out here, with Howard Hinnant’s `civil_from_days` algorithm. It counts
+/// in 400-year eras that start on 1 March, which puts the leap day at the end of each year.
+fn utc_timestamp(time: SystemTime) -> String {
+    let secs = time
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since_epoch| since_epoch.as_secs());
+    let (days, secs_of_day) = (secs / 86_400, secs % 86_400);
+
+    let days = days + 719_468; // days from 0000-03-01 to 1970-01-01
+    let era = days / 146_097; // days in 400 years
+    let day_of_era = days % 146_097;
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let march_based_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * march_based_month + 2) / 5 + 1;
+    let month = if march_based_month < 10 {
+        march_based_month + 3
+    } else {
+        march_based_month - 9
+    };
+    let year = era * 400 + year_of_era + u64::from(month <= 2);
+
+    format!(
+        "{year:04}-{month:02}-{day:02}T{:02}:{:02}:{:02}Z",
+        secs_of_day / 3_600,
+        secs_of_day % 3_600 / 60,
+        secs_of_day % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ir_core::utils::temp_dir::TempDir;
+    use std::{fs, time::Duration};
+
+    #[test]
+    fn it_formats_utc_timestamps() {
+        let at = |secs| utc_timestamp(UNIX_EPOCH + Duration::from_secs(secs));
+        assert_eq!(at(0), "1970-01-01T00:00:00Z");
+        assert_eq!(at(1_704_067_200), "2024-01-01T00:00:00Z");
+        assert_eq!(at(1_709_210_096), "2024-02-29T12:34:56Z");
+        assert_eq!(at(4_107_542_399), "2100-02-28T23:59:59Z");
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn it_appends_one_line_per_message() {
+        let temp = TempDir::new("append").unwrap();
+        let dir = temp.path();
+        let path = dir.join("app.log");
+        let logger = FileLogger::new(&path);
+        logger.try_send("started").unwrap();
+        logger.try_send_at(LogLevel::Error, "Error: out").unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "[INFO] started\n[ERROR] Error: out\n"
+        );
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn it_skips_messages_below_the_minimum_level() {
+        let temp = TempDir::new("min_level").unwrap();
+        let dir = temp.path();
+        let path = dir.join("app.log");
+        let logger = FileLogger::builder(&path).min_level(LogLevel::Warn).build();
+        logger.try_send("started").unwrap();
+        logger
+            .try_send_at(LogLevel::Warn, "Urgent warning: lower")
+            .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "[WARN] Urgent warning: lower\n"
+        );
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn the_level_is_the_senders_whatever_the_text_says() {
+        let temp = TempDir::new("level").unwrap();
+        let path = temp.path().join("app.log");
+        let logger = FileLogger::new(&path);
+        logger.try_send("Error: only a test").unwrap();
+        logger
+            .try_send_at(LogLevel::Error, "Galat: Anda sudah melebihi kuota Anda!")
+            .unwrap();
+
+        assert_eq!(
+            fs::read_to_string(&path).unwrap(),
+            "[INFO] Error: only a test\n[ERROR] Galat: Anda sudah melebihi kuota Anda!\n"
+        );
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn it_prefixes_a_timestamp_when_asked_to() {
+        let temp = TempDir::new("timestamps").unwrap();
+        let dir = temp.path();
+        let path = dir.join("app.log");
+        let logger = FileLogger::builder(&path).timestamps(true).build();
+        logger.try_send_at(LogLevel::Error, "Error: out").unwrap();
+
+        let line = fs::read_to_string(&path).unwrap();
+        // [2024-01-01T00:00:00Z][ERROR] Error: out
+        assert_eq!(&line[..1], "[");
+        assert_eq!(&line[11..12], "T");
+        assert_eq!(&line[20..], "Z][ERROR] Error: out\n");
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn it_rotates_the_log_and_discards_the_oldest() {
+        let temp = TempDir::new("rotation").unwrap();
+        let dir = temp.path();
+        let path = dir.join("app.log");
+        // every line, `[INFO] m0\n`, is 10 bytes long, so each file holds two
+        let logger = FileLogger::builder(&path).rotate(20, 2).build();
+        for i in 0..7 {
+            logger.try_send(&format!("m{i}")).unwrap();
+        }
+
+        let read = |n| fs::read_to_string(rotated(&path, n)).unwrap();
+        assert_eq!(read(0), "[INFO] m6\n");
+        assert_eq!(read(1), "[INFO] m4\n[INFO] m5\n");
+        assert_eq!(read(2), "[INFO] m2\n[INFO] m3\n");
+        assert!(!rotated(&path, 3).exists());
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn it_gives_a_line_longer_than_the_limit_a_file_of_its_own() {
+        let temp = TempDir::new("long_line").unwrap();
+        let dir = temp.path();
+        let path = dir.join("app.log");
+        let logger = FileLogger::builder(&path).rotate(5, 1).build();
+        logger.try_send("a long line").unwrap();
+        logger.try_send("another").unwrap();
+
+        assert_eq!(
+            fs::read_to_string(rotated(&path, 1)).unwrap(),
+            "[INFO] a long line\n"
+        );
+        assert_eq!(fs::read_to_string(&path).unwrap(), "[INFO] another\n");
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn it_can_rotate_without_keeping_old_logs() {
+        let temp = TempDir::new("no_old_logs").unwrap();
+        let dir = temp.path();
+        let path = dir.join("app.log");
+        let logger = FileLogger::builder(&path).rotate(10, 0).build();
+        logger.try_send("m0").unwrap();
+        logger.try_send("m1").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "[INFO] m1\n");
+        assert!(!rotated(&path, 1).exists());
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn a_buffered_log_is_written_when_the_logger_is_dropped() {
+        let temp = TempDir::new("buffered").unwrap();
+        let dir = temp.path();
+        let path = dir.join("app.log");
+        let logger = FileLogger::builder(&path).buffered(true).build();
+        logger.try_send("m0").unwrap();
+        logger.try_send("m1").unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "");
+
+        drop(logger);
+        assert_eq!(fs::read_to_string(&path).unwrap(), "[INFO] m0\n[INFO] m1\n");
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn a_buffered_log_is_flushed_before_it_is_rotated() {
+        let temp = TempDir::new("buffered_rotation").unwrap();
+        let dir = temp.path();
+        let path = dir.join("app.log");
+        let logger = FileLogger::builder(&path)
+            .buffered(true)
+            .rotate(20, 1)
+            .build();
+        for i in 0..3 {
+            logger.try_send(&format!("m{i}")).unwrap();
+        }
+        drop(logger);
+
+        let read = |n| fs::read_to_string(rotated(&path, n)).unwrap();
+        assert_eq!(read(1), "[INFO] m0\n[INFO] m1\n");
+        assert_eq!(read(0), "[INFO] m2\n");
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn it_fails_to_write_to_a_directory() {
+        let temp = TempDir::new("directory").unwrap();
+        let dir = temp.path();
+        assert!(FileLogger::new(dir).try_send("Error: out").is_err());
+    }
+}
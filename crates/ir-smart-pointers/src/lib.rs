@@ -0,0 +1,190 @@
+//! # Smart Pointers
+//! A _pointer_ is a general concept for a variable that contains an address in memory.
+//! This address refers to, or “points at,” some other data. The most common kind of
+//! pointer in Rust is a reference. References are indicated by the `&` symbol and borrow
+//! the value they point to. They don’t have any special capabilities other than referring
+//! to data, and have no overhead.
+//!
+//! _Smart pointers_, on the other hand, are data structures that act like a pointer but
+//! also have additional metadata and capabilities. The concept of smart pointers isn’t
+//! unique to Rust: smart pointers originated in C++ and exist in other languages as well.
+//! Rust has a variety of smart pointers defined in the standard library that provide
+//! functionality beyond that provided by references. To explore the general concept,
+//! we’ll look at a couple of different examples of smart pointers, including a _reference
+//! counting_ smart pointer type. This pointer enables you to allow data to have multiple
+//! owners by keeping track of the number of owners and, when no owners remain, cleaning
+//! up the data.
+//!
+//! Rust, with its concept of ownership and borrowing,
+//! has an additional difference between references and smart pointers:
+//! while references only borrow data, in many cases,
+//! smart pointers own the data they point to.
+//!
+//! Smart pointers are usually implemented using structs. Unlike an ordinary struct,
+//! smart pointers implement the `Deref` and `Drop` traits:
+//! - The `Deref` trait allows an instance of the smart pointer struct to behave like a
+//!   reference so you can write your code to work with either references or smart pointers.
+//! - The `Drop` trait allows you to customize the code that’s run when an instance of the
+//!   smart pointer goes out of scope.
+
+// the lessons print with `ir_core`’s `println!`, and name their parts with `header!`
+#[macro_use]
+extern crate ir_core;
+
+use ir_core::{
+    lesson::{Chapter, Demo, Difficulty, Lesson, LessonCtx, LessonInfo, Registry},
+    output, random,
+};
+use std::io::Result;
+
+// the lessons whose types the memory chapter measures are public. The lessons write each part
+// as `Ok({ ... })`, and spell out some closures’ `-> ()`, which clippy would rewrite
+#[allow(clippy::unit_arg, clippy::unused_unit)]
+pub mod box_t;
+pub(crate) mod count_tracer;
+pub(crate) mod debug_ref_cell;
+pub(crate) mod file_logger;
+pub(crate) mod messengers;
+pub mod rc_registry;
+#[allow(clippy::unit_arg, clippy::unused_unit)]
+pub mod rc_t;
+#[allow(clippy::unit_arg, clippy::unused_unit)]
+pub mod ref_cell_t;
+#[allow(clippy::unit_arg)]
+pub mod traits;
+pub mod viz;
+
+// the types the lessons build, for other code to use
+pub use box_t::List;
+pub use count_tracer::{CountTracer, Snapshot};
+pub use debug_ref_cell::{BorrowConflict, DebugRef, DebugRefCell, DebugRefMut};
+pub use file_logger::{FileLogger, FileLoggerBuilder, LogLevel};
+pub use messengers::{
+    BufferedMessenger, ChannelMessenger, FallbackMessenger, MulticastMessenger, SendErrors,
+    StdoutMessenger, TcpMessenger,
+};
+pub use ref_cell_t::{
+    LimitTracker, Messenger, Quota, QuotaEvent, TraversalOrder, TreeDiff, TreeNode,
+};
+pub use traits::{deref::MyBox, drop::CustomSmartPointer};
+
+/// Here is a recap of the reasons to choose `Box<T>`, `Rc<T>`, or `RefCell<T>`:
+/// 1. `Rc<T>`              enables multiple owners of the same data;
+///    `Box<T>` and `RefCell<T>` have single owners.
+/// 2. `Box<T>`     allows      immutable or mutable borrows checked at compile time;
+///    `Rc<T>`      allows only immutable            borrows checked at compile time;
+///    `RefCell<T>` allows      immutable or mutable borrows checked at      runtime.
+/// 3. Because `RefCell<T>`     allows       mutable borrows checked at      runtime,
+///    you can mutate the value inside the `RefCell<T>` even when the `RefCell<T>`
+///    is immutable.
+pub fn register(registry: &mut Registry) {
+    let lessons = [
+        Demo {
+            id: "smart-pointers/drop",
+            title: "Running code on cleanup with the `Drop` trait",
+            run: traits::drop::drop_trait,
+            info: LessonInfo {
+                description: "Customizing what happens when a value goes out of scope",
+                difficulty: Difficulty::Beginner,
+                prerequisites: &[],
+                tags: &["smart-pointers", "traits"],
+                source: include_str!("traits/drop.rs"),
+            },
+        },
+        Demo {
+            id: "smart-pointers/drop-order",
+            title: "Fields, temporaries, `mem::drop`, `ManuallyDrop` and `mem::forget`",
+            run: traits::drop_order::drop_order,
+            info: LessonInfo {
+                description: "The order values are dropped in, and how to drop them earlier, or never",
+                difficulty: Difficulty::Intermediate,
+                prerequisites: &["smart-pointers/drop"],
+                tags: &["smart-pointers", "ownership"],
+                source: include_str!("traits/drop_order.rs"),
+            },
+        },
+        Demo {
+            id: "smart-pointers/deref",
+            title: "Treating smart pointers like regular references with `Deref`",
+            run: traits::deref::deref_trait,
+            info: LessonInfo {
+                description: "`Deref`, `DerefMut` and deref coercion, on a `MyBox<T>` of our own",
+                difficulty: Difficulty::Beginner,
+                prerequisites: &[],
+                tags: &["smart-pointers", "traits"],
+                source: include_str!("traits/deref.rs"),
+            },
+        },
+        Demo {
+            id: "smart-pointers/box",
+            title: "`Box<T>` for allocating values on the heap",
+            run: box_t::box_t,
+            info: LessonInfo {
+                description: "Recursive types like the cons list, which only have a known size behind a box",
+                difficulty: Difficulty::Beginner,
+                prerequisites: &["smart-pointers/deref"],
+                tags: &["smart-pointers"],
+                source: include_str!("box_t.rs"),
+            },
+        },
+        Demo {
+            id: "smart-pointers/rc",
+            title: "`Rc<T>`, a reference counting type that enables multiple ownership",
+            run: rc_t::rc_t,
+            info: LessonInfo {
+                description: "Sharing a cons list between owners, and watching the reference count change",
+                difficulty: Difficulty::Intermediate,
+                prerequisites: &["smart-pointers/box"],
+                tags: &["smart-pointers", "ownership"],
+                source: include_str!("rc_t.rs"),
+            },
+        },
+        Demo {
+            id: "smart-pointers/ref-cell",
+            title: "`RefCell<T>` and the interior mutability pattern",
+            run: ref_cell_t::ref_cell_t,
+            info: LessonInfo {
+                description: "Mutating through a shared reference, a mock messenger, and reference cycles with `Weak<T>`",
+                difficulty: Difficulty::Intermediate,
+                prerequisites: &["smart-pointers/rc"],
+                tags: &["smart-pointers", "interior-mutability"],
+                source: include_str!("ref_cell_t.rs"),
+            },
+        },
+    ];
+    for lesson in lessons {
+        registry.register(Watched(lesson));
+    }
+    registry.set_default("smart-pointers/ref-cell");
+}
+
+// how `Registry::discover` finds the chapter, without being told about this crate
+ir_core::inventory::submit! {
+    Chapter {
+        topic: "smart-pointers",
+        register,
+    }
+}
+
+/// A lesson run by `rc_registry::watch`, which reports the `Rc`s it leaked.
+struct Watched(Demo);
+impl Lesson for Watched {
+    fn id(&self) -> &str {
+        self.0.id()
+    }
+
+    fn title(&self) -> &str {
+        self.0.title()
+    }
+
+    fn run(&self, ctx: &mut LessonCtx) -> Result<()> {
+        random::with_rng(&mut ctx.rng, || {
+            output::redirect(&mut ctx.output, || rc_registry::watch(self.0.run))
+        })
+        .map(|_leaks| ())
+    }
+
+    fn info(&self) -> LessonInfo {
+        self.0.info()
+    }
+}
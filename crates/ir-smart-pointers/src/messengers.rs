@@ -0,0 +1,440 @@
+//! # More Places to Send a `LimitTracker`’s Messages
+//! `Messenger::send` takes `&self`, so every implementation that changes something when sending
+//! has to get its mutability from somewhere other than the receiver. `MockMessenger` gets it from
+//! a `RefCell`. The backends here show that `RefCell` is only one option: a `Sender` and a
+//! `TcpStream` can both be used through a shared reference already, because they take care of
+//! their own synchronization.
+//!
+//! Like `FileLogger`, these backends can’t return an error from `send`, so they log failures
+//! instead, as errors if a message was lost and as warnings if it wasn’t; `try_send` returns
+//! them. `MulticastMessenger` relies on that to keep going when one of the messengers it
+//! forwards to fails.
+
+use super::{file_logger::LogLevel, ref_cell_t::Messenger};
+use ir_core::utils::retry::retry;
+use std::{
+    cell::RefCell,
+    error::Error,
+    fmt::{self, Display},
+    io::{self, ErrorKind, Result, Write},
+    mem,
+    net::{TcpStream, ToSocketAddrs},
+    sync::mpsc::{self, Receiver, Sender},
+    time::Duration,
+};
+
+/// Prints each message on its own line.
+pub struct StdoutMessenger;
+impl Messenger for StdoutMessenger {
+    fn send(&self, msg: &str) {
+        println!("{msg}");
+    }
+}
+
+/// Sends each message down an mpsc channel, to be received by another thread.
+pub struct ChannelMessenger {
+    sender: Sender<String>,
+}
+impl ChannelMessenger {
+    /// The messenger and the receiving end of its channel. The channel is unbounded, so `send`
+    /// never blocks; the messages wait in the channel until they are received.
+    pub fn new() -> (ChannelMessenger, Receiver<String>) {
+        let (sender, receiver) = mpsc::channel();
+        (ChannelMessenger { sender }, receiver)
+    }
+}
+impl Messenger for ChannelMessenger {
+    // `Sender::send` takes `&self`: a channel is shared mutable state with the locking built in
+    fn send(&self, msg: &str) {
+        if let Err(x) = self.try_send(msg) {
+            log::error!("{x}, discarding: {msg}");
+        }
+    }
+
+    fn try_send(&self, msg: &str) -> Result<()> {
+        self.sender
+            .send(msg.to_string())
+            .map_err(|_| io::Error::new(ErrorKind::BrokenPipe, "the receiver was dropped"))
+    }
+}
+
+/// Writes each message to a TCP connection, one message per line.
+pub struct TcpMessenger {
+    stream: TcpStream,
+}
+impl TcpMessenger {
+    /// Connects to the server at `addr`, which gets each message as a line until the messenger
+    /// is dropped, and the connection with it.
+    pub fn connect(addr: impl ToSocketAddrs) -> Result<TcpMessenger> {
+        Ok(TcpMessenger {
+            stream: TcpStream::connect(addr)?,
+        })
+    }
+}
+impl Messenger for TcpMessenger {
+    // `Write` is implemented for `&TcpStream` as well as for `TcpStream`: writing to a socket
+    // only needs the file descriptor, which the OS synchronizes, so a shared reference is enough
+    fn send(&self, msg: &str) {
+        if let Err(x) = self.try_send(msg) {
+            log::warn!("{x}, printing instead");
+            println!("{msg}");
+        }
+    }
+
+    fn try_send(&self, msg: &str) -> Result<()> {
+        writeln!(&self.stream, "{msg}")
+    }
+}
+
+/// Forwards each message to every messenger it holds, in order.
+///
+/// The messengers can be of different types, so they are held as trait objects: a
+/// `Box<dyn Messenger>` is a pointer to the messenger plus a pointer to its type’s `Messenger`
+/// vtable, which is how `send` finds the right implementation at runtime.
+pub struct MulticastMessenger(pub Vec<Box<dyn Messenger>>);
+impl Messenger for MulticastMessenger {
+    fn send(&self, msg: &str) {
+        self.send_at(LogLevel::Info, msg);
+    }
+
+    fn try_send(&self, msg: &str) -> Result<()> {
+        self.try_send_at(LogLevel::Info, msg)
+    }
+
+    fn send_at(&self, level: LogLevel, msg: &str) {
+        if let Err(x) = self.try_send_at(level, msg) {
+            log::error!("{x}");
+        }
+    }
+
+    /// Sends to every messenger, even after one of them fails, and then fails with
+    /// `SendErrors` if any of them did.
+    fn try_send_at(&self, level: LogLevel, msg: &str) -> Result<()> {
+        let failures: Vec<_> = self
+            .0
+            .iter()
+            .enumerate()
+            .filter_map(|(index, messenger)| {
+                let sent = messenger.try_send_at(level, msg);
+                sent.err().map(|x| (index, x))
+            })
+            .collect();
+        match failures.is_empty() {
+            true => Ok(()),
+            false => Err(io::Error::other(SendErrors(failures))),
+        }
+    }
+}
+
+/// Which of a `MulticastMessenger`’s messengers failed, by index, and why.
+#[derive(Debug)]
+pub struct SendErrors(pub Vec<(usize, io::Error)>);
+impl Display for SendErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} messenger(s) failed:", self.0.len())?;
+        for (index, x) in &self.0 {
+            write!(f, "\n  #{index}: {x}")?;
+        }
+        Ok(())
+    }
+}
+impl Error for SendErrors {}
+
+/// Holds messages back until `threshold` of them have piled up, then sends them to the inner
+/// messenger all at once. Whatever is still held back when the `BufferedMessenger` is dropped
+/// is sent then, so no message is lost by forgetting to `flush`.
+///
+/// `send` only gets `&self`, so the buffer lives in a `RefCell`, just like `MockMessenger`’s
+/// `sent_messages`.
+pub struct BufferedMessenger<M: Messenger> {
+    inner: M,
+    buffer: RefCell<Vec<(LogLevel, String)>>,
+    threshold: usize,
+}
+impl<M: Messenger> BufferedMessenger<M> {
+    pub fn new(inner: M, threshold: usize) -> BufferedMessenger<M> {
+        BufferedMessenger {
+            inner,
+            buffer: RefCell::new(Vec::new()),
+            threshold,
+        }
+    }
+
+    /// Sends every buffered message, oldest first. If the inner messenger fails, the message
+    /// it failed on and the ones after it stay buffered for the next `flush`.
+    pub fn flush(&self) -> Result<()> {
+        // the buffer is moved out rather than borrowed while sending, so that nothing the inner
+        // messenger does can run into an outstanding `borrow_mut`
+        let pending = mem::take(&mut *self.buffer.borrow_mut());
+        for (sent, (level, msg)) in pending.iter().enumerate() {
+            if let Err(x) = self.inner.try_send_at(*level, msg) {
+                let mut buffer = self.buffer.borrow_mut();
+                buffer.splice(0..0, pending.into_iter().skip(sent));
+                return Err(x);
+            }
+        }
+        Ok(())
+    }
+}
+impl<M: Messenger> Messenger for BufferedMessenger<M> {
+    fn send(&self, msg: &str) {
+        self.send_at(LogLevel::Info, msg);
+    }
+
+    fn try_send(&self, msg: &str) -> Result<()> {
+        self.try_send_at(LogLevel::Info, msg)
+    }
+
+    // the message stays buffered, to be sent with the next `flush`
+    fn send_at(&self, level: LogLevel, msg: &str) {
+        if let Err(x) = self.try_send_at(level, msg) {
+            log::warn!("{x}, keeping it buffered");
+        }
+    }
+
+    /// Keeps the message’s level with it, for the inner messenger to get both when it is sent.
+    fn try_send_at(&self, level: LogLevel, msg: &str) -> Result<()> {
+        let buffered = {
+            let mut buffer = self.buffer.borrow_mut();
+            buffer.push((level, msg.to_string()));
+            buffer.len()
+        };
+        match buffered >= self.threshold {
+            true => self.flush(),
+            false => Ok(()),
+        }
+    }
+}
+impl<M: Messenger> Drop for BufferedMessenger<M> {
+    // `drop` can’t return an error, so this is the last chance to at least report one
+    fn drop(&mut self) {
+        if let Err(x) = self.flush() {
+            log::error!("{x}, lost {} message(s)", self.buffer.borrow().len());
+        }
+    }
+}
+
+/// Sends to `primary`, retrying with backoff when it fails, and to `secondary` if it never
+/// succeeds. `FileLogger::send` does a fixed version of this, printing what it couldn’t log;
+/// `FallbackMessenger::new(logger, StdoutMessenger)` does the same with retries, and with any
+/// pair of messengers.
+pub struct FallbackMessenger<P: Messenger, S: Messenger> {
+    primary: P,
+    secondary: S,
+    attempts: usize,
+    backoff: Duration,
+}
+impl<P: Messenger, S: Messenger> FallbackMessenger<P, S> {
+    /// Gives `primary` a single attempt; see `retries`.
+    pub fn new(primary: P, secondary: S) -> FallbackMessenger<P, S> {
+        FallbackMessenger {
+            primary,
+            secondary,
+            attempts: 1,
+            backoff: Duration::ZERO,
+        }
+    }
+
+    /// Retry `primary` up to `retries` times before falling back, waiting `backoff` before the
+    /// first retry and twice as long before each one after it.
+    pub fn retries(mut self, retries: usize, backoff: Duration) -> FallbackMessenger<P, S> {
+        self.attempts = retries + 1;
+        self.backoff = backoff;
+        self
+    }
+}
+impl<P: Messenger, S: Messenger> Messenger for FallbackMessenger<P, S> {
+    fn send(&self, msg: &str) {
+        self.send_at(LogLevel::Info, msg);
+    }
+
+    fn try_send(&self, msg: &str) -> Result<()> {
+        self.try_send_at(LogLevel::Info, msg)
+    }
+
+    fn send_at(&self, level: LogLevel, msg: &str) {
+        if let Err(x) = self.try_send_at(level, msg) {
+            log::error!("{x}");
+        }
+    }
+
+    /// Fails only if `secondary` fails too, with `secondary`’s error.
+    fn try_send_at(&self, level: LogLevel, msg: &str) -> Result<()> {
+        let primary = || self.primary.try_send_at(level, msg);
+        retry(self.attempts, self.backoff, primary).or_else(|x| {
+            log::warn!("{x}, falling back");
+            self.secondary.try_send_at(level, msg)
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        cell::Cell,
+        io::{BufRead, BufReader},
+        net::TcpListener,
+        thread,
+    };
+
+    #[test]
+    fn a_channel_messenger_can_be_received_from_another_thread() {
+        let (messenger, receiver) = ChannelMessenger::new();
+        let received = thread::spawn(move || receiver.iter().collect::<Vec<_>>());
+
+        messenger.send("one");
+        messenger.send("two");
+        drop(messenger); // closes the channel, which ends `receiver.iter()`
+
+        assert_eq!(received.join().unwrap(), ["one", "two"]);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)] // Miri has no network
+    fn a_tcp_messenger_writes_one_line_per_message() -> Result<()> {
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        let received = thread::spawn(move || -> Result<Vec<String>> {
+            let (stream, _) = listener.accept()?;
+            BufReader::new(stream).lines().collect()
+        });
+
+        let messenger = TcpMessenger::connect(addr)?;
+        messenger.send("one");
+        messenger.send("two");
+        drop(messenger); // closes the connection, which ends `lines()`
+
+        assert_eq!(received.join().unwrap()?, ["one", "two"]);
+        Ok(())
+    }
+
+    #[test]
+    fn a_multicast_messenger_keeps_going_after_a_failure() {
+        let (first, first_receiver) = ChannelMessenger::new();
+        let (broken, broken_receiver) = ChannelMessenger::new();
+        let (last, last_receiver) = ChannelMessenger::new();
+        drop(broken_receiver);
+        let multicast = MulticastMessenger(vec![Box::new(first), Box::new(broken), Box::new(last)]);
+
+        let x = multicast.try_send("hello").unwrap_err();
+
+        assert_eq!(first_receiver.try_recv().unwrap(), "hello");
+        assert_eq!(last_receiver.try_recv().unwrap(), "hello");
+        let SendErrors(failures) = x.get_ref().unwrap().downcast_ref().unwrap();
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].0, 1);
+        assert_eq!(failures[0].1.kind(), ErrorKind::BrokenPipe);
+        assert_eq!(
+            x.to_string(),
+            "1 messenger(s) failed:\n  #1: the receiver was dropped"
+        );
+    }
+
+    #[test]
+    fn a_buffered_messenger_flushes_when_the_threshold_is_reached() {
+        let (channel, receiver) = ChannelMessenger::new();
+        let buffered = BufferedMessenger::new(channel, 2);
+
+        buffered.send("one");
+        assert!(receiver.try_recv().is_err());
+        buffered.send("two");
+
+        assert_eq!(receiver.try_iter().collect::<Vec<_>>(), ["one", "two"]);
+        assert!(buffered.buffer.borrow().is_empty());
+    }
+
+    #[test]
+    fn a_buffered_messenger_flushes_when_dropped() {
+        let (channel, receiver) = ChannelMessenger::new();
+        let buffered = BufferedMessenger::new(channel, 10);
+        buffered.send("one");
+        buffered.send("two");
+        assert!(receiver.try_recv().is_err());
+
+        drop(buffered);
+
+        assert_eq!(receiver.iter().collect::<Vec<_>>(), ["one", "two"]);
+    }
+
+    #[test]
+    fn a_failed_flush_keeps_the_unsent_messages() {
+        let (channel, receiver) = ChannelMessenger::new();
+        drop(receiver);
+        let buffered = BufferedMessenger::new(channel, 10);
+        buffered.send("one");
+        buffered.send("two");
+
+        assert!(buffered.flush().is_err());
+        let buffer = buffered.buffer.borrow();
+        let held: Vec<_> = buffer.iter().map(|(_, msg)| msg.as_str()).collect();
+        assert_eq!(held, ["one", "two"]);
+    }
+
+    /// Fails its first `failures` messages, then records the rest.
+    struct FlakyMessenger {
+        failures: Cell<usize>,
+        sent: RefCell<Vec<String>>,
+    }
+    impl FlakyMessenger {
+        fn new(failures: usize) -> FlakyMessenger {
+            FlakyMessenger {
+                failures: Cell::new(failures),
+                sent: RefCell::new(Vec::new()),
+            }
+        }
+    }
+    impl Messenger for FlakyMessenger {
+        fn send(&self, msg: &str) {
+            let _ = self.try_send(msg);
+        }
+
+        fn try_send(&self, msg: &str) -> Result<()> {
+            match self.failures.get() {
+                0 => {
+                    self.sent.borrow_mut().push(msg.to_string());
+                    Ok(())
+                }
+                failures => {
+                    self.failures.set(failures - 1);
+                    Err(io::Error::other("injected failure"))
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn a_fallback_messenger_retries_the_primary_first() {
+        let fallback = FallbackMessenger::new(FlakyMessenger::new(2), FlakyMessenger::new(0))
+            .retries(2, Duration::ZERO);
+
+        fallback.try_send("hello").unwrap();
+
+        assert_eq!(*fallback.primary.sent.borrow(), ["hello"]);
+        assert!(fallback.secondary.sent.borrow().is_empty());
+    }
+
+    #[test]
+    fn a_fallback_messenger_falls_back_once_out_of_retries() {
+        let fallback = FallbackMessenger::new(FlakyMessenger::new(3), FlakyMessenger::new(0))
+            .retries(2, Duration::ZERO);
+
+        fallback.try_send("hello").unwrap();
+        fallback.try_send("again").unwrap(); // the primary has recovered by now
+
+        assert_eq!(*fallback.primary.sent.borrow(), ["again"]);
+        assert_eq!(*fallback.secondary.sent.borrow(), ["hello"]);
+    }
+
+    #[test]
+    fn a_fallback_messenger_fails_when_both_fail() {
+        let fallback = FallbackMessenger::new(FlakyMessenger::new(1), FlakyMessenger::new(1));
+        assert!(fallback.try_send("hello").is_err());
+    }
+
+    #[test]
+    fn a_multicast_messenger_with_no_failures_succeeds() {
+        let multicast = MulticastMessenger(vec![Box::new(StdoutMessenger)]);
+        assert!(multicast.try_send("hello").is_ok());
+    }
+}
@@ -0,0 +1,179 @@
+//! # Detecting Leaked `Rc<T>`s with `Weak<T>` Probes
+//! The reference cycle lesson shows that a leak is invisible from the inside: every node in the
+//! cycle still has a strong count of 1, just as a node that is legitimately in use would. What
+//! gives the leak away is _when_ we look. Once a demo function has returned, all of its local
+//! variables are gone, so any `Rc` it created that still has owners must be owned by something
+//! that can no longer be reached.
+//!
+//! To look at an allocation after its owners are gone we need a handle that doesn’t own it, which
+//! is exactly what a `Weak<T>` is. `track` keeps a labelled `Weak` probe for an `Rc`, and `watch`
+//! runs a demo and then reports every probe whose strong count is still above zero.
+//!
+//! A probe keeps the allocation itself (though not the value) around until the probe is dropped,
+//! so `watch` forgets the probes of each demo once it has reported on them.
+
+use ir_core::{
+    output::{self, Verbosity},
+    style::{paint, Style},
+};
+use std::{cell::RefCell, io::Result, rc::Rc};
+
+struct Probe {
+    label: String,
+    strong_count: Box<dyn Fn() -> usize>,
+}
+
+thread_local! {
+    // `Rc<T>` is single threaded, so a registry per thread sees every `Rc` it needs to
+    static PROBES: RefCell<Vec<Probe>> = const { RefCell::new(Vec::new()) };
+}
+
+/// An `Rc` that still had owners after the demo that created it returned.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Leak {
+    pub label: String,
+    pub strong_count: usize,
+}
+
+/// Registers a `Weak` probe for `rc` under `label`.
+///
+/// The probe is stored as a closure rather than as a `Weak<T>`, so that probes for
+/// `Rc`s of different types can live in one `Vec`.
+pub fn track<T: 'static>(label: &str, rc: &Rc<T>) {
+    let weak = Rc::downgrade(rc);
+    PROBES.with_borrow_mut(|probes| {
+        probes.push(Probe {
+            label: label.to_string(),
+            strong_count: Box::new(move || weak.strong_count()),
+        })
+    });
+}
+
+/// `Rc::new`, plus `track`.
+pub fn tracked<T: 'static>(label: &str, value: T) -> Rc<T> {
+    let rc = Rc::new(value);
+    track(label, &rc);
+    rc
+}
+
+/// Every `Rc` tracked after the first `start` probes that currently has owners.
+fn leaks_since(start: usize) -> Vec<Leak> {
+    PROBES.with_borrow(|probes| {
+        probes
+            .iter()
+            .skip(start)
+            .filter_map(|probe| match (probe.strong_count)() {
+                0 => None,
+                strong_count => Some(Leak {
+                    label: probe.label.clone(),
+                    strong_count,
+                }),
+            })
+            .collect()
+    })
+}
+
+/// Runs `demo`, then reports the `Rc`s it tracked that outlived it, and returns them. With
+/// `-v`, it also says so when there were none.
+pub fn watch(demo: impl FnOnce() -> Result<()>) -> Result<Vec<Leak>> {
+    let start = PROBES.with_borrow(|probes| probes.len());
+    let result = demo();
+    let leaks = leaks_since(start);
+    PROBES.with_borrow_mut(|probes| probes.truncate(start));
+    result?;
+
+    if leaks.is_empty() && output::verbosity() >= Verbosity::Verbose {
+        println!("Leak check: every tracked Rc was dropped");
+    } else if !leaks.is_empty() {
+        println!(
+            "{}",
+            paint(
+                Style::Warning,
+                format_args!(
+                    "Leak check: {} tracked Rc(s) outlived the demo",
+                    leaks.len()
+                )
+            )
+        );
+        for leak in &leaks {
+            println!("  {} (strong = {})", leak.label, leak.strong_count);
+        }
+    }
+    Ok(leaks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Node {
+        next: RefCell<Option<Rc<Node>>>,
+    }
+
+    #[test]
+    fn it_reports_nothing_when_everything_is_dropped() {
+        let leaks = watch(|| {
+            let a = tracked("a", 1);
+            let _b = tracked("b", Rc::clone(&a));
+            Ok(())
+        })
+        .unwrap();
+
+        assert!(leaks.is_empty());
+    }
+
+    #[test]
+    // the cycle is leaked on purpose, and Miri reports leaks as errors
+    #[cfg_attr(miri, ignore)]
+    fn it_reports_a_reference_cycle() {
+        let leaks = watch(|| {
+            let a = tracked(
+                "a",
+                Node {
+                    next: RefCell::new(None),
+                },
+            );
+            let b = tracked(
+                "b",
+                Node {
+                    next: RefCell::new(Some(Rc::clone(&a))),
+                },
+            );
+            *a.next.borrow_mut() = Some(Rc::clone(&b));
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(
+            leaks,
+            vec![
+                Leak {
+                    label: "a".to_string(),
+                    strong_count: 1
+                },
+                Leak {
+                    label: "b".to_string(),
+                    strong_count: 1
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn it_forgets_the_probes_of_a_finished_demo() {
+        let outer = tracked("outer", 0);
+        watch(|| {
+            track("inner", &outer);
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(
+            leaks_since(0),
+            vec![Leak {
+                label: "outer".to_string(),
+                strong_count: 1
+            }]
+        );
+    }
+}
@@ -0,0 +1,295 @@
+//! `Rc<T>`, the Reference Counted Smart Pointer
+//! In the majority of cases, ownership is clear: you know exactly which variable owns a
+//! given value. However, there are cases when a single value might have multiple owners.
+//! For example, in graph data structures, multiple edges might point to the same node,
+//! and that node is conceptually owned by all of the edges that point to it. A node shouldn’t
+//! be cleaned up unless it doesn’t have any edges pointing to it and so has no owners.
+//!
+//! You have to enable multiple ownership explicitly by using the Rust type `Rc<T>`,
+//! which is an abbreviation for _reference counting_. The `Rc<T>` type keeps track of
+//! the number of references to a value to determine whether or not the value is still
+//! in use. If there are zero references to a value, the value can be cleaned up without
+//! any references becoming invalid.
+//!
+//! Imagine `Rc<T>` as a TV in a family room. When one person enters to watch TV,
+//! they turn it on. Others can come into the room and watch the TV. When the last
+//! person leaves the room, they turn off the TV because it’s no longer being used.
+//! If someone turns off the TV while others are still watching it, there would be
+//! uproar from the remaining TV watchers!
+//!
+//! We use the `Rc<T>` type when we want to allocate some data on the
+//! heap for multiple parts of our program to read and we can’t determine
+//! at compile time which part will finish using the data last. If we knew
+//! which part would finish last, we could just make that part the data’s owner,
+//! and the normal ownership rules enforced at compile time would take effect.
+//!
+//! Note that `Rc<T>` is only for use in single-threaded scenarios. `Arc<T>`, which can be shared
+//! between threads, updates its counts with atomic instructions instead, which cost more:
+//! `cargo bench --features bench -- shared_clone` compares the two.
+
+use super::{
+    count_tracer::CountTracer,
+    viz::{self, Graph},
+};
+use ir_core::metrics;
+use std::{
+    fmt::{self, Display},
+    io::Result,
+    rc::Rc,
+};
+
+pub fn rc_t() -> Result<()> {
+    Ok({
+        using_rc_t_to_share_data()?;
+        appending_by_sharing_the_tail()?;
+        counting_the_cost_of_a_clone()?;
+    })
+}
+
+#[derive(Debug)]
+pub enum List<T> {
+    Cons(T, Rc<List<T>>),
+    Nil,
+}
+impl<T> List<T> {
+    pub fn for_each(&self, f: impl Fn(&T) -> ()) {
+        use List::*;
+
+        if let Cons(t, next) = self {
+            f(t);
+            Self::for_each(next, f);
+        }
+    }
+}
+impl<T: Display> Display for List<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut node = self;
+        while let List::Cons(value, next) = node {
+            write!(f, "{value} -> ")?;
+            node = next;
+        }
+        write!(f, "Nil")
+    }
+}
+/// Each node is created with a strong count of 1, owned only by the node in front of it.
+impl<T> From<Vec<T>> for List<T> {
+    fn from(values: Vec<T>) -> List<T> {
+        values
+            .into_iter()
+            .rev()
+            .fold(List::Nil, |list, value| List::Cons(value, Rc::new(list)))
+    }
+}
+impl<T: Clone> List<T> {
+    /// Returns `front` followed by `back`, without touching either. The nodes of `back` are
+    /// shared, not copied: the new list ends in an `Rc::clone` of it. The nodes of `front` do
+    /// have to be copied, because the last of them must now point at `back` instead of `Nil`,
+    /// and a node that others may still be sharing can’t be changed.
+    pub fn append(front: &Rc<List<T>>, back: &Rc<List<T>>) -> Rc<List<T>> {
+        let mut values = vec![];
+        let mut node = &**front;
+        while let List::Cons(value, next) = node {
+            values.push(value.clone());
+            node = next;
+        }
+        values
+            .into_iter()
+            .rev()
+            .fold(Rc::clone(back), |list, value| {
+                Rc::new(List::Cons(value, list))
+            })
+    }
+}
+fn using_rc_t_to_share_data() -> Result<()> {
+    Ok({
+        use List::*;
+
+        println!("String");
+        {
+            let s = Rc::new(List::from(vec![
+                String::from("hello"),
+                String::from("world"),
+            ]));
+            s.for_each(|s| println!("{s}"));
+        }
+
+        let mut tracer = CountTracer::new("a");
+        let a = Rc::new(List::from(vec![5, 10]));
+        tracer.snapshot("after creating `a`", &a);
+
+        let b = Rc::new(Cons(3, Rc::clone(&a)));
+        tracer.snapshot("after creating `b`", &a);
+
+        {
+            let c = Rc::new(Cons(4, Rc::clone(&a)));
+            // We could have called `a.clone()` rather than `Rc::clone(&a)`,
+            // but Rust’s convention is to use `Rc::clone` in this case.
+            // The implementation of `Rc::clone` doesn’t make a deep copy
+            // of all the data like most types’ implementations of `clone`
+            // do. The call `to Rc::clone` only increments the reference count,
+            // which doesn’t take much time. Deep copies of data can take a lot
+            // of time. By using `Rc::clone` for reference counting, we can visually
+            // distinguish between the deep-copy kinds of clones and the kinds of
+            // clones that increase the reference count. When looking for performance
+            // problems in the code, we only need to consider the deep-copy clones and
+            // can disregard calls to `Rc::clone`.
+            tracer.snapshot("after creating `c`", &a);
+            viz::show(
+                Graph::new("`b` and `c` share `a`")
+                    .var("a", &a)
+                    .var("b", &b)
+                    .var("c", &c),
+            );
+
+            a.for_each(|i| println!("{i}"));
+            println!("a = {a}");
+            b.for_each(|i| println!("{i}"));
+            println!("b = {b}");
+            c.for_each(|i| println!("{i}"));
+            println!("c = {c}");
+
+            println!("do this twice to make sure nothing was moved");
+            a.for_each(|i| println!("{i}"));
+            println!("a = {a}");
+            b.for_each(|i| println!("{i}"));
+            println!("b = {b}");
+            c.for_each(|i| println!("{i}"));
+            println!("c = {c}");
+        }
+        tracer.snapshot("after `c` goes out of scope", &a);
+        print!("{}", tracer.chart());
+        // `a`, # strong, - weak
+        // after creating `a`           #    1 0
+        // after creating `b`           ##   2 0
+        // after creating `c`           ###  3 0
+        // after `c` goes out of scope  ##   2 0
+    })
+    // We can see that the `Rc<List>` in a has an initial reference count of 1; then each time we call clone,
+    // the count goes up by 1. When `c` goes out of scope, the count goes down by 1. We don’t have to call a
+    // function to decrease the reference count like we have to call `Rc::clone` to increase the reference count:
+    // the implementation of the `Drop` trait decreases the reference count automatically when an `Rc<T>`
+    // value goes out of scope.
+}
+/// # Appending by Sharing the Tail
+/// `b` and `c` above were built by putting a new head in front of `a`. `List::append` goes one
+/// step further and puts a whole list in front of `a`: the copied front ends in a new `Rc`
+/// pointing at `a`, so `a`’s count goes up by one and its nodes are never duplicated.
+///
+/// ```text
+/// front  = 1 -> 2 -> Nil
+/// joined = 1 -> 2 -> (a)
+/// a      =           5 -> 10 -> Nil
+/// ```
+fn appending_by_sharing_the_tail() -> Result<()> {
+    Ok({
+        span!("appending_by_sharing_the_tail");
+        header!("Appending by Sharing the Tail");
+
+        let (mut a_tracer, mut front_tracer) = (CountTracer::new("a"), CountTracer::new("front"));
+        let a = Rc::new(List::from(vec![5, 10]));
+        let front = Rc::new(List::from(vec![1, 2]));
+        a_tracer.snapshot("before appending", &a);
+        front_tracer.snapshot("before appending", &front);
+
+        let joined = List::append(&front, &a);
+        println!("joined = {joined}"); // joined = 1 -> 2 -> 5 -> 10 -> Nil
+        a_tracer.snapshot("after appending", &a);
+        front_tracer.snapshot("after appending", &front); // still 1: its values were cloned instead
+        viz::show(
+            Graph::new("`joined` copies `front` and shares `a`")
+                .var("a", &a)
+                .var("front", &front)
+                .var("joined", &joined),
+        );
+
+        drop(joined);
+        a_tracer.snapshot("after dropping `joined`", &a);
+        print!("{}{}", a_tracer.table(), front_tracer.table());
+        // `a`                      strong  weak
+        // before appending              1     0
+        // after appending               2     0
+        // after dropping `joined`       1     0
+        // `front`           strong  weak
+        // before appending       1     0
+        // after appending        1     0
+    })
+}
+
+/// # Counting the Cost of a Clone
+/// `Rc::clone` only increments the reference count, while a deep clone copies all of the data,
+/// as the comment in `using_rc_t_to_share_data` says. `metrics` puts numbers on it: it counts
+/// each kind of clone, and with the `track-alloc` feature the bytes they allocate. Cloning the
+/// `Vec` copies each of its `String`s into an allocation of its own; cloning the `Rc` allocates
+/// nothing, and the only bytes allocated are those of the `Vec` the clones are collected in.
+/// `cargo bench --features bench -- clone` times them as well.
+fn counting_the_cost_of_a_clone() -> Result<()> {
+    Ok({
+        span!("counting_the_cost_of_a_clone");
+        header!("Counting the Cost of a Clone");
+
+        let words: Vec<String> = (1..=100).map(|i| format!("word {i}")).collect();
+        let (_copies, deep) = metrics::collect(|| {
+            (0..3)
+                .map(|_| metrics::deep_clone(&words))
+                .collect::<Vec<_>>()
+        });
+        let shared = Rc::new(words);
+        let (_owners, cheap) = metrics::collect(|| {
+            (0..3)
+                .map(|_| metrics::rc_clone(&shared))
+                .collect::<Vec<_>>()
+        });
+        println!("3 deep clones: {deep}");
+        println!("3 Rc clones:   {cheap}");
+        // 3 deep clones: 0 Rc clone(s), 3 deep clone(s), 0 drop(s)
+        // 3 Rc clones:   3 Rc clone(s), 0 deep clone(s), 0 drop(s)
+    })
+}
+
+// What we can’t see in this example is that when b and then a go out of scope at the end of the function,
+// the count is then 0, and the `Rc<List>` is cleaned up completely. Using `Rc<T>` allows a single value
+// to have multiple owners, and the count ensures that the value remains valid as long as any of the
+// owners still exist.
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_builds_a_list_from_a_vec() {
+        let list = List::from(vec![5, 10]);
+        assert_eq!(list.to_string(), "5 -> 10 -> Nil");
+
+        if let List::Cons(_, tail) = &list {
+            assert_eq!(Rc::strong_count(tail), 1);
+        }
+    }
+
+    #[test]
+    fn it_appends_by_sharing_the_back_list() {
+        let front = Rc::new(List::from(vec![1, 2]));
+        let back = Rc::new(List::from(vec![3, 4]));
+
+        let joined = List::append(&front, &back);
+        assert_eq!(joined.to_string(), "1 -> 2 -> 3 -> 4 -> Nil");
+        assert_eq!(Rc::strong_count(&back), 2);
+        assert_eq!(Rc::strong_count(&front), 1);
+
+        let again = List::append(&front, &back);
+        assert_eq!(Rc::strong_count(&back), 3);
+
+        drop(joined);
+        drop(again);
+        assert_eq!(Rc::strong_count(&back), 1);
+    }
+
+    #[test]
+    fn it_shares_the_back_list_itself_when_the_front_is_empty() {
+        let front = Rc::new(List::Nil);
+        let back = Rc::new(List::from(vec![1]));
+
+        let joined = List::append(&front, &back);
+        assert!(Rc::ptr_eq(&joined, &back));
+        assert_eq!(Rc::strong_count(&back), 2);
+    }
+}
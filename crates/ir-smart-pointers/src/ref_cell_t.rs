@@ -0,0 +1,2290 @@
+//! # `RefCell<T>` and the Interior Mutability Pattern
+//! _Interior mutability_ is a design pattern in Rust that allows you to mutate data even when there
+//! are immutable references to that data; normally, this action is disallowed by the borrowing rules.
+//! To mutate data, the pattern uses `unsafe` code inside a data structure to bend Rust’s usual rules
+//! that govern mutation and borrowing. Unsafe code indicates to the compiler that we’re checking the
+//! rules manually instead of relying on the compiler to check them for us.
+//!
+//! We can use types that use the interior mutability pattern only when we can ensure that the borrowing
+//! rules will be followed at runtime, even though the compiler can’t guarantee that. The `unsafe` code
+//! involved is then wrapped in a safe API, and the outer type is still immutable.
+//!
+//! Checking the rules at runtime costs a little on every borrow, which
+//! `cargo bench --features bench -- borrow` measures.
+
+use super::{
+    count_tracer::CountTracer,
+    debug_ref_cell::{DebugRef, DebugRefCell},
+    file_logger::{FileLogger, LogLevel},
+    messengers::{
+        BufferedMessenger, ChannelMessenger, FallbackMessenger, MulticastMessenger,
+        StdoutMessenger, TcpMessenger,
+    },
+    rc_registry,
+    viz::{self, Graph},
+};
+use ir_core::{
+    locale::tr,
+    output,
+    utils::{miri::skip_under_miri, temp_dir::TempDir},
+};
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    fmt::{self, Display},
+    fs,
+    io::{BufRead, BufReader, Result},
+    net::TcpListener,
+    rc::{Rc, Weak},
+    time::Duration,
+};
+
+/// # Enforcing Borrowing Rules at Runtime with `RefCell<T>`
+/// With references and `Box<T>`, the borrowing rules’ invariants are enforced at compile time.
+/// With `RefCell<T>`, these invariants are enforced _at runtime_.
+/// With  references , if you break these rules, you’ll get a compiler error.
+/// With `RefCell<T>`, if you break these rules, your program will panic and exit.
+///
+/// The advantages of checking the borrowing rules at compile time are that errors will be caught
+/// sooner in the development process, and there is no impact on runtime performance because all
+/// the analysis is completed beforehand. For those reasons, checking the borrowing rules at compile
+/// time is the best choice in the majority of cases, which is why this is Rust’s default.
+///
+/// The advantage of checking the borrowing rules at runtime instead is that certain memory-safe
+/// scenarios are then allowed, where they would’ve been disallowed by the compile-time checks.
+/// Static analysis, like the Rust compiler, is inherently conservative. Some properties of code
+/// are impossible to detect by analyzing the code: the most famous example is the Halting Problem.
+///
+/// Because some analysis is impossible, if the Rust compiler can’t be sure the code complies with
+/// the ownership rules, it might reject a correct program; in this way, it’s conservative. If Rust
+/// accepted an incorrect program, users wouldn’t be able to trust in the guarantees Rust makes.
+/// However, if Rust rejects a correct program, the programmer will be inconvenienced, but nothing
+/// catastrophic can occur. The `RefCell<T>` type is useful when you’re sure your code follows the
+/// borrowing rules but the compiler is unable to understand and guarantee that.
+///
+/// Similar to `Rc<T>`, `RefCell<T>` is only for use in single-threaded scenarios and will give you a
+/// compile-time error if you try using it in a multithreaded context.
+///
+/// Mutating the value inside an immutable value is the _interior mutability_ pattern.
+pub fn ref_cell_t() -> Result<()> {
+    Ok({
+        if !skip_under_miri("logging to a file") {
+            // a directory of its own for each run, as runs at once would remove each other’s log
+            let dir = TempDir::new("ref_cell")?;
+            let log_path = dir.path().join("limit_tracker.log");
+            let logger = FileLogger::builder(&log_path)
+                .timestamps(true)
+                .min_level(LogLevel::Warn)
+                .rotate(1024 * 1024, 3) // at most 4 MiB of logs, in `limit_tracker.log{,.1,.2,.3}`
+                .buffered(true)
+                .build();
+            let mut tracker = LimitTracker::new(&logger, 100);
+            tracker.set_value(50); // below `LogLevel::Warn`, so not logged
+            tracker.set_value(95);
+            drop(logger); // writes out what is left in the buffer, and closes the log
+            print!("{}", fs::read_to_string(&log_path)?);
+            // `dir` is dropped here, and the log with it, rotated or not
+        }
+        tracking_a_limit_with_other_messengers()?;
+        tracking_other_kinds_of_quota()?;
+        reporting_each_threshold_once()?;
+
+        having_multiple_owners_of_mutable_data_by_combining_rc_t_and_ref_cell_t()?;
+        finding_where_a_conflicting_borrow_came_from()?;
+        reference_cycles_can_leak_memory()?;
+        breaking_the_cycle_with_a_weak_link()?;
+        creating_a_tree_data_structure_a_node_with_child_nodes()?;
+        visualizing_changes_to_strong_count_and_weak_count()?;
+        keeping_parent_links_consistent()?;
+        traversal_orders()?;
+        tree_metrics()?;
+        searching_a_tree()?;
+        comparing_trees()?;
+        #[cfg(feature = "serde")]
+        round_tripping_a_tree_through_json()?;
+    })
+}
+/// Consider the following scenario:
+/// ```
+/// pub trait Messenger {
+///     fn send(&mut self, msg: &str);
+/// }
+/// pub struct LimitTracker<'a, T: Messenger> {
+///     messenger: &'a mut T,
+///     value: usize,
+///     max: usize,
+/// }
+/// const ERROR_MSG: &str = "Error: You are over your quota!";
+/// const URGENT_WARNING: &str = "Urgent warning: You've used up over 90% of your quota!";
+/// const WARNING_MSG: &str = "Warning: You've used up over 75% of your quota!";
+/// impl<'a, T: Messenger> LimitTracker<'a, T> {
+///     pub fn new(messenger: &'a mut T, max: usize) -> LimitTracker<'a, T> {
+///         LimitTracker {
+///             messenger,
+///             value: 0,
+///             max,
+///         }
+///     }
+///
+///     pub fn set_value(&mut self, value: usize) {
+///         self.value = value;
+///
+///         let percentage_of_max = self.value as f64 / self.max as f64;
+///
+///         if percentage_of_max >= 1.0 {
+///             self.messenger.send(ERROR_MSG);
+///         } else if percentage_of_max >= 0.9 {
+///             self.messenger.send(URGENT_WARNING);
+///         } else if percentage_of_max >= 0.75 {
+///             self.messenger.send(WARNING_MSG);
+///         }
+///     }
+/// }
+///
+/// #[cfg(test)]
+/// mod tests {
+///     use super::*;
+///
+///     struct MockMessenger {
+///         sent_messages: Vec<String>,
+///     }
+///
+///     impl MockMessenger {
+///         fn new() -> MockMessenger {
+///             MockMessenger {
+///                 sent_messages: vec![],
+///             }
+///         }
+///     }
+///
+///     impl Messenger for MockMessenger {
+///         fn send(&mut self, message: &str) {
+///             self.sent_messages.push(String::from(message));
+///         }
+///     }
+///
+///     #[test]
+///     fn it_sends_an_over_75_percent_warning_message() {
+///         let mut mock_messenger = MockMessenger::new();
+///         let mut limit_tracker = LimitTracker::new(&mut mock_messenger, 100);
+///
+///         limit_tracker.set_value(80);
+///
+///         assert_eq!(mock_messenger.sent_messages.len(), 1);
+///         assert_eq!(
+///             mock_messenger.sent_messages.get(0),
+///             Some(&WARNING_MSG.to_string()),
+///             "sent message should be [{}]",
+///             WARNING_MSG
+///         );
+///     }
+/// }
+/// ```
+/// but now struct `LimitTracker.messenger` is declared as `&'a T` instead
+/// of `&'a mut T` where T: `Messenger trait` and you have no access to modify
+/// the code.
+///
+/// This is where `RefCell<T>` is useful.
+#[allow(clippy::test_attr_in_doctest)] // the book’s listing, quoted with its tests
+pub trait Messenger {
+    fn send(&self, msg: &str);
+
+    /// Like `send`, but returns a failure instead of dealing with it, for callers that want to
+    /// decide for themselves. Messengers that can’t fail can keep this default.
+    fn try_send(&self, msg: &str) -> Result<()> {
+        self.send(msg);
+        Ok(())
+    }
+
+    /// Like `send`, for a message the sender knows the `level` of. Only messengers that record
+    /// levels, like `FileLogger`, need more than this default, which sends the text alone.
+    fn send_at(&self, _level: LogLevel, msg: &str) {
+        self.send(msg);
+    }
+
+    /// `try_send` with a `level`, as `send_at` is `send` with one.
+    fn try_send_at(&self, _level: LogLevel, msg: &str) -> Result<()> {
+        self.try_send(msg)
+    }
+}
+/// Anything a `LimitTracker` can measure against a maximum of the same type: a count, a number
+/// of bytes, a percentage, a `Duration`.
+pub trait Quota: Copy {
+    /// How much of `max` this is, as a fraction: `0.8` for 80%.
+    fn fraction_of(self, max: Self) -> f64;
+}
+/// `as f64` works for every primitive number, but there is no trait that says so, so a macro
+/// writes the same `impl` for each of them.
+macro_rules! impl_quota_for_numbers {
+    ($($t:ty),*) => {
+        $(impl Quota for $t {
+            fn fraction_of(self, max: $t) -> f64 {
+                self as f64 / max as f64
+            }
+        })*
+    };
+}
+impl_quota_for_numbers!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize, f32, f64);
+impl Quota for Duration {
+    fn fraction_of(self, max: Duration) -> f64 {
+        self.as_secs_f64() / max.as_secs_f64()
+    }
+}
+
+pub struct LimitTracker<'a, M: Messenger, V: Quota> {
+    messenger: &'a M,
+    value: Option<V>,
+    max: V,
+    /// The highest threshold the value is above and the messenger has been told about.
+    notified: Option<QuotaEvent>,
+    repeat_notifications: bool,
+}
+const ERROR_MSG: &str = "Error: You are over your quota!";
+const URGENT_WARNING: &str = "Urgent warning: You've used up over 90% of your quota!";
+const WARNING_MSG: &str = "Warning: You've used up over 75% of your quota!";
+impl<'a, M: Messenger, V: Quota> LimitTracker<'a, M, V> {
+    pub fn new(messenger: &'a M, max: V) -> LimitTracker<'a, M, V> {
+        LimitTracker {
+            messenger,
+            value: None, // `V` has no zero we could start from, so there is no value until it’s set
+            max,
+            notified: None,
+            repeat_notifications: false,
+        }
+    }
+
+    /// By default each threshold is reported once, and then not again until the value has
+    /// dropped below it. With `repeat_notifications(true)`, every `set_value` above a threshold
+    /// reports it, however many times it has been reported already.
+    pub fn repeat_notifications(mut self, repeat: bool) -> LimitTracker<'a, M, V> {
+        self.repeat_notifications = repeat;
+        self
+    }
+
+    /// The last value `set_value` recorded, or `None` before the first one.
+    pub fn value(&self) -> Option<V> {
+        self.value
+    }
+
+    /// Records `value`, and if it crossed one of the thresholds, tells the messenger and returns
+    /// the event.
+    pub fn set_value(&mut self, value: V) -> Option<QuotaEvent> {
+        self.value = Some(value);
+
+        let percentage_of_max = value.fraction_of(self.max);
+
+        let event = if percentage_of_max >= 1.0 {
+            Some(QuotaEvent::Exceeded)
+        } else if percentage_of_max >= 0.9 {
+            Some(QuotaEvent::Urgent)
+        } else if percentage_of_max >= 0.75 {
+            Some(QuotaEvent::Warning)
+        } else {
+            None
+        };
+        // dropping to a lower threshold only lowers the bar: the next rise past it is news again
+        let already_notified = event <= self.notified;
+        self.notified = event;
+        if already_notified && !self.repeat_notifications {
+            return None;
+        }
+
+        let event = event?;
+        self.messenger.send_at(event.level(), &event.to_string());
+        Some(event)
+    }
+}
+
+/// What `set_value` found. The messenger still gets text, but code that wants to react to the
+/// event can `match` on it instead of comparing strings, and the compiler will point out every
+/// `match` that needs updating if a variant is added.
+///
+/// The variants are ordered by severity, `Warning < Urgent < Exceeded`, which is what lets
+/// `LimitTracker` tell whether a value has gone past a threshold it hasn’t reported yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum QuotaEvent {
+    Warning,  // over 75%
+    Urgent,   // over 90%
+    Exceeded, // at or over 100%
+}
+impl QuotaEvent {
+    /// The level a `FileLogger` logs the event at: a warning until the quota is exceeded.
+    pub fn level(self) -> LogLevel {
+        match self {
+            QuotaEvent::Warning | QuotaEvent::Urgent => LogLevel::Warn,
+            QuotaEvent::Exceeded => LogLevel::Error,
+        }
+    }
+}
+impl Display for QuotaEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QuotaEvent::Warning => f.write_str(tr(WARNING_MSG)),
+            QuotaEvent::Urgent => f.write_str(tr(URGENT_WARNING)),
+            QuotaEvent::Exceeded => f.write_str(tr(ERROR_MSG)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    struct MockMessenger {
+        sent_messages: RefCell<Vec<String>>,
+    }
+
+    impl MockMessenger {
+        /// mismatched types
+        /// expected struct `RefCell<Vec<String>>`
+        ///    found struct `Vec<_>`
+        /// ```
+        /// // --snip--
+        ///     sent_messages: vec![],
+        /// // --snip--
+        /// ```
+        fn new() -> MockMessenger {
+            MockMessenger {
+                sent_messages: RefCell::new(vec![]),
+            }
+        }
+    }
+
+    /// # Keeping Track of Borrows at Runtime with `RefCell<T>`
+    /// When creating immutable and mutable references, we use the `&` and `&mut` syntax,
+    /// respectively. With `RefCell<T>`, we use the `borrow` and `borrow_mut` methods,
+    /// which are part of the safe API that belongs to `RefCell<T>`.
+    impl Messenger for MockMessenger {
+        /// use `.borrow_mut()` to mutably borrow the `Vec<String>`, panicking if any borrows exist: `.borrow_mut()`
+        /// ```
+        /// // --snip--
+        ///     self.sent_messages.push(String::from(message));
+        /// // --snip--
+        /// ```
+        fn send(&self, message: &str) {
+            self.sent_messages.borrow_mut().push(String::from(message));
+            // The `borrow_mut` returns the smart pointer type `RefMut<T>`.
+            // It implements `Deref`, so we can treat them like regular references.
+        }
+    }
+
+    #[test]
+    #[allow(unused_mut, clippy::unnecessary_mut_passed, clippy::get_first)] // as the book writes it
+    fn it_sends_an_over_75_percent_warning_message() {
+        let mut mock_messenger = MockMessenger::new();
+        let mut limit_tracker = LimitTracker::new(&mut mock_messenger, 100);
+
+        assert_eq!(limit_tracker.set_value(80), Some(QuotaEvent::Warning));
+
+        // use `.borrow()` to borrow the `Vec<String>`, panicking if a mutable borrow exists: `.borrow()`
+        assert_eq!(mock_messenger.sent_messages.borrow().len(), 1);
+        assert_eq!(
+            mock_messenger.sent_messages.borrow().get(0),
+            Some(&WARNING_MSG.to_string()),
+            "sent message should be [{}]",
+            WARNING_MSG
+        );
+        // The `borrow` method returns the smart pointer type `Ref<T>`.
+        // It implements `Deref`, so we can treat them like regular references.
+    }
+
+    #[test]
+    fn the_messenger_gets_the_event_as_text() {
+        let mock_messenger = MockMessenger::new();
+        let mut limit_tracker = LimitTracker::new(&mock_messenger, 100);
+
+        let events: Vec<_> = [50, 80, 95, 120]
+            .into_iter()
+            .filter_map(|value| limit_tracker.set_value(value))
+            .collect();
+
+        use QuotaEvent::*;
+        assert_eq!(events, [Warning, Urgent, Exceeded]);
+        let as_text: Vec<_> = events.iter().map(QuotaEvent::to_string).collect();
+        assert_eq!(*mock_messenger.sent_messages.borrow(), as_text);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn a_file_logger_gets_each_event_at_its_level() {
+        let dir = TempDir::new("levels").unwrap();
+        let path = dir.path().join("limit_tracker.log");
+        let logger = FileLogger::new(&path);
+        let mut limit_tracker = LimitTracker::new(&logger, 100);
+        limit_tracker.set_value(95);
+        limit_tracker.set_value(120);
+
+        let log = std::fs::read_to_string(&path).unwrap();
+        let levels: Vec<_> = log.lines().map(|line| line.split(' ').next()).collect();
+        assert_eq!(levels, [Some("[WARN]"), Some("[ERROR]")]);
+    }
+
+    #[test]
+    fn each_threshold_is_reported_once_until_the_value_drops_below_it() {
+        let mock_messenger = MockMessenger::new();
+        let mut limit_tracker = LimitTracker::new(&mock_messenger, 100);
+
+        let events = [80, 85, 95, 80, 95, 50, 80, 80].map(|value| limit_tracker.set_value(value));
+
+        use QuotaEvent::*;
+        assert_eq!(
+            events,
+            [
+                Some(Warning), // 80: past 75% for the first time
+                None,          // 85: still past 75%
+                Some(Urgent),  // 95: past 90% for the first time
+                None,          // 80: back under 90%, but still past 75%
+                Some(Urgent),  // 95: past 90% again
+                None,          // 50: under every threshold
+                Some(Warning), // 80: past 75% again
+                None,
+            ]
+        );
+        assert_eq!(mock_messenger.sent_messages.borrow().len(), 4);
+    }
+
+    #[test]
+    fn repeated_notifications_report_every_value_past_a_threshold() {
+        let mock_messenger = MockMessenger::new();
+        let mut limit_tracker = LimitTracker::new(&mock_messenger, 100).repeat_notifications(true);
+
+        let events = [80, 85, 50, 95, 95].map(|value| limit_tracker.set_value(value));
+
+        use QuotaEvent::*;
+        assert_eq!(
+            events,
+            [
+                Some(Warning),
+                Some(Warning),
+                None,
+                Some(Urgent),
+                Some(Urgent)
+            ]
+        );
+        assert_eq!(
+            *mock_messenger.sent_messages.borrow(),
+            [WARNING_MSG, WARNING_MSG, URGENT_WARNING, URGENT_WARNING]
+        );
+    }
+
+    #[test]
+    fn it_tracks_bytes() {
+        let mock_messenger = MockMessenger::new();
+        let mut limit_tracker = LimitTracker::new(&mock_messenger, 4u64 << 30); // 4 GiB
+
+        assert_eq!(
+            limit_tracker.set_value(3u64 << 30),
+            Some(QuotaEvent::Warning)
+        );
+        assert_eq!(
+            limit_tracker.set_value(5u64 << 30),
+            Some(QuotaEvent::Exceeded)
+        );
+
+        assert_eq!(
+            *mock_messenger.sent_messages.borrow(),
+            [WARNING_MSG, ERROR_MSG]
+        );
+    }
+
+    #[test]
+    fn it_tracks_percentages() {
+        let mock_messenger = MockMessenger::new();
+        let mut limit_tracker = LimitTracker::new(&mock_messenger, 100.0);
+
+        assert_eq!(limit_tracker.value(), None);
+        assert_eq!(limit_tracker.set_value(74.9), None);
+        assert_eq!(limit_tracker.value(), Some(74.9));
+        assert_eq!(limit_tracker.set_value(92.5), Some(QuotaEvent::Urgent));
+
+        assert_eq!(*mock_messenger.sent_messages.borrow(), [URGENT_WARNING]);
+    }
+
+    #[test]
+    fn it_tracks_durations() {
+        let mock_messenger = MockMessenger::new();
+        let mut limit_tracker = LimitTracker::new(&mock_messenger, Duration::from_secs(60));
+
+        let events = [45_000, 60_000].map(|ms| limit_tracker.set_value(Duration::from_millis(ms)));
+
+        assert_eq!(
+            events,
+            [Some(QuotaEvent::Warning), Some(QuotaEvent::Exceeded)]
+        );
+        assert_eq!(
+            *mock_messenger.sent_messages.borrow(),
+            [WARNING_MSG, ERROR_MSG]
+        );
+    }
+    // The `RefCell<T>` keeps track of how many `Ref<T>` and `RefMut<T>` smart pointers are currently active.
+    // Every time we call `borrow`, the `RefCell<T>` increases its count of how many immutable borrows are active.
+    // When a `Ref<T>` value goes out of scope, the count of immutable borrows goes down by one.
+    // Just like the compile-time borrowing rules, `RefCell<T>` lets us have many immutable borrows or one mutable borrow at any point in time.
+
+    /// If we try to violate the borrowing rules, rather than getting a compiler error as we would with references,
+    /// the implementation of `RefCell<T>` will panic at runtime.
+    impl MockMessenger {
+        pub fn try_to_violate_the_borrowing_rules(&self) {
+            let mut borrow1 = self.sent_messages.borrow_mut();
+            let mut borrow2 = self.sent_messages.borrow_mut();
+
+            borrow1.push(ERROR_MSG.to_string());
+            borrow2.push(URGENT_WARNING.to_string());
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "already borrowed")]
+    fn creating_two_mutable_references_in_the_same_scope_to_see_that_ref_cell_t_will_panic() {
+        let messenger = MockMessenger::new();
+        messenger.try_to_violate_the_borrowing_rules();
+    }
+    // Notice that the code panicked with the message `already borrowed: BorrowMutError`.
+    // This is how `RefCell<T>` handles violations of the borrowing rules at runtime.
+
+    #[test]
+    fn it_detects_cycles() {
+        use CyclicList::*;
+
+        let a = Rc::new(Cons(1, RefCell::new(Rc::new(Nil))));
+        let b = Rc::new(Cons(2, RefCell::new(Rc::clone(&a))));
+        let c = Rc::new(Cons(3, RefCell::new(Rc::clone(&b))));
+        assert!(!c.has_cycle());
+        assert!(!Nil::<i32>.has_cycle());
+        assert_eq!(format!("{:?}", c), "Cons(3, RefCell { value: Cons(2, RefCell { value: Cons(1, RefCell { value: Nil }) }) })");
+
+        // a cycle that doesn't include the head is still found
+        *a.tail().unwrap().borrow_mut() = Rc::clone(&b);
+        assert!(c.has_cycle());
+        assert_eq!(format!("{:?}", c), "Cons(3, <cycle>)");
+
+        // a node pointing at itself
+        *a.tail().unwrap().borrow_mut() = Rc::clone(&a);
+        assert!(a.has_cycle());
+        assert!(c.has_cycle());
+
+        *a.tail().unwrap().borrow_mut() = Rc::new(Nil);
+        assert!(!c.has_cycle());
+    }
+
+    #[test]
+    fn a_weak_back_link_lets_both_nodes_drop() {
+        use CyclicList::*;
+
+        let (probe_a, probe_b) = {
+            let a = Rc::new(WeakLink(5, RefCell::new(Weak::new())));
+            let b = Rc::new(Cons(10, RefCell::new(Rc::clone(&a))));
+            if let WeakLink(_, back) = &*a {
+                *back.borrow_mut() = Rc::downgrade(&b);
+            }
+            assert!(b.has_cycle());
+            assert_eq!(b.to_string(), "10 -> 5 ~> (back to 10)");
+            assert_eq!(Rc::strong_count(&b), 1);
+            assert_eq!(Rc::weak_count(&b), 1);
+            (Rc::downgrade(&a), Rc::downgrade(&b))
+        };
+
+        assert_eq!(probe_a.strong_count(), 0);
+        assert_eq!(probe_b.strong_count(), 0);
+    }
+
+    #[test]
+    fn a_dangling_weak_link_displays_as_dropped() {
+        use CyclicList::*;
+
+        let a = WeakLink(5, RefCell::new(Weak::new()));
+        assert_eq!(a.to_string(), "5 ~> (dropped)");
+        assert!(!a.has_cycle());
+    }
+
+    #[test]
+    fn it_displays_a_cyclic_list_without_overflowing_the_stack() {
+        use CyclicList::*;
+
+        let a = Rc::new(Cons(5, RefCell::new(Rc::new(Nil))));
+        let b = Rc::new(Cons(10, RefCell::new(Rc::clone(&a))));
+        assert_eq!(b.to_string(), "10 -> 5 -> Nil");
+
+        if let Some(link) = a.tail() {
+            *link.borrow_mut() = Rc::clone(&b);
+        }
+        assert_eq!(a.to_string(), "5 -> 10 -> (back to 5)");
+        assert_eq!(b.to_string(), "10 -> 5 -> (back to 10)");
+
+        // break the cycle so the test doesn't leak
+        if let Some(link) = a.tail() {
+            *link.borrow_mut() = Rc::new(Nil);
+        }
+    }
+}
+// Choosing to catch borrowing errors at runtime rather than compile time, as we’ve done here, means you’d
+// potentially be finding mistakes in your code later in the development process: possibly not until your
+// code was deployed to production. Also, your code would incur a small runtime performance penalty as a
+// result of keeping track of the borrows at runtime rather than compile time. However, using `RefCell<T>`
+// makes it possible to write a mock object that can modify itself to keep track of the messages it has
+// seen while you’re using it in a context where only immutable values are allowed. You can use `RefCell<T>`
+// despite its trade-offs to get more functionality than regular references provide.
+
+/// # Having Multiple Owners of Mutable Data by Combining `Rc<T>` and `RefCell<T>`
+/// A common way to use `RefCell<T>` is in combination with `Rc<T>`. Recall that `Rc<T>`
+/// lets you have multiple owners of some data, but it only gives immutable access to that data.
+/// If you have an `Rc<T>` that holds a `RefCell<T>`, you can get a value that can have multiple
+/// owners and that you can mutate!
+///
+/// The `RefCell` here is a `DebugRefCell`, which borrows as a `RefCell` does; run with
+/// `--verbose`, its panics also say where the borrows in the way were taken.
+#[derive(Debug)]
+pub enum List<T> {
+    Cons(Rc<DebugRefCell<T>>, Rc<List<T>>),
+    Nil,
+}
+impl<T> List<T> {
+    pub fn for_each(&self, f: impl Fn(&T) -> ()) {
+        use List::*;
+
+        if let Cons(t, next) = self {
+            f(&t.borrow());
+            Self::for_each(next, f);
+        }
+    }
+}
+impl<T: Display> Display for List<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut node = self;
+        while let List::Cons(value, next) = node {
+            write!(f, "{} -> ", value.borrow())?;
+            node = next;
+        }
+        write!(f, "Nil")
+    }
+}
+/// `LimitTracker` only knows it has some `M: Messenger`, so the same tracker code can warn on
+/// stdout, hand the warnings to another thread, or send them over the network.
+fn tracking_a_limit_with_other_messengers() -> Result<()> {
+    Ok({
+        let stdout = StdoutMessenger;
+        if let Some(event) = LimitTracker::new(&stdout, 100).set_value(80) {
+            println!("set_value returned {event:?}");
+        }
+
+        let (channel, receiver) = ChannelMessenger::new();
+        // `output::spawn`, so that what the threads print goes where the lesson prints
+        let listener = output::spawn(move || {
+            for msg in receiver {
+                println!("received over the channel: {msg}");
+            }
+        });
+        LimitTracker::new(&channel, 100).set_value(90);
+        drop(channel); // the thread stops listening once every sender is gone
+        listener.join().expect("the channel listener panicked");
+
+        // a "server" on a port the OS picks, which reads the one line it expects and hangs up
+        if !skip_under_miri("sending over TCP") {
+            let server = TcpListener::bind("127.0.0.1:0")?;
+            let addr = server.local_addr()?;
+            let server = output::spawn(move || -> Result<()> {
+                let (stream, _) = server.accept()?;
+                for line in BufReader::new(stream).lines() {
+                    println!("received over TCP: {}", line?);
+                }
+                Ok(())
+            });
+            let tcp = TcpMessenger::connect(addr)?;
+            LimitTracker::new(&tcp, 100).set_value(100);
+            drop(tcp); // closing the connection ends the server’s `lines()`
+            server.join().expect("the TCP server panicked")?;
+        }
+
+        // stdout still gets the warning, although the file logger fails to write it
+        if !skip_under_miri("failing to log to a file") {
+            let multicast = MulticastMessenger(vec![
+                Box::new(StdoutMessenger),
+                Box::new(FileLogger::new(".")),
+            ]);
+            LimitTracker::new(&multicast, 100).set_value(75);
+        }
+
+        // the warnings are held back until the third one, or until `buffered` is dropped
+        let buffered = BufferedMessenger::new(StdoutMessenger, 3);
+        let mut tracker = LimitTracker::new(&buffered, 100);
+        tracker.set_value(80);
+        tracker.set_value(95);
+        println!("two warnings buffered, none printed yet");
+
+        // the file logger gets two more tries, 10ms and then 20ms later, before stdout takes over
+        if !skip_under_miri("falling back from a file logger") {
+            let fallback = FallbackMessenger::new(FileLogger::new("."), StdoutMessenger)
+                .retries(2, Duration::from_millis(10));
+            LimitTracker::new(&fallback, 100).set_value(100);
+        }
+    })
+    // Warning: You've used up over 75% of your quota!
+    // set_value returned Warning
+    // received over the channel: Urgent warning: You've used up over 90% of your quota!
+    // received over TCP: Error: You are over your quota!
+    // Warning: You've used up over 75% of your quota!
+    // [ERROR] 1 messenger(s) failed:
+    //   #1: Is a directory (os error 21)
+    // two warnings buffered, none printed yet
+    // [WARN] Is a directory (os error 21), falling back
+    // Error: You are over your quota!
+    // Warning: You've used up over 75% of your quota!
+    // Urgent warning: You've used up over 90% of your quota!
+}
+/// `LimitTracker` is also generic over what it measures: anything that implements `Quota`.
+fn tracking_other_kinds_of_quota() -> Result<()> {
+    Ok({
+        let stdout = StdoutMessenger;
+
+        let mut disk = LimitTracker::new(&stdout, 512u64 * 1024 * 1024 * 1024);
+        disk.set_value(500 * 1024 * 1024 * 1024);
+
+        let mut build_time = LimitTracker::new(&stdout, Duration::from_secs(10 * 60));
+        build_time.set_value(Duration::from_secs(8 * 60));
+        println!("build time so far: {:?}", build_time.value());
+    })
+    // Urgent warning: You've used up over 90% of your quota!
+    // Warning: You've used up over 75% of your quota!
+    // build time so far: Some(480s)
+}
+/// A value hovering around a threshold would otherwise repeat the same warning on every update.
+fn reporting_each_threshold_once() -> Result<()> {
+    Ok({
+        let stdout = StdoutMessenger;
+        let readings = [76, 78, 74, 77];
+
+        println!("once per crossing:");
+        let mut tracker = LimitTracker::new(&stdout, 100);
+        for value in readings {
+            tracker.set_value(value);
+        }
+
+        println!("every time:");
+        let mut tracker = LimitTracker::new(&stdout, 100).repeat_notifications(true);
+        for value in readings {
+            tracker.set_value(value);
+        }
+    })
+    // once per crossing:
+    // Warning: You've used up over 75% of your quota!
+    // Warning: You've used up over 75% of your quota!
+    // every time:
+    // Warning: You've used up over 75% of your quota!
+    // Warning: You've used up over 75% of your quota!
+    // Warning: You've used up over 75% of your quota!
+}
+#[allow(clippy::identity_op)] // `0 + *i` makes the dereference visible
+fn having_multiple_owners_of_mutable_data_by_combining_rc_t_and_ref_cell_t() -> Result<()> {
+    Ok({
+        println!("i32");
+        {
+            use List::*;
+
+            let value = Rc::new(DebugRefCell::when_verbose(5));
+
+            let a = Rc::new(Cons(Rc::clone(&value), Rc::new(Nil)));
+
+            let b = Rc::new(Cons(Rc::new(DebugRefCell::when_verbose(3)), Rc::clone(&a)));
+            let c = Rc::new(Cons(Rc::new(DebugRefCell::when_verbose(4)), Rc::clone(&a)));
+
+            *value.borrow_mut() += 10;
+            viz::show(
+                Graph::new("`b` and `c` share `a`, which shares `value`")
+                    .var("a", &a)
+                    .var("b", &b)
+                    .var("c", &c),
+            );
+
+            a.for_each(|i| println!("a:{}", 0 + *i));
+            b.for_each(|i| println!("b:{}", 0 + *i));
+            c.for_each(|i| println!("c:{}", 0 + *i));
+
+            println!("a after = {a}");
+            println!("b after = {b}");
+            println!("c after = {c}");
+        }
+
+        println!("String");
+        {
+            use List::*;
+
+            let value = Rc::new(DebugRefCell::when_verbose(String::from("Rust")));
+
+            let a = Rc::new(Cons(Rc::clone(&value), Rc::new(Nil)));
+
+            let b = Rc::new(Cons(
+                Rc::new(DebugRefCell::when_verbose(String::from("Hello"))),
+                Rc::clone(&a),
+            ));
+            let c = Rc::new(Cons(
+                Rc::new(DebugRefCell::when_verbose(String::from("World"))),
+                Rc::clone(&a),
+            ));
+
+            value.borrow_mut().push_str("acean");
+
+            a.for_each(|s| println!("a:{s}"));
+            b.for_each(|s| println!("b:{s}"));
+            c.for_each(|s| println!("c:{s}"));
+
+            println!("a after = {a}");
+            println!("b after = {b}");
+            println!("c after = {c}");
+        }
+    })
+}
+// This technique is pretty neat! By using `RefCell<T>`, we have an outwardly immutable `List` value.
+// But we can use the methods on `RefCell<T>` that provide access to its interior mutability so we
+// can modify our data when we need to. The runtime checks of the borrowing rules protect us from
+// data races, and it’s sometimes worth trading a bit of speed for this flexibility in our data
+// structures. Note that `RefCell<T>` does not work for multithreaded code! `Mutex<T>` is the thread-
+// safe version of `RefCell<T>`
+
+/// # Finding Where a Conflicting Borrow Came From
+/// Sharing a `RefCell` through `Rc`s is what makes a borrow conflict possible: one owner holds a
+/// borrow while another owner, somewhere else entirely, asks for a mutable one. Here `report`
+/// keeps a shared borrow of the scores while it calls `on_each`, and the callback, which owns a
+/// clone of the same `Rc`, tries to change them. `DebugRefCell` names the line of the borrow that
+/// is in the way, not just the line of the one that failed.
+fn finding_where_a_conflicting_borrow_came_from() -> Result<()> {
+    Ok({
+        span!("finding_where_a_conflicting_borrow_came_from");
+        header!("Finding Where a Conflicting Borrow Came From");
+
+        let scores = Rc::new(DebugRefCell::new(vec![70, 85]));
+
+        let report = |on_each: &dyn Fn(i32)| {
+            for score in scores.borrow().iter() {
+                on_each(*score);
+            }
+        };
+
+        let owner = Rc::clone(&scores);
+        report(&|score| {
+            if let Err(conflict) = owner.try_borrow_mut() {
+                println!("can't curve {score}: {conflict}");
+            }
+        });
+        // can't curve 70: already borrowed: borrow_mut at src/…/ref_cell_t.rs:…:… conflicts with
+        //   outstanding borrow at src/…/ref_cell_t.rs:…:…
+
+        // with the borrow released, the same change succeeds
+        for score in owner.borrow_mut().iter_mut() {
+            *score += 5;
+        }
+        println!("curved = {:?}", *scores.borrow()); // curved = [75, 90]
+    })
+}
+
+/// # Reference Cycles Can Leak Memory
+/// Rust’s memory safety guarantees make it difficult, but not impossible,
+/// to accidentally create memory that is never cleaned up (known as a _memory leak_).
+/// Preventing memory leaks entirely is not one of Rust’s guarantees,
+/// meaning memory leaks are memory safe in Rust.
+/// We can see that Rust allows memory leaks by using `Rc<T>` and `RefCell<T>`:
+/// it’s possible to create references where items refer to each other in a cycle.
+/// This creates memory leaks because the reference count of each item in the cycle will never reach 0,
+/// and the values will never be dropped.
+fn reference_cycles_can_leak_memory() -> Result<()> {
+    Ok({
+        use CyclicList::*;
+
+        span!("reference_cycles_can_leak_memory");
+        header!("Reference Cycles Can Leak Memory");
+
+        let (mut a_tracer, mut b_tracer) = (CountTracer::new("a"), CountTracer::new("b"));
+        let a = Rc::new(Cons(5, RefCell::new(Rc::new(Nil))));
+
+        a_tracer.snapshot("initial", &a);
+        println!("a next item = {:?}", a.tail());
+
+        let b = Rc::new(Cons(10, RefCell::new(Rc::clone(&a))));
+
+        a_tracer.snapshot("after b creation", &a);
+        b_tracer.snapshot("initial", &b);
+        println!("b next item = {:?}", b.tail());
+
+        if let Some(link) = a.tail() {
+            *link.borrow_mut() = Rc::clone(&b);
+        }
+
+        a_tracer.snapshot("after changing a", &a);
+        b_tracer.snapshot("after changing a", &b);
+        print!("{}{}", a_tracer.table(), b_tracer.table());
+        // `a`               strong  weak
+        // initial                1     0
+        // after b creation       2     0
+        // after changing a       2     0
+        // `b`               strong  weak
+        // initial                1     0
+        // after changing a       2     0
+        // tracked only after the snapshots, so that the registry’s own `Weak`s don’t show in them
+        rc_registry::track("reference_cycles_can_leak_memory: a", &a);
+        rc_registry::track("reference_cycles_can_leak_memory: b", &b);
+        viz::show(
+            Graph::new("`a` and `b` own each other")
+                .var("a", &a)
+                .var("b", &b),
+        );
+
+        // With a derived `Debug`, this would follow the cycle forever and overflow the stack;
+        // the `Debug` impl below checks `has_cycle` first
+        println!("a has a cycle = {}", a.has_cycle()); // a has a cycle = true
+        println!("a next item = {:?}", a.tail());
+        // a next item = Some(RefCell { value: Cons(10, <cycle>) })
+
+        // the `Display` impl below remembers which nodes it has already visited
+        println!("a = {a}"); // a = 5 -> 10 -> (back to 5)
+        println!("b = {b}"); // b = 10 -> 5 -> (back to 10)
+    })
+}
+pub enum CyclicList<T> {
+    Cons(T, RefCell<Rc<CyclicList<T>>>),
+    WeakLink(T, RefCell<Weak<CyclicList<T>>>), // a back-pointer that doesn’t own what it points to
+    Nil,
+}
+impl<T> CyclicList<T> {
+    #[allow(clippy::needless_return)] // as the book writes it
+    pub fn tail(&self) -> Option<&RefCell<Rc<CyclicList<T>>>> {
+        use CyclicList::*;
+
+        return match self {
+            Cons(_, tail) => Some(tail),
+            WeakLink(..) | Nil => None,
+        };
+    }
+
+    /// Floyd’s tortoise and hare: `slow` moves one node at a time and `fast` moves two. If the
+    /// list ends, `fast` reaches `Nil` first. If it loops, `fast` laps `slow` inside the loop
+    /// and they land on the same node, which `Rc::ptr_eq` recognizes by address. Unlike a set
+    /// of visited nodes, this needs no extra memory.
+    pub fn has_cycle(&self) -> bool {
+        let mut slow = self.next();
+        let mut fast = slow.as_ref().and_then(|node| node.next());
+        while let (Some(s), Some(h)) = (&slow, &fast) {
+            if Rc::ptr_eq(s, h) {
+                return true;
+            }
+            let next_slow = s.next();
+            let next_fast = h.next().and_then(|node| node.next());
+            slow = next_slow;
+            fast = next_fast;
+        }
+        false
+    }
+
+    /// The following node, through either kind of link. A `WeakLink` whose target has already
+    /// been dropped leads nowhere, like `Nil`.
+    fn next(&self) -> Option<Rc<CyclicList<T>>> {
+        use CyclicList::*;
+
+        match self {
+            Cons(_, tail) => Some(Rc::clone(&tail.borrow())),
+            WeakLink(_, back) => back.borrow().upgrade(),
+            Nil => None,
+        }
+    }
+
+    fn value(&self) -> Option<&T> {
+        use CyclicList::*;
+
+        match self {
+            Cons(value, _) | WeakLink(value, _) => Some(value),
+            Nil => None,
+        }
+    }
+}
+/// Like a derived `Debug`, except that a list with a cycle prints its head and `<cycle>`
+/// instead of recursing into its tail forever.
+impl<T: fmt::Debug> fmt::Debug for CyclicList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use CyclicList::*;
+
+        match self {
+            Cons(value, _) | WeakLink(value, _) if self.has_cycle() => f
+                .debug_tuple("Cons")
+                .field(value)
+                .field(&format_args!("<cycle>"))
+                .finish(),
+            Cons(value, tail) => f.debug_tuple("Cons").field(value).field(tail).finish(),
+            WeakLink(value, back) => f.debug_tuple("WeakLink").field(value).field(back).finish(),
+            Nil => write!(f, "Nil"),
+        }
+    }
+}
+/// Renders `5 -> 10 -> Nil`, or `5 -> 10 -> (back to 5)` when the tail leads back to a node
+/// that was already printed. Nodes are compared by address with `Rc::as_ptr`, not by value:
+/// two different nodes may well hold the same value. A weak link is drawn as `~>`, and one
+/// whose target is gone as `~> (dropped)`.
+impl<T: Display> Display for CyclicList<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(value) = self.value() else {
+            return write!(f, "Nil");
+        };
+        write!(f, "{value}")?;
+
+        let mut visited: Vec<*const CyclicList<T>> = vec![self];
+        let mut from: &CyclicList<T> = self;
+        let mut holder;
+        loop {
+            let arrow = match from {
+                CyclicList::WeakLink(..) => "~>",
+                _ => "->",
+            };
+            let Some(node) = from.next() else {
+                return match from {
+                    CyclicList::WeakLink(..) => write!(f, " {arrow} (dropped)"),
+                    _ => write!(f, " {arrow} Nil"),
+                };
+            };
+            let Some(value) = node.value() else {
+                return write!(f, " {arrow} Nil");
+            };
+            if visited.contains(&Rc::as_ptr(&node)) {
+                return write!(f, " {arrow} (back to {value})");
+            }
+            visited.push(Rc::as_ptr(&node));
+            write!(f, " {arrow} {value}")?;
+            holder = node;
+            from = &holder;
+        }
+    }
+}
+
+/// # Breaking the Cycle with a Weak Link
+/// The same two nodes as above, built twice. In the first version `a` points back at `b` with an
+/// `Rc`, in the second with a `Weak`. We keep a `Weak` probe to each node, so that after the
+/// locals go out of scope we can ask how many strong references are left without keeping the
+/// nodes alive ourselves.
+///
+/// With `Rc` links, each node still holds the only strong reference to the other: counts of 1
+/// that nothing will ever decrement, so both nodes leak. With the `Weak` back-pointer, `b` is
+/// only owned by the local `b`, so it is dropped, which drops its link to `a`, which drops `a`.
+fn breaking_the_cycle_with_a_weak_link() -> Result<()> {
+    Ok({
+        use CyclicList::*;
+
+        span!("breaking_the_cycle_with_a_weak_link");
+        header!("Breaking the Cycle with a Weak Link");
+
+        let (mut a_tracer, mut b_tracer) = (CountTracer::new("a"), CountTracer::new("b"));
+        let strong_probes = {
+            let a = Rc::new(Cons(5, RefCell::new(Rc::new(Nil))));
+            let b = Rc::new(Cons(10, RefCell::new(Rc::clone(&a))));
+            if let Some(link) = a.tail() {
+                *link.borrow_mut() = Rc::clone(&b);
+            }
+            // the leak check that runs after the demo will report these two
+            rc_registry::track("breaking_the_cycle_with_a_weak_link: Rc link a", &a);
+            rc_registry::track("breaking_the_cycle_with_a_weak_link: Rc link b", &b);
+            println!("Rc links:   b = {b}");
+            a_tracer.snapshot("Rc links, in scope", &a);
+            b_tracer.snapshot("Rc links, in scope", &b);
+            (Rc::downgrade(&a), Rc::downgrade(&b))
+        };
+        a_tracer.snapshot_weak("Rc links, out of scope", &strong_probes.0);
+        b_tracer.snapshot_weak("Rc links, out of scope", &strong_probes.1);
+
+        let weak_probes = {
+            // `tracked` is `Rc::new` plus `track`; the leak check will find nothing to report
+            let a = rc_registry::tracked(
+                "breaking_the_cycle_with_a_weak_link: Weak link a",
+                WeakLink(5, RefCell::new(Weak::new())),
+            );
+            let b = rc_registry::tracked(
+                "breaking_the_cycle_with_a_weak_link: Weak link b",
+                Cons(10, RefCell::new(Rc::clone(&a))),
+            );
+            if let WeakLink(_, back) = &*a {
+                *back.borrow_mut() = Rc::downgrade(&b);
+            }
+            println!("Weak link:  b = {b}"); // Weak link:  b = 10 -> 5 ~> (back to 10)
+            viz::show(
+                Graph::new("`a` points back at `b` with a `Weak`")
+                    .var("a", &a)
+                    .var("b", &b),
+            );
+            a_tracer.snapshot("Weak link, in scope", &a);
+            b_tracer.snapshot("Weak link, in scope", &b);
+            (Rc::downgrade(&a), Rc::downgrade(&b))
+        };
+        a_tracer.snapshot_weak("Weak link, out of scope", &weak_probes.0);
+        b_tracer.snapshot_weak("Weak link, out of scope", &weak_probes.1);
+        print!("{}{}", a_tracer.table(), b_tracer.table());
+        // `a`                      strong  weak
+        // Rc links, in scope            2     1
+        // Rc links, out of scope        1     2
+        // Weak link, in scope           2     1
+        // Weak link, out of scope       0     0
+        // `b`                      strong  weak
+        // Rc links, in scope            2     1
+        // Rc links, out of scope        1     2
+        // Weak link, in scope           1     2
+        // Weak link, out of scope       0     0
+        // The weak counts include the leak check’s probe and, out of scope, ours.
+    })
+}
+
+/// What `TreeNode::deserialize` reads: a `TreeNode` without its `parent` links.
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct TreeShape<T> {
+    value: T,
+    children: Vec<TreeShape<T>>,
+}
+#[cfg(feature = "serde")]
+impl<T> TreeShape<T> {
+    /// Builds the tree with `add_child`, which links each child to its parent on the way.
+    fn build(self) -> Rc<TreeNode<T>> {
+        let node = TreeNode::new(self.value);
+        for child in self.children {
+            TreeNode::add_child(&node, child.build());
+        }
+        node
+    }
+}
+
+/// # Creating a Tree Data Structure: a Node with Child Nodes
+/// Both cells are `DebugRefCell`s, like the cons `List`’s: a walk that still holds `children()`
+/// when something adds a child panics, with `--verbose`, naming the line that holds them.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct TreeNode<T> {
+    value: T,
+    children: DebugRefCell<Vec<Rc<TreeNode<T>>>>,
+    //
+    // To make the child node aware of its parent,
+    // we need to add a `parent` field to our Node struct definition.
+    // The trouble is in deciding what the type of parent should be.
+    // We know it can’t contain an `Rc<T>`,
+    // because that would create a reference cycle with `leaf.parent` pointing to
+    // `branch` and `branch.children` pointing to `leaf`,
+    // which would cause their `strong_count` values to never be 0.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    parent: DebugRefCell<Weak<TreeNode<T>>>, // # Add a Reference from a Child to Its Parent
+                                             // Thinking about the relationships another way,
+                                             // a parent node should own its children: if a
+                                             // parent node is dropped, its child nodes should
+                                             // be dropped as well. However, a child should
+                                             // not own its parent: if we drop a child node,
+                                             // the parent should still exist. This is a case
+                                             // for weak references!
+}
+impl<T> TreeNode<T> {
+    pub fn new(value: T) -> Rc<TreeNode<T>> {
+        Rc::new(TreeNode {
+            value,
+            children: DebugRefCell::when_verbose(vec![]),
+            parent: DebugRefCell::when_verbose(Weak::new()),
+        })
+    }
+
+    pub fn value(&self) -> &T {
+        &self.value
+    }
+
+    pub fn parent(&self) -> Option<Rc<TreeNode<T>>> {
+        self.parent.borrow().upgrade()
+    }
+
+    /// The link to the parent itself, which, unlike `parent`, doesn’t add a strong count.
+    #[track_caller]
+    pub fn parent_link(&self) -> DebugRef<'_, Weak<TreeNode<T>>> {
+        self.parent.borrow()
+    }
+
+    /// The children, borrowed; with `--verbose`, a conflicting borrow names where this one was
+    /// taken, which is the caller’s line rather than this one, thanks to `#[track_caller]`.
+    #[track_caller]
+    pub fn children(&self) -> DebugRef<'_, [Rc<TreeNode<T>>]> {
+        DebugRef::map(self.children.borrow(), Vec::as_slice)
+    }
+
+    /// Visits every value parent-first; the same as `traverse(TraversalOrder::PreOrder, ..)`.
+    pub fn for_each(&self, f: &impl Fn(&T) -> ()) {
+        f(&self.value);
+        for child in &*self.children.borrow() {
+            Self::for_each(child, f);
+        }
+    }
+
+    /// Visits every value in the subtree rooted at `self`, in the given `order`.
+    pub fn traverse(&self, order: TraversalOrder, mut f: impl FnMut(&T)) {
+        match order {
+            TraversalOrder::PreOrder => self.pre_order(&mut f),
+            TraversalOrder::PostOrder => self.post_order(&mut f),
+            TraversalOrder::LevelOrder => self.level_order(&mut f),
+        }
+    }
+
+    fn pre_order(&self, f: &mut impl FnMut(&T)) {
+        f(&self.value);
+        for child in &*self.children.borrow() {
+            child.pre_order(f);
+        }
+    }
+
+    fn post_order(&self, f: &mut impl FnMut(&T)) {
+        for child in &*self.children.borrow() {
+            child.post_order(f);
+        }
+        f(&self.value);
+    }
+
+    /// A queue instead of recursion: children are queued behind all the nodes of the level
+    /// above. The queue holds `Rc` clones, so no `children.borrow()` outlives one loop turn.
+    fn level_order(&self, f: &mut impl FnMut(&T)) {
+        f(&self.value);
+        let mut queue: VecDeque<Rc<TreeNode<T>>> = self.children.borrow().iter().cloned().collect();
+        while let Some(node) = queue.pop_front() {
+            f(&node.value);
+            queue.extend(node.children.borrow().iter().cloned());
+        }
+    }
+
+    /// Makes `child` the last child of `parent`: the parent takes a strong reference to the
+    /// child, and the child gets a weak one back. A child that already has a parent is removed
+    /// from it first, so a node is never listed under two parents.
+    ///
+    /// # Panics
+    /// If `child` is `parent` or one of its ancestors. The parent would then own itself through
+    /// a chain of strong references, a cycle that could never be dropped.
+    pub fn add_child(parent: &Rc<Self>, child: Rc<Self>) {
+        assert!(
+            !Self::is_ancestor_or_self(&child, parent),
+            "a node can't become a child of its own subtree"
+        );
+        if let Some(old_parent) = child.parent() {
+            Self::remove_child(&old_parent, &child);
+        }
+        *child.parent.borrow_mut() = Rc::downgrade(parent);
+        parent.children.borrow_mut().push(child);
+    }
+
+    /// Detaches `child` from `parent` and hands the parent’s strong reference back to the
+    /// caller. Returns `None` if `child` isn’t a child of `parent`. Children are compared by
+    /// identity with `Rc::ptr_eq`, never by value.
+    pub fn remove_child(parent: &Rc<Self>, child: &Rc<Self>) -> Option<Rc<Self>> {
+        let removed = {
+            let mut children = parent.children.borrow_mut();
+            let index = children.iter().position(|c| Rc::ptr_eq(c, child))?;
+            children.remove(index)
+        };
+        *removed.parent.borrow_mut() = Weak::new();
+        Some(removed)
+    }
+
+    /// Moves `child`, with its whole subtree, under `new_parent`.
+    ///
+    /// # Panics
+    /// Like `add_child`, if `new_parent` is inside `child`’s subtree.
+    pub fn reparent(child: &Rc<Self>, new_parent: &Rc<Self>) {
+        Self::add_child(new_parent, Rc::clone(child));
+    }
+
+    /// The number of edges between `self` and the root, found by walking up the `Weak` parent
+    /// links. A parent that has already been dropped ends the walk, just like a root does.
+    pub fn depth(&self) -> usize {
+        let mut depth = 0;
+        let mut parent = self.parent();
+        while let Some(p) = parent {
+            depth += 1;
+            parent = p.parent();
+        }
+        depth
+    }
+
+    /// The number of edges on the longest path down to a leaf; `0` for a leaf.
+    pub fn height(&self) -> usize {
+        self.children
+            .borrow()
+            .iter()
+            .map(|child| child.height() + 1)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// The number of nodes in the subtree rooted at `self`, including `self`.
+    pub fn size(&self) -> usize {
+        1 + self
+            .children
+            .borrow()
+            .iter()
+            .map(|child| child.size())
+            .sum::<usize>()
+    }
+
+    /// The values from `self` up to the root, `self` first.
+    pub fn path_to_root(&self) -> Vec<T>
+    where
+        T: Clone,
+    {
+        let mut path = vec![self.value.clone()];
+        let mut parent = self.parent();
+        while let Some(p) = parent {
+            path.push(p.value.clone());
+            parent = p.parent();
+        }
+        path
+    }
+
+    /// The first node, in pre-order, whose value satisfies `pred`. The receiver is `&Rc<Self>`
+    /// rather than `&self` so that `self` itself can be returned as a new strong reference.
+    pub fn find(self: &Rc<Self>, pred: impl Fn(&T) -> bool) -> Option<Rc<TreeNode<T>>> {
+        self.find_by(&pred)
+    }
+
+    fn find_by(self: &Rc<Self>, pred: &impl Fn(&T) -> bool) -> Option<Rc<TreeNode<T>>> {
+        if pred(&self.value) {
+            return Some(Rc::clone(self));
+        }
+        self.children
+            .borrow()
+            .iter()
+            .find_map(|child| child.find_by(pred))
+    }
+
+    /// Every node, in pre-order, whose value satisfies `pred`.
+    pub fn filter_collect(self: &Rc<Self>, pred: impl Fn(&T) -> bool) -> Vec<Rc<TreeNode<T>>> {
+        let mut found = vec![];
+        self.filter_into(&pred, &mut found);
+        found
+    }
+
+    fn filter_into(self: &Rc<Self>, pred: &impl Fn(&T) -> bool, found: &mut Vec<Rc<TreeNode<T>>>) {
+        if pred(&self.value) {
+            found.push(Rc::clone(self));
+        }
+        for child in &*self.children.borrow() {
+            child.filter_into(pred, found);
+        }
+    }
+
+    /// Draws the subtree rooted at `self`, one node per line:
+    /// ```text
+    /// root
+    /// ├── branch
+    /// │   └── leaf
+    /// └── sibling
+    /// ```
+    /// Only the strong `children` links are followed, so the drawing is exactly what the node
+    /// owns; the `Weak` parent links point back up and would be drawn twice.
+    pub fn render(&self) -> String
+    where
+        T: Display,
+    {
+        let mut out = format!("{}\n", self.value);
+        self.render_children("", &mut out);
+        out
+    }
+
+    fn render_children(&self, prefix: &str, out: &mut String)
+    where
+        T: Display,
+    {
+        let children = self.children.borrow();
+        for (i, child) in children.iter().enumerate() {
+            let is_last = i + 1 == children.len();
+            let (branch, indent) = if is_last {
+                ("└── ", "    ")
+            } else {
+                ("├── ", "│   ")
+            };
+            out.push_str(&format!("{prefix}{branch}{}\n", child.value));
+            child.render_children(&format!("{prefix}{indent}"), out);
+        }
+    }
+
+    /// The differences that turn the subtree at `self` into the one at `other`. Children are
+    /// matched by position, and each difference is reported with the path of child indices
+    /// leading to it from `self`, so `[1, 0]` is the first child of the second child.
+    /// An added or removed node is reported once, without listing its subtree.
+    pub fn diff(&self, other: &TreeNode<T>) -> Vec<TreeDiff<T>>
+    where
+        T: PartialEq + Clone,
+    {
+        let mut diffs = vec![];
+        self.diff_into(other, &mut vec![], &mut diffs);
+        diffs
+    }
+
+    fn diff_into(&self, other: &TreeNode<T>, path: &mut Vec<usize>, diffs: &mut Vec<TreeDiff<T>>)
+    where
+        T: PartialEq + Clone,
+    {
+        if self.value != other.value {
+            diffs.push(TreeDiff::Changed {
+                path: path.clone(),
+                from: self.value.clone(),
+                to: other.value.clone(),
+            });
+        }
+
+        let (ours, theirs) = (self.children.borrow(), other.children.borrow());
+        for i in 0..ours.len().max(theirs.len()) {
+            path.push(i);
+            match (ours.get(i), theirs.get(i)) {
+                (Some(a), Some(b)) => a.diff_into(b, path, diffs),
+                (Some(a), None) => diffs.push(TreeDiff::Removed {
+                    path: path.clone(),
+                    value: a.value.clone(),
+                }),
+                (None, Some(b)) => diffs.push(TreeDiff::Added {
+                    path: path.clone(),
+                    value: b.value.clone(),
+                }),
+                (None, None) => unreachable!("`i` is below the longer of the two lengths"),
+            }
+            path.pop();
+        }
+    }
+
+    /// Reads a tree written by `Serialize` back, from any format serde reads, with every child’s
+    /// `parent` pointing at the node that owns it again. The JSON holds no `parent` links, so a
+    /// derived `Deserialize` would hand back a tree without them; and `Deserialize` returns a
+    /// `Self`, where relinking needs the `Rc` each child’s `Weak` points into, so this is a
+    /// function of its own, with `Deserialize::deserialize`’s arguments, returning an `Rc<Self>`.
+    #[cfg(feature = "serde")]
+    pub fn deserialize<'de, D>(deserializer: D) -> std::result::Result<Rc<Self>, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+        T: serde::Deserialize<'de>,
+    {
+        use serde::Deserialize;
+        Ok(TreeShape::deserialize(deserializer)?.build())
+    }
+
+    /// `deserialize`, from a string of JSON, which must hold the tree and nothing after it.
+    #[cfg(feature = "serde")]
+    pub fn from_json(json: &str) -> serde_json::Result<Rc<Self>>
+    where
+        T: serde::de::DeserializeOwned,
+    {
+        let mut deserializer = serde_json::Deserializer::from_str(json);
+        let tree = TreeNode::deserialize(&mut deserializer)?;
+        deserializer.end()?;
+        Ok(tree)
+    }
+
+    /// Walks up from `node` through the `Weak` parent links, looking for `ancestor`.
+    fn is_ancestor_or_self(ancestor: &Rc<Self>, node: &Rc<Self>) -> bool {
+        let mut current = Some(Rc::clone(node));
+        while let Some(n) = current {
+            if Rc::ptr_eq(&n, ancestor) {
+                return true;
+            }
+            current = n.parent();
+        }
+        false
+    }
+}
+#[allow(clippy::identity_op)] // `0 + i` makes the dereference visible
+fn creating_a_tree_data_structure_a_node_with_child_nodes() -> Result<()> {
+    Ok({
+        span!("creating_a_tree_data_structure_a_node_with_child_nodes");
+        header!("Creating a Tree Data Structure: a Node with Child Nodes");
+
+        let leaf = Rc::new(TreeNode {
+            value: 3,
+            children: DebugRefCell::when_verbose(vec![]),
+            parent: DebugRefCell::when_verbose(Weak::new()),
+        });
+
+        println!("leaf parent = {:?}", leaf.parent.borrow().upgrade());
+
+        let branch = Rc::new(TreeNode {
+            value: 5,
+            children: DebugRefCell::when_verbose(vec![Rc::clone(&leaf)]),
+            parent: DebugRefCell::when_verbose(Weak::new()),
+        });
+
+        branch.for_each(&|i| print!(" {{{}}} ", 0 + i));
+        println!();
+
+        *leaf.parent.borrow_mut() = Rc::downgrade(&branch);
+
+        let parent = leaf.parent.borrow().upgrade();
+        if let Some(parent) = parent {
+            print!("leaf parent =\n{}", parent.render());
+        }
+        // leaf parent =
+        // 5
+        // └── 3
+    })
+}
+fn visualizing_changes_to_strong_count_and_weak_count() -> Result<()> {
+    Ok({
+        span!("visualizing_changes_to_strong_count_and_weak_count");
+        header!("Visualizing Changes to strong_count and weak_count");
+
+        let (mut leaf_tracer, mut branch_tracer) =
+            (CountTracer::new("leaf"), CountTracer::new("branch"));
+        let leaf = Rc::new(TreeNode {
+            value: 3,
+            parent: DebugRefCell::when_verbose(Weak::new()),
+            children: DebugRefCell::when_verbose(vec![]),
+        });
+
+        leaf_tracer.snapshot("created", &leaf);
+
+        {
+            let branch = Rc::new(TreeNode {
+                value: 5,
+                parent: DebugRefCell::when_verbose(Weak::new()),
+                children: DebugRefCell::when_verbose(vec![Rc::clone(&leaf)]),
+            });
+
+            *leaf.parent.borrow_mut() = Rc::downgrade(&branch);
+
+            branch_tracer.snapshot("created with leaf", &branch);
+            viz::show(
+                Graph::new("`branch` owns `leaf`, which only points back")
+                    .var("leaf", &leaf)
+                    .var("branch", &branch),
+            );
+
+            leaf_tracer.snapshot("in branch", &leaf);
+        }
+
+        println!("leaf parent = {:?}", leaf.parent.borrow().upgrade());
+        leaf_tracer.snapshot("branch dropped", &leaf);
+        print!("{}{}", leaf_tracer.table(), branch_tracer.table());
+        // `leaf`          strong  weak
+        // created              1     0
+        // in branch            2     0
+        // branch dropped       1     0
+        // `branch`           strong  weak
+        // created with leaf       1     1
+    })
+}
+
+/// # Keeping Parent Links Consistent
+/// The demos above wire `children` and `parent` by hand, and it’s easy to update one side and
+/// forget the other. `add_child`, `remove_child`, and `reparent` always update both, so the
+/// counts stay predictable: every node has one strong count from its parent (plus any local
+/// variables) and one weak count per child pointing back at it.
+fn keeping_parent_links_consistent() -> Result<()> {
+    Ok({
+        span!("keeping_parent_links_consistent");
+        header!("Keeping Parent Links Consistent");
+
+        let root = TreeNode::new("root");
+        let left = TreeNode::new("left");
+        let right = TreeNode::new("right");
+        let leaf = TreeNode::new("leaf");
+
+        TreeNode::add_child(&root, Rc::clone(&left));
+        TreeNode::add_child(&root, Rc::clone(&right));
+        TreeNode::add_child(&left, Rc::clone(&leaf));
+        print!("{}", root.render());
+        viz::show(Graph::new("`leaf` under `left`").var("root", &root));
+
+        let print_counts = || {
+            for node in [&root, &left, &right, &leaf] {
+                println!(
+                    "{:>5}: parent = {:<6} strong = {}, weak = {}",
+                    node.value(),
+                    node.parent().map_or("-", |p| *p.value()),
+                    Rc::strong_count(node),
+                    Rc::weak_count(node),
+                );
+            }
+        };
+        print_counts();
+        //  root: parent = -      strong = 1, weak = 2
+        //  left: parent = root   strong = 2, weak = 1
+        // right: parent = root   strong = 2, weak = 0
+        //  leaf: parent = left   strong = 2, weak = 0
+
+        println!("reparent leaf under right");
+        TreeNode::reparent(&leaf, &right);
+        print!("{}", root.render());
+        viz::show(Graph::new("`leaf` under `right`").var("root", &root));
+        print_counts();
+        //  left: parent = root   strong = 2, weak = 0
+        // right: parent = root   strong = 2, weak = 1
+        //  leaf: parent = right  strong = 2, weak = 0
+
+        println!("remove right from root");
+        let removed = TreeNode::remove_child(&root, &right);
+        drop(removed);
+        print!("{}", root.render());
+        viz::show(
+            Graph::new("`right` removed from `root`")
+                .var("root", &root)
+                .var("right", &right),
+        );
+        print_counts();
+        //  root: parent = -      strong = 1, weak = 1
+        // right: parent = -      strong = 1, weak = 1
+    })
+}
+
+/// # Traversal Orders
+/// The same tree can be walked in different orders, and each suits a different job:
+/// - _Pre-order_ visits a node before its children: copying a tree, or printing it as an outline.
+/// - _Post-order_ visits a node after its children: freeing a tree, or computing the size of a
+///   directory from the sizes of its contents.
+/// - _Level-order_ (breadth-first) visits the tree one depth at a time: finding the node
+///   closest to the root that matches some condition.
+///
+/// `traverse` takes an `FnMut`, unlike `for_each`’s `Fn`, so the closure can collect into a
+/// `Vec` it captured by mutable reference.
+#[allow(clippy::enum_variant_names)] // the textbook names read better than `Pre`, `Post`, `Level`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TraversalOrder {
+    PreOrder,
+    PostOrder,
+    LevelOrder,
+}
+fn traversal_orders() -> Result<()> {
+    Ok({
+        span!("traversal_orders");
+        header!("Traversal Orders");
+
+        //        1
+        //      /   \
+        //     2     3
+        //    / \     \
+        //   4   5     6
+        let root = TreeNode::new(1);
+        let two = TreeNode::new(2);
+        let three = TreeNode::new(3);
+        TreeNode::add_child(&root, Rc::clone(&two));
+        TreeNode::add_child(&root, Rc::clone(&three));
+        TreeNode::add_child(&two, TreeNode::new(4));
+        TreeNode::add_child(&two, TreeNode::new(5));
+        TreeNode::add_child(&three, TreeNode::new(6));
+        print!("{}", root.render());
+
+        for order in [
+            TraversalOrder::PreOrder,
+            TraversalOrder::PostOrder,
+            TraversalOrder::LevelOrder,
+        ] {
+            let mut visited = vec![];
+            root.traverse(order, |i| visited.push(*i));
+            println!("{:<10} = {:?}", format!("{order:?}"), visited);
+        }
+        // PreOrder   = [1, 2, 4, 5, 3, 6]
+        // PostOrder  = [4, 5, 2, 6, 3, 1]
+        // LevelOrder = [1, 2, 3, 4, 5, 6]
+    })
+}
+
+/// # Tree Metrics
+/// `depth` and `path_to_root` look _up_ the tree, so each step is a `Weak::upgrade`: the
+/// parent may be gone, and the loop has to handle `None`. `height` and `size` look _down_, and
+/// follow the strong `children` links recursively.
+///
+/// Every `upgrade` in the loop briefly holds a strong reference to the parent, so the parent
+/// can’t be dropped halfway through the walk.
+fn tree_metrics() -> Result<()> {
+    Ok({
+        span!("tree_metrics");
+        header!("Tree Metrics");
+
+        let root = TreeNode::new("root");
+        let branch = TreeNode::new("branch");
+        let leaf = TreeNode::new("leaf");
+        TreeNode::add_child(&root, Rc::clone(&branch));
+        TreeNode::add_child(&root, TreeNode::new("sibling"));
+        TreeNode::add_child(&branch, Rc::clone(&leaf));
+        print!("{}", root.render());
+
+        println!("root: height = {}, size = {}", root.height(), root.size()); // root: height = 2, size = 4
+        println!(
+            "leaf: depth = {}, path_to_root = {:?}",
+            leaf.depth(),
+            leaf.path_to_root()
+        ); // leaf: depth = 2, path_to_root = ["leaf", "branch", "root"]
+
+        // dropping the root drops `sibling`, but `branch` and `leaf` are kept alive by our locals;
+        // `branch`’s `Weak` link to its parent no longer upgrades
+        drop(root);
+        println!(
+            "after dropping root, leaf: depth = {}, path_to_root = {:?}",
+            leaf.depth(),
+            leaf.path_to_root()
+        ); // after dropping root, leaf: depth = 1, path_to_root = ["leaf", "branch"]
+    })
+}
+
+/// # Searching a Tree
+/// `find` and `filter_collect` hand back `Rc` clones rather than references. A `&TreeNode`
+/// borrowed from inside a `children.borrow()` would keep that `Ref` alive, and with it the
+/// `RefCell`’s shared borrow: any `add_child` on that node’s parent while we held it would panic
+/// with `already borrowed`. An `Rc` clone owns its node, so the borrows taken during the search
+/// are all released by the time it returns, and the result can be used to change the tree.
+fn searching_a_tree() -> Result<()> {
+    Ok({
+        span!("searching_a_tree");
+        header!("Searching a Tree");
+
+        let root = TreeNode::new(1);
+        let two = TreeNode::new(2);
+        TreeNode::add_child(&root, Rc::clone(&two));
+        TreeNode::add_child(&root, TreeNode::new(3));
+        TreeNode::add_child(&two, TreeNode::new(4));
+        TreeNode::add_child(&two, TreeNode::new(5));
+
+        let first_odd_child = two.find(|i| i % 2 == 1);
+        println!(
+            "first odd value under 2 = {:?}",
+            first_odd_child.as_ref().map(|node| node.value())
+        ); // first odd value under 2 = Some(5)
+
+        let evens: Vec<i32> = root
+            .filter_collect(|i| i % 2 == 0)
+            .iter()
+            .map(|node| *node.value())
+            .collect();
+        println!("even values = {:?}", evens); // even values = [2, 4]
+
+        // the search is over and every `children.borrow()` it took is gone, so we can grow the
+        // tree under the node we found
+        if let Some(node) = root.find(|i| *i == 3) {
+            TreeNode::add_child(&node, TreeNode::new(6));
+        }
+        print!("{}", root.render());
+    })
+}
+
+/// # Round-Tripping a Tree Through JSON
+/// With the `serde` feature, `TreeNode<T>` derives `Serialize`. The `children`
+/// serialize recursively (serde’s `rc` feature lets it look through `Rc` and `RefCell`), but the
+/// `parent` field is marked `#[serde(skip)]`, for two reasons:
+/// - Serializing a `Weak` upgrades it and writes out the value behind it. The parent would write
+///   its children, each child its parent again, and so on until the stack overflows.
+/// - A `Weak` is an address, and addresses mean nothing outside the process that made them.
+///
+/// So a derived `Deserialize` would bring the tree back with every `parent` empty. Instead,
+/// `TreeNode::deserialize` reads the values and the `children` into a shape of its own, and
+/// builds the tree again with `add_child`, which links each child to its parent as it goes;
+/// `TreeNode::from_json` is that, from a string of JSON.
+#[cfg(feature = "serde")]
+fn round_tripping_a_tree_through_json() -> Result<()> {
+    Ok({
+        span!("round_tripping_a_tree_through_json");
+        header!("Round-Tripping a Tree Through JSON");
+
+        let root = TreeNode::new(1);
+        let two = TreeNode::new(2);
+        TreeNode::add_child(&root, Rc::clone(&two));
+        TreeNode::add_child(&root, TreeNode::new(3));
+        TreeNode::add_child(&two, TreeNode::new(4));
+
+        let json = serde_json::to_string(&*root)?;
+        println!("{json}");
+        // {"value":1,"children":[{"value":2,"children":[{"value":4,"children":[]}]},{"value":3,"children":[]}]}
+
+        let copy = TreeNode::<i32>::from_json(&json)?;
+        let four = copy.find(|i| *i == 4).unwrap();
+        println!("{:?}", four.path_to_root()); // [4, 2, 1]
+        print!("{}", copy.render());
+    })
+}
+
+/// # Comparing Trees
+/// Two trees are equal when their values and their child structure are equal, so `PartialEq` is
+/// written by hand instead of derived: a derived impl would also compare the `parent` fields,
+/// and `Weak` has no `PartialEq`. Comparing parents wouldn’t make sense anyway, two equal
+/// subtrees may well hang from different places. Comparing `children` compares two
+/// `Vec<Rc<TreeNode<T>>>`s, and `Rc<T>: PartialEq` compares what the `Rc`s point at, so the
+/// recursion happens by itself.
+impl<T: PartialEq> PartialEq for TreeNode<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.value == other.value && *self.children.borrow() == *other.children.borrow()
+    }
+}
+/// One difference found by `TreeNode::diff`, located by its path of child indices.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TreeDiff<T> {
+    Added { path: Vec<usize>, value: T },
+    Removed { path: Vec<usize>, value: T },
+    Changed { path: Vec<usize>, from: T, to: T },
+}
+fn comparing_trees() -> Result<()> {
+    Ok({
+        span!("comparing_trees");
+        header!("Comparing Trees");
+
+        let build = |values: [i32; 4]| {
+            let root = TreeNode::new(values[0]);
+            let child = TreeNode::new(values[1]);
+            TreeNode::add_child(&root, Rc::clone(&child));
+            TreeNode::add_child(&root, TreeNode::new(values[2]));
+            TreeNode::add_child(&child, TreeNode::new(values[3]));
+            root
+        };
+        let expected = build([1, 2, 3, 4]);
+        let same = build([1, 2, 3, 4]);
+        let edited = build([1, 2, 30, 4]);
+
+        println!("expected == same: {}", expected == same); // expected == same: true
+        println!("expected == edited: {}", expected == edited); // expected == edited: false
+
+        TreeNode::add_child(&edited, TreeNode::new(5));
+        if let Some(node) = edited.find(|i| *i == 2) {
+            let four = Rc::clone(&node.children.borrow()[0]);
+            TreeNode::remove_child(&node, &four);
+        }
+        print!("{}", edited.render());
+        for diff in expected.diff(&edited) {
+            println!("{diff:?}");
+        }
+        // Removed { path: [0, 0], value: 4 }
+        // Changed { path: [1], from: 3, to: 30 }
+        // Added { path: [2], value: 5 }
+    })
+}
+
+#[cfg(test)]
+mod tree_node_tests {
+    use super::*;
+    #[cfg(debug_assertions)] // `cargo bench` builds the tests in release, without the origins
+    use {
+        ir_core::output::{self, Output, Verbosity},
+        std::panic::{self, AssertUnwindSafe},
+    };
+
+    #[test]
+    fn add_child_links_both_directions() {
+        let parent = TreeNode::new(1);
+        let child = TreeNode::new(2);
+
+        TreeNode::add_child(&parent, Rc::clone(&child));
+
+        assert!(Rc::ptr_eq(&child.parent().unwrap(), &parent));
+        assert_eq!(parent.children.borrow().len(), 1);
+        assert_eq!(Rc::strong_count(&child), 2);
+        assert_eq!(Rc::weak_count(&parent), 1);
+    }
+
+    #[test]
+    fn remove_child_unlinks_both_directions() {
+        let parent = TreeNode::new(1);
+        let child = TreeNode::new(2);
+        TreeNode::add_child(&parent, Rc::clone(&child));
+
+        let removed = TreeNode::remove_child(&parent, &child).unwrap();
+        drop(removed);
+
+        assert!(child.parent().is_none());
+        assert!(parent.children.borrow().is_empty());
+        assert_eq!(Rc::strong_count(&child), 1);
+        assert_eq!(Rc::weak_count(&parent), 0);
+        assert!(TreeNode::remove_child(&parent, &child).is_none());
+    }
+
+    #[test]
+    fn reparent_moves_the_subtree() {
+        let old_parent = TreeNode::new(1);
+        let new_parent = TreeNode::new(2);
+        let child = TreeNode::new(3);
+        let grandchild = TreeNode::new(4);
+        TreeNode::add_child(&old_parent, Rc::clone(&child));
+        TreeNode::add_child(&child, Rc::clone(&grandchild));
+
+        TreeNode::reparent(&child, &new_parent);
+
+        assert!(old_parent.children.borrow().is_empty());
+        assert_eq!(Rc::weak_count(&old_parent), 0);
+        assert_eq!(Rc::weak_count(&new_parent), 1);
+        assert_eq!(Rc::strong_count(&child), 2);
+        assert!(Rc::ptr_eq(&grandchild.parent().unwrap(), &child));
+    }
+
+    #[test]
+    fn dropping_the_root_drops_the_whole_tree() {
+        let child = TreeNode::new(2);
+        {
+            let root = TreeNode::new(1);
+            TreeNode::add_child(&root, Rc::clone(&child));
+        }
+        assert!(child.parent().is_none());
+        assert_eq!(Rc::strong_count(&child), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "a node can't become a child of its own subtree")]
+    fn reparent_refuses_to_create_a_cycle() {
+        let root = TreeNode::new(1);
+        let child = TreeNode::new(2);
+        TreeNode::add_child(&root, Rc::clone(&child));
+
+        TreeNode::reparent(&root, &child);
+    }
+
+    #[cfg(debug_assertions)] // `cargo bench` builds the tests in release, without the origins
+    #[test]
+    fn with_verbose_a_borrow_conflict_in_a_tree_names_the_borrow_in_the_way() {
+        let mut verbose = Output {
+            verbosity: Verbosity::Verbose,
+            ..Output::silent()
+        };
+        let root = output::redirect(&mut verbose, || TreeNode::new(1));
+
+        let children = root.children();
+        let children_line = line!() - 1;
+        let panic = panic::catch_unwind(AssertUnwindSafe(|| {
+            TreeNode::add_child(&root, TreeNode::new(2));
+        }))
+        .unwrap_err();
+        drop(children);
+        let message = panic.downcast_ref::<String>().unwrap();
+
+        assert!(message.starts_with("already borrowed: borrow_mut at"));
+        assert!(message.contains(&format!(
+            "outstanding borrow at {}:{children_line}:",
+            file!()
+        )));
+    }
+
+    fn sample_tree() -> Rc<TreeNode<i32>> {
+        let root = TreeNode::new(1);
+        let two = TreeNode::new(2);
+        let three = TreeNode::new(3);
+        TreeNode::add_child(&root, Rc::clone(&two));
+        TreeNode::add_child(&root, Rc::clone(&three));
+        TreeNode::add_child(&two, TreeNode::new(4));
+        TreeNode::add_child(&two, TreeNode::new(5));
+        TreeNode::add_child(&three, TreeNode::new(6));
+        root
+    }
+
+    fn collect(root: &TreeNode<i32>, order: TraversalOrder) -> Vec<i32> {
+        let mut visited = vec![];
+        root.traverse(order, |i| visited.push(*i));
+        visited
+    }
+
+    #[test]
+    fn it_traverses_in_each_order() {
+        let root = sample_tree();
+
+        assert_eq!(
+            collect(&root, TraversalOrder::PreOrder),
+            vec![1, 2, 4, 5, 3, 6]
+        );
+        assert_eq!(
+            collect(&root, TraversalOrder::PostOrder),
+            vec![4, 5, 2, 6, 3, 1]
+        );
+        assert_eq!(
+            collect(&root, TraversalOrder::LevelOrder),
+            vec![1, 2, 3, 4, 5, 6]
+        );
+    }
+
+    #[test]
+    fn it_traverses_a_single_node() {
+        let leaf = TreeNode::new(7);
+        for order in [
+            TraversalOrder::PreOrder,
+            TraversalOrder::PostOrder,
+            TraversalOrder::LevelOrder,
+        ] {
+            assert_eq!(collect(&leaf, order), vec![7]);
+        }
+    }
+
+    #[test]
+    fn it_measures_depth_height_and_size() {
+        let root = sample_tree();
+        let two = Rc::clone(&root.children.borrow()[0]);
+        let four = Rc::clone(&two.children.borrow()[0]);
+
+        assert_eq!(root.depth(), 0);
+        assert_eq!(two.depth(), 1);
+        assert_eq!(four.depth(), 2);
+
+        assert_eq!(root.height(), 2);
+        assert_eq!(two.height(), 1);
+        assert_eq!(four.height(), 0);
+
+        assert_eq!(root.size(), 6);
+        assert_eq!(two.size(), 3);
+        assert_eq!(four.size(), 1);
+    }
+
+    #[test]
+    fn it_collects_the_path_to_the_root() {
+        let root = sample_tree();
+        let three = Rc::clone(&root.children.borrow()[1]);
+        let six = Rc::clone(&three.children.borrow()[0]);
+
+        assert_eq!(six.path_to_root(), vec![6, 3, 1]);
+        assert_eq!(root.path_to_root(), vec![1]);
+    }
+
+    #[test]
+    fn it_stops_at_a_detached_parent() {
+        let root = sample_tree();
+        let two = Rc::clone(&root.children.borrow()[0]);
+        let four = Rc::clone(&two.children.borrow()[0]);
+
+        drop(root);
+
+        assert!(two.parent().is_none());
+        assert_eq!(four.depth(), 1);
+        assert_eq!(four.path_to_root(), vec![4, 2]);
+    }
+
+    #[test]
+    fn it_renders_branches_and_indentation() {
+        let root = sample_tree();
+        assert_eq!(
+            root.render(),
+            "1\n\
+             ├── 2\n\
+             │   ├── 4\n\
+             │   └── 5\n\
+             └── 3\n\
+             \x20   └── 6\n"
+        );
+        assert_eq!(TreeNode::new("leaf").render(), "leaf\n");
+    }
+
+    #[test]
+    fn it_finds_the_first_match_in_pre_order() {
+        let root = sample_tree();
+
+        let found = root.find(|i| *i > 3).unwrap();
+        assert_eq!(*found.value(), 4);
+        assert_eq!(Rc::strong_count(&found), 2);
+
+        assert!(Rc::ptr_eq(&root.find(|i| *i == 1).unwrap(), &root));
+        assert!(root.find(|i| *i > 6).is_none());
+    }
+
+    #[test]
+    fn it_collects_every_match() {
+        let root = sample_tree();
+
+        let odd: Vec<i32> = root
+            .filter_collect(|i| i % 2 == 1)
+            .iter()
+            .map(|node| *node.value())
+            .collect();
+        assert_eq!(odd, vec![1, 5, 3]);
+        assert!(root.filter_collect(|_| false).is_empty());
+    }
+
+    #[test]
+    fn it_releases_every_borrow_before_returning() {
+        let root = sample_tree();
+
+        let two = root.find(|i| *i == 2).unwrap();
+        TreeNode::add_child(&two, TreeNode::new(7));
+
+        assert_eq!(root.size(), 7);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn it_round_trips_through_json_and_relinks_parents() {
+        let root = sample_tree();
+        let json = serde_json::to_string(&*root).unwrap();
+        assert!(!json.contains("parent"));
+
+        let copy = TreeNode::<i32>::from_json(&json).unwrap();
+
+        assert_eq!(copy.render(), root.render());
+        let six = copy.find(|i| *i == 6).unwrap();
+        assert_eq!(six.path_to_root(), vec![6, 3, 1]);
+        assert_eq!(Rc::weak_count(&copy), 2);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn every_child_read_from_json_upgrades_to_its_parent() {
+        let json = serde_json::to_string(&*sample_tree()).unwrap();
+        let copy = TreeNode::<i32>::from_json(&json).unwrap();
+
+        assert!(copy.parent_link().upgrade().is_none());
+        let three = copy.find(|i| *i == 3).unwrap();
+        let parent = three.parent_link().upgrade().unwrap();
+        assert!(Rc::ptr_eq(&parent, &copy));
+        for child in three.children().iter() {
+            assert!(Rc::ptr_eq(&child.parent_link().upgrade().unwrap(), &three));
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn it_deserializes_from_any_deserializer() {
+        let value = serde_json::to_value(&*sample_tree()).unwrap();
+        let copy = TreeNode::<i32>::deserialize(value).unwrap();
+
+        assert_eq!(copy.render(), sample_tree().render());
+        let six = copy.find(|i| *i == 6).unwrap();
+        assert_eq!(six.path_to_root(), vec![6, 3, 1]);
+        assert!(TreeNode::<i32>::from_json(r#"{"value":1,"children":[]} 2"#).is_err());
+    }
+
+    #[test]
+    fn equal_trees_ignore_where_they_hang() {
+        let root = sample_tree();
+        let copy = sample_tree();
+        assert!(*root == *copy);
+
+        let parent = TreeNode::new(0);
+        TreeNode::add_child(&parent, Rc::clone(&copy));
+        assert!(*root == *copy);
+
+        TreeNode::add_child(&copy, TreeNode::new(7));
+        assert!(*root != *copy);
+    }
+
+    #[test]
+    fn it_diffs_equal_trees_to_nothing() {
+        assert!(sample_tree().diff(&sample_tree()).is_empty());
+    }
+
+    #[test]
+    fn it_reports_added_removed_and_changed_paths() {
+        let before = sample_tree();
+        let after = sample_tree();
+
+        let two = after.find(|i| *i == 2).unwrap();
+        let five = after.find(|i| *i == 5).unwrap();
+        TreeNode::remove_child(&two, &five);
+        TreeNode::add_child(&after.find(|i| *i == 6).unwrap(), TreeNode::new(8));
+        let root_value_change = TreeNode::new(9);
+        TreeNode::add_child(&root_value_change, TreeNode::new(10));
+
+        assert_eq!(
+            before.diff(&after),
+            vec![
+                TreeDiff::Removed {
+                    path: vec![0, 1],
+                    value: 5
+                },
+                TreeDiff::Added {
+                    path: vec![1, 0, 0],
+                    value: 8
+                },
+            ]
+        );
+        assert_eq!(
+            TreeNode::new(1).diff(&root_value_change),
+            vec![
+                TreeDiff::Changed {
+                    path: vec![],
+                    from: 1,
+                    to: 9
+                },
+                TreeDiff::Added {
+                    path: vec![0],
+                    value: 10
+                },
+            ]
+        );
+    }
+}
+
+/// Whatever sequence of `add_child`, `remove_child` and `reparent` is applied to a forest of
+/// nodes, each node should be listed under at most one parent, and exactly under the one its
+/// `Weak` link points to. A simple model, each node’s parent and each parent’s children in
+/// order, is checked against the nodes after every operation.
+#[cfg(test)]
+mod tree_node_properties {
+    use super::*;
+    use proptest::prelude::*;
+
+    const NODES: usize = 8;
+
+    #[derive(Debug, Clone)]
+    enum Op {
+        AddChild(usize, usize),
+        RemoveChild(usize, usize),
+        Reparent(usize, usize),
+    }
+
+    fn op() -> impl Strategy<Value = Op> {
+        let pair = (0..NODES, 0..NODES);
+        prop_oneof![
+            pair.clone().prop_map(|(p, c)| Op::AddChild(p, c)),
+            pair.clone().prop_map(|(p, c)| Op::RemoveChild(p, c)),
+            pair.prop_map(|(c, p)| Op::Reparent(c, p)),
+        ]
+    }
+
+    struct Model {
+        parents: [Option<usize>; NODES],
+        children: [Vec<usize>; NODES],
+    }
+    impl Model {
+        fn is_ancestor_or_self(&self, ancestor: usize, node: usize) -> bool {
+            let mut current = Some(node);
+            while let Some(n) = current {
+                if n == ancestor {
+                    return true;
+                }
+                current = self.parents[n];
+            }
+            false
+        }
+
+        fn add_child(&mut self, parent: usize, child: usize) {
+            if let Some(old) = self.parents[child].take() {
+                self.children[old].retain(|c| *c != child);
+            }
+            self.parents[child] = Some(parent);
+            self.children[parent].push(child);
+        }
+
+        fn remove_child(&mut self, parent: usize, child: usize) -> bool {
+            let Some(index) = self.children[parent].iter().position(|c| *c == child) else {
+                return false;
+            };
+            self.children[parent].remove(index);
+            self.parents[child] = None;
+            true
+        }
+    }
+
+    proptest! {
+        #![proptest_config(ir_core::utils::miri::proptest_config())]
+
+        #[test]
+        fn parent_and_child_links_stay_consistent(ops in prop::collection::vec(op(), 0..48)) {
+            let nodes: Vec<_> = (0..NODES).map(TreeNode::new).collect();
+            let mut model = Model {
+                parents: [None; NODES],
+                children: Default::default(),
+            };
+            for op in ops {
+                match op {
+                    // the operations that would panic on a cycle are covered by their own tests
+                    Op::AddChild(p, c) | Op::Reparent(c, p) if model.is_ancestor_or_self(c, p) => {}
+                    Op::AddChild(p, c) => {
+                        TreeNode::add_child(&nodes[p], Rc::clone(&nodes[c]));
+                        model.add_child(p, c);
+                    }
+                    Op::Reparent(c, p) => {
+                        TreeNode::reparent(&nodes[c], &nodes[p]);
+                        model.add_child(p, c);
+                    }
+                    Op::RemoveChild(p, c) => {
+                        let removed = TreeNode::remove_child(&nodes[p], &nodes[c]);
+                        prop_assert_eq!(removed.is_some(), model.remove_child(p, c));
+                    }
+                }
+                for (i, node) in nodes.iter().enumerate() {
+                    let parent = node.parent().map(|p| *p.value());
+                    prop_assert_eq!(parent, model.parents[i]);
+                    let children: Vec<_> = node.children().iter().map(|c| *c.value()).collect();
+                    prop_assert_eq!(&children, &model.children[i]);
+                    for child in node.children().iter() {
+                        prop_assert!(Rc::ptr_eq(&child.parent().unwrap(), node));
+                    }
+                    // one strong count for `nodes`, and one for the parent listing it, if any
+                    prop_assert_eq!(Rc::strong_count(node), 1 + usize::from(parent.is_some()));
+                }
+            }
+        }
+    }
+}
@@ -4,17 +4,22 @@
 //! that a smart pointer can be treated like a regular reference, you can write code that operates on
 //! references and use that code with smart pointers too.
 
-use std::{io::Result, ops::Deref};
+use ir_core::metrics::{self, Counter};
+use std::{
+    any::type_name,
+    io::Result,
+    ops::{Deref, DerefMut},
+};
 
-struct MyBox<T>(T);
+pub struct MyBox<T>(T);
 impl<T> MyBox<T> {
-    fn new(x: T) -> MyBox<T> {
+    pub fn new(x: T) -> MyBox<T> {
         MyBox(x)
     }
 }
 impl<T> Deref for MyBox<T> {
     /// associated type in `impl` without body
-    /// ```
+    /// ```compile_fail
     /// // --snip--
     ///     type Target;
     /// // --snip--
@@ -26,6 +31,29 @@ impl<T> Deref for MyBox<T> {
         &self.0
     }
 }
+/// `DerefMut` has no `Target` of its own: it reuses `Deref`’s, which is why `Deref` is a
+/// supertrait of it and has to be implemented first.
+impl<T> DerefMut for MyBox<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.0
+    }
+}
+/// `AsRef` is the explicit cousin of `Deref`: a function that takes `impl AsRef<T>` accepts
+/// anything that can lend out a `&T`, and asks for it with `.as_ref()` instead of relying on
+/// coercion.
+impl<T> AsRef<T> for MyBox<T> {
+    fn as_ref(&self) -> &T {
+        &self.0
+    }
+}
+/// A real `Box<T>` frees its heap allocation here. `MyBox<T>` never allocated anything, so it
+/// only announces itself; the `T` inside is dropped right after, as a field of `MyBox`.
+impl<T> Drop for MyBox<T> {
+    fn drop(&mut self) {
+        metrics::count(Counter::Drop);
+        println!("Dropping MyBox<{}>", type_name::<T>());
+    }
+}
 
 pub fn deref_trait() -> Result<()> {
     Ok({
@@ -42,7 +70,11 @@ pub fn deref_trait() -> Result<()> {
 
         deref_coercion(&y); //        with implicit Deref coercion
         deref_coercion(&(*y)[..]); // without Deref coercion implemented by Rust
+
+        deref_coercion_with_mutable_references()?;
+        as_ref_instead_of_coercion()?;
     })
+    // Dropping MyBox<alloc::string::String>
 }
 
 /// # Implicit `Deref Coercions` with Functions and Methods
@@ -90,3 +122,46 @@ fn deref_coercion(name: &str) {
 // is the only immutable reference to that data, but the borrowing rules don’t guarantee that.
 // Therefore, Rust can’t make the assumption that converting an immutable reference to a mutable
 // reference is possible.
+
+fn shout(name: &mut str) {
+    name.make_ascii_uppercase();
+}
+fn deref_coercion_with_mutable_references() -> Result<()> {
+    Ok({
+        let mut y = MyBox::new(String::from("Rust"));
+
+        // case 2: `&mut MyBox<String>` -> `&mut String` -> `&mut str`, through `DerefMut` twice
+        shout(&mut y);
+        assert_eq!("RUST", *y);
+
+        // `*y = ..` also goes through `DerefMut`: it assigns to the `String` inside the box
+        *y = String::from("Rustacean");
+
+        // case 3: `&mut MyBox<String>` -> `&str`, a mutable reference used where only reading
+        // is needed gives up its exclusivity for the duration of the call
+        let exclusive: &mut MyBox<String> = &mut y;
+        deref_coercion(exclusive);
+
+        // the reverse, `&MyBox<String>` -> `&mut str`, is never done:
+        // shout(&y); // types differ in mutability
+    })
+    // Dropping MyBox<alloc::string::String>
+}
+
+fn length<S: AsRef<str>>(s: S) -> usize {
+    s.as_ref().len()
+}
+fn as_ref_instead_of_coercion() -> Result<()> {
+    Ok({
+        let name = MyBox::new(String::from("Rust"));
+
+        // deref coercion only happens when the expected type is known; in a generic call the
+        // compiler has to find `S` first, and `MyBox<String>` itself doesn’t implement `AsRef<str>`:
+        // length(&name); // the trait bound `MyBox<String>: AsRef<str>` is not satisfied
+
+        // `as_ref` asks for the `&String` explicitly, and `&String` does implement `AsRef<str>`
+        let inner: &String = name.as_ref();
+        assert_eq!(4, length(inner));
+    })
+    // Dropping MyBox<alloc::string::String>
+}
@@ -0,0 +1,151 @@
+//! # Running Code on Clean-up with the `Drop` Trait
+//! `Drop` lets you customize what happens when a value is about to go out of scope. You can provide an implementation
+//! for the `Drop` trait on any type, and that code can be used to release resources like files or network connections.
+//!
+//! `Drop` trait is almost always used when implementing a smart pointer. For example, when a `Box<T>` is dropped
+//! it will deallocate the space on the heap that the box points to.
+//!
+//! In some languages, for some types, the programmer must call code to free memory or resources every time they
+//! finish using an instance of those types. Examples include file handles, sockets, or locks. If they forget, the
+//! system might become overloaded and crash. In Rust, you can specify that a particular bit of code be run whenever
+//! a value goes out of scope, and the compiler will insert this code automatically. As a result, you don’t need to
+//! be careful about placing clean-up code everywhere in a program that an instance of a particular type is finished
+//! with—you still won’t leak resources!
+
+use ir_core::metrics::{self, Counter};
+use std::{
+    fmt::Debug,
+    io::Result,
+    ops::Deref,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// How many `CustomSmartPointer`s have been dropped so far, across the whole program.
+static DROPS: AtomicUsize = AtomicUsize::new(0);
+
+pub(crate) fn drops() -> usize {
+    DROPS.load(Ordering::SeqCst)
+}
+
+/// Owns its `T` on the heap, like a `Box<T>`, and counts its own drops.
+pub struct CustomSmartPointer<T: Debug> {
+    data: Box<T>,
+}
+impl<T: Debug> CustomSmartPointer<T> {
+    pub fn new(data: T) -> CustomSmartPointer<T> {
+        CustomSmartPointer {
+            data: Box::new(data),
+        }
+    }
+}
+impl<T: Debug> Deref for CustomSmartPointer<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.data
+    }
+}
+impl<T: Debug> Drop for CustomSmartPointer<T> {
+    // `drop` runs first, while `self.data` is still valid; the `Box` field is dropped
+    // (and its heap allocation freed) right after it returns
+    fn drop(&mut self) {
+        metrics::count(Counter::Drop);
+        let nth = DROPS.fetch_add(1, Ordering::SeqCst) + 1;
+        println!(
+            "Dropping CustomSmartPointer with data `{:?}`! (drop #{nth})",
+            self.data
+        );
+    }
+}
+
+pub fn drop_trait() -> Result<()> {
+    Ok({
+        let before = drops();
+        {
+            let _c = CustomSmartPointer::new("my stuff");
+            let _d = CustomSmartPointer::new(String::from("other stuff"));
+            println!("CustomSmartPointers created.");
+            assert_eq!(0, drops() - before);
+        }
+        // remember stack:
+        assert_eq!(2, drops() - before);
+
+        // # Dropping a Value Early with `std::mem::drop`
+        let c = CustomSmartPointer::new(vec![1, 2, 3]);
+        let d = CustomSmartPointer::new(4);
+        println!("len = {}, d = {}", c.len(), *d); // `Deref` at work
+        drop(c);
+        println!("CustomSmartPointer dropped before the end of the function.");
+        assert_eq!(3, drops() - before);
+        // // borrow of moved value: `c`
+        // println!("stuff: {:?}", *c); // value borrowed here after move
+    })
+}
+// CustomSmartPointers created.
+// Dropping CustomSmartPointer with data `"other stuff"`! (drop #1)
+// Dropping CustomSmartPointer with data `"my stuff"`! (drop #2)
+// len = 3, d = 4
+// Dropping CustomSmartPointer with data `[1, 2, 3]`! (drop #3)
+// CustomSmartPointer dropped before the end of the function.
+// Dropping CustomSmartPointer with data `4`! (drop #4)
+
+// Rust automatically called `drop` for us when our instances went out of scope,
+// calling the code we specified. Variables are dropped in the reverse order
+// of their creation, so `d` was dropped before `c`. This example’s purpose is
+// to give you a visual guide to how the `drop` method works; usually you would
+// specify the clean-up code that your type needs to run rather than a print
+// message.
+
+// You can use code specified in a `Drop` trait implementation in many ways to make
+// cleanup convenient and safe: for instance, you could use it to create your own
+// memory allocator! With the `Drop` trait and Rust’s ownership system, you don’t
+// have to remember to clean up because Rust does it automatically.
+
+// You also don’t have to worry about problems resulting from accidentally cleaning
+// up values still in use: the ownership system that makes sure references are always
+// valid also ensures that `drop` gets called only once when the value is no longer
+// being used.
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    // `DROPS` is shared by every test thread, so the tests that read it take turns
+    static SERIAL: Mutex<()> = Mutex::new(());
+
+    #[test]
+    fn it_derefs_to_the_boxed_value() {
+        let _serial = SERIAL.lock().unwrap();
+        let p = CustomSmartPointer::new(String::from("stuff"));
+
+        assert_eq!(*p, "stuff");
+        assert_eq!(p.len(), 5);
+    }
+
+    #[test]
+    fn it_is_dropped_exactly_once_in_reverse_order_of_creation() {
+        let _serial = SERIAL.lock().unwrap();
+        let before = drops();
+        let c = CustomSmartPointer::new(1);
+        let d = CustomSmartPointer::new(2);
+
+        drop(d);
+        assert_eq!(drops() - before, 1);
+        let moved = c;
+        assert_eq!(drops() - before, 1); // a move is not a drop
+        drop(moved);
+        assert_eq!(drops() - before, 2);
+    }
+
+    #[test]
+    fn it_counts_the_pointers_dropped_at_the_end_of_a_scope() {
+        let _serial = SERIAL.lock().unwrap();
+        let before = drops();
+        {
+            let _pointers: Vec<_> = (0..3).map(CustomSmartPointer::new).collect();
+            assert_eq!(drops() - before, 0);
+        }
+        assert_eq!(drops() - before, 3);
+    }
+}
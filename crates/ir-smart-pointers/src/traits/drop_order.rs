@@ -0,0 +1,156 @@
+//! # When Exactly Does `drop` Run?
+//! The `Drop` lesson shows that locals are dropped in the reverse order of their creation. That
+//! is one rule of several, and the others matter as soon as a `drop` does something observable,
+//! such as releasing a lock or flushing a buffer:
+//! - the fields of a struct are dropped _after_ the struct’s own `drop`, in declaration order;
+//! - a temporary is dropped at the end of the statement that created it, and `let _ = ..` binds
+//!   nothing, so the value is a temporary too;
+//! - `std::mem::drop` ends a value’s life early, simply by taking ownership of it;
+//! - `ManuallyDrop<T>` and `std::mem::forget` make sure `drop` doesn’t run at all.
+//!
+//! Instead of printing from `drop`, the values here record their names in a `Sink`, so the
+//! order can be asserted on as well as shown.
+
+use ir_core::metrics::{self, Counter};
+use std::{
+    cell::RefCell,
+    io::Result,
+    mem::{self, ManuallyDrop},
+};
+
+/// The names of dropped values, in the order they were dropped.
+type Sink = RefCell<Vec<&'static str>>;
+/// Creates some `Noisy` values and lets them go.
+type Lesson = fn(&Sink);
+
+struct Noisy<'s> {
+    name: &'static str,
+    sink: &'s Sink,
+}
+impl<'s> Noisy<'s> {
+    fn new(name: &'static str, sink: &'s Sink) -> Noisy<'s> {
+        Noisy { name, sink }
+    }
+}
+impl Drop for Noisy<'_> {
+    fn drop(&mut self) {
+        metrics::count(Counter::Drop);
+        self.sink.borrow_mut().push(self.name);
+    }
+}
+
+#[allow(dead_code)] // `first` and `second` are only ever dropped
+struct Pair<'s> {
+    first: Noisy<'s>,
+    second: Noisy<'s>,
+    sink: &'s Sink,
+}
+impl Drop for Pair<'_> {
+    // `first` and `second` are still alive in here, which is what makes it safe to use them
+    fn drop(&mut self) {
+        self.sink.borrow_mut().push("pair");
+    }
+}
+
+pub fn drop_order() -> Result<()> {
+    Ok({
+        let lessons: [(&str, Lesson); 5] = [
+            ("locals", locals),
+            ("fields", fields),
+            ("temporaries", temporaries),
+            ("mem::drop", dropping_early),
+            ("ManuallyDrop and mem::forget", not_dropping_at_all),
+        ];
+        for (name, lesson) in lessons {
+            let sink = Sink::default();
+            lesson(&sink);
+            println!("{name:>28}: {:?}", sink.borrow());
+        }
+    })
+}
+//                       locals: ["b", "a"]
+//                       fields: ["pair", "first", "second"]
+//                  temporaries: ["ignored", "temporary", "bound"]
+//                    mem::drop: ["a", "b"]
+// ManuallyDrop and mem::forget: ["dropped by hand"]
+
+fn locals(sink: &Sink) {
+    let _a = Noisy::new("a", sink);
+    let _b = Noisy::new("b", sink);
+}
+
+fn fields(sink: &Sink) {
+    let _pair = Pair {
+        // the order of the fields in the literal doesn’t matter, the order in the definition does
+        second: Noisy::new("second", sink),
+        first: Noisy::new("first", sink),
+        sink,
+    };
+}
+
+fn temporaries(sink: &Sink) {
+    // `let _` doesn’t bind, so there is nothing to keep the value alive past the `;`
+    let _ = Noisy::new("ignored", sink);
+    // `_bound` does bind, and lives until the end of the function like any other local
+    let _bound = Noisy::new("bound", sink);
+    // a temporary lives until the end of the whole statement, not just the sub-expression
+    let len = Noisy::new("temporary", sink).name.len();
+    assert_eq!(sink.borrow().len(), 2);
+    assert_eq!(len, 9);
+}
+
+fn dropping_early(sink: &Sink) {
+    let a = Noisy::new("a", sink);
+    let _b = Noisy::new("b", sink);
+    // `drop` is an ordinary function, `pub fn drop<T>(_x: T) {}`: it takes ownership, and the
+    // value is dropped when `_x` goes out of scope at the end of its (empty) body
+    drop(a);
+    // println!("{}", a.name); // borrow of moved value: `a`
+}
+
+fn not_dropping_at_all(sink: &Sink) {
+    // `ManuallyDrop<T>` is `T` without the automatic `drop`
+    let _never = ManuallyDrop::new(Noisy::new("never", sink));
+    let mut by_hand = ManuallyDrop::new(Noisy::new("dropped by hand", sink));
+    // SAFETY: `by_hand` is not used again after this
+    unsafe { ManuallyDrop::drop(&mut by_hand) };
+
+    // `mem::forget` takes ownership and never drops; it is safe because leaking is safe
+    mem::forget(Noisy::new("forgotten", sink));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recorded(lesson: Lesson) -> Vec<&'static str> {
+        let sink = Sink::default();
+        lesson(&sink);
+        sink.into_inner()
+    }
+
+    #[test]
+    fn locals_are_dropped_in_reverse_order_of_creation() {
+        assert_eq!(recorded(locals), ["b", "a"]);
+    }
+
+    #[test]
+    fn a_struct_is_dropped_before_its_fields_in_declaration_order() {
+        assert_eq!(recorded(fields), ["pair", "first", "second"]);
+    }
+
+    #[test]
+    fn temporaries_are_dropped_at_the_end_of_their_statement() {
+        assert_eq!(recorded(temporaries), ["ignored", "temporary", "bound"]);
+    }
+
+    #[test]
+    fn mem_drop_ends_a_value_early() {
+        assert_eq!(recorded(dropping_early), ["a", "b"]);
+    }
+
+    #[test]
+    fn manually_drop_and_forget_skip_drop() {
+        assert_eq!(recorded(not_dropping_at_all), ["dropped by hand"]);
+    }
+}
@@ -1,2 +1,3 @@
 pub mod deref;
 pub mod drop;
+pub mod drop_order;
@@ -0,0 +1,363 @@
+//! # Drawing Who Owns What
+//! The counts the lessons print say how many owners a value has, but not which. `Graph` draws the
+//! cons lists, the cyclic list and the tree as Graphviz DOT instead: a box per allocation,
+//! labelled with its value and its counts, a solid arrow per `Rc` and a dashed one per `Weak`.
+//! A reference cycle is a loop of solid arrows, and breaking it with a `Weak` turns one of them
+//! dashed.
+//!
+//! The lessons `show` their graphs as they go, and the graphs are kept only while a lesson runs
+//! inside `collect`, which `intermediate_rust --dot out.dot` does. `dot -Tsvg -O out.dot`
+//! renders them, one picture per graph.
+//!
+//! Drawing never clones an `Rc`, so the counts in the labels are the ones the lesson sees.
+
+use super::{
+    rc_t,
+    ref_cell_t::{self, CyclicList, TreeNode},
+};
+use ir_core::utils::scope_guard::ScopeGuard;
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    fmt::{self, Display, Write as _},
+    fs,
+    io::Result,
+    path::Path,
+    rc::{Rc, Weak},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Link {
+    Strong,
+    Weak,
+}
+
+/// Where an edge goes: a node, by the address of its allocation, or a `Weak` whose value is gone.
+#[derive(Debug, Clone, Copy)]
+enum Target {
+    Node(usize),
+    Dropped,
+}
+
+/// The allocations reachable from some variables, and the links between them.
+#[derive(Debug, Default)]
+pub struct Graph {
+    name: String,
+    /// The variables, and the address of the allocation each one owns.
+    vars: Vec<(String, usize)>,
+    /// The addresses of the allocations, in the order they were found, and their labels.
+    nodes: Vec<(usize, String)>,
+    ids: HashMap<usize, usize>,
+    edges: Vec<(usize, Target, Link)>,
+}
+
+/// Something that can add itself, and everything it owns, to a `Graph`.
+pub trait Draw {
+    /// Adds the allocation `self` points to, unless it is already there, and returns its address.
+    fn draw(&self, graph: &mut Graph) -> usize;
+}
+
+fn address<T: ?Sized>(ptr: *const T) -> usize {
+    ptr as *const () as usize
+}
+
+fn counts<T: ?Sized>(rc: &Rc<T>) -> String {
+    format!(
+        "strong = {}, weak = {}",
+        Rc::strong_count(rc),
+        Rc::weak_count(rc)
+    )
+}
+
+impl Graph {
+    pub fn new(name: &str) -> Graph {
+        Graph {
+            name: name.to_string(),
+            ..Graph::default()
+        }
+    }
+
+    /// Draws `value` as owned by the variable `name`.
+    pub fn var(mut self, name: &str, value: &impl Draw) -> Graph {
+        let target = value.draw(&mut self);
+        self.vars.push((name.to_string(), target));
+        self
+    }
+
+    /// Adds a node for the allocation of `rc`, and returns whether it is new. A walk stops at a
+    /// node that isn’t, which is how a cycle is drawn without going round it forever.
+    fn node<T: ?Sized>(&mut self, rc: &Rc<T>, label: impl FnOnce() -> String) -> bool {
+        let address = address(Rc::as_ptr(rc));
+        if self.ids.contains_key(&address) {
+            return false;
+        }
+        self.ids.insert(address, self.nodes.len());
+        self.nodes
+            .push((address, format!("{}\n{}", label(), counts(rc))));
+        true
+    }
+
+    fn edge<T: ?Sized>(&mut self, from: &Rc<T>, to: &Rc<impl ?Sized>, link: Link) {
+        let to = Target::Node(address(Rc::as_ptr(to)));
+        self.edges.push((address(Rc::as_ptr(from)), to, link));
+    }
+
+    /// A `Weak` link, which is followed no further: what it points to is drawn only if something
+    /// in the graph owns it. A `Weak::new()` never pointed anywhere, so it isn’t drawn at all.
+    fn weak_edge<T: ?Sized, U>(&mut self, from: &Rc<T>, to: &Weak<U>) {
+        if to.ptr_eq(&Weak::new()) {
+            return;
+        }
+        let to = match to.strong_count() {
+            0 => Target::Dropped,
+            _ => Target::Node(address(Weak::as_ptr(to))),
+        };
+        self.edges.push((address(Rc::as_ptr(from)), to, Link::Weak));
+    }
+}
+
+/// Quotes `text` as a DOT string.
+fn quoted(text: &str) -> String {
+    let escaped = text
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n");
+    format!("\"{escaped}\"")
+}
+
+/// The graph in the DOT language.
+impl Display for Graph {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "digraph {} {{", quoted(&self.name))?;
+        writeln!(f, "    label={};", quoted(&self.name))?;
+        writeln!(f, "    node [shape=box];")?;
+        for (i, (name, _)) in self.vars.iter().enumerate() {
+            writeln!(f, "    v{i} [label={}, shape=plaintext];", quoted(name))?;
+        }
+        for (i, (_, label)) in self.nodes.iter().enumerate() {
+            writeln!(f, "    n{i} [label={}];", quoted(label))?;
+        }
+        for (i, (_, target)) in self.vars.iter().enumerate() {
+            writeln!(f, "    v{i} -> n{};", self.ids[target])?;
+        }
+        let mut extra = 0;
+        for (from, to, link) in &self.edges {
+            let from = self.ids[from];
+            let to = match to {
+                Target::Node(address) if self.ids.contains_key(address) => {
+                    format!("n{}", self.ids[address])
+                }
+                // a `Weak` to something the graph doesn’t own, or to nothing at all
+                target => {
+                    let label = match target {
+                        Target::Dropped => "(dropped)",
+                        Target::Node(_) => "…",
+                    };
+                    writeln!(
+                        f,
+                        "    x{extra} [label={}, shape=plaintext];",
+                        quoted(label)
+                    )?;
+                    extra += 1;
+                    format!("x{}", extra - 1)
+                }
+            };
+            match link {
+                Link::Strong => writeln!(f, "    n{from} -> {to};")?,
+                Link::Weak => writeln!(f, "    n{from} -> {to} [style=dashed];")?,
+            }
+        }
+        writeln!(f, "}}")
+    }
+}
+
+impl<T: Display> Draw for Rc<rc_t::List<T>> {
+    fn draw(&self, graph: &mut Graph) -> usize {
+        let label = || match &**self {
+            rc_t::List::Cons(value, _) => value.to_string(),
+            rc_t::List::Nil => String::from("Nil"),
+        };
+        if graph.node(self, label) {
+            if let rc_t::List::Cons(_, next) = &**self {
+                graph.edge(self, next, Link::Strong);
+                next.draw(graph);
+            }
+        }
+        address(Rc::as_ptr(self))
+    }
+}
+
+/// The values are allocations of their own, which several lists can share.
+impl<T: Display> Draw for Rc<ref_cell_t::List<T>> {
+    fn draw(&self, graph: &mut Graph) -> usize {
+        let label = || match &**self {
+            ref_cell_t::List::Cons(..) => String::from("Cons"),
+            ref_cell_t::List::Nil => String::from("Nil"),
+        };
+        if graph.node(self, label) {
+            if let ref_cell_t::List::Cons(value, next) = &**self {
+                graph.edge(self, value, Link::Strong);
+                graph.node(value, || format!("RefCell({})", value.borrow()));
+                graph.edge(self, next, Link::Strong);
+                next.draw(graph);
+            }
+        }
+        address(Rc::as_ptr(self))
+    }
+}
+
+impl<T: Display> Draw for Rc<CyclicList<T>> {
+    fn draw(&self, graph: &mut Graph) -> usize {
+        let label = || match &**self {
+            CyclicList::Cons(value, _) | CyclicList::WeakLink(value, _) => value.to_string(),
+            CyclicList::Nil => String::from("Nil"),
+        };
+        if graph.node(self, label) {
+            match &**self {
+                CyclicList::Cons(_, tail) => {
+                    let tail = tail.borrow();
+                    graph.edge(self, &*tail, Link::Strong);
+                    tail.draw(graph);
+                }
+                CyclicList::WeakLink(_, back) => graph.weak_edge(self, &back.borrow()),
+                CyclicList::Nil => {}
+            }
+        }
+        address(Rc::as_ptr(self))
+    }
+}
+
+impl<T: Display> Draw for Rc<TreeNode<T>> {
+    fn draw(&self, graph: &mut Graph) -> usize {
+        if graph.node(self, || self.value().to_string()) {
+            graph.weak_edge(self, &self.parent_link());
+            for child in self.children().iter() {
+                graph.edge(self, child, Link::Strong);
+                child.draw(graph);
+            }
+        }
+        address(Rc::as_ptr(self))
+    }
+}
+
+thread_local! {
+    static GRAPHS: RefCell<Option<Vec<Graph>>> = const { RefCell::new(None) };
+}
+
+/// Keeps `graph`, if the lesson is running inside `collect`; otherwise the graph is dropped.
+pub fn show(graph: Graph) {
+    GRAPHS.with_borrow_mut(|graphs| {
+        if let Some(graphs) = graphs {
+            graphs.push(graph);
+        }
+    });
+}
+
+/// Runs `f`, and returns what it returned and the graphs it showed. Whatever was collecting
+/// before is collecting again afterwards, even if `f` panics.
+pub fn collect<R>(f: impl FnOnce() -> R) -> (R, Vec<Graph>) {
+    let previous = GRAPHS.replace(Some(Vec::new()));
+    let _restore = ScopeGuard::new(|| GRAPHS.set(previous));
+    let result = f();
+    (result, GRAPHS.take().unwrap_or_default())
+}
+
+/// Writes the `graphs` one after another to the file at `path`, or to stdout if `path` is `-`.
+pub fn write(graphs: &[Graph], path: &Path) -> Result<()> {
+    let mut dot = String::new();
+    for graph in graphs {
+        let _ = write!(dot, "{graph}");
+    }
+    match path.to_str() {
+        Some("-") => {
+            std::print!("{dot}");
+            Ok(())
+        }
+        _ => fs::write(path, dot),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shared_tails_are_drawn_once() {
+        let a = Rc::new(rc_t::List::from(vec![5]));
+        let b = Rc::new(rc_t::List::Cons(3, Rc::clone(&a)));
+        let graph = Graph::new("b shares a").var("a", &a).var("b", &b);
+
+        assert_eq!(
+            graph.to_string(),
+            "digraph \"b shares a\" {\n    \
+                 label=\"b shares a\";\n    \
+                 node [shape=box];\n    \
+                 v0 [label=\"a\", shape=plaintext];\n    \
+                 v1 [label=\"b\", shape=plaintext];\n    \
+                 n0 [label=\"5\\nstrong = 2, weak = 0\"];\n    \
+                 n1 [label=\"Nil\\nstrong = 1, weak = 0\"];\n    \
+                 n2 [label=\"3\\nstrong = 1, weak = 0\"];\n    \
+                 v0 -> n0;\n    \
+                 v1 -> n2;\n    \
+                 n0 -> n1;\n    \
+                 n2 -> n0;\n\
+             }\n"
+        );
+    }
+
+    #[test]
+    fn a_cycle_is_drawn_as_a_loop_of_strong_links() {
+        let a = Rc::new(CyclicList::Cons(5, RefCell::new(Rc::new(CyclicList::Nil))));
+        let b = Rc::new(CyclicList::Cons(10, RefCell::new(Rc::clone(&a))));
+        if let Some(link) = a.tail() {
+            *link.borrow_mut() = Rc::clone(&b);
+        }
+        let dot = Graph::new("cycle").var("a", &a).to_string();
+
+        assert!(dot.contains("n0 -> n1;\n    n1 -> n0;\n"), "{dot}");
+        // break the cycle, so that the test doesn’t leak
+        if let Some(link) = a.tail() {
+            *link.borrow_mut() = Rc::new(CyclicList::Nil);
+        }
+    }
+
+    #[test]
+    fn parent_links_are_dashed() {
+        let root = TreeNode::new("root");
+        let leaf = TreeNode::new("leaf");
+        TreeNode::add_child(&root, Rc::clone(&leaf));
+        let dot = Graph::new("tree").var("root", &root).to_string();
+
+        assert!(
+            dot.contains("n0 [label=\"root\\nstrong = 1, weak = 1\"];"),
+            "{dot}"
+        );
+        assert!(
+            dot.contains("n1 [label=\"leaf\\nstrong = 2, weak = 0\"];"),
+            "{dot}"
+        );
+        assert!(
+            dot.contains("n0 -> n1;\n    n1 -> n0 [style=dashed];\n"),
+            "{dot}"
+        );
+
+        drop(root);
+        let dot = Graph::new("orphan").var("leaf", &leaf).to_string();
+        assert!(
+            dot.contains("x0 [label=\"(dropped)\", shape=plaintext];"),
+            "{dot}"
+        );
+        assert!(dot.contains("n0 -> x0 [style=dashed];"), "{dot}");
+    }
+
+    #[test]
+    fn only_collected_graphs_are_kept() {
+        show(Graph::new("nobody is collecting"));
+        let ((), graphs) = collect(|| {
+            show(Graph::new("one"));
+            show(Graph::new("two"));
+        });
+        let names: Vec<_> = graphs.iter().map(|graph| graph.name.as_str()).collect();
+        assert_eq!(names, ["one", "two"]);
+    }
+}
@@ -0,0 +1,89 @@
+//! # The Lessons in a Browser
+//! `wasm-bindgen` makes the functions marked `#[wasm_bindgen]` callable from JavaScript, with
+//! the `&str`s and `String`s they take and return converted on the way; `www/index.html` calls
+//! them to list the lessons and to show what each one prints:
+//! ```text
+//! wasm-pack build crates/ir-wasm --target web
+//! python3 -m http.server --directory crates/ir-wasm
+//! ```
+//! and then open `http://localhost:8000/www/`.
+//!
+//! There is no stdout in a browser, so a lesson runs with `Output::callback`, which hands what it
+//! prints to a closure instead; here, one that collects it into the `String` `run_lesson`
+//! returns. A lesson that needs what only an operating system has, threads, files or sockets,
+//! isn’t listed, and `run_lesson` refuses to run it: WebAssembly would stop at the first of them,
+//! with no more than a `RuntimeError: unreachable` to show for it.
+
+use intermediate_rust::{registry, runner, LessonCtx};
+use ir_core::output::Output;
+use std::{cell::RefCell, rc::Rc};
+use wasm_bindgen::prelude::*;
+
+/// The lessons a browser can’t run: `RefCell<T>`’s messengers send their warnings to another
+/// thread and over TCP, and its logger writes to a file; the unsafe superpowers spawn scoped
+/// threads, to show a static and an `unsafe impl Sync` shared between them.
+const NEEDS_THE_HOST: &[&str] = &["smart-pointers/ref-cell", "unsafe-rust/superpowers"];
+
+/// The ids of the lessons that run in a browser, in the order they were registered, which is
+/// the order of their prerequisites.
+#[wasm_bindgen]
+pub fn lessons() -> Vec<String> {
+    registry()
+        .iter()
+        .map(|lesson| lesson.id())
+        .filter(|id| !NEEDS_THE_HOST.contains(id))
+        .map(String::from)
+        .collect()
+}
+
+/// The title of the lesson `id`, as `Registry::find` finds it.
+#[wasm_bindgen]
+pub fn title(id: &str) -> Option<String> {
+    registry().find(id).map(|lesson| lesson.title().to_string())
+}
+
+/// Runs the lesson `id`, as `Registry::find` finds it, and returns what it printed, with what it
+/// `eprint`ed in between, in the order it was printed. To JavaScript, an `Err` is an exception.
+#[wasm_bindgen]
+pub fn run_lesson(id: &str) -> Result<String, String> {
+    let registry = registry();
+    let lesson = registry.lookup(id)?;
+    if NEEDS_THE_HOST.contains(&lesson.id()) {
+        return Err(format!(
+            "`{}` needs threads, files and sockets, which a browser doesn't have: \
+             run it with `cargo run -- {0}` instead",
+            lesson.id()
+        ));
+    }
+    let printed = Rc::new(RefCell::new(String::new()));
+    let mut ctx = LessonCtx {
+        output: Output::callback({
+            let printed = Rc::clone(&printed);
+            move |_, text| printed.borrow_mut().push_str(text)
+        }),
+        ..LessonCtx::default()
+    };
+    runner::run(&registry, lesson.id(), &mut ctx).map_err(|e| e.to_string())?;
+    Ok(printed.take())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_lesson_returns_what_it_printed() {
+        let printed = run_lesson("smart_pointers/rc").unwrap();
+        assert!(printed.contains("a = 5 -> 10 -> Nil\n"), "{printed}");
+        assert!(title("smart-pointers/rc").unwrap().starts_with("`Rc<T>`"));
+    }
+
+    #[test]
+    fn the_lessons_that_need_the_host_are_left_out() {
+        assert!(lessons().contains(&"smart-pointers/rc".to_string()));
+        assert!(!lessons().contains(&"smart-pointers/ref-cell".to_string()));
+        assert!(!lessons().contains(&"unsafe-rust/superpowers".to_string()));
+        let err = run_lesson("smart-pointers/ref-cell").unwrap_err();
+        assert!(err.contains("a browser doesn't have"), "{err}");
+    }
+}
@@ -0,0 +1,37 @@
+//! # The Closures Lesson, and Its Quiz
+//! Runs the `lambda` lesson, and then asks its quiz, from `quiz::bank`, with `quiz::ask` reading
+//! the answers from stdin. The end of the input ends the quiz early, so the answers can be piped
+//! in as well as typed:
+//! ```text
+//! cargo run --example closures
+//! printf 'b\na\nc\n' | cargo run --example closures -- --no-lesson
+//! ```
+
+use ::intermediate_rust::{
+    intermediate_rust::{self, lesson::LessonCtx},
+    quiz, run,
+};
+use clap::Parser;
+use std::io::{self, Error, Result};
+
+#[derive(Debug, Parser)]
+#[command(about = "Runs the closures lesson, then quizzes you on it")]
+struct Args {
+    /// Only ask the quiz
+    #[arg(long)]
+    no_lesson: bool,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    if !args.no_lesson {
+        let registry = intermediate_rust::registry();
+        run(&registry, "lambda", &mut LessonCtx::default())?;
+        println!();
+    }
+
+    let (chapter, questions) = quiz::bank("lambda").map_err(Error::other)?;
+    let score = quiz::ask(questions, io::stdin().lock(), &mut io::stdout())?;
+    println!("{chapter}: {score}");
+    Ok(())
+}
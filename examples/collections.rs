@@ -0,0 +1,57 @@
+//! # Every Collections Lesson, One After Another
+//! Runs the lessons of the collections chapter in the order they were registered, each with
+//! `runner::run_one`, and ends with how each went. `--quiet` runs them with `Output::silent`,
+//! so that only that summary is printed:
+//! ```text
+//! cargo run --example collections
+//! cargo run --example collections -- --quiet
+//! ```
+
+use ::intermediate_rust::{
+    intermediate_rust::{self, lesson::LessonCtx, output::Output},
+    progress::Progress,
+    runner,
+};
+use clap::Parser;
+use std::io::{Error, Result};
+
+#[derive(Debug, Parser)]
+#[command(about = "Runs every collections lesson")]
+struct Args {
+    /// Don't show what the lessons print, only how they went
+    #[arg(short, long)]
+    quiet: bool,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    let registry = intermediate_rust::registry();
+    let mut ctx = LessonCtx {
+        output: match args.quiet {
+            true => Output::silent(),
+            false => Output::stdout(),
+        },
+        ..LessonCtx::default()
+    };
+    let mut progress = Progress::default();
+
+    let mut failed = 0;
+    let mut summary = String::new();
+    for lesson in registry.filter(|lesson| lesson.topic() == "collections") {
+        // forced, since some build on lessons of other chapters, which haven't run here
+        let line = match runner::run_one(lesson, &mut ctx, &mut progress, true) {
+            Ok(measurement) => format!("ok      {} ({measurement})", lesson.id()),
+            Err(x) => {
+                failed += 1;
+                format!("failed  {}: {x}", lesson.id())
+            }
+        };
+        summary.push_str(&line);
+        summary.push('\n');
+    }
+    print!("\n{summary}");
+    match failed {
+        0 => Ok(()),
+        failed => Err(Error::other(format!("{failed} lesson(s) failed"))),
+    }
+}
@@ -0,0 +1,47 @@
+//! # The Concurrency Lesson, Run Again and Again
+//! Threads interleave differently from one run to the next, so a lesson that prints from more
+//! than one thread would print differently too. The `thread-and-move` lesson joins its threads
+//! before it prints what they did, and this runs it over and over with `runner::run_captured`,
+//! to check that it printed the same every time:
+//! ```text
+//! cargo run --example concurrency
+//! cargo run --example concurrency -- --runs 100 --show
+//! ```
+
+use ::intermediate_rust::{intermediate_rust, intermediate_rust::output::Verbosity, runner};
+use clap::Parser;
+use std::io::{Error, Result};
+
+#[derive(Debug, Parser)]
+#[command(about = "Checks that the concurrency lesson prints the same on every run")]
+struct Args {
+    /// How many times to run the lesson
+    #[arg(long, value_name = "N", default_value_t = 10)]
+    runs: usize,
+
+    /// Print what the lesson printed, once
+    #[arg(long)]
+    show: bool,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    let registry = intermediate_rust::registry();
+    let lesson = registry.lookup("thread-and-move").map_err(Error::other)?;
+
+    let first = runner::run_captured(lesson, Verbosity::Normal)?;
+    if args.show {
+        print!("{}", first.stdout);
+    }
+    for run in 2..=args.runs {
+        let output = runner::run_captured(lesson, Verbosity::Normal)?;
+        if output.stdout != first.stdout {
+            return Err(Error::other(format!(
+                "run {run} printed something else:\n{}",
+                output.stdout
+            )));
+        }
+    }
+    println!("{} run(s), all printed the same", args.runs.max(1));
+    Ok(())
+}
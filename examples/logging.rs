@@ -0,0 +1,38 @@
+//! # The Logging Lesson, with a Logger of Our Own
+//! The library logs with the `log` facade and leaves the logger to the program, as the `logging`
+//! lesson explains; the binary’s is `env_logger`, set up in `src/logger.rs`. This program sets up
+//! its own, which lets through what `--level` says rather than what `RUST_LOG` does, and hands
+//! the lines to `output::Logged`, so that they are printed with the lesson’s `eprint!`, in order
+//! with the rest of what the lesson prints:
+//! ```text
+//! cargo run --example logging
+//! cargo run --example logging -- --level debug
+//! ```
+//! What the lesson says it prints “without RUST_LOG” is what it prints at `--level warn`.
+
+use ::intermediate_rust::intermediate_rust::{self, lesson::LessonCtx, output::Logged};
+use clap::Parser;
+use env_logger::{Builder, Target};
+use log::LevelFilter;
+use std::io::{Error, Result, Write};
+
+#[derive(Debug, Parser)]
+#[command(about = "Runs the logging lesson with a logger of its own")]
+struct Args {
+    /// The most verbose level to let through: `off`, `error`, `warn`, `info`, `debug` or `trace`
+    #[arg(long, default_value_t = LevelFilter::Warn)]
+    level: LevelFilter,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    Builder::new()
+        .filter_level(args.level)
+        .format(|buf, record| writeln!(buf, "[{}] {}", record.level(), record.args()))
+        .target(Target::Pipe(Box::new(Logged)))
+        .init();
+
+    let registry = intermediate_rust::registry();
+    let lesson = registry.lookup("logging").map_err(Error::other)?;
+    lesson.run(&mut LessonCtx::default())
+}
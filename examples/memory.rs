@@ -0,0 +1,58 @@
+//! # A Memory Lesson, with What It Teaches and Its Source
+//! Looks a lesson of the memory chapter up, and prints what the library knows about it: its
+//! `LessonInfo`, what it teaches with `explain`, and its code with `source::show`, before running
+//! it:
+//! ```text
+//! cargo run --example memory -- niche
+//! cargo run --example memory -- layout --explain --source
+//! ```
+
+use ::intermediate_rust::{
+    explain,
+    intermediate_rust::{self, lesson::LessonCtx},
+    progress::Progress,
+    runner, source,
+};
+use clap::Parser;
+use std::io::{Error, Result};
+
+#[derive(Debug, Parser)]
+#[command(about = "Runs a memory lesson")]
+struct Args {
+    /// `slices`, `dst`, `niche`, `layout` or `replace`
+    lesson: String,
+
+    /// Print what the lesson teaches before running it
+    #[arg(long)]
+    explain: bool,
+
+    /// Print the lesson's source before running it
+    #[arg(long)]
+    source: bool,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    let registry = intermediate_rust::registry();
+    let lesson = registry
+        .lookup(&format!("memory {}", args.lesson))
+        .map_err(Error::other)?;
+    let info = lesson.info();
+
+    println!("{}: {}", lesson.id(), lesson.title());
+    println!("{} ({})", info.description, info.difficulty);
+    if !info.prerequisites.is_empty() {
+        println!("builds on {}", info.prerequisites.join(", "));
+    }
+    if args.explain {
+        println!("\n{}", explain::explain(lesson, false));
+    }
+    if args.source {
+        println!("\n{}", source::show(info.source, false));
+    }
+
+    println!();
+    let mut ctx = LessonCtx::default();
+    runner::run_one(lesson, &mut ctx, &mut Progress::default(), true)?;
+    Ok(())
+}
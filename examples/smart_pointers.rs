@@ -0,0 +1,57 @@
+//! # The Smart Pointer Lessons, through the Library
+//! Runs one of the smart pointer lessons the way the binary does: looked up in the registry, and
+//! run by `runner::run_one` with a `LessonCtx` of its own. Only this chapter’s lessons, and only
+//! the flags they need:
+//! ```text
+//! cargo run --example smart_pointers -- rc
+//! cargo run --example smart_pointers -- --list
+//! cargo run --example smart_pointers -- ref-cell --seed 42
+//! ```
+//! The `Progress` isn’t saved, so each lesson runs as if it were the first, with `force`.
+
+use ::intermediate_rust::{
+    catalog,
+    intermediate_rust::{self, lesson::LessonCtx, random},
+    progress::Progress,
+    runner,
+};
+use clap::Parser;
+use std::io::{Error, Result};
+
+const TOPIC: &str = "smart-pointers";
+
+#[derive(Debug, Parser)]
+#[command(about = "Runs a smart pointer lesson")]
+struct Args {
+    /// `box`, `rc`, `ref-cell`, ...; without one, the chapter’s default lesson runs
+    lesson: Option<String>,
+
+    /// List the chapter’s lessons instead
+    #[arg(long, conflicts_with = "lesson")]
+    list: bool,
+
+    /// Seed the lesson’s random numbers with N
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    seed: u64,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    let registry = intermediate_rust::registry();
+    let mut progress = Progress::default();
+    if args.list {
+        let lessons = registry.filter(|lesson| lesson.topic() == TOPIC);
+        print!("{}", catalog::catalog(lessons, &progress));
+        return Ok(());
+    }
+
+    let name = format!("{TOPIC} {}", args.lesson.unwrap_or_default());
+    let lesson = registry.lookup(&name).map_err(Error::other)?;
+    let mut ctx = LessonCtx {
+        rng: random::seeded(args.seed),
+        ..LessonCtx::default()
+    };
+    let measurement = runner::run_one(lesson, &mut ctx, &mut progress, true)?;
+    println!("\n`{}` took {measurement}", lesson.id());
+    Ok(())
+}
@@ -0,0 +1,48 @@
+//! # The Strings Lesson, in Every Language
+//! The lessons’ titles and headers, and some of what they print, are translated; `locale::set`
+//! picks the language, for the whole program, as `--lang` does. This runs the `strings` lesson
+//! once in each language, with `run_captured`, and prints what it printed under the language’s
+//! name, or only in the one `--lang` asks for:
+//! ```text
+//! cargo run --example strings
+//! cargo run --example strings -- --lang id
+//! ```
+
+use ::intermediate_rust::{
+    intermediate_rust::{
+        self,
+        locale::{self, Lang},
+    },
+    run_captured, Verbosity,
+};
+use clap::Parser;
+use std::io::{Error, Result};
+
+#[derive(Debug, Parser)]
+#[command(about = "Runs the strings lesson in each language")]
+struct Args {
+    /// Only run it in LANG, `en` or `id`
+    #[arg(long, value_name = "LANG")]
+    lang: Option<Lang>,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    let registry = intermediate_rust::registry();
+    let lesson = registry.lookup("strings").map_err(Error::other)?;
+
+    let langs = match args.lang {
+        Some(lang) => vec![lang],
+        None => vec![Lang::En, Lang::Id],
+    };
+    for (i, lang) in langs.into_iter().enumerate() {
+        locale::set(lang);
+        let output = run_captured(lesson, Verbosity::Normal)?;
+        if i > 0 {
+            println!();
+        }
+        println!("--- {lang} ---");
+        print!("{}{}", output.stdout, output.stderr);
+    }
+    Ok(())
+}
@@ -0,0 +1,46 @@
+//! # The Unsafe Lessons, under Miri
+//! Runs every lesson of the unsafe chapter, with `run_captured`, and prints only whether each
+//! went well. Which makes it a short program to run under Miri, which checks the unsafe code as
+//! it runs, and stops at the first undefined behavior it finds; what only a real operating
+//! system can do, the lessons skip under Miri, see `utils/miri.rs`:
+//! ```text
+//! cargo run --example unsafe_rust
+//! cargo +nightly miri run --example unsafe_rust -- --show
+//! ```
+
+use ::intermediate_rust::{intermediate_rust, run_captured, Verbosity};
+use clap::Parser;
+use std::io::{Error, Result};
+
+#[derive(Debug, Parser)]
+#[command(about = "Runs every unsafe Rust lesson")]
+struct Args {
+    /// Print what the lessons print, too
+    #[arg(long)]
+    show: bool,
+}
+
+fn main() -> Result<()> {
+    let args = Args::parse();
+    let registry = intermediate_rust::registry();
+
+    let mut failed = 0;
+    for lesson in registry.filter(|lesson| lesson.topic() == "unsafe-rust") {
+        match run_captured(lesson, Verbosity::Normal) {
+            Ok(output) => {
+                if args.show {
+                    print!("{}{}", output.stdout, output.stderr);
+                }
+                println!("ok      {} ({:.2?})", lesson.id(), output.duration);
+            }
+            Err(x) => {
+                failed += 1;
+                println!("failed  {}: {x}", lesson.id());
+            }
+        }
+    }
+    match failed {
+        0 => Ok(()),
+        failed => Err(Error::other(format!("{failed} lesson(s) failed"))),
+    }
+}
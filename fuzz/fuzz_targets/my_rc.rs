@@ -0,0 +1,9 @@
+//! `MyRc<T>`, checked against `Rc<T>` by `run_rc_ops`, which the unsafe-rust chapter’s fuzzing
+//! lesson, `src/intermediate_rust/unsafe_rust/fuzzing.rs`, explains.
+#![no_main]
+
+use intermediate_rust::fuzzing::run_rc_ops;
+
+libfuzzer_sys::fuzz_target!(|data: &[u8]| {
+    run_rc_ops(data);
+});
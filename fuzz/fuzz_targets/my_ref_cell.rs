@@ -0,0 +1,9 @@
+//! `MyRefCell<T>`, checked against `RefCell<T>` by `run_ref_cell_ops`, which the unsafe-rust
+//! chapter’s fuzzing lesson, `src/intermediate_rust/unsafe_rust/fuzzing.rs`, explains.
+#![no_main]
+
+use intermediate_rust::fuzzing::run_ref_cell_ops;
+
+libfuzzer_sys::fuzz_target!(|data: &[u8]| {
+    run_ref_cell_ops(data);
+});
@@ -0,0 +1,9 @@
+//! `MyVec<T>`, checked against `Vec<T>` by `run_vec_ops`, which the unsafe-rust chapter’s fuzzing
+//! lesson, `src/intermediate_rust/unsafe_rust/fuzzing.rs`, explains.
+#![no_main]
+
+use intermediate_rust::fuzzing::run_vec_ops;
+
+libfuzzer_sys::fuzz_target!(|data: &[u8]| {
+    run_vec_ops(data);
+});
@@ -0,0 +1,5 @@
+//! # Bindings to Other Languages
+//! The lessons, for programs that aren’t written in Rust. Each language is a feature, as it
+//! takes that language’s toolchain to build; `python`, with PyO3, is the one so far.
+
+pub mod python;
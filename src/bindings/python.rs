@@ -0,0 +1,158 @@
+//! # Rust and Python, with PyO3
+//! The lessons as a Python module, built with `maturin develop`, which reads `pyproject.toml`:
+//! ```text
+//! >>> import intermediate_rust
+//! >>> intermediate_rust.lessons()[:2]
+//! ['lambda', 'thread-and-move']
+//! >>> print(intermediate_rust.run_lesson("smart-pointers/rc"))
+//! >>> tracker = intermediate_rust.LimitTracker(messenger, 100)
+//! >>> tracker.set_value(80)
+//! "Warning: You've used up over 75% of your quota!"
+//! ```
+//! Python has no ownership, only references, counted like an `Rc<T>`’s, and a garbage collector
+//! for the cycles they make; so what crosses over has to be owned by both sides at once.
+//!
+//! - `Python<'py>` is the proof that this thread holds the _GIL_, the global interpreter lock
+//!   Python’s reference counts are kept under, and `Python::attach` is how a thread takes it.
+//!   `Bound<'py, T>` is a reference to a Python object that can’t outlive that proof.
+//! - `Py<T>` is a reference to a Python object that can: another smart pointer, which counts
+//!   as one of the object’s owners, as an `Rc<T>` does, for as long as Rust keeps it. The
+//!   `LimitTracker` class keeps its messenger in one.
+//! - A `#[pyclass]` value is moved into a Python object, and Python owns it from then on. Rust
+//!   borrows it back for each method call, `&self` as a `PyRef` and `&mut self` as a `PyRefMut`,
+//!   which are checked at runtime like `RefCell<T>`’s `Ref` and `RefMut`: a messenger that calls
+//!   `set_value` while being sent a message gets an error, not a second `&mut`.
+//! - A `#[pyclass]` can’t borrow, as Python keeps it for as long as it likes, so it can’t
+//!   hold a `LimitTracker<'a, M, V>` that borrows its messenger. Its tracker borrows one that
+//!   sends nowhere and lives forever, in a `static`, and the class sends the events on itself.
+
+use crate::{intermediate_rust::registry, runner, smart_pointers, Verbosity};
+use pyo3::{
+    exceptions::{PyKeyError, PyRuntimeError},
+    prelude::*,
+};
+
+/// The ids of the lessons, in the order they were registered.
+#[pyfunction]
+fn lessons() -> Vec<String> {
+    registry()
+        .iter()
+        .map(|lesson| lesson.id().to_string())
+        .collect()
+}
+
+/// Runs the lesson `id`, as `Registry::find` finds it, and returns what it printed. An `id`
+/// that isn’t a lesson’s raises a `KeyError`, and a lesson that fails a `RuntimeError`.
+#[pyfunction]
+fn run_lesson(id: &str) -> PyResult<String> {
+    let registry = registry();
+    let lesson = registry.lookup(id).map_err(PyKeyError::new_err)?;
+    let output = runner::run_captured(lesson, Verbosity::Normal)
+        .map_err(|e| PyRuntimeError::new_err(e.to_string()))?;
+    Ok(output.stdout)
+}
+
+/// What the `LimitTracker` class’s tracker sends its messages to: nothing.
+struct Unsent;
+impl smart_pointers::Messenger for Unsent {
+    fn send(&self, _msg: &str) {}
+}
+static UNSENT: Unsent = Unsent;
+
+/// `LimitTracker(messenger, max)`: tracks a value against `max`, and calls `messenger.send`
+/// with a message when it goes over 75%, 90% and 100% of it.
+#[pyclass(name = "LimitTracker")]
+struct LimitTracker {
+    tracker: smart_pointers::LimitTracker<'static, Unsent, f64>,
+    messenger: Py<PyAny>,
+}
+#[pymethods]
+impl LimitTracker {
+    #[new]
+    fn new(messenger: Py<PyAny>, max: f64) -> LimitTracker {
+        LimitTracker {
+            tracker: smart_pointers::LimitTracker::new(&UNSENT, max),
+            messenger,
+        }
+    }
+
+    /// Records `value`, and returns the message sent to the messenger, or `None` if there was
+    /// none.
+    fn set_value(&mut self, py: Python<'_>, value: f64) -> PyResult<Option<String>> {
+        let Some(event) = self.tracker.set_value(value) else {
+            return Ok(None);
+        };
+        let msg = event.to_string();
+        self.messenger.call_method1(py, "send", (&msg,))?;
+        Ok(Some(msg))
+    }
+}
+
+/// The `intermediate_rust` module: the name has to be the library’s, which is the name of the
+/// file Python imports.
+#[pymodule(name = "intermediate_rust")]
+pub fn python(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_function(wrap_pyfunction!(lessons, module)?)?;
+    module.add_function(wrap_pyfunction!(run_lesson, module)?)?;
+    module.add_class::<LimitTracker>()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ffi::CStr;
+
+    /// Runs `code` in a fresh module that has imported `intermediate_rust`.
+    fn run(code: &CStr) -> PyResult<()> {
+        Python::initialize();
+        Python::attach(|py| {
+            let intermediate_rust = PyModule::new(py, "intermediate_rust")?;
+            python(&intermediate_rust)?;
+            let globals = pyo3::types::PyDict::new(py);
+            globals.set_item("intermediate_rust", intermediate_rust)?;
+            py.run(code, Some(&globals), None)
+        })
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn python_can_run_the_lessons() {
+        run(cr#"
+assert "smart-pointers/rc" in intermediate_rust.lessons()
+assert "a = 5 -> 10 -> Nil\n" in intermediate_rust.run_lesson("smart_pointers/rc")
+try:
+    intermediate_rust.run_lesson("no-such-lesson")
+    assert False
+except KeyError:
+    pass
+"#)
+        .unwrap();
+    }
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn a_tracker_is_borrowed_as_a_ref_cell_is() {
+        run(cr#"
+class Messenger:
+    def __init__(self):
+        self.sent = []
+    def send(self, msg):
+        self.sent.append(msg)
+        if len(self.sent) == 2:
+            tracker.set_value(0)
+
+messenger = Messenger()
+tracker = intermediate_rust.LimitTracker(messenger, 100)
+assert tracker.set_value(50) is None
+assert tracker.set_value(80).startswith("Warning")
+try:
+    tracker.set_value(95)
+    assert False
+except RuntimeError as e:
+    assert "borrowed" in str(e), e
+assert len(messenger.sent) == 2
+"#)
+        .unwrap();
+    }
+}
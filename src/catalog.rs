@@ -0,0 +1,107 @@
+//! # The Catalog of Lessons
+//! `intermediate_rust list` prints every lesson: whether it has been completed, its id, how hard
+//! it is and its title, with its description underneath. `list --tag memory` only prints the
+//! lessons tagged `memory`.
+
+use crate::{
+    intermediate_rust::{
+        lesson::{Lesson, Registry},
+        locale::tr,
+    },
+    progress::Progress,
+};
+use std::fmt::Write;
+
+/// The catalog of the lessons tagged `tag`, or of every lesson, or what the tags are if no lesson
+/// has that tag.
+pub fn list(registry: &Registry, tag: Option<&str>, progress: &Progress) -> Result<String, String> {
+    let lessons: Vec<_> = registry
+        .filter(|lesson| tag.is_none_or(|tag| lesson.info().tags.contains(&tag)))
+        .collect();
+    if lessons.is_empty() {
+        let tags: Vec<_> = registry.by_tag().into_iter().map(|(tag, _)| tag).collect();
+        return Err(format!(
+            "no lesson is tagged `{}`, the tags are: {}",
+            tag.unwrap_or_default(),
+            tags.join(", ")
+        ));
+    }
+    Ok(catalog(lessons, progress))
+}
+
+/// Two lines per lesson, in the order they are given.
+pub fn catalog<'r>(
+    lessons: impl IntoIterator<Item = &'r dyn Lesson>,
+    progress: &Progress,
+) -> String {
+    let lessons: Vec<_> = lessons.into_iter().collect();
+    let width = lessons
+        .iter()
+        .map(|lesson| lesson.id().len())
+        .max()
+        .unwrap_or(0);
+    let mut catalog = String::new();
+    for lesson in lessons {
+        let done = match progress.is_completed(lesson.id()) {
+            true => "done",
+            false => "",
+        };
+        let info = lesson.info();
+        let _ = writeln!(
+            catalog,
+            "{done:<4}  {:<width$}  {:<12}  {}",
+            lesson.id(),
+            info.difficulty,
+            tr(lesson.title())
+        );
+        let _ = writeln!(
+            catalog,
+            "{:indent$}{}",
+            "",
+            tr(info.description),
+            indent = width + 22
+        );
+    }
+    catalog
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intermediate_rust::lesson::{Demo, Difficulty, LessonInfo};
+
+    #[test]
+    fn each_lesson_has_its_status_id_difficulty_title_and_description() {
+        let lessons = [
+            Demo {
+                id: "lambda",
+                title: "Closures",
+                run: || Ok(()),
+                info: LessonInfo {
+                    description: "Capturing the environment",
+                    ..LessonInfo::default()
+                },
+            },
+            Demo {
+                id: "memory/niche",
+                title: "Niches",
+                run: || Ok(()),
+                info: LessonInfo {
+                    description: "Enum layout",
+                    difficulty: Difficulty::Intermediate,
+                    ..LessonInfo::default()
+                },
+            },
+        ];
+        let mut progress = Progress::default();
+        progress.complete("memory/niche");
+
+        assert_eq!(
+            catalog(lessons.iter().map(|l| l as &dyn Lesson), &progress),
+            "      lambda        beginner      Closures\n\
+             \x20                                 Capturing the environment\n\
+             done  memory/niche  intermediate  Niches\n\
+             \x20                                 Enum layout\n"
+        );
+    }
+}
@@ -0,0 +1,533 @@
+//! # The Command Line
+//! ```text
+//! intermediate_rust [--verbose] [TOPIC] [LESSON]
+//! intermediate_rust smart-pointers ref-cell
+//! intermediate_rust lambda
+//! ```
+//! The topics and their lessons are whatever the chapters added to the `Registry`; `clap` only
+//! collects the names, and `Cli::lesson` looks them up.
+//!
+//! `intermediate_rust --all` runs every lesson instead, and ends with a summary, and
+//! `intermediate_rust list` lists them. The other commands are the study aids:
+//! - `intermediate_rust tour` takes the lessons in order, with their exercises, see `tour`,
+//! - `intermediate_rust quiz lambda` asks the questions of a chapter, see `quiz`,
+//! - `intermediate_rust review` asks the ones due for review, see `review`,
+//! - `intermediate_rust grade collections maps` compares what a lesson prints with its
+//!   transcript, see `grade`,
+//! - `intermediate_rust share memory niche` links to a lesson on the Rust Playground, see
+//!   `share`,
+//! - `intermediate_rust report` sums up the work so far, see `report`,
+//! - `intermediate_rust verify deref` checks an exercise, and `hint deref` helps with it, see
+//!   `exercises`.
+//!
+//! With the `plugins` feature, `--plugin PATH` adds the lessons of a plugin, see `plugin`.
+//!
+//! `--output`, `--color` and the verbosity are the last layer of the runner’s settings, over
+//! those of the environment and of the configuration file, see `config`.
+
+use crate::intermediate_rust::{
+    config::{ColorChoice, Layer},
+    lesson::{Lesson, Registry},
+    locale::Lang,
+    output::Verbosity,
+};
+use clap::{error::ErrorKind, ArgAction, CommandFactory, Parser, Subcommand};
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+#[command(about = "Runs the intermediate Rust lessons")]
+pub struct Cli {
+    /// Run every lesson, carrying on past failures, and summarize the results
+    #[arg(long, conflicts_with = "topic")]
+    pub all: bool,
+
+    /// Run the lesson even if the lessons it builds on haven't been completed
+    #[arg(long, conflicts_with = "all")]
+    pub force: bool,
+
+    /// Print what the lesson teaches before running it
+    #[arg(long, conflicts_with = "all")]
+    pub explain: bool,
+
+    /// Write the ownership graphs the lessons draw to FILE, as Graphviz DOT, or to stdout if FILE
+    /// is `-`
+    #[arg(long, value_name = "FILE")]
+    pub dot: Option<PathBuf>,
+
+    /// Don't show what the lessons print, only how they went
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+
+    /// Show the extra detail some lessons have to show, and with -vv each step on the way
+    #[arg(short, long, global = true, action = ArgAction::Count)]
+    pub verbose: u8,
+
+    /// Write what the lessons print to PATH instead of the terminal
+    #[arg(long, value_name = "PATH", global = true)]
+    pub output: Option<PathBuf>,
+
+    /// Color what the lessons print `always`, `never`, or on a terminal only, `auto`
+    #[arg(long, value_name = "WHEN", global = true)]
+    pub color: Option<ColorChoice>,
+
+    /// Seed the lessons’ random numbers with N, so that they draw the same ones every run
+    #[arg(long, value_name = "N", global = true)]
+    pub seed: Option<u64>,
+
+    /// The language of the lessons, `en` or `id`; without it, the one `LANG` names, or English
+    #[arg(long, value_name = "LANG", global = true)]
+    pub lang: Option<Lang>,
+
+    /// Add the lessons of the plugin at PATH, a library such as `crates/ir-example-plugin`
+    /// builds; there can be more than one
+    #[cfg(feature = "plugins")]
+    #[arg(long = "plugin", value_name = "PATH", global = true)]
+    pub plugins: Vec<PathBuf>,
+
+    /// Without a topic, the smart pointer lessons run
+    pub topic: Option<String>,
+
+    /// Without a lesson, the topic’s default lesson runs
+    pub lesson: Option<String>,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, PartialEq, Eq, Subcommand)]
+pub enum Command {
+    /// List the lessons, how hard they are, and which you have completed
+    List {
+        /// Only list the lessons with this tag
+        #[arg(long)]
+        tag: Option<String>,
+    },
+    /// List the lessons that mention every word of a query, best match first
+    Search {
+        #[arg(required = true)]
+        query: Vec<String>,
+    },
+    /// Print the source of a lesson, highlighted with the `highlight` feature
+    ShowSource {
+        /// Without a topic, the smart pointer lessons’ source is shown
+        topic: Option<String>,
+        /// Without a lesson, the topic’s default lesson’s source is shown
+        lesson: Option<String>,
+    },
+    /// Run a lesson, or check an exercise, and compare what it prints with its transcript
+    Grade {
+        /// Without a topic, the smart pointer lessons are graded; `exercises` grades an exercise
+        topic: Option<String>,
+        /// Without a lesson, the topic’s default lesson is graded
+        lesson: Option<String>,
+    },
+    /// Print a Rust Playground link to a lesson, as a program of its own
+    Share {
+        /// Without a topic, the smart pointer lessons are shared
+        topic: Option<String>,
+        /// Without a lesson, the topic’s default lesson is shared
+        lesson: Option<String>,
+        /// Write the program to PATH instead, to run with `rustc` or `cargo`
+        #[arg(long, value_name = "PATH")]
+        main: Option<PathBuf>,
+    },
+    /// Answer the questions of a chapter’s quiz, and keep the best score
+    Quiz {
+        /// `smart-pointers`, `lambda` or `thread-and-move`
+        chapter: String,
+    },
+    /// Answer the quiz questions due for review, of the chapters with a completed lesson
+    Review {
+        /// Ask at most N of them
+        #[arg(long, value_name = "N", default_value_t = 10)]
+        limit: usize,
+    },
+    /// Check an exercise, or, without one, list the exercises
+    Verify {
+        /// `deref`, `rc-owners`, ...
+        exercise: Option<String>,
+    },
+    /// Reveal the next hint for an exercise, and after the last one its solution
+    Hint {
+        /// `deref`, `rc-owners`, ...
+        exercise: String,
+    },
+    /// Sum up the lessons completed, the quiz scores, and the exercises solved
+    Report {
+        /// Also write the report to PATH, a `.md` or an `.html` file
+        #[arg(long, value_name = "PATH")]
+        out: Option<PathBuf>,
+        /// Whom the report is a certificate for, once everything is done
+        #[arg(long, requires = "out")]
+        name: Option<String>,
+    },
+    /// Take the lessons in the order they were written for, with their exercises
+    Tour {
+        /// Ask each chapter’s quiz at its last stop
+        #[arg(long)]
+        quiz: bool,
+    },
+    /// Run lessons one after another from a prompt
+    Repl,
+    /// Choose and run the lessons from a menu
+    #[cfg(feature = "tui")]
+    Menu,
+}
+
+impl Cli {
+    /// Catches what the derive can’t express: `clap` can make an argument conflict with another
+    /// argument, but not with a subcommand.
+    pub fn check(self) -> Result<Cli, clap::Error> {
+        let runs_lessons = self.all || self.force || self.explain || self.dot.is_some();
+        match (runs_lessons, &self.command) {
+            (true, Some(_)) => Err(Cli::command().error(
+                ErrorKind::ArgumentConflict,
+                "--all, --force, --explain and --dot run lessons, so they can't be given a command",
+            )),
+            _ => Ok(self),
+        }
+    }
+
+    pub fn verbosity(&self) -> Verbosity {
+        match (self.quiet, self.verbose) {
+            (true, _) => Verbosity::Quiet,
+            (false, 0) => Verbosity::Normal,
+            (false, 1) => Verbosity::Verbose,
+            (false, _) => Verbosity::Debug,
+        }
+    }
+
+    /// What the command line says of the runner’s settings: the verbosity only if it was given
+    /// `--quiet` or `-v`, so that the environment and the file can set it otherwise.
+    pub fn layer(&self) -> Layer {
+        Layer {
+            output: self.output.clone(),
+            color: self.color,
+            verbosity: (self.quiet || self.verbose > 0).then(|| self.verbosity()),
+        }
+    }
+
+    /// The lesson the topic and lesson name, or an error listing the topics, or the lessons of
+    /// the topic if it is one.
+    pub fn lesson<'r>(&self, registry: &'r Registry) -> Result<&'r dyn Lesson, clap::Error> {
+        lookup(registry, self.topic.as_deref(), self.lesson.as_deref())
+    }
+}
+
+/// The lesson named by `topic` and `lesson`, as `Cli::lesson` looks it up; for the commands that
+/// name a lesson too.
+pub fn lookup<'r>(
+    registry: &'r Registry,
+    topic: Option<&str>,
+    lesson: Option<&str>,
+) -> Result<&'r dyn Lesson, clap::Error> {
+    // the smart pointer lessons, or, in a build without them, the first chapter there is
+    let topics = registry.topics();
+    let topic = topic.unwrap_or(match topics.first() {
+        Some(first) if !topics.contains(&"smart-pointers") => first,
+        _ => "smart-pointers",
+    });
+    let name = match lesson {
+        Some(lesson) => format!("{topic}/{lesson}"),
+        None => topic.to_string(),
+    };
+    registry
+        .lookup(&name)
+        .map_err(|message| Cli::command().error(ErrorKind::InvalidValue, message))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intermediate_rust;
+
+    fn parse(args: &[&str]) -> Cli {
+        Cli::try_parse_from([&["intermediate_rust"], args].concat()).unwrap()
+    }
+
+    fn lesson(args: &[&str]) -> Result<String, ErrorKind> {
+        parse(args)
+            .lesson(&intermediate_rust::registry())
+            .map(|lesson| lesson.id().to_string())
+            .map_err(|x| x.kind())
+    }
+
+    #[test]
+    fn the_derived_command_is_consistent() {
+        Cli::command().debug_assert();
+    }
+
+    #[cfg(all(feature = "closures", feature = "collections"))]
+    #[test]
+    fn topics_and_lessons_name_a_registered_lesson() {
+        assert_eq!(
+            lesson(&["smart-pointers", "box"]).as_deref(),
+            Ok("smart-pointers/box")
+        );
+        assert_eq!(
+            lesson(&["collections", "vec_deque"]).as_deref(),
+            Ok("collections/vec-deque")
+        );
+        assert_eq!(lesson(&["lambda"]).as_deref(), Ok("lambda"));
+    }
+
+    #[cfg(feature = "unsafe-rust")]
+    #[test]
+    fn a_missing_topic_or_lesson_runs_the_default() {
+        assert_eq!(lesson(&[]).as_deref(), Ok("smart-pointers/ref-cell"));
+        assert_eq!(
+            lesson(&["unsafe-rust"]).as_deref(),
+            Ok("unsafe-rust/superpowers")
+        );
+    }
+
+    #[cfg(not(feature = "smart-pointers"))]
+    #[test]
+    fn without_the_smart_pointer_lessons_the_first_chapter_runs() {
+        let registry = intermediate_rust::registry();
+        assert_eq!(
+            lookup(&registry, None, None).ok().map(|lesson| lesson.id()),
+            registry.iter().next().map(|lesson| lesson.id())
+        );
+    }
+
+    #[cfg(feature = "memory")]
+    #[test]
+    fn verbose_is_accepted_before_or_after_the_topic() {
+        assert_eq!(parse(&["--verbose", "memory", "replace"]).verbose, 1);
+        let cli = parse(&["memory", "niche", "-v"]);
+        assert_eq!(cli.verbosity(), Verbosity::Verbose);
+        assert_eq!(
+            cli.lesson(&intermediate_rust::registry()).unwrap().id(),
+            "memory/niche"
+        );
+    }
+
+    #[test]
+    fn a_seed_is_optional_and_accepted_anywhere() {
+        assert_eq!(parse(&["lambda"]).seed, None);
+        assert_eq!(parse(&["--seed", "42", "lambda"]).seed, Some(42));
+        assert_eq!(parse(&["--all", "--seed", "7"]).seed, Some(7));
+        assert!(Cli::try_parse_from(["intermediate_rust", "--seed", "-1"]).is_err());
+    }
+
+    #[test]
+    fn a_language_is_given_by_its_code() {
+        assert_eq!(parse(&["lambda"]).lang, None);
+        assert_eq!(parse(&["--lang", "id", "lambda"]).lang, Some(Lang::Id));
+        assert_eq!(parse(&["list", "--lang", "en"]).lang, Some(Lang::En));
+        assert!(Cli::try_parse_from(["intermediate_rust", "--lang", "fr"]).is_err());
+    }
+
+    #[test]
+    fn only_what_is_given_is_a_setting() {
+        assert_eq!(parse(&["lambda"]).layer(), Layer::default());
+        let layer = parse(&["-v", "--color", "never", "--output", "out.txt"]).layer();
+        assert_eq!(layer.verbosity, Some(Verbosity::Verbose));
+        assert_eq!(layer.color, Some(ColorChoice::Never));
+        assert_eq!(layer.output, Some(PathBuf::from("out.txt")));
+        assert!(Cli::try_parse_from(["intermediate_rust", "--color", "sometimes"]).is_err());
+    }
+
+    #[test]
+    fn quiet_and_each_v_set_the_verbosity() {
+        assert_eq!(parse(&[]).verbosity(), Verbosity::Normal);
+        assert_eq!(parse(&["-q"]).verbosity(), Verbosity::Quiet);
+        assert_eq!(parse(&["-vv", "lambda"]).verbosity(), Verbosity::Debug);
+        assert_eq!(parse(&["-v", "lambda", "-v"]).verbosity(), Verbosity::Debug);
+        assert!(Cli::try_parse_from(["intermediate_rust", "-q", "-v"]).is_err());
+    }
+
+    #[test]
+    fn all_does_not_take_a_topic() {
+        assert!(parse(&["--all"]).all);
+        let x = Cli::try_parse_from(["intermediate_rust", "--all", "lambda"]).unwrap_err();
+        assert_eq!(x.kind(), ErrorKind::ArgumentConflict);
+    }
+
+    #[test]
+    fn explain_is_for_one_lesson() {
+        assert!(parse(&["--explain", "smart-pointers", "rc"]).explain);
+        assert!(Cli::try_parse_from(["intermediate_rust", "--explain", "--all"]).is_err());
+        let x = parse(&["--explain", "repl"]).check().unwrap_err();
+        assert_eq!(x.kind(), ErrorKind::ArgumentConflict);
+    }
+
+    #[test]
+    fn dot_takes_a_file_and_no_command() {
+        let cli = parse(&["--dot", "out.dot", "smart-pointers", "rc"]);
+        assert_eq!(cli.dot, Some(PathBuf::from("out.dot")));
+        assert_eq!(parse(&["--dot", "-"]).dot, Some(PathBuf::from("-")));
+        let x = parse(&["--dot", "out.dot", "list"]).check().unwrap_err();
+        assert_eq!(x.kind(), ErrorKind::ArgumentConflict);
+    }
+
+    #[test]
+    fn unknown_lessons_are_rejected() {
+        assert_eq!(lesson(&["memory", "nothing"]), Err(ErrorKind::InvalidValue));
+        assert_eq!(lesson(&["lambda", "niche"]), Err(ErrorKind::InvalidValue));
+        assert_eq!(lesson(&["pointers"]), Err(ErrorKind::InvalidValue));
+    }
+
+    #[test]
+    fn list_takes_an_optional_tag() {
+        assert_eq!(parse(&["list"]).command, Some(Command::List { tag: None }));
+        assert_eq!(
+            parse(&["list", "--tag", "memory"]).command,
+            Some(Command::List {
+                tag: Some(String::from("memory"))
+            })
+        );
+        assert_eq!(parse(&["list", "-v"]).check().unwrap().verbose, 1);
+        let x = parse(&["--all", "list"]).check().unwrap_err();
+        assert_eq!(x.kind(), ErrorKind::ArgumentConflict);
+    }
+
+    #[cfg(feature = "smart-pointers")]
+    #[test]
+    fn show_source_names_a_lesson_like_running_one() {
+        assert_eq!(
+            parse(&["show-source", "smart-pointers", "rc"]).command,
+            Some(Command::ShowSource {
+                topic: Some(String::from("smart-pointers")),
+                lesson: Some(String::from("rc"))
+            })
+        );
+        let registry = intermediate_rust::registry();
+        let rc = lookup(&registry, Some("smart-pointers"), Some("rc")).unwrap();
+        assert_eq!(rc.id(), "smart-pointers/rc");
+        assert_eq!(
+            lookup(&registry, None, None).unwrap().id(),
+            "smart-pointers/ref-cell"
+        );
+    }
+
+    #[test]
+    fn search_takes_one_or_more_words() {
+        assert_eq!(
+            parse(&["search", "interior", "mutability"]).command,
+            Some(Command::Search {
+                query: vec![String::from("interior"), String::from("mutability")]
+            })
+        );
+        assert!(Cli::try_parse_from(["intermediate_rust", "search"]).is_err());
+    }
+
+    #[test]
+    fn grade_names_a_lesson_like_running_one() {
+        assert_eq!(
+            parse(&["grade", "collections", "maps"]).command,
+            Some(Command::Grade {
+                topic: Some(String::from("collections")),
+                lesson: Some(String::from("maps"))
+            })
+        );
+        assert_eq!(
+            parse(&["grade"]).command,
+            Some(Command::Grade {
+                topic: None,
+                lesson: None
+            })
+        );
+    }
+
+    #[test]
+    fn share_prints_a_link_or_writes_a_main() {
+        assert_eq!(
+            parse(&["share", "memory", "niche"]).command,
+            Some(Command::Share {
+                topic: Some(String::from("memory")),
+                lesson: Some(String::from("niche")),
+                main: None
+            })
+        );
+        assert_eq!(
+            parse(&["share", "collections", "--main", "main.rs"]).command,
+            Some(Command::Share {
+                topic: Some(String::from("collections")),
+                lesson: None,
+                main: Some(PathBuf::from("main.rs"))
+            })
+        );
+    }
+
+    #[test]
+    fn a_report_can_be_written_to_a_file_with_a_name() {
+        assert_eq!(
+            parse(&["report"]).command,
+            Some(Command::Report {
+                out: None,
+                name: None
+            })
+        );
+        assert_eq!(
+            parse(&["report", "--out", "report.html", "--name", "Ada"]).command,
+            Some(Command::Report {
+                out: Some(PathBuf::from("report.html")),
+                name: Some(String::from("Ada"))
+            })
+        );
+        // the name is only written to the file
+        assert!(Cli::try_parse_from(["intermediate_rust", "report", "--name", "Ada"]).is_err());
+    }
+
+    #[test]
+    fn review_asks_ten_at_most_by_default() {
+        assert_eq!(
+            parse(&["review"]).command,
+            Some(Command::Review { limit: 10 })
+        );
+        assert_eq!(
+            parse(&["review", "--limit", "3"]).command,
+            Some(Command::Review { limit: 3 })
+        );
+    }
+
+    #[test]
+    fn tour_quizzes_only_when_asked_to() {
+        assert_eq!(
+            parse(&["tour"]).command,
+            Some(Command::Tour { quiz: false })
+        );
+        assert_eq!(
+            parse(&["tour", "--quiz"]).command,
+            Some(Command::Tour { quiz: true })
+        );
+    }
+
+    #[test]
+    fn hint_takes_an_exercise() {
+        assert_eq!(
+            parse(&["hint", "deref"]).command,
+            Some(Command::Hint {
+                exercise: String::from("deref")
+            })
+        );
+        assert!(Cli::try_parse_from(["intermediate_rust", "hint"]).is_err());
+    }
+
+    #[test]
+    fn quiz_takes_a_chapter() {
+        assert_eq!(
+            parse(&["quiz", "lambda"]).command,
+            Some(Command::Quiz {
+                chapter: String::from("lambda")
+            })
+        );
+        assert!(Cli::try_parse_from(["intermediate_rust", "quiz"]).is_err());
+    }
+
+    #[test]
+    fn verify_takes_an_optional_exercise() {
+        assert_eq!(
+            parse(&["verify"]).command,
+            Some(Command::Verify { exercise: None })
+        );
+        assert_eq!(
+            parse(&["verify", "deref"]).command,
+            Some(Command::Verify {
+                exercise: Some(String::from("deref"))
+            })
+        );
+    }
+}
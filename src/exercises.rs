@@ -0,0 +1,433 @@
+//! # Exercises
+//! Each exercise is a function, or an impl, with a `todo!()` for a body, in
+//! `src/exercises/`, and a task in its doc comment; each goes with a lesson, which shows how.
+//! Once it is written, `verify` checks it:
+//! ```text
+//! intermediate_rust verify              # the exercises, and the lesson each goes with
+//! intermediate_rust verify deref        # compiles what has been written, and checks it
+//! ```
+//! The checks are the exercise’s tests, kept apart in `exercises/checks.rs`. Each runs on its
+//! own, so one that panics, on a `todo!()` for instance, doesn’t keep the others from running.
+//!
+//! Stuck, `hint` helps, a little more each time: the first hint, then the next, and at last the
+//! solution. How many of them each exercise took is kept in `.intermediate_rust_hints` in the
+//! current directory, or wherever `INTERMEDIATE_RUST_HINTS` says, and `verify` lists it:
+//! ```text
+//! intermediate_rust hint deref
+//! ```
+
+use crate::runner;
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    env, fs,
+    io::{ErrorKind, Result, Write},
+    panic::{self, AssertUnwindSafe},
+    path::{Path, PathBuf},
+};
+
+pub mod borrow;
+mod checks;
+pub mod counter;
+pub mod deref;
+mod hints;
+pub mod rc_owners;
+pub mod sum_in_thread;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Exercise {
+    pub id: &'static str,
+    pub title: &'static str,
+    /// The id of the lesson that shows how.
+    pub lesson: &'static str,
+    /// Where the `todo!()`s are.
+    pub file: &'static str,
+    /// The checks, by name.
+    pub checks: &'static [(&'static str, fn())],
+    /// The hints, each telling more than the one before.
+    pub hints: &'static [&'static str],
+    /// The body that replaces the `todo!()`.
+    pub solution: &'static str,
+}
+impl Exercise {
+    /// How many times `hint` tells something new: each hint, and then the solution.
+    pub fn tiers(&self) -> usize {
+        self.hints.len() + 1
+    }
+
+    /// The `tier`th thing `hint` tells, counting from 0: a hint, or, after the last one, the
+    /// solution, which is what it keeps telling from then on.
+    pub fn hint(&self, tier: usize) -> String {
+        match self.hints.get(tier) {
+            Some(hint) => format!("hint {}/{}: {hint}", tier + 1, self.hints.len()),
+            None => format!(
+                "the solution, in place of the `todo!()`:\n{}",
+                self.solution
+            ),
+        }
+    }
+}
+
+/// Every exercise, in the order of the lessons they go with.
+pub const EXERCISES: &[Exercise] = &[
+    Exercise {
+        id: "counter",
+        title: "A closure that counts",
+        lesson: "lambda",
+        file: "src/exercises/counter.rs",
+        checks: &[
+            ("it_counts_from_one", checks::it_counts_from_one),
+            (
+                "each_counter_counts_on_its_own",
+                checks::each_counter_counts_on_its_own,
+            ),
+        ],
+        hints: hints::COUNTER,
+        solution: hints::COUNTER_SOLUTION,
+    },
+    Exercise {
+        id: "sum-in-thread",
+        title: "Sum in a thread",
+        lesson: "thread-and-move",
+        file: "src/exercises/sum_in_thread.rs",
+        checks: &[
+            ("the_thread_sums", checks::the_thread_sums),
+            ("a_long_sum", checks::a_long_sum),
+        ],
+        hints: hints::SUM_IN_THREAD,
+        solution: hints::SUM_IN_THREAD_SOLUTION,
+    },
+    Exercise {
+        id: "deref",
+        title: "Implement `Deref`",
+        lesson: "smart-pointers/deref",
+        file: "src/exercises/deref.rs",
+        checks: &[
+            ("deref_gives_the_value", checks::deref_gives_the_value),
+            (
+                "deref_coercion_calls_f64_methods",
+                checks::deref_coercion_calls_f64_methods,
+            ),
+        ],
+        hints: hints::DEREF,
+        solution: hints::DEREF_SOLUTION,
+    },
+    Exercise {
+        id: "rc-owners",
+        title: "Two owners",
+        lesson: "smart-pointers/rc",
+        file: "src/exercises/rc_owners.rs",
+        checks: &[
+            ("both_own_the_same_string", checks::both_own_the_same_string),
+            ("the_strong_count_is_two", checks::the_strong_count_is_two),
+        ],
+        hints: hints::RC_OWNERS,
+        solution: hints::RC_OWNERS_SOLUTION,
+    },
+    Exercise {
+        id: "borrow",
+        title: "Fix this borrow error",
+        lesson: "memory/slices",
+        file: "src/exercises/borrow.rs",
+        checks: &[
+            (
+                "the_longest_before_the_push_is_measured",
+                checks::the_longest_before_the_push_is_measured,
+            ),
+            ("no_words_measure_zero", checks::no_words_measure_zero),
+        ],
+        hints: hints::BORROW,
+        solution: hints::BORROW_SOLUTION,
+    },
+];
+
+/// The exercise `id`, also with `_` for `-`, or what the exercises are.
+pub fn find(id: &str) -> std::result::Result<&'static Exercise, String> {
+    let id = id.replace('_', "-");
+    EXERCISES
+        .iter()
+        .find(|exercise| exercise.id == id)
+        .ok_or_else(|| {
+            let ids: Vec<_> = EXERCISES.iter().map(|exercise| exercise.id).collect();
+            format!(
+                "there is no exercise `{id}`, the exercises are: {}",
+                ids.join(", ")
+            )
+        })
+}
+
+/// One line per exercise: its id, its title, the lesson it goes with, and how many hints it
+/// took, if any.
+pub fn list(hints: &Hints) -> String {
+    let width = EXERCISES.iter().map(|exercise| exercise.id.len()).max();
+    EXERCISES
+        .iter()
+        .map(|exercise| {
+            let used = match hints.used(exercise.id) {
+                0 => String::new(),
+                used if used >= exercise.tiers() => String::from(" (solution shown)"),
+                used => format!(" ({used} hint(s) used)"),
+            };
+            format!(
+                "{:<width$}  {}, after `{}`{used}\n",
+                exercise.id,
+                exercise.title,
+                exercise.lesson,
+                width = width.unwrap_or(0)
+            )
+        })
+        .collect()
+}
+
+/// How many of its hints each exercise took so far, the solution counting as the last one.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Hints {
+    used: BTreeMap<String, usize>,
+}
+impl Hints {
+    pub fn path() -> PathBuf {
+        env::var_os("INTERMEDIATE_RUST_HINTS")
+            .map_or_else(|| PathBuf::from(".intermediate_rust_hints"), PathBuf::from)
+    }
+
+    /// No file yet means no hint has been taken yet, and a line that can’t be read is skipped.
+    /// Under Miri, which has no file system, nothing is ever loaded or saved.
+    pub fn load(path: &Path) -> Result<Hints> {
+        if cfg!(miri) {
+            return Ok(Hints::default());
+        }
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(x) if x.kind() == ErrorKind::NotFound => return Ok(Hints::default()),
+            Err(x) => return Err(x),
+        };
+        let used = text
+            .lines()
+            .filter_map(|line| {
+                let (id, used) = line.trim().split_once(' ')?;
+                Some((id.to_string(), used.parse().ok()?))
+            })
+            .collect();
+        Ok(Hints { used })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if cfg!(miri) {
+            return Ok(());
+        }
+        let mut text = String::new();
+        for (id, used) in &self.used {
+            text.push_str(&format!("{id} {used}\n"));
+        }
+        fs::write(path, text)
+    }
+
+    pub fn used(&self, id: &str) -> usize {
+        self.used.get(id).copied().unwrap_or(0)
+    }
+
+    /// The next hint for `exercise`, which is then counted as used; once it is all used, the
+    /// solution again.
+    pub fn reveal(&mut self, exercise: &Exercise) -> String {
+        let used = self.used(exercise.id);
+        let hint = exercise.hint(used);
+        self.used
+            .insert(exercise.id.to_string(), (used + 1).min(exercise.tiers()));
+        hint
+    }
+}
+
+thread_local! {
+    /// Where the last check to panic on this thread panicked.
+    static PANICKED_AT: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Runs each of the checks of `exercise`, reporting to `out` how each went, and returns whether
+/// they all passed. A check fails by panicking, like a test, and is reported with the panic’s
+/// message and where it happened: in the exercise, for a `todo!()`, or in the check, for an
+/// `assert!`. The panic hook is swapped for one that only records where, for the time being, so
+/// that nothing but the report is printed.
+pub fn verify(exercise: &Exercise, out: &mut impl Write) -> Result<bool> {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|info| {
+        let at = info.location().map(ToString::to_string);
+        PANICKED_AT.set(at);
+    }));
+    let results: Vec<_> = exercise
+        .checks
+        .iter()
+        .map(|(name, check)| {
+            let result = panic::catch_unwind(AssertUnwindSafe(check)).map_err(|panic| {
+                let message = runner::panic_message(&*panic).to_string();
+                match PANICKED_AT.take() {
+                    Some(at) => format!("{message}, at {at}"),
+                    None => message,
+                }
+            });
+            (name, result)
+        })
+        .collect();
+    panic::set_hook(hook);
+
+    let mut failed = 0;
+    for (name, result) in results {
+        match result {
+            Ok(()) => writeln!(out, "ok      {name}")?,
+            Err(x) => {
+                failed += 1;
+                writeln!(out, "FAILED  {name}: {x}")?;
+            }
+        }
+    }
+    match failed {
+        0 => writeln!(out, "`{}` passed: on to the next one", exercise.id)?,
+        failed => writeln!(
+            out,
+            "`{}`: {failed} of {} check(s) failed, see {}",
+            exercise.id,
+            exercise.checks.len(),
+            exercise.file
+        )?,
+    }
+    Ok(failed == 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intermediate_rust;
+    use std::path::Path;
+
+    #[cfg(feature = "chapters")]
+    #[test]
+    fn every_exercise_goes_with_a_lesson_and_has_checks() {
+        let registry = intermediate_rust::registry();
+        for exercise in EXERCISES {
+            assert!(
+                registry.find(exercise.lesson).is_some(),
+                "{}",
+                exercise.lesson
+            );
+            assert!(!exercise.checks.is_empty(), "{}", exercise.id);
+            assert!(!exercise.hints.is_empty(), "{}", exercise.id);
+            assert!(
+                Path::new(env!("CARGO_MANIFEST_DIR"))
+                    .join(exercise.file)
+                    .is_file(),
+                "{}",
+                exercise.file
+            );
+        }
+    }
+
+    #[test]
+    fn exercises_are_found_by_id() {
+        assert_eq!(find("rc_owners").unwrap().id, "rc-owners");
+        assert!(find("nothing")
+            .unwrap_err()
+            .starts_with("there is no exercise `nothing`, the exercises are: counter, "));
+    }
+
+    #[test]
+    fn each_check_is_reported_and_a_failure_doesnt_stop_the_others() {
+        let exercise = Exercise {
+            id: "half",
+            title: "Half done",
+            lesson: "lambda",
+            file: "src/exercises/half.rs",
+            checks: &[
+                ("unfinished", || todo!("not yet")),
+                ("finished", || assert_eq!(1 + 1, 2)),
+            ],
+            hints: &[],
+            solution: "",
+        };
+        let mut out = Vec::new();
+        assert!(!verify(&exercise, &mut out).unwrap());
+        let out = String::from_utf8(out).unwrap();
+        let (failed, rest) = out.split_once('\n').unwrap();
+
+        // the line it panicked on is the `todo!` above
+        assert!(
+            failed.starts_with(
+                "FAILED  unfinished: not yet implemented: not yet, at src/exercises.rs:"
+            ),
+            "{failed}"
+        );
+        assert_eq!(
+            rest,
+            "ok      finished\n\
+             `half`: 1 of 2 check(s) failed, see src/exercises/half.rs\n"
+        );
+    }
+
+    #[test]
+    fn an_exercise_passes_when_every_check_does() {
+        let exercise = Exercise {
+            checks: &[("finished", || ())],
+            ..EXERCISES[0]
+        };
+        let mut out = Vec::new();
+        assert!(verify(&exercise, &mut out).unwrap());
+        assert!(String::from_utf8(out)
+            .unwrap()
+            .ends_with("`counter` passed: on to the next one\n"));
+    }
+
+    #[test]
+    fn hints_come_one_at_a_time_and_then_the_solution() {
+        let exercise = find("rc-owners").unwrap();
+        let mut hints = Hints::default();
+
+        assert!(hints.reveal(exercise).starts_with("hint 1/2: `Rc::new`"));
+        assert!(hints
+            .reveal(exercise)
+            .starts_with("hint 2/2: `Rc::clone(&first)`"));
+        let solution = hints.reveal(exercise);
+        assert!(solution.starts_with("the solution, in place of the `todo!()`:\nlet first"));
+        assert_eq!(hints.reveal(exercise), solution);
+        assert_eq!(hints.used("rc-owners"), 3);
+        assert_eq!(hints.used("deref"), 0);
+    }
+
+    #[test]
+    fn the_list_shows_the_hints_used() {
+        let mut hints = Hints::default();
+        hints.reveal(find("counter").unwrap());
+        for _ in 0..2 {
+            hints.reveal(find("deref").unwrap());
+        }
+        let list = list(&hints);
+
+        assert!(
+            list.contains(
+                "counter        A closure that counts, after `lambda` (1 hint(s) used)\n"
+            ),
+            "{list}"
+        );
+        assert!(
+            list.contains("after `smart-pointers/deref` (solution shown)\n"),
+            "{list}"
+        );
+        assert!(list.contains("after `thread-and-move`\n"), "{list}");
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)] // under Miri, nothing is saved
+    fn the_hints_used_are_saved_and_loaded_again() {
+        let path = env::temp_dir().join(format!("hints-{}", std::process::id()));
+        assert_eq!(Hints::load(&path).unwrap(), Hints::default());
+
+        let mut hints = Hints::default();
+        hints.reveal(find("borrow").unwrap());
+        hints.reveal(find("borrow").unwrap());
+        hints.reveal(find("counter").unwrap());
+        hints.save(&path).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        let loaded = Hints::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(text, "borrow 2\ncounter 1\n");
+        assert_eq!(loaded, hints);
+    }
+}
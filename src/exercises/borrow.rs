@@ -0,0 +1,24 @@
+//! # Fix This Borrow Error
+//! `push_and_measure` pushes `word` onto `words`, and returns how long the longest word was
+//! before the push. This is the obvious way to write it, and it doesn’t compile:
+//! ```text
+//! let longest = words.iter().max_by_key(|word| word.len()).unwrap();
+//! words.push(word);
+//! longest.len()
+//! ```
+//! ```text
+//! error[E0502]: cannot borrow `*words` as mutable because it is also borrowed as immutable
+//! ```
+//! `longest` borrows from `words`, and `push` may move its buffer, leaving `longest` dangling.
+//! Write it so that nothing borrowed from `words` is still in use when it is pushed onto. With
+//! no words yet, the longest was 0 long.
+//!
+//! See `memory slices`.
+
+#[allow(clippy::ptr_arg)] // the `todo!()` doesn’t push onto it yet, but the answer will
+pub fn push_and_measure(words: &mut Vec<String>, word: String) -> usize {
+    todo!(
+        "push `{word}` onto the {} word(s), after measuring them",
+        words.len()
+    )
+}
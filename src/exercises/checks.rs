@@ -0,0 +1,66 @@
+//! The checks `verify` runs on the exercises: no peeking before they pass.
+
+use super::{borrow, counter, deref::Meters, rc_owners, sum_in_thread};
+use std::rc::Rc;
+
+pub fn deref_gives_the_value() {
+    let meters = Meters(2.5);
+    assert_eq!(*meters, 2.5);
+}
+
+pub fn deref_coercion_calls_f64_methods() {
+    assert_eq!(Meters(16.0).sqrt(), 4.0);
+    assert_eq!(Meters(-3.0).abs(), 3.0);
+}
+
+pub fn both_own_the_same_string() {
+    let (a, b) = rc_owners::owners(String::from("Ferris"));
+    assert_eq!(*a, "Ferris");
+    assert!(Rc::ptr_eq(&a, &b), "the two point to different strings");
+}
+
+pub fn the_strong_count_is_two() {
+    let (a, b) = rc_owners::owners(String::from("Ferris"));
+    assert_eq!(Rc::strong_count(&a), 2);
+    drop(b);
+    assert_eq!(Rc::strong_count(&a), 1);
+}
+
+pub fn the_longest_before_the_push_is_measured() {
+    let mut words = vec![String::from("box"), String::from("ref-cell")];
+    assert_eq!(
+        borrow::push_and_measure(&mut words, String::from("deref-coercion")),
+        8
+    );
+    assert_eq!(words, ["box", "ref-cell", "deref-coercion"]);
+}
+
+pub fn no_words_measure_zero() {
+    let mut words = Vec::new();
+    assert_eq!(borrow::push_and_measure(&mut words, String::from("rc")), 0);
+    assert_eq!(words, ["rc"]);
+}
+
+pub fn it_counts_from_one() {
+    let mut count = counter::counter();
+    assert_eq!([count(), count(), count()], [1, 2, 3]);
+}
+
+pub fn each_counter_counts_on_its_own() {
+    let mut first = counter::counter();
+    let mut second = counter::counter();
+    first();
+    first();
+    assert_eq!(second(), 1);
+    assert_eq!(first(), 3);
+}
+
+pub fn the_thread_sums() {
+    assert_eq!(sum_in_thread::sum_in_thread(vec![1, 2, 3, 4]), 10);
+    assert_eq!(sum_in_thread::sum_in_thread(Vec::new()), 0);
+}
+
+pub fn a_long_sum() {
+    let numbers = (1..=100_000).collect();
+    assert_eq!(sum_in_thread::sum_in_thread(numbers), 5_000_050_000);
+}
@@ -0,0 +1,11 @@
+//! # A Closure That Counts
+//! `counter` returns a closure that returns 1 the first time it is called, 2 the second, and so
+//! on. It has to capture the count and mutate it, so it is an `FnMut`, and it has to own the
+//! count, since the count would otherwise be a local of `counter`, gone once it returns. It is
+//! returned in a `Box`, as each closure has a type of its own, that can’t be written out.
+//!
+//! See `lambda`.
+
+pub fn counter() -> Box<dyn FnMut() -> u32> {
+    todo!("return a closure that counts its calls")
+}
@@ -0,0 +1,19 @@
+//! # Implement `Deref`
+//! `Meters` wraps an `f64`, so that a length can’t be mixed up with any other number. Implement
+//! `Deref` for it, so that `*meters` is the `f64`, and the methods of `f64` can be called on a
+//! `Meters` directly, through deref coercion: `meters.sqrt()`.
+//!
+//! See `smart-pointers deref`.
+
+use std::ops::Deref;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Meters(pub f64);
+
+impl Deref for Meters {
+    type Target = f64;
+
+    fn deref(&self) -> &f64 {
+        todo!("return a reference to the `f64` inside")
+    }
+}
@@ -0,0 +1,53 @@
+//! The hints `hint` reveals for each exercise, one at a time, and then its solution: no
+//! peeking, either.
+
+pub const COUNTER: &[&str] = &[
+    "The count has to outlive `counter`, so the closure has to own it: a `move` closure.",
+    "Start the count at 0, and have the closure add 1 to it before returning it; mutating what \
+     it captured is what makes it an `FnMut`.",
+];
+pub const COUNTER_SOLUTION: &str = "\
+let mut count = 0;
+Box::new(move || {
+    count += 1;
+    count
+})";
+
+pub const SUM_IN_THREAD: &[&str] = &[
+    "`thread::spawn` takes a closure, and returns a `JoinHandle`, whose `join` returns what the \
+     closure returned.",
+    "The closure uses `numbers`, which the thread may outlive: `move` it in. `join` returns a \
+     `Result`, which is an `Err` only if the thread panicked.",
+];
+pub const SUM_IN_THREAD_SOLUTION: &str = "\
+std::thread::spawn(move || numbers.iter().sum())
+    .join()
+    .expect(\"the summing thread panicked\")";
+
+pub const DEREF: &[&str] = &[
+    "`deref` returns a reference to what `*meters` should be, and the `f64` is the first field \
+     of the tuple struct.",
+];
+pub const DEREF_SOLUTION: &str = "&self.0";
+
+pub const RC_OWNERS: &[&str] = &[
+    "`Rc::new` makes the first owner; the second one comes from cloning the `Rc`, not the \
+     `String`.",
+    "`Rc::clone(&first)` increments the strong count, and returns a pointer to the same \
+     allocation.",
+];
+pub const RC_OWNERS_SOLUTION: &str = "\
+let first = Rc::new(name);
+let second = Rc::clone(&first);
+(first, second)";
+
+pub const BORROW: &[&str] = &[
+    "The error is that `longest` still borrows from `words` when `push` needs it mutably. What \
+     is needed of `longest` is only its length.",
+    "Measure first, into a `usize`, which borrows nothing, then push. `max` of no words is \
+     `None`, which is 0 long.",
+];
+pub const BORROW_SOLUTION: &str = "\
+let longest = words.iter().map(|word| word.len()).max().unwrap_or(0);
+words.push(word);
+longest";
@@ -0,0 +1,12 @@
+//! # Two Owners
+//! `owners` takes a `String`, and returns two `Rc<String>`s pointing to the same one: no deep
+//! clone of the string, just a second owner of it. Both point to one allocation, and its strong
+//! count is 2.
+//!
+//! See `smart-pointers rc`.
+
+use std::rc::Rc;
+
+pub fn owners(name: String) -> (Rc<String>, Rc<String>) {
+    todo!("share `{name}` between two owners")
+}
@@ -0,0 +1,13 @@
+//! # Sum in a Thread
+//! `sum_in_thread` sums `numbers` on a thread of its own, and returns what the thread returned.
+//! The thread has to own `numbers`, since it may outlive the call that spawned it, and the sum
+//! comes back through `join`.
+//!
+//! See `thread-and-move`.
+
+pub fn sum_in_thread(numbers: Vec<u64>) -> u64 {
+    todo!(
+        "spawn a thread that sums the {} number(s), and join it",
+        numbers.len()
+    )
+}
@@ -0,0 +1,136 @@
+//! # Explaining a Lesson
+//! The text that teaches a lesson is in the `//!` doc comment at the top of its source, where
+//! the binary would never show it. `intermediate_rust --explain smart-pointers rc` prints it
+//! before running the lesson, from the source the lesson embeds with `include_str!`.
+//!
+//! The doc comments are Markdown, which a terminal doesn’t render, so `render` does a little of
+//! it: headings and `code` are colored, code blocks are indented, and list items get a bullet.
+//! Everything else is printed as written, already wrapped as it is in the source.
+//!
+//! In another language than English, the explanation is the lesson’s translation from
+//! `locale/`, if it has one, rendered the same way.
+
+use crate::intermediate_rust::{
+    lesson::Lesson,
+    locale::{self, tr},
+    style::Style,
+};
+
+/// The lines of the `//!` doc comment in `source`, without the `//!`.
+fn docs(source: &str) -> Vec<&str> {
+    source
+        .lines()
+        .filter_map(|line| line.trim_start().strip_prefix("//!"))
+        .map(|line| line.strip_prefix(' ').unwrap_or(line))
+        .collect()
+}
+
+/// `line`, with its `code` spans colored, and without their backticks if colored.
+fn inline(line: &str, color: bool) -> String {
+    if !color {
+        return line.to_string();
+    }
+    line.split('`')
+        .enumerate()
+        .map(|(i, part)| match i % 2 {
+            0 => part.to_string(),
+            _ => Style::Code.paint(part, color).to_string(),
+        })
+        .collect()
+}
+
+/// Renders the Markdown of `docs` for a terminal.
+fn render(docs: &[&str], color: bool) -> String {
+    let mut text = String::new();
+    let mut in_code = false;
+    for line in docs {
+        let line = match line.trim_start().starts_with("```") {
+            true => {
+                in_code = !in_code;
+                continue;
+            }
+            false if in_code => format!("    {}", Style::Code.paint(line, color)),
+            false if line.starts_with('#') => {
+                let heading = line.trim_start_matches('#').trim();
+                let heading = match color {
+                    true => heading.replace('`', ""),
+                    false => heading.to_string(),
+                };
+                Style::Header.paint(heading, color).to_string()
+            }
+            false => match line.strip_prefix("- ") {
+                Some(item) => format!("• {}", inline(item, color)),
+                None => inline(line, color),
+            },
+        };
+        text.push_str(&line);
+        text.push('\n');
+    }
+    text
+}
+
+/// What `lesson` teaches: its doc comment, or its title and description if it has none.
+pub fn explain(lesson: &dyn Lesson, color: bool) -> String {
+    if let Some(translated) = locale::explanation(lesson.id()) {
+        return render(&translated.lines().collect::<Vec<_>>(), color);
+    }
+    let info = lesson.info();
+    match docs(info.source) {
+        docs if docs.is_empty() => format!(
+            "{}\n{}\n",
+            Style::Header.paint(tr(lesson.title()), color),
+            tr(info.description)
+        ),
+        docs => render(&docs, color),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intermediate_rust;
+
+    const SOURCE: &str = "\
+//! # The `Drop` Trait
+//! Runs `drop` when a value goes out of scope:
+//! ```text
+//! dropped `a`
+//! ```
+//! - first item
+//!
+use std::io;
+// not a doc comment
+";
+
+    #[test]
+    fn it_renders_the_module_doc_comment() {
+        assert_eq!(
+            render(&docs(SOURCE), false),
+            "The `Drop` Trait\n\
+             Runs `drop` when a value goes out of scope:\n    \
+             dropped `a`\n\
+             • first item\n\
+             \n"
+        );
+    }
+
+    #[test]
+    fn it_colors_headings_and_code() {
+        assert_eq!(
+            render(&docs("//! # The `Drop` Trait\n//! Runs `drop`.\n"), true),
+            "\x1b[1;36mThe Drop Trait\x1b[0m\n\
+             Runs \x1b[32mdrop\x1b[0m.\n"
+        );
+    }
+
+    #[cfg(all(feature = "closures", feature = "smart-pointers"))]
+    #[test]
+    fn a_lesson_without_docs_is_explained_by_its_description() {
+        let registry = intermediate_rust::registry();
+        let lambda = registry.get("lambda").unwrap();
+        assert!(explain(lambda, false).starts_with("Closures and the `Fn` traits\nHow a closure"));
+
+        let rc = registry.get("smart-pointers/rc").unwrap();
+        assert!(explain(rc, false).starts_with("`Rc<T>`, the Reference Counted Smart Pointer\n"));
+    }
+}
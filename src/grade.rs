@@ -0,0 +1,498 @@
+//! # Grading a Lesson’s Output
+//! Every lesson ships a transcript of what it prints, in `src/transcripts/`, or, for the lessons
+//! with an insta snapshot, in `src/snapshots/`. After changing one of them, `grade` runs it with
+//! what it prints captured, and compares that with the transcript. An exercise’s transcript is
+//! what `verify` prints once the exercise is done:
+//! ```text
+//! intermediate_rust grade collections maps
+//! intermediate_rust grade exercises deref
+//! ```
+//! A transcript is what the lesson prints, line for line, except for what changes from one run
+//! to the next, like the order a `HashMap` iterates in, which is left out with a `{..}`:
+//! ```text
+//! HashMap: {..}
+//! ```
+//! A `{..}` stands for any text, even none, on the line it is on. A line that only some builds
+//! print starts with which, `{serde} ` for a build with the `serde` feature, `{debug} ` for a
+//! debug build. When the lesson didn’t print what the
+//! transcript says, the lines that differ are shown as a diff, with a few lines of context: `-`
+//! for a line of the transcript that wasn’t printed, `+` for a line printed that isn’t in the
+//! transcript. The transcripts are in English, so lessons are graded in English, whatever the
+//! `--lang`.
+
+use crate::intermediate_rust::style::Style;
+use std::fmt::Write;
+
+/// The lessons and exercises with a transcript in `src/transcripts/`, by id, and their
+/// transcripts, in the order of the lessons, and then of the exercises. An exercise’s id is its
+/// own after `exercises/`, and its transcript is what `verify` prints once it is done.
+pub const TRANSCRIPTS: &[(&str, &str)] = &[
+    ("lambda", include_str!("transcripts/lambda.txt")),
+    (
+        "thread-and-move",
+        include_str!("transcripts/thread-and-move.txt"),
+    ),
+    (
+        "smart-pointers/drop-order",
+        include_str!("transcripts/smart-pointers__drop-order.txt"),
+    ),
+    (
+        "smart-pointers/ref-cell",
+        include_str!("transcripts/smart-pointers__ref-cell.txt"),
+    ),
+    (
+        "collections/persistent",
+        include_str!("transcripts/collections__persistent.txt"),
+    ),
+    (
+        "collections/copy-on-write",
+        include_str!("transcripts/collections__copy-on-write.txt"),
+    ),
+    (
+        "collections/maps",
+        include_str!("transcripts/collections__maps.txt"),
+    ),
+    (
+        "collections/vec-deque",
+        include_str!("transcripts/collections__vec-deque.txt"),
+    ),
+    (
+        "collections/binary-heap",
+        include_str!("transcripts/collections__binary-heap.txt"),
+    ),
+    (
+        "collections/vec-internals",
+        include_str!("transcripts/collections__vec-internals.txt"),
+    ),
+    (
+        "memory/slices",
+        include_str!("transcripts/memory__slices.txt"),
+    ),
+    ("memory/dst", include_str!("transcripts/memory__dst.txt")),
+    (
+        "memory/niche",
+        include_str!("transcripts/memory__niche.txt"),
+    ),
+    (
+        "memory/layout",
+        include_str!("transcripts/memory__layout.txt"),
+    ),
+    (
+        "memory/replace",
+        include_str!("transcripts/memory__replace.txt"),
+    ),
+    (
+        "memory/const-eval",
+        include_str!("transcripts/memory__const-eval.txt"),
+    ),
+    ("strings", include_str!("transcripts/strings.txt")),
+    ("logging", include_str!("transcripts/logging.txt")),
+    (
+        "unsafe-rust/superpowers",
+        include_str!("transcripts/unsafe-rust__superpowers.txt"),
+    ),
+    (
+        "unsafe-rust/casts",
+        include_str!("transcripts/unsafe-rust__casts.txt"),
+    ),
+    (
+        "unsafe-rust/non-null",
+        include_str!("transcripts/unsafe-rust__non-null.txt"),
+    ),
+    (
+        "unsafe-rust/miri",
+        include_str!("transcripts/unsafe-rust__miri.txt"),
+    ),
+    (
+        "unsafe-rust/fuzzing",
+        include_str!("transcripts/unsafe-rust__fuzzing.txt"),
+    ),
+    (
+        "ffi/calling-c",
+        include_str!("transcripts/ffi__calling-c.txt"),
+    ),
+    (
+        "ffi/called-from-c",
+        include_str!("transcripts/ffi__called-from-c.txt"),
+    ),
+    (
+        "build-scripts",
+        include_str!("transcripts/build-scripts.txt"),
+    ),
+    (
+        "plugins/self-registration",
+        include_str!("transcripts/plugins__self-registration.txt"),
+    ),
+    (
+        "plugins/dynamic-loading",
+        include_str!("transcripts/plugins__dynamic-loading.txt"),
+    ),
+    (
+        "macros/declarative",
+        include_str!("transcripts/macros__declarative.txt"),
+    ),
+    (
+        "macros/procedural",
+        include_str!("transcripts/macros__procedural.txt"),
+    ),
+    ("patterns", include_str!("transcripts/patterns.txt")),
+    ("serde", include_str!("transcripts/serde.txt")),
+    ("io/files", include_str!("transcripts/io__files.txt")),
+    ("io/tcp", include_str!("transcripts/io__tcp.txt")),
+    (
+        "io/processes",
+        include_str!("transcripts/io__processes.txt"),
+    ),
+    ("config", include_str!("transcripts/config.txt")),
+    ("testing", include_str!("transcripts/testing.txt")),
+    ("no-std/box", include_str!("transcripts/no-std__box.txt")),
+    ("no-std/rc", include_str!("transcripts/no-std__rc.txt")),
+    (
+        "no-std/ref-cell",
+        include_str!("transcripts/no-std__ref-cell.txt"),
+    ),
+    (
+        "no-std/iterators",
+        include_str!("transcripts/no-std__iterators.txt"),
+    ),
+    (
+        "no-std/my-box",
+        include_str!("transcripts/no-std__my-box.txt"),
+    ),
+    (
+        "exercises/counter",
+        include_str!("transcripts/exercises__counter.txt"),
+    ),
+    (
+        "exercises/sum-in-thread",
+        include_str!("transcripts/exercises__sum-in-thread.txt"),
+    ),
+    (
+        "exercises/deref",
+        include_str!("transcripts/exercises__deref.txt"),
+    ),
+    (
+        "exercises/rc-owners",
+        include_str!("transcripts/exercises__rc-owners.txt"),
+    ),
+    (
+        "exercises/borrow",
+        include_str!("transcripts/exercises__borrow.txt"),
+    ),
+];
+
+/// The lessons whose transcript is their snapshot in `src/snapshots/`, which
+/// `cargo test --features snapshots` checks what they print against; its redactions are a
+/// transcript’s `{..}`, so only the header insta writes above what was printed is left out.
+pub const SNAPSHOTS: &[(&str, &str)] = &[
+    (
+        "smart-pointers/box",
+        include_str!("snapshots/intermediate_rust__runner__snapshots__smart-pointers__box.snap"),
+    ),
+    (
+        "smart-pointers/rc",
+        include_str!("snapshots/intermediate_rust__runner__snapshots__smart-pointers__rc.snap"),
+    ),
+    (
+        "smart-pointers/deref",
+        include_str!("snapshots/intermediate_rust__runner__snapshots__smart-pointers__deref.snap"),
+    ),
+    (
+        "smart-pointers/drop",
+        include_str!("snapshots/intermediate_rust__runner__snapshots__smart-pointers__drop.snap"),
+    ),
+];
+
+/// What a line of a transcript can depend on the build for: a line that starts with `{serde} `
+/// is only printed in a build with the `serde` feature, and one that starts with `{debug} ` only
+/// in a debug build, such as the borrows `DebugRefCell` only records in one.
+const BUILDS: &[(&str, bool)] = &[
+    ("serde", cfg!(feature = "serde")),
+    ("debug", cfg!(debug_assertions)),
+];
+
+/// The transcript of the lesson or exercise `id`, for this build.
+pub fn transcript(id: &str) -> Result<String, String> {
+    let find = |transcripts: &[(&str, &'static str)]| {
+        transcripts
+            .iter()
+            .find(|(lesson, _)| *lesson == id)
+            .map(|(_, transcript)| *transcript)
+    };
+    let transcript = match (find(TRANSCRIPTS), find(SNAPSHOTS)) {
+        (Some(transcript), _) => transcript,
+        // `---`, insta’s header, `---`, and then what was printed
+        (None, Some(snapshot)) => snapshot.splitn(3, "---\n").nth(2).unwrap_or_default(),
+        (None, None) => return Err(format!("`{id}` has no transcript")),
+    };
+    Ok(for_this_build(transcript))
+}
+
+/// `transcript` without the lines of the builds this one isn’t, and with the `{build} ` taken
+/// off the lines of those it is.
+fn for_this_build(transcript: &str) -> String {
+    transcript
+        .lines()
+        .filter_map(|line| {
+            let build = BUILDS.iter().find_map(|(build, this_one)| {
+                let rest = line.strip_prefix(&format!("{{{build}}} "))?;
+                Some((rest, *this_one))
+            });
+            match build {
+                Some((rest, this_one)) => this_one.then_some(rest),
+                None => Some(line),
+            }
+        })
+        .map(|line| format!("{line}\n"))
+        .collect()
+}
+
+/// Whether `line` is what `expected`, a line of a transcript, says, with each `{..}` in it
+/// standing for any text.
+pub fn matches(expected: &str, line: &str) -> bool {
+    let mut parts = expected.split("{..}");
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = line.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts: Vec<_> = parts.collect();
+    let Some(last) = parts.pop() else {
+        // no `{..}` at all
+        return rest.is_empty();
+    };
+    for part in parts {
+        match rest.find(part) {
+            Some(at) => rest = &rest[at + part.len()..],
+            None => return false,
+        }
+    }
+    rest.len() >= last.len() && rest.ends_with(last)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Line<'t> {
+    /// A line of the transcript, and the line printed that matched it.
+    Same(&'t str),
+    /// A line of the transcript that wasn’t printed.
+    Missing(&'t str),
+    /// A line printed that isn’t in the transcript.
+    Unexpected(&'t str),
+}
+
+/// The lines of `transcript` and of `printed`, matched up as a diff does: the longest sequence of
+/// lines that match, in order, with what is left in between missing or unexpected.
+pub fn diff<'t>(transcript: &'t str, printed: &'t str) -> Vec<Line<'t>> {
+    let (expected, actual): (Vec<_>, Vec<_>) =
+        (transcript.lines().collect(), printed.lines().collect());
+    // longest[i][j]: how many lines match, at most, from `expected[i..]` and `actual[j..]`
+    let mut longest = vec![vec![0; actual.len() + 1]; expected.len() + 1];
+    for i in (0..expected.len()).rev() {
+        for j in (0..actual.len()).rev() {
+            longest[i][j] = match matches(expected[i], actual[j]) {
+                true => longest[i + 1][j + 1] + 1,
+                false => longest[i + 1][j].max(longest[i][j + 1]),
+            };
+        }
+    }
+    let (mut i, mut j) = (0, 0);
+    let mut lines = Vec::new();
+    while i < expected.len() || j < actual.len() {
+        if i < expected.len() && j < actual.len() && matches(expected[i], actual[j]) {
+            lines.push(Line::Same(actual[j]));
+            (i, j) = (i + 1, j + 1);
+        } else if i < expected.len()
+            && (j == actual.len() || longest[i + 1][j] >= longest[i][j + 1])
+        {
+            // what was missing first, as `diff` does
+            lines.push(Line::Missing(expected[i]));
+            i += 1;
+        } else {
+            lines.push(Line::Unexpected(actual[j]));
+            j += 1;
+        }
+    }
+    lines
+}
+
+/// How many lines of context are shown around the lines that differ.
+const CONTEXT: usize = 2;
+
+/// The diff between `transcript` and `printed`, if they differ: the lines that differ, with
+/// `CONTEXT` lines around them, and `...` where lines that are the same were left out.
+pub fn report(transcript: &str, printed: &str, color: bool) -> Option<String> {
+    let lines = diff(transcript, printed);
+    let changed: Vec<_> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !matches!(line, Line::Same(_)))
+        .map(|(at, _)| at)
+        .collect();
+    if changed.is_empty() {
+        return None;
+    }
+    let shown = |at: usize| {
+        changed
+            .iter()
+            .any(|changed| changed.abs_diff(at) <= CONTEXT)
+    };
+    let mut report = String::new();
+    let mut skipped = false;
+    for (at, line) in lines.iter().enumerate() {
+        if !shown(at) {
+            skipped = true;
+            continue;
+        }
+        if std::mem::take(&mut skipped) || (at > 0 && report.is_empty()) {
+            report.push_str("  ...\n");
+        }
+        let _ = match line {
+            Line::Same(line) => writeln!(report, "  {line}"),
+            Line::Missing(line) => writeln!(
+                report,
+                "{}",
+                Style::Removed.paint(format!("- {line}"), color)
+            ),
+            Line::Unexpected(line) => {
+                writeln!(report, "{}", Style::Added.paint(format!("+ {line}"), color))
+            }
+        };
+    }
+    if skipped {
+        report.push_str("  ...\n");
+    }
+    Some(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        exercises::{self, EXERCISES},
+        intermediate_rust,
+        intermediate_rust::output::Verbosity,
+        runner,
+    };
+
+    #[test]
+    fn a_placeholder_stands_for_any_text_on_its_line() {
+        assert!(matches("HashMap: {..}", "HashMap: Mars=4 Earth=3"));
+        assert!(matches("HashMap: {..}", "HashMap: "));
+        assert!(matches("{..} took {..}ms", "the lesson took 12ms"));
+        assert!(matches("a{..}a", "aa"));
+        assert!(!matches("a{..}ab", "ab"));
+        assert!(!matches("HashMap: {..}", "BTreeMap: Earth=3"));
+        assert!(!matches("len = 4", "len = 42"));
+        assert!(matches("len = 4", "len = 4"));
+    }
+
+    #[test]
+    fn the_diff_keeps_the_lines_that_match_in_order() {
+        assert_eq!(
+            diff("a\nb = {..}\nc\n", "a\nb = 2\nx\nc\n"),
+            [
+                Line::Same("a"),
+                Line::Same("b = 2"),
+                Line::Unexpected("x"),
+                Line::Same("c")
+            ]
+        );
+        assert_eq!(
+            diff("a\nb\nc\n", "a\nc\n"),
+            [Line::Same("a"), Line::Missing("b"), Line::Same("c")]
+        );
+    }
+
+    #[test]
+    fn the_report_shows_what_differs_with_some_context() {
+        let transcript = "1\n2\n3\n4\n5\n6\n7\n8\n";
+        let printed = "1\n2\n3\n4\n5\nsix\n7\n8\n";
+
+        assert_eq!(report(transcript, transcript, false), None);
+        assert_eq!(
+            report(transcript, printed, false).unwrap(),
+            "  ...\n  4\n  5\n- 6\n+ six\n  7\n  8\n"
+        );
+        assert_eq!(
+            report("1\n2\n3\n4\n", "0\n1\n2\n3\n4\n", false).unwrap(),
+            "+ 0\n  1\n  2\n  ...\n"
+        );
+    }
+
+    #[test]
+    fn transcripts_are_found_by_lesson() {
+        assert!(transcript("collections/maps")
+            .unwrap()
+            .contains("HashMap: {..}"));
+        assert!(transcript("exercises/deref")
+            .unwrap()
+            .ends_with("`deref` passed: on to the next one\n"));
+        assert_eq!(
+            transcript("nothing").unwrap_err(),
+            "`nothing` has no transcript"
+        );
+    }
+
+    #[test]
+    fn a_snapshot_is_a_transcript_without_its_header() {
+        let transcript = transcript("smart-pointers/drop").unwrap();
+        assert!(transcript.starts_with("CustomSmartPointers created.\n"));
+        assert!(transcript.contains("(drop #{..})"));
+    }
+
+    #[test]
+    fn the_lines_of_a_build_are_only_in_that_build() {
+        let transcript = for_this_build("a\n{serde} b\n{..} c\n");
+        match cfg!(feature = "serde") {
+            true => assert_eq!(transcript, "a\nb\n{..} c\n"),
+            false => assert_eq!(transcript, "a\n{..} c\n"),
+        }
+    }
+
+    #[cfg(feature = "chapters")]
+    #[test]
+    #[cfg_attr(miri, ignore)] // too slow, and the lessons skip what Miri can't run
+    fn every_lesson_prints_what_its_transcript_says() {
+        let registry = intermediate_rust::registry();
+        for lesson in registry.iter() {
+            let transcript = transcript(lesson.id()).unwrap();
+            let output = runner::run_captured(lesson, Verbosity::Normal).unwrap();
+            if let Some(report) = report(&transcript, &output.stdout, false) {
+                panic!(
+                    "`{}` didn't print what its transcript says:\n{report}",
+                    lesson.id()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn every_exercise_has_a_transcript_of_its_checks_passing() {
+        for exercise in EXERCISES {
+            let transcript = transcript(&format!("exercises/{}", exercise.id)).unwrap();
+            let mut lines = transcript.lines();
+            for (name, _) in exercise.checks {
+                assert_eq!(lines.next(), Some(format!("ok      {name}").as_str()));
+            }
+            let passed = format!("`{}` passed: on to the next one", exercise.id);
+            assert_eq!(lines.next(), Some(passed.as_str()));
+            assert_eq!(lines.next(), None);
+        }
+    }
+
+    #[cfg(feature = "chapters")]
+    #[test]
+    fn every_transcript_is_of_a_lesson_or_an_exercise() {
+        let registry = intermediate_rust::registry();
+        for (id, _) in TRANSCRIPTS.iter().chain(SNAPSHOTS) {
+            // the lessons without `std` aren’t one of the `chapters`
+            if id.starts_with("no-std/") && !cfg!(feature = "no-std") {
+                continue;
+            }
+            let exercise = id.strip_prefix("exercises/").map(exercises::find);
+            assert!(
+                registry.get(id).is_some() || exercise.is_some_and(|found| found.is_ok()),
+                "{id}"
+            );
+        }
+    }
+}
@@ -1,24 +1,357 @@
-use std::io::Result;
+use lesson::{Lesson, Registry};
+use std::{
+    fmt::{self, Display},
+    str::FromStr,
+};
 
+// what every chapter builds on is in `ir-core`, and its macros, imported in `lib.rs`, stand in
+// for the standard printing macros here as well
+pub use ir_core::{lesson, locale, output, random, style};
+pub(crate) use ir_core::{metrics, utils};
+
+// the runner’s settings, which the `config` chapter’s lesson is about, so they are built without
+// the chapter too
+pub mod config;
+
+// each chapter is a feature, see `Cargo.toml`; the ones with a crate of their own are in `crates/`
+#[cfg(feature = "build-scripts")]
+mod build_scripts;
+#[cfg(feature = "collections")]
+pub(crate) mod collections;
+#[cfg(feature = "ffi")]
+mod ffi;
+#[cfg(feature = "io")]
+mod io_lessons;
+#[cfg(feature = "closures")]
 mod lambda;
-mod smart_pointers;
-mod thread_and_move;
+#[cfg(feature = "logging")]
+mod logging;
+#[cfg(feature = "macros")]
+mod macros;
+#[cfg(feature = "memory")]
+pub(crate) mod memory;
+#[cfg(feature = "no-std")]
+mod no_std;
+#[cfg(feature = "patterns")]
+mod patterns_matching;
+#[cfg(feature = "plugins")]
+mod plugins;
+#[cfg(feature = "serialization")]
+mod serde_lessons;
+#[cfg(feature = "smart-pointers")]
+pub use ir_smart_pointers as smart_pointers;
+#[cfg(feature = "strings")]
+mod strings;
+#[cfg(feature = "testing")]
+pub mod testing;
+#[cfg(feature = "unsafe-rust")]
+pub mod unsafe_rust;
+
+// the chapters with their lessons in `intermediate_rust/lessons.toml`, which `build.rs` submits
+include!(concat!(env!("OUT_DIR"), "/lessons.rs"));
 
-#[allow(dead_code)]
-pub enum IntermediateRust {
-    Nothing,
+// the chapters with a crate of their own submit themselves, but a crate nothing uses isn’t
+// linked, and its submissions with it
+#[cfg(feature = "concurrency")]
+use ir_concurrency as _;
+
+/// Every lesson of the chapters in the build, in the order the chapters build on each other.
+pub fn registry() -> Registry {
+    let order: Vec<_> = Topic::ALL.iter().map(|topic| topic.id()).collect();
+    Registry::discover(&order)
+}
+
+/// The chapters, by the topic their lessons’ ids start with, for code that picks them from a list
+/// rather than by a string. Only the chapters in the build are variants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Topic {
+    #[cfg(feature = "closures")]
     Lambda,
+    #[cfg(feature = "concurrency")]
     ThreadAndMove,
+    #[cfg(feature = "smart-pointers")]
     SmartPointers,
+    #[cfg(feature = "collections")]
+    Collections,
+    #[cfg(feature = "memory")]
+    Memory,
+    #[cfg(feature = "strings")]
+    Strings,
+    #[cfg(feature = "logging")]
+    Logging,
+    #[cfg(feature = "unsafe-rust")]
+    UnsafeRust,
+    #[cfg(feature = "ffi")]
+    Ffi,
+    #[cfg(feature = "build-scripts")]
+    BuildScripts,
+    #[cfg(feature = "plugins")]
+    Plugins,
+    #[cfg(feature = "macros")]
+    Macros,
+    #[cfg(feature = "patterns")]
+    Patterns,
+    #[cfg(feature = "serialization")]
+    Serde,
+    #[cfg(feature = "io")]
+    Io,
+    #[cfg(feature = "config")]
+    Config,
+    #[cfg(feature = "testing")]
+    Testing,
+    #[cfg(feature = "no-std")]
+    NoStd,
+}
+impl Topic {
+    /// Every topic, in the order `registry` registers them.
+    pub const ALL: &'static [Topic] = &[
+        #[cfg(feature = "closures")]
+        Topic::Lambda,
+        #[cfg(feature = "concurrency")]
+        Topic::ThreadAndMove,
+        #[cfg(feature = "smart-pointers")]
+        Topic::SmartPointers,
+        #[cfg(feature = "collections")]
+        Topic::Collections,
+        #[cfg(feature = "memory")]
+        Topic::Memory,
+        #[cfg(feature = "strings")]
+        Topic::Strings,
+        #[cfg(feature = "logging")]
+        Topic::Logging,
+        #[cfg(feature = "unsafe-rust")]
+        Topic::UnsafeRust,
+        #[cfg(feature = "ffi")]
+        Topic::Ffi,
+        #[cfg(feature = "build-scripts")]
+        Topic::BuildScripts,
+        #[cfg(feature = "plugins")]
+        Topic::Plugins,
+        #[cfg(feature = "macros")]
+        Topic::Macros,
+        #[cfg(feature = "patterns")]
+        Topic::Patterns,
+        #[cfg(feature = "serialization")]
+        Topic::Serde,
+        #[cfg(feature = "io")]
+        Topic::Io,
+        #[cfg(feature = "config")]
+        Topic::Config,
+        #[cfg(feature = "testing")]
+        Topic::Testing,
+        #[cfg(feature = "no-std")]
+        Topic::NoStd,
+    ];
+
+    /// The topic as the lessons’ ids, and the command line, spell it: `smart-pointers`.
+    pub fn id(self) -> &'static str {
+        match self {
+            #[cfg(feature = "closures")]
+            Topic::Lambda => "lambda",
+            #[cfg(feature = "concurrency")]
+            Topic::ThreadAndMove => "thread-and-move",
+            #[cfg(feature = "smart-pointers")]
+            Topic::SmartPointers => "smart-pointers",
+            #[cfg(feature = "collections")]
+            Topic::Collections => "collections",
+            #[cfg(feature = "memory")]
+            Topic::Memory => "memory",
+            #[cfg(feature = "strings")]
+            Topic::Strings => "strings",
+            #[cfg(feature = "logging")]
+            Topic::Logging => "logging",
+            #[cfg(feature = "unsafe-rust")]
+            Topic::UnsafeRust => "unsafe-rust",
+            #[cfg(feature = "ffi")]
+            Topic::Ffi => "ffi",
+            #[cfg(feature = "build-scripts")]
+            Topic::BuildScripts => "build-scripts",
+            #[cfg(feature = "plugins")]
+            Topic::Plugins => "plugins",
+            #[cfg(feature = "macros")]
+            Topic::Macros => "macros",
+            #[cfg(feature = "patterns")]
+            Topic::Patterns => "patterns",
+            #[cfg(feature = "serialization")]
+            Topic::Serde => "serde",
+            #[cfg(feature = "io")]
+            Topic::Io => "io",
+            #[cfg(feature = "config")]
+            Topic::Config => "config",
+            #[cfg(feature = "testing")]
+            Topic::Testing => "testing",
+            #[cfg(feature = "no-std")]
+            Topic::NoStd => "no-std",
+        }
+    }
+
+    /// The lessons of this topic in `registry`, in the order they were registered.
+    pub fn lessons(self, registry: &Registry) -> impl Iterator<Item = &dyn Lesson> {
+        registry.filter(move |lesson| lesson.topic() == self.id())
+    }
+}
+impl FromStr for Topic {
+    type Err = String;
+
+    /// `snake_case` works as well as `kebab-case`, as it does for `Registry::find`.
+    fn from_str(id: &str) -> Result<Topic, String> {
+        let id = id.trim().replace('_', "-");
+        Topic::ALL
+            .iter()
+            .copied()
+            .find(|topic| topic.id() == id)
+            .ok_or_else(|| {
+                let ids: Vec<_> = Topic::ALL.iter().map(|topic| topic.id()).collect();
+                format!(
+                    "there is no topic `{id}`, the topics are: {}",
+                    ids.join(", ")
+                )
+            })
+    }
+}
+impl Display for Topic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(self.id())
+    }
 }
 
-pub fn run(intermediate_rust: IntermediateRust) -> Result<()> {
-    match intermediate_rust {
-        IntermediateRust::Lambda => lambda::lambda(),
-        IntermediateRust::ThreadAndMove => thread_and_move::main_thread(),
-        IntermediateRust::SmartPointers => {
-            smart_pointers::smart_pointer(smart_pointers::SmartPointer::RefCellT)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_lesson_is_found_by_its_id_in_snake_case() {
+        let registry = registry();
+        for lesson in registry.iter() {
+            let snake_case = lesson.id().replace('-', "_");
+            assert_eq!(
+                registry.find(&snake_case).map(|x| x.id()),
+                Some(lesson.id())
+            );
+        }
+    }
+
+    #[cfg(feature = "chapters")]
+    #[test]
+    fn every_topic_has_a_lesson_of_its_own() {
+        let registry = registry();
+        let chapters = [
+            "lambda",
+            "thread-and-move",
+            "smart-pointers",
+            "collections",
+            "memory",
+            "strings",
+            "logging",
+            "unsafe-rust",
+            "ffi",
+            "build-scripts",
+            "plugins",
+            "macros",
+            "patterns",
+            "serde",
+            "io",
+            "config",
+            "testing",
+        ];
+        assert_eq!(registry.topics()[..chapters.len()], chapters);
+        for topic in registry.topics() {
+            let lesson = registry.find(topic).unwrap();
+            assert_eq!(lesson.topic(), topic);
+        }
+        assert_eq!(
+            registry.find("smart-pointers").map(|x| x.id()),
+            Some("smart-pointers/ref-cell")
+        );
+    }
+
+    #[test]
+    fn the_topics_are_the_registrys_in_its_order() {
+        let registry = registry();
+        let ids: Vec<_> = Topic::ALL.iter().map(|topic| topic.id()).collect();
+        assert_eq!(ids, registry.topics());
+        for &topic in Topic::ALL {
+            assert_eq!(topic.id().replace('-', "_").parse(), Ok(topic));
+            assert!(topic.lessons(&registry).all(|x| x.topic() == topic.id()));
+            assert!(topic.lessons(&registry).next().is_some());
+        }
+        assert!("cooking".parse::<Topic>().is_err());
+    }
+
+    #[test]
+    fn every_lesson_describes_itself() {
+        for lesson in registry().iter() {
+            let info = lesson.info();
+            assert!(!info.description.is_empty(), "{}", lesson.id());
+            assert!(!info.tags.is_empty(), "{}", lesson.id());
+        }
+    }
+
+    /// `--lang id` shows a lesson in Indonesian only if each of its texts is in the table: its
+    /// title, its description, and the headers of its parts, printed by `header!` or, without
+    /// `std`, by `Output::header`. A header with arguments is formatted, not translated, so only
+    /// the literal ones are looked for.
+    #[cfg(feature = "chapters")]
+    #[test]
+    fn every_text_in_the_lessons_is_translated() {
+        let table = locale::Lang::Id.table();
+        let translated = |text: &str| table.iter().any(|(english, _)| *english == text);
+        let registry = registry();
+        let mut missing = vec![];
+        for lesson in registry.iter() {
+            let info = lesson.info();
+            let headers = info.source.split("header!(\"").skip(1);
+            let headers = headers.chain(info.source.split(".header(\"").skip(1));
+            let headers = headers.filter_map(|rest| {
+                let (header, after) = rest.split_once('"')?;
+                after.starts_with(')').then_some(header)
+            });
+            for text in [lesson.title(), info.description]
+                .into_iter()
+                .chain(headers)
+            {
+                if !translated(text) && !missing.contains(&text) {
+                    missing.push(text);
+                }
+            }
+        }
+        assert!(missing.is_empty(), "not translated into `id`: {missing:#?}");
+    }
+
+    /// A translation whose English was changed in the lesson would never be shown again.
+    #[cfg(feature = "chapters")]
+    #[test]
+    fn every_translation_is_of_a_text_in_the_lessons() {
+        let registry = registry();
+        // the lessons without `std` aren't among `chapters`, but their translations are there
+        let without_std: &[&str] = if cfg!(feature = "no-std") {
+            &[]
+        } else {
+            &[
+                include_str!("intermediate_rust/no_std.rs"),
+                include_str!("../crates/ir-no-std/src/box_t.rs"),
+                include_str!("../crates/ir-no-std/src/rc_t.rs"),
+                include_str!("../crates/ir-no-std/src/ref_cell_t.rs"),
+                include_str!("../crates/ir-no-std/src/iterators.rs"),
+                include_str!("../crates/ir-no-std/src/my_box.rs"),
+            ]
+        };
+        let texts: Vec<_> = registry
+            .iter()
+            .flat_map(|lesson| {
+                let info = lesson.info();
+                [lesson.title(), info.description, info.source]
+            })
+            .chain(without_std.iter().copied())
+            .collect();
+        for (english, _) in locale::Lang::Id.table() {
+            assert!(
+                texts.iter().any(|text| text.contains(english)),
+                "no lesson says {english:?}"
+            );
+        }
+        for (id, _) in locale::Lang::Id.explanations() {
+            assert!(registry.get(id).is_some(), "no lesson is called `{id}`");
         }
-        _ => Ok(()),
     }
 }
@@ -0,0 +1,83 @@
+//! # Build Scripts
+//! Some packages need code built, or generated, before they can be compiled: a C library to link
+//! against, bindings to it, or Rust written from a description that is easier to keep than the
+//! Rust itself. Placing a file named `build.rs` in the root of a package causes Cargo to compile
+//! that script and execute it just before building the package.
+//!
+//! This package has one. The registry of its lessons is written by it, from the manifest
+//! `src/intermediate_rust/lessons.toml`: adding a lesson is adding it there, and the script
+//! checks what Rust couldn’t, that each lesson’s prerequisites are registered before it, that
+//! its source file is there, that no id is taken twice, and stops the build if one isn’t so.
+
+use std::{io::Result, path::Path};
+
+pub fn build_scripts() -> Result<()> {
+    Ok({
+        out_dir()?;
+        including_generated_code()?;
+        talking_to_cargo()?;
+    })
+}
+
+/// # `OUT_DIR`
+/// A build script mustn’t write into the package’s sources: they may be read-only, and shared
+/// between builds with different features. Cargo gives each package its own directory to write
+/// to instead, and sets `OUT_DIR` to it, for the build script to read with `env::var` and the
+/// package with `env!`, which reads the variable when it is compiled rather than when it runs.
+fn out_dir() -> Result<()> {
+    Ok({
+        span!("out_dir");
+        header!("`OUT_DIR`");
+
+        let out_dir = Path::new(env!("OUT_DIR"));
+        // target/debug/build/intermediate_rust-<hash>/out
+        let out = out_dir.file_name().unwrap_or_default();
+        println!("OUT_DIR ends in {out:?}"); // OUT_DIR ends in "out"
+        let build = out_dir.ancestors().nth(2).and_then(Path::file_name);
+        println!("within {:?}", build.unwrap_or_default()); // within "build"
+    })
+}
+
+/// # Including Generated Code
+/// `include!` pastes a file in as Rust, where it is written, and `include_str!` as a `&str`:
+/// `intermediate_rust.rs` `include!`s `lessons.rs`, which the build script wrote to `OUT_DIR`,
+/// and here it is as text. `concat!(env!("OUT_DIR"), "/lessons.rs")` is its path, as both take
+/// a literal, and a macro that expands to one.
+fn including_generated_code() -> Result<()> {
+    Ok({
+        span!("including_generated_code");
+        header!("Including Generated Code");
+
+        let generated = include_str!(concat!(env!("OUT_DIR"), "/lessons.rs"));
+        println!("{}", generated.lines().next().unwrap_or_default());
+        // // Generated by `build.rs` from `src/intermediate_rust/lessons.toml`: edit that instead.
+        let registered = generated.matches("registry.register(").count();
+        println!("lessons written out: {registered}"); // lessons written out: 34
+
+        // `build.rs` counted them as well, and passed the count on as a variable
+        println!("lessons counted: {}", env!("IR_MANIFEST_LESSONS")); // lessons counted: 34
+    })
+}
+
+/// # Talking to Cargo
+/// A build script talks to Cargo by printing lines that start with `cargo::`. `rustc-env` sets
+/// a variable for `env!`, `rustc-link-lib` links a library, and `rerun-if-changed` says which
+/// files the script reads: without it, Cargo runs the script again whenever any file of the
+/// package changes, and with it, only when one of those does, or the script itself.
+fn talking_to_cargo() -> Result<()> {
+    Ok({
+        span!("talking_to_cargo");
+        header!("Talking to Cargo");
+
+        let build_rs = include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/build.rs"));
+        for line in build_rs
+            .lines()
+            .filter(|line| line.contains("println!(\"cargo::"))
+        {
+            println!("{}", line.trim());
+        }
+        // println!("cargo::rerun-if-changed={MANIFEST}");
+        // println!("cargo::rerun-if-changed=src/intermediate_rust/ffi/helper.c");
+        // println!("cargo::rustc-env=IR_MANIFEST_LESSONS={lessons}");
+    })
+}
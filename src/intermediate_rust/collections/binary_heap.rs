@@ -0,0 +1,241 @@
+//! # Priority Queues with `BinaryHeap<T>`
+//! A `BinaryHeap<T>` always knows its greatest element: `peek` is O(1), and `push` and `pop`
+//! are O(log n). It stores its elements in a `Vec<T>` arranged as a binary tree in which every
+//! parent is greater than or equal to its children, which is all the ordering it maintains, so
+//! iterating over a heap visits the elements in no particular order.
+//!
+//! “Greatest” is decided by the element’s `Ord` implementation alone, which makes the heap easy
+//! to bend to other orders: wrap the elements in `std::cmp::Reverse` for a min-heap, or give
+//! your own type an `Ord` that compares exactly what should be prioritized.
+
+use std::{
+    cell::Cell,
+    cmp::{Ordering, Reverse},
+    collections::BinaryHeap,
+    io::Result,
+    rc::Rc,
+};
+
+pub fn binary_heap() -> Result<()> {
+    Ok({
+        a_max_heap()?;
+        a_min_heap_with_reverse()?;
+        a_custom_ord_for_dijkstra()?;
+        mutating_keys_inside_the_heap()?;
+    })
+}
+
+fn a_max_heap() -> Result<()> {
+    Ok({
+        span!("a_max_heap");
+        header!("A Max-Heap");
+
+        let mut heap = BinaryHeap::from(vec![3, 1, 4, 1, 5, 9, 2, 6]);
+        println!("peek = {:?}", heap.peek()); // peek = Some(9)
+
+        heap.push(7);
+        print!("pop order:");
+        while let Some(i) = heap.pop() {
+            print!(" {i}");
+        }
+        println!(); // pop order: 9 7 6 5 4 3 2 1 1
+
+        // `into_sorted_vec` is heapsort: ascending, because it keeps popping the maximum to the back
+        let heap = BinaryHeap::from(vec![3, 1, 4]);
+        println!("into_sorted_vec = {:?}", heap.into_sorted_vec()); // into_sorted_vec = [1, 3, 4]
+    })
+}
+
+/// # A Min-Heap with `Reverse`
+/// `Reverse<T>` is a wrapper whose `Ord` is the opposite of `T`’s, so the “greatest” `Reverse`
+/// is the smallest `T`. Pattern matching on `Reverse(x)` unwraps it again.
+fn a_min_heap_with_reverse() -> Result<()> {
+    Ok({
+        span!("a_min_heap_with_reverse");
+        header!("A Min-Heap with Reverse");
+
+        let mut heap = BinaryHeap::new();
+        for i in [5, 1, 8, 3] {
+            heap.push(Reverse(i));
+        }
+
+        print!("pop order:");
+        while let Some(Reverse(i)) = heap.pop() {
+            print!(" {i}");
+        }
+        println!(); // pop order: 1 3 5 8
+
+        // tuples compare field by field, so `Reverse` can also reverse just one of them
+        let mut jobs = BinaryHeap::new();
+        jobs.push((2, Reverse("b")));
+        jobs.push((2, Reverse("a")));
+        jobs.push((1, Reverse("c")));
+        print!("highest priority first, then alphabetical:");
+        while let Some((priority, Reverse(name))) = jobs.pop() {
+            print!(" {priority}{name}");
+        }
+        println!(); // highest priority first, then alphabetical: 2a 2b 1c
+    })
+}
+
+/// # A Custom `Ord` for Dijkstra’s Frontier
+/// Dijkstra’s shortest-path algorithm repeatedly expands the unvisited node with the smallest
+/// known distance. The frontier is a priority queue of `(cost, node)` entries, and since we
+/// want the _smallest_ cost first, `State` flips the comparison on `cost`.
+///
+/// `Ord` must be a total order consistent with `PartialEq`, so ties on `cost` are broken by
+/// `node`; otherwise two different states would compare `Equal` while not being equal.
+#[derive(Debug, PartialEq, Eq)]
+struct State {
+    cost: usize,
+    node: usize,
+}
+impl Ord for State {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .cost
+            .cmp(&self.cost) // flipped: lower cost is “greater”
+            .then_with(|| self.node.cmp(&other.node))
+    }
+}
+impl PartialOrd for State {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+/// `adjacency[node]` lists the `(neighbor, weight)` edges leaving `node`.
+fn shortest_distances(adjacency: &[Vec<(usize, usize)>], start: usize) -> Vec<Option<usize>> {
+    let mut distances: Vec<Option<usize>> = vec![None; adjacency.len()];
+    let mut frontier = BinaryHeap::new();
+
+    distances[start] = Some(0);
+    frontier.push(State {
+        cost: 0,
+        node: start,
+    });
+
+    while let Some(State { cost, node }) = frontier.pop() {
+        // A node can be pushed more than once; skip the stale, more expensive entries
+        if distances[node].is_some_and(|best| cost > best) {
+            continue;
+        }
+        for &(neighbor, weight) in &adjacency[node] {
+            let next = State {
+                cost: cost + weight,
+                node: neighbor,
+            };
+            if distances[neighbor].is_none_or(|best| next.cost < best) {
+                distances[neighbor] = Some(next.cost);
+                frontier.push(next);
+            }
+        }
+    }
+
+    distances
+}
+fn a_custom_ord_for_dijkstra() -> Result<()> {
+    Ok({
+        span!("a_custom_ord_for_dijkstra");
+        header!("A Custom Ord for Dijkstra’s Frontier");
+
+        //        7
+        //   0 ------- 1
+        //   |         |
+        // 2 |         | 1
+        //   |    3    |
+        //   2 ------- 3      4 (unreachable)
+        let adjacency = vec![
+            vec![(1, 7), (2, 2)],
+            vec![(0, 7), (3, 1)],
+            vec![(0, 2), (3, 3)],
+            vec![(1, 1), (2, 3)],
+            vec![],
+        ];
+
+        let distances = shortest_distances(&adjacency, 0);
+        for (node, distance) in distances.iter().enumerate() {
+            println!("0 -> {node} = {:?}", distance);
+        }
+        // 0 -> 1 = Some(6), through 2 and 3 rather than the direct edge of 7
+    })
+}
+
+/// # Mutating Keys Inside the Heap
+/// The heap only restores its invariant inside `push` and `pop`. If an element’s ordering
+/// changes while it sits in the heap, nothing notices, and the heap silently returns elements
+/// in the wrong order. The standard library calls this a _logic error_: it is not undefined
+/// behavior, but the results are unspecified.
+///
+/// The heap hands out only shared references, so this can only happen through interior
+/// mutability, for example a `Cell` shared through an `Rc`, as below.
+#[derive(Debug)]
+struct Task {
+    name: &'static str,
+    priority: Rc<Cell<u32>>,
+}
+impl PartialEq for Task {
+    fn eq(&self, other: &Self) -> bool {
+        self.priority.get() == other.priority.get()
+    }
+}
+impl Eq for Task {}
+impl Ord for Task {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority.get().cmp(&other.priority.get())
+    }
+}
+impl PartialOrd for Task {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+fn mutating_keys_inside_the_heap() -> Result<()> {
+    Ok({
+        span!("mutating_keys_inside_the_heap");
+        header!("Mutating Keys Inside the Heap");
+
+        let tasks = [("write docs", 1), ("fix bug", 5), ("review", 3)];
+        let handles: Vec<Rc<Cell<u32>>> =
+            tasks.iter().map(|&(_, p)| Rc::new(Cell::new(p))).collect();
+
+        let mut heap = BinaryHeap::new();
+        for (&(name, _), priority) in tasks.iter().zip(&handles) {
+            heap.push(Task {
+                name,
+                priority: Rc::clone(priority),
+            });
+        }
+
+        // Escalate "write docs" from 1 to 10 behind the heap’s back
+        handles[0].set(10);
+
+        print!("pop order after mutating in place:");
+        while let Some(task) = heap.pop() {
+            print!(" {}({})", task.name, task.priority.get());
+        }
+        println!(); // pop order after mutating in place: fix bug(5) write docs(10) review(3)
+
+        // The fix: never change the key of an element while it is in the heap. Take the
+        // elements out, change them, and let the heap re-establish its order.
+        let heap: BinaryHeap<_> = tasks
+            .iter()
+            .map(|&(name, p)| Task {
+                name,
+                priority: Rc::new(Cell::new(p)),
+            })
+            .collect();
+        let tasks = heap.into_vec();
+        if let Some(task) = tasks.iter().find(|task| task.name == "write docs") {
+            task.priority.set(10);
+        }
+        let mut heap = BinaryHeap::from(tasks); // O(n) rebuild
+
+        print!("pop order after rebuilding:");
+        while let Some(task) = heap.pop() {
+            print!(" {}({})", task.name, task.priority.get());
+        }
+        println!(); // pop order after rebuilding: write docs(10) fix bug(5) review(3)
+    })
+    // Dijkstra above side-steps the problem the usual way: instead of decreasing a key in place,
+    // it pushes a new, cheaper entry and skips the stale one when it eventually pops out.
+}
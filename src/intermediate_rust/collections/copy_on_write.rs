@@ -0,0 +1,115 @@
+//! # Copy-on-Write with `Rc::make_mut`
+//! The persistent list shares data by never changing it. Sometimes we do want to change
+//! a shared value in place, but without the other owners noticing. _Copy-on-write_ (COW)
+//! gets us there: all owners share one allocation for as long as nobody writes, and the
+//! first owner that wants to write gets its own private copy at that moment.
+//!
+//! `Rc::make_mut` implements exactly this. Given a `&mut Rc<T>`, it returns a `&mut T`:
+//! - if this `Rc<T>` is the only strong owner, it simply hands out the existing value;
+//! - otherwise it clones the inner value into a new allocation, points _this_ `Rc<T>` at
+//!   the clone, and hands that out, leaving every other owner with the original.
+//!
+//! The deep copy is therefore deferred until, and only if, it is actually needed.
+
+use crate::intermediate_rust::metrics::{self, Counter};
+use std::{io::Result, rc::Rc};
+
+pub fn copy_on_write() -> Result<()> {
+    Ok({
+        sharing_until_the_first_write()?;
+    })
+}
+
+/// An element type whose `Clone` announces itself, so we can see exactly when
+/// `Rc::make_mut` performs the deep copy.
+#[derive(Debug)]
+struct Noisy(String);
+impl Clone for Noisy {
+    fn clone(&self) -> Self {
+        metrics::count(Counter::DeepClone);
+        println!("    (deep copy of `{}`)", self.0);
+        Noisy(self.0.clone())
+    }
+}
+
+/// Cloning a `CowList` is cheap: it is an `Rc::clone` of the underlying `Vec<T>`.
+#[derive(Debug, Clone)]
+struct CowList<T: Clone> {
+    items: Rc<Vec<T>>,
+}
+impl<T: Clone> CowList<T> {
+    pub fn new() -> CowList<T> {
+        CowList {
+            items: Rc::new(vec![]),
+        }
+    }
+
+    /// Mutating needs `&mut self`, but not exclusive ownership of the items:
+    /// `Rc::make_mut` decides whether a deep copy is required first.
+    pub fn push(&mut self, item: T) {
+        if Rc::strong_count(&self.items) > 1 {
+            println!(
+                "  push: items are shared by {} owners, copying before writing",
+                Rc::strong_count(&self.items)
+            );
+        }
+        Rc::make_mut(&mut self.items).push(item);
+    }
+
+    pub fn shares_items_with(&self, other: &CowList<T>) -> bool {
+        Rc::ptr_eq(&self.items, &other.items)
+    }
+
+    pub fn strong_count(&self) -> usize {
+        Rc::strong_count(&self.items)
+    }
+
+    pub fn for_each(&self, f: impl Fn(&T) -> ()) {
+        self.items.iter().for_each(f);
+    }
+}
+fn sharing_until_the_first_write() -> Result<()> {
+    Ok({
+        span!("sharing_until_the_first_write");
+        header!("Sharing Until the First Write");
+
+        let mut a = CowList::new();
+        println!("push to `a` while it is the only owner");
+        a.push(Noisy(String::from("one")));
+        a.push(Noisy(String::from("two")));
+        // no deep copy: `a` owns the items alone
+
+        let mut b = a.clone();
+        println!(
+            "after `b = a.clone()`: shared = {}, count = {}",
+            a.shares_items_with(&b),
+            a.strong_count()
+        ); // after `b = a.clone()`: shared = true, count = 2
+
+        println!("push to `b` while it shares the items with `a`");
+        b.push(Noisy(String::from("three")));
+        //   push: items are shared by 2 owners, copying before writing
+        //     (deep copy of `one`)
+        //     (deep copy of `two`)
+        println!(
+            "after pushing to `b`: shared = {}, count of `a` = {}, count of `b` = {}",
+            a.shares_items_with(&b),
+            a.strong_count(),
+            b.strong_count()
+        ); // after pushing to `b`: shared = false, count of `a` = 1, count of `b` = 1
+
+        println!("push to `b` again, it owns its copy now");
+        b.push(Noisy(String::from("four")));
+        // no deep copy this time
+
+        print!("a:");
+        a.for_each(|n| print!(" {}", n.0));
+        println!(); // a: one two
+        print!("b:");
+        b.for_each(|n| print!(" {}", n.0));
+        println!(); // b: one two three four
+    })
+    // `a` never saw `three` or `four`: from its point of view the list is just as immutable
+    // as the persistent list, yet `b` could be written with ordinary `&mut` methods and only
+    // paid for a copy once. `Rc::make_mut` requires `T: Clone` for precisely this reason.
+}
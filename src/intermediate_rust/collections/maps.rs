@@ -0,0 +1,228 @@
+//! # Storing Keys with Associated Values in Maps
+//! The type `HashMap<K, V>` stores a mapping of keys of type `K` to values of type `V`
+//! using a _hashing function_, which determines how it places these keys and values into
+//! memory. `BTreeMap<K, V>` stores the same kind of mapping in a balanced tree ordered by
+//! the keys instead. Both own their keys and values: inserting a `String` moves it into the
+//! map, and the map drops it when the entry is removed or the map itself goes out of scope.
+//!
+//! Which one to choose?
+//! - `HashMap<K, V>` needs `K: Eq + Hash`, has O(1) average lookups, and iterates in an
+//!   arbitrary order that can change from one run to the next.
+//! - `BTreeMap<K, V>` needs `K: Ord`, has O(log n) lookups, and always iterates in key order,
+//!   which also makes range queries possible.
+
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, HashMap},
+    hash::{Hash, Hasher},
+    io::Result,
+};
+
+pub fn maps() -> Result<()> {
+    Ok({
+        inserting_and_reading_values()?;
+        updating_values_with_the_entry_api()?;
+        ordered_iteration()?;
+        custom_key_types()?;
+        borrowed_lookups()?;
+    })
+}
+
+fn inserting_and_reading_values() -> Result<()> {
+    Ok({
+        span!("inserting_and_reading_values");
+        header!("Inserting and Reading Values");
+
+        let mut scores = HashMap::new();
+        scores.insert(String::from("Blue"), 10);
+        scores.insert(String::from("Yellow"), 50);
+
+        // `insert` overwrites and hands back the value it replaced
+        let old = scores.insert(String::from("Blue"), 25);
+        println!("replaced Blue score = {:?}", old); // replaced Blue score = Some(10)
+
+        // `get` returns an `Option<&V>` because the key might not be there
+        let blue = scores.get("Blue").copied().unwrap_or(0);
+        println!("Blue = {blue}"); // Blue = 25
+        println!("Red = {:?}", scores.get("Red")); // Red = None
+
+        // For owned values like `String`, the values are moved and the map becomes their owner
+        let field_name = String::from("Favorite color");
+        let field_value = String::from("Blue");
+        let mut map = HashMap::new();
+        map.insert(field_name, field_value);
+        // borrow of moved value: `field_name`
+        // println!("{field_name}"); // value borrowed here after move
+        println!("map = {:?}", map);
+    })
+}
+
+/// # Updating a Value Based on the Old Value with the Entry API
+/// The naïve way to update a map is a `get` followed by an `insert`, which hashes the key
+/// twice and fights the borrow checker when the value must be modified in place. `entry`
+/// looks the key up once and returns an `Entry` enum that is either `Occupied` or `Vacant`,
+/// with methods to handle both cases in one expression.
+fn updating_values_with_the_entry_api() -> Result<()> {
+    Ok({
+        span!("updating_values_with_the_entry_api");
+        header!("Updating Values with the Entry API");
+
+        let text = "hello world wonderful world";
+
+        // `or_insert` inserts a default and returns a `&mut V` either way
+        let mut word_counts: HashMap<&str, usize> = HashMap::new();
+        for word in text.split_whitespace() {
+            *word_counts.entry(word).or_insert(0) += 1;
+        }
+        println!("world = {:?}", word_counts.get("world")); // world = Some(2)
+
+        // `or_insert_with` only runs the closure when the key is vacant,
+        // so an expensive default is never built needlessly
+        let mut by_first_letter: HashMap<char, Vec<&str>> = HashMap::new();
+        for word in text.split_whitespace() {
+            let first = word.chars().next().unwrap_or_default();
+            by_first_letter
+                .entry(first)
+                .or_insert_with(|| {
+                    println!("  creating the group for {first:?}");
+                    Vec::new()
+                })
+                .push(word);
+        }
+        println!("w = {:?}", by_first_letter.get(&'w')); // w = Some(["world", "wonderful", "world"])
+
+        // `and_modify` runs only on an occupied entry, and chains with an `or_insert` for the vacant case
+        let mut stock: HashMap<&str, u32> = HashMap::new();
+        for fruit in ["apple", "pear", "apple"] {
+            stock
+                .entry(fruit)
+                .and_modify(|count| *count += 10)
+                .or_insert(1);
+        }
+        println!("apple = {:?}, pear = {:?}", stock["apple"], stock["pear"]); // apple = 11, pear = 1
+    })
+}
+
+fn ordered_iteration() -> Result<()> {
+    Ok({
+        span!("ordered_iteration");
+        header!("Ordered Iteration");
+
+        let planets = [("Mercury", 1), ("Venus", 2), ("Earth", 3), ("Mars", 4)];
+
+        let hash_map: HashMap<_, _> = planets.into_iter().collect();
+        let b_tree_map: BTreeMap<_, _> = planets.into_iter().collect();
+
+        // The order here depends on the random hashing seed and may differ between runs
+        print!("HashMap:");
+        hash_map.iter().for_each(|(k, v)| print!(" {k}={v}"));
+        println!();
+
+        // The order here is always alphabetical, because `&str` is `Ord`
+        print!("BTreeMap:");
+        b_tree_map.iter().for_each(|(k, v)| print!(" {k}={v}"));
+        println!(); // BTreeMap: Earth=3 Mars=4 Mercury=1 Venus=2
+
+        // Ordering also enables range queries, which a `HashMap` simply cannot answer
+        print!("BTreeMap from \"M\" to \"N\":");
+        b_tree_map
+            .range("M".."N")
+            .for_each(|(k, v)| print!(" {k}={v}"));
+        println!(); // BTreeMap from "M" to "N": Mars=4 Mercury=1
+    })
+}
+
+/// # Custom Key Types
+/// Any type can be a key as long as it implements the traits the map needs. Deriving them is
+/// usually right, but sometimes two values should count as the _same_ key even though some
+/// fields differ. The rule to keep: `Hash` and `Eq` (and `Ord` and `Eq`) must agree — if
+/// `a == b` then `hash(a) == hash(b)` and `a.cmp(b) == Equal` — or lookups will silently miss.
+#[derive(Debug, Clone)]
+struct Username {
+    name: String, // compared case-insensitively
+}
+impl PartialEq for Username {
+    fn eq(&self, other: &Self) -> bool {
+        self.name.eq_ignore_ascii_case(&other.name)
+    }
+}
+impl Eq for Username {}
+impl Hash for Username {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        // hash exactly what `eq` compares, so equal keys land in the same bucket
+        for byte in self.name.bytes() {
+            state.write_u8(byte.to_ascii_lowercase());
+        }
+    }
+}
+impl PartialOrd for Username {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Username {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let lhs = self.name.bytes().map(|b| b.to_ascii_lowercase());
+        let rhs = other.name.bytes().map(|b| b.to_ascii_lowercase());
+        lhs.cmp(rhs)
+    }
+}
+fn custom_key_types() -> Result<()> {
+    Ok({
+        span!("custom_key_types");
+        header!("Custom Key Types");
+
+        let user = |name: &str| Username {
+            name: String::from(name),
+        };
+
+        let mut logins = HashMap::new();
+        logins.insert(user("Ferris"), 1);
+        *logins.entry(user("FERRIS")).or_insert(0) += 1;
+        println!("HashMap entries = {}", logins.len()); // HashMap entries = 1
+        println!("ferris = {:?}", logins.get(&user("ferris"))); // ferris = Some(2)
+
+        let mut ranks = BTreeMap::new();
+        ranks.insert(user("bob"), "member");
+        ranks.insert(user("Alice"), "admin");
+        ranks.insert(user("ALICE"), "owner"); // same key as "Alice": the value is replaced
+        print!("BTreeMap:");
+        ranks.iter().for_each(|(k, v)| print!(" {}={v}", k.name));
+        println!(); // BTreeMap: Alice=owner bob=member
+    })
+}
+
+/// # Borrowed Lookups
+/// A `HashMap<String, V>` owns its `String` keys, yet we can look values up with a plain
+/// `&str` without allocating a `String` first. That works because `get` is generic:
+/// ```text
+/// pub fn get<Q: ?Sized>(&self, k: &Q) -> Option<&V>
+/// where
+///     K: Borrow<Q>,
+///     Q: Hash + Eq,
+/// ```
+/// `String` implements `Borrow<str>`, and the `Borrow` contract promises that a `String` and
+/// the `str` it borrows as hash and compare identically — the same rule our `Username` had
+/// to uphold by hand. It is a close cousin of the `Deref<Target = str>` coercion from
+/// `traits/deref.rs`, but stated as a trait bound the map can rely on.
+fn borrowed_lookups() -> Result<()> {
+    Ok({
+        span!("borrowed_lookups");
+        header!("Borrowed Lookups");
+
+        let mut capitals: HashMap<String, String> = HashMap::new();
+        capitals.insert(String::from("France"), String::from("Paris"));
+        capitals.insert(String::from("Japan"), String::from("Tokyo"));
+
+        let country: &str = "Japan";
+        // `Q` is inferred as `str` here; spelled out, it is `capitals.get::<str>(country)`
+        println!("capital of {country} = {:?}", capitals.get::<str>(country)); // capital of Japan = Some("Tokyo")
+        println!("contains Peru = {}", capitals.contains_key("Peru")); // contains Peru = false
+
+        // The same holds for `BTreeMap`, and for removing entries
+        let mut sorted: BTreeMap<String, usize> =
+            capitals.keys().map(|k| (k.clone(), k.len())).collect();
+        println!("removed = {:?}", sorted.remove("France")); // removed = Some(6)
+        println!("left = {:?}", sorted); // left = {"Japan": 5}
+    })
+}
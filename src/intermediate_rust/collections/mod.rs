@@ -0,0 +1,18 @@
+//! # Collections
+//! Rust’s standard library includes a number of very useful data structures called
+//! _collections_. Most other data types represent one specific value, but collections
+//! can contain multiple values. Unlike the built-in array and tuple types, the data these
+//! collections point to is stored on the heap, which means the amount of data does not
+//! need to be known at compile time and can grow or shrink as the program runs.
+//!
+//! Each kind of collection has different capabilities and costs, and choosing an
+//! appropriate one for your current situation is a skill you’ll develop over time.
+//! This chapter also builds a few collections of our own on top of the smart pointers
+//! from the previous chapter, to show what they make possible.
+
+pub(super) mod binary_heap;
+pub(super) mod copy_on_write;
+pub(super) mod maps;
+pub(super) mod persistent;
+pub(super) mod vec_deque;
+pub(super) mod vec_internals;
@@ -0,0 +1,313 @@
+//! # Persistent Data Structures with `Rc<T>`
+//! A _persistent_ data structure always preserves the previous version of itself when it
+//! is “modified”: every update returns a new version, and every old version stays valid
+//! and unchanged. Functional languages use them everywhere because they make sharing
+//! data between parts of a program trivially safe: nobody can change a value out from
+//! under you if nobody can change it at all.
+//!
+//! Copying the whole structure on every update would make this idea far too expensive.
+//! The trick is _structural sharing_: the new version only allocates the parts that
+//! actually differ and points at the rest of the old version. Because several versions
+//! now own the same nodes, and we can’t know at compile time which version will be
+//! dropped last, this is exactly the multiple-ownership problem `Rc<T>` solves.
+
+use std::{cmp::Ordering, io::Result, rc::Rc};
+
+pub fn persistent() -> Result<()> {
+    Ok({
+        sharing_the_tail_of_a_persistent_list()?;
+        path_copying_in_a_persistent_map()?;
+    })
+}
+
+/// # Sharing the Tail of a Persistent List
+/// Compare this list with the `Rc`-based cons list from `rc_t.rs`: there, we shared a tail by
+/// hand with `Rc::clone(&a)`. Here, `push_front` does the same thing for us, so every call
+/// returns a brand-new list whose tail is the (untouched) list we called it on.
+#[derive(Debug)]
+struct Node<T> {
+    value: T,
+    next: Option<Rc<Node<T>>>,
+}
+#[derive(Debug)]
+struct PersistentList<T> {
+    head: Option<Rc<Node<T>>>,
+}
+impl<T> PersistentList<T> {
+    pub fn new() -> PersistentList<T> {
+        PersistentList { head: None }
+    }
+
+    /// `self` is only borrowed: the old version is still there after the call.
+    pub fn push_front(&self, value: T) -> PersistentList<T> {
+        PersistentList {
+            head: Some(Rc::new(Node {
+                value,
+                next: self.head.as_ref().map(Rc::clone),
+            })),
+        }
+    }
+
+    /// The tail is not copied either: it is the very same nodes, with one more owner.
+    pub fn tail(&self) -> PersistentList<T> {
+        PersistentList {
+            head: self
+                .head
+                .as_ref()
+                .and_then(|node| node.next.as_ref().map(Rc::clone)),
+        }
+    }
+
+    pub fn head(&self) -> Option<&T> {
+        self.head.as_ref().map(|node| &node.value)
+    }
+
+    /// How many versions (or other nodes) currently point at this version’s first node.
+    pub fn strong_count(&self) -> usize {
+        self.head.as_ref().map_or(0, Rc::strong_count)
+    }
+
+    pub fn for_each(&self, f: impl Fn(&T) -> ()) {
+        let mut next = &self.head;
+        while let Some(node) = next {
+            f(&node.value);
+            next = &node.next;
+        }
+    }
+}
+fn sharing_the_tail_of_a_persistent_list() -> Result<()> {
+    Ok({
+        span!("sharing_the_tail_of_a_persistent_list");
+        header!("Sharing the Tail of a Persistent List");
+
+        let a = PersistentList::new()
+            .push_front(3)
+            .push_front(2)
+            .push_front(1);
+        println!("count after creating `a` = {}", a.strong_count());
+        // count after creating `a` = 1
+
+        let b = a.push_front(0);
+        println!("count after creating `b` = {}", a.strong_count());
+        // count after creating `b` = 2
+
+        {
+            // `c` starts from `a`’s tail, so it shares the `2 -> 3` nodes but not the `1`
+            let c = a.tail().push_front(42);
+            println!("count after creating `c` = {}", a.strong_count());
+            // count after creating `c` = 2
+            println!(
+                "count of `a`’s tail after creating `c` = {}",
+                a.tail().strong_count() - 1 // minus the temporary `a.tail()` itself
+            );
+            // count of `a`’s tail after creating `c` = 2
+
+            print!("a:");
+            a.for_each(|i| print!(" {i}"));
+            println!();
+            print!("b:");
+            b.for_each(|i| print!(" {i}"));
+            println!();
+            print!("c:");
+            c.for_each(|i| print!(" {i}"));
+            println!();
+        }
+        println!(
+            "count of `a`’s tail after `c` goes out of scope = {}",
+            a.tail().strong_count() - 1
+        ); // count of `a`’s tail after `c` goes out of scope = 1
+
+        drop(b);
+        println!("count after `b` is dropped = {}", a.strong_count());
+        // count after `b` is dropped = 1
+
+        // Every version we made is still intact: `a` was never changed by `push_front`
+        println!("a head = {:?}", a.head());
+    })
+    // Creating three versions of the list allocated only five nodes in total instead of
+    // the ten a naive copy-on-every-update approach would have needed. The strong counts
+    // are the proof: the shared nodes have one owner per version that reaches them.
+}
+
+/// # Path Copying in a Persistent Map
+/// Lists are easy because an update only ever touches the front. For a tree-shaped structure,
+/// like this (unbalanced) binary search tree used as a map, an insert has to change a node
+/// somewhere deep inside. We can’t mutate that node, because older versions still point at it,
+/// so instead we copy every node on the _path_ from the root down to the change and let the
+/// copies point at the untouched subtrees of the old version.
+///
+/// Inserting into a tree of `n` balanced nodes therefore allocates only about `log n` new
+/// nodes, while everything off the path is shared.
+#[derive(Debug)]
+struct MapNode<K, V> {
+    key: K,
+    value: V,
+    left: Option<Rc<MapNode<K, V>>>,
+    right: Option<Rc<MapNode<K, V>>>,
+}
+#[derive(Debug)]
+struct PersistentMap<K, V> {
+    root: Option<Rc<MapNode<K, V>>>,
+}
+impl<K: Ord + Clone, V: Clone> PersistentMap<K, V> {
+    pub fn new() -> PersistentMap<K, V> {
+        PersistentMap { root: None }
+    }
+
+    pub fn insert(&self, key: K, value: V) -> PersistentMap<K, V> {
+        PersistentMap {
+            root: Some(Self::insert_into(&self.root, key, value)),
+        }
+    }
+
+    fn insert_into(node: &Option<Rc<MapNode<K, V>>>, key: K, value: V) -> Rc<MapNode<K, V>> {
+        match node {
+            None => Rc::new(MapNode {
+                key,
+                value,
+                left: None,
+                right: None,
+            }),
+            // Only the node on the path is copied, its other child is shared
+            Some(node) => match key.cmp(&node.key) {
+                Ordering::Less => Rc::new(MapNode {
+                    key: node.key.clone(),
+                    value: node.value.clone(),
+                    left: Some(Self::insert_into(&node.left, key, value)),
+                    right: node.right.as_ref().map(Rc::clone),
+                }),
+                Ordering::Greater => Rc::new(MapNode {
+                    key: node.key.clone(),
+                    value: node.value.clone(),
+                    left: node.left.as_ref().map(Rc::clone),
+                    right: Some(Self::insert_into(&node.right, key, value)),
+                }),
+                Ordering::Equal => Rc::new(MapNode {
+                    key,
+                    value,
+                    left: node.left.as_ref().map(Rc::clone),
+                    right: node.right.as_ref().map(Rc::clone),
+                }),
+            },
+        }
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        let mut next = &self.root;
+        while let Some(node) = next {
+            next = match key.cmp(&node.key) {
+                Ordering::Less => &node.left,
+                Ordering::Greater => &node.right,
+                Ordering::Equal => return Some(&node.value),
+            };
+        }
+        None
+    }
+
+    /// Visits the entries in key order.
+    pub fn for_each(&self, f: &impl Fn(&K, &V) -> ()) {
+        fn visit<K, V>(node: &Option<Rc<MapNode<K, V>>>, f: &impl Fn(&K, &V) -> ()) {
+            if let Some(node) = node {
+                visit(&node.left, f);
+                f(&node.key, &node.value);
+                visit(&node.right, f);
+            }
+        }
+        visit(&self.root, f);
+    }
+}
+fn path_copying_in_a_persistent_map() -> Result<()> {
+    Ok({
+        span!("path_copying_in_a_persistent_map");
+        header!("Path Copying in a Persistent Map");
+
+        //       5
+        //     /   \
+        //    3     8
+        //   /
+        //  1
+        let v1 = PersistentMap::new()
+            .insert(5, "five")
+            .insert(3, "three")
+            .insert(8, "eight")
+            .insert(1, "one");
+
+        // 9 goes to the right of 8, so only 5 and 8 are copied: the `3 -> 1` subtree is shared
+        let v2 = v1.insert(9, "nine");
+
+        if let (Some(root1), Some(root2)) = (&v1.root, &v2.root) {
+            println!(
+                "roots are the same allocation = {}",
+                Rc::ptr_eq(root1, root2)
+            ); // roots are the same allocation = false
+
+            if let (Some(left1), Some(left2)) = (&root1.left, &root2.left) {
+                println!(
+                    "left subtrees are the same allocation = {}",
+                    Rc::ptr_eq(left1, left2)
+                ); // left subtrees are the same allocation = true
+                println!("left subtree strong count = {}", Rc::strong_count(left1));
+                // left subtree strong count = 2
+            }
+        }
+
+        print!("v1:");
+        v1.for_each(&|k, v| print!(" {k}={v}"));
+        println!();
+        print!("v2:");
+        v2.for_each(&|k, v| print!(" {k}={v}"));
+        println!();
+
+        println!("v1 get 9 = {:?}", v1.get(&9)); // v1 get 9 = None
+        println!("v2 get 9 = {:?}", v2.get(&9)); // v2 get 9 = Some("nine")
+
+        // Overwriting a key is just another insert, so `v2` still sees the old value
+        let v3 = v2.insert(3, "THREE");
+        println!("v2 get 3 = {:?}", v2.get(&3)); // v2 get 3 = Some("three")
+        println!("v3 get 3 = {:?}", v3.get(&3)); // v3 get 3 = Some("THREE")
+    })
+}
+// Persistence is what makes “undo”, snapshots and cheap concurrent readers easy: keeping an
+// old version around is as cheap as keeping an `Rc` to its root. Note that these versions
+// can only be shared within one thread; swap `Rc<T>` for `Arc<T>` to share them across threads.
+
+/// Every version of a `PersistentMap` should hold what a `BTreeMap` given the same inserts holds,
+/// in the same order, however much of it is shared with the versions before and after it.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use std::{cell::RefCell, collections::BTreeMap};
+
+    fn entries(map: &PersistentMap<u8, u32>) -> Vec<(u8, u32)> {
+        let entries = RefCell::new(vec![]);
+        map.for_each(&|k, v| entries.borrow_mut().push((*k, *v)));
+        entries.into_inner()
+    }
+
+    proptest! {
+        #![proptest_config(crate::intermediate_rust::utils::miri::proptest_config())]
+
+        #[test]
+        fn every_version_matches_a_btree_map(
+            inserts in prop::collection::vec(any::<(u8, u32)>(), 0..64)
+        ) {
+            let mut versions = vec![(PersistentMap::new(), BTreeMap::new())];
+            for (key, value) in inserts {
+                let (map, model) = versions.last().unwrap();
+                let mut model = model.clone();
+                model.insert(key, value);
+                versions.push((map.insert(key, value), model));
+            }
+            // checked only once all of them are made, so that no insert went back and changed
+            // an older version
+            for (map, model) in &versions {
+                let expected: Vec<_> = model.iter().map(|(k, v)| (*k, *v)).collect();
+                prop_assert_eq!(entries(map), expected);
+                for key in 0..=u8::MAX {
+                    prop_assert_eq!(map.get(&key), model.get(&key));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,257 @@
+//! # Double-Ended Queues with `VecDeque<T>`
+//! A `Vec<T>` is great at adding and removing at its end, but removing from its front has to
+//! shift every remaining element one slot to the left, which is O(n). `VecDeque<T>` is a
+//! growable _ring buffer_: it keeps track of where its first element lives inside its
+//! allocation and lets the elements wrap around the end of that allocation, so pushing and
+//! popping at _both_ ends is O(1).
+//!
+//! The price is that the elements are not always contiguous in memory. `as_slices` returns
+//! the (up to) two pieces, and `make_contiguous` rotates them into one slice when needed.
+
+use std::{collections::VecDeque, io::Result};
+
+pub fn vec_deque() -> Result<()> {
+    Ok({
+        using_vec_deque_as_a_queue()?;
+        using_vec_deque_as_a_sliding_window()?;
+        a_fixed_capacity_ring_buffer()?;
+    })
+}
+
+fn using_vec_deque_as_a_queue() -> Result<()> {
+    Ok({
+        span!("using_vec_deque_as_a_queue");
+        header!("Using VecDeque as a Queue");
+
+        let mut queue = VecDeque::new();
+        queue.push_back("first");
+        queue.push_back("second");
+        queue.push_back("third");
+
+        // first in, first out
+        while let Some(job) = queue.pop_front() {
+            println!("processing {job}, {} left", queue.len());
+        }
+
+        // both ends are cheap, so the same type is also a stack or a deque
+        queue.push_back("back");
+        queue.push_front("front");
+        println!("front = {:?}, back = {:?}", queue.front(), queue.back()); // front = Some("front"), back = Some("back")
+
+        // after some pushes and pops the contents may wrap around the allocation
+        let mut wrapped: VecDeque<i32> = VecDeque::with_capacity(4);
+        wrapped.extend([1, 2, 3, 4]);
+        wrapped.pop_front();
+        wrapped.pop_front();
+        wrapped.push_back(5);
+        println!("as_slices = {:?}", wrapped.as_slices()); // as_slices = ([3, 4], [5])
+        println!("make_contiguous = {:?}", wrapped.make_contiguous()); // make_contiguous = [3, 4, 5]
+    })
+}
+
+/// # Using `VecDeque` as a Sliding Window
+/// A moving average only ever needs the last `n` values: new readings enter at the back and
+/// the oldest ones leave from the front, which is exactly the access pattern a deque is for.
+fn using_vec_deque_as_a_sliding_window() -> Result<()> {
+    Ok({
+        span!("using_vec_deque_as_a_sliding_window");
+        header!("Using VecDeque as a Sliding Window");
+
+        const WINDOW: usize = 3;
+        let readings = [10, 20, 30, 40, 50];
+
+        let mut window = VecDeque::with_capacity(WINDOW);
+        let mut sum = 0;
+        for reading in readings {
+            window.push_back(reading);
+            sum += reading;
+            if window.len() > WINDOW {
+                sum -= window.pop_front().unwrap_or_default();
+            }
+            println!(
+                "window = {:?}, average = {}",
+                window,
+                sum as f64 / window.len() as f64
+            );
+        }
+        // window = [30, 40, 50], average = 40
+    })
+}
+
+/// # A Fixed-Capacity Ring Buffer
+/// To see what `VecDeque` does for us, here is a much simpler ring buffer over a plain array.
+/// It never grows: `N` is part of the type, so the whole buffer can live on the stack.
+///
+/// The buffer remembers the index of its first element (`head`) and how many elements it holds
+/// (`len`). The element at logical position `i` is stored at physical index `(head + i) % N`,
+/// so once the back reaches the end of the array it wraps around to index `0`.
+///
+/// The slots are `Option<T>` so that an empty slot doesn’t need a placeholder `T` value, and
+/// so that `pop_front` can move the value out with `Option::take` instead of copying it.
+#[derive(Debug)]
+struct RingBuffer<T, const N: usize> {
+    slots: [Option<T>; N],
+    head: usize,
+    len: usize,
+}
+impl<T, const N: usize> RingBuffer<T, N> {
+    pub fn new() -> RingBuffer<T, N> {
+        RingBuffer {
+            slots: std::array::from_fn(|_| None),
+            head: 0,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    fn physical_index(&self, logical_index: usize) -> usize {
+        (self.head + logical_index) % N
+    }
+
+    /// Hands the value back if there is no room for it.
+    pub fn push_back(&mut self, value: T) -> std::result::Result<(), T> {
+        if self.is_full() {
+            return Err(value);
+        }
+        let tail = self.physical_index(self.len);
+        self.slots[tail] = Some(value);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Always accepts the value, evicting and returning the oldest one when full.
+    pub fn push_back_overwrite(&mut self, value: T) -> Option<T> {
+        let evicted = if self.is_full() {
+            self.pop_front()
+        } else {
+            None
+        };
+        // a full buffer has room again after the eviction
+        let _ = self.push_back(value);
+        evicted
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        let value = self.slots[self.head].take();
+        self.head = self.physical_index(1);
+        self.len -= 1;
+        value
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        (0..self.len).filter_map(|i| self.slots[self.physical_index(i)].as_ref())
+    }
+}
+fn a_fixed_capacity_ring_buffer() -> Result<()> {
+    Ok({
+        span!("a_fixed_capacity_ring_buffer");
+        header!("A Fixed-Capacity Ring Buffer");
+
+        let mut buffer: RingBuffer<i32, 3> = RingBuffer::new();
+        for i in 1..=4 {
+            match buffer.push_back(i) {
+                Ok(()) => println!("pushed {i}"),
+                Err(rejected) => println!("buffer is full, {rejected} was handed back"),
+            }
+        }
+
+        println!("popped {:?}, len = {}", buffer.pop_front(), buffer.len()); // popped Some(1), len = 2
+        buffer.push_back(4).ok();
+        // `4` was stored at physical index 0: the buffer has wrapped around
+        println!("head = {}, slots = {:?}", buffer.head, buffer.slots); // head = 1, slots = [Some(4), Some(2), Some(3)]
+        println!("in order = {:?}", buffer.iter().collect::<Vec<_>>()); // in order = [2, 3, 4]
+
+        println!("evicted {:?}", buffer.push_back_overwrite(5)); // evicted Some(2)
+        println!("in order = {:?}", buffer.iter().collect::<Vec<_>>()); // in order = [3, 4, 5]
+    })
+    // `VecDeque<T>` is this same idea plus growth: when it is full it reallocates a bigger
+    // buffer and moves the wrapped-around part so the elements stay in logical order.
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_rejects_a_push_when_full() {
+        let mut buffer: RingBuffer<i32, 2> = RingBuffer::new();
+
+        assert_eq!(buffer.push_back(1), Ok(()));
+        assert_eq!(buffer.push_back(2), Ok(()));
+        assert_eq!(buffer.push_back(3), Err(3));
+        assert!(buffer.is_full());
+    }
+
+    #[test]
+    fn it_wraps_around_the_end_of_the_array() {
+        let mut buffer: RingBuffer<i32, 3> = RingBuffer::new();
+        buffer.push_back(1).unwrap();
+        buffer.push_back(2).unwrap();
+        buffer.push_back(3).unwrap();
+
+        assert_eq!(buffer.pop_front(), Some(1));
+        assert_eq!(buffer.pop_front(), Some(2));
+        buffer.push_back(4).unwrap();
+        buffer.push_back(5).unwrap();
+
+        // 4 and 5 went to the physical slots 0 and 1, in front of 3
+        assert_eq!(buffer.head, 2);
+        assert_eq!(buffer.slots, [Some(4), Some(5), Some(3)]);
+        assert_eq!(buffer.iter().copied().collect::<Vec<_>>(), vec![3, 4, 5]);
+    }
+
+    #[test]
+    fn it_keeps_fifo_order_over_many_wrap_arounds() {
+        let mut buffer: RingBuffer<usize, 4> = RingBuffer::new();
+        let mut model = VecDeque::new();
+
+        for i in 0..100 {
+            buffer.push_back(i).unwrap();
+            model.push_back(i);
+            if i % 3 != 0 {
+                assert_eq!(buffer.pop_front(), model.pop_front());
+            }
+            if buffer.is_full() {
+                assert_eq!(buffer.pop_front(), model.pop_front());
+            }
+            assert_eq!(buffer.len(), model.len());
+            assert!(buffer.iter().eq(model.iter()));
+        }
+    }
+
+    #[test]
+    fn it_evicts_the_oldest_value_when_overwriting() {
+        let mut buffer: RingBuffer<char, 2> = RingBuffer::new();
+
+        assert_eq!(buffer.push_back_overwrite('a'), None);
+        assert_eq!(buffer.push_back_overwrite('b'), None);
+        assert_eq!(buffer.push_back_overwrite('c'), Some('a'));
+        assert_eq!(buffer.push_back_overwrite('d'), Some('b'));
+        assert_eq!(buffer.iter().collect::<String>(), "cd");
+    }
+
+    #[test]
+    fn it_returns_none_when_popping_an_empty_buffer() {
+        let mut buffer: RingBuffer<i32, 1> = RingBuffer::new();
+
+        assert_eq!(buffer.pop_front(), None);
+        buffer.push_back(1).unwrap();
+        assert_eq!(buffer.pop_front(), Some(1));
+        assert_eq!(buffer.pop_front(), None);
+        assert!(buffer.is_empty());
+    }
+}
@@ -0,0 +1,156 @@
+//! # What a `Vec<T>` Really Is
+//! On the stack, a `Vec<T>` is just three words: a pointer to a heap buffer, the buffer’s
+//! _capacity_ (how many elements fit), and its _length_ (how many elements are actually
+//! initialized). Pushing while `len < capacity` simply writes into the next free slot.
+//! Pushing while `len == capacity` has to _reallocate_: allocate a bigger buffer, move every
+//! element over, and free the old buffer.
+//!
+//! To keep that affordable, the capacity grows geometrically (it roughly doubles), so over many
+//! pushes every element is moved only a constant number of times on average. This is what
+//! “`push` is _amortized_ O(1)” means: the occasional expensive push is paid for by all the
+//! cheap ones before it.
+
+use std::io::Result;
+
+pub fn vec_internals() -> Result<()> {
+    Ok({
+        watching_a_vec_grow()?;
+        reserving_capacity_up_front()?;
+        giving_capacity_back()?;
+        holding_a_reference_across_a_push()?;
+    })
+}
+
+fn watching_a_vec_grow() -> Result<()> {
+    Ok({
+        span!("watching_a_vec_grow");
+        header!("Watching a Vec Grow");
+
+        println!(
+            "size of a Vec<u64> on the stack = {} bytes",
+            std::mem::size_of::<Vec<u64>>()
+        ); // size of a Vec<u64> on the stack = 24 bytes (on a 64-bit target)
+
+        let mut v: Vec<u64> = Vec::new();
+        // An empty `Vec` hasn’t allocated at all: its pointer is a dangling, well-aligned placeholder
+        println!(
+            "len = {:2}, capacity = {:2}, ptr = {:p}",
+            v.len(),
+            v.capacity(),
+            v.as_ptr()
+        );
+
+        let mut reallocations = 0;
+        for i in 0..33 {
+            let (capacity, ptr) = (v.capacity(), v.as_ptr());
+            v.push(i);
+            if v.capacity() != capacity {
+                reallocations += 1;
+                // the allocator may occasionally grow the buffer in place, so `moved` can be false
+                println!(
+                    "len = {:2}, capacity = {:2}, ptr = {:p} <- reallocated, moved = {}",
+                    v.len(),
+                    v.capacity(),
+                    v.as_ptr(),
+                    v.as_ptr() != ptr
+                );
+            }
+        }
+        println!("33 pushes, {reallocations} reallocations");
+        // The capacities go 4, 8, 16, 32, 64: each reallocation doubles the room
+    })
+}
+
+/// # Reserving Capacity Up Front
+/// When you know roughly how many elements are coming, `Vec::with_capacity` (or `reserve` on an
+/// existing vector) allocates once, and none of the following pushes has to move anything.
+fn reserving_capacity_up_front() -> Result<()> {
+    Ok({
+        span!("reserving_capacity_up_front");
+        header!("Reserving Capacity Up Front");
+
+        let mut v: Vec<u64> = Vec::with_capacity(33);
+        let start = v.as_ptr();
+        for i in 0..33 {
+            v.push(i);
+        }
+        println!(
+            "len = {}, capacity = {}, same buffer = {}",
+            v.len(),
+            v.capacity(),
+            v.as_ptr() == start
+        ); // len = 33, capacity = 33, same buffer = true
+
+        // `reserve` only grows when the spare room is too small
+        v.reserve(10);
+        println!(
+            "after reserve(10): capacity >= 43 is {}",
+            v.capacity() >= 43
+        );
+    })
+}
+
+/// # Giving Capacity Back
+/// Removing elements never shrinks the buffer on its own, since the vector might grow again.
+/// `shrink_to_fit` reallocates down to (about) `len` when we know it won’t.
+fn giving_capacity_back() -> Result<()> {
+    Ok({
+        span!("giving_capacity_back");
+        header!("Giving Capacity Back");
+
+        let mut v: Vec<u64> = (0..1000).collect();
+        v.truncate(10);
+        println!(
+            "after truncate: len = {}, capacity = {}",
+            v.len(),
+            v.capacity()
+        );
+        // after truncate: len = 10, capacity = 1000
+
+        v.shrink_to_fit();
+        println!(
+            "after shrink_to_fit: len = {}, capacity = {}",
+            v.len(),
+            v.capacity()
+        );
+        // after shrink_to_fit: len = 10, capacity = 10
+
+        v.clear();
+        println!(
+            "after clear: len = {}, capacity = {}",
+            v.len(),
+            v.capacity()
+        );
+        // after clear: len = 0, capacity = 10
+    })
+}
+
+/// # Holding a Reference Across a Push
+/// Now the borrow checker’s most famous complaint about vectors makes sense:
+/// ```compile_fail
+/// let mut v = vec![1, 2, 3];
+/// let first = &v[0];
+/// v.push(4);
+/// println!("The first element is: {first}");
+/// ```
+/// cannot borrow `v` as mutable because it is also borrowed as immutable
+///
+/// `first` points _into the heap buffer_. If `push` has to reallocate, that buffer is freed and
+/// `first` would dangle. The compiler can’t know whether this particular push reallocates, so
+/// it rejects every mutable borrow while a shared one is alive. The fixes are to stop using the
+/// reference before pushing, to copy the element out, or to remember an index instead.
+fn holding_a_reference_across_a_push() -> Result<()> {
+    Ok({
+        span!("holding_a_reference_across_a_push");
+        header!("Holding a Reference Across a Push");
+
+        let mut v = vec![1, 2, 3];
+
+        let first = v[0]; // copy the value out instead of borrowing it
+        let first_index = 0; // or remember where it is
+        v.push(4);
+
+        println!("The first element is: {first}");
+        println!("The first element is still: {}", v[first_index]);
+    })
+}
@@ -0,0 +1,399 @@
+//! # Configuration and the Environment
+//! A program is configured from outside, and from several places at once: its own defaults, a
+//! file, environment variables and the command line. Each is more specific than the one before,
+//! to a machine, a shell, a single run, so each overrides what the one before said, and says
+//! nothing of what it leaves out. The result is the program’s settings, with a type for each.
+//!
+//! The runner is configured this way, with this module’s `Settings`, and the `config` lesson
+//! runs the same functions on made-up layers, to show which wins. The file is
+//! `.intermediate_rust.conf` in the current directory, or wherever `INTERMEDIATE_RUST_CONFIG`
+//! says, with a `key = value` on each line:
+//! ```text
+//! # where the lessons print to, instead of the terminal
+//! output = lessons.txt
+//! color = never
+//! verbosity = verbose
+//! ```
+//! Then come `INTERMEDIATE_RUST_OUTPUT`, `INTERMEDIATE_RUST_COLOR`, `INTERMEDIATE_RUST_VERBOSITY`
+//! and `NO_COLOR`, and last `--output`, `--color`, and `--quiet` or `-v`.
+
+use super::output::{Output, Verbosity};
+use std::{
+    collections::HashMap,
+    env,
+    ffi::OsString,
+    fmt::{self, Display},
+    fs::{self, File},
+    io::{self, Error, ErrorKind, IsTerminal, LineWriter, Result, Write},
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+pub fn config() -> Result<()> {
+    Ok({
+        reading_the_environment()?;
+        parsing_with_from_str()?;
+        layered_configuration()?;
+    })
+}
+
+/// When to color what the lessons print.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ColorChoice {
+    /// Only when printing to a terminal.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+impl FromStr for ColorChoice {
+    type Err = String;
+
+    fn from_str(when: &str) -> std::result::Result<ColorChoice, String> {
+        match when.to_ascii_lowercase().as_str() {
+            "auto" => Ok(ColorChoice::Auto),
+            "always" => Ok(ColorChoice::Always),
+            "never" => Ok(ColorChoice::Never),
+            _ => Err(format!(
+                "`{when}` isn't when to color: `auto`, `always` or `never`"
+            )),
+        }
+    }
+}
+impl Display for ColorChoice {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.pad(match self {
+            ColorChoice::Auto => "auto",
+            ColorChoice::Always => "always",
+            ColorChoice::Never => "never",
+        })
+    }
+}
+
+/// The settings one place gives, with `None` for what it leaves to the others.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Layer {
+    pub output: Option<PathBuf>,
+    pub color: Option<ColorChoice>,
+    pub verbosity: Option<Verbosity>,
+}
+impl Layer {
+    /// The settings of a configuration file, or what is wrong with it, and on which line.
+    pub fn parse(text: &str) -> std::result::Result<Layer, String> {
+        let mut layer = Layer::default();
+        for (n, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let at = |message: String| format!("line {}: {message}", n + 1);
+            let Some((key, value)) = line.split_once('=') else {
+                return Err(at(format!("`{line}` isn't a `key = value`")));
+            };
+            let value = value.trim();
+            match key.trim() {
+                "output" => layer.output = Some(PathBuf::from(value)),
+                "color" => layer.color = Some(value.parse().map_err(at)?),
+                "verbosity" => layer.verbosity = Some(value.parse().map_err(at)?),
+                key => return Err(at(format!("there is no setting `{key}`"))),
+            }
+        }
+        Ok(layer)
+    }
+
+    /// The settings of the environment variables `var` finds, which is `env::var_os` but in the
+    /// tests and the lesson. `NO_COLOR`, set to anything, is `never`, as no-color.org asks,
+    /// unless `INTERMEDIATE_RUST_COLOR` says otherwise.
+    pub fn from_env(var: impl Fn(&str) -> Option<OsString>) -> std::result::Result<Layer, String> {
+        let no_color = var("NO_COLOR").is_some_and(|value| !value.is_empty());
+        Ok(Layer {
+            // a path needn’t be Unicode, so it is taken as it is
+            output: var("INTERMEDIATE_RUST_OUTPUT").map(PathBuf::from),
+            color: match parse_var(&var, "INTERMEDIATE_RUST_COLOR")? {
+                None if no_color => Some(ColorChoice::Never),
+                color => color,
+            },
+            verbosity: parse_var(&var, "INTERMEDIATE_RUST_VERBOSITY")?,
+        })
+    }
+
+    /// `self`, with what it leaves out taken from `below`.
+    pub fn over(self, below: Layer) -> Layer {
+        Layer {
+            output: self.output.or(below.output),
+            color: self.color.or(below.color),
+            verbosity: self.verbosity.or(below.verbosity),
+        }
+    }
+}
+
+/// The variable `name`, parsed, if it is set; an error names it.
+fn parse_var<T: FromStr<Err = String>>(
+    var: impl Fn(&str) -> Option<OsString>,
+    name: &str,
+) -> std::result::Result<Option<T>, String> {
+    var(name)
+        .map(|value| match value.into_string() {
+            Ok(value) => value.parse().map_err(|x| format!("{name}: {x}")),
+            Err(value) => Err(format!("{name}: {value:?} isn't Unicode")),
+        })
+        .transpose()
+}
+
+/// How the runner prints, once every layer has had its say.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Settings {
+    /// The file the lessons print to, or the terminal, if `None`.
+    pub output: Option<PathBuf>,
+    pub color: ColorChoice,
+    pub verbosity: Verbosity,
+}
+impl Settings {
+    pub fn path() -> PathBuf {
+        env::var_os("INTERMEDIATE_RUST_CONFIG")
+            .map_or_else(|| PathBuf::from(".intermediate_rust.conf"), PathBuf::from)
+    }
+
+    /// The settings `layers` give, the most specific first, over the defaults.
+    pub fn layered(layers: impl IntoIterator<Item = Layer>) -> Settings {
+        let layer = layers.into_iter().fold(Layer::default(), Layer::over);
+        let defaults = Settings::default();
+        Settings {
+            output: layer.output.or(defaults.output),
+            color: layer.color.unwrap_or(defaults.color),
+            verbosity: layer.verbosity.unwrap_or(defaults.verbosity),
+        }
+    }
+
+    /// The settings of this run: `cli`’s, over the environment’s, over those of the file at
+    /// `path`, if there is one.
+    pub fn load(path: &Path, cli: Layer) -> Result<Settings> {
+        let env = Layer::from_env(|name| env::var_os(name))
+            .map_err(|x| Error::new(ErrorKind::InvalidInput, x))?;
+        Ok(Settings::layered([cli, env, Settings::file(path)?]))
+    }
+
+    /// The settings of the file at `path`, or none, if there is no file. Under Miri, which has
+    /// no file system, there is never a file.
+    fn file(path: &Path) -> Result<Layer> {
+        if cfg!(miri) {
+            return Ok(Layer::default());
+        }
+        match fs::read_to_string(path) {
+            Ok(text) => Layer::parse(&text).map_err(|x| {
+                Error::new(ErrorKind::InvalidData, format!("{}: {x}", path.display()))
+            }),
+            Err(x) if x.kind() == ErrorKind::NotFound => Ok(Layer::default()),
+            Err(x) => Err(x),
+        }
+    }
+
+    /// Whether to color what is printed: with `auto`, only what is printed to a terminal.
+    pub fn color(&self) -> bool {
+        match self.color {
+            ColorChoice::Always => true,
+            ColorChoice::Never => false,
+            ColorChoice::Auto => self.output.is_none() && io::stdout().is_terminal(),
+        }
+    }
+
+    /// Where the lessons print: the `output` file, created anew, or the terminal, or nowhere,
+    /// when they are quiet. The file gets what they `eprint`, and so what they log, as well, a
+    /// line at a time from each, so that the lines are in the order they were printed in.
+    pub fn output(&self) -> Result<Output> {
+        if self.verbosity == Verbosity::Quiet {
+            return Ok(Output::silent());
+        }
+        let stdout = Output::stdout();
+        let (out, err): (Box<dyn Write>, Box<dyn Write>) = match &self.output {
+            Some(path) => {
+                // a clone of a `File` is another handle to the same file, at the same position
+                let file = File::create(path)?;
+                (
+                    Box::new(LineWriter::new(file.try_clone()?)),
+                    Box::new(LineWriter::new(file)),
+                )
+            }
+            None => (stdout.out, stdout.err),
+        };
+        Ok(Output {
+            out,
+            err,
+            color: self.color(),
+            verbosity: self.verbosity,
+        })
+    }
+}
+
+/// # Reading the Environment
+/// `env::var` is a variable’s value as a `String`, or why there isn’t one: it isn’t set, or it
+/// isn’t Unicode, which on Unix it needn’t be. `env::var_os` is the value as it is, an
+/// `OsString`, for what only has to be handed on, such as a path. `env::args` are the
+/// command-line arguments, the program’s own name first, which a parser such as `clap` makes
+/// sense of. Setting a variable, `env::set_var`, is `unsafe` in the 2024 edition, as another
+/// thread may be reading the environment, so code that reads it takes a function to read it
+/// with, `env::var_os` when it runs, and a map in a test.
+fn reading_the_environment() -> Result<()> {
+    Ok({
+        span!("reading_the_environment");
+        header!("Reading the Environment");
+
+        println!("{:?}", env::var("INTERMEDIATE_RUST_NOT_SET")); // Err(NotPresent)
+        println!("PATH is set: {}", env::var_os("PATH").is_some()); // PATH is set: true
+        println!("the program is first: {}", env::args_os().next().is_some()); // ...: true
+
+        let vars = HashMap::from([("INTERMEDIATE_RUST_VERBOSITY", "debug"), ("NO_COLOR", "1")]);
+        let layer = Layer::from_env(|name| vars.get(name).map(OsString::from));
+        println!("{:?}", layer.map_err(Error::other)?);
+        // Layer { output: None, color: Some(Never), verbosity: Some(Debug) }
+    })
+}
+
+/// # Parsing with `FromStr`
+/// A setting is text, wherever it comes from, and the program wants a type: `str::parse` turns
+/// one into the other, for any type that implements `FromStr`, the numbers of the standard
+/// library, `ColorChoice` and `Verbosity` here, and `clap` parses the command line with the same
+/// impls. Its `Err` says what was wrong, which the file’s parser adds the line number to.
+fn parsing_with_from_str() -> Result<()> {
+    Ok({
+        span!("parsing_with_from_str");
+        header!("Parsing with `FromStr`");
+
+        println!("{:?}", "never".parse::<ColorChoice>()); // Ok(Never)
+        println!("{:?}", "Verbose".parse::<Verbosity>()); // Ok(Verbose)
+        println!("{:?}", "8080".parse::<u16>()); // Ok(8080)
+        if let Err(x) = "80800".parse::<u16>() {
+            println!("{x}"); // number too large to fit in target type
+        }
+        if let Err(x) = Layer::parse("color = never\ncolor = sometimes") {
+            println!("{x}"); // line 2: `sometimes` isn't when to color: `auto`, `always` or `never`
+        }
+    })
+}
+
+/// # Layered Configuration
+/// Each layer is a `Layer`, of `Option`s, and `over` puts one on top of another: what the top
+/// one sets wins, and what it leaves out falls through, with `Option::or`. The defaults are
+/// under them all, and what falls through every layer is the default. A `--color` on the
+/// command line wins over the environment’s `NO_COLOR`, which wins over the file.
+fn layered_configuration() -> Result<()> {
+    Ok({
+        span!("layered_configuration");
+        header!("Layered Configuration");
+
+        let file = Layer::parse("output = lessons.txt\ncolor = always\nverbosity = verbose")
+            .map_err(Error::other)?;
+        let vars = HashMap::from([("NO_COLOR", "1")]);
+        let env =
+            Layer::from_env(|name| vars.get(name).map(OsString::from)).map_err(Error::other)?;
+        let cli = Layer {
+            verbosity: Some(Verbosity::Quiet),
+            ..Layer::default()
+        };
+
+        println!("{:?}", Settings::layered([]));
+        // Settings { output: None, color: Auto, verbosity: Normal }
+        println!("{:?}", Settings::layered([file.clone()]));
+        // Settings { output: Some("lessons.txt"), color: Always, verbosity: Verbose }
+        println!("{:?}", Settings::layered([env.clone(), file.clone()]));
+        // Settings { output: Some("lessons.txt"), color: Never, verbosity: Verbose }
+        println!("{:?}", Settings::layered([cli, env, file]));
+        // Settings { output: Some("lessons.txt"), color: Never, verbosity: Quiet }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intermediate_rust::output;
+
+    fn env<'a>(vars: &'a [(&str, &str)]) -> impl Fn(&str) -> Option<OsString> + 'a {
+        |name| {
+            let value = vars.iter().find(|(key, _)| *key == name);
+            value.map(|(_, value)| OsString::from(value))
+        }
+    }
+
+    #[test]
+    fn each_layer_overrides_only_what_it_sets() {
+        let file = Layer::parse("# a comment\n\ncolor = never\nverbosity = debug\n").unwrap();
+        let env = Layer::from_env(env(&[("INTERMEDIATE_RUST_OUTPUT", "out.txt")])).unwrap();
+        let cli = Layer {
+            color: Some(ColorChoice::Always),
+            ..Layer::default()
+        };
+        assert_eq!(
+            Settings::layered([cli, env, file]),
+            Settings {
+                output: Some(PathBuf::from("out.txt")),
+                color: ColorChoice::Always,
+                verbosity: Verbosity::Debug,
+            }
+        );
+    }
+
+    #[test]
+    fn no_color_is_never_unless_the_color_is_set() {
+        let layer = Layer::from_env(env(&[("NO_COLOR", "1")])).unwrap();
+        assert_eq!(layer.color, Some(ColorChoice::Never));
+        let layer = Layer::from_env(env(&[("NO_COLOR", "")])).unwrap();
+        assert_eq!(layer.color, None);
+        let layer = Layer::from_env(env(&[
+            ("NO_COLOR", "1"),
+            ("INTERMEDIATE_RUST_COLOR", "always"),
+        ]))
+        .unwrap();
+        assert_eq!(layer.color, Some(ColorChoice::Always));
+    }
+
+    #[test]
+    fn bad_settings_are_errors_that_say_where() {
+        assert_eq!(
+            Layer::parse("verbosity = debug\ncolour = never").unwrap_err(),
+            "line 2: there is no setting `colour`"
+        );
+        assert!(Layer::parse("output").unwrap_err().starts_with("line 1: "));
+        let error = Layer::from_env(env(&[("INTERMEDIATE_RUST_VERBOSITY", "loud")])).unwrap_err();
+        assert!(error.starts_with("INTERMEDIATE_RUST_VERBOSITY: "));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn a_missing_file_is_no_settings_and_a_bad_one_is_an_error() {
+        let dir = env::temp_dir().join(format!("intermediate_rust-config-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let cli = Layer {
+            verbosity: Some(Verbosity::Verbose),
+            ..Layer::default()
+        };
+        let settings = Settings::load(&dir.join("missing.conf"), cli).unwrap();
+        assert_eq!(settings.verbosity, Verbosity::Verbose);
+
+        fs::write(dir.join("bad.conf"), "verbosity = loud").unwrap();
+        let error = Settings::load(&dir.join("bad.conf"), Layer::default()).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::InvalidData);
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn the_output_file_gets_what_is_eprinted_in_order() {
+        let path = env::temp_dir().join(format!("intermediate_rust-out-{}", std::process::id()));
+        let settings = Settings {
+            output: Some(path.clone()),
+            color: ColorChoice::Auto,
+            verbosity: Verbosity::Normal,
+        };
+        let mut output = settings.output().unwrap();
+        output::redirect(&mut output, || {
+            println!("out");
+            eprintln!("err");
+            println!("out again");
+        });
+        drop(output);
+        let text = fs::read_to_string(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(text, "out\nerr\nout again\n");
+    }
+}
@@ -0,0 +1,131 @@
+//! # Being Called from C
+//! `extern` works the other way too: a `pub extern "C" fn` can be called by C, with C’s ABI.
+//! `#[no_mangle]` keeps the name as it is written, so that C’s linker can find it; Rust otherwise
+//! _mangles_ each name into a unique one, with the module path and a hash in it.
+//!
+//! The function is where C’s promises become Rust’s: it checks what it can, a null pointer, and
+//! states the rest as its `# Safety` section, before it hands safe Rust types to safe Rust code,
+//! here the `LimitTracker` of the `RefCell<T>` lesson. A panic mustn’t unwind into C, which
+//! wouldn’t know what to do with it, so an `extern "C" fn` that panics aborts the program.
+
+use crate::intermediate_rust::{
+    smart_pointers::{LimitTracker, Messenger},
+    utils::miri::skip_under_miri,
+};
+use std::{
+    ffi::{c_char, c_void, CStr, CString},
+    io::Result,
+    slice,
+};
+
+extern "C" {
+    // from `helper.c`: runs a C program that uses `ir_track_usage`, printing with `print`
+    fn ir_c_track_usage(print: extern "C" fn(line: *const c_char)) -> usize;
+}
+
+/// What C gives Rust to send the tracker’s messages to: a function, and the `data` it wants
+/// passed back to it, which is how C makes do without closures.
+type Send = extern "C" fn(msg: *const c_char, data: *mut c_void);
+
+struct CMessenger {
+    send: Send,
+    data: *mut c_void,
+}
+impl Messenger for CMessenger {
+    fn send(&self, msg: &str) {
+        // the tracker’s messages are ours, with no `'\0'` in them
+        let msg = CString::new(msg).expect("a message with a '\\0' in it");
+        (self.send)(msg.as_ptr(), self.data);
+    }
+}
+
+/// Tracks `values`, the `len` values at `values`, against `max`, as `LimitTracker` does, and
+/// sends what it reports to `send`, with `data`. Returns how many messages it sent.
+///
+/// # Safety
+/// `values` must point at `len` values, or may be null if `len` is 0, and `send` must be safe to
+/// call with `data` and a `'\0'`-terminated string that it doesn’t keep.
+#[no_mangle]
+pub unsafe extern "C" fn ir_track_usage(
+    max: usize,
+    values: *const usize,
+    len: usize,
+    send: Send,
+    data: *mut c_void,
+) -> usize {
+    let values = match values.is_null() {
+        true => &[],
+        // SAFETY: the caller promises there are `len` values
+        false => unsafe { slice::from_raw_parts(values, len) },
+    };
+    let messenger = CMessenger { send, data };
+    let mut tracker = LimitTracker::new(&messenger, max);
+    values
+        .iter()
+        .filter_map(|&value| tracker.set_value(value))
+        .count()
+}
+
+/// What C calls to print, into the lesson’s output.
+extern "C" fn print(line: *const c_char) {
+    // SAFETY: `helper.c` only passes its own `'\0'`-terminated lines
+    let line = unsafe { CStr::from_ptr(line) };
+    println!("{}", line.to_string_lossy());
+}
+
+pub fn called_from_c() -> Result<()> {
+    Ok({
+        if skip_under_miri("calling C, which Miri can't interpret") {
+            return Ok(());
+        }
+        a_c_program_using_rust()?;
+    })
+}
+
+/// # A C Program Using Rust
+/// Rust calls C, which calls `ir_track_usage`, whose messenger calls C’s `send_to_printer`,
+/// which calls Rust’s `print`: function pointers cross the boundary as well as data does.
+fn a_c_program_using_rust() -> Result<()> {
+    Ok({
+        span!("a_c_program_using_rust");
+        header!("A C Program Using Rust");
+
+        // SAFETY: `print` has the signature `helper.c` declares it with
+        let sent = unsafe { ir_c_track_usage(print) };
+        println!("messages sent to C: {sent}");
+        // C was sent: Warning: You've used up over 75% of your quota!
+        // C was sent: Urgent warning: You've used up over 90% of your quota!
+        // C was sent: Error: You are over your quota!
+        // messages sent to C: 3
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+
+    extern "C" fn collect(msg: *const c_char, data: *mut c_void) {
+        // SAFETY: `data` is the `RefCell` the test passes, and `msg` a message of the tracker
+        let (sent, msg) = unsafe { (&*data.cast::<RefCell<Vec<String>>>(), CStr::from_ptr(msg)) };
+        sent.borrow_mut().push(msg.to_string_lossy().into_owned());
+    }
+
+    // no C in this one, so Miri can check the pointers
+    #[test]
+    fn rust_can_call_its_own_c_abi() {
+        let sent = RefCell::new(Vec::<String>::new());
+        let values = [80, 85, 120];
+        let data = (&raw const sent).cast_mut().cast();
+        // SAFETY: `values` has 3 values, and `collect` takes `data` back as the `RefCell`
+        let count = unsafe { ir_track_usage(100, values.as_ptr(), values.len(), collect, data) };
+        assert_eq!(count, 2);
+        assert_eq!(sent.borrow().len(), 2);
+        assert!(sent.borrow()[1].starts_with("Error"));
+        // SAFETY: a null pointer is fine with no values
+        assert_eq!(
+            unsafe { ir_track_usage(100, std::ptr::null(), 0, collect, data) },
+            0
+        );
+    }
+}
@@ -0,0 +1,99 @@
+//! # Calling C
+//! An `extern "C"` block lists the names and signatures of the C functions Rust calls; the
+//! linker finds them, in the C standard library, which the standard library links already, or in
+//! what `build.rs` compiles. Rust can’t check a foreign function’s body, so it is up to the
+//! declaration to be right, and to each call to be `unsafe`.
+//!
+//! C’s text is a pointer to bytes ending with `'\0'`. `CString` is an owned one, made from Rust
+//! text that has no `'\0'` in it, and `CStr` is a borrowed one, like `&str`, to read what C hands
+//! back.
+
+use crate::intermediate_rust::utils::miri::skip_under_miri;
+use std::{
+    ffi::{c_char, c_int, CStr, CString},
+    io::{Error, Result},
+};
+
+extern "C" {
+    // from the C standard library
+    fn abs(input: c_int) -> c_int;
+    fn strlen(s: *const c_char) -> usize;
+
+    // from `helper.c`
+    fn ir_c_count_vowels(text: *const c_char) -> usize;
+    fn ir_c_greet(name: *const c_char, buf: *mut c_char, len: usize);
+}
+
+pub fn calling_c() -> Result<()> {
+    Ok({
+        if skip_under_miri("calling C, which Miri can't interpret") {
+            return Ok(());
+        }
+        declaring_c_functions()?;
+        text_across_the_boundary()?;
+        a_helper_built_by_build_rs()?;
+    })
+}
+
+/// # Declaring C Functions
+/// Within the `extern "C"` block, we list the names and signatures of external functions from
+/// another language we want to call. `abs` takes and returns an `int`, which is `c_int` in Rust:
+/// C’s types have no fixed size, so `std::ffi` has their Rust equivalents on each platform.
+fn declaring_c_functions() -> Result<()> {
+    Ok({
+        span!("declaring_c_functions");
+        header!("Declaring C Functions");
+
+        // SAFETY: `abs` is C’s, with this signature; it is only undefined for `INT_MIN`
+        let absolute = unsafe { abs(-3) };
+        println!("Absolute value of -3 according to C: {absolute}");
+        // Absolute value of -3 according to C: 3
+    })
+}
+
+/// # Text Across the Boundary
+/// `CString::new` fails on text with a `'\0'` in it, as C would take it for the end.
+/// `as_ptr` borrows the `CString`, so the pointer is only valid while it lives: passing
+/// `CString::new(..)?.as_ptr()` would hand C a pointer into a temporary that is already freed.
+fn text_across_the_boundary() -> Result<()> {
+    Ok({
+        span!("text_across_the_boundary");
+        header!("Text Across the Boundary");
+
+        let text = CString::new("Ferris").map_err(Error::other)?;
+        // SAFETY: `text` is a `'\0'`-terminated string, alive for the whole call
+        let len = unsafe { strlen(text.as_ptr()) };
+        println!("strlen({text:?}) = {len}"); // strlen("Ferris") = 6
+
+        let nul = CString::new("Fer\0ris");
+        println!("CString::new(\"Fer\\0ris\") = {nul:?}");
+        // CString::new("Fer\0ris") = Err(NulError(3, [70, 101, 114, 0, 114, 105, 115]))
+
+        // a `c"..."` literal is a `&'static CStr`, with the `'\0'` added
+        let borrowed: &CStr = c"crab";
+        println!("{borrowed:?} as a &str = {:?}", borrowed.to_str());
+        // "crab" as a &str = Ok("crab")
+    })
+}
+
+/// # A Helper Built by `build.rs`
+/// C can’t allocate a `String` for us to own, so Rust lends it a buffer to write into instead,
+/// and says how long it is. `CStr::from_bytes_until_nul` then reads up to the `'\0'` C wrote.
+fn a_helper_built_by_build_rs() -> Result<()> {
+    Ok({
+        span!("a_helper_built_by_build_rs");
+        header!("A Helper Built by `build.rs`");
+
+        let sentence = CString::new("The quick brown fox").map_err(Error::other)?;
+        // SAFETY: as for `strlen`
+        let vowels = unsafe { ir_c_count_vowels(sentence.as_ptr()) };
+        println!("vowels in {sentence:?} = {vowels}"); // vowels in "The quick brown fox" = 5
+
+        let name = CString::new("Rustacean").map_err(Error::other)?;
+        let mut buf = [0u8; 64];
+        // SAFETY: `buf` has room for `buf.len()` bytes, which is all `ir_c_greet` writes
+        unsafe { ir_c_greet(name.as_ptr(), buf.as_mut_ptr().cast(), buf.len()) };
+        let greeting = CStr::from_bytes_until_nul(&buf).map_err(Error::other)?;
+        println!("{}", greeting.to_string_lossy()); // Hello, Rustacean, from C!
+    })
+}
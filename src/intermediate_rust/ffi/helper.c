@@ -0,0 +1,46 @@
+/*
+ * The C the `ffi` chapter calls: `build.rs` compiles it with the `cc` crate, and links it into
+ * the library, when the `ffi` feature is on.
+ */
+#include <stddef.h>
+#include <stdio.h>
+
+/* Exported by Rust with `#[no_mangle]`, see `called_from_c.rs`. */
+size_t ir_track_usage(size_t max, const size_t *values, size_t len,
+                      void (*send)(const char *msg, void *data), void *data);
+
+/* Called by Rust, see `calling_c.rs`. */
+size_t ir_c_count_vowels(const char *text) {
+    size_t count = 0;
+    for (; *text != '\0'; text++) {
+        switch (*text | 0x20) { /* lowercase, for ASCII letters */
+        case 'a': case 'e': case 'i': case 'o': case 'u':
+            count++;
+        }
+    }
+    return count;
+}
+
+/* Writes at most `len` bytes into `buf`, including the terminating '\0'. */
+void ir_c_greet(const char *name, char *buf, size_t len) {
+    snprintf(buf, len, "Hello, %s, from C!", name);
+}
+
+struct printer {
+    void (*print)(const char *line);
+};
+
+static void send_to_printer(const char *msg, void *data) {
+    const struct printer *printer = data;
+    char line[128];
+    snprintf(line, sizeof line, "C was sent: %s", msg);
+    printer->print(line);
+}
+
+/* A C program using the Rust `LimitTracker` through its C ABI, printing with `print`. */
+size_t ir_c_track_usage(void (*print)(const char *line)) {
+    const size_t values[] = {50, 80, 85, 95, 120};
+    struct printer printer = {print};
+    return ir_track_usage(100, values, sizeof values / sizeof values[0], send_to_printer,
+                          &printer);
+}
@@ -0,0 +1,16 @@
+//! # FFI: Rust and C
+//! Sometimes, your Rust code might need to interact with code written in another language. For
+//! this, Rust has the keyword `extern` that facilitates the creation and use of a _Foreign
+//! Function Interface (FFI)_, a way for a programming language to define functions and enable a
+//! different programming language to call those functions.
+//!
+//! The two sides have to agree on an _application binary interface (ABI)_, how a call passes its
+//! arguments and returns its result; `"C"` is the ABI of the C language, which almost every
+//! other language can speak. What they can’t agree on is what Rust’s types promise: a C pointer
+//! may be null, dangle, or point at text without a terminating `'\0'`, and the compiler can’t
+//! check any of it, so every call across the boundary is `unsafe`.
+//!
+//! The C of this chapter is in `helper.c`, which `build.rs` compiles with the `cc` crate.
+
+pub(super) mod called_from_c;
+pub(super) mod calling_c;
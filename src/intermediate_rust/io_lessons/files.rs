@@ -0,0 +1,223 @@
+//! # Files, and Buffering
+//! Each read from or write to a `File` is a _system call_, a request to the operating system,
+//! which costs far more than copying a few bytes in memory. Reading a line at a time, or
+//! writing one, straight from a `File` makes one call for each; a `BufReader` or a `BufWriter`
+//! sits in front of it, with a buffer of 8 KiB, and makes one call for each buffer full.
+//!
+//! The smart pointer chapter’s `FileLogger`, in `crates/ir-smart-pointers/src/file_logger.rs`,
+//! is the applied example: it keeps its log open in a `BufWriter`, rather than opening the file
+//! for each message, and with `.buffered(true)` only writes it out when the buffer is full.
+
+use crate::intermediate_rust::utils::{miri::skip_under_miri, temp_dir::TempDir};
+use std::{
+    fs::{self, File},
+    io::{BufRead, BufReader, BufWriter, Error, ErrorKind, Read, Result, Write},
+    path::Path,
+};
+
+pub fn files() -> Result<()> {
+    Ok({
+        if skip_under_miri("reading and writing files") {
+            return Ok(());
+        }
+        temporary_files_cleaned_up_by_drop()?;
+        buffered_writing()?;
+        reading_all_at_once_or_line_by_line()?;
+        errors_and_the_question_mark()?;
+    })
+}
+
+/// # Temporary Files, Cleaned Up by `Drop`
+/// A file a program only needs for a while has to be removed when it is done, whether it is
+/// done because it succeeded, returned an error with `?` or panicked. `Drop` runs in all three
+/// cases, so the removal goes in a `drop`, as `TempDir`’s does, in `ir_core::utils::temp_dir`,
+/// where the lessons that write files all get theirs; the `tempfile` crate is the same idea,
+/// with more care for names that can’t be guessed, and permissions.
+fn temporary_files_cleaned_up_by_drop() -> Result<()> {
+    Ok({
+        span!("temporary_files_cleaned_up_by_drop");
+        header!("Temporary Files, Cleaned Up by `Drop`");
+
+        let path = {
+            let dir = TempDir::new("files")?;
+            fs::write(dir.path().join("notes.txt"), "hello\n")?;
+            println!("exists: {}", dir.path().join("notes.txt").exists()); // exists: true
+            dir.path().to_path_buf()
+        }; // `dir` is dropped here
+        println!("exists after the scope: {}", path.exists()); // exists after the scope: false
+    })
+}
+
+/// A `Write` that counts the calls to `write`, standing in for the system calls a `File` makes.
+struct CountingWriter<W> {
+    inner: W,
+    writes: usize,
+}
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.writes += 1;
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Writes `lines` numbered lines to `out`.
+fn write_lines(out: &mut impl Write, lines: usize) -> Result<()> {
+    for i in 0..lines {
+        writeln!(out, "line {i}")?;
+    }
+    Ok(())
+}
+
+/// # Buffered Writing
+/// `writeln!` to a `File` is a `write` system call for each piece of the line it formats: three
+/// for `"line {i}"`, the text, the number and the newline. A `BufWriter` collects the pieces in
+/// its buffer and writes them out together when it is full, when it is flushed, and when it is
+/// dropped. Dropping can’t return an error, so one there is lost: `flush`, or
+/// `into_inner`, before the end is how to hear of it. Writing to a `Vec<u8>` counts the calls
+/// the same way a `File` would make them, without the file.
+fn buffered_writing() -> Result<()> {
+    Ok({
+        span!("buffered_writing");
+        header!("Buffered Writing");
+
+        let mut unbuffered = CountingWriter {
+            inner: Vec::new(),
+            writes: 0,
+        };
+        write_lines(&mut unbuffered, 1000)?;
+        println!("unbuffered: {} writes", unbuffered.writes); // unbuffered: 3000 writes
+
+        let mut buffered = BufWriter::new(CountingWriter {
+            inner: Vec::new(),
+            writes: 0,
+        });
+        write_lines(&mut buffered, 1000)?;
+        let counted = buffered.into_inner().map_err(|x| x.into_error())?;
+        println!("buffered: {} writes", counted.writes); // buffered: 2 writes, as 8 KiB filled up
+        println!("the same bytes: {}", counted.inner == unbuffered.inner); // the same bytes: true
+
+        let dir = TempDir::new("buffered_writing")?;
+        let mut file = BufWriter::new(File::create(dir.path().join("lines.txt"))?);
+        write_lines(&mut file, 1000)?;
+        file.flush()?;
+        let size = fs::metadata(dir.path().join("lines.txt"))?.len();
+        println!("lines.txt is {size} bytes"); // lines.txt is 8890 bytes
+    })
+}
+
+/// # Reading All at Once, or Line by Line
+/// `fs::read_to_string` reads a whole file into one `String`: the simplest, when the file is
+/// known to be small. A file of any size is read in pieces, by _streaming_ it: `BufReader`’s
+/// `lines` reads a buffer at a time and hands out a `String` for each line, so only a buffer and
+/// a line are in memory at once. Each line is an `io::Result`, as reading may fail halfway.
+fn reading_all_at_once_or_line_by_line() -> Result<()> {
+    Ok({
+        span!("reading_all_at_once_or_line_by_line");
+        header!("Reading All at Once, or Line by Line");
+
+        let dir = TempDir::new("reading")?;
+        let path = dir.path().join("lines.txt");
+        let mut file = BufWriter::new(File::create(&path)?);
+        write_lines(&mut file, 1000)?;
+        file.flush()?;
+
+        let whole = fs::read_to_string(&path)?;
+        println!("{} bytes, {} lines", whole.len(), whole.lines().count());
+        // 8890 bytes, 1000 lines
+
+        let mut longest = String::new();
+        for line in BufReader::new(File::open(&path)?).lines() {
+            let line = line?;
+            if line.len() > longest.len() {
+                longest = line;
+            }
+        }
+        println!("the first longest line: {longest:?}"); // the first longest line: "line 100"
+
+        let mut first = [0; 6];
+        File::open(&path)?.read_exact(&mut first)?;
+        println!("the first bytes: {:?}", String::from_utf8_lossy(&first));
+        // the first bytes: "line 0"
+    })
+}
+
+/// The number of words in the file at `path`, or why they can’t be counted, naming the file.
+fn count_words(path: &Path) -> Result<usize> {
+    let text = fs::read_to_string(path)
+        .map_err(|x| Error::new(x.kind(), format!("{}: {x}", path.display())))?;
+    Ok(text.split_whitespace().count())
+}
+
+/// # Errors and the `?` Operator
+/// `?` returns an `Err` to the caller, where a `match` on every call would; each error has a
+/// `kind`, such as `NotFound` or `PermissionDenied`, for the caller to decide what to do with
+/// it. What it doesn’t have is the file’s name, which is usually what the user needs, so a
+/// function that knows it adds it, with `map_err`, before passing the error up.
+fn errors_and_the_question_mark() -> Result<()> {
+    Ok({
+        span!("errors_and_the_question_mark");
+        header!("Errors and the `?` Operator");
+
+        let dir = TempDir::new("errors")?;
+        let path = dir.path().join("words.txt");
+        fs::write(&path, "the quick brown fox")?;
+        println!("{} words", count_words(&path)?); // 4 words
+
+        match count_words(&dir.path().join("missing.txt")) {
+            Ok(words) => println!("{words} words"),
+            Err(x) if x.kind() == ErrorKind::NotFound => {
+                let file = x.to_string().contains("missing.txt");
+                println!("not found, and the error names the file: {file}");
+            } // not found, and the error names the file: true
+            Err(x) => return Err(x),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn a_temp_dir_is_removed_when_dropped() {
+        let dir = TempDir::new("test").unwrap();
+        fs::write(dir.path().join("a.txt"), "a").unwrap();
+        let path = dir.path().to_path_buf();
+        assert!(path.exists());
+        drop(dir);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn a_buf_writer_writes_once_it_is_full() {
+        let mut buffered = BufWriter::with_capacity(
+            16,
+            CountingWriter {
+                inner: Vec::new(),
+                writes: 0,
+            },
+        );
+        write_lines(&mut buffered, 3).unwrap(); // 21 bytes, the first two lines fill the buffer
+        let counted = buffered.into_inner().map_err(|x| x.into_error()).unwrap();
+        assert_eq!(counted.writes, 2);
+        assert_eq!(counted.inner, b"line 0\nline 1\nline 2\n");
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn counting_words_names_the_missing_file() {
+        let dir = TempDir::new("count_words").unwrap();
+        let path = dir.path().join("words.txt");
+        fs::write(&path, " one\ttwo\nthree ").unwrap();
+        assert_eq!(count_words(&path).unwrap(), 3);
+
+        let error = count_words(&dir.path().join("nope.txt")).unwrap_err();
+        assert_eq!(error.kind(), ErrorKind::NotFound);
+        assert!(error.to_string().contains("nope.txt"));
+    }
+}
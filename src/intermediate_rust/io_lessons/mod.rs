@@ -0,0 +1,13 @@
+//! # Input and Output
+//! A program talks to the world through files, sockets and other processes, and `std::io` gives
+//! them all the same two traits: `Read`, for what can be read from, a byte at a time or a buffer
+//! at a time, and `Write`, for what can be written to. Code written against them works as well
+//! with a `File` as with a `TcpStream`, a child process’s pipe, or a `Vec<u8>` in a test.
+//!
+//! Every operation can fail, as the world can refuse: a file may not be there, a disk may be
+//! full, a connection may drop. So each returns an `io::Result`, and `?` passes the error up.
+//! Miri has no world to talk to, so these lessons are skipped under it.
+
+pub(super) mod files;
+pub(super) mod processes;
+pub(super) mod tcp;
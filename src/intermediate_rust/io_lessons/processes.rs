@@ -0,0 +1,180 @@
+//! # Child Processes
+//! `std::process::Command` runs another program: it is built up like a builder, with the
+//! program, its arguments, its environment and what its standard input, output and error are
+//! connected to, and then run, with `output`, which waits and collects what it wrote, or with
+//! `spawn`, which returns a `Child` at once, while it runs. A child’s pipes are a `Read` and a
+//! `Write`, like a file or a socket.
+//!
+//! The commands here are run by the platform’s shell, `sh` or `cmd`, and written in what the
+//! two have in common, so that the lesson runs the same on Linux, macOS and Windows.
+
+use crate::intermediate_rust::utils::miri::skip_under_miri;
+use std::{
+    io::{BufRead, BufReader, Error, Result, Write},
+    process::{Command, Stdio},
+};
+
+pub fn processes() -> Result<()> {
+    Ok({
+        if skip_under_miri("running child processes") {
+            return Ok(());
+        }
+        running_a_command_and_capturing_its_output()?;
+        standard_error_and_the_exit_status()?;
+        piping_one_child_into_another()?;
+        streaming_a_childs_output()?;
+    })
+}
+
+/// A `Command` that runs `script` with the platform’s shell: `sh -c` on Unix, `cmd /C` on
+/// Windows. `echo a&& echo b`, `>&2 echo a` and `exit 3` mean the same to both.
+fn shell(script: &str) -> Command {
+    let (program, flag) = if cfg!(windows) {
+        ("cmd", "/C")
+    } else {
+        ("sh", "-c")
+    };
+    let mut command = Command::new(program);
+    command.args([flag, script]);
+    command
+}
+
+/// The lines of what a child wrote, without the `\r\n`s of Windows.
+fn lines(bytes: &[u8]) -> Vec<String> {
+    String::from_utf8_lossy(bytes)
+        .lines()
+        .map(String::from)
+        .collect()
+}
+
+/// # Running a Command and Capturing Its Output
+/// `output` runs the command to the end, with its standard output and error captured in
+/// `Vec<u8>`s, bytes rather than a `String`, as a program may write anything. Each argument is
+/// passed to the program as it is, not split on spaces nor interpreted by a shell, unless the
+/// program is a shell, as here. `status` would run it with the lesson’s own output instead,
+/// which would bypass the lesson’s `println!`.
+fn running_a_command_and_capturing_its_output() -> Result<()> {
+    Ok({
+        span!("running_a_command_and_capturing_its_output");
+        header!("Running a Command and Capturing Its Output");
+
+        let output = shell("echo hello").output()?;
+        println!("stdout: {:?}", lines(&output.stdout)); // stdout: ["hello"]
+        println!("success: {}", output.status.success()); // success: true
+
+        let missing = Command::new("no-such-program-of-intermediate-rust").output();
+        let kind = missing.err().map(|x| x.kind());
+        println!("a program that isn't there: {kind:?}");
+        // a program that isn't there: Some(NotFound)
+    })
+}
+
+/// # Standard Error and the Exit Status
+/// A program says whether it succeeded with its exit code, `0` for success, and why it didn’t on
+/// its standard error, kept apart from its output. `success` is the usual question; `code` is
+/// `None` on Unix for a child killed by a signal, which has no code, and
+/// `std::os::unix::process::ExitStatusExt` has the signal. A failed child isn’t an `Err`: the
+/// command ran, and it is for the caller to decide what its failure means.
+fn standard_error_and_the_exit_status() -> Result<()> {
+    Ok({
+        span!("standard_error_and_the_exit_status");
+        header!("Standard Error and the Exit Status");
+
+        let output = shell("echo some output&& >&2 echo something went wrong&& exit 3").output()?;
+        println!("stdout: {:?}", lines(&output.stdout)); // stdout: ["some output"]
+        println!("stderr: {:?}", lines(&output.stderr)); // stderr: ["something went wrong"]
+        println!(
+            "success: {}, code: {:?}",
+            output.status.success(),
+            output.status.code()
+        );
+        // success: false, code: Some(3)
+    })
+}
+
+/// # Piping One Child into Another
+/// `Stdio::piped()` connects a child’s stream to a pipe its parent holds, in `Child`’s `stdin`,
+/// `stdout` and `stderr`; a `ChildStdout` can also be another child’s input, with `Stdio::from`,
+/// as a shell’s `|` does, without the bytes passing through the parent. Writing to a child’s
+/// `stdin` is writing to a pipe, which has to be closed, by dropping it, for the child to see the
+/// end of its input; `wait_with_output` drops it before waiting, or `sort` would wait for more.
+fn piping_one_child_into_another() -> Result<()> {
+    Ok({
+        span!("piping_one_child_into_another");
+        header!("Piping One Child into Another");
+
+        let mut fruits = shell("echo cherry&& echo apple&& echo banana")
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let fruits_out = fruits.stdout.take().ok_or(Error::other("no stdout"))?;
+        let sorted = Command::new("sort")
+            .stdin(Stdio::from(fruits_out))
+            .output()?;
+        fruits.wait()?;
+        println!("{:?}", lines(&sorted.stdout)); // ["apple", "banana", "cherry"]
+
+        let mut sort = Command::new("sort")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let mut stdin = sort.stdin.take().ok_or(Error::other("no stdin"))?;
+        stdin.write_all(b"2\n3\n1\n")?;
+        drop(stdin);
+        println!("{:?}", lines(&sort.wait_with_output()?.stdout)); // ["1", "2", "3"]
+    })
+}
+
+/// # Streaming a Child’s Output
+/// `output` only returns once the child has exited; a child that runs for long, or writes a lot,
+/// is better read while it runs, a line at a time, with a `BufReader` on its `stdout`. The child
+/// waits when the pipe is full, so reading is also what keeps it going. `wait` then collects its
+/// exit status: a child that isn’t waited for stays a _zombie_, on Unix, until its parent exits.
+fn streaming_a_childs_output() -> Result<()> {
+    Ok({
+        span!("streaming_a_childs_output");
+        header!("Streaming a Child’s Output");
+
+        let mut child = shell("echo compiling&& echo testing&& echo done")
+            .stdout(Stdio::piped())
+            .spawn()?;
+        let stdout = child.stdout.take().ok_or(Error::other("no stdout"))?;
+        for (n, line) in BufReader::new(stdout).lines().enumerate() {
+            println!("{}: {}", n + 1, line?);
+        }
+        // 1: compiling
+        // 2: testing
+        // 3: done
+        let status = child.wait()?;
+        if !status.success() {
+            return Err(Error::other(format!("the child failed: {status}")));
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn the_shell_runs_the_same_script_on_every_platform() {
+        let output = shell("echo a&& echo b&& >&2 echo c").output().unwrap();
+        assert!(output.status.success());
+        assert_eq!(lines(&output.stdout), ["a", "b"]);
+        assert_eq!(lines(&output.stderr), ["c"]);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn a_failing_child_is_a_status_not_an_error() {
+        let status = shell("exit 7").status().unwrap();
+        assert!(!status.success());
+        assert_eq!(status.code(), Some(7));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn the_lesson_runs() {
+        processes().unwrap();
+    }
+}
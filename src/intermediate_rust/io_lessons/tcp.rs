@@ -0,0 +1,262 @@
+//! # TCP: a Server and Its Clients
+//! A `TcpListener` waits for connections on a port; each one it accepts is a `TcpStream`, as is
+//! what a client gets from `TcpStream::connect`. A stream is a `Read` and a `Write` at once, of
+//! bytes, in order, with no messages in them: where one ends is for the two ends to agree on,
+//! and here it is a newline, so a `BufReader`’s `lines` splits them.
+//!
+//! The server and its clients all run in this lesson, on `localhost`, on a port the operating
+//! system picks, so nothing outside the machine is reached.
+
+use crate::intermediate_rust::{
+    smart_pointers::{LimitTracker, TcpMessenger},
+    utils::miri::skip_under_miri,
+};
+use std::{
+    io::{BufRead, BufReader, ErrorKind, Read, Result, Write},
+    net::{Shutdown, SocketAddr, TcpListener, TcpStream},
+    thread::{self, JoinHandle},
+};
+
+pub fn tcp() -> Result<()> {
+    Ok({
+        if skip_under_miri("talking over TCP") {
+            return Ok(());
+        }
+        a_line_echo_server()?;
+        reading_and_writing_a_stream()?;
+        a_thread_for_each_connection()?;
+        sending_a_limit_trackers_messages_over_tcp()?;
+    })
+}
+
+/// Writes each line read from `stream` back to it, until the client is done, and returns the
+/// lines. A client that hangs up with an echo it hasn’t read is _reset_ rather than closed, and
+/// that is the end of the connection as much as a close is, rather than an error.
+fn echo(stream: TcpStream) -> Result<Vec<String>> {
+    let mut received = Vec::new();
+    // `Read` and `Write` are implemented for `&TcpStream`, so the one stream is both
+    let mut writer = &stream;
+    let echoed = BufReader::new(&stream).lines().try_for_each(|line| {
+        let line = line?;
+        let echoed = writeln!(writer, "{line}");
+        received.push(line);
+        echoed
+    });
+    match echoed {
+        Ok(()) => Ok(received),
+        Err(x) if hung_up(x.kind()) => Ok(received),
+        Err(x) => Err(x),
+    }
+}
+
+/// Whether an error is of a client that has gone: reset, when it is read from, or a broken
+/// pipe, when it is written to; Windows calls both an abort.
+fn hung_up(kind: ErrorKind) -> bool {
+    matches!(
+        kind,
+        ErrorKind::ConnectionReset | ErrorKind::BrokenPipe | ErrorKind::ConnectionAborted
+    )
+}
+
+/// A line-echo server on `localhost`, running on a thread of its own, with a thread for each
+/// connection it accepts.
+///
+/// It serves a number of connections, known in advance, and stops listening. A server that runs
+/// until it is told to stop has to interrupt `accept`, which blocks with no timeout: with a
+/// connection of its own, or with a listener that doesn’t block, polled, which is what an async
+/// runtime does.
+struct EchoServer {
+    addr: SocketAddr,
+    thread: JoinHandle<Result<Vec<Vec<String>>>>,
+}
+impl EchoServer {
+    fn start(connections: usize) -> Result<EchoServer> {
+        // port 0 is for the operating system to pick one that is free
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let addr = listener.local_addr()?;
+        let thread = thread::spawn(move || {
+            let mut echoes = Vec::new();
+            for stream in listener.incoming().take(connections) {
+                let stream = stream?;
+                echoes.push(thread::spawn(move || echo(stream)));
+            }
+            // the listener is dropped here, and the port closed, while the echoes go on
+            let echoes = echoes.into_iter().map(JoinHandle::join);
+            echoes.map(|x| x.expect("a connection panicked")).collect()
+        });
+        Ok(EchoServer { addr, thread })
+    }
+
+    fn addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    /// Waits for the server to have served all its connections, and returns the lines each
+    /// received, in the order they were accepted.
+    fn join(self) -> Result<Vec<Vec<String>>> {
+        self.thread.join().expect("the server panicked")
+    }
+}
+
+/// # A Line-Echo Server
+/// The server binds a `TcpListener`, and a thread `accept`s its connections, in a loop; a client
+/// `connect`s to its address, writes a line, and reads the same line back. Reading the answer
+/// line by line needs a `BufReader`, as a stream only knows of bytes.
+fn a_line_echo_server() -> Result<()> {
+    Ok({
+        span!("a_line_echo_server");
+        header!("A Line-Echo Server");
+
+        let server = EchoServer::start(1)?;
+        let mut client = TcpStream::connect(server.addr())?;
+        writeln!(client, "hello")?;
+        let mut echoed = String::new();
+        BufReader::new(&client).read_line(&mut echoed)?;
+        println!("echoed: {echoed:?}"); // echoed: "hello\n"
+
+        drop(client); // ends the connection’s `lines()`, on the server
+        let received = server.join()?;
+        println!("the server received: {received:?}"); // the server received: [["hello"]]
+    })
+}
+
+/// # Reading and Writing a Stream
+/// A client that has said all it has to say `shutdown`s its writing half: the server reads the
+/// end of the stream, as it would at the end of a file, echoes what is left and hangs up, while
+/// the client can still read. `read_to_string` then reads everything up to the server’s hang-up,
+/// however many packets it arrived in. Dropping the stream would have closed both halves.
+fn reading_and_writing_a_stream() -> Result<()> {
+    Ok({
+        span!("reading_and_writing_a_stream");
+        header!("Reading and Writing a Stream");
+
+        let server = EchoServer::start(1)?;
+        let mut client = TcpStream::connect(server.addr())?;
+        client.write_all(b"one\ntwo\nthree\n")?;
+        client.shutdown(Shutdown::Write)?;
+
+        let mut echoed = String::new();
+        client.read_to_string(&mut echoed)?;
+        println!("{:?}", echoed.lines().collect::<Vec<_>>()); // ["one", "two", "three"]
+
+        let error = writeln!(client, "four").err().map(|x| x.kind());
+        println!("writing after the shutdown: {error:?}");
+        // writing after the shutdown: Some(BrokenPipe)
+        server.join()?;
+    })
+}
+
+/// # A Thread for Each Connection
+/// `accept` hands each connection to a thread of its own, and goes back to waiting for the next
+/// one, so a slow client doesn’t hold up the others. A thread for each is simple, and enough
+/// for a few hundred clients; a server of thousands waits for all of them on a few threads, with
+/// an async runtime such as `tokio`.
+fn a_thread_for_each_connection() -> Result<()> {
+    Ok({
+        span!("a_thread_for_each_connection");
+        header!("A Thread for Each Connection");
+
+        let server = EchoServer::start(3)?;
+        let addr = server.addr();
+        let clients = (0..3).map(|n| {
+            thread::spawn(move || -> Result<String> {
+                let mut stream = TcpStream::connect(addr)?;
+                writeln!(stream, "client {n}, first")?;
+                writeln!(stream, "client {n}, second")?;
+                stream.shutdown(Shutdown::Write)?;
+                let mut echoed = String::new();
+                stream.read_to_string(&mut echoed)?;
+                Ok(echoed)
+            })
+        });
+        let mut echoed = Vec::new();
+        for client in clients.collect::<Vec<_>>() {
+            echoed.push(client.join().expect("a client panicked")?);
+        }
+        // each client got its own lines back, whichever order the server accepted them in
+        println!("{:?}", echoed[2].lines().collect::<Vec<_>>());
+        // ["client 2, first", "client 2, second"]
+
+        let received = server.join()?;
+        println!("{} connections", received.len()); // 3 connections
+        let refused = TcpStream::connect(addr).is_err();
+        println!("a fourth is refused: {refused}"); // a fourth is refused: true
+    })
+}
+
+/// # Sending a `LimitTracker`’s Messages over TCP
+/// The `RefCell<T>` lesson’s `TcpMessenger` is a client like the ones above: `connect` opens a
+/// `TcpStream`, and `send` writes each message to it as a line, through a `&TcpStream`, as
+/// `Messenger::send` only has `&self`. It never reads, so its server is one that only listens,
+/// not the echo server: a client that closes its end with echoes it hasn’t read resets the
+/// connection, and the server loses what it hadn’t read yet with it.
+///
+/// `connect` returns once the operating system has queued the connection, before the server
+/// `accept`s it, so one thread can do both, one after the other.
+fn sending_a_limit_trackers_messages_over_tcp() -> Result<()> {
+    Ok({
+        span!("sending_a_limit_trackers_messages_over_tcp");
+        header!("Sending a `LimitTracker`’s Messages over TCP");
+
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let messenger = TcpMessenger::connect(listener.local_addr()?)?;
+        let (stream, _) = listener.accept()?;
+
+        let mut tracker = LimitTracker::new(&messenger, 100);
+        tracker.set_value(80);
+        tracker.set_value(100);
+        drop(messenger); // the end of the stream, for the server
+
+        for line in BufReader::new(stream).lines() {
+            println!("the server received: {}", line?);
+        }
+        // the server received: Warning: You've used up over 75% of your quota!
+        // the server received: Error: You are over your quota!
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn each_line_is_echoed_back() {
+        let server = EchoServer::start(1).unwrap();
+        let mut client = TcpStream::connect(server.addr()).unwrap();
+        client.write_all(b"a\n\nb c\n").unwrap();
+        client.shutdown(Shutdown::Write).unwrap();
+        let mut echoed = String::new();
+        client.read_to_string(&mut echoed).unwrap();
+        assert_eq!(echoed, "a\n\nb c\n");
+
+        assert_eq!(server.join().unwrap(), [["a", "", "b c"]]);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn the_server_stops_listening_after_its_connections() {
+        let server = EchoServer::start(2).unwrap();
+        let addr = server.addr();
+        for line in ["first", "second"] {
+            let mut client = TcpStream::connect(addr).unwrap();
+            writeln!(client, "{line}").unwrap();
+        }
+        assert_eq!(server.join().unwrap(), [["first"], ["second"]]);
+        assert!(TcpStream::connect(addr).is_err());
+        assert!(EchoServer::start(0).unwrap().join().unwrap().is_empty());
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn a_client_that_hangs_up_without_reading_is_not_an_error() {
+        let server = EchoServer::start(10).unwrap();
+        for _ in 0..10 {
+            let mut client = TcpStream::connect(server.addr()).unwrap();
+            writeln!(client, "unread").unwrap();
+        }
+        for received in server.join().unwrap() {
+            assert_eq!(received, ["unread"]);
+        }
+    }
+}
@@ -0,0 +1,82 @@
+//! # Logging with the `log` Facade
+//! What a program prints with `println!` is what it is there to print. What it has to say about
+//! how it is getting on, that a retry was needed, that a message was lost, is better _logged_:
+//! given a level of seriousness, and left for whoever runs the program to let through or not.
+//!
+//! The `log` crate is a facade. It has the macros, `error!`, `warn!`, `info!`, `debug!` and
+//! `trace!`, but no logger: a library logs with the macros, and the binary picks the logger that
+//! decides which records are let through and where they go. Until a logger is set, every record
+//! is discarded. This binary sets up `env_logger`, which reads what to let through from
+//! `RUST_LOG`, and lets warnings and errors through without it:
+//! ```text
+//! RUST_LOG=info intermediate_rust logging
+//! RUST_LOG=warn,logging=trace intermediate_rust logging
+//! ```
+
+use log::Level;
+use std::io::Result;
+
+pub fn logging() -> Result<()> {
+    Ok({
+        levels()?;
+        targets()?;
+    })
+}
+
+/// # Levels
+/// Every record has a level, from `Error`, the most serious, down to `Trace`. The logger filters
+/// by level, and tells `log` the most verbose level it may let through, `log::max_level()`; the
+/// macros check it before doing anything else, so a record nobody will see costs a comparison.
+/// `log_enabled!` asks the same question ahead of work that is only worth doing if the record is
+/// going to be seen.
+fn levels() -> Result<()> {
+    Ok({
+        span!("levels");
+        header!("Levels");
+
+        // without RUST_LOG
+        println!("max level = {}", log::max_level()); // max level = WARN
+        for level in [Level::Error, Level::Warn, Level::Info] {
+            println!("{level} enabled = {}", log::log_enabled!(level));
+        }
+        // ERROR enabled = true
+        // WARN enabled = true
+        // INFO enabled = false
+
+        log::error!("out of quota");
+        log::warn!("75% of the quota used");
+        log::info!("checked the quota");
+        // [ERROR] out of quota
+        // [WARN] 75% of the quota used
+        // and not the info, which isn't let through
+
+        if log::log_enabled!(Level::Debug) {
+            let used: Vec<_> = (1..=4).map(|i| i * 25).collect();
+            log::debug!("quota used over time: {used:?}");
+        }
+        // with RUST_LOG=debug
+        // [DEBUG] quota used over time: [25, 50, 75, 100]
+    })
+}
+
+/// # Targets
+/// Every record also has a target, which is the path of the module it was logged from, unless
+/// the macro is given one with `target:`. `RUST_LOG` can set a level per target, and a target
+/// includes every target under it: `RUST_LOG=warn,logging=trace` lets everything through from
+/// the targets starting with `logging`, and only warnings and errors from the rest.
+fn targets() -> Result<()> {
+    Ok({
+        span!("targets");
+        header!("Targets");
+
+        println!("module path = {}", module_path!());
+        // module path = intermediate_rust::intermediate_rust::logging
+
+        log::warn!(target: "logging::quota", "90% of the quota used");
+        log::trace!(target: "logging::quota", "quota checked at 90%");
+        // [WARN] 90% of the quota used
+        // with RUST_LOG=warn,logging=trace
+        // [WARN] 90% of the quota used
+        // [TRACE] quota checked at 90%
+    })
+}
@@ -0,0 +1,316 @@
+//! # Declarative Macros with `macro_rules!`
+//! A declarative macro compares the code it is given with patterns, like `match` does with
+//! values, and replaces itself with the code of the arm that matched, before the code is
+//! compiled. It matches code, not values: the pieces it captures, _fragments_, are parsed as an
+//! expression, an identifier, a type, or a single token tree, and are pasted in whole.
+//!
+//! The expansions below are written out in comments the way the nightly-only `trace_macros!`
+//! prints them, one step at a time. `cargo expand`, or rust-analyzer’s “Expand macro
+//! recursively”, shows them for any macro on stable.
+//!
+//! A procedural macro is a function instead, from code to code, see `procedural`.
+
+use crate::intermediate_rust::smart_pointers::List;
+use std::{collections::BTreeMap, io::Result};
+
+pub(super) mod procedural;
+
+pub fn declarative() -> Result<()> {
+    Ok({
+        matchers_and_fragments()?;
+        repetition()?;
+        building_cons_step_by_step()?;
+        building_defer_step_by_step()?;
+        hygiene()?;
+        a_hashmap_constructor()?;
+    })
+}
+
+/// Squares an expression, which is matched as a whole: it is parenthesized however it was
+/// written, as it was parsed as an expression before it was pasted in.
+macro_rules! square {
+    ($x:expr) => {
+        $x * $x
+    };
+}
+
+/// A function named `$name` that returns `$value`, of type `$t`: an identifier and a type are
+/// fragments too.
+macro_rules! constant {
+    ($name:ident: $t:ty = $value:expr) => {
+        fn $name() -> $t {
+            $value
+        }
+    };
+}
+
+/// # Matchers and Fragments
+/// `$x:expr` matches an expression, and names it `$x`. A C macro pastes text, and
+/// `SQUARE(1 + 2)` is `1 + 2 * 1 + 2`, which is 5; `square!(1 + 2)` is `(1 + 2) * (1 + 2)`.
+/// The other fragments are `ident`, `ty`, `pat`, `stmt`, `block`, `item`, `path`, `lifetime`,
+/// `literal`, `vis`, `meta` and `tt`, a single token or a group in brackets. Anything else in a
+/// matcher, such as `:` and `=` in `constant!`, has to be there as it is.
+fn matchers_and_fragments() -> Result<()> {
+    Ok({
+        span!("matchers_and_fragments");
+        header!("Matchers and Fragments");
+
+        // square!(1 + 2) => (1 + 2) * (1 + 2)
+        println!("square!(1 + 2) = {}", square!(1 + 2)); // square!(1 + 2) = 9
+
+        constant!(answer: u8 = 6 * 7);
+        // constant!(answer: u8 = 6 * 7) => fn answer() -> u8 { 6 * 7 }
+        println!("answer() = {}", answer()); // answer() = 42
+    })
+}
+
+/// Adds up any number of expressions.
+macro_rules! sum {
+    ($($x:expr),* $(,)?) => {
+        0 $(+ $x)*
+    };
+}
+
+/// # Repetition
+/// `$( ... ),*` matches what is in the parentheses any number of times, separated by commas,
+/// `+` once or more, and `?` at most once, as `$(,)?` matches a trailing comma, or none. In the
+/// expansion, `$( ... )*` repeats once for each time the fragments in it matched.
+fn repetition() -> Result<()> {
+    Ok({
+        span!("repetition");
+        header!("Repetition");
+
+        // sum!(1, 2, 3,) => 0 + 1 + 2 + 3
+        println!("sum!(1, 2, 3,) = {}", sum!(1, 2, 3,)); // sum!(1, 2, 3,) = 6
+        println!("sum!() = {}", sum!()); // sum!() = 0
+    })
+}
+
+/// The first step: one element, and nothing more.
+macro_rules! cons_one {
+    ($head:expr) => {
+        List::Cons($head, Box::new(List::Nil))
+    };
+}
+
+/// The last: no element, or one followed by the rest, which it calls itself on.
+macro_rules! cons {
+    () => {
+        List::Nil
+    };
+    ($head:expr $(, $tail:expr)* $(,)?) => {
+        List::Cons($head, Box::new(cons![$($tail),*]))
+    };
+}
+
+/// # Building `cons!`, Step by Step
+/// The cons list of the `Box<T>` lesson, `Cons(1, Box::new(Cons(2, Box::new(Nil))))`, is a lot
+/// to write. `cons_one!` writes a list of one element; `cons!` one of any length, by taking the
+/// first element off and calling itself on the rest, until there is none: a macro can recurse,
+/// as a function can, up to `#![recursion_limit]`, 128 by default.
+fn building_cons_step_by_step() -> Result<()> {
+    Ok({
+        span!("building_cons_step_by_step");
+        header!("Building `cons!`, Step by Step");
+
+        let one = cons_one!(1);
+        println!("{one}"); // 1 -> Nil
+
+        // cons![1, 2, 3]
+        // => List::Cons(1, Box::new(cons![2, 3]))
+        // => List::Cons(1, Box::new(List::Cons(2, Box::new(cons![3]))))
+        // => List::Cons(1, Box::new(List::Cons(2, Box::new(List::Cons(3, Box::new(cons![]))))))
+        // => List::Cons(1, Box::new(List::Cons(2, Box::new(List::Cons(3, Box::new(List::Nil))))))
+        let three = cons![1, 2, 3];
+        println!("{three}"); // 1 -> 2 -> 3 -> Nil
+    })
+}
+
+/// Meant to run its statements when the scope ends, but `let _ =` doesn’t bind, so the guard is
+/// dropped, and they run, right away.
+macro_rules! defer_too_soon {
+    ($($body:tt)*) => {
+        let _ = $crate::intermediate_rust::utils::scope_guard::ScopeGuard::new(|| {
+            $($body)*
+        });
+    };
+}
+
+/// Runs its statements when the scope ends: `_guard` is a local, which lives until then.
+macro_rules! my_defer {
+    ($($body:tt)*) => {
+        let _guard = $crate::intermediate_rust::utils::scope_guard::ScopeGuard::new(|| {
+            $($body)*
+        });
+    };
+}
+
+/// # Building `defer!`, Step by Step
+/// `defer!`, which the lessons clean up with, is a `ScopeGuard` whose `drop` runs the closure
+/// it is given. `$($body:tt)*` takes any statements at all, as tokens, to paste into the
+/// closure. A macro’s expansion is a statement like any other, so where the guard is dropped
+/// is where a local would be: `let _ =` drops it at once, and `let _guard =` at the end of the
+/// scope, in the reverse order of the `my_defer!`s.
+fn building_defer_step_by_step() -> Result<()> {
+    Ok({
+        span!("building_defer_step_by_step");
+        header!("Building `defer!`, Step by Step");
+
+        {
+            defer_too_soon! { println!("too soon") }
+            println!("the end of the scope");
+        }
+        // too soon
+        // the end of the scope
+
+        {
+            my_defer! { println!("cleaned up second") }
+            my_defer! { println!("cleaned up first") }
+            println!("the end of the scope");
+        }
+        // the end of the scope
+        // cleaned up first
+        // cleaned up second
+    })
+}
+
+/// Evaluates `$e` with an `a` of its own in scope, or so it seems, and returns both.
+macro_rules! using_a {
+    ($e:expr) => {{
+        let a = 42;
+        (a, $e)
+    }};
+}
+
+/// Declares `$name` for the caller, who named it, so the caller can use it.
+macro_rules! declare {
+    ($name:ident = $value:expr) => {
+        let $name = $value;
+    };
+}
+
+/// # Hygiene
+/// Each name a macro uses belongs to where it was written: the `a` `using_a!` declares is the
+/// macro’s, and the `a` in `$e` the caller’s, so the two can’t be mixed up, as they would be by
+/// pasting text. To declare a name for the caller, the caller has to pass it in, as `declare!`
+/// takes `$name`. Paths aren’t hygienic that way: `cons!`’s `List` is whichever `List` is in
+/// scope where it is expanded, so a macro for use in other modules starts its paths with
+/// `$crate`, the crate the macro is in, as `my_defer!` does.
+fn hygiene() -> Result<()> {
+    Ok({
+        span!("hygiene");
+        header!("Hygiene");
+
+        let a = 4;
+        // using_a!(a / 2) => { let a = 42; (a, a / 2) }, where the last `a` is the caller’s
+        println!("using_a!(a / 2) = {:?}", using_a!(a / 2)); // using_a!(a / 2) = (42, 2)
+
+        declare!(b = a * 10);
+        println!("b = {b}"); // b = 40
+
+        // `_guard` is `my_defer!`’s own, so it doesn’t shadow the caller’s
+        let _guard = "the caller's";
+        my_defer! { println!("{_guard} `_guard` is untouched") }
+        // the caller's `_guard` is untouched
+    })
+}
+
+/// A `HashMap` of the `key => value` pairs, with room for all of them from the start. The
+/// `@`-arms are internal: `@count` counts the keys, as the length of an array with a `()` for
+/// each, and without evaluating them, as `@unit` throws each away.
+macro_rules! hashmap {
+    (@unit $key:expr) => {
+        ()
+    };
+    (@count $($key:expr),*) => {
+        <[()]>::len(&[$(hashmap!(@unit $key)),*])
+    };
+    ($($key:expr => $value:expr),* $(,)?) => {{
+        let mut map = ::std::collections::HashMap::with_capacity(hashmap!(@count $($key),*));
+        $(map.insert($key, $value);)*
+        map
+    }};
+}
+
+/// # A `hashmap!` Constructor
+/// `vec!` is a macro, as a function can’t take any number of arguments; `hashmap!` is the same
+/// for a `HashMap`, with `=>` between a key and its value. The internal arms come first: an
+/// arm whose fragment can’t begin with `@` is skipped, but one that starts parsing `@count` as
+/// an expression and fails stops the macro with an error.
+fn a_hashmap_constructor() -> Result<()> {
+    Ok({
+        span!("a_hashmap_constructor");
+        header!("A `hashmap!` Constructor");
+
+        // hashmap! { "one" => 1, "two" => 2 }
+        // => {
+        //     let mut map = HashMap::with_capacity(hashmap!(@count "one", "two"));
+        //     map.insert("one", 1);
+        //     map.insert("two", 2);
+        //     map
+        // }
+        // hashmap!(@count "one", "two") => <[()]>::len(&[hashmap!(@unit "one"), ...]) => 2
+        let numbers = hashmap! { "one" => 1, "two" => 2 };
+        println!("capacity >= {}", numbers.len()); // capacity >= 2
+        let sorted: BTreeMap<_, _> = numbers.into_iter().collect();
+        println!("{sorted:?}"); // {"one": 1, "two": 2}
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::RefCell;
+
+    #[test]
+    fn fragments_are_pasted_in_whole() {
+        assert_eq!(square!(1 + 2), 9);
+        assert_eq!(sum!(), 0);
+        assert_eq!(sum!(1, 2, 3,), 6);
+    }
+
+    #[test]
+    fn cons_builds_a_list_of_any_length() {
+        use crate::intermediate_rust::smart_pointers::List;
+
+        let empty: List<i32> = cons![];
+        assert_eq!(empty.len(), 0);
+        assert_eq!(
+            cons![1, 2, 3].to_string(),
+            List::from(vec![1, 2, 3]).to_string()
+        );
+        assert_eq!(cons!["a", "b",].len(), 2);
+    }
+
+    #[test]
+    fn deferred_statements_run_in_reverse_when_the_scope_ends() {
+        let log = RefCell::new(Vec::new());
+        {
+            defer_too_soon! { log.borrow_mut().push("too soon") }
+            my_defer! { log.borrow_mut().push("second") }
+            my_defer! { log.borrow_mut().push("first") }
+            log.borrow_mut().push("end");
+        }
+        assert_eq!(*log.borrow(), ["too soon", "end", "first", "second"]);
+    }
+
+    #[test]
+    fn macros_are_hygienic() {
+        let a = 4;
+        assert_eq!(using_a!(a / 2), (42, 2));
+        declare!(b = a + 1);
+        assert_eq!(b, 5);
+    }
+
+    #[test]
+    fn hashmap_counts_its_keys_without_evaluating_them() {
+        let evaluated = RefCell::new(0);
+        let key = |k| {
+            *evaluated.borrow_mut() += 1;
+            k
+        };
+        let map = hashmap! { key(1) => "one", key(2) => "two", key(1) => "uno" };
+        assert_eq!(*evaluated.borrow(), 3);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map[&1], "uno");
+    }
+}
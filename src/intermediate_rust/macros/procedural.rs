@@ -0,0 +1,105 @@
+//! # Procedural Macros
+//! A `macro_rules!` macro is patterns and templates; a procedural macro is a Rust function,
+//! which can do whatever a function can with the code it is given. It is compiled into a crate
+//! of its own, `crates/intermediate_rust_derive`, for the compiler to load and call as it
+//! compiles the crates that use it. There are three kinds:
+//! - `#[derive(Trait)]` is given an item, and adds code after it, usually an `impl`;
+//! - `#[attribute]` is given an item, and replaces it;
+//! - `macro!(...)`, which looks like a `macro_rules!` macro, is given what is in the brackets,
+//!   and replaces itself.
+//!
+//! This lesson is registered with one of each of the first and the last: its type is a
+//! `#[derive(Lesson)]`, and `thread-and-move` is a `lesson!`.
+
+use crate::intermediate_rust::lesson::{Demo, Lesson};
+use intermediate_rust_derive::{lesson, Lesson};
+use std::io::Result;
+
+/// The lesson, which `lessons.toml` registers by its type.
+#[derive(Lesson)]
+#[lesson(
+    id = "macros/procedural",
+    title = "Procedural macros: `#[derive(Lesson)]` and `lesson!`",
+    run = procedural
+)]
+#[lesson(
+    description = "Functions from code to code, which the compiler calls, with `syn` and `quote`",
+    difficulty = Advanced,
+    prerequisites = ["macros/declarative"],
+    tags = ["macros"]
+)]
+pub struct Procedural;
+
+pub fn procedural() -> Result<()> {
+    Ok({
+        deriving_a_trait()?;
+        a_function_like_macro()?;
+    })
+}
+
+/// # Deriving a Trait
+/// `#[derive(Lesson)]` is given the tokens of `struct Procedural;`, with the `#[lesson(...)]`
+/// attributes on it, which it declares as its own so that the compiler lets them be. It parses
+/// them with `syn`, and returns `impl Lesson for Procedural`, written with `quote!`:
+/// ```text
+/// impl ::ir_core::lesson::Lesson for Procedural {
+///     fn id(&self) -> &str {
+///         "macros/procedural"
+///     }
+///     ...
+///     fn info(&self) -> ::ir_core::lesson::LessonInfo {
+///         ::ir_core::lesson::LessonInfo {
+///             description: "Functions from code to code, ...",
+///             difficulty: ::ir_core::lesson::Difficulty::Advanced,
+///             prerequisites: &["macros/declarative"],
+///             tags: &["macros"],
+///             source: include_str!("/.../src/intermediate_rust/macros/procedural.rs"),
+///             ..::ir_core::lesson::LessonInfo::default()
+///         }
+///     }
+/// }
+/// ```
+/// Its paths start with `::`, a crate’s name, as a procedural macro has no `$crate`.
+fn deriving_a_trait() -> Result<()> {
+    Ok({
+        span!("deriving_a_trait");
+        header!("Deriving a Trait");
+
+        let info = Procedural.info();
+        println!("{} is {}", Procedural.id(), info.difficulty); // macros/procedural is advanced
+        println!("after {:?}", info.prerequisites); // after ["macros/declarative"]
+    })
+}
+
+/// # A Function-like Macro
+/// `lesson!` writes a `Demo`, from fewer words than a `Demo` is written with. Being a function,
+/// it does what `macro_rules!` can’t: it asks the compiler which file it is used in, for the
+/// lesson’s `source`, and checks the ids, returning an error for the compiler to report where
+/// the id is, as a `compile_error!`:
+/// ```compile_fail
+/// intermediate_rust_derive::lesson! {
+///     id: "Macros::Procedural",
+///     title: "Procedural macros",
+///     run: || Ok(()),
+/// }
+/// ```
+/// `Macros::Procedural` isn't a lesson's id: its topic and its name, in kebab-case, separated
+/// by a `/`
+fn a_function_like_macro() -> Result<()> {
+    Ok({
+        span!("a_function_like_macro");
+        header!("A Function-like Macro");
+
+        let demo: Demo = lesson! {
+            id: "macros/inner",
+            title: "A lesson in a lesson",
+            run: || Ok(println!("the inner lesson runs")),
+            tags: ["macros"],
+        };
+        println!("{}: {}", demo.id, demo.title); // macros/inner: A lesson in a lesson
+        (demo.run)()?; // the inner lesson runs
+        let first = demo.info.source.lines().next().unwrap_or_default();
+        println!("its source starts with {first:?}");
+        // its source starts with "//! # Procedural Macros"
+    })
+}
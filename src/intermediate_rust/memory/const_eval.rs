@@ -0,0 +1,219 @@
+//! # Compile-Time Evaluation: `const fn`, `const` and `static`
+//! Some values are known before the program runs: a size, a limit, a table of precomputed
+//! results. Rust computes them while it compiles, with an interpreter for a subset of the
+//! language, the one `const fn`s are written in, and puts the results in the binary, so that
+//! the program finds them ready, at no cost.
+//!
+//! Where a value is computed depends on where it is used, not on the function: a `const fn`
+//! called in a `const`, a `static`, an array length or a `const { ... }` block runs while the
+//! compiler runs; called anywhere else, it is an ordinary function.
+
+use std::{
+    io::Result,
+    mem::{align_of, size_of},
+    ptr,
+};
+
+pub fn const_eval() -> Result<()> {
+    Ok({
+        const_fn()?;
+        const_items_and_statics()?;
+        const_blocks()?;
+        a_lookup_table_built_while_compiling()?;
+        the_limits_of_const_evaluation()?;
+    })
+}
+
+/// The greatest common divisor of `a` and `b`, by Euclid’s algorithm.
+const fn gcd(mut a: u64, mut b: u64) -> u64 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Computed while compiling: the binary only has the `12`.
+const GCD: u64 = gcd(84, 36);
+
+/// # `const fn`
+/// A `const fn` may be called where a constant is expected, and the compiler runs it there. Its
+/// body is limited to what the compiler can run: arithmetic, `if`, `match`, `loop` and `while`,
+/// and calls to other `const fn`s.
+fn const_fn() -> Result<()> {
+    Ok({
+        span!("const_fn");
+        header!("`const fn`");
+
+        println!("GCD = {GCD}"); // GCD = 12
+
+        // `black_box` hides the value from the optimizer, so `gcd` runs when the program does
+        let a = std::hint::black_box(1071);
+        println!("at runtime, gcd({a}, 462) = {}", gcd(a, 462)); // at runtime, gcd(1071, 462) = 21
+    })
+}
+
+/// A `const` is a value, which each use copies in anew.
+const EMPTY: Vec<u32> = Vec::new();
+/// A `static` is a place, which every use shares.
+static GREETING: &str = "hello";
+
+/// # `const` Items and `static`s
+/// A `const` has no address of its own: each use of it is as if the value were written there,
+/// so `EMPTY` is a new, empty `Vec` each time it is named, and pushing onto one doesn’t change
+/// the next. A `static` is one place in memory, for the whole program, which every use refers
+/// to; it is what to use for a value whose address matters, or which is too big to copy around.
+/// Both are evaluated while compiling.
+fn const_items_and_statics() -> Result<()> {
+    Ok({
+        span!("const_items_and_statics");
+        header!("`const` Items and `static`s");
+
+        let mut numbers = EMPTY;
+        numbers.push(1);
+        println!("numbers = {numbers:?}, EMPTY = {EMPTY:?}"); // numbers = [1], EMPTY = []
+
+        let (first, second): (&&str, &&str) = (&GREETING, &GREETING);
+        println!("one GREETING: {}", ptr::eq(first, second)); // one GREETING: true
+    })
+}
+
+/// # `const` Blocks
+/// `const { ... }` evaluates an expression while compiling, where it is. It is how to repeat a
+/// value that isn’t `Copy` in an array, `[const { Vec::new() }; 3]`, as `[Vec::new(); 3]` won’t
+/// compile, and how to make sure a `const fn` is run by the compiler rather than the program.
+fn const_blocks() -> Result<()> {
+    Ok({
+        span!("const_blocks");
+        header!("`const` Blocks");
+
+        let mut buckets: [Vec<&str>; 3] = [const { Vec::new() }; 3];
+        buckets[1].push("one");
+        println!("{buckets:?}"); // [[], ["one"], []]
+
+        let lcm = const { 84 * 36 / gcd(84, 36) };
+        println!("lcm(84, 36) = {lcm}"); // lcm(84, 36) = 252
+    })
+}
+
+/// The CRC-32 polynomial, reversed, as the table is for the least significant bit first.
+const POLYNOMIAL: u32 = 0xedb8_8320;
+
+/// The CRC-32 of each byte, for `crc32` to look up rather than compute a bit at a time. A `for`
+/// loop calls `Iterator::next`, which isn’t a `const fn`, so the loops are `while`s.
+const fn crc32_table() -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut byte = 0;
+    while byte < 256 {
+        let mut crc = byte as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ POLYNOMIAL
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[byte] = crc;
+        byte += 1;
+    }
+    table
+}
+
+/// Built while compiling, 1 KiB of the binary.
+static CRC32_TABLE: [u32; 256] = crc32_table();
+
+/// The CRC-32 of `bytes`, as zip and PNG compute it.
+fn crc32(bytes: &[u8]) -> u32 {
+    !bytes.iter().fold(!0, |crc, &byte| {
+        CRC32_TABLE[usize::from(crc as u8 ^ byte)] ^ (crc >> 8)
+    })
+}
+
+/// # A Lookup Table Built While Compiling
+/// A CRC-32 takes eight steps for each bit of a byte, or one look-up in a table of 256 entries.
+/// The table is the same for every run, so `crc32_table` computes it while compiling, and the
+/// program starts with it already there, in a `static`.
+fn a_lookup_table_built_while_compiling() -> Result<()> {
+    Ok({
+        span!("a_lookup_table_built_while_compiling");
+        header!("A Lookup Table Built While Compiling");
+
+        println!("CRC32_TABLE[1] = {:#010x}", CRC32_TABLE[1]); // CRC32_TABLE[1] = 0x77073096
+        println!("crc32(b\"123456789\") = {:#010x}", crc32(b"123456789"));
+        // crc32(b"123456789") = 0xcbf43926
+    })
+}
+
+// Static assertions: each is checked while compiling, and the build fails if one is false.
+const _: () = assert!(size_of::<Option<Box<u8>>>() == size_of::<usize>());
+const _: () = assert!(align_of::<u64>() <= size_of::<u64>());
+const _: () = assert!(GCD == 12);
+
+/// # The Limits of Const Evaluation
+/// The compiler’s interpreter can’t do what needs the machine the program will run on: it
+/// can’t read a file or the clock, allocate on the heap, or call a function that isn’t a
+/// `const fn`, which includes trait methods, so no `for` loops and no `==` on a `String`:
+/// ```compile_fail
+/// const ARGS: usize = std::env::args().count();
+/// ```
+/// cannot call non-const method `Args::count` in constants
+///
+/// A panic while evaluating is an error, which makes `const _: () = assert!(...)` an assertion
+/// the compiler checks, such as that `Option<Box<u8>>` is the size of a pointer, above:
+/// ```compile_fail
+/// const _: () = assert!(std::mem::size_of::<u64>() == 4, "a u64 is 4 bytes");
+/// ```
+/// evaluation panicked: a u64 is 4 bytes
+///
+/// So is an overflow, `const BYTE: u8 = 255 + 1;`, where the program would have panicked, or
+/// wrapped, when it ran.
+fn the_limits_of_const_evaluation() -> Result<()> {
+    Ok({
+        span!("the_limits_of_const_evaluation");
+        header!("The Limits of Const Evaluation");
+
+        const PAGE: usize = 4096;
+        const PAGES: usize = 3;
+        let buffer = [0u8; PAGE * PAGES]; // an array length is a constant too
+        println!("buffer is {} bytes", buffer.len()); // buffer is 12288 bytes
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd_runs_the_same_while_compiling_and_at_runtime() {
+        const AT_COMPILE_TIME: u64 = gcd(1071, 462);
+        assert_eq!(AT_COMPILE_TIME, 21);
+        assert_eq!(gcd(std::hint::black_box(1071), 462), AT_COMPILE_TIME);
+        assert_eq!(gcd(7, 0), 7);
+    }
+
+    #[test]
+    fn the_table_matches_a_crc_computed_bit_by_bit() {
+        let bitwise = |bytes: &[u8]| {
+            let mut crc = !0u32;
+            for &byte in bytes {
+                crc ^= u32::from(byte);
+                for _ in 0..8 {
+                    crc = (crc >> 1) ^ (POLYNOMIAL & (crc & 1).wrapping_neg());
+                }
+            }
+            !crc
+        };
+        for text in [&b""[..], b"a", b"123456789", b"The quick brown fox"] {
+            assert_eq!(crc32(text), bitwise(text));
+        }
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn each_use_of_a_const_is_a_new_value() {
+        let mut numbers = EMPTY;
+        numbers.push(1);
+        assert!(EMPTY.is_empty());
+    }
+}
@@ -0,0 +1,200 @@
+//! # Dynamically Sized Types and the `Sized` Trait
+//! Rust needs to know certain details about its types, such as how much space to allocate for a
+//! value of a particular type. This leaves one corner of its type system a little confusing at
+//! first: the concept of _dynamically sized types_. Sometimes referred to as _DSTs_ or _unsized
+//! types_, these types let us write code using values whose size we can know only at runtime.
+//!
+//! `str`, slices `[T]`, and trait objects `dyn Trait` are the built-in DSTs. The golden rule is
+//! that we must always put values of dynamically sized types behind a pointer of some kind:
+//! `&str`, `Box<[T]>`, `Rc<dyn Trait>`, and so on. That pointer is a fat pointer, carrying the
+//! missing size information (a length, or a vtable) next to the address.
+
+use std::{
+    fmt::Debug,
+    io::Result,
+    mem::{size_of, size_of_val},
+};
+
+pub fn dst() -> Result<()> {
+    Ok({
+        str_cannot_live_on_the_stack()?;
+        generic_functions_and_question_mark_sized()?;
+        boxes_as_dst_containers()?;
+        a_custom_dst()?;
+    })
+}
+
+/// # `str` Can’t Live on the Stack
+/// A local variable needs a fixed-size stack slot, chosen at compile time:
+/// ```compile_fail
+/// let s1: str = "Hello there!";
+/// let s2: str = "How's it going?";
+/// ```
+/// the size for values of type `str` cannot be known at compilation time
+///
+/// `s1` needs 12 bytes and `s2` needs 15, yet both have the type `str`. All values of one type
+/// must use the same amount of memory, so no stack slot can fit “a `str`”. Instead we put the
+/// bytes somewhere else (the binary, or the heap) and keep a fixed-size `&str` on the stack.
+fn str_cannot_live_on_the_stack() -> Result<()> {
+    Ok({
+        println!("str Can’t Live on the Stack");
+
+        let s1: &str = "Hello there!";
+        let s2: &str = "How's it going?";
+
+        // the pointers have one size, what they point at does not
+        println!(
+            "size of s1 = {}, size_of_val(s1) = {}",
+            size_of::<&str>(),
+            size_of_val(s1)
+        );
+        // size of s1 = 16, size_of_val(s1) = 12
+        println!(
+            "size of s2 = {}, size_of_val(s2) = {}",
+            size_of::<&str>(),
+            size_of_val(s2)
+        );
+        // size of s2 = 16, size_of_val(s2) = 15
+    })
+}
+
+/// # Generic Functions and `?Sized`
+/// To work with DSTs, Rust provides the `Sized` trait to determine whether or not a type’s size is
+/// known at compile time. In addition, Rust implicitly adds a bound on `Sized` to every generic
+/// function. That is, a generic function definition like this:
+/// ```
+/// fn generic<T>(t: T) {}
+/// ```
+/// is actually treated as though we had written this:
+/// ```
+/// fn generic<T: Sized>(t: T) {}
+/// ```
+/// By default, generic functions will work only on types that have a known size at compile time.
+/// `?Sized` means “`T` may or may not be `Sized`”, and lifts the restriction. Because `T` might
+/// not be sized, we can only take it behind a pointer: `&T` instead of `T`.
+fn describe<T: Debug + ?Sized>(t: &T) -> String {
+    format!(
+        "{:?} ({} bytes behind a {}-byte reference)",
+        t,
+        size_of_val(t),
+        size_of::<&T>()
+    )
+}
+fn generic_functions_and_question_mark_sized() -> Result<()> {
+    Ok({
+        span!("generic_functions_and_question_mark_sized");
+        header!("Generic Functions and ?Sized");
+
+        println!("{}", describe(&42u8)); //           42 (1 bytes behind a 8-byte reference)
+        println!("{}", describe("four")); //          "four" (4 bytes behind a 16-byte reference)
+        println!("{}", describe(&[1u16, 2, 3][..])); // [1, 2, 3] (6 bytes behind a 16-byte reference)
+
+        // without `?Sized`, `describe("four")` would fail:
+        // the size for values of type `str` cannot be known at compilation time
+    })
+}
+
+/// # Boxes as DST Containers
+/// A `Box<T>` can own an unsized value just as a reference can borrow one, which makes
+/// `Box<[T]>`, `Box<str>`, and `Box<dyn Trait>` owned, heap-allocated DSTs:
+/// - `Box<[T]>` is like a `Vec<T>` that can’t grow: a pointer and a length, but no capacity.
+/// - `Box<dyn Trait>` is a pointer to the value and a pointer to the _vtable_ of the concrete
+///   type, which holds its size, alignment, `drop` function, and trait methods.
+trait Shape {
+    fn area(&self) -> f64;
+}
+struct Square(f64);
+impl Shape for Square {
+    fn area(&self) -> f64 {
+        self.0 * self.0
+    }
+}
+struct Circle(f64);
+impl Shape for Circle {
+    fn area(&self) -> f64 {
+        std::f64::consts::PI * self.0 * self.0
+    }
+}
+fn boxes_as_dst_containers() -> Result<()> {
+    Ok({
+        span!("boxes_as_dst_containers");
+        header!("Boxes as DST Containers");
+
+        let boxed_slice: Box<[i32]> = vec![1, 2, 3].into_boxed_slice();
+        println!(
+            "Box<[i32]> = {:?}, {} bytes on the stack, {} on the heap",
+            boxed_slice,
+            size_of::<Box<[i32]>>(),
+            size_of_val(&*boxed_slice)
+        ); // Box<[i32]> = [1, 2, 3], 16 bytes on the stack, 12 on the heap
+        println!(
+            "Vec<i32> would need {} bytes on the stack",
+            size_of::<Vec<i32>>()
+        );
+
+        // values of different sizes, one element type
+        let shapes: Vec<Box<dyn Shape>> = vec![Box::new(Square(2.0)), Box::new(Circle(1.0))];
+        for shape in &shapes {
+            println!(
+                "area = {:.2}, value size = {} bytes",
+                shape.area(),
+                size_of_val(&**shape)
+            );
+        }
+        println!("size of Box<dyn Shape> = {}", size_of::<Box<dyn Shape>>()); // size of Box<dyn Shape> = 16
+        println!("size of Box<Square>    = {}", size_of::<Box<Square>>()); //    size of Box<Square>    = 8
+    })
+}
+
+/// # A Custom DST
+/// A struct becomes dynamically sized when its _last_ field is. `Packet<[u8]>` below has a fixed
+/// header followed by a payload of any length, stored inline in the same allocation.
+///
+/// The simplest safe way to make one is to start from a sized `Packet<[u8; N]>` and let an
+/// _unsizing coercion_ forget `N`, moving it from the type into the fat pointer’s length. After
+/// that, packets with different payload lengths share one type.
+#[derive(Debug)]
+struct Packet<P: ?Sized> {
+    id: u32,
+    payload: P,
+}
+impl Packet<[u8]> {
+    pub fn payload_len(&self) -> usize {
+        self.payload.len()
+    }
+}
+fn a_custom_dst() -> Result<()> {
+    Ok({
+        span!("a_custom_dst");
+        header!("A Custom DST");
+
+        let small: Box<Packet<[u8]>> = Box::new(Packet {
+            id: 1,
+            payload: [0xAA, 0xBB],
+        });
+        let large: Box<Packet<[u8]>> = Box::new(Packet {
+            id: 2,
+            payload: [0x11; 10],
+        });
+
+        for packet in [&small, &large] {
+            println!(
+                "id = {}, payload_len = {}, size_of_val = {}, payload = {:?}",
+                packet.id,
+                packet.payload_len(),
+                size_of_val(&**packet),
+                &packet.payload
+            );
+        }
+        // id = 1, payload_len = 2, size_of_val = 8, payload = [170, 187]
+        // id = 2, payload_len = 10, size_of_val = 16, payload = [17, 17, 17, 17, 17, 17, 17, 17, 17, 17]
+
+        println!(
+            "size of &Packet<[u8]> = {}, size of &Packet<[u8; 2]> = {}",
+            size_of::<&Packet<[u8]>>(),
+            size_of::<&Packet<[u8; 2]>>()
+        ); // size of &Packet<[u8]> = 16, size of &Packet<[u8; 2]> = 8
+    })
+    // The `size_of_val` numbers include padding: the `u32` header makes the struct 4-byte
+    // aligned, so a 2-byte payload rounds up to 8 bytes and a 10-byte one to 16.
+}
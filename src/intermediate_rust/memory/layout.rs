@@ -0,0 +1,137 @@
+//! # Type Layout: Size, Alignment, and `repr`
+//! Every type has a _size_, the number of bytes a value of it occupies, and an _alignment_,
+//! a power of two that the value’s address must be a multiple of. A `u32` has an alignment of
+//! 4, so it may live at address 8 but not at address 6. A struct’s alignment is the largest
+//! alignment of its fields, and its size is always a multiple of its alignment, so that the
+//! elements of an array of it are all correctly aligned.
+//!
+//! To satisfy the alignment of every field, the compiler inserts unused _padding_ bytes. With
+//! the default representation, `repr(Rust)`, it is free to reorder the fields to minimize that
+//! padding. `#[repr(C)]` forbids reordering and lays the fields out in declaration order, the
+//! way C does, which is what you need when the layout must match another language or a file
+//! format.
+
+use super::super::smart_pointers::{box_t, rc_t, ref_cell_t, traits::deref::MyBox};
+use std::{
+    io::Result,
+    mem::{align_of, offset_of, size_of},
+};
+
+macro_rules! print_layout {
+    ($t:ty) => {
+        println!(
+            "{:<32} size = {:2}, align = {}",
+            stringify!($t),
+            size_of::<$t>(),
+            align_of::<$t>()
+        )
+    };
+}
+
+pub fn layout() -> Result<()> {
+    Ok({
+        primitive_sizes_and_alignments()?;
+        padding_and_field_reordering()?;
+        the_layout_of_this_crates_types()?;
+    })
+}
+
+fn primitive_sizes_and_alignments() -> Result<()> {
+    Ok({
+        span!("primitive_sizes_and_alignments");
+        header!("Primitive Sizes and Alignments");
+
+        print_layout!(u8); //   size =  1, align = 1
+        print_layout!(u16); //  size =  2, align = 2
+        print_layout!(u32); //  size =  4, align = 4
+        print_layout!(u64); //  size =  8, align = 8
+        print_layout!(u128); // size = 16, align = 16 (8 on some targets)
+        print_layout!(usize); // size = 8, align = 8, like any pointer
+        print_layout!([u16; 3]); // size = 6, align = 2: arrays have no padding between elements
+        print_layout!((u8, u32)); // size = 8, align = 4: tuples are laid out like structs
+        print_layout!(()); // size = 0, align = 1: zero-sized types take no space at all
+    })
+}
+
+/// # Padding and Field Reordering
+/// The three structs below hold the same fields. Declared in this order, a C compiler would
+/// have to pad after `a` (to align `b`) and after `c` (to round the size up to a multiple of 4).
+/// The default representation sorts the fields by alignment and needs no inner padding.
+#[allow(dead_code)]
+struct RustRepr {
+    a: u8,
+    b: u32,
+    c: u16,
+}
+#[allow(dead_code)]
+#[repr(C)]
+struct DeclarationOrder {
+    a: u8,  // offset 0, then 3 bytes of padding
+    b: u32, // offset 4
+    c: u16, // offset 8, then 2 bytes of padding
+}
+/// With `repr(C)`, ordering the fields from the largest alignment down avoids the padding by hand.
+#[allow(dead_code)]
+#[repr(C)]
+struct SortedByHand {
+    b: u32,
+    c: u16,
+    a: u8, // offset 6, then 1 byte of padding to reach a multiple of 4
+}
+fn padding_and_field_reordering() -> Result<()> {
+    Ok({
+        span!("padding_and_field_reordering");
+        header!("Padding and Field Reordering");
+
+        print_layout!(RustRepr); //         size =  8, align = 4
+        print_layout!(DeclarationOrder); // size = 12, align = 4
+        print_layout!(SortedByHand); //     size =  8, align = 4
+
+        println!(
+            "RustRepr         offsets: a = {}, b = {}, c = {}",
+            offset_of!(RustRepr, a),
+            offset_of!(RustRepr, b),
+            offset_of!(RustRepr, c)
+        ); // chosen by the compiler, for example a = 6, b = 0, c = 4
+        println!(
+            "DeclarationOrder offsets: a = {}, b = {}, c = {}",
+            offset_of!(DeclarationOrder, a),
+            offset_of!(DeclarationOrder, b),
+            offset_of!(DeclarationOrder, c)
+        ); // DeclarationOrder offsets: a = 0, b = 4, c = 8
+    })
+    // The offsets of a `repr(Rust)` struct are not guaranteed and can change between compiler
+    // versions, so never rely on them. Reach for `repr(C)` when the layout is part of a contract.
+}
+
+/// # The Layout of This Crate’s Types
+/// Now we can check the claims from the smart pointer chapter against the compiler:
+/// - The `Box` cons list is “the size of a `T` plus the size of a box’s pointer data”, rounded
+///   up to the alignment of the pointer. Only the first node lives on the stack; each `Box` is a
+///   single pointer to the next node on the heap.
+/// - The `Rc` cons list is the same size: an `Rc<T>` is also a single pointer, and the strong
+///   and weak counts live on the heap next to the value, not in the pointer.
+/// - `RefCell<T>` is a `T` plus a borrow counter, so wrapping a value in a `RefCell` makes it
+///   bigger where it is stored.
+/// - `MyBox<T>` is a tuple struct with a single field, so it has exactly the layout of `T`: the
+///   deref lesson’s “box” never left the stack.
+fn the_layout_of_this_crates_types() -> Result<()> {
+    Ok({
+        span!("the_layout_of_this_crates_types");
+        header!("The Layout of This Crate’s Types");
+
+        print_layout!(box_t::List<i32>); //                size = 16, align = 8
+        print_layout!(box_t::List<u64>); //                size = 16, align = 8
+        print_layout!(Box<box_t::List<i32>>); //           size =  8, align = 8
+        print_layout!(rc_t::List<i32>); //                 size = 16, align = 8
+        print_layout!(ref_cell_t::List<i32>); //           size = 16, align = 8
+        print_layout!(ref_cell_t::CyclicList<i32>); //     size = 24, align = 8
+        print_layout!(ref_cell_t::TreeNode<i32>); //       size = 152, align = 8
+        print_layout!(std::cell::RefCell<i32>); //         size = 16, align = 8
+        print_layout!(MyBox<i32>); //                      size =  4, align = 4
+        print_layout!(MyBox<String>); //                   size = 24, align = 8
+    })
+    // `TreeNode<i32>` is the `i32` value and two `DebugRefCell`s. Each is a `RefCell`, of a `Vec`
+    // (24 bytes plus the borrow flag) or of a `Weak` (8 bytes plus the flag), and 48 bytes to
+    // remember its borrows by: whether to, a `RefCell<Vec<..>>` of them, and a counter.
+}
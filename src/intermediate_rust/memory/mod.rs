@@ -0,0 +1,14 @@
+//! # Memory
+//! The smart pointer chapter keeps making claims about memory: a box is “just a pointer”, a
+//! recursive type needs “the size of a `T` plus the size of a box’s pointer data”, and so on.
+//! This chapter makes those claims observable. With `std::mem::size_of` and friends we can ask
+//! the compiler how big a type is, and by printing addresses we can see where values live.
+//!
+//! The numbers printed here are for a 64-bit target, where a pointer (a _word_) is 8 bytes.
+
+pub(super) mod const_eval;
+pub(super) mod dst;
+pub(super) mod layout;
+pub(super) mod niche;
+pub(super) mod replace;
+pub(super) mod slices;
@@ -0,0 +1,125 @@
+//! # Enum Layout and the Niche Optimization
+//! A value of an enum is one of its variants, so it needs room for the largest variant plus a
+//! _discriminant_ (a tag) recording which variant it is. Naively, `Option<u32>` is therefore a
+//! `u32` plus a tag, padded to 8 bytes.
+//!
+//! But some types have bit patterns that can never be valid values: a reference or a `Box<T>`
+//! is never null, a `NonZeroU32` is never zero, a `bool` is only ever `0` or `1`. Such unused
+//! bit patterns are called _niches_. When the payload has a niche, the compiler can store the
+//! tag inside it: `None` is simply represented by the impossible value, and no separate tag is
+//! needed. This is why `Option<Box<T>>` is exactly as big as `Box<T>`, and why using `Option`
+//! for “maybe a pointer” costs nothing compared to a nullable pointer in C.
+
+use std::{io::Result, mem::size_of, num::NonZeroU32, rc::Rc};
+
+macro_rules! print_size {
+    ($t:ty) => {
+        println!("size of {:<34} = {}", stringify!($t), size_of::<$t>())
+    };
+}
+
+pub fn niche() -> Result<()> {
+    Ok({
+        enums_without_a_niche()?;
+        the_null_pointer_niche()?;
+        niches_in_other_types()?;
+        nested_enums()?;
+    })
+}
+
+fn enums_without_a_niche() -> Result<()> {
+    Ok({
+        span!("enums_without_a_niche");
+        header!("Enums Without a Niche");
+
+        // every bit pattern of a `u32` is a valid `u32`, so the tag needs its own space
+        print_size!(u32); // 4
+        print_size!(Option<u32>); // 8
+        print_size!(Option<u64>); // 16
+    })
+    // `Option<u64>` doubles in size: the tag needs just one byte, but padding rounds the
+    // enum up to a multiple of the `u64`’s 8-byte alignment.
+}
+
+/// # The Null Pointer Niche
+/// `&T`, `&mut T`, `Box<T>`, `Rc<T>`, `Arc<T>`, and `NonNull<T>` can never be null, so `None`
+/// is stored as the null address. The cons list from `box_t.rs` benefits too: its `Nil` variant
+/// has no payload, so it can be encoded as a null `Box` in the `Cons` layout.
+#[allow(dead_code)]
+enum List<T> {
+    Cons(T, Box<List<T>>),
+    Nil,
+}
+fn the_null_pointer_niche() -> Result<()> {
+    Ok({
+        span!("the_null_pointer_niche");
+        header!("The Null Pointer Niche");
+
+        print_size!(&u64); // 8
+        print_size!(Option<&u64>); // 8
+        print_size!(Box<u64>); // 8
+        print_size!(Option<Box<u64>>); // 8
+        print_size!(Option<Rc<u64>>); // 8
+
+        // a raw pointer may be null, so it has no niche to offer
+        print_size!(Option<*const u64>); // 16
+
+        // fat pointers keep their niche in the data pointer, the length is left alone
+        print_size!(Option<&[u64]>); // 16
+        print_size!(Option<Box<str>>); // 16
+
+        // `Cons(i32, Box)` is 16 bytes (4 + padding + 8), and `Nil` hides in the Box’s niche
+        print_size!(List<i32>); // 16
+    })
+}
+
+/// # Niches in Other Types
+/// The standard library offers `NonZero` integers precisely to provide a niche: an ID that is
+/// never zero can be stored in an `Option` for free. `bool` and `char` have niches too, as do
+/// enums that don’t use all the values of their tag.
+#[allow(dead_code)]
+enum Direction {
+    North,
+    East,
+    South,
+    West,
+}
+fn niches_in_other_types() -> Result<()> {
+    Ok({
+        span!("niches_in_other_types");
+        header!("Niches in Other Types");
+
+        print_size!(Option<NonZeroU32>); // 4
+        print_size!(Option<bool>); // 1
+        print_size!(Option<char>); // 4
+        print_size!(Option<Direction>); // 1
+
+        // `None` is the one bit pattern `NonZeroU32` can’t use
+        let id = NonZeroU32::new(7);
+        let no_id = NonZeroU32::new(0);
+        println!(
+            "NonZeroU32::new(7) = {:?}, NonZeroU32::new(0) = {:?}",
+            id, no_id
+        );
+    })
+}
+
+/// # Nested Enums
+/// A niche can hold more than one extra value: `bool` uses 2 of its 256 byte values, leaving
+/// 254 to encode outer variants. Nesting keeps eating into the niche until it runs out.
+fn nested_enums() -> Result<()> {
+    Ok({
+        span!("nested_enums");
+        header!("Nested Enums");
+
+        print_size!(Option<Option<bool>>); // 1
+        print_size!(Option<Option<&u64>>); // 16
+        print_size!(std::result::Result<Box<u64>, ()>); // 8
+        print_size!(Option<Option<Option<Direction>>>); // 1
+    })
+    // A pointer has exactly one forbidden value, null, so after `None` takes it there is no
+    // room left for the outer `None`, and `Option<Option<&u64>>` falls back to a separate tag.
+}
+// None of these sizes are guaranteed by the language except the documented ones, such as
+// `Option<Box<T>>`, `Option<&T>`, and `Option<NonZeroU32>` having the size of their payload.
+// Check with `size_of` instead of assuming.
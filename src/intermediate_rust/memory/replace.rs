@@ -0,0 +1,176 @@
+//! # Moving Out of a `&mut` with `mem::replace`, `mem::take`, and `mem::swap`
+//! A `&mut T` lets us change a value, but not move it out: the owner still expects to find a
+//! valid `T` behind the reference when the borrow ends. Moving out would leave a hole there.
+//!
+//! The way around it is to never leave a hole. `mem::replace(dest, src)` moves `src` into the
+//! place and hands back what was there; `mem::take(dest)` does the same with `T::default()`;
+//! `Option::take` is `mem::take` for an `Option`, leaving `None`; and `mem::swap(a, b)` trades
+//! two values. None of them clone: they only move the bytes of the values, which for a `Vec`
+//! or a `String` is the pointer, the length and the capacity, not the heap data.
+//!
+//! The linked lists in the smart pointer chapter (`push_front`, `pop_front`) rely on this.
+
+use std::{io::Result, mem};
+
+pub fn replace() -> Result<()> {
+    Ok({
+        moving_a_field_out_from_behind_a_reference()?;
+        unlinking_a_node_with_option_take()?;
+        a_builder_that_can_be_reused()?;
+        swapping_without_cloning()?;
+    })
+}
+
+#[derive(Debug, Default)]
+struct Player {
+    name: String,
+    inventory: Vec<&'static str>,
+}
+
+fn moving_a_field_out_from_behind_a_reference() -> Result<()> {
+    Ok({
+        fn retire(player: &mut Player) -> (String, Vec<&'static str>) {
+            // (player.name, player.inventory) // cannot move out of `player.name` which is behind a mutable reference
+            let name = mem::replace(&mut player.name, String::from("(retired)"));
+            let inventory = mem::take(&mut player.inventory);
+            (name, inventory)
+        }
+
+        let mut player = Player {
+            name: String::from("Ferris"),
+            inventory: vec!["crab claw", "shell"],
+        };
+        let (name, inventory) = retire(&mut player);
+
+        assert_eq!((name.as_str(), inventory.len()), ("Ferris", 2));
+        assert_eq!(player.name, "(retired)");
+        assert!(player.inventory.is_empty());
+        println!("retired {name} with {inventory:?}, left behind {player:?}");
+    })
+    // retired Ferris with ["crab claw", "shell"], left behind Player { name: "(retired)", inventory: [] }
+}
+
+type Link = Option<Box<Node>>;
+struct Node {
+    value: i32,
+    next: Link,
+}
+
+/// Removes the first node holding `value`.
+///
+/// `link.take()` moves the node out and leaves `None` behind, so for a moment the list simply
+/// ends there. Then the node’s own `next` is moved into that place, and the node is dropped.
+fn remove(mut link: &mut Link, value: i32) -> bool {
+    loop {
+        match link {
+            None => return false,
+            Some(node) if node.value == value => {
+                let removed = link.take().unwrap();
+                *link = removed.next;
+                return true;
+            }
+            Some(node) => link = &mut node.next,
+        }
+    }
+}
+
+fn values(mut link: &Link) -> Vec<i32> {
+    let mut values = Vec::new();
+    while let Some(node) = link {
+        values.push(node.value);
+        link = &node.next;
+    }
+    values
+}
+
+fn unlinking_a_node_with_option_take() -> Result<()> {
+    Ok({
+        let mut list: Link = None;
+        for value in [3, 2, 1] {
+            list = Some(Box::new(Node { value, next: list }));
+        }
+        assert_eq!(values(&list), [1, 2, 3]);
+
+        assert!(remove(&mut list, 2));
+        assert_eq!(values(&list), [1, 3]);
+        assert!(remove(&mut list, 1)); // the head is just another link
+        assert!(!remove(&mut list, 42));
+        assert_eq!(values(&list), [3]);
+        println!("after removing 2 and 1: {:?}", values(&list));
+    })
+    // after removing 2 and 1: [3]
+}
+
+#[derive(Debug)]
+struct Request {
+    path: String,
+    headers: Vec<(String, String)>,
+}
+#[derive(Default)]
+struct RequestBuilder {
+    path: String,
+    headers: Vec<(String, String)>,
+}
+impl RequestBuilder {
+    fn path(&mut self, path: &str) -> &mut Self {
+        self.path = path.to_string();
+        self
+    }
+
+    fn header(&mut self, name: &str, value: &str) -> &mut Self {
+        self.headers.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    /// Takes the collected fields instead of cloning them, which leaves the builder empty and
+    /// ready for the next request. A `build(self)` would move the fields too, but would use up
+    /// the builder.
+    fn build(&mut self) -> Request {
+        Request {
+            path: mem::take(&mut self.path),
+            headers: mem::take(&mut self.headers),
+        }
+    }
+}
+
+fn a_builder_that_can_be_reused() -> Result<()> {
+    Ok({
+        let mut builder = RequestBuilder::default();
+
+        let first = builder
+            .path("/hello")
+            .header("Accept", "text/plain")
+            .build();
+        assert_eq!((first.path.as_str(), first.headers.len()), ("/hello", 1));
+        assert!(builder.path.is_empty() && builder.headers.is_empty());
+
+        let second = builder.path("/bye").build();
+        assert_eq!((second.path.as_str(), second.headers.len()), ("/bye", 0));
+        println!("{first:?}\n{second:?}");
+    })
+    // Request { path: "/hello", headers: [("Accept", "text/plain")] }
+    // Request { path: "/bye", headers: [] }
+}
+
+fn swapping_without_cloning() -> Result<()> {
+    Ok({
+        let mut front = vec![1, 2, 3];
+        let mut back = vec![4, 5];
+        let (front_data, back_data) = (front.as_ptr(), back.as_ptr());
+
+        mem::swap(&mut front, &mut back);
+
+        assert_eq!(
+            (front.as_slice(), back.as_slice()),
+            (&[4, 5][..], &[1, 2, 3][..])
+        );
+        // the heap buffers stayed where they were; only the `Vec`s, three words each, moved
+        assert_eq!((front.as_ptr(), back.as_ptr()), (back_data, front_data));
+
+        // within one slice there is no second `&mut` to pass, so the slice swaps for us
+        front.swap(0, 1);
+        assert_eq!(front, [5, 4]);
+        println!("front = {front:?}, back = {back:?}");
+    })
+    // front = [5, 4], back = [1, 2, 3]
+}
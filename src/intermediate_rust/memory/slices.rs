@@ -0,0 +1,151 @@
+//! # Slices and Fat Pointers
+//! A _slice_ lets you reference a contiguous sequence of elements in a collection rather than
+//! the whole collection. A slice is a kind of reference, so it does not have ownership.
+//!
+//! An ordinary reference like `&u64` is a single word: the address of the value. But a slice
+//! can cover any number of elements, and that number is not part of its type, so a reference
+//! to a slice carries the length alongside the address. Such two-word references are called
+//! _fat pointers_. `&str` is the same thing for UTF-8 text: a pointer plus a length in bytes.
+
+use std::{io::Result, mem::size_of, slice};
+
+pub fn slices() -> Result<()> {
+    Ok({
+        slice_references_are_two_words()?;
+        slicing_syntax()?;
+        splitting_a_slice_mutably()?;
+        passing_slices_instead_of_owners()?;
+    })
+}
+
+fn slice_references_are_two_words() -> Result<()> {
+    Ok({
+        span!("slice_references_are_two_words");
+        header!("Slice References Are Two Words");
+
+        println!("size of &u64   = {}", size_of::<&u64>()); //   size of &u64   = 8
+        println!("size of &[u64] = {}", size_of::<&[u64]>()); // size of &[u64] = 16
+        println!("size of &str   = {}", size_of::<&str>()); //   size of &str   = 16
+        println!("size of &[u64; 4] = {}", size_of::<&[u64; 4]>()); // size of &[u64; 4] = 8
+
+        // The length of an array is part of its type, so `&[u64; 4]` is a thin pointer;
+        // coercing it to `&[u64]` moves the length out of the type and into the pointer.
+        let array = [1u64, 2, 3, 4];
+        let whole: &[u64] = &array;
+        let (ptr, len) = (whole.as_ptr(), whole.len());
+        println!("whole = {:?} at {:p} with len {}", whole, ptr, len);
+
+        // The two words are all there is to a slice: we can rebuild one from them (unsafely)
+        // SAFETY: `ptr` and `len` were just taken from a live slice of `array`
+        let rebuilt = unsafe { slice::from_raw_parts(ptr, len) };
+        println!("rebuilt = {:?}", rebuilt);
+    })
+}
+
+/// # Slicing Syntax
+/// Ranges select the part of a collection to borrow: `[start..end]` includes `start` and
+/// excludes `end`, and either bound can be omitted. An out-of-bounds range is a bug, so indexing
+/// with it panics; `get` returns an `Option` instead when the bounds come from user input.
+///
+/// With `&str` the indices are byte offsets, and slicing in the middle of a multi-byte
+/// character panics too, because the result would not be valid UTF-8.
+fn slicing_syntax() -> Result<()> {
+    Ok({
+        span!("slicing_syntax");
+        header!("Slicing Syntax");
+
+        let v = Vec::from([10, 20, 30, 40, 50]);
+        println!("v[1..3]  = {:?}", &v[1..3]); //  v[1..3]  = [20, 30]
+        println!("v[..2]   = {:?}", &v[..2]); //   v[..2]   = [10, 20]
+        println!("v[3..]   = {:?}", &v[3..]); //   v[3..]   = [40, 50]
+        println!("v[1..=3] = {:?}", &v[1..=3]); // v[1..=3] = [20, 30, 40]
+        println!("v[..]    = {:?}", &v[..]); //    v[..]    = [10, 20, 30, 40, 50]
+
+        // &v[2..10] would panic: range end index 10 out of range for slice of length 5
+        println!("v.get(2..10) = {:?}", v.get(2..10)); // v.get(2..10) = None
+
+        let s = String::from("héllo");
+        println!("s[0..1] = {:?}", &s[0..1]); // s[0..1] = "h"
+
+        // `é` takes the two bytes 1..3, so &s[0..2] would panic: byte index 2 is not a char boundary
+        println!("s.get(0..2) = {:?}", s.get(0..2)); // s.get(0..2) = None
+        println!("s.get(0..3) = {:?}", s.get(0..3)); // s.get(0..3) = Some("hé")
+    })
+}
+
+/// # Splitting a Slice Mutably
+/// The borrow checker can’t tell that two ranges of the same slice don’t overlap, so this is
+/// rejected even though it is perfectly safe:
+/// ```compile_fail
+/// fn split_at_mut(values: &mut [i32], mid: usize) -> (&mut [i32], &mut [i32]) {
+///     (&mut values[..mid], &mut values[mid..])
+/// }
+/// ```
+/// cannot borrow `*values` as mutable more than once at a time
+///
+/// The standard library’s `split_at_mut` is a safe function implemented with `unsafe` code:
+/// it checks `mid <= len` itself, and then builds the two slices from raw pointers. Below is
+/// the same function written out, which is the pattern behind many safe APIs: the `unsafe`
+/// block is small, and the safe signature makes it impossible to call incorrectly.
+fn split_at_mut(values: &mut [i32], mid: usize) -> (&mut [i32], &mut [i32]) {
+    let len = values.len();
+    let ptr = values.as_mut_ptr();
+
+    assert!(mid <= len);
+
+    // SAFETY: both halves lie inside the original slice and don’t overlap
+    unsafe {
+        (
+            slice::from_raw_parts_mut(ptr, mid),
+            slice::from_raw_parts_mut(ptr.add(mid), len - mid),
+        )
+    }
+}
+fn splitting_a_slice_mutably() -> Result<()> {
+    Ok({
+        span!("splitting_a_slice_mutably");
+        header!("Splitting a Slice Mutably");
+
+        let mut v = vec![1, 2, 3, 4, 5, 6];
+
+        let (left, right) = split_at_mut(&mut v, 3);
+        left[0] = 100;
+        right[0] = 400;
+        println!("left = {:?}, right = {:?}", left, right); // left = [100, 2, 3], right = [400, 5, 6]
+
+        // the standard library version, and a common use: swapping across the halves
+        let (left, right) = v.split_at_mut(3);
+        std::mem::swap(&mut left[1], &mut right[1]);
+        println!("v = {:?}", v); // v = [100, 5, 3, 400, 2, 6]
+    })
+}
+
+/// # Passing Slices Instead of Owners
+/// A function that only reads a sequence should take `&[T]` rather than `&Vec<T>`, and `&str`
+/// rather than `&String`. Thanks to deref coercion (see `traits/deref.rs`), a `&Vec<T>` becomes
+/// a `&[T]` and a `&String` becomes a `&str` automatically, because `Vec<T>: Deref<Target = [T]>`
+/// and `String: Deref<Target = str>`. The slice versions then also accept arrays, string
+/// literals, and sub-slices, which the owner versions never could.
+fn sum(values: &[i32]) -> i32 {
+    values.iter().sum()
+}
+fn first_word(text: &str) -> &str {
+    text.split_whitespace().next().unwrap_or("")
+}
+fn passing_slices_instead_of_owners() -> Result<()> {
+    Ok({
+        span!("passing_slices_instead_of_owners");
+        header!("Passing Slices Instead of Owners");
+
+        let vec = vec![1, 2, 3, 4];
+        let array = [5, 6, 7];
+        println!("sum(&vec)       = {}", sum(&vec)); //       &Vec<i32> -> &[i32]
+        println!("sum(&array)     = {}", sum(&array)); //     &[i32; 3] -> &[i32]
+        println!("sum(&vec[1..3]) = {}", sum(&vec[1..3])); // already a &[i32]
+
+        let owned = String::from("hello world");
+        println!("first_word(&owned)      = {}", first_word(&owned)); // &String -> &str
+        println!("first_word(\"hi there\")  = {}", first_word("hi there")); // &'static str
+        println!("first_word(&owned[6..]) = {}", first_word(&owned[6..])); // already a &str
+    })
+}
@@ -0,0 +1,143 @@
+//! # The Lessons without `std`, on the Command Line
+//! The lessons of `ir-no-std` don’t print; they write to the `Output` they are given. `Printed`
+//! is the one they are given here, which prints where `print!` does, into the lesson’s output,
+//! with its headers translated and styled as `header!` does them.
+
+use super::{
+    lesson::{Chapter, Difficulty, Lesson, LessonCtx, LessonInfo, Registry},
+    locale, output,
+};
+use ir_no_std::{box_t, iterators, my_box, rc_t, ref_cell_t, Output, Run};
+use std::{
+    fmt::{self, Write},
+    io::{Error, Result},
+};
+
+ir_core::inventory::submit! {
+    Chapter {
+        topic: "no-std",
+        register,
+    }
+}
+
+fn register(registry: &mut Registry) {
+    let lessons = [
+        NoStd {
+            id: "no-std/box",
+            title: "`Box<T>`, from `alloc`",
+            run: box_t::box_t,
+            info: LessonInfo {
+                description: "`Box`, `Vec` and `format!` are in `alloc`, which is all they need",
+                difficulty: Difficulty::Intermediate,
+                prerequisites: &[],
+                tags: &["no-std", "smart-pointers"],
+                source: include_str!("../../crates/ir-no-std/src/box_t.rs"),
+            },
+        },
+        NoStd {
+            id: "no-std/rc",
+            title: "`Rc<T>` and `Weak<T>`, from `alloc`",
+            run: rc_t::rc_t,
+            info: LessonInfo {
+                description: "Counting the owners of a value takes a heap, and no operating system",
+                difficulty: Difficulty::Intermediate,
+                prerequisites: &["no-std/box"],
+                tags: &["no-std", "smart-pointers"],
+                source: include_str!("../../crates/ir-no-std/src/rc_t.rs"),
+            },
+        },
+        NoStd {
+            id: "no-std/ref-cell",
+            title: "`Cell<T>` and `RefCell<T>`, from `core`",
+            run: ref_cell_t::ref_cell_t,
+            info: LessonInfo {
+                description: "Interior mutability needs no heap, only `Rc` around it does",
+                difficulty: Difficulty::Intermediate,
+                prerequisites: &["no-std/rc"],
+                tags: &["no-std", "smart-pointers"],
+                source: include_str!("../../crates/ir-no-std/src/ref_cell_t.rs"),
+            },
+        },
+        NoStd {
+            id: "no-std/iterators",
+            title: "Iterators, from `core`",
+            run: iterators::iterators,
+            info: LessonInfo {
+                description:
+                    "`Iterator` and its adapters are in `core`; `collect` may need `alloc`",
+                difficulty: Difficulty::Intermediate,
+                prerequisites: &[],
+                tags: &["no-std", "iterators"],
+                source: include_str!("../../crates/ir-no-std/src/iterators.rs"),
+            },
+        },
+        NoStd {
+            id: "no-std/my-box",
+            title: "A `Box<T>` of our own, with `alloc::alloc`",
+            run: my_box::my_box,
+            info: LessonInfo {
+                description: "Allocating, dropping and freeing a value by hand, without `std`",
+                difficulty: Difficulty::Advanced,
+                prerequisites: &["no-std/box"],
+                tags: &["no-std", "smart-pointers", "unsafe"],
+                source: include_str!("../../crates/ir-no-std/src/my_box.rs"),
+            },
+        },
+    ];
+    for lesson in lessons {
+        registry.register(lesson);
+    }
+}
+
+/// A lesson of `ir-no-std`, which is a function of its `Output` rather than of nothing.
+struct NoStd {
+    id: &'static str,
+    title: &'static str,
+    run: Run,
+    info: LessonInfo,
+}
+impl Lesson for NoStd {
+    fn id(&self) -> &str {
+        self.id
+    }
+
+    fn title(&self) -> &str {
+        self.title
+    }
+
+    fn run(&self, ctx: &mut LessonCtx) -> Result<()> {
+        output::redirect(&mut ctx.output, || (self.run)(&mut Printed))
+            .map_err(|fmt::Error| Error::other(format!("`{}` couldn't print", self.id)))
+    }
+
+    fn info(&self) -> LessonInfo {
+        self.info
+    }
+}
+
+/// Prints into the current lesson’s output.
+struct Printed;
+impl Write for Printed {
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        Ok(print!("{text}"))
+    }
+}
+impl Output for Printed {
+    fn header(&mut self, text: &str) -> fmt::Result {
+        Ok(header!("{}", locale::tr(text)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{intermediate_rust, runner};
+
+    #[test]
+    fn the_lessons_print_into_the_lessons_output() {
+        let registry = intermediate_rust::registry();
+        let rc = registry.get("no-std/ref-cell").unwrap();
+        let output = runner::run_captured(rc, Default::default()).unwrap();
+        assert!(output.stdout.starts_with("Interior Mutability\n"));
+        assert!(output.stdout.ends_with("value = 30\n"));
+    }
+}
@@ -0,0 +1,271 @@
+//! # Patterns
+//! A pattern says what shape a value has to have, and names the parts of it. `match` is where
+//! they are most often seen, but every `let`, every function parameter and every `for` has one
+//! too: `let (a, b) = pair;` destructures a tuple as much as a `match` arm does. The patterns in
+//! `match` have to be _exhaustive_, between them, and the ones in `let` _irrefutable_, matching
+//! every value, unless the `let` has an `else`.
+//!
+//! This lesson checks what it says with `assert!`s rather than printing it: each part runs
+//! quietly, unless a pattern doesn’t match what it says it does.
+
+use crate::intermediate_rust::smart_pointers::{List, TreeNode};
+use std::{cell::RefCell, io::Result, rc::Rc};
+
+pub fn patterns() -> Result<()> {
+    Ok({
+        match_guards()?;
+        at_bindings()?;
+        destructuring_the_lessons_types()?;
+        slice_patterns()?;
+        binding_modes_and_ref()?;
+        let_else_and_if_let()?;
+    })
+}
+
+/// Describes `n`, and whether it is the one `lucky` number.
+fn classify(n: i32, lucky: i32) -> &'static str {
+    match n {
+        n if n == lucky => "lucky",
+        n if n < 0 => "negative",
+        0 => "zero",
+        // the guard is of the whole `|` pattern: `(2 | 4 | 6) if ...`, not `2 | 4 | (6 if ...)`
+        2 | 4 | 6 if lucky > 0 => "small, even, and there is a lucky number",
+        _ => "something else",
+    }
+}
+
+/// # Match Guards
+/// A pattern can only compare with literals; `if` after one adds any condition at all, which
+/// can use the names the pattern bound, and what else is in scope. A guarded arm that doesn’t
+/// hold moves on to the next arm. The compiler doesn’t look into guards to decide whether the
+/// arms are exhaustive, so a `match` with guards usually ends with an arm without one.
+fn match_guards() -> Result<()> {
+    Ok({
+        span!("match_guards");
+        header!("Match Guards");
+
+        assert_eq!(classify(7, 7), "lucky");
+        assert_eq!(classify(-3, 7), "negative");
+        assert_eq!(classify(0, 7), "zero");
+        assert_eq!(classify(4, 7), "small, even, and there is a lucky number");
+        assert_eq!(classify(4, -1), "something else");
+    })
+}
+
+#[derive(Debug, PartialEq)]
+enum Message {
+    Hello { id: u32 },
+    Move { x: i32, y: i32 },
+}
+
+/// # `@` Bindings
+/// `name @ pattern` tests a value against a pattern, and binds the whole of it as well, where a
+/// range or a `|` alone would only say it matched, not what it was.
+fn at_bindings() -> Result<()> {
+    Ok({
+        span!("at_bindings");
+        header!("`@` Bindings");
+
+        let describe = |message: &Message| match message {
+            Message::Hello { id: id @ 3..=7 } => format!("a hello in range, {id}"),
+            Message::Hello { id: id @ (10 | 20) } => format!("a round hello, {id}"),
+            Message::Hello { id } => format!("another hello, {id}"),
+            moved @ Message::Move { x: 0, .. } => format!("a vertical {moved:?}"),
+            Message::Move { .. } => String::from("a move"),
+        };
+        assert_eq!(describe(&Message::Hello { id: 5 }), "a hello in range, 5");
+        assert_eq!(describe(&Message::Hello { id: 20 }), "a round hello, 20");
+        assert_eq!(describe(&Message::Hello { id: 8 }), "another hello, 8");
+        assert_eq!(
+            describe(&Message::Move { x: 0, y: 3 }),
+            "a vertical Move { x: 0, y: 3 }"
+        );
+    })
+}
+
+/// The first two elements of `list`, if it has two.
+fn first_two<T: Copy>(list: &List<T>) -> Option<(T, T)> {
+    match list {
+        // `rest` is a `&Box<List<T>>`: a pattern can’t look into a `Box`, so `&**rest` does
+        List::Cons(a, rest) => match &**rest {
+            List::Cons(b, _) => Some((*a, *b)),
+            List::Nil => None,
+        },
+        List::Nil => None,
+    }
+}
+
+/// What the tree under `node` looks like, from its value and its children.
+fn shape(node: &TreeNode<i32>) -> String {
+    match (*node.value(), &*node.children()) {
+        (value, []) => format!("a leaf, {value}"),
+        (value, [only]) => format!("{value}, with one child, {}", only.value()),
+        (value, [first, .., last]) => {
+            format!("{value}, from {} to {}", first.value(), last.value())
+        }
+    }
+}
+
+/// # Destructuring the Lessons’ Types
+/// Patterns nest as deep as the types do: the `Box<T>` lesson’s `List` is a `Cons` of a value
+/// and another `List`, so a pattern of a `Cons` can hold one of a `Cons`. Not through a `Box`,
+/// though, nor an `Rc` or a `RefCell`: a pattern matches what a value is, and a smart pointer is
+/// a pointer, so it is dereferenced, `&**rest`, and matched again. The `RefCell<T>` lesson’s
+/// `TreeNode` keeps its fields private, so its pattern is of what its methods return, a tuple
+/// of its value and a slice of its children.
+fn destructuring_the_lessons_types() -> Result<()> {
+    Ok({
+        span!("destructuring_the_lessons_types");
+        header!("Destructuring the Lessons’ Types");
+
+        assert_eq!(first_two(&List::from(vec![1, 2, 3])), Some((1, 2)));
+        assert_eq!(first_two(&List::from(vec![1])), None);
+
+        let branch = TreeNode::new(5);
+        assert_eq!(shape(&branch), "a leaf, 5");
+        TreeNode::add_child(&branch, TreeNode::new(3));
+        assert_eq!(shape(&branch), "5, with one child, 3");
+        TreeNode::add_child(&branch, TreeNode::new(4));
+        TreeNode::add_child(&branch, TreeNode::new(8));
+        assert_eq!(shape(&branch), "5, from 3 to 8");
+    })
+}
+
+/// The sum of `numbers`, one element at a time, as a `List` would be summed.
+fn sum(numbers: &[i32]) -> i32 {
+    match numbers {
+        [] => 0,
+        [first, rest @ ..] => first + sum(rest),
+    }
+}
+
+/// # Slice Patterns
+/// A slice, or an array, is matched element by element: `[a, b]` is exactly two, `[first, ..]`
+/// one or more, and `[.., last]` the same from the end. `rest @ ..` binds what `..` skipped, as
+/// a slice, so a slice can be taken apart head first, like a cons list. For an array, whose
+/// length the compiler knows, `[x, y, z]` is irrefutable, and can be a `let`.
+fn slice_patterns() -> Result<()> {
+    Ok({
+        span!("slice_patterns");
+        header!("Slice Patterns");
+
+        let describe = |words: &[&str]| match words {
+            [] => String::from("nothing"),
+            [one] => format!("just {one}"),
+            [first, second] => format!("{first} and {second}"),
+            [first, .., last] => format!("{first} to {last}"),
+        };
+        assert_eq!(describe(&[]), "nothing");
+        assert_eq!(describe(&["a"]), "just a");
+        assert_eq!(describe(&["a", "b"]), "a and b");
+        assert_eq!(describe(&["a", "b", "c", "d"]), "a to d");
+
+        assert_eq!(sum(&[1, 2, 3, 4]), 10);
+        let [x, y, z] = [1, 2, 3];
+        assert_eq!(x + y + z, sum(&[1, 2, 3]));
+    })
+}
+
+/// # Binding Modes and `ref`
+/// Matching a reference with a pattern of a value, `Some(name)` against a `&Option<String>`,
+/// binds `name` as a reference, `&String`, rather than moving the `String` out from behind the
+/// reference: that is the _default binding mode_. Matching a value itself moves what is bound
+/// out of it, unless the binding says `ref`, or `ref mut`, which is how to borrow from a value
+/// that isn’t a reference, such as what a `Box`, a `RefCell`’s guard or an `Rc` dereferences to.
+fn binding_modes_and_ref() -> Result<()> {
+    Ok({
+        span!("binding_modes_and_ref");
+        header!("Binding Modes and `ref`");
+
+        let name = Some(String::from("Ferris"));
+        // `Some(borrowed)` against a `&Option<String>`: `borrowed` is a `&String`
+        if let Some(borrowed) = &name {
+            assert_eq!(borrowed.len(), 6);
+        }
+        // `ref` borrows from the `Option<String>` itself, so `name` isn’t moved
+        if let Some(ref borrowed) = name {
+            assert_eq!(borrowed, "Ferris");
+        }
+        assert!(name.is_some());
+
+        // the value a `Box` holds, matched in place
+        let mut boxed = Box::new(Some(41));
+        if let Some(ref mut n) = *boxed {
+            *n += 1;
+        }
+        assert_eq!(*boxed, Some(42));
+
+        // the value behind a `RefCell` shared by an `Rc`, through the guard `borrow_mut` returns
+        let shared = Rc::new(RefCell::new((String::from("count"), 0)));
+        let mut guard = shared.borrow_mut();
+        let (ref label, ref mut count) = *guard;
+        *count += 1;
+        assert_eq!((label.as_str(), *count), ("count", 1));
+    })
+}
+
+/// # `let else`, and `if let` Instead of Chains
+/// `let pattern = value else { ... };` binds the names of a refutable pattern for the rest of
+/// the block, and runs the `else`, which has to leave the block, if the value doesn’t match:
+/// the happy path stays unindented. Several conditions with `if let` read best as chains,
+/// `if let Some(a) = x && a > 0`, which need the 2024 edition; this crate is 2021:
+/// ```compile_fail,edition2021
+/// let (x, y) = (Some(1), Some(2));
+/// if let Some(a) = x && let Some(b) = y {
+///     assert_eq!(a + b, 3);
+/// }
+/// ```
+/// `let` expressions in this position are unstable
+///
+/// Until then, a tuple of the values matches them all at once, and a guard adds a condition.
+fn let_else_and_if_let() -> Result<()> {
+    Ok({
+        span!("let_else_and_if_let");
+        header!("`let else`, and `if let` Instead of Chains");
+
+        let parse_pair = |text: &str| -> std::result::Result<(i32, i32), String> {
+            let Some((left, right)) = text.split_once(',') else {
+                return Err(format!("no `,` in {text:?}"));
+            };
+            let (Ok(left), Ok(right)) = (left.trim().parse(), right.trim().parse()) else {
+                return Err(format!("{text:?} isn't two numbers"));
+            };
+            Ok((left, right))
+        };
+        assert_eq!(parse_pair("3, 4"), Ok((3, 4)));
+        assert_eq!(parse_pair("3 4"), Err(String::from("no `,` in \"3 4\"")));
+        assert!(parse_pair("3, four").is_err());
+
+        let (x, y) = (Some(1), Some(2));
+        let both = match (x, y) {
+            (Some(a), Some(b)) if a < b => Some(a + b),
+            _ => None,
+        };
+        assert_eq!(both, Some(3));
+        assert!(matches!(parse_pair("0, 9"), Ok((0, 9 | 10))));
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn the_parts_of_the_lesson_hold() {
+        patterns().unwrap();
+    }
+
+    #[test]
+    fn guards_apply_to_the_whole_alternative() {
+        assert_eq!(classify(2, 1), "small, even, and there is a lucky number");
+        assert_eq!(classify(6, 0), "something else");
+        assert_eq!(classify(0, 0), "lucky");
+    }
+
+    #[test]
+    fn slices_are_taken_apart_head_first() {
+        assert_eq!(sum(&[]), 0);
+        assert_eq!(sum(&[-1, 1, 5]), 5);
+        assert_eq!(first_two(&List::<u8>::Nil), None);
+    }
+}
@@ -0,0 +1,95 @@
+//! # Dynamic Loading
+//! A library linked into a program is found when the program starts, or the program doesn’t
+//! start; one loaded with `dlopen`, or `LoadLibrary` on Windows, is found when the program asks
+//! for it, by a path it may only learn while it runs, such as `--plugin`’s. `libloading` wraps
+//! both: `Library::new` loads a library, and `Library::get` looks a symbol up in it, a function
+//! or a `static`, by name.
+//!
+//! It is FFI again, without even a declaration to check a call against: the type a symbol is
+//! looked up as is the only one it has, and nothing can check it is right.
+
+use crate::intermediate_rust::{lesson::PLUGIN_VERSION, utils::miri::skip_under_miri};
+use libloading::{Library, Symbol};
+use std::{
+    ffi::c_double,
+    io::{Error, Result},
+};
+
+/// The C maths library, which has `pow`.
+#[cfg(target_os = "linux")]
+const LIBM: &str = "libm.so.6";
+#[cfg(target_os = "macos")]
+const LIBM: &str = "libSystem.B.dylib";
+#[cfg(windows)]
+const LIBM: &str = "ucrtbase.dll";
+#[cfg(not(any(target_os = "linux", target_os = "macos", windows)))]
+const LIBM: &str = "libm.so";
+
+pub fn dynamic_loading() -> Result<()> {
+    Ok({
+        if skip_under_miri("loading a library, which Miri can't") {
+            return Ok(());
+        }
+        opening_a_library()?;
+        symbols_borrow_their_library()?;
+        plugins_in_rust()?;
+    })
+}
+
+/// # Opening a Library
+/// `Library::new` is `unsafe`, as loading a library runs its initializers, and `get` is, as the
+/// symbol is taken to be of whatever type it is asked for. A function is asked for as an
+/// `extern "C" fn`, which is called as any function pointer is.
+fn opening_a_library() -> Result<()> {
+    Ok({
+        span!("opening_a_library");
+        header!("Opening a Library");
+
+        // SAFETY: the C library’s initializers are the ones every C program runs
+        let libm = unsafe { Library::new(LIBM) }.map_err(Error::other)?;
+        // SAFETY: `pow` is `double pow(double, double)`
+        let pow: Symbol<extern "C" fn(c_double, c_double) -> c_double> =
+            unsafe { libm.get(b"pow") }.map_err(Error::other)?;
+        println!("pow(2.0, 10.0) = {}", pow(2.0, 10.0)); // pow(2.0, 10.0) = 1024
+    })
+}
+
+/// # Symbols Borrow Their Library
+/// Unloading a library frees its code, and a pointer into it would dangle. So a `Symbol<'lib, T>`
+/// borrows the `Library` it is from, and the borrow checker keeps the library loaded for as long
+/// as there is one: `drop(libm)` wouldn’t compile before the last call to `pow`. What isn’t in
+/// the library is an `Err`, as a file that isn’t a library is.
+fn symbols_borrow_their_library() -> Result<()> {
+    Ok({
+        span!("symbols_borrow_their_library");
+        header!("Symbols Borrow Their Library");
+
+        // SAFETY: as above
+        let libm = unsafe { Library::new(LIBM) }.map_err(Error::other)?;
+        // SAFETY: it isn’t there to be called
+        let missing = unsafe { libm.get::<extern "C" fn()>(b"ir_no_such_function") };
+        println!("ir_no_such_function is there: {}", missing.is_ok());
+        // ir_no_such_function is there: false
+        drop(libm);
+    })
+}
+
+/// # Plugins in Rust
+/// Rust has no stable ABI: how a `Registry` is laid out, or a `Box<dyn Lesson>`, may change
+/// from one compiler to the next. A plugin and its program only agree on them if the same
+/// version of `ir-core` is built by the same compiler, so the plugin’s one function is
+/// `extern "C"`, and `--plugin` checks the version before it calls it. The library is never
+/// unloaded, as the lessons it registers point into it.
+fn plugins_in_rust() -> Result<()> {
+    Ok({
+        span!("plugins_in_rust");
+        header!("Plugins in Rust");
+
+        let version = PLUGIN_VERSION.to_string_lossy();
+        println!("a plugin has to be built with ir-core {version}");
+        // a plugin has to be built with ir-core 0.1.0
+        let file = libloading::library_filename("ir_example_plugin");
+        println!("the example plugin is {}", file.display());
+        // the example plugin is libir_example_plugin.so
+    })
+}
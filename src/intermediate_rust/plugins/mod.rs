@@ -0,0 +1,11 @@
+//! # Plugins
+//! A program is extensible when code it has never heard of can add to it: a lesson, a command, a
+//! benchmark. Rust has no reflection to find such code with, so it has to be told about it, by a
+//! list somebody keeps up to date, or by the code itself, which is what these lessons are about.
+//!
+//! The chapters of this package tell the registry about themselves: each one submits a `Chapter`
+//! with `inventory::submit!`, and `registry` is whichever chapters were submitted. Then lessons
+//! can come from outside the program too, from a plugin it loads with `--plugin`.
+
+pub(super) mod dynamic_loading;
+pub(super) mod self_registration;
@@ -0,0 +1,126 @@
+//! # Self-Registration
+//! A chapter of lessons doesn’t have to be added to a list to be found: it submits itself, with
+//! `inventory::submit!`, next to its lessons, and `Registry::discover` collects whatever was
+//! submitted. `linkme` does the same with a `#[distributed_slice]`. Neither can be done in safe
+//! Rust, as neither is Rust: both are the linker’s and the loader’s, and this is how.
+//!
+//! Whatever the crate, only what is linked into the program is found. The linker leaves out any
+//! crate the program doesn’t use, and its submissions with it: `intermediate_rust.rs` has a
+//! `use ir_concurrency as _;` for that reason, without which the `thread-and-move` lesson would
+//! build, and never be registered.
+
+use crate::intermediate_rust::{lesson::Chapter, utils::miri::skip_under_miri};
+use ir_core::inventory;
+use linkme::distributed_slice;
+use std::{
+    io::Result,
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+pub fn self_registration() -> Result<()> {
+    Ok({
+        if skip_under_miri("sections of the binary, which Miri doesn't have") {
+            return Ok(());
+        }
+        submitting_to_a_collection()?;
+        life_before_main()?;
+        distributed_slices()?;
+    })
+}
+
+/// # Submitting to a Collection
+/// `inventory::collect!(Chapter)` makes `Chapter` a type values can be submitted as, and each
+/// `inventory::submit!` a `static` of one, in a node of a linked list; `inventory::iter` walks
+/// the list. Submissions have no order, so `discover` sorts them, by the order it is given.
+fn submitting_to_a_collection() -> Result<()> {
+    Ok({
+        span!("submitting_to_a_collection");
+        header!("Submitting to a Collection");
+
+        let mut topics: Vec<_> = inventory::iter::<Chapter>
+            .into_iter()
+            .map(|chapter| chapter.topic)
+            .collect();
+        topics.sort_unstable();
+        println!("{} chapters were submitted:", topics.len()); // 11 chapters were submitted:
+        println!("{topics:?}");
+        // ["build-scripts", "collections", "ffi", "lambda", "logging", "memory", "plugins",
+        //  "smart-pointers", "strings", "thread-and-move", "unsafe-rust"]
+    })
+}
+
+/// # Life Before `main`
+/// Nothing in the program calls a submission, yet it is on the list by the time `main` looks.
+/// `submit!` writes a constructor that pushes it on, and a pointer to the constructor in a
+/// section of the binary the loader calls every pointer of before `main`: `.init_array` on Linux,
+/// `__mod_init_func` on macOS and `.CRT$XCU` on Windows. `#[used]` keeps the pointer, which
+/// nothing reads, from being left out. Here is one of our own.
+fn life_before_main() -> Result<()> {
+    Ok({
+        span!("life_before_main");
+        header!("Life Before `main`");
+
+        let constructed = CONSTRUCTED.load(Ordering::Relaxed);
+        println!("constructed before `main`: {constructed}"); // constructed before `main`: true
+    })
+}
+
+static CONSTRUCTED: AtomicBool = AtomicBool::new(false);
+
+/// The constructor, which the loader calls with C’s ABI; it runs before `std` is set up, so it
+/// mustn’t print, allocate, or panic.
+extern "C" fn constructor() {
+    CONSTRUCTED.store(true, Ordering::Relaxed);
+}
+
+#[used]
+#[cfg_attr(target_os = "linux", link_section = ".init_array")]
+static CONSTRUCTOR: extern "C" fn() = constructor;
+
+/// # Distributed Slices
+/// `linkme` runs no code at all. Each element of a `#[distributed_slice]` is a `static` in a
+/// section named for the slice, and the linker puts all of a section together, wherever in the
+/// program its parts come from, and names its start and end: `__start_linkme_GREETINGS` and
+/// `__stop_linkme_GREETINGS` on Linux. The slice is what is between them, as a `&'static [T]`.
+fn distributed_slices() -> Result<()> {
+    Ok({
+        span!("distributed_slices");
+        header!("Distributed Slices");
+
+        println!("GREETINGS.len() = {}", GREETINGS.len()); // GREETINGS.len() = 2
+        let mut greetings = GREETINGS.to_vec();
+        greetings.sort_unstable();
+        for (lang, greeting) in greetings {
+            println!("{lang}: {greeting}");
+        }
+        // en: Hello
+        // id: Halo
+    })
+}
+
+#[distributed_slice]
+static GREETINGS: [(&str, &str)];
+
+#[distributed_slice(GREETINGS)]
+static ENGLISH: (&str, &str) = ("en", "Hello");
+
+// it could be in any module, or crate, of the program
+#[distributed_slice(GREETINGS)]
+static INDONESIAN: (&str, &str) = ("id", "Halo");
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn what_is_linked_in_is_collected_before_main() {
+        assert!(inventory::iter::<Chapter>
+            .into_iter()
+            .any(|chapter| chapter.topic == "plugins"));
+        if cfg!(target_os = "linux") {
+            assert!(CONSTRUCTED.load(Ordering::Relaxed));
+        }
+        assert_eq!(GREETINGS.len(), 2);
+    }
+}
@@ -0,0 +1,298 @@
+//! # Serialization with Serde
+//! A value in memory is pointers and padding, laid out for this program on this machine; to
+//! save it to a file, or send it to another program, it has to be turned into bytes that mean
+//! the same thing anywhere, and back. Serde splits that in two: a type says what it is made of,
+//! by implementing `Serialize` and `Deserialize`, usually with a `#[derive]`, and a _format_,
+//! such as `serde_json` or `bincode`, says how those parts are written. Any type works with any
+//! format, without either knowing about the other.
+//!
+//! `Serialize` calls a `Serializer`’s methods, `serialize_struct`, `serialize_seq` and so on,
+//! one for each of the 29 kinds of value of serde’s data model; `Deserialize` asks a
+//! `Deserializer` for the kind it expects, and is called back with what was there.
+
+use crate::intermediate_rust::smart_pointers::List;
+use serde::{
+    de::{SeqAccess, Visitor},
+    ser::SerializeSeq,
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+use std::{
+    fmt,
+    io::{Error, Result},
+    marker::PhantomData,
+};
+
+pub fn serde() -> Result<()> {
+    Ok({
+        deriving_serialize_and_deserialize()?;
+        field_attributes()?;
+        serializing_a_type_of_another_crate()?;
+        json_and_a_binary_format()?;
+    })
+}
+
+/// How far a learner is with a lesson.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Progress {
+    lesson: String,
+    completed: bool,
+    score: Option<u8>,
+}
+
+/// Something that happened while the lessons ran.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+enum Event {
+    Started,
+    Completed { lesson: String, seconds: f64 },
+    Failed(String),
+}
+
+/// # Deriving `Serialize` and `Deserialize`
+/// The derives write the two `impl`s from the type’s definition: a struct is a map of its
+/// fields’ names to their values, and an enum is, by default, a map of the variant’s name to its
+/// content, or just the name of a variant without one. `Option` is a value or `null`.
+fn deriving_serialize_and_deserialize() -> Result<()> {
+    Ok({
+        span!("deriving_serialize_and_deserialize");
+        header!("Deriving `Serialize` and `Deserialize`");
+
+        let progress = Progress {
+            lesson: String::from("smart-pointers/rc"),
+            completed: true,
+            score: None,
+        };
+        let json = serde_json::to_string(&progress)?;
+        println!("{json}"); // {"lesson":"smart-pointers/rc","completed":true,"score":null}
+        assert_eq!(serde_json::from_str::<Progress>(&json)?, progress);
+
+        let events = [
+            Event::Started,
+            Event::Completed {
+                lesson: String::from("lambda"),
+                seconds: 0.5,
+            },
+            Event::Failed(String::from("ffi/calling-c")),
+        ];
+        println!("{}", serde_json::to_string(&events)?);
+        // ["Started",{"Completed":{"lesson":"lambda","seconds":0.5}},{"Failed":"ffi/calling-c"}]
+    })
+}
+
+/// The settings of a run, as a file would have them.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+struct Settings {
+    /// `lesson-id` in the file, as `rename_all` has it.
+    lesson_id: String,
+    /// `colour` in the file: `rename` is for a single field.
+    #[serde(rename = "colour")]
+    color: bool,
+    /// `Verbosity::Normal` when the file doesn’t say.
+    #[serde(default)]
+    verbosity: Verbosity,
+    /// Left out when it is `None`, rather than written as `null`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    out: Option<String>,
+    /// Never written nor read, and `Default::default()` when deserialized.
+    #[serde(skip)]
+    runs: u32,
+}
+
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Verbosity {
+    Quiet,
+    #[default]
+    Normal,
+    Verbose,
+}
+
+/// # Field Attributes
+/// `#[serde(...)]` changes what the derives write without changing the type: `rename` and
+/// `rename_all` for names the Rust code can’t or shouldn’t have, `default` for fields a file may
+/// leave out, `skip_serializing_if` for fields it should, and `skip` for what isn’t data at
+/// all, such as a cache or a counter.
+fn field_attributes() -> Result<()> {
+    Ok({
+        span!("field_attributes");
+        header!("Field Attributes");
+
+        let settings: Settings = serde_json::from_str(r#"{"lesson-id":"lambda","colour":false}"#)?;
+        println!("{settings:?}");
+        // Settings { lesson_id: "lambda", color: false, verbosity: Normal, out: None, runs: 0 }
+
+        let settings = Settings {
+            verbosity: Verbosity::Verbose,
+            runs: 3,
+            ..settings
+        };
+        println!("{}", serde_json::to_string(&settings)?);
+        // {"lesson-id":"lambda","colour":false,"verbosity":"verbose"}
+    })
+}
+
+/// A `List` to serialize, as a sequence of its elements. `List` and `Serialize` are both of
+/// other crates, so `impl Serialize for List<T>` isn’t allowed here; a type of this crate
+/// wrapping one is.
+struct SerializeList<'a, T>(&'a List<T>);
+
+impl<T: Serialize> Serialize for SerializeList<'_, T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+        let mut list = self.0;
+        while let List::Cons(value, rest) = list {
+            seq.serialize_element(value)?;
+            list = rest;
+        }
+        seq.end()
+    }
+}
+
+/// A `List` deserialized from a sequence, which `into_inner` takes out.
+struct DeserializeList<T>(List<T>);
+
+impl<T> DeserializeList<T> {
+    fn into_inner(self) -> List<T> {
+        self.0
+    }
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for DeserializeList<T> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        /// What `deserialize_seq` calls back with the elements.
+        struct ListVisitor<T>(PhantomData<T>);
+
+        impl<'de, T: Deserialize<'de>> Visitor<'de> for ListVisitor<T> {
+            type Value = DeserializeList<T>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a sequence")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(
+                self,
+                mut seq: A,
+            ) -> std::result::Result<Self::Value, A::Error> {
+                // the length is what the input says, so it isn’t trusted with an allocation
+                let mut values = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(1024));
+                while let Some(value) = seq.next_element()? {
+                    values.push(value);
+                }
+                Ok(DeserializeList(List::from(values)))
+            }
+        }
+
+        deserializer.deserialize_seq(ListVisitor(PhantomData))
+    }
+}
+
+/// # Serializing a Type of Another Crate
+/// The `Box<T>` lesson’s `List` is in `ir-smart-pointers`, and doesn’t derive anything. Deriving
+/// would write it as nested `Cons`es, `{"Cons":[1,{"Cons":[2,"Nil"]}]}`, one level deeper for
+/// each element; as a sequence, `[1,2]`, it is what a reader expects, and it round-trips to
+/// the same list. A sequence is written element by element, and read by a `Visitor`, whose
+/// `visit_seq` the format calls with the elements it finds, and whose `expecting` the error
+/// names when it finds something else.
+fn serializing_a_type_of_another_crate() -> Result<()> {
+    Ok({
+        span!("serializing_a_type_of_another_crate");
+        header!("Serializing a Type of Another Crate");
+
+        let list = List::from(vec![1, 2, 3]);
+        let json = serde_json::to_string(&SerializeList(&list))?;
+        println!("{list} as JSON is {json}"); // 1 -> 2 -> 3 -> Nil as JSON is [1,2,3]
+
+        let back = serde_json::from_str::<DeserializeList<i32>>(&json)?.into_inner();
+        println!("and back: {back}"); // and back: 1 -> 2 -> 3 -> Nil
+
+        let error = serde_json::from_str::<DeserializeList<i32>>("{}").err();
+        println!("{}", error.map(|e| e.to_string()).unwrap_or_default());
+        // invalid type: map, expected a sequence at line 1 column 0
+    })
+}
+
+/// # JSON and a Binary Format
+/// The same `Serialize` writes JSON, for people and for other languages, or `bincode`, for Rust
+/// programs: the bytes of each value, little-endian, with a length before each sequence and
+/// string, and no names, as the reader is known to expect the same type. It is smaller and
+/// faster, and can’t be read without knowing the type.
+fn json_and_a_binary_format() -> Result<()> {
+    Ok({
+        span!("json_and_a_binary_format");
+        header!("JSON and a Binary Format");
+
+        let progress = Progress {
+            lesson: String::from("lambda"),
+            completed: true,
+            score: Some(90),
+        };
+        let json = serde_json::to_vec(&progress)?;
+        let binary = bincode::serialize(&progress).map_err(Error::other)?;
+        println!(
+            "JSON: {} bytes, bincode: {} bytes",
+            json.len(),
+            binary.len()
+        );
+        // JSON: 47 bytes, bincode: 17 bytes
+        println!("{binary:?}"); // [6, 0, 0, 0, 0, 0, 0, 0, 108, 97, 109, 98, 100, 97, 1, 1, 90]
+
+        let back: Progress = bincode::deserialize(&binary).map_err(Error::other)?;
+        assert_eq!(back, progress);
+
+        let list = List::from(vec![1u8, 2, 3]);
+        let binary = bincode::serialize(&SerializeList(&list)).map_err(Error::other)?;
+        let back: DeserializeList<u8> = bincode::deserialize(&binary).map_err(Error::other)?;
+        println!("{} in {} bytes", back.into_inner(), binary.len());
+        // 1 -> 2 -> 3 -> Nil in 11 bytes
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_list_round_trips_through_json_and_bincode() {
+        let list = List::from(vec![String::from("a"), String::from("b")]);
+        let json = serde_json::to_string(&SerializeList(&list)).unwrap();
+        assert_eq!(json, r#"["a","b"]"#);
+        let back: DeserializeList<String> = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.into_inner().to_string(), list.to_string());
+
+        let binary = bincode::serialize(&SerializeList(&list)).unwrap();
+        let back: DeserializeList<String> = bincode::deserialize(&binary).unwrap();
+        assert_eq!(back.into_inner().to_string(), list.to_string());
+
+        let empty: DeserializeList<u8> = serde_json::from_str("[]").unwrap();
+        assert!(empty.into_inner().is_empty());
+    }
+
+    #[test]
+    fn missing_fields_take_their_defaults_and_skipped_ones_are_not_written() {
+        let settings: Settings =
+            serde_json::from_str(r#"{"lesson-id":"patterns","colour":true,"out":"a.md"}"#).unwrap();
+        assert_eq!(settings.verbosity, Verbosity::Normal);
+        assert_eq!(settings.out.as_deref(), Some("a.md"));
+
+        let json = serde_json::to_string(&Settings {
+            runs: 9,
+            ..settings
+        })
+        .unwrap();
+        assert!(!json.contains("runs"));
+        assert!(json.contains(r#""out":"a.md""#));
+        assert!(serde_json::from_str::<Settings>(r#"{"colour":true}"#).is_err());
+    }
+
+    #[test]
+    fn enums_round_trip_through_both_formats() {
+        let event = Event::Completed {
+            lesson: String::from("macros"),
+            seconds: 1.25,
+        };
+        let json = serde_json::to_string(&event).unwrap();
+        assert_eq!(serde_json::from_str::<Event>(&json).unwrap(), event);
+        let binary = bincode::serialize(&event).unwrap();
+        assert_eq!(bincode::deserialize::<Event>(&binary).unwrap(), event);
+    }
+}
@@ -0,0 +1,197 @@
+//! # Storing UTF-8 Encoded Text with Strings
+//! Rust has only one string type in the core language, which is the string slice `str` that is
+//! usually seen in its borrowed form `&str`. The `String` type, which is provided by Rust’s
+//! standard library rather than coded into the core language, is a growable, mutable, owned,
+//! UTF-8 encoded string type.
+//!
+//! Under the hood, a `String` is a wrapper over a `Vec<u8>` that promises the bytes are always
+//! valid UTF-8, and a `&str` is a fat pointer to some of those bytes (see `memory/slices.rs`).
+//! Almost every surprising rule about strings follows from that one promise.
+
+use std::{io::Result, mem::size_of, ops::Deref};
+
+pub fn strings() -> Result<()> {
+    Ok({
+        indexing_into_strings()?;
+        bytes_chars_and_grapheme_clusters()?;
+        how_a_string_grows()?;
+        concatenation_with_push_str_plus_and_format()?;
+        converting_through_deref()?;
+    })
+}
+
+/// # Indexing into Strings
+/// In many other programming languages, accessing individual characters in a string by
+/// referencing them by index is a valid and common operation. However, if you try to access
+/// parts of a `String` using indexing syntax in Rust, you’ll get an error:
+/// ```compile_fail
+/// let s1 = String::from("hello");
+/// let h = s1[0];
+/// ```
+/// the type `str` cannot be indexed by `{integer}`
+///
+/// A UTF-8 character takes one to four bytes, so “the character at index 0” can’t be found in
+/// constant time, and “the byte at index 0” may be only part of a character. Rather than pick
+/// an answer that is either slow or surprising, Rust makes you say which one you mean. Slicing
+/// with a byte range is allowed, but panics when a range boundary falls inside a character.
+fn indexing_into_strings() -> Result<()> {
+    Ok({
+        span!("indexing_into_strings");
+        header!("Indexing into Strings");
+
+        let hello = String::from("Здравствуйте");
+        println!(
+            "characters = {}, bytes = {}",
+            hello.chars().count(),
+            hello.len()
+        );
+        // characters = 12, bytes = 24: each Cyrillic letter takes two bytes
+
+        println!("first byte = {}", hello.as_bytes()[0]); // first byte = 208, half of `З`
+        println!("first char = {:?}", hello.chars().next()); // first char = Some('З')
+        println!("first two bytes = {}", &hello[0..2]); // first two bytes = З
+
+        // &hello[0..1] would panic: byte index 1 is not a char boundary
+        println!("is_char_boundary(1) = {}", hello.is_char_boundary(1)); // is_char_boundary(1) = false
+    })
+}
+
+/// # Bytes, Scalar Values, and Grapheme Clusters
+/// There are three relevant ways to look at strings from Rust’s perspective: as bytes, scalar
+/// values (`char`s), and grapheme clusters (the closest thing to what we would call _letters_).
+/// The standard library gives us the first two with `bytes()` and `chars()`. Grapheme clusters
+/// depend on ever-evolving Unicode rules, so getting them is left to crates such as
+/// `unicode-segmentation`. One user-visible “letter” can be several `char`s:
+fn bytes_chars_and_grapheme_clusters() -> Result<()> {
+    Ok({
+        span!("bytes_chars_and_grapheme_clusters");
+        header!("Bytes, Scalar Values, and Grapheme Clusters");
+
+        // `é` written as one precomposed scalar value, and as `e` followed by a combining accent
+        let precomposed = "caf\u{e9}";
+        let combining = "cafe\u{301}";
+
+        for s in [precomposed, combining] {
+            println!(
+                "{s}: bytes = {:?}, chars = {:?}",
+                s.bytes().collect::<Vec<_>>(),
+                s.chars().collect::<Vec<_>>()
+            );
+        }
+        // café: bytes = [99, 97, 102, 195, 169], chars = ['c', 'a', 'f', 'é']
+        // café: bytes = [99, 97, 102, 101, 204, 129], chars = ['c', 'a', 'f', 'e', '\u{301}']
+
+        // Both print as four letters, yet they are different strings
+        println!("equal = {}", precomposed == combining); // equal = false
+
+        // Reversing by `char` separates the accent from its letter
+        println!("reversed = {}", combining.chars().rev().collect::<String>());
+
+        // `char` itself is always four bytes, no matter how many bytes it takes in UTF-8
+        println!("size of char = {}", size_of::<char>()); // size of char = 4
+        println!("'é'.len_utf8() = {}", 'é'.len_utf8()); // 'é'.len_utf8() = 2
+    })
+}
+
+/// # How a `String` Grows
+/// Being a `Vec<u8>` inside, a `String` has a length and a capacity in bytes and reallocates
+/// the same way (see `collections/vec_internals.rs`).
+fn how_a_string_grows() -> Result<()> {
+    Ok({
+        span!("how_a_string_grows");
+        header!("How a String Grows");
+
+        println!("size of String = {}", size_of::<String>()); // size of String = 24, like a Vec
+        println!("size of &str   = {}", size_of::<&str>()); //   size of &str   = 16, a fat pointer
+
+        let mut s = String::new();
+        let mut capacity = s.capacity();
+        for word in ["grow", "ing", " one", " push", " at", " a", " time"] {
+            s.push_str(word);
+            if s.capacity() != capacity {
+                capacity = s.capacity();
+                println!("len = {:2}, capacity = {:2}: {s:?}", s.len(), capacity);
+            }
+        }
+
+        let mut t = String::with_capacity(32);
+        t.push_str("no reallocation needed");
+        println!("len = {}, capacity = {}", t.len(), t.capacity()); // len = 22, capacity = 32
+    })
+}
+
+/// # Concatenation with `push_str`, `+`, and `format!`
+/// - `push_str` appends a `&str` to a `String` we own and can mutate, reusing its buffer.
+/// - `+` calls `fn add(self, s: &str) -> String`: it _takes ownership_ of the left-hand
+///   `String`, appends to its buffer, and hands it back. The right-hand side is only borrowed,
+///   which is why it has to be a `&str` (a `&String` deref-coerces to one).
+/// - `format!` borrows all of its arguments and builds a brand-new `String`, which is the most
+///   readable choice for anything more than two pieces.
+fn concatenation_with_push_str_plus_and_format() -> Result<()> {
+    Ok({
+        span!("concatenation_with_push_str_plus_and_format");
+        header!("Concatenation with push_str, +, and format!");
+
+        let mut s = String::from("foo");
+        s.push_str("bar");
+        s.push('!');
+        println!("push_str: {s}"); // push_str: foobar!
+
+        let s1 = String::from("Hello, ");
+        let s2 = String::from("world!");
+        // note s1 has been moved here and can no longer be used
+        let s3 = s1 + &s2;
+        // borrow of moved value: `s1`
+        // println!("{s1}"); // value borrowed here after move
+        println!("+: {s3}, and s2 is still usable: {s2}"); // +: Hello, world!, and s2 is still usable: world!
+
+        let tic = String::from("tic");
+        let tac = String::from("tac");
+        let toe = String::from("toe");
+        let game = format!("{tic}-{tac}-{toe}");
+        println!("format!: {game}, nothing was moved: {tic} {tac} {toe}");
+    })
+}
+
+/// # Converting Through `Deref<Target = str>`
+/// `String` implements `Deref<Target = str>`, exactly like `MyBox<T>` implements
+/// `Deref<Target = T>` in `traits/deref.rs`. So every `str` method is available on a `String`,
+/// and `&String` coerces to `&str` wherever a `&str` is expected. Going the other way, from a
+/// borrowed `&str` to an owned `String`, always allocates, and there are several spellings of it.
+fn takes_str(s: &str) -> usize {
+    s.len()
+}
+fn converting_through_deref() -> Result<()> {
+    Ok({
+        span!("converting_through_deref");
+        header!("Converting Through Deref<Target = str>");
+
+        let owned = String::from("Rust");
+
+        // &String -> &str, by deref coercion and by hand
+        println!("coerced = {}", takes_str(&owned));
+        println!("deref   = {}", takes_str(owned.deref()));
+        println!("as_str  = {}", takes_str(owned.as_str()));
+        println!("slice   = {}", takes_str(&owned[..]));
+
+        // `to_uppercase` is a `str` method, found on `String` through auto-deref
+        println!("to_uppercase = {}", owned.to_uppercase());
+
+        // &str -> String: all of these allocate a copy
+        let a: String = "Rust".to_string();
+        let b: String = String::from("Rust");
+        let c: String = "Rust".to_owned();
+        let d: String = "Rust".into();
+        println!(
+            "all equal = {}",
+            [&a, &b, &c, &d].iter().all(|s| **s == owned)
+        );
+
+        // String -> Box<str>: drops the spare capacity, leaving just a pointer and a length
+        let boxed: Box<str> = owned.into_boxed_str();
+        println!(
+            "boxed = {boxed}, size of Box<str> = {}",
+            size_of::<Box<str>>()
+        ); // size of Box<str> = 16
+    })
+}
@@ -0,0 +1,319 @@
+//! # Testing
+//! `cargo test` builds the crate with `cfg(test)` set, and runs every function marked `#[test]`,
+//! each on a thread of its own: a test passes if it returns, or returns `Ok`, and fails if it
+//! panics, as a failed `assert!` does. There are three places to put them:
+//! - _unit tests_ go next to the code they test, in a `mod tests` marked `#[cfg(test)]`, which
+//!   other builds leave out, with whatever helpers only the tests need; being in the module, they
+//!   can use its private items as well. The tests at the end of this file are the lesson’s own,
+//!   and run with every other test, in CI;
+//! - _integration tests_ are the files in `tests/`, each a crate of its own, which sees only
+//!   what the library makes `pub`, as a user would: `tests/testing.rs` puts `Reminder` to use
+//!   with the smart pointer chapter’s `LimitTracker`, and `tests/lessons.rs` runs the lessons;
+//! - _doc tests_ are the examples in doc comments, such as the one in `lib.rs`, which are
+//!   compiled and run as well, so that they stay true.
+//!
+//! `#[should_panic(expected = "...")]` is a test that passes only if it panics, with a message
+//! that contains the text, as the `RefCell<T>` lesson’s test of two `borrow_mut`s does, and as
+//! `a_zero_interval_panics` below does.
+
+use crate::intermediate_rust::smart_pointers::Messenger;
+use std::{
+    cell::{Cell, Ref, RefCell},
+    io::Result,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+pub fn testing() -> Result<()> {
+    Ok({
+        test_doubles_behind_a_trait()?;
+        matching_the_shape_of_a_result()?;
+    })
+}
+
+/// What time it is, for code that needs to know, and for tests to decide.
+pub trait Clock {
+    /// The time since an epoch, which only has to be the same for every call.
+    fn now(&self) -> Duration;
+}
+
+/// The time of the system, since the Unix epoch.
+pub struct SystemClock;
+impl Clock for SystemClock {
+    fn now(&self) -> Duration {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+    }
+}
+
+/// A `Clock` that only moves when it is told to: a _fake_, which works, but in a way only fit
+/// for a test.
+#[derive(Default)]
+pub struct FakeClock {
+    now: Cell<Duration>,
+}
+impl FakeClock {
+    pub fn new() -> FakeClock {
+        FakeClock::default()
+    }
+
+    pub fn advance(&self, by: Duration) {
+        self.now.set(self.now.get() + by);
+    }
+}
+impl Clock for FakeClock {
+    fn now(&self) -> Duration {
+        self.now.get()
+    }
+}
+
+/// Records the calls made to it, for a test to check afterwards: a _mock_. It is the
+/// `RefCell<T>` lesson’s `MockMessenger`, with the kind of call a type parameter, so that one
+/// `Recorder` does for any trait: `Recorder<String>` is a `Messenger`, and the tests record
+/// tuples of what another trait’s methods are called with.
+pub struct Recorder<T> {
+    calls: RefCell<Vec<T>>,
+}
+impl<T> Recorder<T> {
+    pub fn new() -> Recorder<T> {
+        Recorder {
+            calls: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Records `call`; the trait methods take `&self`, so the calls are in a `RefCell`.
+    pub fn record(&self, call: T) {
+        self.calls.borrow_mut().push(call);
+    }
+
+    pub fn calls(&self) -> Ref<'_, [T]> {
+        Ref::map(self.calls.borrow(), Vec::as_slice)
+    }
+
+    /// The calls recorded so far, which are forgotten, for the next check to start afresh.
+    pub fn take(&self) -> Vec<T> {
+        self.calls.take()
+    }
+}
+impl<T> Default for Recorder<T> {
+    fn default() -> Recorder<T> {
+        Recorder::new()
+    }
+}
+impl Messenger for Recorder<String> {
+    fn send(&self, msg: &str) {
+        self.record(msg.to_string());
+    }
+}
+
+/// What `Reminder::remind` did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reminded {
+    Sent,
+    /// The last reminder was too recent; the next may be sent after `wait`.
+    TooSoon {
+        wait: Duration,
+    },
+}
+
+/// Sends reminders through a `Messenger`, but not more often than once every `interval`, by the
+/// time of a `Clock`. Which messenger and which clock is for the caller to say, so a test can
+/// hand it a `Recorder` and a `FakeClock`, rather than wait an hour of real time.
+pub struct Reminder<'a, C: Clock, M: Messenger> {
+    clock: &'a C,
+    messenger: &'a M,
+    interval: Duration,
+    last: Cell<Option<Duration>>,
+}
+impl<'a, C: Clock, M: Messenger> Reminder<'a, C, M> {
+    /// # Panics
+    /// If `interval` is zero, which would be no limit at all.
+    pub fn new(clock: &'a C, messenger: &'a M, interval: Duration) -> Reminder<'a, C, M> {
+        assert!(
+            !interval.is_zero(),
+            "a reminder needs an interval, not zero"
+        );
+        Reminder {
+            clock,
+            messenger,
+            interval,
+            last: Cell::new(None),
+        }
+    }
+
+    pub fn remind(&self, text: &str) -> Reminded {
+        let now = self.clock.now();
+        if let Some(last) = self.last.get() {
+            let next = last + self.interval;
+            if now < next {
+                return Reminded::TooSoon { wait: next - now };
+            }
+        }
+        self.messenger.send(text);
+        self.last.set(Some(now));
+        Reminded::Sent
+    }
+}
+
+/// # Test Doubles Behind a Trait
+/// Code that sends messages, or reads the clock, is hard to test as it is: the messages go out,
+/// and the clock can’t be made to move. If it takes them through a trait, as `Reminder` takes a
+/// `Messenger` and a `Clock`, a test hands it a _test double_ instead: a `FakeClock` the test
+/// moves along, and a `Recorder` that keeps the messages for the test to look at. The program
+/// hands it the real ones, `SystemClock` and whichever messenger it sends with.
+fn test_doubles_behind_a_trait() -> Result<()> {
+    Ok({
+        span!("test_doubles_behind_a_trait");
+        header!("Test Doubles Behind a Trait");
+
+        let (clock, recorder) = (FakeClock::new(), Recorder::new());
+        let reminder = Reminder::new(&clock, &recorder, Duration::from_secs(60 * 60));
+        reminder.remind("stretch");
+        clock.advance(Duration::from_secs(10 * 60));
+        reminder.remind("stretch again"); // too soon
+        clock.advance(Duration::from_secs(50 * 60));
+        reminder.remind("drink some water");
+        println!("{:?}", recorder.take()); // ["stretch", "drink some water"]
+    })
+}
+
+/// # Matching the Shape of a Result
+/// `assert_eq!` needs the whole of the expected value, which is more than a test may know, or
+/// care about. `matches!` checks a value against a pattern, with a guard, and the tests below
+/// have an `assert_matches!` of their own, which prints the value when it doesn’t match: the
+/// standard library’s isn’t stable yet.
+fn matching_the_shape_of_a_result() -> Result<()> {
+    Ok({
+        span!("matching_the_shape_of_a_result");
+        header!("Matching the Shape of a Result");
+
+        let (clock, recorder) = (FakeClock::new(), Recorder::new());
+        let reminder = Reminder::new(&clock, &recorder, Duration::from_secs(60));
+        let first = reminder.remind("stand up");
+        let second = reminder.remind("stand up");
+        println!("{first:?}, then {second:?}"); // Sent, then TooSoon { wait: 60s }
+        let soon = matches!(second, Reminded::TooSoon { wait } if wait <= Duration::from_secs(60));
+        println!("at most a minute to wait: {soon}"); // at most a minute to wait: true
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Asserts that `$value` matches `$pattern`, and its guard, if it has one, and shows the
+    /// value if it doesn’t. A macro only the tests use is defined in `mod tests`, and so only
+    /// exists with `cfg(test)`, as does everything else in here.
+    macro_rules! assert_matches {
+        ($value:expr, $pattern:pat $(if $guard:expr)? $(,)?) => {
+            match $value {
+                $pattern $(if $guard)? => {}
+                ref value => panic!(
+                    "`{value:?}` doesn't match `{}`",
+                    stringify!($pattern $(if $guard)?)
+                ),
+            }
+        };
+    }
+
+    /// A test’s starting point: a clock at zero, and a recorder that has recorded nothing, with
+    /// a `Reminder` of them a call away. A _fixture_ is set up by every test that needs one,
+    /// rather than shared, so that no test depends on what another did.
+    struct Fixture {
+        clock: FakeClock,
+        recorder: Recorder<String>,
+    }
+    impl Fixture {
+        fn new() -> Fixture {
+            Fixture {
+                clock: FakeClock::new(),
+                recorder: Recorder::new(),
+            }
+        }
+
+        fn reminder(&self, minutes: u64) -> Reminder<'_, FakeClock, Recorder<String>> {
+            Reminder::new(&self.clock, &self.recorder, minutes_of(minutes))
+        }
+    }
+
+    fn minutes_of(minutes: u64) -> Duration {
+        Duration::from_secs(minutes * 60)
+    }
+
+    #[test]
+    fn a_reminder_is_sent_at_most_once_an_interval() {
+        let fixture = Fixture::new();
+        let reminder = fixture.reminder(30);
+        assert_eq!(reminder.remind("a"), Reminded::Sent);
+        fixture.clock.advance(minutes_of(29));
+        assert_matches!(reminder.remind("b"), Reminded::TooSoon { .. });
+        fixture.clock.advance(minutes_of(1));
+        assert_eq!(reminder.remind("c"), Reminded::Sent);
+        assert_eq!(*fixture.recorder.calls(), ["a", "c"]);
+    }
+
+    #[test]
+    fn too_soon_says_how_long_to_wait() {
+        let fixture = Fixture::new();
+        let reminder = fixture.reminder(10);
+        reminder.remind("a");
+        fixture.clock.advance(minutes_of(4));
+        assert_matches!(
+            reminder.remind("b"),
+            Reminded::TooSoon { wait } if wait == minutes_of(6)
+        );
+        assert_eq!(fixture.recorder.take(), ["a"]);
+        assert!(fixture.recorder.calls().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "needs an interval")]
+    fn a_zero_interval_panics() {
+        Fixture::new().reminder(0);
+    }
+
+    #[test]
+    #[should_panic(expected = "doesn't match `Reminded::Sent`")]
+    fn assert_matches_fails_with_the_value() {
+        assert_matches!(
+            Reminded::TooSoon {
+                wait: Duration::ZERO
+            },
+            Reminded::Sent
+        );
+    }
+
+    /// A second trait, to record the calls to: the `Recorder` records them whatever they are.
+    trait Storage {
+        fn put(&self, key: &str, value: u32);
+    }
+    impl Storage for Recorder<(String, u32)> {
+        fn put(&self, key: &str, value: u32) {
+            self.record((key.to_string(), value));
+        }
+    }
+
+    #[test]
+    fn a_recorder_records_the_calls_of_any_trait() {
+        let storage = Recorder::new();
+        storage.put("a", 1);
+        storage.put("b", 2);
+        assert_eq!(
+            storage.take(),
+            [(String::from("a"), 1), (String::from("b"), 2)]
+        );
+    }
+
+    #[test]
+    fn the_lesson_runs() {
+        testing().unwrap();
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn the_system_clock_goes_forward() {
+        let before = SystemClock.now();
+        assert!(SystemClock.now() >= before);
+        assert!(before > Duration::ZERO);
+    }
+}
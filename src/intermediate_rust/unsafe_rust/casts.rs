@@ -0,0 +1,228 @@
+//! # Casts, Conversions and `transmute`
+//! Rust has four ways to turn a value of one type into another, from the safest to the least:
+//! - `From` and `TryFrom`, which only exist where the conversion makes sense, and return an
+//!   `Err` where it may not;
+//! - `as`, between numbers, and between pointers, which always succeeds, and may quietly give
+//!   a different value;
+//! - a pointer cast, which is safe, and reading through the pointer, which is not;
+//! - `mem::transmute`, which takes the bits of a value as they are and calls them another type,
+//!   and checks nothing but that both are the same size.
+//!
+//! Most `transmute`s have a safe function that does the same, and says what it means.
+
+use std::{
+    io::Result,
+    mem::{align_of, size_of, size_of_val},
+    slice,
+};
+
+pub fn casts() -> Result<()> {
+    Ok({
+        as_casts()?;
+        from_and_try_from()?;
+        pointer_casts()?;
+        transmute()?;
+        safe_alternatives()?;
+    })
+}
+
+/// # `as` Casts
+/// Between integers, `as` keeps the low bits, so a value that doesn’t fit wraps, and a negative
+/// one is read as a large positive one. From a float to an integer, it rounds toward zero and
+/// saturates, and `NaN` is 0; from a large integer to a float, it rounds to the nearest float.
+/// None of these is an error, or even a warning; clippy has lints for some.
+#[allow(clippy::cast_nan_to_int)]
+fn as_casts() -> Result<()> {
+    Ok({
+        span!("as_casts");
+        header!("`as` Casts");
+
+        println!("300i32 as u8 = {}", 300i32 as u8); // 300i32 as u8 = 44
+        println!("-1i32 as u32 = {}", -1i32 as u32); // -1i32 as u32 = 4294967295
+        println!("300.7f32 as u8 = {}", 300.7f32 as u8); // 300.7f32 as u8 = 255
+        println!("f32::NAN as u8 = {}", f32::NAN as u8); // f32::NAN as u8 = 0
+        println!("u64::MAX as f32 = {}", u64::MAX as f32); // u64::MAX as f32 = 18446744000000000000
+    })
+}
+
+/// # `From` and `TryFrom`
+/// `From` is only implemented where every value converts, such as from `u8` to `u16`, and not
+/// from `u16` to `u8`, or from `i32` to `u32`; `TryFrom` is for those, and returns an error for
+/// the values that don’t fit. `into` and `try_into` are the same, seen from the other side.
+fn from_and_try_from() -> Result<()> {
+    Ok({
+        span!("from_and_try_from");
+        header!("`From` and `TryFrom`");
+
+        println!("u16::from(200u8) = {}", u16::from(200u8)); // u16::from(200u8) = 200
+        println!("u8::try_from(44i32) = {:?}", u8::try_from(44i32)); // u8::try_from(44i32) = Ok(44)
+        match u8::try_from(300i32) {
+            Ok(byte) => println!("300 fits in a u8: {byte}"),
+            Err(e) => println!("300 doesn't fit in a u8: {e}"),
+        } // 300 doesn't fit in a u8: out of range integral type conversion attempted
+        let index: usize = 7u32.try_into().map_err(std::io::Error::other)?;
+        println!("index = {index}"); // index = 7
+    })
+}
+
+/// # Pointer Casts
+/// `as`, or `cast`, turns a `*const u32` into a `*const u8`, safely: the pointer is the same,
+/// only what it is read as changes. Reading through it is where the promises are: the bytes
+/// have to be initialized, and a valid value of the new type, and the pointer aligned for it.
+/// A `u8` is aligned anywhere, and a `u32` only at a multiple of 4, so a pointer into the middle
+/// of one is read with `read_unaligned`.
+fn pointer_casts() -> Result<()> {
+    Ok({
+        span!("pointer_casts");
+        header!("Pointer Casts");
+
+        let numbers = [0x0403_0201u32, 0x0807_0605];
+        let bytes = numbers.as_ptr().cast::<u8>();
+        // SAFETY: `bytes` points to 8 initialized bytes, and any byte is a valid `u8`
+        let first = unsafe { *bytes };
+        println!("the first byte = {first}"); // the first byte = 1, on a little-endian machine
+
+        // SAFETY: `bytes + 2..bytes + 6` is in `numbers`; `read_unaligned` doesn’t need alignment
+        let middle = unsafe { bytes.add(2).cast::<u32>().read_unaligned() };
+        println!("bytes 2..6 as a u32 = {middle:#010x}");
+        // bytes 2..6 as a u32 = 0x06050403, on a little-endian machine
+        println!("align_of::<u32>() = {}", align_of::<u32>()); // align_of::<u32>() = 4
+    })
+}
+
+/// # `transmute`
+/// `mem::transmute::<A, B>` copies the bits of an `A` into a `B`. It won’t compile unless the
+/// two are the same size:
+/// ```compile_fail
+/// let bytes: [u8; 3] = unsafe { std::mem::transmute(1u32) };
+/// ```
+/// cannot transmute between types of different sizes, or dependently-sized types
+///
+/// It is sound when the bits are a valid `B`: every bit pattern is a valid `u32`, `f32` or
+/// `[u8; 4]`, so those are fine. It is undefined behavior when they aren’t, a `bool` that is
+/// `2`, or a reference that is null, and when the layouts only look the same: a `Vec<u32>` and a
+/// `Vec<u8>` are both three words, but their lengths and capacities count different things.
+///
+/// The two below are sound, and the compiler warns about them all the same, naming the safe
+/// function that does what each does.
+#[allow(unnecessary_transmutes)]
+fn transmute() -> Result<()> {
+    Ok({
+        span!("transmute");
+        header!("`transmute`");
+
+        // SAFETY: any 32 bits are a valid `u32`
+        let bits: u32 = unsafe { std::mem::transmute(1.0f32) };
+        println!("1.0f32 as bits = {bits:#010x}"); // 1.0f32 as bits = 0x3f800000
+
+        // SAFETY: any 4 bytes are a valid `u32`
+        let number: u32 = unsafe { std::mem::transmute([1u8, 0, 0, 0]) };
+        println!("[1, 0, 0, 0] as a u32 = {number}");
+        // [1, 0, 0, 0] as a u32 = 1, on a little-endian machine
+
+        // UB: 2 isn’t a `bool`, which is only ever 0 or 1
+        // let b: bool = unsafe { std::mem::transmute(2u8) };
+        // UB: a reference is never null
+        // let r: &u32 = unsafe { std::mem::transmute(0usize) };
+        // UB: a `&mut` that aliases a `&`, which promised nobody would write
+        // let m: &mut u32 = unsafe { std::mem::transmute(&number) };
+        // UB: the length and the capacity are of `u32`s, not of bytes
+        // let v: Vec<u8> = unsafe { std::mem::transmute(vec![1u32, 2]) };
+    })
+}
+
+/// Plain old data: a type that is valid for any bits at all, with no padding, so that its bytes
+/// can be read, and any bytes read as one, as `bytemuck::Pod` is.
+///
+/// # Safety
+/// Every bit pattern of `size_of::<Self>()` bytes has to be a valid `Self`, with no padding.
+unsafe trait Pod: Copy + 'static {}
+
+// SAFETY: integers and floats are valid for any bits, and have no padding
+unsafe impl Pod for u8 {}
+// SAFETY: as above
+unsafe impl Pod for u16 {}
+// SAFETY: as above
+unsafe impl Pod for u32 {}
+// SAFETY: as above
+unsafe impl Pod for f32 {}
+
+/// The bytes of `value`.
+fn bytes_of<T: Pod>(value: &T) -> &[u8] {
+    // SAFETY: a `Pod` is initialized, without padding, and any byte is a valid `u8`
+    unsafe { slice::from_raw_parts((value as *const T).cast(), size_of::<T>()) }
+}
+
+/// `from` as a slice of `B`s, if it is aligned for a `B`, and a whole number of them long.
+fn cast_slice<A: Pod, B: Pod>(from: &[A]) -> Option<&[B]> {
+    let bytes = size_of_val(from);
+    let aligned = from.as_ptr().cast::<B>().is_aligned();
+    if size_of::<B>() == 0 || !bytes.is_multiple_of(size_of::<B>()) || !aligned {
+        return None;
+    }
+    // SAFETY: the bytes are initialized, any of them are a `B`, and they are aligned for one
+    Some(unsafe { slice::from_raw_parts(from.as_ptr().cast(), bytes / size_of::<B>()) })
+}
+
+/// # Safe Alternatives
+/// The standard library names the common `transmute`s: `f32::to_bits` and `from_bits`, and
+/// `to_ne_bytes`, `to_le_bytes` and `to_be_bytes`, whose names say which byte order they use,
+/// where `transmute` takes the machine’s. For the rest, `bytemuck` and `zerocopy` have an
+/// `unsafe` trait, implemented for the types that are valid for any bits, and safe functions
+/// over it, which check sizes and alignments as they go. `Pod`, `bytes_of` and `cast_slice`
+/// here are the same idea, small.
+fn safe_alternatives() -> Result<()> {
+    Ok({
+        span!("safe_alternatives");
+        header!("Safe Alternatives");
+
+        println!("1.0f32.to_bits() = {:#010x}", 1.0f32.to_bits()); // 1.0f32.to_bits() = 0x3f800000
+        let number = u32::from_le_bytes([1, 0, 0, 0]);
+        println!("u32::from_le_bytes([1, 0, 0, 0]) = {number}"); // u32::from_le_bytes(...) = 1
+        println!(
+            "bytes_of(&1u32) == 1u32.to_ne_bytes(): {}",
+            bytes_of(&1u32) == 1u32.to_ne_bytes()
+        ); // bytes_of(&1u32) == 1u32.to_ne_bytes(): true
+
+        let numbers = [1u32, 2];
+        let bytes: &[u8] = cast_slice(&numbers).unwrap_or_default();
+        println!("{} bytes", bytes.len()); // 8 bytes
+        let back: Option<&[u32]> = cast_slice(bytes);
+        println!("back: {back:?}"); // back: Some([1, 2])
+        let misaligned: Option<&[u32]> = cast_slice(&bytes[1..5]);
+        println!("misaligned: {misaligned:?}"); // misaligned: None
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn as_wraps_and_saturates_where_try_from_fails() {
+        assert_eq!(300i32 as u8, 44);
+        assert_eq!(-1.5f32 as u8, 0);
+        assert!(u8::try_from(300i32).is_err());
+        assert_eq!(u8::try_from(255i32), Ok(255));
+    }
+
+    #[test]
+    #[allow(unnecessary_transmutes)]
+    fn transmutes_agree_with_their_safe_alternatives() {
+        let bits: u32 = unsafe { std::mem::transmute(-2.5f32) };
+        assert_eq!(bits, (-2.5f32).to_bits());
+        let number: u32 = unsafe { std::mem::transmute([1u8, 2, 3, 4]) };
+        assert_eq!(number, u32::from_ne_bytes([1, 2, 3, 4]));
+        assert_eq!(bytes_of(&0x0102u16), 0x0102u16.to_ne_bytes());
+    }
+
+    #[test]
+    fn cast_slice_checks_lengths_and_alignments() {
+        let numbers = [1u16, 2, 3];
+        let bytes: &[u8] = cast_slice(&numbers).unwrap();
+        assert_eq!(bytes.len(), 6);
+        assert_eq!(cast_slice::<u8, u16>(bytes), Some(&numbers[..]));
+        assert_eq!(cast_slice::<u8, u32>(bytes), None);
+        assert_eq!(cast_slice::<u8, u16>(&bytes[1..3]), None);
+    }
+}
@@ -0,0 +1,133 @@
+//! # Checking Unsafe Code with Miri
+//! Undefined behavior doesn’t have to crash, or even misbehave. A read of freed memory may well
+//! find the value that was there before; the program prints what it should, on this machine,
+//! with this compiler, today. That is what makes it dangerous: nothing tells us it is there.
+//!
+//! Miri is an interpreter for the compiler’s intermediate representation, MIR. It runs the
+//! program one operation at a time, and keeps track of what the compiled program forgets: which
+//! allocation each pointer points into and whether it is still alive, which bytes have been
+//! initialized, and which references are allowed to access which memory. It stops with an
+//! error at the first operation that is undefined behavior:
+//! - a read or write out of bounds, or through a pointer to memory that has been freed,
+//! - a read of uninitialized memory, or of an invalid value, such as a `bool` that is `2`,
+//! - a misaligned access,
+//! - an access that breaks the aliasing rules, like writing through a raw pointer after a
+//!   `&mut` to the same place has been created and used (_Stacked Borrows_),
+//! - a data race between threads,
+//!
+//! and, when the program ends, it reports the memory that was leaked.
+//! ```text
+//! rustup +nightly component add miri
+//! cargo +nightly miri-test                          # the unit tests, see `.cargo/config.toml`
+//! cargo +nightly miri run -- unsafe-rust non-null   # a lesson
+//! ```
+//! Miri runs in isolation from the host, without files, sockets or the wall clock; code that
+//! needs them checks `cfg!(miri)`, see `utils/miri.rs`. It only checks the code that runs, so
+//! it is only as good as the tests it runs; `unsafe_rust/fuzzing.rs` is about finding more.
+
+use std::{io::Result, mem::MaybeUninit};
+
+pub fn miri() -> Result<()> {
+    Ok({
+        a_use_after_free()?;
+        aliasing()?;
+        invalid_values()?;
+    })
+}
+
+/// # A Use After Free
+/// A raw pointer doesn’t keep what it points to alive. Once the owner is dropped, the pointer
+/// dangles: it is still not null, and still compares and prints like any other, but
+/// dereferencing it reads freed memory. Miri stops there:
+/// ```text
+/// error: Undefined Behavior: memory access failed: alloc1234 has been freed, so this pointer
+/// is dangling
+/// ```
+/// No `drop` has to be in sight for this to happen: a pointer into a `Vec<T>`’s buffer dangles
+/// as soon as a `push` has to grow the buffer and moves it.
+fn a_use_after_free() -> Result<()> {
+    Ok({
+        span!("a_use_after_free");
+        header!("A Use After Free");
+
+        let boxed = Box::new(1);
+        let ptr: *const i32 = &*boxed;
+        println!("*ptr = {}", unsafe { *ptr }); // *ptr = 1
+        drop(boxed);
+
+        // UB: `ptr` points to freed memory. This may still print 1.
+        // println!("{}", unsafe { *ptr });
+
+        // looking at the pointer itself is fine; only dereferencing it isn’t
+        println!("ptr is null = {}", ptr.is_null()); // ptr is null = false
+    })
+}
+
+/// # Aliasing
+/// A `&mut T` promises that nothing else accesses the value while it is in use; the compiler
+/// optimizes on that promise. Creating a `&mut` to a place invalidates the raw pointers derived
+/// from earlier references to it, so writing through one of them afterwards breaks the promise,
+/// even though the program prints what we meant:
+/// ```text
+/// error: Undefined Behavior: attempting a write access using <1234> at alloc5678[0x0], but
+/// that tag does not exist in the borrow stack for this location
+/// ```
+fn aliasing() -> Result<()> {
+    Ok({
+        span!("aliasing");
+        header!("Aliasing");
+
+        let mut x = 5;
+        let raw = &mut x as *mut i32;
+
+        // UB: `r` is created, so `raw` is no longer allowed to access `x`
+        // let r = &mut x;
+        // unsafe { *raw += 1 };
+        // *r += 1;
+
+        // the raw pointer is used up before the `&mut` is created
+        unsafe { *raw += 1 };
+        let r = &mut x;
+        *r += 1;
+        println!("x = {x}"); // x = 7
+    })
+}
+
+/// # Invalid Values
+/// Every type has values it may never hold: a `bool` is `0` or `1`, a `char` is never a
+/// surrogate, a reference is never null. Producing such a value is undefined behavior at once,
+/// whether or not it is used, because the compiler assumes it never happens (the _niches_ of
+/// `memory/niche.rs` are built on the assumption). The same goes for reading memory that was
+/// never written, which `MaybeUninit<T>` exists to hold:
+/// ```text
+/// error: Undefined Behavior: constructing invalid value: encountered 0x02, but expected a boolean
+/// ```
+fn invalid_values() -> Result<()> {
+    Ok({
+        span!("invalid_values");
+        header!("Invalid Values");
+
+        // UB: 2 isn't a `bool`
+        // let b: bool = unsafe { std::mem::transmute(2u8) };
+
+        // checking a byte before it becomes a `bool`
+        for byte in [0u8, 1, 2] {
+            let b = match byte {
+                0 => Some(false),
+                1 => Some(true),
+                _ => None,
+            };
+            println!("{byte} as a bool = {b:?}");
+        }
+        // 0 as a bool = Some(false)
+        // 1 as a bool = Some(true)
+        // 2 as a bool = None
+
+        let mut slot = MaybeUninit::<u64>::uninit();
+        // UB: nothing has been written yet
+        // let n = unsafe { slot.assume_init() };
+        slot.write(42);
+        // SAFETY: `slot` was just written
+        println!("slot = {}", unsafe { slot.assume_init() }); // slot = 42
+    })
+}
@@ -0,0 +1,289 @@
+//! # Unsafe Rust
+//! All the code we’ve discussed so far has had Rust’s memory safety guarantees enforced at
+//! compile time. However, Rust has a second language hidden inside it that doesn’t enforce
+//! these memory safety guarantees: it’s called _unsafe Rust_ and works just like regular Rust,
+//! but gives us extra superpowers.
+//!
+//! Unsafe Rust exists because, by nature, static analysis is conservative. When the compiler
+//! tries to determine whether or not code upholds the guarantees, it’s better for it to reject
+//! some valid programs than to accept some invalid programs. Every smart pointer in the
+//! previous chapters is built on a small amount of carefully checked unsafe code.
+//!
+//! The examples in this chapter are meant to be checked with Miri, an interpreter that detects
+//! undefined behavior: `cargo +nightly miri-test`. The `miri` lesson is about what it checks.
+//!
+//! In an `unsafe` block, or an `unsafe fn`, Rust lets us do five things it otherwise won’t:
+//! dereference a raw pointer, call an `unsafe` function, access a mutable `static`, implement
+//! an `unsafe` trait, and read a field of a `union`. Nothing else changes: the borrow checker
+//! still checks references, and the types are still the types. Those superpowers are the
+//! `superpowers` lesson, below; the others build on them.
+
+use std::{
+    cell::UnsafeCell,
+    io::Result,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex, OnceLock, PoisonError,
+    },
+    thread,
+};
+
+pub(super) mod casts;
+// `pub`, for the fuzz targets in `fuzz/` to drive its types
+pub mod fuzzing;
+pub(super) mod miri;
+pub(super) mod non_null;
+
+pub fn superpowers() -> Result<()> {
+    Ok({
+        dereferencing_raw_pointers()?;
+        calling_unsafe_functions()?;
+        safe_abstractions()?;
+        mutable_statics()?;
+        unions()?;
+        unsafe_traits()?;
+    })
+}
+
+/// # Dereferencing Raw Pointers
+/// `*const T` and `*mut T` are allowed to be null, to dangle, and to alias each other, a `*mut`
+/// and a `*const` to the same place at once, which references never are. Creating one is safe;
+/// dereferencing it is the superpower, as only we can know it still points to a live `T`.
+fn dereferencing_raw_pointers() -> Result<()> {
+    Ok({
+        span!("dereferencing_raw_pointers");
+        header!("Dereferencing Raw Pointers");
+
+        let mut num = 5;
+        let r1 = &raw const num;
+        let r2 = &raw mut num;
+        // SAFETY: both point to `num`, which is alive, and no reference to it is in use
+        unsafe {
+            *r2 += 1;
+            println!("*r1 = {}", *r1); // *r1 = 6
+        }
+
+        // an address is a pointer too, but it points into no allocation, so reading it is UB
+        let address = std::ptr::without_provenance::<i32>(0x012345);
+        println!("address.is_null() = {}", address.is_null()); // address.is_null() = false
+    })
+}
+
+/// Returns the element at `index`, without checking that there is one.
+///
+/// # Safety
+/// `index` has to be less than `slice.len()`.
+unsafe fn get_unchecked(slice: &[u32], index: usize) -> u32 {
+    // SAFETY: the caller promised `index` is in bounds
+    unsafe { *slice.as_ptr().add(index) }
+}
+
+/// # Calling Unsafe Functions
+/// An `unsafe fn` has a precondition the compiler can’t check, written in its `# Safety`
+/// section, and calling it is a promise to meet it, made in an `unsafe` block. The body of an
+/// `unsafe fn` needs `unsafe` blocks of its own for its unsafe operations, so each of them is
+/// marked, with a `// SAFETY:` comment saying why it is sound.
+fn calling_unsafe_functions() -> Result<()> {
+    Ok({
+        span!("calling_unsafe_functions");
+        header!("Calling Unsafe Functions");
+
+        let numbers = [10, 20, 30];
+        // SAFETY: 2 < 3
+        let last = unsafe { get_unchecked(&numbers, 2) };
+        println!("last = {last}"); // last = 30
+    })
+}
+
+/// Splits `values` in two at `mid`, as `<[T]>::split_at_mut` does.
+fn split_at_mut(values: &mut [i32], mid: usize) -> (&mut [i32], &mut [i32]) {
+    let len = values.len();
+    let ptr = values.as_mut_ptr();
+    assert!(mid <= len, "mid > len");
+    // SAFETY: `..mid` and `mid..len` are in bounds, and don’t overlap
+    unsafe {
+        (
+            std::slice::from_raw_parts_mut(ptr, mid),
+            std::slice::from_raw_parts_mut(ptr.add(mid), len - mid),
+        )
+    }
+}
+
+/// # Safe Abstractions
+/// Borrowing two halves of a slice mutably, at once, is sound, as they don’t overlap, but the
+/// borrow checker only sees two borrows of the same slice:
+/// ```compile_fail
+/// fn split_at_mut(values: &mut [i32], mid: usize) -> (&mut [i32], &mut [i32]) {
+///     (&mut values[..mid], &mut values[mid..])
+/// }
+/// ```
+/// cannot borrow `*values` as mutable more than once at a time
+///
+/// `split_at_mut` is a safe function around unsafe code: it checks `mid` itself, so that no
+/// caller can make the `unsafe` block unsound. Most unsafe code is hidden that way, behind an
+/// API the compiler checks the use of.
+fn safe_abstractions() -> Result<()> {
+    Ok({
+        span!("safe_abstractions");
+        header!("Safe Abstractions");
+
+        let mut values = [1, 2, 3, 4, 5, 6];
+        let (left, right) = split_at_mut(&mut values, 3);
+        left[0] = 10;
+        right[0] = 40;
+        println!("{values:?}"); // [10, 2, 3, 40, 5, 6]
+    })
+}
+
+static mut CALLS: usize = 0;
+
+/// Counts a call in `CALLS`.
+///
+/// # Safety
+/// No other thread may be accessing `CALLS`.
+unsafe fn count_call() {
+    // SAFETY: the caller promised we have `CALLS` to ourselves
+    unsafe { CALLS += 1 };
+}
+
+/// Held by `mutable_statics` while it uses `CALLS`, as the tests may run the lesson on more than
+/// one thread at once.
+static USING_CALLS: Mutex<()> = Mutex::new(());
+
+static ATOMIC_CALLS: AtomicUsize = AtomicUsize::new(0);
+static GREETING: OnceLock<String> = OnceLock::new();
+
+/// # Mutable Statics, and What to Use Instead
+/// A `static mut` is one place in memory for the whole program, which any thread can read or
+/// write at any time: every access is `unsafe`, as two at once are a data race. Even taking a
+/// reference to one is an error since the 2024 edition, so it is read and written by value, or
+/// through a raw pointer, `&raw mut`. Here a `Mutex` keeps the promise `count_call` asks for,
+/// which a `Mutex<usize>` would do without `unsafe`. There is rarely a reason for one: an
+/// atomic counts without `unsafe`, and a `OnceLock` is set once, by whichever thread gets there
+/// first, and then shared.
+fn mutable_statics() -> Result<()> {
+    Ok({
+        span!("mutable_statics");
+        header!("Mutable Statics, and What to Use Instead");
+
+        let _using = USING_CALLS.lock().unwrap_or_else(PoisonError::into_inner);
+        // SAFETY: no other thread uses `CALLS` while we hold `USING_CALLS`
+        unsafe {
+            let before = CALLS;
+            count_call();
+            count_call();
+            println!("CALLS went up by {}", CALLS - before); // CALLS went up by 2
+        }
+
+        let before = ATOMIC_CALLS.load(Ordering::Relaxed);
+        thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| ATOMIC_CALLS.fetch_add(1, Ordering::Relaxed));
+            }
+        });
+        let after = ATOMIC_CALLS.load(Ordering::Relaxed);
+        println!("ATOMIC_CALLS went up by {}", after - before); // ATOMIC_CALLS went up by 4
+
+        let greeting = GREETING.get_or_init(|| String::from("set once"));
+        println!("GREETING = {greeting}"); // GREETING = set once
+        println!("set again: {:?}", GREETING.set(String::from("twice"))); // set again: Err("twice")
+    })
+}
+
+/// Four bytes that are a `u32` or an `f32`, whichever was written last.
+#[repr(C)]
+union Bits {
+    int: u32,
+    float: f32,
+}
+
+/// # Unions
+/// A `union` is a `struct` whose fields share their memory, so that it only holds one of them at
+/// a time, and doesn’t know which. Writing a field is safe; reading one is `unsafe`, as it is
+/// read as whatever type the field is, whichever was written. A `u32` and an `f32` are valid
+/// for every bit pattern, so reading either is always sound; a `bool` or a reference wouldn’t
+/// be. Unions are mostly for C’s, see `ffi`; `enum`s are unions that remember.
+fn unions() -> Result<()> {
+    Ok({
+        span!("unions");
+        header!("Unions");
+
+        let bits = Bits { float: 1.0 };
+        // SAFETY: any four bytes are a valid `u32`
+        println!("1.0 is {:#010x}", unsafe { bits.int }); // 1.0 is 0x3f800000
+        println!("as to_bits: {:#010x}", 1.0f32.to_bits()); // as to_bits: 0x3f800000
+    })
+}
+
+/// A counter that threads can share, without a lock, as long as only one writes at a time.
+struct Tally {
+    count: UnsafeCell<u32>,
+}
+
+impl Tally {
+    /// Adds one to the count.
+    ///
+    /// # Safety
+    /// No other thread may be using the count.
+    unsafe fn add_one(&self) {
+        // SAFETY: the caller promised we have the count to ourselves
+        unsafe { *self.count.get() += 1 };
+    }
+}
+// SAFETY: `add_one` is the only way to write a shared `Tally`, and its callers take turns
+unsafe impl Sync for Tally {}
+
+/// # Implementing Unsafe Traits
+/// An `unsafe trait` has a promise its implementations make, which code relying on it trusts:
+/// `Send`, that a value can move to another thread, and `Sync`, that a reference to one can be
+/// shared with another. The compiler implements them for types whose fields are all `Send` or
+/// `Sync`; a raw pointer or an `UnsafeCell<T>` isn’t `Sync`, so a type with one isn’t, unless an
+/// `unsafe impl` says it is, and whoever writes it makes sure it is true.
+fn unsafe_traits() -> Result<()> {
+    Ok({
+        span!("unsafe_traits");
+        header!("Implementing Unsafe Traits");
+
+        let tally = Tally {
+            count: UnsafeCell::new(0),
+        };
+        for _ in 0..3 {
+            // each thread is joined before the next is spawned, so no two write at once
+            thread::scope(|scope| {
+                // SAFETY: this is the only thread using `count` until it is joined
+                scope.spawn(|| unsafe { tally.add_one() });
+            });
+        }
+        println!("count = {}", tally.count.into_inner()); // count = 3
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_at_mut_gives_two_halves() {
+        let mut values = [1, 2, 3, 4];
+        let (left, right) = split_at_mut(&mut values, 1);
+        left[0] = 0;
+        right.reverse();
+        assert_eq!(values, [0, 4, 3, 2]);
+
+        let (left, right) = split_at_mut(&mut values, 4);
+        assert_eq!((left.len(), right.len()), (4, 0));
+    }
+
+    #[test]
+    #[should_panic(expected = "mid > len")]
+    fn split_at_mut_checks_mid() {
+        split_at_mut(&mut [1, 2], 3);
+    }
+
+    #[test]
+    fn a_union_reads_the_bits_it_was_written_with() {
+        let bits = Bits { float: -2.5 };
+        assert_eq!(unsafe { bits.int }, (-2.5f32).to_bits());
+        assert_eq!(unsafe { get_unchecked(&[7, 8], 1) }, 8);
+    }
+}
@@ -0,0 +1,185 @@
+//! # `NonNull<T>`: the Pointer Inside the Smart Pointers
+//! Unsafe Rust has two raw pointer types, `*const T` and `*mut T`. Open up `Box<T>`, `Rc<T>`,
+//! or `Vec<T>` in the standard library, though, and the pointer you’ll find is neither: it is
+//! `NonNull<T>`, a `*mut T` wrapper with two extra promises.
+//!
+//! 1. It is never null. That gives it a _niche_ (see `memory/niche.rs`), so `Option<Box<T>>`
+//!    and `Option<Rc<T>>` cost no more than the pointer itself.
+//! 2. It is _covariant_ in `T`, like `*const T` and unlike `*mut T`. That is what lets a
+//!    `Box<&'static str>` be used where a `Box<&'a str>` is expected, just as a
+//!    `&'static str` can be used where a `&'a str` is expected.
+//!
+//! `NonNull<T>` doesn’t make dereferencing safe: it is still a raw pointer that may dangle.
+//! It only records, in the type, facts the smart pointer has already checked.
+
+use std::{io::Result, marker::PhantomData, mem::size_of, ptr::NonNull};
+
+pub fn non_null() -> Result<()> {
+    Ok({
+        the_null_niche()?;
+        covariance()?;
+        a_pointer_round_trip()?;
+    })
+}
+
+fn the_null_niche() -> Result<()> {
+    Ok({
+        span!("the_null_niche");
+        header!("The Null Niche");
+
+        println!("size of *mut u64               = {}", size_of::<*mut u64>()); // 8
+        println!(
+            "size of Option<*mut u64>       = {}",
+            size_of::<Option<*mut u64>>()
+        ); // 16
+        println!(
+            "size of NonNull<u64>           = {}",
+            size_of::<NonNull<u64>>()
+        ); // 8
+        println!(
+            "size of Option<NonNull<u64>>   = {}",
+            size_of::<Option<NonNull<u64>>>()
+        ); // 8
+
+        // Creating a `NonNull` checks (or asserts, in `new_unchecked`) the promise once
+        let mut value = 42u64;
+        println!(
+            "NonNull::new(&mut value) is some = {}",
+            NonNull::new(&mut value).is_some()
+        );
+        println!(
+            "NonNull::new(null_mut) = {:?}",
+            NonNull::new(std::ptr::null_mut::<u64>())
+        );
+        // NonNull::new(null_mut) = None
+    })
+}
+
+/// # Covariance
+/// _Variance_ describes when `Wrapper<Sub>` may be used as a `Wrapper<Super>`. For lifetimes,
+/// `&'static str` is a subtype of `&'a str`: it lives at least as long, so it can stand in.
+///
+/// A wrapper around `NonNull<T>` is covariant, so it keeps that relationship:
+/// ```
+/// # use std::ptr::NonNull;
+/// struct MyBox<T> { ptr: NonNull<T> }
+/// fn shorten<'a>(b: MyBox<&'static str>) -> MyBox<&'a str> { b }
+/// ```
+/// A wrapper around `*mut T` is _invariant_, and refuses:
+/// ```compile_fail
+/// struct MyBox<T> { ptr: *mut T }
+/// fn shorten<'a>(b: MyBox<&'static str>) -> MyBox<&'a str> { b }
+/// ```
+/// lifetime may not live long enough
+///
+/// `*mut T` has to be invariant because writing through it could store a short-lived `&'a str`
+/// into a place that others still believe holds a `&'static str`. A `Box<T>` or `Rc<T>` only
+/// lets you write through it when you own it exclusively, so it can afford covariance, and it
+/// uses `NonNull<T>` to say so. `PhantomData<T>` additionally tells the compiler that the
+/// wrapper owns a `T`, so that dropping it may drop a `T`.
+struct Covariant<T> {
+    ptr: NonNull<T>,
+    _owns: PhantomData<T>,
+}
+impl<T> Covariant<T> {
+    pub fn new(value: T) -> Covariant<T> {
+        Covariant {
+            ptr: NonNull::from(Box::leak(Box::new(value))),
+            _owns: PhantomData,
+        }
+    }
+
+    pub fn get(&self) -> &T {
+        // SAFETY: `ptr` came from a `Box` and stays valid until `drop`
+        unsafe { self.ptr.as_ref() }
+    }
+}
+impl<T> Drop for Covariant<T> {
+    fn drop(&mut self) {
+        // SAFETY: `ptr` came from `Box::leak` and is only turned back into a `Box` once, here
+        drop(unsafe { Box::from_raw(self.ptr.as_ptr()) });
+    }
+}
+fn shorten<'a>(pointer: Covariant<&'static str>) -> Covariant<&'a str> {
+    pointer // accepted because `Covariant<T>` is covariant in `T`
+}
+fn covariance() -> Result<()> {
+    Ok({
+        span!("covariance");
+        header!("Covariance");
+
+        let long_lived: Covariant<&'static str> = Covariant::new("hello");
+        let local = String::from("world");
+        {
+            // an array of the shorter-lived type can hold both kinds
+            let short_lived: Covariant<&str> = Covariant::new(&local);
+            let both = [shorten(long_lived), short_lived];
+            for pointer in &both {
+                println!("{}", pointer.get());
+            }
+        }
+    })
+}
+
+/// # A Pointer Round Trip
+/// This is the life of the pointer inside a `Box<T>`: allocate, give up ownership to get a raw
+/// pointer, use it, and finally reclaim ownership so the value is dropped and the memory freed
+/// exactly once. Forgetting the last step leaks; doing it twice is a double free, which Miri
+/// reports as undefined behavior.
+fn a_pointer_round_trip() -> Result<()> {
+    Ok({
+        span!("a_pointer_round_trip");
+        header!("A Pointer Round Trip");
+
+        let boxed = Box::new(String::from("on the heap"));
+        let mut ptr: NonNull<String> = NonNull::from(Box::leak(boxed));
+
+        // SAFETY: the pointer is valid and we hold the only access to it
+        unsafe {
+            ptr.as_mut().push_str(", via NonNull");
+            println!("through the pointer: {}", ptr.as_ref());
+        }
+
+        // SAFETY: the pointer came from a `Box` and is reclaimed exactly once
+        let boxed = unsafe { Box::from_raw(ptr.as_ptr()) };
+        println!("back in a Box: {boxed}");
+
+        // An empty `Vec` holds `NonNull::dangling()`: non-null and aligned, but never dereferenced
+        let dangling: NonNull<u64> = NonNull::dangling();
+        println!("dangling = {:p}", dangling); // dangling = 0x8, the alignment of u64
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::rc::Rc;
+
+    #[test]
+    fn it_round_trips_a_box_through_non_null() {
+        let ptr = NonNull::from(Box::leak(Box::new(vec![1, 2, 3])));
+
+        let boxed = unsafe {
+            (*ptr.as_ptr()).push(4);
+            Box::from_raw(ptr.as_ptr())
+        };
+
+        assert_eq!(*boxed, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn it_drops_the_pointee_exactly_once() {
+        let value = Rc::new(());
+        {
+            let pointer = Covariant::new(Rc::clone(&value));
+            assert_eq!(Rc::strong_count(pointer.get()), 2);
+        }
+        assert_eq!(Rc::strong_count(&value), 1);
+    }
+
+    #[test]
+    fn it_uses_the_null_niche() {
+        assert_eq!(size_of::<Option<NonNull<u8>>>(), size_of::<*mut u8>());
+        assert!(NonNull::new(std::ptr::null_mut::<u8>()).is_none());
+    }
+}
@@ -0,0 +1,87 @@
+//! # The Lessons as a Library
+//! Everything but the command line: `intermediate_rust::registry` has every lesson, `runner`
+//! runs them, `progress` remembers which have been completed, `catalog`, `search`, `explain`
+//! and `source` describe them, `share` makes programs of them, `quiz` asks about them,
+//! `exercises` has them put to use, and `report` sums it all up. The `intermediate_rust` binary
+//! is one way to use them; the programs in `examples/` are others, one per chapter:
+//! ```text
+//! cargo run --example smart_pointers -- rc
+//! ```
+//! With the `python` feature, `bindings::python` makes them a Python module as well, and with
+//! the `plugins` feature, `plugin::load` adds the lessons of a library loaded while it runs.
+//! What another program needs most is at the root: the `registry`, a `Topic` for each chapter,
+//! `run` and `run_captured`, and, in `smart_pointers`, the types its lessons build, such as the
+//! cons `List`, `TreeNode`, `LimitTracker` with its `Messenger`s, and the smart pointers of our
+//! own, `MyBox`, `CustomSmartPointer` and `DebugRefCell`; in `testing`, the test doubles of the
+//! testing chapter, which `tests/testing.rs` uses as any other program would; and in `fuzzing`,
+//! the `MyVec`, `MyRc` and `MyRefCell` the fuzz targets in `fuzz/` check against the standard
+//! library’s:
+//! ```
+//! use intermediate_rust::{registry, run_captured, Topic, Verbosity};
+//!
+//! let registry = registry();
+//! for &topic in Topic::ALL {
+//!     let ids: Vec<_> = topic.lessons(&registry).map(|lesson| lesson.id()).collect();
+//!     println!("{topic}: {}", ids.join(", "));
+//! }
+//!
+//! # #[cfg(feature = "smart-pointers")] {
+//! use intermediate_rust::smart_pointers::{LimitTracker, List, QuotaEvent, StdoutMessenger};
+//!
+//! let rc = registry.get("smart-pointers/rc").unwrap();
+//! let output = run_captured(rc, Verbosity::Normal)?;
+//! assert!(output.stdout.contains("a = 5 -> 10 -> Nil\n"));
+//!
+//! let list = List::from(vec![1, 2, 3]);
+//! assert_eq!(list.len(), 3);
+//! let mut tracker = LimitTracker::new(&StdoutMessenger, 100);
+//! assert_eq!(tracker.set_value(80), Some(QuotaEvent::Warning));
+//! # }
+//! # Ok::<(), std::io::Error>(())
+//! ```
+// A build with only some of the chapters, see `Cargo.toml`, leaves unused what only the others
+// use: the helpers of their lessons, and what the tests about them import.
+#![cfg_attr(
+    not(feature = "chapters"),
+    allow(dead_code, unused_imports, unused_macros, unused_mut)
+)]
+
+// the lessons print with `ir_core`’s `println!`, and name their parts with `header!`; nothing
+// else in the library prints with the standard macros
+#[macro_use]
+extern crate ir_core;
+
+#[cfg(feature = "python")]
+pub mod bindings;
+pub mod catalog;
+pub mod exercises;
+pub mod explain;
+pub mod grade;
+// The lessons write each part as `Ok({ ... })`, so that the part reads as one block under its
+// header; clippy would rewrite that, and the `-> ()` some of their closures spell out.
+#[allow(clippy::unit_arg, clippy::unused_unit)]
+pub mod intermediate_rust;
+#[cfg(feature = "plugins")]
+pub mod plugin;
+pub mod progress;
+pub mod quiz;
+pub mod report;
+pub mod review;
+pub mod runner;
+pub mod search;
+pub mod share;
+pub mod source;
+pub mod tour;
+
+#[cfg(feature = "smart-pointers")]
+pub use intermediate_rust::smart_pointers;
+#[cfg(feature = "testing")]
+pub use intermediate_rust::testing;
+#[cfg(feature = "unsafe-rust")]
+pub use intermediate_rust::unsafe_rust::fuzzing;
+pub use intermediate_rust::{
+    lesson::{Demo, Difficulty, Lesson, LessonCtx, LessonInfo, Registry},
+    output::Verbosity,
+    registry, Topic,
+};
+pub use runner::{run, run_all, run_captured, run_one, LessonOutput, Outcome};
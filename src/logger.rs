@@ -0,0 +1,67 @@
+//! # Logging
+//! The lessons and their helpers log with the `log` facade: `log::warn!` when something went
+//! wrong that they could work around, `log::error!` when something was lost, and `log::debug!`
+//! for the steps on the way. `log` only hands the records to whichever logger the binary set up,
+//! and that is `env_logger`, set up here, which `RUST_LOG` tells what to let through:
+//! ```text
+//! RUST_LOG=off intermediate_rust smart-pointers ref-cell       # nothing
+//! RUST_LOG=debug intermediate_rust smart-pointers ref-cell     # the retries as well
+//! RUST_LOG=logging=trace intermediate_rust logging             # only the `logging` targets
+//! ```
+//! Without `RUST_LOG`, warnings and errors are let through. Each line is the level and the
+//! message, `[WARN] ...`, written with the lesson’s `eprint!`, so that the lines are captured,
+//! silenced and colored along with the rest of what the lesson prints.
+//!
+//! With the `tracing` feature, a `tracing` subscriber is set up as well, which prints the spans
+//! of the lessons and their parts as they open and close, nested, with how long each took.
+//! `RUST_TRACE` filters them as `RUST_LOG` filters the log, and lets everything through without
+//! it, unless `--quiet`. `RUST_TRACE=intermediate_rust::runner intermediate_rust --all` shows
+//! only the lessons’ spans, which the runner opens, and not those of their parts.
+
+use crate::intermediate_rust::{
+    output::{self, Logged, Verbosity},
+    style::Style,
+};
+use env_logger::{Builder, Env, Target};
+use log::Level;
+use std::io::Write;
+
+pub fn init(verbosity: Verbosity) {
+    #[cfg(feature = "tracing")]
+    init_tracing(verbosity);
+    #[cfg(not(feature = "tracing"))]
+    let _ = verbosity;
+    Builder::from_env(Env::default().default_filter_or("warn"))
+        .format(|buf, record| {
+            let level = format!("[{}]", record.level());
+            let color = output::color() && record.level() <= Level::Warn;
+            writeln!(
+                buf,
+                "{} {}",
+                Style::Warning.paint(level, color),
+                record.args()
+            )
+        })
+        .target(Target::Pipe(Box::new(Logged)))
+        .init();
+}
+
+#[cfg(feature = "tracing")]
+fn init_tracing(verbosity: Verbosity) {
+    use tracing_subscriber::{fmt::format::FmtSpan, EnvFilter};
+
+    // the lessons’ spans open before their output is redirected, so `--quiet` can’t silence them
+    let default = match verbosity {
+        Verbosity::Quiet => "off",
+        _ => "info",
+    };
+    let filter = EnvFilter::try_from_env("RUST_TRACE").unwrap_or_else(|_| EnvFilter::new(default));
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_span_events(FmtSpan::NEW | FmtSpan::CLOSE)
+        .with_target(false)
+        // no timestamps, but still how long each span took
+        .with_timer(())
+        .with_writer(|| Logged)
+        .init();
+}
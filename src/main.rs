@@ -1,8 +1,384 @@
-use intermediate_rust::*;
-use std::io::Result;
+// the library's modules, under the paths they had when they were the binary's own
+use ::intermediate_rust::{
+    catalog, exercises, explain, grade, intermediate_rust, progress, quiz, report, review, runner,
+    search, share, source, tour,
+};
+use clap::Parser;
+use cli::{Cli, Command};
+#[cfg(feature = "smart-pointers")]
+use intermediate_rust::smart_pointers::viz;
+use intermediate_rust::{
+    config::Settings,
+    lesson::{LessonCtx, Registry},
+    locale::{self, Lang},
+    output::Verbosity,
+    random,
+};
+use progress::Progress;
+use repl::Repl;
+use std::{
+    fs,
+    io::{self, Error, Result, Write},
+    path::{Path, PathBuf},
+    process::ExitCode,
+};
 
-mod intermediate_rust;
+mod cli;
+mod logger;
+#[cfg(feature = "tui")]
+mod menu;
+mod repl;
 
-fn main() -> Result<()> {
-    intermediate_rust::run(IntermediateRust::SmartPointers)
+fn main() -> ExitCode {
+    let cli = Cli::parse().check().unwrap_or_else(|x| x.exit());
+    match run(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        // a `Result` returned from `main` would be printed with `Debug`, as `Custom { .. }`
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Sets up what every command needs, and runs the one `cli` names, or the lessons.
+fn run(mut cli: Cli) -> Result<()> {
+    let settings = Settings::load(&Settings::path(), cli.layer())?;
+    logger::init(settings.verbosity);
+    locale::set(cli.lang.unwrap_or_else(Lang::from_env));
+    let registry = intermediate_rust::registry();
+    #[cfg(feature = "plugins")]
+    let registry = {
+        let mut registry = registry;
+        for path in &cli.plugins {
+            ::intermediate_rust::plugin::load(&mut registry, path)?;
+        }
+        registry
+    };
+    let path = Progress::path();
+    let progress = Progress::load(&path)?;
+    let color = settings.color();
+    match cli.command.take() {
+        Some(Command::List { tag }) => list(&registry, tag.as_deref(), &progress),
+        Some(Command::Search { query }) => search(&registry, &query.join(" ")),
+        Some(Command::ShowSource { topic, lesson }) => {
+            show_source(&registry, topic.as_deref(), lesson.as_deref(), color)
+        }
+        Some(Command::Grade { topic, lesson }) => {
+            grade(&registry, topic.as_deref(), lesson.as_deref(), color)
+        }
+        Some(Command::Share {
+            topic,
+            lesson,
+            main,
+        }) => share(
+            &registry,
+            topic.as_deref(),
+            lesson.as_deref(),
+            main.as_deref(),
+        ),
+        Some(Command::Quiz { chapter }) => quiz(&chapter),
+        Some(Command::Review { limit }) => review(&registry, &progress, limit),
+        Some(Command::Verify { exercise }) => verify(exercise.as_deref()),
+        Some(Command::Hint { exercise }) => hint(&exercise),
+        Some(Command::Report { out, name }) => {
+            report(&registry, &progress, out.as_deref(), name.as_deref(), color)
+        }
+        Some(Command::Tour { quiz }) => {
+            let (ctx, _) = lesson_ctx(&cli, &settings)?;
+            tour(&registry, ctx, progress, path, quiz)
+        }
+        Some(Command::Repl) => {
+            let (ctx, _) = lesson_ctx(&cli, &settings)?;
+            repl(&registry, ctx, progress, path)
+        }
+        #[cfg(feature = "tui")]
+        Some(Command::Menu) => {
+            let (ctx, _) = lesson_ctx(&cli, &settings)?;
+            menu::menu(&registry, ctx, progress, Some(path))
+        }
+        None => lessons(&cli, &settings, &registry, progress, &path),
+    }
+}
+
+/// Only the commands that run lessons print to `--output`, so only they create it. The seed is
+/// `--seed`, or a random one.
+fn lesson_ctx(cli: &Cli, settings: &Settings) -> Result<(LessonCtx, u64)> {
+    let seed = cli.seed.unwrap_or_else(rand::random);
+    let ctx = LessonCtx {
+        output: settings.output()?,
+        rng: random::seeded(seed),
+    };
+    Ok((ctx, seed))
+}
+
+fn list(registry: &Registry, tag: Option<&str>, progress: &Progress) -> Result<()> {
+    let catalog = catalog::list(registry, tag, progress).map_err(Error::other)?;
+    print!("{catalog}");
+    Ok(())
+}
+
+fn search(registry: &Registry, query: &str) -> Result<()> {
+    let index = search::Index::new(registry);
+    let found = index.search(query);
+    if found.is_empty() {
+        println!("no lesson mentions every word of `{query}`");
+    }
+    let width = found.iter().map(|(lesson, _)| lesson.id().len()).max();
+    for (lesson, _) in found {
+        println!(
+            "{:<2$}  {}",
+            lesson.id(),
+            lesson.title(),
+            width.unwrap_or(0)
+        );
+    }
+    Ok(())
+}
+
+fn show_source(
+    registry: &Registry,
+    topic: Option<&str>,
+    lesson: Option<&str>,
+    color: bool,
+) -> Result<()> {
+    let lesson = cli::lookup(registry, topic, lesson).unwrap_or_else(|x| x.exit());
+    print!("{}", source::show(lesson.info().source, color));
+    Ok(())
+}
+
+fn grade(
+    registry: &Registry,
+    topic: Option<&str>,
+    lesson: Option<&str>,
+    color: bool,
+) -> Result<()> {
+    // the transcripts are of what the lessons print in English
+    locale::set(Lang::En);
+    if topic == Some("exercises") {
+        let exercise = lesson.ok_or_else(|| {
+            Error::other("name the exercise to grade, like `grade exercises deref`")
+        })?;
+        let exercise = exercises::find(exercise).map_err(Error::other)?;
+        let mut printed = Vec::new();
+        exercises::verify(exercise, &mut printed)?;
+        let id = format!("exercises/{}", exercise.id);
+        return graded(&id, &String::from_utf8_lossy(&printed), color);
+    }
+    let lesson = cli::lookup(registry, topic, lesson).unwrap_or_else(|x| x.exit());
+    let output = runner::run_captured(lesson, Verbosity::Normal)?;
+    graded(lesson.id(), &output.stdout, color)
+}
+
+/// Compares what the lesson or exercise `id` printed with its transcript.
+fn graded(id: &str, printed: &str, color: bool) -> Result<()> {
+    let transcript = grade::transcript(id).map_err(Error::other)?;
+    match grade::report(&transcript, printed, color) {
+        None => {
+            println!("`{id}` printed what its transcript says");
+            Ok(())
+        }
+        Some(report) => {
+            print!("{report}");
+            Err(Error::other(format!(
+                "`{id}` didn't print what its transcript says"
+            )))
+        }
+    }
+}
+
+fn share(
+    registry: &Registry,
+    topic: Option<&str>,
+    lesson: Option<&str>,
+    main: Option<&Path>,
+) -> Result<()> {
+    let lesson = cli::lookup(registry, topic, lesson).unwrap_or_else(|x| x.exit());
+    let program = share::program(lesson).map_err(Error::other)?;
+    match main {
+        Some(path) => {
+            fs::write(path, program)?;
+            println!("wrote `{}` to {}", lesson.id(), path.display());
+        }
+        None => println!("{}", share::playground_url(&program)),
+    }
+    Ok(())
+}
+
+fn quiz(chapter: &str) -> Result<()> {
+    let (chapter, questions) = quiz::bank(chapter).map_err(Error::other)?;
+    let path = quiz::Scores::path();
+    let mut scores = quiz::Scores::load(&path)?;
+    let best = scores.best(chapter);
+    let score = quiz::ask(questions, io::stdin().lock(), &mut io::stdout())?;
+    println!("score: {score}");
+    match (scores.record(chapter, score), best) {
+        (true, Some(_)) => println!("a new best!"),
+        (false, Some(best)) => println!("best so far: {best}"),
+        (_, None) => {}
+    }
+    scores.save(&path)
+}
+
+fn review(registry: &Registry, progress: &Progress, limit: usize) -> Result<()> {
+    let ids: Vec<_> = registry.iter().map(|lesson| lesson.id()).collect();
+    let path = review::Schedule::path();
+    let mut schedule = review::Schedule::load(&path)?;
+    let today = review::today();
+    let due = schedule.due(progress, &ids, today);
+    if due.is_empty() {
+        match schedule.next(progress, &ids, today) {
+            Some(next) => println!("nothing is due, until {} day(s) from now", next - today),
+            None => println!(
+                "nothing to review yet: complete a lesson of a chapter with a quiz first, {}",
+                quiz::BANKS
+                    .iter()
+                    .map(|(chapter, _)| *chapter)
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+        }
+        return Ok(());
+    }
+    let score = schedule.review(&due, limit, today, io::stdin().lock(), &mut io::stdout())?;
+    println!("{score} right, {} left for today", due.len() - score.asked);
+    schedule.save(&path)
+}
+
+fn verify(exercise: Option<&str>) -> Result<()> {
+    let hints = exercises::Hints::load(&exercises::Hints::path())?;
+    let Some(exercise) = exercise else {
+        print!("{}", exercises::list(&hints));
+        return Ok(());
+    };
+    let exercise = exercises::find(exercise).map_err(Error::other)?;
+    if !exercises::verify(exercise, &mut io::stdout())? {
+        return Err(Error::other(format!("`{}` isn't done yet", exercise.id)));
+    }
+    match hints.used(exercise.id) {
+        0 => println!("without a hint"),
+        used if used >= exercise.tiers() => println!("after seeing the solution"),
+        used => println!("with {used} of {} hint(s)", exercise.hints.len()),
+    }
+    Ok(())
+}
+
+fn hint(exercise: &str) -> Result<()> {
+    let exercise = exercises::find(exercise).map_err(Error::other)?;
+    let path = exercises::Hints::path();
+    let mut hints = exercises::Hints::load(&path)?;
+    println!("{}", hints.reveal(exercise));
+    hints.save(&path)
+}
+
+fn report(
+    registry: &Registry,
+    progress: &Progress,
+    out: Option<&Path>,
+    name: Option<&str>,
+    color: bool,
+) -> Result<()> {
+    let report = report::Report::new(
+        registry,
+        progress,
+        &quiz::Scores::load(&quiz::Scores::path())?,
+        exercises::EXERCISES,
+        &exercises::Hints::load(&exercises::Hints::path())?,
+    )?;
+    print!("{}", report.terminal(color));
+    if let Some(out) = out {
+        let file = report.file(out, name).map_err(Error::other)?;
+        fs::write(out, file)?;
+        println!("wrote the report to {}", out.display());
+    }
+    Ok(())
+}
+
+fn tour(
+    registry: &Registry,
+    ctx: LessonCtx,
+    progress: Progress,
+    path: PathBuf,
+    quiz: bool,
+) -> Result<()> {
+    let mut tour = tour::Tour::new(
+        registry,
+        tour::CURRICULUM,
+        exercises::EXERCISES,
+        ctx,
+        progress,
+        Some(path),
+    );
+    tour.run(io::stdin().lock(), &mut io::stdout(), quiz)
+}
+
+fn repl(registry: &Registry, ctx: LessonCtx, progress: Progress, path: PathBuf) -> Result<()> {
+    let mut repl = Repl::new(registry, ctx, progress, Some(path));
+    repl.run(io::stdin().lock(), &mut io::stdout())
+}
+
+/// Runs the lesson `cli` names, or with `--all`, every lesson, carrying on past failures.
+fn lessons(
+    cli: &Cli,
+    settings: &Settings,
+    registry: &Registry,
+    mut progress: Progress,
+    path: &Path,
+) -> Result<()> {
+    let (mut ctx, seed) = lesson_ctx(cli, settings)?;
+    if ctx.output.verbosity >= Verbosity::Verbose {
+        // to repeat a run that wasn't given a seed
+        writeln!(ctx.output.out, "seed = {seed}")?;
+    }
+    if !cli.all {
+        let lesson = cli.lesson(registry).unwrap_or_else(|x| x.exit());
+        if cli.explain {
+            let explanation = explain::explain(lesson, ctx.output.color);
+            writeln!(ctx.output.out, "{explanation}")?;
+        }
+        let measurement = with_graphs(cli.dot.as_deref(), || {
+            runner::run_one(lesson, &mut ctx, &mut progress, cli.force)
+        })??;
+        if ctx.output.verbosity >= Verbosity::Verbose {
+            writeln!(ctx.output.out, "\n`{}` took {measurement}", lesson.id())?;
+        }
+        return progress.save(path);
+    }
+
+    let outcomes = with_graphs(cli.dot.as_deref(), || {
+        runner::run_all(registry, &mut ctx, &mut progress)
+    })?;
+    progress.save(path)?;
+    println!("\n{}", runner::summary(&outcomes));
+    println!("\n{}", runner::summary_by_tag(registry, &outcomes));
+    println!("\n{}", runner::report(&outcomes));
+    match outcomes
+        .iter()
+        .filter(|outcome| outcome.failure.is_some())
+        .count()
+    {
+        0 => Ok(()),
+        failed => Err(Error::other(format!("{failed} lesson(s) failed"))),
+    }
+}
+
+/// Runs `f`, and writes the graphs the smart pointer lessons drew meanwhile to `dot`, if given.
+#[cfg(feature = "smart-pointers")]
+fn with_graphs<R>(dot: Option<&Path>, f: impl FnOnce() -> R) -> Result<R> {
+    let (result, graphs) = viz::collect(f);
+    if let Some(dot) = dot {
+        viz::write(&graphs, dot)?;
+    }
+    Ok(result)
+}
+
+/// Without the smart pointer lessons, nothing draws a graph.
+#[cfg(not(feature = "smart-pointers"))]
+fn with_graphs<R>(dot: Option<&Path>, f: impl FnOnce() -> R) -> Result<R> {
+    match dot {
+        Some(_) => Err(Error::other(
+            "--dot draws the smart pointer lessons, which aren't in this build",
+        )),
+        None => Ok(f()),
+    }
 }
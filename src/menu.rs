@@ -0,0 +1,325 @@
+//! # The Menu
+//! `intermediate_rust menu`, with the `tui` feature, lists the topics and their lessons in the
+//! terminal. The arrow keys choose a lesson, and `Enter` runs it; its output is shown next to the
+//! list, and once it has been completed it is ticked.
+//!
+//! The lessons are run as `intermediate_rust TOPIC LESSON` runs them, with the plugins, the
+//! language, the seed and the verbosity it was given, but what they print would draw all over the
+//! menu, so it is captured and shown instead, without color. A lesson that panics is reported as
+//! having panicked, and the menu carries on.
+
+use crate::{
+    intermediate_rust::{
+        lesson::{LessonCtx, Registry},
+        output::{Buffer, Output},
+    },
+    progress::Progress,
+    runner,
+};
+use ratatui::{
+    crossterm::event::{self, Event, KeyCode, KeyEventKind},
+    layout::{Constraint, Layout},
+    style::{Modifier, Style},
+    widgets::{Block, List, ListItem, ListState, Paragraph, Wrap},
+    DefaultTerminal, Frame,
+};
+use std::{
+    io::Result,
+    panic::{self, AssertUnwindSafe},
+    path::PathBuf,
+};
+
+enum Row {
+    Topic(String),
+    Lesson { id: String, title: String },
+}
+
+pub struct Menu<'r> {
+    registry: &'r Registry,
+    rows: Vec<Row>,
+    state: ListState,
+    /// What the lessons print to, which `output` is taken from after each run.
+    ctx: LessonCtx,
+    printed: Buffer,
+    output: String,
+    /// How many lines of `output` are scrolled out of view.
+    scroll: u16,
+    progress: Progress,
+    /// Where the progress is saved, if anywhere.
+    path: Option<PathBuf>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+enum Action {
+    Run { forced: bool },
+    Quit,
+}
+
+impl<'r> Menu<'r> {
+    /// What the lessons print to `ctx` is captured instead, out and err together, in the order it
+    /// was printed in; only its verbosity is kept.
+    pub fn new(
+        registry: &'r Registry,
+        ctx: LessonCtx,
+        progress: Progress,
+        path: Option<PathBuf>,
+    ) -> Menu<'r> {
+        let mut rows = Vec::new();
+        for topic in registry.topics() {
+            rows.push(Row::Topic(topic.to_string()));
+            for lesson in registry.filter(|lesson| lesson.topic() == topic) {
+                rows.push(Row::Lesson {
+                    id: lesson.id().to_string(),
+                    title: lesson.title().to_string(),
+                });
+            }
+        }
+        let printed = Buffer::new();
+        let ctx = LessonCtx {
+            output: Output {
+                verbosity: ctx.output.verbosity,
+                ..Output::captured(&printed, &printed)
+            },
+            ..ctx
+        };
+        let mut menu = Menu {
+            registry,
+            rows,
+            state: ListState::default(),
+            ctx,
+            printed,
+            output: String::from("Enter runs the lesson, f runs it even if it isn't ready yet"),
+            scroll: 0,
+            progress,
+            path,
+        };
+        menu.select(0, 1);
+        menu
+    }
+
+    fn selected(&self) -> Option<&str> {
+        match self.rows.get(self.state.selected()?) {
+            Some(Row::Lesson { id, .. }) => Some(id),
+            _ => None,
+        }
+    }
+
+    /// Selects the first lesson from row `from` on, stepping by `step` past the topics.
+    fn select(&mut self, from: usize, step: isize) {
+        let mut row = from;
+        while let Some(Row::Topic(_)) = self.rows.get(row) {
+            match row.checked_add_signed(step) {
+                Some(next) => row = next,
+                None => return,
+            }
+        }
+        if row < self.rows.len() {
+            self.state.select(Some(row));
+        }
+    }
+
+    fn on_key(&mut self, key: KeyCode) -> Option<Action> {
+        let selected = self.state.selected().unwrap_or(0);
+        match key {
+            KeyCode::Up | KeyCode::Char('k') => {
+                if let Some(row) = selected.checked_sub(1) {
+                    self.select(row, -1)
+                }
+            }
+            KeyCode::Down | KeyCode::Char('j') => self.select(selected + 1, 1),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(10),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_add(10),
+            KeyCode::Enter => return Some(Action::Run { forced: false }),
+            KeyCode::Char('f') => return Some(Action::Run { forced: true }),
+            KeyCode::Char('q') | KeyCode::Esc => return Some(Action::Quit),
+            _ => {}
+        }
+        None
+    }
+
+    /// Runs the selected lesson, and shows what it printed, and how it failed if it did. The
+    /// panic hook is swapped for a silent one for the time being, as the default one would print
+    /// over the menu.
+    fn run_selected(&mut self, forced: bool) -> Result<()> {
+        let Some(lesson) = self.selected().and_then(|id| self.registry.get(id)) else {
+            return Ok(());
+        };
+        let hook = panic::take_hook();
+        panic::set_hook(Box::new(|_| {}));
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            runner::run_one(lesson, &mut self.ctx, &mut self.progress, forced)
+        }));
+        panic::set_hook(hook);
+
+        self.output = self.printed.take();
+        let failure = match result {
+            Ok(Ok(_)) => None,
+            Ok(Err(x)) => Some(x.to_string()),
+            Err(panic) => Some(format!("panicked: {}", runner::panic_message(&*panic))),
+        };
+        if let Some(failure) = failure {
+            self.output
+                .push_str(&format!("\n`{}` failed: {failure}", lesson.id()));
+        }
+        self.scroll = 0;
+        match &self.path {
+            Some(path) => self.progress.save(path),
+            None => Ok(()),
+        }
+    }
+
+    fn draw(&mut self, frame: &mut Frame) {
+        let [body, help] =
+            Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+        let [lessons, output] =
+            Layout::horizontal([Constraint::Percentage(40), Constraint::Percentage(60)])
+                .areas(body);
+
+        let items: Vec<_> = self
+            .rows
+            .iter()
+            .map(|row| match row {
+                Row::Topic(topic) => ListItem::new(topic.as_str())
+                    .style(Style::default().add_modifier(Modifier::BOLD)),
+                Row::Lesson { id, title } => {
+                    let done = match self.progress.is_completed(id) {
+                        true => '✓',
+                        false => ' ',
+                    };
+                    let name = id.rsplit('/').next().unwrap_or(id);
+                    ListItem::new(format!("  {done} {name}: {title}"))
+                }
+            })
+            .collect();
+        let list = List::new(items)
+            .block(Block::bordered().title("Lessons"))
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, lessons, &mut self.state);
+
+        let title = self.selected().unwrap_or("Output").to_string();
+        let paragraph = Paragraph::new(self.output.as_str())
+            .block(Block::bordered().title(title))
+            .wrap(Wrap { trim: false })
+            .scroll((self.scroll, 0));
+        frame.render_widget(paragraph, output);
+
+        frame.render_widget(
+            "↑/↓ choose  Enter run  f force  PgUp/PgDn scroll  q quit",
+            help,
+        );
+    }
+
+    fn run(mut self, terminal: &mut DefaultTerminal) -> Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame))?;
+            let Event::Key(key) = event::read()? else {
+                continue;
+            };
+            if key.kind != KeyEventKind::Press {
+                continue;
+            }
+            match self.on_key(key.code) {
+                Some(Action::Run { forced }) => self.run_selected(forced)?,
+                Some(Action::Quit) => return Ok(()),
+                None => {}
+            }
+        }
+    }
+}
+
+/// Takes over the terminal until the menu is quit, and gives it back however the menu ends.
+pub fn menu(
+    registry: &Registry,
+    ctx: LessonCtx,
+    progress: Progress,
+    path: Option<PathBuf>,
+) -> Result<()> {
+    let mut terminal = ratatui::init();
+    let result = Menu::new(registry, ctx, progress, path).run(&mut terminal);
+    ratatui::restore();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intermediate_rust::{
+        self,
+        lesson::{Demo, LessonInfo},
+    };
+    // the lessons’ own, which print to the current output
+    use ir_core::{eprintln, println};
+
+    #[test]
+    fn the_arrow_keys_move_between_lessons_past_the_topics() {
+        let registry = intermediate_rust::registry();
+        let mut menu = Menu::new(&registry, LessonCtx::default(), Progress::default(), None);
+        assert_eq!(menu.selected(), Some("lambda"));
+
+        menu.on_key(KeyCode::Up);
+        assert_eq!(menu.selected(), Some("lambda"));
+        menu.on_key(KeyCode::Down);
+        assert_eq!(menu.selected(), Some("thread-and-move"));
+        menu.on_key(KeyCode::Down);
+        assert_eq!(menu.selected(), Some("smart-pointers/drop"));
+        menu.on_key(KeyCode::Up);
+        assert_eq!(menu.selected(), Some("thread-and-move"));
+
+        for _ in 0..100 {
+            menu.on_key(KeyCode::Down);
+        }
+        assert_eq!(menu.selected(), registry.iter().last().map(|x| x.id()));
+    }
+
+    #[test]
+    fn enter_runs_and_q_quits() {
+        let registry = intermediate_rust::registry();
+        let mut menu = Menu::new(&registry, LessonCtx::default(), Progress::default(), None);
+        assert_eq!(
+            menu.on_key(KeyCode::Enter),
+            Some(Action::Run { forced: false })
+        );
+        assert_eq!(
+            menu.on_key(KeyCode::Char('f')),
+            Some(Action::Run { forced: true })
+        );
+        assert_eq!(menu.on_key(KeyCode::Char('q')), Some(Action::Quit));
+        assert_eq!(menu.on_key(KeyCode::Char('x')), None);
+    }
+
+    #[test]
+    fn a_lesson_runs_in_the_menu_with_what_it_printed_shown() {
+        let mut registry = Registry::new();
+        for (id, run) in [
+            (
+                "prints",
+                (|| {
+                    println!("to out");
+                    eprintln!("to err");
+                    Ok(())
+                }) as fn() -> Result<()>,
+            ),
+            ("panics", || panic!("no luck")),
+        ] {
+            registry.register(Demo {
+                id,
+                title: "",
+                run,
+                info: LessonInfo::default(),
+            });
+        }
+        let mut menu = Menu::new(&registry, LessonCtx::default(), Progress::default(), None);
+
+        menu.run_selected(false).unwrap();
+        // with metrics after it, in a build that collects them
+        assert!(menu.output.starts_with("to out\nto err\n"));
+        assert!(menu.progress.is_completed("prints"));
+
+        menu.on_key(KeyCode::Down);
+        menu.run_selected(false).unwrap();
+        assert!(menu
+            .output
+            .ends_with("\n`panics` failed: panicked: no luck"));
+        assert!(!menu.progress.is_completed("panics"));
+    }
+}
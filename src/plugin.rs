@@ -0,0 +1,80 @@
+//! # Lessons from a Plugin
+//! `intermediate_rust --plugin target/debug/libir_example_plugin.so extras cow` loads a library
+//! while the program runs, and registers its lessons after the program’s own, so that lessons
+//! can be shipped without forking the package; `crates/ir-example-plugin` is an example.
+//!
+//! A plugin exports the two functions `ir_core::plugin!` writes: `ir_core_version`, which is
+//! checked first, and `extern "C" fn register(registry: &mut Registry)`. Loading a library runs
+//! its initializers, and calling into it trusts it to be what it says it is, so a plugin is
+//! trusted as the program itself is, and only loaded from where the program would be.
+
+use crate::intermediate_rust::lesson::{Registry, PLUGIN_VERSION};
+use libloading::{Library, Symbol};
+use std::{
+    ffi::{c_char, CStr},
+    io::{Error, Result},
+    mem,
+    path::Path,
+};
+
+/// Loads the plugin at `path`, and registers its lessons in `registry`, after its own.
+pub fn load(registry: &mut Registry, path: &Path) -> Result<()> {
+    let failed = |e: String| Error::other(format!("`{}` {e}", path.display()));
+    // SAFETY: loading a library runs its initializers, which can do anything; a plugin is
+    // trusted as the program is
+    let library = unsafe { Library::new(path) }.map_err(|e| failed(why(e)))?;
+    // SAFETY: `ir_core_version` is `plugin!`’s, with this signature
+    let version: Symbol<extern "C" fn() -> *const c_char> = unsafe {
+        library.get(b"ir_core_version").map_err(|_| {
+            failed("isn't a plugin, it has no `ir_core_version`, see `ir_core::plugin!`".into())
+        })?
+    };
+    // SAFETY: it returns its `ir-core`’s `PLUGIN_VERSION`, which is `'static`
+    let version = unsafe { CStr::from_ptr(version()) };
+    if version != PLUGIN_VERSION {
+        return Err(failed(format!(
+            "was built with ir-core {}, not {}",
+            version.to_string_lossy(),
+            PLUGIN_VERSION.to_string_lossy()
+        )));
+    }
+    // SAFETY: the same version of `ir-core` has the same `Registry`, if the same compiler built
+    // both, which is the one thing that can’t be checked
+    let register: Symbol<extern "C" fn(&mut Registry)> =
+        unsafe { library.get(b"register") }.map_err(|e| failed(why(e)))?;
+    register(registry);
+    // the lessons’ code, and their vtables, are the library’s, so it stays loaded for good
+    mem::forget(library);
+    Ok(())
+}
+
+/// What went wrong, with why, which `libloading` has as the error’s source: `dlerror`’s text.
+fn why(e: libloading::Error) -> String {
+    match std::error::Error::source(&e) {
+        Some(source) => format!("failed: {e}: {source}"),
+        None => format!("failed: {e}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg_attr(miri, ignore)]
+    #[test]
+    fn only_a_plugin_is_loaded() {
+        let mut registry = Registry::new();
+        let error = load(&mut registry, Path::new("Cargo.toml")).unwrap_err();
+        assert!(
+            error.to_string().starts_with("`Cargo.toml` failed: "),
+            "{error}"
+        );
+
+        #[cfg(target_os = "linux")]
+        {
+            let error = load(&mut registry, Path::new("libc.so.6")).unwrap_err();
+            assert!(error.to_string().contains("isn't a plugin"), "{error}");
+        }
+        assert_eq!(registry.iter().count(), 0);
+    }
+}
@@ -0,0 +1,121 @@
+//! # Remembering Completed Lessons
+//! A lesson is completed once it has run without failing. The ids of the completed lessons are
+//! kept in a file, one per line, so that a lesson’s prerequisites can be checked in a later run.
+//! The file is `.intermediate_rust_progress` in the current directory, or wherever
+//! `INTERMEDIATE_RUST_PROGRESS` says.
+
+use crate::intermediate_rust::lesson::Lesson;
+use std::{
+    collections::BTreeSet,
+    env, fs,
+    io::{ErrorKind, Result},
+    path::{Path, PathBuf},
+};
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Progress {
+    completed: BTreeSet<String>,
+}
+impl Progress {
+    pub fn path() -> PathBuf {
+        env::var_os("INTERMEDIATE_RUST_PROGRESS").map_or_else(
+            || PathBuf::from(".intermediate_rust_progress"),
+            PathBuf::from,
+        )
+    }
+
+    /// No file yet means nothing has been completed yet. Under Miri, which has no file system,
+    /// nothing is ever loaded or saved.
+    pub fn load(path: &Path) -> Result<Progress> {
+        if cfg!(miri) {
+            return Ok(Progress::default());
+        }
+        match fs::read_to_string(path) {
+            Ok(text) => Ok(Progress {
+                completed: text
+                    .lines()
+                    .map(str::trim)
+                    .filter(|id| !id.is_empty())
+                    .map(String::from)
+                    .collect(),
+            }),
+            Err(x) if x.kind() == ErrorKind::NotFound => Ok(Progress::default()),
+            Err(x) => Err(x),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if cfg!(miri) {
+            return Ok(());
+        }
+        let mut text = String::new();
+        for id in &self.completed {
+            text.push_str(id);
+            text.push('\n');
+        }
+        fs::write(path, text)
+    }
+
+    pub fn complete(&mut self, id: &str) {
+        self.completed.insert(id.to_string());
+    }
+
+    pub fn is_completed(&self, id: &str) -> bool {
+        self.completed.contains(id)
+    }
+
+    /// The prerequisites of `lesson` that haven’t been completed yet.
+    pub fn missing(&self, lesson: &dyn Lesson) -> Vec<&'static str> {
+        lesson
+            .info()
+            .prerequisites
+            .iter()
+            .copied()
+            .filter(|id| !self.is_completed(id))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intermediate_rust::lesson::{Demo, LessonInfo};
+
+    #[test]
+    #[cfg_attr(miri, ignore)] // under Miri, nothing is saved
+    fn it_is_saved_and_loaded_again() {
+        let path = env::temp_dir().join(format!("progress-{}", std::process::id()));
+        assert_eq!(Progress::load(&path).unwrap(), Progress::default());
+
+        let mut progress = Progress::default();
+        progress.complete("memory/slices");
+        progress.complete("lambda");
+        progress.complete("lambda");
+        progress.save(&path).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        let loaded = Progress::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(text, "lambda\nmemory/slices\n");
+        assert_eq!(loaded, progress);
+    }
+
+    #[test]
+    fn only_uncompleted_prerequisites_are_missing() {
+        let strings = Demo {
+            id: "strings",
+            title: "Strings",
+            run: || Ok(()),
+            info: LessonInfo {
+                prerequisites: &["memory/slices", "lambda"],
+                ..LessonInfo::default()
+            },
+        };
+        let mut progress = Progress::default();
+        assert_eq!(progress.missing(&strings), ["memory/slices", "lambda"]);
+        progress.complete("lambda");
+        assert_eq!(progress.missing(&strings), ["memory/slices"]);
+        progress.complete("memory/slices");
+        assert!(progress.missing(&strings).is_empty());
+    }
+}
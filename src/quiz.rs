@@ -0,0 +1,344 @@
+//! # Quizzes
+//! Running a lesson shows what it teaches; a quiz checks what stuck. Each chapter with a quiz
+//! has a bank of multiple-choice questions, asked one after another:
+//! ```text
+//! intermediate_rust quiz smart-pointers
+//! 1/5. Which smart pointer lets a value have more than one owner?
+//!   a) Box<T>
+//!   b) Rc<T>
+//!   ...
+//! answer: b
+//! right: `Rc<T>` counts its owners, and drops the value when the last one goes away
+//! ```
+//! An answer is a letter or a number. After the last question, the score is shown next to the
+//! best one so far, which is kept in `.intermediate_rust_scores` in the current directory, or
+//! wherever `INTERMEDIATE_RUST_SCORES` says, one chapter per line.
+
+use std::{
+    collections::BTreeMap,
+    env,
+    fmt::{self, Display},
+    fs,
+    io::{BufRead, ErrorKind, Result, Write},
+    path::{Path, PathBuf},
+};
+
+#[cfg(feature = "closures")]
+mod lambda;
+#[cfg(feature = "smart-pointers")]
+mod smart_pointers;
+#[cfg(feature = "concurrency")]
+mod thread_and_move;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Question {
+    pub prompt: &'static str,
+    pub choices: &'static [&'static str],
+    /// The index of the right choice.
+    pub answer: usize,
+    /// Why it is the right one, shown after the question is answered.
+    pub explanation: &'static str,
+}
+
+/// The chapters with a quiz, by topic, and their questions, if the chapter is in the build.
+pub const BANKS: &[(&str, &[Question])] = &[
+    #[cfg(feature = "closures")]
+    ("lambda", lambda::QUESTIONS),
+    #[cfg(feature = "concurrency")]
+    ("thread-and-move", thread_and_move::QUESTIONS),
+    #[cfg(feature = "smart-pointers")]
+    ("smart-pointers", smart_pointers::QUESTIONS),
+];
+
+/// The questions of `chapter`, named like a topic on the command line, or which chapters have a
+/// quiz.
+pub fn bank(chapter: &str) -> std::result::Result<(&'static str, &'static [Question]), String> {
+    let chapter = chapter.replace('_', "-");
+    BANKS
+        .iter()
+        .copied()
+        .find(|(name, _)| *name == chapter)
+        .ok_or_else(|| {
+            let names: Vec<_> = BANKS.iter().map(|(name, _)| *name).collect();
+            format!(
+                "there is no quiz for `{chapter}`, the quizzes are: {}",
+                names.join(", ")
+            )
+        })
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct Score {
+    pub right: usize,
+    pub asked: usize,
+}
+impl Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.right, self.asked)
+    }
+}
+
+/// `a`, `B` or `2` for the second of `count` choices.
+fn parse_answer(line: &str, count: usize) -> Option<usize> {
+    let line = line.trim();
+    let index = match line.parse::<usize>() {
+        Ok(number) => number.checked_sub(1)?,
+        Err(_) => match line.as_bytes() {
+            &[letter] if letter.is_ascii_alphabetic() => {
+                usize::from(letter.to_ascii_lowercase() - b'a')
+            }
+            _ => return None,
+        },
+    };
+    (index < count).then_some(index)
+}
+
+fn letter(index: usize) -> char {
+    char::from(b'a' + index as u8)
+}
+
+/// Asks each of `questions` in turn, reading the answers from `input`, and returns the score.
+/// An answer that isn’t one of the choices is asked for again; at the end of the input, the
+/// questions that are left aren’t asked, and don’t count.
+pub fn ask(questions: &[Question], input: impl BufRead, out: &mut impl Write) -> Result<Score> {
+    let mut lines = input.lines();
+    let mut score = Score::default();
+    for (number, question) in questions.iter().enumerate() {
+        let heading = format!("{}/{}.", number + 1, questions.len());
+        match ask_one(&heading, question, &mut lines, out)? {
+            Some(right) => {
+                score.asked += 1;
+                score.right += usize::from(right);
+            }
+            None => return Ok(score),
+        }
+    }
+    Ok(score)
+}
+
+/// Asks `question`, headed with `heading`, with its choices, until one of them is answered from
+/// `lines`, and explains the answer. Returns whether it was right, or `None` at the end of the
+/// input.
+pub fn ask_one(
+    heading: &str,
+    question: &Question,
+    lines: &mut impl Iterator<Item = Result<String>>,
+    out: &mut impl Write,
+) -> Result<Option<bool>> {
+    writeln!(out, "{heading} {}", question.prompt)?;
+    for (index, choice) in question.choices.iter().enumerate() {
+        writeln!(out, "  {}) {choice}", letter(index))?;
+    }
+    let answer = loop {
+        write!(out, "answer: ")?;
+        out.flush()?;
+        let Some(line) = lines.next() else {
+            writeln!(out)?;
+            return Ok(None);
+        };
+        match parse_answer(&line?, question.choices.len()) {
+            Some(answer) => break answer,
+            None => writeln!(
+                out,
+                "answer with a letter from a to {}",
+                letter(question.choices.len() - 1)
+            )?,
+        }
+    };
+    let right = answer == question.answer;
+    match right {
+        true => writeln!(out, "right: {}\n", question.explanation)?,
+        false => writeln!(
+            out,
+            "wrong, it is {}): {}\n",
+            letter(question.answer),
+            question.explanation
+        )?,
+    }
+    Ok(Some(right))
+}
+
+/// The best score of each chapter so far.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Scores {
+    best: BTreeMap<String, Score>,
+}
+impl Scores {
+    pub fn path() -> PathBuf {
+        env::var_os("INTERMEDIATE_RUST_SCORES")
+            .map_or_else(|| PathBuf::from(".intermediate_rust_scores"), PathBuf::from)
+    }
+
+    /// No file yet means no quiz has been taken yet, and a line that can’t be read is skipped.
+    /// Under Miri, which has no file system, nothing is ever loaded or saved.
+    pub fn load(path: &Path) -> Result<Scores> {
+        if cfg!(miri) {
+            return Ok(Scores::default());
+        }
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(x) if x.kind() == ErrorKind::NotFound => return Ok(Scores::default()),
+            Err(x) => return Err(x),
+        };
+        let best = text
+            .lines()
+            .filter_map(|line| {
+                let (chapter, score) = line.trim().split_once(' ')?;
+                let (right, asked) = score.split_once('/')?;
+                let score = Score {
+                    right: right.parse().ok()?,
+                    asked: asked.parse().ok()?,
+                };
+                Some((chapter.to_string(), score))
+            })
+            .collect();
+        Ok(Scores { best })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if cfg!(miri) {
+            return Ok(());
+        }
+        let mut text = String::new();
+        for (chapter, score) in &self.best {
+            text.push_str(&format!("{chapter} {score}\n"));
+        }
+        fs::write(path, text)
+    }
+
+    pub fn best(&self, chapter: &str) -> Option<Score> {
+        self.best.get(chapter).copied()
+    }
+
+    /// Keeps `score` if it is the best of `chapter` so far, and returns whether it was. A score
+    /// is better with more right answers, and, with as many, with fewer questions asked.
+    pub fn record(&mut self, chapter: &str, score: Score) -> bool {
+        let better = self.best(chapter).is_none_or(|best| {
+            (score.right, std::cmp::Reverse(score.asked))
+                > (best.right, std::cmp::Reverse(best.asked))
+        });
+        if better {
+            self.best.insert(chapter.to_string(), score);
+        }
+        better
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const QUESTIONS: &[Question] = &[
+        Question {
+            prompt: "One?",
+            choices: &["1", "2"],
+            answer: 0,
+            explanation: "one is 1",
+        },
+        Question {
+            prompt: "Two?",
+            choices: &["1", "2", "3"],
+            answer: 1,
+            explanation: "two is 2",
+        },
+    ];
+
+    fn quiz(input: &str) -> (Score, String) {
+        let mut out = Vec::new();
+        let score = ask(QUESTIONS, input.as_bytes(), &mut out).unwrap();
+        (score, String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn every_bank_has_questions_with_a_right_choice() {
+        for (chapter, questions) in BANKS {
+            assert!(!questions.is_empty(), "{chapter}");
+            for question in *questions {
+                assert!(question.choices.len() >= 2, "{}", question.prompt);
+                assert!(
+                    question.answer < question.choices.len(),
+                    "{}",
+                    question.prompt
+                );
+            }
+        }
+    }
+
+    #[cfg(feature = "chapters")]
+    #[test]
+    fn chapters_are_named_like_topics() {
+        assert_eq!(bank("smart_pointers").unwrap().0, "smart-pointers");
+        assert_eq!(
+            bank("memory").unwrap_err(),
+            "there is no quiz for `memory`, the quizzes are: lambda, thread-and-move, smart-pointers"
+        );
+    }
+
+    #[test]
+    fn answers_are_letters_or_numbers() {
+        assert_eq!(parse_answer(" b ", 3), Some(1));
+        assert_eq!(parse_answer("C", 3), Some(2));
+        assert_eq!(parse_answer("1", 3), Some(0));
+        assert_eq!(parse_answer("d", 3), None);
+        assert_eq!(parse_answer("0", 3), None);
+        assert_eq!(parse_answer("ab", 3), None);
+        assert_eq!(parse_answer("", 3), None);
+    }
+
+    #[test]
+    fn each_answer_is_scored_and_explained() {
+        let (score, out) = quiz("a\nc\n");
+
+        assert_eq!(score, Score { right: 1, asked: 2 });
+        assert_eq!(
+            out,
+            "1/2. One?\n  a) 1\n  b) 2\nanswer: right: one is 1\n\n\
+             2/2. Two?\n  a) 1\n  b) 2\n  c) 3\nanswer: wrong, it is b): two is 2\n\n"
+        );
+    }
+
+    #[test]
+    fn a_choice_that_isnt_one_is_asked_for_again() {
+        let (score, out) = quiz("z\n2\n");
+
+        assert_eq!(score, Score { right: 0, asked: 1 });
+        assert!(
+            out.contains("answer: answer with a letter from a to b\nanswer: wrong"),
+            "{out}"
+        );
+    }
+
+    #[test]
+    fn the_questions_left_at_the_end_of_the_input_dont_count() {
+        assert_eq!(quiz("1\n").0, Score { right: 1, asked: 1 });
+        assert_eq!(quiz("").0, Score::default());
+    }
+
+    #[test]
+    fn only_the_best_score_is_kept() {
+        let mut scores = Scores::default();
+        assert!(scores.record("lambda", Score { right: 2, asked: 4 }));
+        assert!(!scores.record("lambda", Score { right: 1, asked: 4 }));
+        assert!(scores.record("lambda", Score { right: 2, asked: 3 }));
+        assert_eq!(scores.best("lambda"), Some(Score { right: 2, asked: 3 }));
+        assert_eq!(scores.best("smart-pointers"), None);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)] // under Miri, nothing is saved
+    fn scores_are_saved_and_loaded_again() {
+        let path = env::temp_dir().join(format!("scores-{}", std::process::id()));
+        assert_eq!(Scores::load(&path).unwrap(), Scores::default());
+
+        let mut scores = Scores::default();
+        scores.record("smart-pointers", Score { right: 4, asked: 5 });
+        scores.record("lambda", Score { right: 3, asked: 3 });
+        scores.save(&path).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        let loaded = Scores::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(text, "lambda 3/3\nsmart-pointers 4/5\n");
+        assert_eq!(loaded, scores);
+    }
+}
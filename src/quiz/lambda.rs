@@ -0,0 +1,54 @@
+use super::Question;
+
+pub const QUESTIONS: &[Question] = &[
+    Question {
+        prompt: "Which `Fn` trait does every closure implement?",
+        choices: &["Fn", "FnMut", "FnOnce", "None of them, necessarily"],
+        answer: 2,
+        explanation: "every closure can be called at least once, so every closure is `FnOnce`",
+    },
+    Question {
+        prompt:
+            "A closure moves a captured `String` out of its body. Which traits does it implement?",
+        choices: &[
+            "Only `FnOnce`",
+            "`FnOnce` and `FnMut`",
+            "All three",
+            "None, it doesn't compile",
+        ],
+        answer: 0,
+        explanation: "once the `String` has been moved out, there is nothing left to move on a \
+                      second call, so it can only be called once",
+    },
+    Question {
+        prompt: "A closure pushes onto a captured `Vec`. Which is the most it implements?",
+        choices: &["Fn", "FnMut", "FnOnce", "Copy"],
+        answer: 1,
+        explanation: "it mutates what it captured without moving it out, so it can be called \
+                      more than once, but not through a shared reference",
+    },
+    Question {
+        prompt: "Which closures implement `Fn`?",
+        choices: &[
+            "Only those that capture nothing",
+            "Only `move` closures",
+            "Those that neither move captured values out nor mutate them",
+            "All of them",
+        ],
+        answer: 2,
+        explanation: "including closures that capture nothing; they can be called any number of \
+                      times, even concurrently",
+    },
+    Question {
+        prompt: "What does the `move` keyword change about a closure?",
+        choices: &[
+            "Which `Fn` traits it implements",
+            "That it takes ownership of what it captures, instead of borrowing it",
+            "That it can only be called once",
+            "That it runs on another thread",
+        ],
+        answer: 1,
+        explanation: "how it captures, not what it does with what it captured, which is what \
+                      decides the `Fn` traits",
+    },
+];
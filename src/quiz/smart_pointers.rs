@@ -0,0 +1,71 @@
+use super::Question;
+
+pub const QUESTIONS: &[Question] = &[
+    Question {
+        prompt: "Why does `enum List { Cons(i32, List), Nil }` not compile?",
+        choices: &[
+            "A recursive type has no known size, so it needs a pointer such as `Box<List>`",
+            "Enums can't hold other enums",
+            "`i32` can't be stored in an enum variant",
+            "`Nil` needs a value",
+        ],
+        answer: 0,
+        explanation: "a `List` would contain a `List`, infinitely; a `Box<List>` is the size of a \
+                      pointer, whatever it points to",
+    },
+    Question {
+        prompt: "Which smart pointer lets a value have more than one owner?",
+        choices: &["Box<T>", "Rc<T>", "RefCell<T>", "&mut T"],
+        answer: 1,
+        explanation: "`Rc<T>` counts its owners, and drops the value when the last one goes away",
+    },
+    Question {
+        prompt: "What does `Rc::clone(&a)` do?",
+        choices: &[
+            "Deep clones the value `a` points to",
+            "Moves `a`",
+            "Increments the strong count, and returns another pointer to the same value",
+            "Creates a `Weak<T>`",
+        ],
+        answer: 2,
+        explanation: "it only increments a count, which is why it is cheap, and why the book \
+                      prefers `Rc::clone(&a)` to `a.clone()`, which looks like a deep clone",
+    },
+    Question {
+        prompt: "When are `RefCell<T>`'s borrowing rules checked?",
+        choices: &[
+            "At compile time",
+            "At run time, with a panic when they are broken",
+            "Never",
+            "Only in debug builds",
+        ],
+        answer: 1,
+        explanation: "`borrow` and `borrow_mut` keep count, and `borrow_mut` panics while the \
+                      value is already borrowed",
+    },
+    Question {
+        prompt: "How is a reference cycle between `Rc<T>`s broken, so that it doesn't leak?",
+        choices: &[
+            "With `mem::forget`",
+            "By calling `drop` twice",
+            "It can't be",
+            "By making one direction a `Weak<T>`, which doesn't keep the value alive",
+        ],
+        answer: 3,
+        explanation:
+            "a `Weak<T>` only counts towards the weak count, and `upgrade` returns `None` \
+                      once the value is gone",
+    },
+    Question {
+        prompt: "What makes `*y` work when `y` is a `MyBox<i32>` of our own?",
+        choices: &[
+            "An impl of `Deref` for `MyBox<T>`",
+            "An impl of `Drop` for `MyBox<T>`",
+            "Deriving `Copy`",
+            "Nothing; only `Box<T>` can be dereferenced",
+        ],
+        answer: 0,
+        explanation: "`*y` is `*(y.deref())`, so a type that implements `Deref` can be \
+                      dereferenced like a reference",
+    },
+];
@@ -0,0 +1,52 @@
+use super::Question;
+
+pub const QUESTIONS: &[Question] = &[
+    Question {
+        prompt: "Why does `thread::spawn(|| i)` not compile when `i` is a local variable?",
+        choices: &[
+            "`i` isn't `Copy`",
+            "The closure borrows `i`, and the thread may outlive the function that owns it",
+            "Threads can't return values",
+            "`thread::spawn` takes a function, not a closure",
+        ],
+        answer: 1,
+        explanation: "`spawn` requires a `'static` closure, and nothing guarantees the thread is \
+                      done before `i` goes out of scope",
+    },
+    Question {
+        prompt: "How is the thread given ownership of the values its closure uses?",
+        choices: &[
+            "With `move` before the closure",
+            "By cloning the closure",
+            "With `Rc<T>`",
+            "It always has it",
+        ],
+        answer: 0,
+        explanation: "`move || i` moves `i` into the closure, or copies it if it is `Copy`, as \
+                      an `i32` is",
+    },
+    Question {
+        prompt: "What does `handle.join()` return?",
+        choices: &[
+            "Nothing",
+            "The thread's id",
+            "A `Result`, with what the closure returned, or `Err` if the thread panicked",
+            "A new thread",
+        ],
+        answer: 2,
+        explanation: "joining waits for the thread to finish, and hands back its result, or the \
+                      payload of its panic",
+    },
+    Question {
+        prompt: "What happens to a spawned thread whose `JoinHandle` is dropped?",
+        choices: &[
+            "It is killed",
+            "It is joined",
+            "The program panics",
+            "It is detached, and carries on on its own",
+        ],
+        answer: 3,
+        explanation: "which is why the lesson keeps the handle in a guard that joins it, should \
+                      the function return early",
+    },
+];
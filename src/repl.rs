@@ -0,0 +1,253 @@
+//! # The REPL
+//! `intermediate_rust repl` runs one lesson after another without starting over each time:
+//! ```text
+//! > run smart-pointers rc
+//! > run --force unsafe-rust
+//! > list memory
+//! > last
+//! > quit
+//! ```
+//! A lesson is named as on the command line, and `last` runs the last lesson again. The progress
+//! is saved after each lesson, as it would be had the lesson been run on its own.
+
+use crate::{
+    catalog,
+    intermediate_rust::lesson::{Lesson, LessonCtx, Registry},
+    progress::Progress,
+    runner,
+};
+use std::{
+    io::{BufRead, Result, Write},
+    path::PathBuf,
+};
+
+const HELP: &str = "\
+run [--force] TOPIC [LESSON]  run a lesson, with --force even if it isn't ready yet
+list [TAG]                    list the lessons, or only the ones tagged TAG
+last                          run the last lesson again
+help                          show this
+quit                          leave";
+
+#[derive(Debug, PartialEq, Eq)]
+enum Input<'l> {
+    Run { name: String, forced: bool },
+    List { tag: Option<&'l str> },
+    Last,
+    Help,
+    Quit,
+    Nothing,
+}
+
+fn parse(line: &str) -> std::result::Result<Input<'_>, String> {
+    let mut words = line.split_whitespace();
+    let input = match words.next() {
+        None => Input::Nothing,
+        Some("run") => {
+            let mut words = words.peekable();
+            let forced = words.next_if_eq(&"--force").is_some();
+            let name: Vec<_> = words.collect();
+            if name.is_empty() {
+                return Err(String::from("run which lesson? `list` lists them"));
+            }
+            return Ok(Input::Run {
+                name: name.join(" "),
+                forced,
+            });
+        }
+        Some("list") => Input::List { tag: words.next() },
+        Some("last") => Input::Last,
+        Some("help") => Input::Help,
+        Some("quit" | "exit") => Input::Quit,
+        Some(command) => return Err(format!("unknown command `{command}`, try `help`")),
+    };
+    match words.next() {
+        None => Ok(input),
+        Some(word) => Err(format!("unexpected `{word}`, try `help`")),
+    }
+}
+
+pub struct Repl<'r> {
+    registry: &'r Registry,
+    ctx: LessonCtx,
+    progress: Progress,
+    /// Where the progress is saved, if anywhere.
+    path: Option<PathBuf>,
+    last: Option<&'r dyn Lesson>,
+}
+impl<'r> Repl<'r> {
+    pub fn new(
+        registry: &'r Registry,
+        ctx: LessonCtx,
+        progress: Progress,
+        path: Option<PathBuf>,
+    ) -> Repl<'r> {
+        Repl {
+            registry,
+            ctx,
+            progress,
+            path,
+            last: None,
+        }
+    }
+
+    /// Reads and runs commands from `input` until `quit` or the end of the input. The prompt and
+    /// what the commands have to say go to `out`, and the lessons print as they always do.
+    pub fn run(&mut self, input: impl BufRead, out: &mut impl Write) -> Result<()> {
+        write!(out, "> ")?;
+        out.flush()?;
+        for line in input.lines() {
+            if !self.eval(&line?, out)? {
+                return Ok(());
+            }
+            write!(out, "> ")?;
+            out.flush()?;
+        }
+        writeln!(out)
+    }
+
+    /// Runs one command, and returns whether to carry on. Only failing to write to `out` is an
+    /// error; a lesson that fails, or a command that makes no sense, is reported to `out`.
+    fn eval(&mut self, line: &str, out: &mut impl Write) -> Result<bool> {
+        let lesson = match parse(line) {
+            Err(x) => return writeln!(out, "{x}").map(|_| true),
+            Ok(Input::Nothing) => return Ok(true),
+            Ok(Input::Quit) => return Ok(false),
+            Ok(Input::Help) => return writeln!(out, "{HELP}").map(|_| true),
+            Ok(Input::List { tag }) => {
+                match catalog::list(self.registry, tag, &self.progress) {
+                    Ok(catalog) => write!(out, "{catalog}")?,
+                    Err(x) => writeln!(out, "{x}")?,
+                }
+                return Ok(true);
+            }
+            Ok(Input::Last) => match self.last {
+                Some(lesson) => (lesson, false),
+                None => return writeln!(out, "no lesson has run yet").map(|_| true),
+            },
+            Ok(Input::Run { name, forced }) => match self.registry.lookup(&name) {
+                Ok(lesson) => (lesson, forced),
+                Err(x) => return writeln!(out, "{x}").map(|_| true),
+            },
+        };
+        let (lesson, forced) = lesson;
+        self.last = Some(lesson);
+        match runner::run_one(lesson, &mut self.ctx, &mut self.progress, forced) {
+            Ok(_) => writeln!(out, "`{}` passed", lesson.id())?,
+            Err(x) => writeln!(out, "`{}` failed: {x}", lesson.id())?,
+        }
+        if let Some(path) = &self.path {
+            if let Err(x) = self.progress.save(path) {
+                writeln!(out, "the progress couldn't be saved: {x}")?;
+            }
+        }
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intermediate_rust::lesson::{Demo, LessonInfo};
+    use std::io::Error;
+
+    fn registry() -> Registry {
+        let mut registry = Registry::new();
+        registry.register(Demo {
+            id: "basics",
+            title: "Basics",
+            run: || Ok(()),
+            info: LessonInfo {
+                description: "The basics",
+                tags: &["easy"],
+                ..LessonInfo::default()
+            },
+        });
+        registry.register(Demo {
+            id: "more",
+            title: "More",
+            run: || Err(Error::other("no luck")),
+            info: LessonInfo {
+                description: "More than the basics",
+                prerequisites: &["basics"],
+                ..LessonInfo::default()
+            },
+        });
+        registry
+    }
+
+    fn session(input: &str) -> (String, Progress) {
+        let registry = registry();
+        let mut repl = Repl::new(&registry, LessonCtx::default(), Progress::default(), None);
+        let mut out = Vec::new();
+        repl.run(input.as_bytes(), &mut out).unwrap();
+        (String::from_utf8(out).unwrap(), repl.progress)
+    }
+
+    #[test]
+    fn commands_are_parsed_with_their_arguments() {
+        assert_eq!(
+            parse("run smart-pointers rc"),
+            Ok(Input::Run {
+                name: String::from("smart-pointers rc"),
+                forced: false
+            })
+        );
+        assert_eq!(
+            parse("  run --force   unsafe-rust "),
+            Ok(Input::Run {
+                name: String::from("unsafe-rust"),
+                forced: true
+            })
+        );
+        assert_eq!(
+            parse("list memory"),
+            Ok(Input::List {
+                tag: Some("memory")
+            })
+        );
+        assert_eq!(parse(""), Ok(Input::Nothing));
+        assert!(parse("run").is_err());
+        assert!(parse("last lesson").is_err());
+        assert!(parse("walk").is_err());
+    }
+
+    #[test]
+    fn lessons_run_and_are_completed() {
+        let (out, progress) = session("run basics\nrun more\nlast\n");
+
+        assert_eq!(
+            out,
+            "> `basics` passed\n\
+             > `more` failed: no luck\n\
+             > `more` failed: no luck\n\
+             > \n"
+        );
+        assert!(progress.is_completed("basics"));
+        assert!(!progress.is_completed("more"));
+    }
+
+    #[test]
+    fn mistakes_are_reported_and_the_session_carries_on() {
+        let (out, _) = session("last\nrun nothing\nrun more\nlist hard\nquit\nrun basics\n");
+
+        assert_eq!(
+            out,
+            "> no lesson has run yet\n\
+             > there is no topic `nothing`, the topics are: basics, more\n\
+             > `more` failed: `more` builds on `basics`, which you haven't completed yet: run it \
+             first, or pass --force\n\
+             > no lesson is tagged `hard`, the tags are: easy\n\
+             > "
+        );
+    }
+
+    #[test]
+    fn list_shows_the_progress_so_far() {
+        let (out, _) = session("run basics\nlist easy\nquit\n");
+
+        assert!(
+            out.contains("done  basics  beginner      Basics\n"),
+            "{out}"
+        );
+    }
+}
@@ -0,0 +1,397 @@
+//! # A Report of the Work So Far
+//! `intermediate_rust report` sums up what the other commands remember: the lessons completed,
+//! kept by `progress`, the best score of each quiz, kept by `quiz`, and the hints taken for each
+//! exercise, kept by `exercises`. Whether an exercise is solved isn’t remembered anywhere, since
+//! its stub is the record of that, so the report verifies each of them again, quietly.
+//! ```text
+//! intermediate_rust report
+//! intermediate_rust report --out report.html --name "Ada Lovelace"
+//! ```
+//! `--out` also writes the report to a file, in Markdown or as an HTML page, as its extension
+//! says. Once every lesson is completed and every exercise solved, the file is a certificate of
+//! completion, made out to `--name`.
+
+use crate::{
+    exercises::{self, Exercise, Hints},
+    intermediate_rust::{lesson::Registry, style::Style},
+    progress::Progress,
+    quiz::{self, Score, Scores},
+};
+use std::{
+    fmt::Write as _,
+    io::{self, Result},
+    path::Path,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExerciseStatus {
+    pub id: &'static str,
+    pub solved: bool,
+    pub hints_used: usize,
+    /// The hints there are, and then the solution.
+    pub tiers: usize,
+}
+impl ExerciseStatus {
+    fn help(&self) -> String {
+        match self.hints_used {
+            0 => String::from("without a hint"),
+            used if used >= self.tiers => String::from("after seeing the solution"),
+            used => format!("with {used} of {} hint(s)", self.tiers - 1),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub completed: usize,
+    pub lessons: usize,
+    /// Each chapter with a quiz, and its best score, if it has been taken.
+    pub quizzes: Vec<(&'static str, Option<Score>)>,
+    pub exercises: Vec<ExerciseStatus>,
+}
+impl Report {
+    /// Verifies `exercises` to find out which are solved; what they print goes nowhere.
+    pub fn new(
+        registry: &Registry,
+        progress: &Progress,
+        scores: &Scores,
+        exercises: &[Exercise],
+        hints: &Hints,
+    ) -> Result<Report> {
+        let lessons: Vec<_> = registry.iter().collect();
+        let mut statuses = Vec::new();
+        for exercise in exercises {
+            statuses.push(ExerciseStatus {
+                id: exercise.id,
+                solved: exercises::verify(exercise, &mut io::sink())?,
+                hints_used: hints.used(exercise.id),
+                tiers: exercise.tiers(),
+            });
+        }
+        Ok(Report {
+            completed: lessons
+                .iter()
+                .filter(|lesson| progress.is_completed(lesson.id()))
+                .count(),
+            lessons: lessons.len(),
+            quizzes: quiz::BANKS
+                .iter()
+                .map(|(chapter, _)| (*chapter, scores.best(chapter)))
+                .collect(),
+            exercises: statuses,
+        })
+    }
+
+    /// The average of the best scores of the quizzes taken, as a percentage.
+    pub fn quiz_average(&self) -> Option<f64> {
+        let taken: Vec<_> = self
+            .quizzes
+            .iter()
+            .filter_map(|(_, score)| score.filter(|score| score.asked > 0))
+            .collect();
+        let sum: f64 = taken
+            .iter()
+            .map(|score| score.right as f64 / score.asked as f64)
+            .sum();
+        (!taken.is_empty()).then(|| 100.0 * sum / taken.len() as f64)
+    }
+
+    pub fn solved(&self) -> usize {
+        self.exercises.iter().filter(|status| status.solved).count()
+    }
+
+    pub fn hints_used(&self) -> usize {
+        self.exercises.iter().map(|status| status.hints_used).sum()
+    }
+
+    /// Every lesson completed and every exercise solved.
+    pub fn is_complete(&self) -> bool {
+        self.completed == self.lessons && self.solved() == self.exercises.len()
+    }
+
+    /// The report for the terminal, with its headings colored if `color`.
+    pub fn terminal(&self, color: bool) -> String {
+        let mut text = String::new();
+        let heading = |name: &str| {
+            Style::Header
+                .paint(format!("{name:<10}"), color)
+                .to_string()
+        };
+        let _ = writeln!(
+            text,
+            "{}  {}/{}  {}",
+            heading("lessons"),
+            self.completed,
+            self.lessons,
+            bar(self.completed, self.lessons, 20)
+        );
+        let _ = writeln!(
+            text,
+            "{}  {}",
+            heading("quizzes"),
+            match self.quiz_average() {
+                Some(average) => format!("{average:.0}% on average"),
+                None => String::from("none taken yet"),
+            }
+        );
+        for (chapter, score) in self.quiz_lines() {
+            let _ = writeln!(text, "  {chapter:<16}  {score}");
+        }
+        let _ = writeln!(
+            text,
+            "{}  {}/{} solved, {} hint(s) used",
+            heading("exercises"),
+            self.solved(),
+            self.exercises.len(),
+            self.hints_used()
+        );
+        for (id, status) in self.exercise_lines() {
+            let _ = writeln!(text, "  {id:<16}  {status}");
+        }
+        if self.is_complete() {
+            let _ = writeln!(text, "\nevery lesson completed and every exercise solved!");
+        }
+        text
+    }
+
+    /// The report in Markdown, as a certificate if it is complete.
+    pub fn markdown(&self, name: Option<&str>) -> String {
+        let mut text = format!("# {}\n\n", self.title());
+        if let Some(awarded) = self.awarded(name) {
+            let _ = writeln!(text, "{awarded}\n");
+        }
+        let _ = writeln!(
+            text,
+            "- Lessons completed: {}/{}",
+            self.completed, self.lessons
+        );
+        let _ = writeln!(
+            text,
+            "- Exercises solved: {}/{}, with {} hint(s)",
+            self.solved(),
+            self.exercises.len(),
+            self.hints_used()
+        );
+        if let Some(average) = self.quiz_average() {
+            let _ = writeln!(text, "- Quiz average: {average:.0}%");
+        }
+        text.push_str("\n| Quiz | Best score |\n| --- | --- |\n");
+        for (chapter, score) in self.quiz_lines() {
+            let _ = writeln!(text, "| `{chapter}` | {score} |");
+        }
+        text.push_str("\n| Exercise | |\n| --- | --- |\n");
+        for (id, status) in self.exercise_lines() {
+            let _ = writeln!(text, "| `{id}` | {status} |");
+        }
+        text
+    }
+
+    /// The report as an HTML page of its own, as a certificate if it is complete.
+    pub fn html(&self, name: Option<&str>) -> String {
+        let title = self.title();
+        let mut body = format!("<h1>{title}</h1>\n");
+        if let Some(awarded) = self.awarded(name) {
+            let _ = writeln!(body, "<p class=\"awarded\">{}</p>", escape(&awarded));
+        }
+        let _ = writeln!(
+            body,
+            "<ul>\n<li>Lessons completed: {}/{}</li>",
+            self.completed, self.lessons
+        );
+        let _ = writeln!(
+            body,
+            "<li>Exercises solved: {}/{}, with {} hint(s)</li>",
+            self.solved(),
+            self.exercises.len(),
+            self.hints_used()
+        );
+        if let Some(average) = self.quiz_average() {
+            let _ = writeln!(body, "<li>Quiz average: {average:.0}%</li>");
+        }
+        body.push_str("</ul>\n<table>\n<tr><th>Quiz</th><th>Best score</th></tr>\n");
+        for (chapter, score) in self.quiz_lines() {
+            let _ = writeln!(
+                body,
+                "<tr><td><code>{chapter}</code></td><td>{score}</td></tr>"
+            );
+        }
+        body.push_str("</table>\n<table>\n<tr><th>Exercise</th><th></th></tr>\n");
+        for (id, status) in self.exercise_lines() {
+            let _ = writeln!(body, "<tr><td><code>{id}</code></td><td>{status}</td></tr>");
+        }
+        body.push_str("</table>\n");
+        format!(
+            "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+             <title>{title}</title>\n<style>{STYLE}</style>\n</head>\n<body>\n{body}</body>\n\
+             </html>\n"
+        )
+    }
+
+    /// The report in the format the extension of `path` says.
+    pub fn file(&self, path: &Path, name: Option<&str>) -> std::result::Result<String, String> {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some("md" | "markdown") => Ok(self.markdown(name)),
+            Some("html" | "htm") => Ok(self.html(name)),
+            _ => Err(format!(
+                "can't write a report to `{}`, only to a `.md` or an `.html` file",
+                path.display()
+            )),
+        }
+    }
+
+    fn title(&self) -> &'static str {
+        match self.is_complete() {
+            true => "Certificate of Completion: Intermediate Rust",
+            false => "Intermediate Rust: Progress Report",
+        }
+    }
+
+    fn awarded(&self, name: Option<&str>) -> Option<String> {
+        self.is_complete().then(|| {
+            format!(
+                "{} completed every lesson of Intermediate Rust and solved every exercise.",
+                name.unwrap_or("The holder of this certificate")
+            )
+        })
+    }
+
+    fn quiz_lines(&self) -> impl Iterator<Item = (&'static str, String)> + '_ {
+        self.quizzes.iter().map(|(chapter, score)| {
+            let score = score.map_or_else(|| String::from("not taken"), |score| score.to_string());
+            (*chapter, score)
+        })
+    }
+
+    fn exercise_lines(&self) -> impl Iterator<Item = (&'static str, String)> + '_ {
+        self.exercises.iter().map(|status| {
+            let solved = match status.solved {
+                true => "solved",
+                false => "not yet",
+            };
+            (status.id, format!("{solved}, {}", status.help()))
+        })
+    }
+}
+
+const STYLE: &str = "body { font-family: sans-serif; max-width: 40em; margin: 3em auto; } \
+    .awarded { font-size: 1.3em; } table { border-collapse: collapse; margin: 1em 0; } \
+    td, th { border: 1px solid #ccc; padding: 0.3em 0.8em; text-align: left; }";
+
+/// `done` out of `total`, as a bar `width` wide, and a percentage.
+fn bar(done: usize, total: usize, width: usize) -> String {
+    let filled = (done * width).checked_div(total).unwrap_or(0);
+    let percent = (100 * done).checked_div(total).unwrap_or(0);
+    format!(
+        "[{}{}] {percent:>3}%",
+        "#".repeat(filled),
+        "-".repeat(width - filled)
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intermediate_rust;
+
+    fn report(completed: usize, solved: bool) -> Report {
+        Report {
+            completed,
+            lessons: 4,
+            quizzes: vec![
+                ("lambda", Some(Score { right: 3, asked: 4 })),
+                ("smart-pointers", Some(Score { right: 5, asked: 5 })),
+                ("thread-and-move", None),
+            ],
+            exercises: vec![
+                ExerciseStatus {
+                    id: "counter",
+                    solved: true,
+                    hints_used: 0,
+                    tiers: 3,
+                },
+                ExerciseStatus {
+                    id: "deref",
+                    solved,
+                    hints_used: 1,
+                    tiers: 3,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn it_averages_the_quizzes_taken() {
+        assert_eq!(report(1, false).quiz_average(), Some(87.5));
+        let mut untaken = report(1, false);
+        untaken.quizzes = vec![("lambda", None)];
+        assert_eq!(untaken.quiz_average(), None);
+    }
+
+    #[test]
+    fn it_sums_up_the_work_so_far() {
+        assert_eq!(
+            report(2, false).terminal(false),
+            "\
+lessons     2/4  [##########----------]  50%
+quizzes     88% on average
+  lambda            3/4
+  smart-pointers    5/5
+  thread-and-move   not taken
+exercises   1/2 solved, 1 hint(s) used
+  counter           solved, without a hint
+  deref             not yet, with 1 of 2 hint(s)
+"
+        );
+    }
+
+    #[test]
+    fn only_a_complete_report_is_a_certificate() {
+        let partial = report(4, false).markdown(Some("Ada"));
+        assert!(partial.starts_with("# Intermediate Rust: Progress Report\n"));
+        assert!(!partial.contains("Ada"));
+
+        let complete = report(4, true).html(Some("Ada <3"));
+        assert!(complete.contains("<h1>Certificate of Completion: Intermediate Rust</h1>"));
+        assert!(
+            complete.contains("Ada &lt;3 completed every lesson"),
+            "{complete}"
+        );
+    }
+
+    #[test]
+    fn the_file_format_is_the_extension() {
+        let report = report(1, false);
+        assert!(report
+            .file(Path::new("r.md"), None)
+            .unwrap()
+            .starts_with("# "));
+        assert!(report
+            .file(Path::new("r.html"), None)
+            .unwrap()
+            .starts_with("<!DOCTYPE"));
+        assert!(report.file(Path::new("r.pdf"), None).is_err());
+    }
+
+    #[test]
+    fn the_exercises_as_handed_out_are_not_solved() {
+        let report = Report::new(
+            &intermediate_rust::registry(),
+            &Progress::default(),
+            &Scores::default(),
+            exercises::EXERCISES,
+            &Hints::default(),
+        )
+        .unwrap();
+        assert_eq!(report.completed, 0);
+        assert_eq!(report.solved(), 0);
+        assert_eq!(report.quiz_average(), None);
+        assert_eq!(report.exercises.len(), exercises::EXERCISES.len());
+    }
+}
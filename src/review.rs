@@ -0,0 +1,330 @@
+//! # Reviewing What Was Learned
+//! What was learned once is forgotten, unless it is recalled again, and it is remembered longer
+//! each time it is. `intermediate_rust review` asks the quiz questions of the chapters with a
+//! completed lesson, each when it is due, with a simplified SM-2, the scheduler of SuperMemo:
+//! - a question is first due as soon as its chapter has a completed lesson,
+//! - answered right, it is due again in 1 day, then 6, then each time its interval multiplied
+//!   by its _ease_, which starts at 2.5 and grows by 0.1 with each right answer,
+//! - answered wrong, it starts over at 1 day, and its ease drops by 0.54, to no less than 1.3,
+//!   so that the questions that are hard are asked more often.
+//!
+//! ```text
+//! intermediate_rust review              # up to 10 of the questions due today
+//! intermediate_rust review --limit 3
+//! ```
+//! The schedule is kept in `.intermediate_rust_review` in the current directory, or wherever
+//! `INTERMEDIATE_RUST_REVIEW` says, one question per line.
+
+use crate::{
+    progress::Progress,
+    quiz::{self, Question},
+};
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    io::{BufRead, ErrorKind, Result, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// When a question is due, and how long until it is due again after a right answer. The ease is
+/// in hundredths, so that the schedule is saved and compared exactly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Card {
+    pub ease: u32,
+    /// In days.
+    pub interval: u32,
+    /// How many times in a row it was answered right.
+    pub repetitions: u32,
+    /// The day it is due, counted from the Unix epoch.
+    pub due: u64,
+}
+impl Card {
+    const EASE: u32 = 250;
+    const MIN_EASE: u32 = 130;
+
+    /// A question never asked, due on `today`.
+    pub fn new(today: u64) -> Card {
+        Card {
+            ease: Card::EASE,
+            interval: 0,
+            repetitions: 0,
+            due: today,
+        }
+    }
+
+    /// The card after the question was answered on `today`, `right`ly or not.
+    pub fn answered(self, right: bool, today: u64) -> Card {
+        let (ease, interval, repetitions) = match (right, self.repetitions) {
+            (false, _) => ((self.ease.saturating_sub(54)).max(Card::MIN_EASE), 1, 0),
+            (true, 0) => (self.ease + 10, 1, 1),
+            (true, 1) => (self.ease + 10, 6, 2),
+            (true, repetitions) => (
+                self.ease + 10,
+                (self.interval * self.ease).div_ceil(100),
+                repetitions + 1,
+            ),
+        };
+        Card {
+            ease,
+            interval,
+            repetitions,
+            due: today + u64::from(interval),
+        }
+    }
+}
+
+/// The day it is, counted from the Unix epoch.
+pub fn today() -> u64 {
+    let since = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    since.as_secs() / (24 * 60 * 60)
+}
+
+/// A question of a chapter’s quiz, and what it is called in the schedule: `lambda#2` for the
+/// third question of `lambda`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Due {
+    pub chapter: &'static str,
+    pub index: usize,
+    pub question: &'static Question,
+}
+impl Due {
+    pub fn key(&self) -> String {
+        format!("{}#{}", self.chapter, self.index)
+    }
+}
+
+/// Whether one of the lessons of `chapter`, a topic, has been completed.
+fn started(progress: &Progress, chapter: &str, ids: &[&str]) -> bool {
+    ids.iter().any(|id| {
+        (*id == chapter
+            || id
+                .strip_prefix(chapter)
+                .is_some_and(|rest| rest.starts_with('/')))
+            && progress.is_completed(id)
+    })
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Schedule {
+    cards: BTreeMap<String, Card>,
+}
+impl Schedule {
+    pub fn path() -> PathBuf {
+        env::var_os("INTERMEDIATE_RUST_REVIEW")
+            .map_or_else(|| PathBuf::from(".intermediate_rust_review"), PathBuf::from)
+    }
+
+    /// No file yet means nothing has been reviewed yet, and a line that can’t be read is skipped.
+    /// Under Miri, which has no file system, nothing is ever loaded or saved.
+    pub fn load(path: &Path) -> Result<Schedule> {
+        if cfg!(miri) {
+            return Ok(Schedule::default());
+        }
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(x) if x.kind() == ErrorKind::NotFound => return Ok(Schedule::default()),
+            Err(x) => return Err(x),
+        };
+        let cards = text
+            .lines()
+            .filter_map(|line| {
+                let [key, ease, interval, repetitions, due] =
+                    line.split_whitespace().collect::<Vec<_>>()[..]
+                else {
+                    return None;
+                };
+                let card = Card {
+                    ease: ease.parse().ok()?,
+                    interval: interval.parse().ok()?,
+                    repetitions: repetitions.parse().ok()?,
+                    due: due.parse().ok()?,
+                };
+                Some((key.to_string(), card))
+            })
+            .collect();
+        Ok(Schedule { cards })
+    }
+
+    /// One line per question: its key, its ease, interval and repetitions, and when it is due.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        if cfg!(miri) {
+            return Ok(());
+        }
+        let mut text = String::new();
+        for (key, card) in &self.cards {
+            text.push_str(&format!(
+                "{key} {} {} {} {}\n",
+                card.ease, card.interval, card.repetitions, card.due
+            ));
+        }
+        fs::write(path, text)
+    }
+
+    pub fn card(&self, key: &str, today: u64) -> Card {
+        self.cards.get(key).copied().unwrap_or(Card::new(today))
+    }
+
+    /// The questions of the chapters started in `progress` that are due by `today`, the longest
+    /// overdue first, and in the order of the chapters and their questions otherwise. `ids` are
+    /// the ids of every lesson.
+    pub fn due(&self, progress: &Progress, ids: &[&str], today: u64) -> Vec<Due> {
+        let mut due: Vec<_> = quiz::BANKS
+            .iter()
+            .filter(|(chapter, _)| started(progress, chapter, ids))
+            .flat_map(|(chapter, questions)| {
+                questions.iter().enumerate().map(|(index, question)| Due {
+                    chapter,
+                    index,
+                    question,
+                })
+            })
+            .filter(|due| self.card(&due.key(), today).due <= today)
+            .collect();
+        due.sort_by_key(|due| self.card(&due.key(), today).due);
+        due
+    }
+
+    /// The day the next question of the chapters started in `progress` is due after `today`.
+    pub fn next(&self, progress: &Progress, ids: &[&str], today: u64) -> Option<u64> {
+        quiz::BANKS
+            .iter()
+            .filter(|(chapter, _)| started(progress, chapter, ids))
+            .flat_map(|(chapter, questions)| {
+                (0..questions.len()).map(move |index| format!("{chapter}#{index}"))
+            })
+            .map(|key| self.card(&key, today).due)
+            .filter(|due| *due > today)
+            .min()
+    }
+
+    /// Asks the questions of `due`, up to `limit` of them, reading the answers from `input`, and
+    /// reschedules each one answered. Returns how many were answered, and how many right.
+    pub fn review(
+        &mut self,
+        due: &[Due],
+        limit: usize,
+        today: u64,
+        input: impl BufRead,
+        out: &mut impl Write,
+    ) -> Result<quiz::Score> {
+        let mut lines = input.lines();
+        let mut score = quiz::Score::default();
+        let due = &due[..due.len().min(limit)];
+        for (number, question) in due.iter().enumerate() {
+            let heading = format!("{}/{} ({}).", number + 1, due.len(), question.chapter);
+            let Some(right) = quiz::ask_one(&heading, question.question, &mut lines, out)? else {
+                break;
+            };
+            score.asked += 1;
+            score.right += usize::from(right);
+            let key = question.key();
+            let card = self.card(&key, today).answered(right, today);
+            self.cards.insert(key, card);
+        }
+        Ok(score)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const IDS: &[&str] = &[
+        "lambda",
+        "thread-and-move",
+        "smart-pointers/box",
+        "memory/dst",
+    ];
+
+    fn progress(completed: &[&str]) -> Progress {
+        let mut progress = Progress::default();
+        for id in completed {
+            progress.complete(id);
+        }
+        progress
+    }
+
+    #[test]
+    fn right_answers_space_the_question_out() {
+        let mut card = Card::new(100);
+        let mut intervals = Vec::new();
+        for day in [100, 101, 107, 123] {
+            card = card.answered(true, day);
+            intervals.push(card.interval);
+        }
+        assert_eq!(intervals, [1, 6, 17, 48]);
+        assert_eq!(card.ease, 290);
+        assert_eq!(card.due, 123 + 48);
+    }
+
+    #[test]
+    fn a_wrong_answer_starts_over_and_makes_it_harder() {
+        let card = Card::new(0).answered(true, 0).answered(true, 1);
+        let wrong = card.answered(false, 7);
+        assert_eq!((wrong.interval, wrong.repetitions, wrong.due), (1, 0, 8));
+        assert_eq!(wrong.ease, 270 - 54);
+
+        let mut hard = Card::new(0);
+        for _ in 0..5 {
+            hard = hard.answered(false, 0);
+        }
+        assert_eq!(hard.ease, 130);
+    }
+
+    #[cfg(feature = "smart-pointers")]
+    #[test]
+    fn only_the_chapters_started_are_due() {
+        let schedule = Schedule::default();
+        assert!(schedule.due(&progress(&[]), IDS, 0).is_empty());
+        assert!(schedule.due(&progress(&["memory/dst"]), IDS, 0).is_empty());
+
+        let due = schedule.due(&progress(&["smart-pointers/box"]), IDS, 0);
+        assert_eq!(due.len(), quiz::bank("smart-pointers").unwrap().1.len());
+        assert_eq!(due[0].key(), "smart-pointers#0");
+    }
+
+    #[cfg(feature = "closures")]
+    #[test]
+    fn a_review_reschedules_what_was_answered() {
+        let progress = progress(&["lambda"]);
+        let mut schedule = Schedule::default();
+        let due = schedule.due(&progress, IDS, 10);
+        // the first right, and the second wrong, after an answer that isn't one
+        let right = char::from(b'a' + due[0].question.answer as u8);
+        let answers = format!("{right}\nz\nd\n");
+
+        let score = schedule
+            .review(&due, 2, 10, answers.as_bytes(), &mut Vec::new())
+            .unwrap();
+
+        assert_eq!(score, quiz::Score { right: 1, asked: 2 });
+        assert_eq!(schedule.card("lambda#0", 10).due, 11);
+        assert_eq!(schedule.card("lambda#1", 10).due, 11);
+        assert_eq!(schedule.card("lambda#1", 10).ease, 196);
+        // the rest are still due today, and nothing is due after tomorrow
+        assert_eq!(schedule.due(&progress, IDS, 10).len(), due.len() - 2);
+        assert_eq!(schedule.next(&progress, IDS, 10), Some(11));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)] // under Miri, nothing is saved
+    fn the_schedule_is_saved_and_loaded_again() {
+        let path = env::temp_dir().join(format!("review-{}", std::process::id()));
+        assert_eq!(Schedule::load(&path).unwrap(), Schedule::default());
+
+        let mut schedule = Schedule::default();
+        schedule
+            .cards
+            .insert(String::from("lambda#3"), Card::new(5).answered(true, 5));
+        schedule.save(&path).unwrap();
+        let text = fs::read_to_string(&path).unwrap();
+        let loaded = Schedule::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(text, "lambda#3 260 1 1 6\n");
+        assert_eq!(loaded, schedule);
+    }
+}
@@ -0,0 +1,542 @@
+//! # Running the Lessons
+//! `run_one` runs a single lesson, and `run_all` runs each lesson in turn; both measure it, with
+//! `measure`, for how long it took and, with the `track-alloc` feature, what it allocated, and
+//! print what it counted with `metrics` after it. With the `tracing` feature, each lesson also
+//! runs in a `lesson` span, which its parts nest in. A lesson that returns an `Err`, or panics on
+//! one of its `assert!`s, is recorded as failed, and the rest still run. A lesson whose
+//! prerequisites haven’t been completed doesn’t run at all; `run_all` runs the lessons in the
+//! order they were registered, so their prerequisites run first.
+//!
+//! `run_captured` runs a lesson for what it prints instead, to be checked rather than read, and
+//! `run` runs one by name, for code that embeds the lessons and keeps no progress of its own.
+//!
+//! After `--all`, `report` compares the lessons that passed by what they cost, slowest first.
+
+use crate::{
+    intermediate_rust::{
+        lesson::{Lesson, LessonCtx, Registry},
+        metrics::{self, Metrics},
+        output::{Buffer, Output, Verbosity},
+        style::Style,
+        utils::measure::{self, Allocs, Measurement},
+    },
+    progress::Progress,
+};
+use std::{
+    any::Any,
+    cmp::Reverse,
+    fmt::Write as _,
+    io::{Error, Result, Write},
+    panic::{self, AssertUnwindSafe},
+    time::{Duration, Instant},
+};
+
+/// How one lesson went.
+#[derive(Debug)]
+pub struct Outcome {
+    pub name: String,
+    /// Why the lesson failed, or `None` if it didn’t.
+    pub failure: Option<String>,
+    pub duration: Duration,
+    /// What the lesson allocated, with the `track-alloc` feature.
+    pub allocs: Option<Allocs>,
+}
+
+/// Runs `lesson`, unless it has prerequisites that haven’t been completed and it isn’t `forced`,
+/// and records it as completed if it succeeds.
+pub fn run_one(
+    lesson: &dyn Lesson,
+    ctx: &mut LessonCtx,
+    progress: &mut Progress,
+    forced: bool,
+) -> Result<Measurement> {
+    match progress.missing(lesson) {
+        missing if !missing.is_empty() && !forced => Err(Error::other(format!(
+            "`{}` builds on {}, which you haven't completed yet: run {} first, or pass --force",
+            lesson.id(),
+            quoted(&missing),
+            if missing.len() == 1 { "it" } else { "them" }
+        ))),
+        _ => {
+            #[cfg(feature = "tracing")]
+            let _span = tracing::info_span!("lesson", id = lesson.id()).entered();
+            let ((result, measurement), metrics) =
+                metrics::collect(|| measure::measure(|| lesson.run(ctx)));
+            show_metrics(ctx, metrics);
+            result?;
+            progress.complete(lesson.id());
+            Ok(measurement)
+        }
+    }
+}
+
+/// What a lesson printed, and how long it took.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LessonOutput {
+    pub stdout: String,
+    pub stderr: String,
+    pub duration: Duration,
+}
+
+/// Runs `lesson` with what it prints captured. This is a lesson run for its output, not by a
+/// learner, so its prerequisites aren’t checked, and it isn’t recorded as completed. At
+/// `Verbosity::Quiet` it captures nothing, as `--quiet` prints nothing.
+pub fn run_captured(lesson: &dyn Lesson, verbosity: Verbosity) -> Result<LessonOutput> {
+    let (stdout, stderr) = (Buffer::new(), Buffer::new());
+    let mut ctx = LessonCtx {
+        output: match verbosity {
+            Verbosity::Quiet => Output::silent(),
+            _ => Output {
+                verbosity,
+                ..Output::captured(&stdout, &stderr)
+            },
+        },
+        // a fixed seed, so that what the lesson prints can be checked
+        ..LessonCtx::default()
+    };
+    let start = Instant::now();
+    lesson.run(&mut ctx)?;
+    Ok(LessonOutput {
+        stdout: stdout.text(),
+        stderr: stderr.text(),
+        duration: start.elapsed(),
+    })
+}
+
+/// Runs the lesson `name` finds in `registry`, as `Registry::find` finds it, in `ctx`. Like
+/// `run_captured`, it neither checks nor records progress.
+pub fn run(registry: &Registry, name: &str, ctx: &mut LessonCtx) -> Result<()> {
+    registry.lookup(name).map_err(Error::other)?.run(ctx)
+}
+
+pub fn run_all(registry: &Registry, ctx: &mut LessonCtx, progress: &mut Progress) -> Vec<Outcome> {
+    run_each(registry.iter(), ctx, progress)
+}
+
+/// Runs each lesson in turn, whatever happened to the ones before it.
+fn run_each<'r>(
+    lessons: impl IntoIterator<Item = &'r dyn Lesson>,
+    ctx: &mut LessonCtx,
+    progress: &mut Progress,
+) -> Vec<Outcome> {
+    lessons
+        .into_iter()
+        .map(|lesson| {
+            let header = format!("===== {}: {} =====", lesson.id(), lesson.title());
+            let header = Style::Header.paint(header, ctx.output.color);
+            let _ = writeln!(ctx.output.out, "\n{header}");
+            let missing = progress.missing(lesson);
+            if !missing.is_empty() {
+                return Outcome {
+                    name: lesson.id().to_string(),
+                    failure: Some(format!("skipped: {} didn't pass", quoted(&missing))),
+                    duration: Duration::ZERO,
+                    allocs: None,
+                };
+            }
+            #[cfg(feature = "tracing")]
+            let _span = tracing::info_span!("lesson", id = lesson.id()).entered();
+            // the lessons only share state through thread locals they clean up themselves, so
+            // nothing is left half-updated for the next lesson to observe after a panic
+            let ((result, Measurement { duration, allocs }), metrics) = metrics::collect(|| {
+                measure::measure(|| panic::catch_unwind(AssertUnwindSafe(|| lesson.run(ctx))))
+            });
+            show_metrics(ctx, metrics);
+            let failure = match result {
+                Ok(Ok(())) => None,
+                Ok(Err(x)) => Some(x.to_string()),
+                Err(panic) => Some(format!("panicked: {}", panic_message(&*panic))),
+            };
+            if failure.is_none() {
+                progress.complete(lesson.id());
+            }
+            Outcome {
+                name: lesson.id().to_string(),
+                failure,
+                duration,
+                allocs,
+            }
+        })
+        .collect()
+}
+
+/// Prints what the lesson counted, if anything, after what it printed itself.
+fn show_metrics(ctx: &mut LessonCtx, metrics: Metrics) {
+    if !metrics.is_empty() {
+        let metrics = Style::Count.paint(format!("metrics: {metrics}"), ctx.output.color);
+        let _ = writeln!(ctx.output.out, "{metrics}");
+    }
+}
+
+/// `a`, `b` and `c`.
+fn quoted(ids: &[&str]) -> String {
+    let ids: Vec<_> = ids.iter().map(|id| format!("`{id}`")).collect();
+    match ids.split_last() {
+        Some((last, [])) => last.clone(),
+        Some((last, rest)) => format!("{} and {last}", rest.join(", ")),
+        None => String::new(),
+    }
+}
+
+/// `panic!` with a literal message panics with a `&str`, and with a formatted one with a
+/// `String`; anything else was passed to `panic_any`, and there is no telling what it is.
+pub fn panic_message(panic: &(dyn Any + Send)) -> &str {
+    match (panic.downcast_ref::<&str>(), panic.downcast_ref::<String>()) {
+        (Some(message), _) => message,
+        (_, Some(message)) => message,
+        _ => "a non-string payload",
+    }
+}
+
+/// One row per lesson, and a line with the totals.
+pub fn summary(outcomes: &[Outcome]) -> String {
+    let width = outcomes
+        .iter()
+        .map(|outcome| outcome.name.len())
+        .max()
+        .unwrap_or(0)
+        .max("lesson".len());
+    let mut table = format!("{:<width$}  {:<6}  {:>10}\n", "lesson", "result", "time");
+    for outcome in outcomes {
+        let result = match outcome.failure {
+            None => "ok",
+            Some(_) => "FAILED",
+        };
+        let time = format!("{:.1?}", outcome.duration);
+        let _ = write!(table, "{:<width$}  {result:<6}  {time:>10}", outcome.name);
+        if let Some(failure) = &outcome.failure {
+            let _ = write!(table, "  {failure}");
+        }
+        table.push('\n');
+    }
+    let failed = outcomes
+        .iter()
+        .filter(|outcome| outcome.failure.is_some())
+        .count();
+    let total: Duration = outcomes.iter().map(|outcome| outcome.duration).sum();
+    let _ = write!(
+        table,
+        "{} passed, {failed} failed in {total:.1?}",
+        outcomes.len() - failed
+    );
+    table
+}
+
+/// The lessons that passed, slowest first, each with its share of their total time, and what it
+/// allocated if that was tracked.
+pub fn report(outcomes: &[Outcome]) -> String {
+    let mut passed: Vec<_> = outcomes
+        .iter()
+        .filter(|outcome| outcome.failure.is_none())
+        .collect();
+    passed.sort_by_key(|outcome| Reverse(outcome.duration));
+    let total: Duration = passed.iter().map(|outcome| outcome.duration).sum();
+    let width = passed
+        .iter()
+        .map(|outcome| outcome.name.len())
+        .max()
+        .unwrap_or(0)
+        .max("lesson".len());
+    let tracked = passed.iter().any(|outcome| outcome.allocs.is_some());
+    let mut table = format!("{:<width$}  {:>10}  {:>5}", "lesson", "time", "share");
+    if tracked {
+        let _ = write!(table, "  {:>11}  {:>10}", "allocations", "peak");
+    }
+    table.push('\n');
+    for outcome in passed {
+        let share = match total.is_zero() {
+            true => 0.0,
+            false => outcome.duration.as_secs_f64() / total.as_secs_f64() * 100.0,
+        };
+        let time = format!("{:.1?}", outcome.duration);
+        let share = format!("{share:.0}%");
+        let _ = write!(table, "{:<width$}  {time:>10}  {share:>5}", outcome.name);
+        if let Some(Allocs { count, peak, .. }) = outcome.allocs {
+            let _ = write!(table, "  {count:>11}  {:>10}", measure::bytes(peak));
+        }
+        table.push('\n');
+    }
+    table.pop();
+    table
+}
+
+/// One line per tag, with how many of its lessons passed.
+pub fn summary_by_tag(registry: &Registry, outcomes: &[Outcome]) -> String {
+    let tags = registry.by_tag();
+    let width = tags.iter().map(|(tag, _)| tag.len()).max().unwrap_or(0);
+    let mut lines = Vec::new();
+    for (tag, lessons) in tags {
+        let ran: Vec<_> = outcomes
+            .iter()
+            .filter(|outcome| lessons.iter().any(|lesson| lesson.id() == outcome.name))
+            .collect();
+        let passed = ran
+            .iter()
+            .filter(|outcome| outcome.failure.is_none())
+            .count();
+        lines.push(format!("{tag:<width$}  {passed}/{} passed", ran.len()));
+    }
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intermediate_rust::{
+        self,
+        lesson::{Demo, LessonInfo},
+    };
+
+    fn demo(
+        id: &'static str,
+        run: fn() -> Result<()>,
+        prerequisites: &'static [&'static str],
+    ) -> Demo {
+        Demo {
+            id,
+            title: "",
+            run,
+            info: LessonInfo {
+                prerequisites,
+                tags: &["all"],
+                ..LessonInfo::default()
+            },
+        }
+    }
+
+    fn run(lessons: &[Demo], progress: &mut Progress) -> Vec<Outcome> {
+        run_each(
+            lessons.iter().map(|lesson| lesson as &dyn Lesson),
+            &mut LessonCtx::default(),
+            progress,
+        )
+    }
+
+    #[test]
+    fn it_carries_on_past_errors_and_panics() {
+        let mut progress = Progress::default();
+        let outcomes = run(
+            &[
+                demo("errs", || Err(Error::other("no luck")), &[]),
+                demo("panics", || panic!("assertion failed"), &[]),
+                demo("passes", || Ok(()), &[]),
+            ],
+            &mut progress,
+        );
+
+        let names: Vec<_> = outcomes.iter().map(|o| o.name.as_str()).collect();
+        assert_eq!(names, ["errs", "panics", "passes"]);
+        let failures: Vec<_> = outcomes.iter().map(|o| o.failure.as_deref()).collect();
+        assert_eq!(
+            failures,
+            [Some("no luck"), Some("panicked: assertion failed"), None]
+        );
+        assert!(!progress.is_completed("errs"));
+        assert!(!progress.is_completed("panics"));
+        assert!(progress.is_completed("passes"));
+    }
+
+    #[test]
+    fn a_lesson_is_skipped_when_a_prerequisite_fails() {
+        let outcomes = run(
+            &[
+                demo("basics", || Err(Error::other("no luck")), &[]),
+                demo("more", || Ok(()), &[]),
+                demo("advanced", || Ok(()), &["basics", "more"]),
+            ],
+            &mut Progress::default(),
+        );
+
+        assert_eq!(
+            outcomes[2].failure.as_deref(),
+            Some("skipped: `basics` didn't pass")
+        );
+    }
+
+    #[test]
+    fn run_one_refuses_a_lesson_with_missing_prerequisites_unless_forced() {
+        let advanced = demo("advanced", || Ok(()), &["basics", "more", "most"]);
+        let mut ctx = LessonCtx::default();
+        let mut progress = Progress::default();
+
+        let x = run_one(&advanced, &mut ctx, &mut progress, false).unwrap_err();
+        assert_eq!(
+            x.to_string(),
+            "`advanced` builds on `basics`, `more` and `most`, which you haven't completed \
+             yet: run them first, or pass --force"
+        );
+        assert!(!progress.is_completed("advanced"));
+
+        run_one(&advanced, &mut ctx, &mut progress, true).unwrap();
+        assert!(progress.is_completed("advanced"));
+    }
+
+    #[test]
+    fn run_captured_returns_what_the_lesson_printed() {
+        let lesson = demo(
+            "prints",
+            || {
+                println!("to out");
+                eprintln!("to err");
+                Ok(())
+            },
+            &["elsewhere"],
+        );
+        let output = run_captured(&lesson, Verbosity::Normal).unwrap();
+        assert_eq!(output.stdout, "to out\n");
+        assert_eq!(output.stderr, "to err\n");
+
+        #[cfg(feature = "smart-pointers")]
+        {
+            let registry = intermediate_rust::registry();
+            let rc = registry.get("smart-pointers/rc").unwrap();
+            let output = run_captured(rc, Verbosity::Normal).unwrap();
+            assert!(output.stdout.contains("a = 5 -> 10 -> Nil\n"));
+        }
+        assert!(run_captured(
+            &demo("errs", || Err(Error::other("no luck")), &[]),
+            Verbosity::Normal
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn run_finds_the_lesson_by_name_without_checking_prerequisites() {
+        let mut registry = Registry::new();
+        registry.register(demo("basics/errs", || Err(Error::other("no luck")), &[]));
+        registry.register(demo("basics/first", || Ok(()), &["basics/errs"]));
+        let mut ctx = LessonCtx::default();
+
+        super::run(&registry, "basics first", &mut ctx).unwrap();
+        let x = super::run(&registry, "basics/errs", &mut ctx).unwrap_err();
+        assert_eq!(x.to_string(), "no luck");
+        let x = super::run(&registry, "basics/last", &mut ctx).unwrap_err();
+        assert!(x
+            .to_string()
+            .starts_with("there is no lesson `basics/last`"));
+    }
+
+    #[test]
+    fn the_summary_has_a_row_per_lesson_and_the_totals() {
+        let outcome = |name: &str, failure: Option<&str>| Outcome {
+            name: name.to_string(),
+            failure: failure.map(String::from),
+            duration: Duration::from_millis(2),
+            allocs: None,
+        };
+        let summary = summary(&[
+            outcome("lambda", None),
+            outcome("memory/niche", Some("oops")),
+        ]);
+
+        assert_eq!(
+            summary,
+            "lesson        result        time\n\
+             lambda        ok           2.0ms\n\
+             memory/niche  FAILED       2.0ms  oops\n\
+             1 passed, 1 failed in 4.0ms"
+        );
+    }
+
+    #[test]
+    fn the_report_compares_the_lessons_that_passed_slowest_first() {
+        let outcome = |name: &str, millis, failure: Option<&str>, allocs| Outcome {
+            name: name.to_string(),
+            failure: failure.map(String::from),
+            duration: Duration::from_millis(millis),
+            allocs,
+        };
+        let report = report(&[
+            outcome("lambda", 1, None, None),
+            outcome("memory/niche", 3, None, None),
+            outcome("strings", 9, Some("oops"), None),
+        ]);
+        assert_eq!(
+            report,
+            "lesson              time  share\n\
+             memory/niche       3.0ms    75%\n\
+             lambda             1.0ms    25%"
+        );
+
+        let allocs = Some(Allocs {
+            count: 3,
+            bytes: 4096,
+            peak: 2048,
+        });
+        assert_eq!(
+            super::report(&[outcome("lambda", 1, None, allocs)]),
+            "lesson        time  share  allocations        peak\n\
+             lambda       1.0ms   100%            3     2.0 KiB"
+        );
+    }
+
+    #[test]
+    fn the_summary_by_tag_counts_the_lessons_that_passed() {
+        let mut registry = Registry::new();
+        registry.register(demo("basics", || Ok(()), &[]));
+        registry.register(demo("more", || Err(Error::other("no luck")), &[]));
+        let outcomes = run_all(
+            &registry,
+            &mut LessonCtx::default(),
+            &mut Progress::default(),
+        );
+
+        assert_eq!(summary_by_tag(&registry, &outcomes), "all  1/2 passed");
+    }
+}
+
+/// What the deterministic lessons print, compared with the snapshots in `src/snapshots`, so that
+/// a change to it, even only to the formatting or to the order of the lines, is reviewed with
+/// `cargo insta review` rather than slipping through.
+#[cfg(all(test, feature = "snapshots"))]
+mod snapshots {
+    use super::*;
+    use crate::intermediate_rust;
+
+    /// What the lesson printed to stdout, followed by what it printed to stderr, if anything.
+    /// Whatever depends on the other tests running meanwhile, or on the features, is redacted:
+    /// the `Drop` lesson numbers its drops across the whole program, and the bytes allocated
+    /// are only counted with the `track-alloc` feature. It is redacted to `{..}`, which is what
+    /// a transcript leaves out, so that `grade` can take a snapshot for the lesson’s transcript.
+    fn printed(id: &str) -> String {
+        let registry = intermediate_rust::registry();
+        let lesson = registry.get(id).unwrap();
+        let output = run_captured(lesson, Verbosity::Normal).unwrap();
+        match output.stderr.is_empty() {
+            true => output.stdout,
+            false => format!("{}--- stderr ---\n{}", output.stdout, output.stderr),
+        }
+    }
+
+    /// Asserts that `id` prints what its snapshot says it does.
+    fn assert_prints_its_snapshot(id: &str) {
+        insta::with_settings!({
+            filters => vec![
+                (r"\(drop #\d+\)", "(drop #{..})"),
+                (r"drop\(s\)(, [\d.]+ (B|KiB|MiB) allocated)?", "drop(s){..}"),
+            ],
+        }, {
+            insta::assert_snapshot!(id.replace('/', "__"), printed(id));
+        });
+    }
+
+    #[test]
+    fn box_t() {
+        assert_prints_its_snapshot("smart-pointers/box");
+    }
+
+    #[test]
+    fn rc_t() {
+        assert_prints_its_snapshot("smart-pointers/rc");
+    }
+
+    #[test]
+    fn deref() {
+        assert_prints_its_snapshot("smart-pointers/deref");
+    }
+
+    #[test]
+    fn drop() {
+        assert_prints_its_snapshot("smart-pointers/drop");
+    }
+}
@@ -0,0 +1,143 @@
+//! # Searching the Lessons
+//! `intermediate_rust search "interior mutability"` lists the lessons that mention every word of
+//! the query, best match first. A lesson mentions a word if it is in its id, title, description
+//! or tags, or in a comment in its source; a query word matches any word it is the start of, so
+//! `mutab` finds `mutable` and `mutability` alike.
+//!
+//! The index weighs where a word is found: a word in the title or the tags says more about what a
+//! lesson is about than a word somewhere in its comments.
+
+use crate::intermediate_rust::lesson::{Lesson, Registry};
+use std::collections::BTreeMap;
+
+/// How much a word found in each part of a lesson counts.
+const TITLE: u32 = 10;
+const TAGS: u32 = 10;
+const DESCRIPTION: u32 = 5;
+const COMMENTS: u32 = 1;
+
+/// The words of `text`, in lowercase, split at anything that isn’t a letter or a digit.
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+}
+
+/// The text of the comments in `source`: doc comments and plain ones alike.
+fn comments(source: &str) -> impl Iterator<Item = &str> {
+    source
+        .lines()
+        .filter_map(|line| line.trim_start().strip_prefix("//"))
+        .map(|comment| comment.trim_start_matches(['/', '!']))
+}
+
+/// For each lesson, each word it mentions, and how much it counts.
+pub struct Index<'r> {
+    lessons: Vec<(&'r dyn Lesson, BTreeMap<String, u32>)>,
+}
+impl<'r> Index<'r> {
+    pub fn new(registry: &'r Registry) -> Index<'r> {
+        let lessons = registry
+            .iter()
+            .map(|lesson| {
+                let info = lesson.info();
+                let mut weights = BTreeMap::new();
+                let mut add = |text: &str, weight: u32| {
+                    for word in words(text) {
+                        *weights.entry(word).or_insert(0) += weight;
+                    }
+                };
+                add(lesson.id(), TITLE);
+                add(lesson.title(), TITLE);
+                add(&info.tags.join(" "), TAGS);
+                add(info.description, DESCRIPTION);
+                for comment in comments(info.source) {
+                    add(comment, COMMENTS);
+                }
+                (lesson, weights)
+            })
+            .collect();
+        Index { lessons }
+    }
+
+    /// The lessons that mention every word of `query`, and how well they match, best first.
+    pub fn search(&self, query: &str) -> Vec<(&'r dyn Lesson, u32)> {
+        let query: Vec<_> = words(query).collect();
+        if query.is_empty() {
+            return Vec::new();
+        }
+        let mut found: Vec<_> = self
+            .lessons
+            .iter()
+            .filter_map(|(lesson, weights)| {
+                let mut score = 0;
+                for word in &query {
+                    let weight: u32 = weights
+                        .range(word.clone()..)
+                        .take_while(|(candidate, _)| candidate.starts_with(word.as_str()))
+                        .map(|(_, weight)| weight)
+                        .sum();
+                    match weight {
+                        0 => return None,
+                        weight => score += weight,
+                    }
+                }
+                Some((*lesson, score))
+            })
+            .collect();
+        // a stable sort, so that lessons that match equally well stay in the registry’s order
+        found.sort_by(|(_, a), (_, b)| b.cmp(a));
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intermediate_rust;
+
+    fn ids(query: &str) -> Vec<String> {
+        let registry = intermediate_rust::registry();
+        let index = Index::new(&registry);
+        index
+            .search(query)
+            .into_iter()
+            .map(|(lesson, _)| lesson.id().to_string())
+            .collect()
+    }
+
+    #[cfg(feature = "collections")]
+    #[test]
+    fn the_best_match_comes_first() {
+        assert_eq!(
+            ids("interior mutability").first().map(String::as_str),
+            Some("smart-pointers/ref-cell")
+        );
+        assert_eq!(
+            ids("ring buffer").first().map(String::as_str),
+            Some("collections/vec-deque")
+        );
+    }
+
+    #[cfg(feature = "smart-pointers")]
+    #[test]
+    fn every_word_has_to_match_the_start_of_a_word() {
+        assert!(ids("interior mutab").contains(&String::from("smart-pointers/ref-cell")));
+        assert!(ids("nior").is_empty());
+        assert!(ids("interior xylophone").is_empty());
+        assert!(ids("  ").is_empty());
+    }
+
+    #[test]
+    fn comments_are_searched_too() {
+        assert_eq!(
+            words("Hello, `Rc<T>`!").collect::<Vec<_>>(),
+            ["hello", "rc", "t"]
+        );
+        let source = "//! # Title\nfn main() {\n    // a comment\n    let x = 1; // code\n}\n";
+        assert_eq!(
+            comments(source).collect::<Vec<_>>(),
+            [" # Title", " a comment"]
+        );
+    }
+}
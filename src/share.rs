@@ -0,0 +1,649 @@
+//! # Sharing a Lesson
+//! `intermediate_rust share memory/slices` prints a link to the Rust Playground with the lesson
+//! in it, as a program of its own that runs in the browser, so that a lesson can be handed to
+//! someone who hasn’t cloned the repository. `--main PATH` writes the program to a `main.rs`
+//! instead, for `rustc main.rs`, or a `cargo new` project.
+//!
+//! The program is the lesson’s code, without its tests and its docs, which `show-source` has,
+//! with a `main` that runs it. The crate’s macros it uses, `header!`, `span!` and `defer!`, are
+//! written out plainly in front, and what it uses of the crate’s helpers, `metrics` and the
+//! `utils`, is stood in for behind, by a `mod intermediate_rust` of the same names with only the
+//! standard library; `ir_core`’s paths become that module’s. A lesson that builds on anything else
+//! of the crate, on a lesson of another chapter, or on a crate other than the standard library,
+//! is refused, saying what it uses, as is one that needs what a build script makes, or the
+//! package’s files, which the Playground doesn’t have. The lessons declared in a lesson’s file,
+//! with `mod name;`, are left out of it, unless it uses them.
+//! ```text
+//! intermediate_rust share collections maps
+//! intermediate_rust share memory niche --main niche.rs
+//! ```
+
+use crate::intermediate_rust::lesson::Lesson;
+
+const PLAYGROUND: &str = "https://play.rust-lang.org/?version=stable&mode=debug&edition=2021";
+
+/// What a program of its own doesn’t have: the crate, and `ir-core` under it, and the crates
+/// they depend on. The paths of `intermediate_rust` are checked apart, against `STAND_INS`.
+const FROM_THE_CRATE: &[&str] = &["super::", "ir_core::", "rand::", "serde", "log::"];
+
+/// What only a build of the package has: the directory a build script writes to, the variables
+/// it sets for the crate, the files it makes there, included into the code, the package’s own
+/// files, and the C it compiles, whose functions are named `ir_c_`.
+const FROM_THE_BUILD: &[&str] = &[
+    "OUT_DIR",
+    "env!(\"IR_",
+    "include!(",
+    "CARGO_MANIFEST_DIR",
+    "ir_c_",
+];
+
+/// `header!` prints the header as the lessons do without color, and `span!` does nothing, as it
+/// does without the `tracing` feature.
+const PRELUDE: &str = "\
+// the macros of `intermediate_rust` the lesson uses, without color or tracing
+#[allow(unused_macros)]
+macro_rules! header {
+    ($($arg:tt)*) => {
+        println!($($arg)*)
+    };
+}
+#[allow(unused_macros)]
+macro_rules! span {
+    ($name:literal) => {};
+}
+";
+
+/// `defer!`, for a lesson that uses it, which needs the `scope_guard` of `STAND_INS`.
+const DEFER: &str = "\
+macro_rules! defer {
+    ($($body:tt)*) => {
+        let _guard = $crate::intermediate_rust::utils::scope_guard::ScopeGuard::new(|| {
+            $($body)*
+        });
+    };
+}
+";
+
+/// The helpers of `intermediate_rust` a lesson may use, by their path in the crate, and what
+/// stands in for them in a program of its own: the same names, doing the same, but counting
+/// nothing, as there is no runner to show the counts.
+const STAND_INS: &[(&str, &str)] = &[
+    (
+        "metrics",
+        "\
+use std::rc::Rc;
+
+pub enum Counter {
+    RcClone,
+    DeepClone,
+    Drop,
+}
+
+pub fn count(_counter: Counter) {}
+
+pub fn rc_clone<T: ?Sized>(rc: &Rc<T>) -> Rc<T> {
+    Rc::clone(rc)
+}
+
+pub fn deep_clone<T: Clone>(value: &T) -> T {
+    value.clone()
+}
+",
+    ),
+    (
+        "utils::miri",
+        "\
+pub fn skip_under_miri(what: &str) -> bool {
+    if cfg!(miri) {
+        println!(\"skipped under Miri, which runs in isolation from the host: {what}\");
+    }
+    cfg!(miri)
+}
+",
+    ),
+    (
+        "utils::scope_guard",
+        "\
+use std::ops::{Deref, DerefMut};
+
+pub struct ScopeGuard<F: FnOnce()>(Option<F>);
+impl<F: FnOnce()> ScopeGuard<F> {
+    pub fn new(cleanup: F) -> ScopeGuard<F> {
+        ScopeGuard(Some(cleanup))
+    }
+}
+impl<F: FnOnce()> Drop for ScopeGuard<F> {
+    fn drop(&mut self) {
+        if let Some(cleanup) = self.0.take() {
+            cleanup();
+        }
+    }
+}
+
+pub struct Guard<T, F: FnOnce(T)>(Option<(T, F)>);
+pub fn guard<T, F: FnOnce(T)>(value: T, cleanup: F) -> Guard<T, F> {
+    Guard(Some((value, cleanup)))
+}
+impl<T, F: FnOnce(T)> Guard<T, F> {
+    pub fn into_inner(mut guard: Guard<T, F>) -> T {
+        guard.0.take().unwrap().0
+    }
+}
+impl<T, F: FnOnce(T)> Deref for Guard<T, F> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0.as_ref().unwrap().0
+    }
+}
+impl<T, F: FnOnce(T)> DerefMut for Guard<T, F> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0.as_mut().unwrap().0
+    }
+}
+impl<T, F: FnOnce(T)> Drop for Guard<T, F> {
+    fn drop(&mut self) {
+        if let Some((value, cleanup)) = self.0.take() {
+            cleanup(value);
+        }
+    }
+}
+",
+    ),
+    (
+        "utils::temp_dir",
+        "\
+use std::{
+    env, fs,
+    io::Result,
+    path::{Path, PathBuf},
+    process,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+pub struct TempDir {
+    path: PathBuf,
+}
+impl TempDir {
+    pub fn new(name: &str) -> Result<TempDir> {
+        static CREATED: AtomicUsize = AtomicUsize::new(0);
+        let n = CREATED.fetch_add(1, Ordering::Relaxed);
+        let name = format!(\"intermediate_rust-{}-{n}-{name}\", process::id());
+        let path = env::temp_dir().join(name);
+        fs::create_dir_all(&path)?;
+        Ok(TempDir { path })
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+",
+    ),
+];
+
+/// The lesson as a program of its own, or why it can’t be one.
+pub fn program(lesson: &dyn Lesson) -> Result<String, String> {
+    let source = lesson.info().source;
+    // the tests are the last thing in a lesson’s source, and need the crate; a `#[cfg(test)]`
+    // that starts a line, as a doc comment’s doesn’t
+    let code = match source.find("\n#[cfg(test)]") {
+        Some(tests) => &source[..tests + 1],
+        None => source,
+    };
+    let code = without_docs(code);
+    let built: Vec<_> = FROM_THE_BUILD
+        .iter()
+        .filter(|name| code.contains(*name))
+        .map(|name| format!("`{name}`"))
+        .collect();
+    if !built.is_empty() {
+        return Err(format!(
+            "`{}` can't be shared: it needs the package's build, with {}, and the Playground \
+             only builds a `main.rs`",
+            lesson.id(),
+            built.join(", ")
+        ));
+    }
+    let (code, modules) = without_mod_declarations(&code);
+    let code = code.replace("ir_core::", "crate::intermediate_rust::");
+    let code = own_module_dropped(&code);
+    let mut uses: Vec<_> = FROM_THE_CRATE
+        .iter()
+        .filter(|name| code.contains(*name))
+        .map(|name| format!("`{name}`"))
+        .collect();
+    let (stood_in, not_stood_in) = crate_paths(&code);
+    uses.extend(not_stood_in.iter().map(|path| format!("`{path}`")));
+    let next_to_it = modules
+        .iter()
+        .any(|name| code.contains(&format!("{name}::")));
+    if !uses.is_empty() || next_to_it {
+        return Err(format!(
+            "`{}` can't be shared: it uses {}",
+            lesson.id(),
+            match uses.is_empty() {
+                true => String::from("the modules next to it"),
+                false => uses.join(", "),
+            }
+        ));
+    }
+    let run = code
+        .lines()
+        .find_map(|line| {
+            line.strip_prefix("pub fn ")?
+                .strip_suffix("() -> Result<()> {")
+        })
+        .ok_or_else(|| format!("`{}` has no `pub fn` that runs it", lesson.id()))?;
+
+    let defer = match uses_defer(&code) {
+        true => DEFER,
+        false => "",
+    };
+    Ok(format!(
+        "// {}: the `{}` lesson of intermediate_rust, without its docs\n\n\
+         {PRELUDE}{defer}\n{}\n\nfn main() -> Result<()> {{\n    {run}()\n}}\n{}",
+        lesson.title(),
+        lesson.id(),
+        code.trim(),
+        stand_ins(&stood_in, defer.is_empty())
+    ))
+}
+
+/// `code` without its doc comments, `///` and `//!`, which are most of a lesson’s source.
+fn without_docs(code: &str) -> String {
+    let lines = code.lines().filter(|line| {
+        let line = line.trim_start();
+        !line.starts_with("///") && !line.starts_with("//!")
+    });
+    lines.map(|line| format!("{line}\n")).collect()
+}
+
+/// `code` without its `mod name;` declarations, and the comments and attributes on them, and
+/// the names of the modules they declared: the lessons in files of their own, next to it.
+fn without_mod_declarations(code: &str) -> (String, Vec<String>) {
+    let mut kept: Vec<&str> = Vec::new();
+    let mut modules = Vec::new();
+    for line in code.lines() {
+        if !is_mod_declaration(line) {
+            kept.push(line);
+            continue;
+        }
+        let name = line
+            .trim_end_matches(';')
+            .rsplit(' ')
+            .next()
+            .unwrap_or(line);
+        modules.push(name.to_string());
+        while kept.last().is_some_and(|last| {
+            let last = last.trim_start();
+            last.starts_with("//") || last.starts_with("#[")
+        }) {
+            kept.pop();
+        }
+    }
+    let code = kept.iter().map(|line| format!("{line}\n")).collect();
+    (code, modules)
+}
+
+/// `code` with the `$crate::module::` of its macros `$crate::`: a macro of a lesson names the
+/// lesson’s items by the lesson’s module in the crate, and they are at the top of the program.
+fn own_module_dropped(code: &str) -> String {
+    let mut dropped = String::with_capacity(code.len());
+    let mut rest = code;
+    while let Some(at) = rest.find("$crate::") {
+        let (before, path) = rest.split_at(at + "$crate::".len());
+        dropped.push_str(before);
+        rest = path;
+        let module = path.split("::").next().unwrap_or(path);
+        let is_module = module.starts_with(|c: char| c.is_lowercase())
+            && module.chars().all(|c| c.is_alphanumeric() || c == '_')
+            && path.len() > module.len();
+        if is_module && module != "intermediate_rust" {
+            rest = &path[module.len() + "::".len()..];
+        }
+    }
+    dropped.push_str(rest);
+    dropped
+}
+
+/// Whether `line` declares a module in a file of its own, `mod name;`, with any visibility:
+/// `pub`, `pub(crate)`, `pub(super)` or `pub(in path)`.
+fn is_mod_declaration(line: &str) -> bool {
+    let line = line.trim_start();
+    let line = match line.strip_prefix("pub") {
+        Some(restricted) if restricted.starts_with('(') => match restricted.find(')') {
+            Some(end) => &restricted[end + 1..],
+            None => line,
+        },
+        Some(public) => public,
+        None => line,
+    };
+    let line = line.trim_start();
+    line.starts_with("mod ") && line.ends_with(';')
+}
+
+/// Whether `code` uses the crate’s `defer!`, and not only one of its own, such as `my_defer!`.
+fn uses_defer(code: &str) -> bool {
+    code.match_indices("defer!").any(|(at, _)| {
+        let before = code[..at].chars().next_back();
+        !before.is_some_and(|c| c.is_alphanumeric() || c == '_')
+    })
+}
+
+/// The paths `code` uses under `crate::intermediate_rust`, `use` trees spelled out, split into
+/// those under one of `STAND_INS`, by which, and those that aren’t. A `crate::` path to a module
+/// other than `intermediate_rust` isn’t either, as the program has no modules but that one.
+fn crate_paths(code: &str) -> (Vec<&'static str>, Vec<String>) {
+    let mut stood_in = Vec::new();
+    let mut not_stood_in = Vec::new();
+    for (at, _) in code.match_indices("crate::") {
+        let rest = &code[at + "crate::".len()..];
+        let Some(rest) = rest.strip_prefix("intermediate_rust::") else {
+            // an item at the top of the program, as a macro names it, is there
+            if rest.starts_with(|c: char| c.is_lowercase()) {
+                not_stood_in.push(format!("crate::{}", path_tree(rest)));
+            }
+            continue;
+        };
+        for path in spelled_out(path_tree(rest)) {
+            let stand_in = STAND_INS
+                .iter()
+                .find(|(module, _)| path.starts_with(&format!("{module}::")));
+            match stand_in {
+                Some((module, _)) if !stood_in.contains(module) => stood_in.push(*module),
+                Some(_) => {}
+                None => not_stood_in.push(format!("crate::intermediate_rust::{path}")),
+            }
+        }
+    }
+    (stood_in, not_stood_in)
+}
+
+/// The path at the start of `code`, with the `{ ... }` of a `use` tree, up to where it ends.
+fn path_tree(code: &str) -> &str {
+    let mut depth = 0;
+    for (at, c) in code.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' if depth > 0 => depth -= 1,
+            '_' | ':' => {}
+            c if c.is_alphanumeric() => {}
+            c if depth > 0 && (c.is_whitespace() || c == ',') => {}
+            _ => return &code[..at],
+        }
+    }
+    code
+}
+
+/// The paths of a `use` tree: `a::{b, c::{d, e}}` is `a::b`, `a::c::d` and `a::c::e`.
+fn spelled_out(tree: &str) -> Vec<String> {
+    let tree = tree.trim();
+    let Some(open) = tree.find('{') else {
+        return vec![tree.to_string()];
+    };
+    let (prefix, inner) = (&tree[..open], &tree[open + 1..tree.len() - 1]);
+    let mut entries = Vec::new();
+    let (mut depth, mut start) = (0, 0);
+    for (at, c) in inner.char_indices() {
+        match c {
+            '{' => depth += 1,
+            '}' => depth -= 1,
+            ',' if depth == 0 => {
+                entries.push(&inner[start..at]);
+                start = at + 1;
+            }
+            _ => {}
+        }
+    }
+    entries.push(&inner[start..]);
+    let entries = entries.into_iter().filter(|entry| !entry.trim().is_empty());
+    entries
+        .flat_map(|entry| spelled_out(entry).into_iter())
+        .map(|path| format!("{prefix}{path}"))
+        .collect()
+}
+
+/// The `mod intermediate_rust` with the `stood_in` modules, and `scope_guard` for `defer!`
+/// unless `without_defer`, or nothing, if it would be empty.
+fn stand_ins(stood_in: &[&str], without_defer: bool) -> String {
+    let needed: Vec<_> = STAND_INS
+        .iter()
+        .filter(|(module, _)| {
+            stood_in.contains(module) || (*module == "utils::scope_guard" && !without_defer)
+        })
+        .collect();
+    if needed.is_empty() {
+        return String::new();
+    }
+    let mut stand_ins = String::from(
+        "\n// what the lesson uses of `intermediate_rust`, with only the standard library\n\
+         #[allow(dead_code)]\nmod intermediate_rust {\n",
+    );
+    let mut open: Vec<&str> = Vec::new();
+    for (module, code) in needed {
+        let mut path: Vec<_> = module.split("::").collect();
+        let name = path.pop().unwrap_or(module);
+        // close the modules this one isn’t in, and open those it is in that aren’t open yet
+        while !path.starts_with(&open) {
+            open.pop();
+            stand_ins.push_str(&format!("{}}}\n", "    ".repeat(open.len() + 1)));
+        }
+        for parent in &path[open.len()..] {
+            stand_ins.push_str(&format!(
+                "{}pub mod {parent} {{\n",
+                "    ".repeat(open.len() + 1)
+            ));
+            open.push(parent);
+        }
+        let indent = "    ".repeat(open.len() + 1);
+        stand_ins.push_str(&format!("{indent}pub mod {name} {{\n"));
+        for line in code.lines() {
+            match line.is_empty() {
+                true => stand_ins.push('\n'),
+                false => stand_ins.push_str(&format!("{indent}    {line}\n")),
+            }
+        }
+        stand_ins.push_str(&format!("{indent}}}\n"));
+    }
+    while !open.is_empty() {
+        open.pop();
+        stand_ins.push_str(&format!("{}}}\n", "    ".repeat(open.len() + 1)));
+    }
+    stand_ins.push_str("}\n");
+    stand_ins
+}
+
+/// A link to the Rust Playground with `program` in it.
+pub fn playground_url(program: &str) -> String {
+    format!("{PLAYGROUND}&code={}", url_encode(program))
+}
+
+/// `text` percent-encoded as the value of a query, every byte but those RFC 3986 lets a query
+/// have as they are, its unreserved characters and sub-delimiters, `:`, `@`, `/` and `?`; but
+/// `&`, `=` and `+` are encoded too, which the Playground would take for the end of the value, and
+/// for a space.
+fn url_encode(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len() * 3);
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(char::from(byte))
+            }
+            b'!' | b'$' | b'\'' | b'(' | b')' | b'*' | b',' | b';' | b':' | b'@' | b'/' | b'?' => {
+                encoded.push(char::from(byte))
+            }
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intermediate_rust;
+
+    /// What a program of its own can’t have, found another way than `program` finds it: a
+    /// `super::` or `ir_core` path, a path into `intermediate_rust` that the program’s stand-ins
+    /// don’t have, what the package’s build has, and a `mod name;`, with whatever visibility,
+    /// whose file isn’t in the program.
+    fn crate_private(program: &str) -> Vec<&str> {
+        let code = program
+            .lines()
+            .filter(|line| !line.trim_start().starts_with("//"));
+        code.filter(|line| {
+            let words: Vec<_> = line.split_whitespace().collect();
+            let declares_a_mod = match words.iter().position(|word| *word == "mod") {
+                Some(at) => line.ends_with(';') && words[..at].iter().all(|w| w.starts_with("pub")),
+                None => false,
+            };
+            // each module named after it, `a` and `b` of `a::{b::C, D}`, is one the program has
+            let stand_ins = line
+                .match_indices("intermediate_rust::")
+                .all(|(at, found)| {
+                    let path = &line[at + found.len()..];
+                    let modules = path
+                        .split("::")
+                        .map(|segment| segment.rsplit(['{', ' ']).next().unwrap_or(segment))
+                        .filter(|segment| segment.starts_with(|c: char| c.is_lowercase()));
+                    let mut modules = modules.take(path.matches("::").count());
+                    modules.all(|module| {
+                        module == "utils" || program.contains(&format!("pub mod {module} {{"))
+                    })
+                });
+            let paths = [
+                "super::",
+                "ir_core",
+                "OUT_DIR",
+                "env!(\"IR_",
+                "include!(",
+                "CARGO_MANIFEST_DIR",
+                "ir_c_",
+            ];
+            declares_a_mod || !stand_ins || paths.iter().any(|path| line.contains(path))
+        })
+        .collect()
+    }
+
+    #[test]
+    fn every_lesson_is_refused_or_a_program_of_its_own() {
+        let registry = intermediate_rust::registry();
+        for lesson in registry.iter() {
+            let Ok(program) = program(lesson) else {
+                continue;
+            };
+            let id = lesson.id();
+            assert_eq!(crate_private(&program), Vec::<&str>::new(), "{id}");
+            assert!(program.contains("macro_rules! header"), "{id}");
+            assert!(!program.contains("#[cfg(test)]\nmod tests"), "{id}");
+            assert!(!program.contains("///") && !program.contains("//!"), "{id}");
+            assert!(program.contains("\nfn main() -> Result<()> {\n"), "{id}");
+        }
+    }
+
+    #[cfg(all(feature = "collections", feature = "memory"))]
+    #[test]
+    fn a_link_is_to_the_code_without_the_docs() {
+        let registry = intermediate_rust::registry();
+        for id in ["collections/maps", "collections/persistent", "memory/niche"] {
+            let lesson = registry.get(id).unwrap();
+            let program = program(lesson).unwrap();
+            let docs = lesson.info().source.lines().next().unwrap();
+            assert!(
+                docs.starts_with("//! # ") && !program.contains(docs),
+                "{id}"
+            );
+            assert!(playground_url(&program).len() < 16 * 1024, "{id}");
+        }
+    }
+
+    #[cfg(feature = "concurrency")]
+    #[test]
+    fn the_helpers_a_lesson_uses_are_stood_in_for() {
+        let registry = intermediate_rust::registry();
+        let program = program(registry.get("thread-and-move").unwrap()).unwrap();
+        assert!(
+            program.contains("use crate::intermediate_rust::utils::scope_guard::{guard, Guard};")
+        );
+        assert!(program.contains("macro_rules! defer"));
+        assert!(program.contains("    pub mod utils {\n        pub mod scope_guard {\n"));
+        assert!(!program.contains("pub mod metrics"));
+        assert!(!program.contains("pub mod temp_dir"));
+
+        let lambda = super::program(registry.get("lambda").unwrap()).unwrap();
+        assert!(!lambda.contains("mod intermediate_rust"));
+        assert!(!lambda.contains("macro_rules! defer"));
+    }
+
+    #[cfg(feature = "unsafe-rust")]
+    #[test]
+    fn the_lessons_next_to_a_lesson_are_left_out() {
+        let registry = intermediate_rust::registry();
+        let program = program(registry.get("unsafe-rust/superpowers").unwrap()).unwrap();
+        assert!(!program.contains("mod casts;"));
+        assert!(!program.contains("mod fuzzing;"));
+        // nor are the comment and attributes on them left behind
+        assert!(!program.contains("fuzz targets"));
+        assert!(is_mod_declaration("pub(in crate::a) mod b;"));
+        assert!(!is_mod_declaration("mod tests {"));
+    }
+
+    #[cfg(feature = "build-scripts")]
+    #[test]
+    fn a_lesson_that_needs_its_build_script_is_refused() {
+        let registry = intermediate_rust::registry();
+        let refused = program(registry.get("build-scripts").unwrap()).unwrap_err();
+        assert!(refused.contains("`OUT_DIR`, `env!(\"IR_`"), "{refused}");
+        assert!(refused.contains("only builds a `main.rs`"), "{refused}");
+    }
+
+    #[cfg(feature = "patterns")]
+    #[test]
+    fn a_lesson_that_builds_on_another_chapter_is_refused() {
+        let registry = intermediate_rust::registry();
+        let refused = program(registry.get("patterns").unwrap()).unwrap_err();
+        assert_eq!(
+            refused,
+            "`patterns` can't be shared: it uses \
+             `crate::intermediate_rust::smart_pointers::List`, \
+             `crate::intermediate_rust::smart_pointers::TreeNode`"
+        );
+    }
+
+    #[test]
+    fn the_paths_of_a_use_tree_are_spelled_out() {
+        let tree = path_tree("utils::{miri::skip, temp_dir::{self, TempDir}};\nfn f() {}");
+        assert_eq!(tree, "utils::{miri::skip, temp_dir::{self, TempDir}}");
+        assert_eq!(
+            spelled_out(tree),
+            [
+                "utils::miri::skip",
+                "utils::temp_dir::self",
+                "utils::temp_dir::TempDir"
+            ]
+        );
+        assert_eq!(path_tree("metrics::count(Counter::Drop)"), "metrics::count");
+        assert_eq!(
+            own_module_dropped("$crate::box_t::List::Nil"),
+            "$crate::List::Nil"
+        );
+        assert!(uses_defer("defer! { x }") && !uses_defer("my_defer! { x }"));
+    }
+
+    #[test]
+    fn the_code_is_percent_encoded() {
+        assert_eq!(url_encode("fn main() {}"), "fn%20main()%20%7B%7D");
+        assert_eq!(url_encode("a-b_c.d~e\n’"), "a-b_c.d~e%0A%E2%80%99");
+        assert_eq!(
+            url_encode("a & b = a + b;"),
+            "a%20%26%20b%20%3D%20a%20%2B%20b;"
+        );
+        assert!(playground_url("x").ends_with("&edition=2021&code=x"));
+    }
+}
@@ -0,0 +1,81 @@
+//! # Showing a Lesson’s Source
+//! `intermediate_rust show-source smart-pointers rc` prints the code of a lesson, so that it can
+//! be read right after it ran, without opening the repository. The code is the source file the
+//! lesson embeds with `include_str!`, so it is always the code of the binary that shows it.
+//!
+//! With the `highlight` feature, the code is highlighted by `syntect` when the output may be
+//! colored; without it, or when it may not, it is printed as it is, with line numbers.
+
+/// `source`, one numbered line after another.
+pub fn show(source: &str, color: bool) -> String {
+    let lines = highlighted(source, color);
+    let width = lines.len().to_string().len();
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| format!("{:>width$}  {line}\n", i + 1))
+        .collect()
+}
+
+/// The lines of `source`, highlighted as Rust if `color`.
+#[cfg(feature = "highlight")]
+fn highlighted(source: &str, color: bool) -> Vec<String> {
+    use syntect::{
+        easy::HighlightLines,
+        highlighting::ThemeSet,
+        parsing::SyntaxSet,
+        util::{as_24_bit_terminal_escaped, LinesWithEndings},
+    };
+
+    if !color {
+        return source.lines().map(String::from).collect();
+    }
+    let syntaxes = SyntaxSet::load_defaults_newlines();
+    let themes = ThemeSet::load_defaults();
+    let syntax = syntaxes
+        .find_syntax_by_extension("rs")
+        .unwrap_or_else(|| syntaxes.find_syntax_plain_text());
+    let mut highlighter = HighlightLines::new(syntax, &themes.themes["base16-ocean.dark"]);
+    LinesWithEndings::from(source)
+        .map(|line| match highlighter.highlight_line(line, &syntaxes) {
+            Ok(ranges) => {
+                let escaped = as_24_bit_terminal_escaped(&ranges, false);
+                format!("{}\x1b[0m", escaped.trim_end_matches(['\r', '\n']))
+            }
+            // a line that can't be highlighted is still worth showing
+            Err(_) => line.trim_end_matches(['\r', '\n']).to_string(),
+        })
+        .collect()
+}
+
+#[cfg(not(feature = "highlight"))]
+fn highlighted(source: &str, _color: bool) -> Vec<String> {
+    source.lines().map(String::from).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lines_are_numbered_and_aligned() {
+        let source = (1..=10)
+            .map(|i| format!("let x{i} = {i};\n"))
+            .collect::<String>();
+        let shown = show(&source, false);
+
+        assert!(
+            shown.starts_with(" 1  let x1 = 1;\n 2  let x2 = 2;\n"),
+            "{shown}"
+        );
+        assert!(shown.ends_with("10  let x10 = 10;\n"), "{shown}");
+    }
+
+    #[cfg(feature = "highlight")]
+    #[test]
+    fn with_color_the_code_is_highlighted() {
+        let shown = show("fn main() {}\n", true);
+        assert!(shown.starts_with("1  \x1b[38;2;"), "{shown:?}");
+        assert!(shown.ends_with("\x1b[0m\n"), "{shown:?}");
+    }
+}
@@ -0,0 +1,412 @@
+//! # The Guided Tour
+//! The lessons can be taken in any order that respects their prerequisites; `intermediate_rust
+//! tour` takes them in the one they were written for, from ownership and borrowing, through
+//! `Box<T>`, `Rc<T>`, `RefCell<T>` and `Weak<T>`, to closures and threads. At each stop, it
+//! explains what the lesson teaches, runs it, with `--quiz` asks the quiz of a chapter once its
+//! last stop is reached, and checks the exercises that go with the lesson:
+//! ```text
+//! intermediate_rust tour
+//! intermediate_rust tour --quiz
+//! ```
+//! The tour only moves on once the lesson has passed and its exercises have too; it picks up
+//! where it was left, at the first stop that hasn’t.
+
+use crate::{
+    exercises::{self, Exercise},
+    explain,
+    intermediate_rust::lesson::{LessonCtx, Registry},
+    progress::Progress,
+    quiz, runner,
+};
+use std::{
+    io::{self, BufRead, Result, Write},
+    path::PathBuf,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stop {
+    pub lesson: &'static str,
+    /// Why the lesson comes here.
+    pub note: &'static str,
+    /// The chapter whose quiz is asked after this stop, the last of it.
+    pub quiz: Option<&'static str>,
+}
+
+/// The stops, in order; every lesson’s prerequisites come before it. A stop is only there if
+/// its chapter is in the build.
+pub const CURRICULUM: &[Stop] = &[
+    #[cfg(feature = "memory")]
+    Stop {
+        lesson: "memory/slices",
+        note: "Ownership and borrowing: what a reference to part of a value is",
+        quiz: None,
+    },
+    #[cfg(feature = "memory")]
+    Stop {
+        lesson: "memory/replace",
+        note: "Ownership through a `&mut`: moving a value out by putting another in",
+        quiz: None,
+    },
+    #[cfg(feature = "smart-pointers")]
+    Stop {
+        lesson: "smart-pointers/drop",
+        note: "What happens when an owner goes out of scope",
+        quiz: None,
+    },
+    #[cfg(feature = "smart-pointers")]
+    Stop {
+        lesson: "smart-pointers/deref",
+        note: "What makes a smart pointer behave like a reference",
+        quiz: None,
+    },
+    #[cfg(feature = "smart-pointers")]
+    Stop {
+        lesson: "smart-pointers/box",
+        note: "The simplest smart pointer: one owner, on the heap",
+        quiz: None,
+    },
+    #[cfg(feature = "smart-pointers")]
+    Stop {
+        lesson: "smart-pointers/rc",
+        note: "More than one owner, counted",
+        quiz: None,
+    },
+    #[cfg(feature = "smart-pointers")]
+    Stop {
+        lesson: "smart-pointers/ref-cell",
+        note: "Mutating what is shared, and breaking cycles with `Weak<T>`",
+        quiz: Some("smart-pointers"),
+    },
+    #[cfg(feature = "collections")]
+    Stop {
+        lesson: "collections/copy-on-write",
+        note: "`Rc<T>` at work: sharing until the first write",
+        quiz: None,
+    },
+    #[cfg(feature = "closures")]
+    Stop {
+        lesson: "lambda",
+        note: "Closures, and what they capture",
+        quiz: Some("lambda"),
+    },
+    #[cfg(feature = "concurrency")]
+    Stop {
+        lesson: "thread-and-move",
+        note: "Handing what a closure captures to another thread",
+        quiz: Some("thread-and-move"),
+    },
+    #[cfg(feature = "unsafe-rust")]
+    Stop {
+        lesson: "unsafe-rust/superpowers",
+        note: "What `unsafe` allows, and how safe code keeps it sound",
+        quiz: None,
+    },
+    #[cfg(feature = "unsafe-rust")]
+    Stop {
+        lesson: "unsafe-rust/non-null",
+        note: "Under the hood of `Box<T>` and `Rc<T>`",
+        quiz: None,
+    },
+];
+
+pub struct Tour<'r> {
+    registry: &'r Registry,
+    curriculum: &'r [Stop],
+    exercises: &'r [Exercise],
+    ctx: LessonCtx,
+    progress: Progress,
+    /// Where the progress is saved, if anywhere.
+    path: Option<PathBuf>,
+}
+impl<'r> Tour<'r> {
+    pub fn new(
+        registry: &'r Registry,
+        curriculum: &'r [Stop],
+        exercises: &'r [Exercise],
+        ctx: LessonCtx,
+        progress: Progress,
+        path: Option<PathBuf>,
+    ) -> Tour<'r> {
+        Tour {
+            registry,
+            curriculum,
+            exercises,
+            ctx,
+            progress,
+            path,
+        }
+    }
+
+    /// The exercises that go with `stop`.
+    fn exercises(&self, stop: &Stop) -> impl Iterator<Item = &'r Exercise> {
+        let lesson = stop.lesson;
+        self.exercises
+            .iter()
+            .filter(move |exercise| exercise.lesson == lesson)
+    }
+
+    /// Where the tour is: the first stop whose lesson hasn’t passed, or whose exercises haven’t.
+    fn position(&self) -> Result<Option<usize>> {
+        for (at, stop) in self.curriculum.iter().enumerate() {
+            if !self.progress.is_completed(stop.lesson) {
+                return Ok(Some(at));
+            }
+            for exercise in self.exercises(stop) {
+                if !exercises::verify(exercise, &mut io::sink())? {
+                    return Ok(Some(at));
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Takes the tour from where it is, one stop after another, for as long as each passes and
+    /// the answer to going on, read from `input`, isn’t no. What the tour has to say goes to
+    /// `out`, and the lessons print as they always do.
+    pub fn run(&mut self, input: impl BufRead, out: &mut impl Write, quiz: bool) -> Result<()> {
+        let mut lines = input.lines();
+        loop {
+            let Some(at) = self.position()? else {
+                return writeln!(out, "the tour is over: every stop has passed");
+            };
+            if !self.visit(at, &mut lines, out, quiz)? {
+                return Ok(());
+            }
+            let Some(next) = self.curriculum.get(at + 1) else {
+                continue;
+            };
+            write!(out, "on to `{}`? [Y/n] ", next.lesson)?;
+            out.flush()?;
+            match lines.next().transpose()? {
+                Some(line) if !line.trim().eq_ignore_ascii_case("n") => writeln!(out)?,
+                _ => return writeln!(out, "\n`tour` picks up from there"),
+            }
+        }
+    }
+
+    /// Explains and runs the lesson of the stop `at`, unless it has passed already, quizzes, and
+    /// checks the exercises. Returns whether the stop has passed.
+    fn visit(
+        &mut self,
+        at: usize,
+        lines: &mut impl Iterator<Item = Result<String>>,
+        out: &mut impl Write,
+        quiz: bool,
+    ) -> Result<bool> {
+        let stop = self.curriculum[at];
+        let lesson = self
+            .registry
+            .get(stop.lesson)
+            .expect("the curriculum names registered lessons");
+        writeln!(
+            out,
+            "stop {}/{}: {}\n{}\n",
+            at + 1,
+            self.curriculum.len(),
+            lesson.title(),
+            stop.note
+        )?;
+        if !self.progress.is_completed(lesson.id()) {
+            let explanation = explain::explain(lesson, self.ctx.output.color);
+            writeln!(self.ctx.output.out, "{explanation}")?;
+            let result = runner::run_one(lesson, &mut self.ctx, &mut self.progress, false);
+            if let Some(path) = &self.path {
+                if let Err(x) = self.progress.save(path) {
+                    writeln!(out, "the progress couldn't be saved: {x}")?;
+                }
+            }
+            if let Err(x) = result {
+                writeln!(out, "`{}` failed: {x}", lesson.id())?;
+                return Ok(false);
+            }
+            writeln!(out, "\n`{}` passed", lesson.id())?;
+            if let (true, Some(chapter)) = (quiz, stop.quiz) {
+                self.quiz(chapter, lines, out)?;
+            }
+        }
+        for exercise in self.exercises(&stop) {
+            writeln!(out, "\nthe exercise `{}`: {}", exercise.id, exercise.title)?;
+            if !exercises::verify(exercise, out)? {
+                writeln!(
+                    out,
+                    "write it in {}, then `tour` again, or ask for a `hint {}`",
+                    exercise.file, exercise.id
+                )?;
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    fn quiz(
+        &mut self,
+        chapter: &str,
+        lines: &mut impl Iterator<Item = Result<String>>,
+        out: &mut impl Write,
+    ) -> Result<()> {
+        let Ok((chapter, questions)) = quiz::bank(chapter) else {
+            return Ok(());
+        };
+        writeln!(out, "\nthe quiz of `{chapter}`:")?;
+        let mut score = quiz::Score::default();
+        for (number, question) in questions.iter().enumerate() {
+            let heading = format!("{}/{}.", number + 1, questions.len());
+            let Some(right) = quiz::ask_one(&heading, question, lines, out)? else {
+                break;
+            };
+            score.asked += 1;
+            score.right += usize::from(right);
+        }
+        writeln!(out, "score: {score}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::intermediate_rust::{
+        self,
+        lesson::{Demo, LessonInfo},
+        output::{Buffer, Output},
+    };
+
+    #[test]
+    fn every_stop_comes_after_its_prerequisites() {
+        let registry = intermediate_rust::registry();
+        for (at, stop) in CURRICULUM.iter().enumerate() {
+            let lesson = registry.get(stop.lesson).unwrap();
+            for prerequisite in lesson.info().prerequisites {
+                assert!(
+                    CURRICULUM[..at]
+                        .iter()
+                        .any(|stop| stop.lesson == *prerequisite),
+                    "`{}` comes before its prerequisite `{prerequisite}`",
+                    stop.lesson
+                );
+            }
+            if let Some(chapter) = stop.quiz {
+                assert!(quiz::bank(chapter).is_ok(), "{chapter}");
+            }
+        }
+    }
+
+    fn registry() -> Registry {
+        let mut registry = Registry::new();
+        for id in ["first", "second", "third"] {
+            registry.register(Demo {
+                id,
+                title: id,
+                run: || Ok(()),
+                info: LessonInfo::default(),
+            });
+        }
+        registry
+    }
+
+    const STOPS: &[Stop] = &[
+        Stop {
+            lesson: "first",
+            note: "to begin with",
+            quiz: None,
+        },
+        Stop {
+            lesson: "second",
+            note: "and then",
+            quiz: None,
+        },
+        Stop {
+            lesson: "third",
+            note: "at last",
+            quiz: None,
+        },
+    ];
+
+    fn tour(exercises: &[Exercise], progress: Progress, input: &str) -> (String, Progress) {
+        let registry = registry();
+        let (stdout, stderr) = (Buffer::new(), Buffer::new());
+        let ctx = LessonCtx {
+            output: Output::captured(&stdout, &stderr),
+            ..LessonCtx::default()
+        };
+        let mut tour = Tour::new(&registry, STOPS, exercises, ctx, progress, None);
+        let mut out = Vec::new();
+        tour.run(input.as_bytes(), &mut out, false).unwrap();
+        (String::from_utf8(out).unwrap(), tour.progress)
+    }
+
+    fn exercise(checks: &'static [(&'static str, fn())]) -> Exercise {
+        Exercise {
+            id: "second-exercise",
+            title: "An exercise",
+            lesson: "second",
+            file: "src/exercises/second.rs",
+            checks,
+            hints: &[],
+            solution: "",
+        }
+    }
+
+    #[test]
+    fn it_goes_on_until_told_not_to() {
+        let (out, progress) = tour(&[], Progress::default(), "\nn\n");
+
+        assert!(
+            out.starts_with("stop 1/3: first\nto begin with\n\n"),
+            "{out}"
+        );
+        assert!(
+            out.contains("`first` passed\non to `second`? [Y/n] \nstop 2/3"),
+            "{out}"
+        );
+        assert!(
+            out.ends_with("on to `third`? [Y/n] \n`tour` picks up from there\n"),
+            "{out}"
+        );
+        assert!(progress.is_completed("second"));
+        assert!(!progress.is_completed("third"));
+    }
+
+    #[test]
+    fn it_picks_up_where_it_was_left() {
+        let mut progress = Progress::default();
+        progress.complete("first");
+        progress.complete("second");
+        let (out, progress) = tour(&[], progress, "");
+
+        assert!(out.starts_with("stop 3/3: third\n"), "{out}");
+        assert!(
+            out.ends_with("the tour is over: every stop has passed\n"),
+            "{out}"
+        );
+        assert!(progress.is_completed("third"));
+    }
+
+    #[test]
+    fn it_stops_at_an_unfinished_exercise() {
+        let unfinished = [exercise(&[("unfinished", || todo!())])];
+        let (out, progress) = tour(&unfinished, Progress::default(), "y\ny\n");
+
+        assert!(
+            out.contains("the exercise `second-exercise`: An exercise\nFAILED"),
+            "{out}"
+        );
+        assert!(out.ends_with(
+            "write it in src/exercises/second.rs, then `tour` again, or ask for a \
+             `hint second-exercise`\n"
+        ));
+        assert!(progress.is_completed("second"));
+        assert!(!progress.is_completed("third"));
+
+        // the lesson has passed, so only the exercise is checked again
+        let (out, progress) = tour(&unfinished, progress, "");
+        assert!(
+            out.starts_with("stop 2/3: second\nand then\n\n\nthe exercise"),
+            "{out}"
+        );
+
+        // and once it is done, the tour moves on
+        let finished = [exercise(&[("finished", || ())])];
+        let (out, _) = tour(&finished, progress, "");
+        assert!(out.starts_with("stop 3/3: third\n"), "{out}");
+    }
+}
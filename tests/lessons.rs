@@ -0,0 +1,265 @@
+//! What the lessons print, checked line by line, so that a change to a lesson that changes what
+//! it teaches doesn't go unnoticed. The lessons run in this process, with what they print
+//! captured by `run_captured`, which neither checks nor records progress. What only the binary
+//! does, such as logging to stderr, is checked by running it.
+
+// without the chapters they run, the tests aren't built, and neither need their helpers be
+#![cfg_attr(not(feature = "chapters"), allow(dead_code, unused_imports))]
+
+use intermediate_rust::{registry, run_captured, LessonOutput, Verbosity};
+use ir_core::metrics::{self, Metrics};
+use std::{env, fs, process::Command};
+
+/// Runs the lesson `id` with what it prints captured, at `verbosity`.
+fn captured_at(id: &str, verbosity: Verbosity) -> LessonOutput {
+    let registry = registry();
+    let lesson = registry
+        .get(id)
+        .unwrap_or_else(|| panic!("no lesson `{id}`"));
+    run_captured(lesson, verbosity).unwrap()
+}
+
+/// Runs the lesson `id` with what it prints captured.
+fn captured(id: &str) -> LessonOutput {
+    captured_at(id, Verbosity::Normal)
+}
+
+/// Runs the lesson `id` with what it prints captured, and what it counted, which the binary
+/// prints after it as `metrics: ...`.
+fn captured_counting(id: &str) -> (LessonOutput, Metrics) {
+    metrics::collect(|| captured(id))
+}
+
+/// Runs `intermediate_rust --force ARGS` and returns what it printed to stdout and to stderr.
+fn run(args: &[&str]) -> (String, String) {
+    // a progress file of its own, so that the test doesn't touch the learner's
+    let progress = env::temp_dir().join(format!(
+        "intermediate_rust_progress_{}_{}",
+        std::process::id(),
+        args.join("_")
+    ));
+    let output = Command::new(env!("CARGO_BIN_EXE_intermediate_rust"))
+        .arg("--force")
+        .args(args)
+        .env("INTERMEDIATE_RUST_PROGRESS", &progress)
+        // with the `tracing` feature, the spans would be printed to stderr along with the rest
+        .env("RUST_TRACE", "off")
+        .output()
+        .unwrap();
+    let _ = fs::remove_file(&progress);
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(output.status.success(), "{stdout}{stderr}");
+    (stdout, stderr)
+}
+
+/// `line` without the bytes allocated at its end, which are only counted with the `track-alloc`
+/// feature, and depend on the allocator: `3 Rc clones: ..., 0 drop(s), 1.2 KiB allocated`.
+fn without_allocated(line: &str) -> &str {
+    match line.rsplit_once(", ") {
+        Some((rest, allocated)) if allocated.ends_with(" allocated") => rest,
+        _ => line,
+    }
+}
+
+/// Asserts that `text` has each of `lines`, in this order, though not necessarily one after the
+/// other.
+fn assert_lines_in_order(text: &str, lines: &[&str]) {
+    let mut rest = text.lines().map(without_allocated);
+    for line in lines {
+        assert!(
+            rest.any(|candidate| candidate == *line),
+            "`{line}` is missing, or out of order, in:\n{text}"
+        );
+    }
+}
+
+#[cfg(feature = "smart-pointers")]
+#[test]
+fn box_builds_a_recursive_list() {
+    let LessonOutput { stdout, stderr, .. } = captured("smart-pointers/box");
+
+    assert_lines_in_order(
+        &stdout,
+        &[
+            "b = 5",
+            "1 -> 2 -> 3 -> Nil",
+            "one -> two -> three -> Nil",
+            "popped one, 2 left",
+            "popped two, 1 left",
+            "popped three, 0 left",
+            "pop_front on Nil = None",
+            "appended = 1 -> 2 -> 3 -> 4 -> Nil",
+        ],
+    );
+    assert_eq!(stderr, "");
+}
+
+#[cfg(feature = "smart-pointers")]
+#[test]
+fn rc_counts_the_owners_of_a_shared_list() {
+    let stdout = captured("smart-pointers/rc").stdout;
+
+    assert_lines_in_order(
+        &stdout,
+        &[
+            "a = 5 -> 10 -> Nil",
+            "b = 3 -> 5 -> 10 -> Nil",
+            "c = 4 -> 5 -> 10 -> Nil",
+            "`a`, # strong, - weak",
+            "after creating `a`           #    1 0",
+            "after creating `b`           ##   2 0",
+            "after creating `c`           ###  3 0",
+            "after `c` goes out of scope  ##   2 0",
+            "joined = 1 -> 2 -> 5 -> 10 -> Nil",
+            "`a`                      strong  weak",
+            "before appending              1     0",
+            "after appending               2     0",
+            "after dropping `joined`       1     0",
+            "3 deep clones: 0 Rc clone(s), 3 deep clone(s), 0 drop(s)",
+            "3 Rc clones:   3 Rc clone(s), 0 deep clone(s), 0 drop(s)",
+        ],
+    );
+}
+
+#[cfg(feature = "smart-pointers")]
+#[test]
+fn drop_runs_in_reverse_order_of_creation() {
+    let (LessonOutput { stdout, .. }, metrics) = captured_counting("smart-pointers/drop");
+
+    assert_lines_in_order(
+        &stdout,
+        &[
+            "CustomSmartPointers created.",
+            "Dropping CustomSmartPointer with data `\"other stuff\"`! (drop #1)",
+            "Dropping CustomSmartPointer with data `\"my stuff\"`! (drop #2)",
+            "Dropping CustomSmartPointer with data `[1, 2, 3]`! (drop #3)",
+            "CustomSmartPointer dropped before the end of the function.",
+            "Dropping CustomSmartPointer with data `4`! (drop #4)",
+        ],
+    );
+    assert_eq!(
+        (metrics.rc_clones, metrics.deep_clones, metrics.drops),
+        (0, 0, 4)
+    );
+}
+
+#[cfg(feature = "smart-pointers")]
+#[test]
+fn drop_order_follows_the_language_rules() {
+    let stdout = captured("smart-pointers/drop-order").stdout;
+    let trimmed: Vec<_> = stdout.lines().map(str::trim).collect();
+
+    assert_lines_in_order(
+        &trimmed.join("\n"),
+        &[
+            "locals: [\"b\", \"a\"]",
+            "fields: [\"pair\", \"first\", \"second\"]",
+            "temporaries: [\"ignored\", \"temporary\", \"bound\"]",
+            "mem::drop: [\"a\", \"b\"]",
+            "ManuallyDrop and mem::forget: [\"dropped by hand\"]",
+        ],
+    );
+}
+
+#[cfg(feature = "smart-pointers")]
+#[test]
+fn deref_coercion_turns_a_my_box_into_a_str() {
+    let (LessonOutput { stdout, .. }, metrics) = captured_counting("smart-pointers/deref");
+
+    assert_lines_in_order(
+        &stdout,
+        &[
+            "Hello, Rust!",
+            "Hello, Rust!",
+            "Hello, Rustacean!",
+            "Dropping MyBox<alloc::string::String>",
+        ],
+    );
+    assert_eq!(
+        (metrics.rc_clones, metrics.deep_clones, metrics.drops),
+        (0, 0, 3)
+    );
+}
+
+#[cfg(feature = "concurrency")]
+#[test]
+fn a_moved_value_is_joined_back_from_the_thread() {
+    let stdout = captured("thread-and-move").stdout;
+
+    assert_lines_in_order(
+        &stdout,
+        &[
+            "at main_thread: 42",
+            "at thread::join: 42",
+            "after thread::join: 42",
+            "main_thread done",
+        ],
+    );
+}
+
+#[test]
+fn every_lesson_passes() {
+    let registry = registry();
+    let failed: Vec<_> = registry
+        .iter()
+        .filter_map(|lesson| {
+            let failure = run_captured(lesson, Verbosity::Quiet).err()?;
+            Some(format!("{}: {failure}", lesson.id()))
+        })
+        .collect();
+    assert_eq!(failed, Vec::<String>::new());
+}
+
+#[cfg(feature = "smart-pointers")]
+#[test]
+fn ref_cell_leaks_a_cycle_and_breaks_it_with_weak() {
+    let stdout = captured("smart-pointers/ref-cell").stdout;
+
+    assert_lines_in_order(
+        &stdout,
+        &[
+            "`a`               strong  weak",
+            "initial                1     0",
+            "after b creation       2     0",
+            "after changing a       2     0",
+            "`b`               strong  weak",
+            "initial                1     0",
+            "after changing a       2     0",
+            "a = 5 -> 10 -> (back to 5)",
+            "Weak link:  b = 10 -> 5 ~> (back to 10)",
+            "Rc links, out of scope        1     2",
+            "Weak link, out of scope       0     0",
+            "leaf parent = None",
+            "`leaf`          strong  weak",
+            "created              1     0",
+            "in branch            2     0",
+            "branch dropped       1     0",
+            "`branch`           strong  weak",
+            "created with leaf       1     1",
+            "Leak check: 4 tracked Rc(s) outlived the demo",
+        ],
+    );
+}
+
+#[cfg(feature = "smart-pointers")]
+#[test]
+fn quiet_lessons_print_nothing() {
+    let output = captured_at("smart-pointers/ref-cell", Verbosity::Quiet);
+    assert_eq!(
+        (output.stdout, output.stderr),
+        (String::new(), String::new())
+    );
+}
+
+/// The binary sets up `env_logger`, which the lessons' `log::error!`s go to, on stderr: a
+/// messenger that fails is logged there, while the lesson goes on, on stdout.
+#[cfg(feature = "smart-pointers")]
+#[test]
+fn the_binary_logs_a_failed_messenger_to_stderr() {
+    let (stdout, stderr) = run(&["smart-pointers", "ref-cell"]);
+
+    assert_lines_in_order(&stdout, &["a = 5 -> 10 -> (back to 5)"]);
+    assert_lines_in_order(&stderr, &["[ERROR] 1 messenger(s) failed:"]);
+}
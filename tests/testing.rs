@@ -0,0 +1,50 @@
+//! The testing chapter’s integration tests: a crate of their own, which only sees what the
+//! library makes `pub`, and uses it as any other program would. Together with the unit tests in
+//! `src/intermediate_rust/testing/mod.rs`, they are the chapter’s examples, run in CI.
+
+#![cfg(feature = "testing")]
+
+use intermediate_rust::{
+    smart_pointers::{LimitTracker, QuotaEvent},
+    testing::{FakeClock, Recorder, Reminded, Reminder},
+};
+use std::time::Duration;
+
+#[test]
+fn a_limit_tracker_sends_its_warnings_to_a_recorder() {
+    let recorder = Recorder::new();
+    let mut tracker = LimitTracker::new(&recorder, 100);
+    assert_eq!(tracker.set_value(50), None);
+    assert_eq!(tracker.set_value(80), Some(QuotaEvent::Warning));
+    assert_eq!(tracker.set_value(100), Some(QuotaEvent::Exceeded));
+    assert_eq!(
+        recorder.take(),
+        [
+            "Warning: You've used up over 75% of your quota!",
+            "Error: You are over your quota!",
+        ]
+    );
+}
+
+#[test]
+fn a_reminder_passes_on_a_limit_trackers_warnings_once_an_hour() {
+    let (clock, reminders) = (FakeClock::new(), Recorder::new());
+    let reminder = Reminder::new(&clock, &reminders, Duration::from_secs(60 * 60));
+    let warnings = Recorder::new();
+    let mut tracker = LimitTracker::new(&warnings, 10).repeat_notifications(true);
+
+    // the tracker warns every 20 minutes, at 0, 20, 40, 60 and 80, and the reminder passes on
+    // the ones at 0 and 60
+    for _ in 0..5 {
+        tracker.set_value(11);
+        for warning in warnings.take() {
+            reminder.remind(&warning);
+        }
+        clock.advance(Duration::from_secs(20 * 60));
+    }
+    assert_eq!(reminders.calls().len(), 2);
+    assert!(matches!(
+        reminder.remind("again"),
+        Reminded::TooSoon { wait } if wait == Duration::from_secs(20 * 60)
+    ));
+}